@@ -38,6 +38,10 @@ fn place_setup<P: AsRef<Path>>(input_path: P) -> (TempDir, BuildCommand) {
         watch: false,
         plugin: None,
         output,
+        format: None,
+        skip_disk_check: false,
+        compact_xml: false,
+        ignore_unknown_properties: false,
     };
 
     (dir, options)
@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use memofs::{InMemoryFs, Vfs, VfsSnapshot};
+
+use librojo::{snapshot_from_vfs, InstanceContext};
+
+/// Number of scripts in the synthetic place, spread across folders of
+/// `SCRIPTS_PER_FOLDER` each.
+const SCRIPT_COUNT: usize = 4000;
+const SCRIPTS_PER_FOLDER: usize = 100;
+
+/// Compares snapshotting a wide tree on the global rayon pool and on a pool
+/// with a single thread, which runs the directory middleware's fan-out
+/// without any parallelism. Both runs read from the same in-memory
+/// filesystem so only the snapshotting itself is measured.
+pub fn benchmark_wide_tree(c: &mut Criterion) {
+    let vfs = wide_tree_vfs();
+    let context = InstanceContext::default();
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("Snapshot wide tree");
+    group.sample_size(20);
+
+    group.bench_function("parallel", |b| {
+        b.iter(|| snapshot_from_vfs(&context, &vfs, Path::new("/place")).unwrap())
+    });
+
+    group.bench_function("single thread", |b| {
+        b.iter(|| {
+            single_thread
+                .install(|| snapshot_from_vfs(&context, &vfs, Path::new("/place")).unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_wide_tree);
+criterion_main!(benches);
+
+fn wide_tree_vfs() -> Vfs {
+    let folders = (0..SCRIPT_COUNT / SCRIPTS_PER_FOLDER).map(|folder| {
+        let scripts = (0..SCRIPTS_PER_FOLDER).map(|script| {
+            (
+                format!("Module{script:03}.luau"),
+                VfsSnapshot::file(format!(
+                    "local Module = {{}}\n\nfunction Module.run()\n\treturn {folder} * {script}\nend\n\nreturn Module\n"
+                )),
+            )
+        });
+        (format!("Folder{folder:02}"), VfsSnapshot::dir(scripts))
+    });
+
+    let mut imfs = InMemoryFs::new();
+    imfs.load_snapshot("/place", VfsSnapshot::dir(folders))
+        .unwrap();
+    Vfs::new(imfs)
+}
@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// A single filesystem change, applied as part of a batch by
+/// [`Vfs::apply_batch`](crate::Vfs::apply_batch). Written contents are
+/// borrowed, so building a batch doesn't copy every file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsOp<'a> {
+    /// Create a directory and any missing parents. Succeeds if the directory
+    /// already exists.
+    CreateDir(PathBuf),
+
+    /// Write a file, replacing its contents if it exists.
+    Write(PathBuf, &'a [u8]),
+
+    /// Remove a file.
+    RemoveFile(PathBuf),
+
+    /// Remove a directory and all of its descendants.
+    RemoveDirAll(PathBuf),
+}
+
+impl FsOp<'_> {
+    /// The path this operation acts on.
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            FsOp::CreateDir(path)
+            | FsOp::Write(path, _)
+            | FsOp::RemoveFile(path)
+            | FsOp::RemoveDirAll(path) => path,
+        }
+    }
+}
+
+/// The error returned when an operation in a batch fails. Operations applied
+/// before it stay applied, and the rest of the batch is skipped.
+#[derive(Debug)]
+pub struct BatchError {
+    /// The index of the failing operation in the batch that was passed in.
+    pub index: usize,
+    pub source: io::Error,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "operation {} of batch failed: {}",
+            self.index, self.source
+        )
+    }
+}
+
+impl Error for BatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl From<BatchError> for io::Error {
+    fn from(err: BatchError) -> Self {
+        err.source
+    }
+}
@@ -1,11 +1,11 @@
-use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use crossbeam_channel::{Receiver, Sender};
 
-use crate::{DirEntry, Metadata, ReadDir, VfsBackend, VfsEvent, VfsSnapshot};
+use crate::{slice_range, DirEntry, Metadata, ReadDir, VfsBackend, VfsEvent, VfsSnapshot};
 
 /// In-memory filesystem that can be used as a VFS backend.
 ///
@@ -40,11 +40,64 @@ impl InMemoryFs {
         inner.load_snapshot(path.into(), snapshot)
     }
 
+    /// Writes every file and directory in this filesystem to real disk under
+    /// `root`, creating directories as needed.
+    ///
+    /// Paths are placed relative to `root` with their root and prefix
+    /// components stripped, so `/project/src/init.luau` is written to
+    /// `root/project/src/init.luau`. Existing files are overwritten.
+    pub fn export_to_disk(&self, root: &Path) -> io::Result<()> {
+        let inner = self.inner.lock().unwrap();
+
+        for path in &inner.orphans {
+            let relative: PathBuf = path
+                .components()
+                .filter(|component| matches!(component, Component::Normal(_)))
+                .collect();
+            inner.export(path, &root.join(relative))?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds a [`VfsSnapshot`](enum.VfsSnapshot.html) of the subtree at
+    /// `path`. This is the inverse of [`load_snapshot`](#method.load_snapshot).
+    pub(crate) fn snapshot(&self, path: &Path) -> io::Result<VfsSnapshot> {
+        let inner = self.inner.lock().unwrap();
+        inner.snapshot(path)
+    }
+
+    /// Turns case-insensitive path lookups on or off. Off by default.
+    ///
+    /// While on, `read`, `exists`, `metadata`, and `read_dir` match paths
+    /// regardless of case, like the default filesystems on Windows and macOS.
+    /// Paths keep the casing they were created with, so `read_dir` still
+    /// reports the stored names. This makes it possible to reproduce
+    /// case-folding bugs on case-sensitive CI machines.
+    pub fn set_case_insensitive(&mut self, case_insensitive: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.case_insensitive = case_insensitive;
+    }
+
     /// Raises a filesystem change event.
     ///
     /// If this `InMemoryFs` is being used as the backend of a
     /// [`Vfs`](struct.Vfs.html), then any listeners be notified of this event.
     pub fn raise_event(&mut self, event: VfsEvent) {
+        self.push_event(event);
+    }
+
+    /// Queues a filesystem change event for any [`Vfs`](struct.Vfs.html)
+    /// using this `InMemoryFs` as its backend, as if a watcher had reported
+    /// it.
+    ///
+    /// Unlike [`raise_event`](#method.raise_event), this only needs a shared
+    /// reference, so a clone kept by a test can drive events through a `Vfs`
+    /// that owns another clone. Events are delivered in the order they're
+    /// pushed. The filesystem's contents are not changed, so callers should
+    /// apply the matching change (e.g. with
+    /// [`load_snapshot`](#method.load_snapshot)) first.
+    pub fn push_event(&self, event: VfsEvent) {
         let inner = self.inner.lock().unwrap();
         inner.event_sender.send(event).unwrap();
     }
@@ -60,6 +113,7 @@ impl Default for InMemoryFs {
 struct InMemoryFsInner {
     entries: HashMap<PathBuf, Entry>,
     orphans: BTreeSet<PathBuf>,
+    case_insensitive: bool,
 
     event_receiver: Receiver<VfsEvent>,
     event_sender: Sender<VfsEvent>,
@@ -72,11 +126,29 @@ impl InMemoryFsInner {
         Self {
             entries: HashMap::new(),
             orphans: BTreeSet::new(),
+            case_insensitive: false,
             event_receiver,
             event_sender,
         }
     }
 
+    /// Looks up the entry at `path`, ignoring case if case-insensitive
+    /// lookups are turned on.
+    fn lookup(&self, path: &Path) -> Option<&Entry> {
+        if let Some(entry) = self.entries.get(path) {
+            return Some(entry);
+        }
+        if !self.case_insensitive {
+            return None;
+        }
+
+        let folded = fold_case(path);
+        self.entries
+            .iter()
+            .find(|(candidate, _)| fold_case(candidate) == folded)
+            .map(|(_, entry)| entry)
+    }
+
     fn load_snapshot(&mut self, path: PathBuf, snapshot: VfsSnapshot) -> io::Result<()> {
         if let Some(parent_path) = path.parent() {
             if let Some(parent_entry) = self.entries.get_mut(parent_path) {
@@ -114,6 +186,144 @@ impl InMemoryFsInner {
         Ok(())
     }
 
+    fn export(&self, path: &Path, destination: &Path) -> io::Result<()> {
+        match self.entries.get(path) {
+            Some(Entry::File { contents }) => {
+                if let Some(parent) = destination.parent() {
+                    fs_err::create_dir_all(parent)?;
+                }
+                fs_err::write(destination, contents)
+            }
+            Some(Entry::Dir { children }) => {
+                fs_err::create_dir_all(destination)?;
+                for child in children {
+                    let name = child.file_name().expect("child paths always have a name");
+                    self.export(child, &destination.join(name))?;
+                }
+                Ok(())
+            }
+            None => not_found(path),
+        }
+    }
+
+    fn snapshot(&self, path: &Path) -> io::Result<VfsSnapshot> {
+        match self.entries.get(path) {
+            Some(Entry::File { contents }) => Ok(VfsSnapshot::file(contents.clone())),
+            Some(Entry::Dir { children }) => {
+                let mut snapshot_children = BTreeMap::new();
+                for child in children {
+                    let name = child
+                        .file_name()
+                        .expect("child paths always have a name")
+                        .to_str()
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("path {} is not valid UTF-8", child.display()),
+                            )
+                        })?;
+                    snapshot_children.insert(name.to_owned(), self.snapshot(child)?);
+                }
+                Ok(VfsSnapshot::Dir {
+                    children: snapshot_children,
+                })
+            }
+            None => not_found(path),
+        }
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        if !self.entries.contains_key(from) {
+            return not_found(from);
+        }
+        if from == to {
+            return Ok(());
+        }
+        if to.starts_with(from) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot move {} into its own subdirectory {}",
+                    from.display(),
+                    to.display()
+                ),
+            ));
+        }
+
+        match (self.entries.get(from), self.entries.get(to)) {
+            (Some(Entry::File { .. }), Some(Entry::Dir { .. })) => return must_be_file(to),
+            (Some(Entry::Dir { .. }), Some(Entry::File { .. })) => return must_be_dir(to),
+            (_, Some(Entry::Dir { children })) if !children.is_empty() => {
+                return Err(io::Error::other(format!(
+                    "directory {} is not empty",
+                    to.display()
+                )));
+            }
+            _ => {}
+        }
+        if let Some(parent_path) = to.parent() {
+            if let Some(Entry::File { .. }) = self.entries.get(parent_path) {
+                return must_be_dir(parent_path);
+            }
+        }
+
+        self.remove(to.to_path_buf());
+        self.detach(from);
+
+        let remap = |path: &Path| match path.strip_prefix(from) {
+            Ok(relative) if relative.as_os_str().is_empty() => to.to_path_buf(),
+            Ok(relative) => to.join(relative),
+            Err(_) => path.to_path_buf(),
+        };
+
+        let mut to_move = VecDeque::new();
+        to_move.push_back(from.to_path_buf());
+
+        while let Some(old_path) = to_move.pop_front() {
+            let entry = match self.entries.remove(&old_path) {
+                Some(Entry::Dir { children }) => {
+                    let new_children = children.iter().map(|child| remap(child)).collect();
+                    to_move.extend(children);
+                    Entry::Dir {
+                        children: new_children,
+                    }
+                }
+                Some(file) => file,
+                None => continue,
+            };
+            self.entries.insert(remap(&old_path), entry);
+        }
+
+        self.attach(to.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Links `path` into its parent's children, or records it as an orphan
+    /// if the parent isn't present.
+    fn attach(&mut self, path: PathBuf) {
+        if let Some(Entry::Dir { children }) = path
+            .parent()
+            .and_then(|parent_path| self.entries.get_mut(parent_path))
+        {
+            children.insert(path);
+        } else {
+            self.orphans.insert(path);
+        }
+    }
+
+    /// Unlinks `path` from its parent's children and from the orphan set.
+    fn detach(&mut self, path: &Path) {
+        self.orphans.remove(path);
+
+        if let Some(Entry::Dir { children }) = path
+            .parent()
+            .and_then(|parent_path| self.entries.get_mut(parent_path))
+        {
+            children.remove(path);
+        }
+    }
+
     fn remove(&mut self, root_path: PathBuf) {
         self.orphans.remove(&root_path);
 
@@ -139,13 +349,23 @@ impl VfsBackend for InMemoryFs {
     fn read(&mut self, path: &Path) -> io::Result<Vec<u8>> {
         let inner = self.inner.lock().unwrap();
 
-        match inner.entries.get(path) {
+        match inner.lookup(path) {
             Some(Entry::File { contents }) => Ok(contents.clone()),
             Some(Entry::Dir { .. }) => must_be_file(path),
             None => not_found(path),
         }
     }
 
+    fn read_range(&mut self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let inner = self.inner.lock().unwrap();
+
+        match inner.lookup(path) {
+            Some(Entry::File { contents }) => Ok(slice_range(contents, offset, len)),
+            Some(Entry::Dir { .. }) => must_be_file(path),
+            None => not_found(path),
+        }
+    }
+
     fn write(&mut self, path: &Path, data: &[u8]) -> io::Result<()> {
         let mut inner = self.inner.lock().unwrap();
 
@@ -159,13 +379,13 @@ impl VfsBackend for InMemoryFs {
 
     fn exists(&mut self, path: &Path) -> io::Result<bool> {
         let inner = self.inner.lock().unwrap();
-        Ok(inner.entries.contains_key(path))
+        Ok(inner.lookup(path).is_some())
     }
 
     fn read_dir(&mut self, path: &Path) -> io::Result<ReadDir> {
         let inner = self.inner.lock().unwrap();
 
-        match inner.entries.get(path) {
+        match inner.lookup(path) {
             Some(Entry::Dir { children }) => {
                 let iter = children
                     .clone()
@@ -222,10 +442,33 @@ impl VfsBackend for InMemoryFs {
         }
     }
 
+    fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.rename(from, to)
+    }
+
+    fn copy(&mut self, from: &Path, to: &Path) -> io::Result<u64> {
+        let mut inner = self.inner.lock().unwrap();
+
+        let contents = match inner.entries.get(from) {
+            Some(Entry::File { contents }) => contents.clone(),
+            Some(Entry::Dir { .. }) => return must_be_file(from),
+            None => return not_found(from),
+        };
+        if let Some(Entry::Dir { .. }) = inner.entries.get(to) {
+            return must_be_file(to);
+        }
+
+        let len = contents.len() as u64;
+        inner.load_snapshot(to.to_path_buf(), VfsSnapshot::File { contents })?;
+
+        Ok(len)
+    }
+
     fn metadata(&mut self, path: &Path) -> io::Result<Metadata> {
         let inner = self.inner.lock().unwrap();
 
-        match inner.entries.get(path) {
+        match inner.lookup(path) {
             Some(Entry::File { .. }) => Ok(Metadata { is_file: true }),
             Some(Entry::Dir { .. }) => Ok(Metadata { is_file: false }),
             None => not_found(path),
@@ -248,6 +491,15 @@ impl VfsBackend for InMemoryFs {
     fn unwatch(&mut self, _path: &Path) -> io::Result<()> {
         Ok(())
     }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+/// Lowercases every component of `path` for case-insensitive comparison.
+fn fold_case(path: &Path) -> PathBuf {
+    PathBuf::from(path.to_string_lossy().to_lowercase())
 }
 
 fn must_be_file<T>(path: &Path) -> io::Result<T> {
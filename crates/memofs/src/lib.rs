@@ -17,19 +17,25 @@ memofs is currently an unstable minimum viable library. Its primary consumer is
 * Configurable caching (write-through, write-around, write-back)
 */
 
+mod batch;
 mod in_memory_fs;
 mod noop_backend;
 mod snapshot;
 mod std_backend;
 
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, MutexGuard};
-use std::{io, str};
+use std::time::{Duration, Instant};
 
+use globset::{Glob, GlobMatcher};
+
+pub use batch::{BatchError, FsOp};
 pub use in_memory_fs::InMemoryFs;
 pub use noop_backend::NoopBackend;
-pub use snapshot::VfsSnapshot;
+pub use snapshot::{SnapshotChange, VfsSnapshot};
 pub use std_backend::{CriticalErrorHandler, StdBackend, WatcherCriticalError};
 
 /// Pre-read file contents, canonical paths, and metadata for fast startup.
@@ -57,6 +63,37 @@ pub struct PrefetchCache {
     pub walked_roots: Vec<PathBuf>,
 }
 
+impl PrefetchCache {
+    /// Returns the combined size of all pre-read file contents, in bytes.
+    pub fn total_bytes(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+
+    /// Drops file contents until the cache fits within `max_bytes`.
+    ///
+    /// Files are kept in path order so the result is deterministic. Metadata
+    /// (`is_file`, `children`, `dir_init`) is left untouched, so reads of
+    /// dropped files fall through to the backend.
+    fn trim_to_budget(&mut self, max_bytes: usize) {
+        if self.total_bytes() <= max_bytes {
+            return;
+        }
+
+        let mut paths: Vec<PathBuf> = self.files.keys().cloned().collect();
+        paths.sort();
+
+        let mut total = 0;
+        for path in paths {
+            let len = self.files[&path].len();
+            if total + len <= max_bytes {
+                total += len;
+            } else {
+                self.files.remove(&path);
+            }
+        }
+    }
+}
+
 mod sealed {
     use super::*;
 
@@ -90,11 +127,22 @@ impl<T> IoResultExt<T> for io::Result<T> {
     }
 }
 
+/// Returns the bytes of `contents` in `offset..offset + len`, clamped to the
+/// end of the buffer.
+pub(crate) fn slice_range(contents: &[u8], offset: u64, len: usize) -> Vec<u8> {
+    let start = usize::try_from(offset)
+        .unwrap_or(usize::MAX)
+        .min(contents.len());
+    let end = start.saturating_add(len).min(contents.len());
+    contents[start..end].to_vec()
+}
+
 /// Backend that can be used to create a `Vfs`.
 ///
 /// This trait is sealed and cannot not be implemented outside this crate.
 pub trait VfsBackend: sealed::Sealed + Send + 'static {
     fn read(&mut self, path: &Path) -> io::Result<Vec<u8>>;
+    fn read_range(&mut self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>>;
     fn write(&mut self, path: &Path, data: &[u8]) -> io::Result<()>;
     fn exists(&mut self, path: &Path) -> io::Result<bool>;
     fn read_dir(&mut self, path: &Path) -> io::Result<ReadDir>;
@@ -103,10 +151,13 @@ pub trait VfsBackend: sealed::Sealed + Send + 'static {
     fn metadata(&mut self, path: &Path) -> io::Result<Metadata>;
     fn remove_file(&mut self, path: &Path) -> io::Result<()>;
     fn remove_dir_all(&mut self, path: &Path) -> io::Result<()>;
+    fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()>;
+    fn copy(&mut self, from: &Path, to: &Path) -> io::Result<u64>;
 
     fn event_receiver(&self) -> crossbeam_channel::Receiver<VfsEvent>;
     fn watch(&mut self, path: &Path, recursive: bool) -> io::Result<()>;
     fn unwatch(&mut self, path: &Path) -> io::Result<()>;
+    fn watched_paths(&self) -> Vec<PathBuf>;
 }
 
 /// Vfs equivalent to [`std::fs::DirEntry`][std::fs::DirEntry].
@@ -157,12 +208,39 @@ impl Metadata {
 
 /// Represents an event that a filesystem can raise that might need to be
 /// handled.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum VfsEvent {
     Create(PathBuf),
     Write(PathBuf),
     Remove(PathBuf),
+    /// The watcher dropped events (for example because its queue overflowed)
+    /// and anything under this path may have changed without notice.
+    /// Consumers should re-read the whole subtree.
+    RescanRequired(PathBuf),
+}
+
+/// Counts of the operations a `Vfs` has sent to its backend, returned by
+/// [`Vfs::stats`].
+///
+/// Operations answered from the prefetch cache aren't counted, so these show
+/// how much work actually reached the filesystem.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VfsStats {
+    /// Whole or partial file reads.
+    pub reads: u64,
+    /// File writes and copies.
+    pub writes: u64,
+    /// Directory listings.
+    pub read_dirs: u64,
+    /// `exists` and `metadata` lookups.
+    pub metadata: u64,
+    /// Directories created.
+    pub create_dirs: u64,
+    /// Files and directories removed.
+    pub removes: u64,
+    /// Renames.
+    pub renames: u64,
 }
 
 /// Contains implementation details of the Vfs, wrapped by `Vfs` and `VfsLock`,
@@ -173,10 +251,27 @@ struct VfsInner {
     watch_recursive: bool,
     prefetch_cache: Option<PrefetchCache>,
     recorded_watch_paths: Option<HashSet<PathBuf>>,
+    watch_filter: Option<GlobMatcher>,
+    unwatch_grace_period: Option<Duration>,
+    /// Removed paths waiting to be unwatched, keyed by path, with the
+    /// instant after which the watch may be released.
+    pending_unwatches: HashMap<PathBuf, Instant>,
+    stats: VfsStats,
 }
 
 impl VfsInner {
+    /// Whether a read of `path` should register a watch: watching must be
+    /// enabled and the path must match the watch filter, if one is set.
+    fn should_watch(&self, path: &Path) -> bool {
+        self.watch_enabled
+            && self
+                .watch_filter
+                .as_ref()
+                .is_none_or(|filter| filter.is_match(path))
+    }
+
     fn watch_or_record(&mut self, path: &Path) -> io::Result<()> {
+        self.pending_unwatches.remove(path);
         if let Some(ref mut recorded) = self.recorded_watch_paths {
             recorded.insert(path.to_path_buf());
             Ok(())
@@ -190,16 +285,17 @@ impl VfsInner {
     fn read_raw(&mut self, path: &Path) -> io::Result<Vec<u8>> {
         if let Some(cache) = &mut self.prefetch_cache {
             if let Some(contents) = cache.files.remove(path) {
-                if self.watch_enabled {
+                if self.should_watch(path) {
                     self.watch_or_record(path)?;
                 }
                 return Ok(contents);
             }
         }
 
+        self.stats.reads += 1;
         let contents = self.backend.read(path)?;
 
-        if self.watch_enabled {
+        if self.should_watch(path) {
             self.watch_or_record(path)?;
         }
 
@@ -211,28 +307,43 @@ impl VfsInner {
         Ok(Arc::new(self.read_raw(path)?))
     }
 
+    fn read_range(&mut self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let contents = match self
+            .prefetch_cache
+            .as_ref()
+            .and_then(|cache| cache.files.get(path))
+        {
+            Some(contents) => slice_range(contents, offset, len),
+            None => {
+                self.stats.reads += 1;
+                self.backend.read_range(path, offset, len)?
+            }
+        };
+
+        if self.should_watch(path) {
+            self.watch_or_record(path)?;
+        }
+
+        Ok(contents)
+    }
+
     fn read_to_string<P: AsRef<Path>>(&mut self, path: P) -> io::Result<Arc<String>> {
         let path = path.as_ref();
         let contents = self.read_raw(path)?;
 
-        let contents_str = str::from_utf8(&contents).map_err(|_| {
-            io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("File was not valid UTF-8: {}", path.display()),
-            )
-        })?;
-
-        Ok(Arc::new(contents_str.into()))
+        Ok(Arc::new(decode_text(path, contents)?))
     }
 
     fn exists<P: AsRef<Path>>(&mut self, path: P) -> io::Result<bool> {
         let path = path.as_ref();
+        self.stats.metadata += 1;
         self.backend.exists(path)
     }
 
     fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&mut self, path: P, contents: C) -> io::Result<()> {
         let path = path.as_ref();
         let contents = contents.as_ref();
+        self.stats.writes += 1;
         self.backend.write(path, contents)
     }
 
@@ -241,7 +352,7 @@ impl VfsInner {
 
         if let Some(cache) = &mut self.prefetch_cache {
             if let Some(child_paths) = cache.children.remove(path) {
-                if self.watch_enabled {
+                if self.should_watch(path) {
                     self.watch_or_record(path)?;
                 }
                 let inner = child_paths.into_iter().map(|p| Ok(DirEntry { path: p }));
@@ -251,9 +362,10 @@ impl VfsInner {
             }
         }
 
+        self.stats.read_dirs += 1;
         let dir = self.backend.read_dir(path)?;
 
-        if self.watch_enabled {
+        if self.should_watch(path) {
             self.watch_or_record(path)?;
         }
 
@@ -262,11 +374,13 @@ impl VfsInner {
 
     fn create_dir<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
         let path = path.as_ref();
+        self.stats.create_dirs += 1;
         self.backend.create_dir(path)
     }
 
     fn create_dir_all<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
         let path = path.as_ref();
+        self.stats.create_dirs += 1;
         self.backend.create_dir_all(path)
     }
 
@@ -275,6 +389,7 @@ impl VfsInner {
         if self.watch_enabled {
             let _ = self.backend.unwatch(path);
         }
+        self.stats.removes += 1;
         self.backend.remove_file(path)
     }
 
@@ -283,9 +398,44 @@ impl VfsInner {
         if self.watch_enabled {
             let _ = self.backend.unwatch(path);
         }
+        self.stats.removes += 1;
         self.backend.remove_dir_all(path)
     }
 
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> io::Result<()> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        if self.watch_enabled {
+            if let Err(err) = self.backend.unwatch(from) {
+                log::debug!(
+                    "Could not unwatch {} before renaming it: {}",
+                    from.display(),
+                    err
+                );
+            }
+        }
+        self.stats.renames += 1;
+        self.backend.rename(from, to)?;
+        if self.should_watch(to) {
+            if let Err(err) = self.watch_or_record(to) {
+                log::warn!(
+                    "Could not watch {} after renaming {} to it: {}",
+                    to.display(),
+                    from.display(),
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> io::Result<u64> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        self.stats.writes += 1;
+        self.backend.copy(from, to)
+    }
+
     fn metadata<P: AsRef<Path>>(&mut self, path: P) -> io::Result<Metadata> {
         let path = path.as_ref();
 
@@ -301,23 +451,98 @@ impl VfsInner {
             }
         }
 
+        self.stats.metadata += 1;
         self.backend.metadata(path)
     }
 
+    fn apply_batch(&mut self, ops: &[FsOp<'_>]) -> Result<(), BatchError> {
+        // Directories go first, parents before children, so a batch can list
+        // a file before the directory that holds it.
+        let mut order: Vec<usize> = (0..ops.len()).collect();
+        order.sort_by_key(|&index| match &ops[index] {
+            FsOp::CreateDir(path) => (0, path.components().count()),
+            _ => (1, 0),
+        });
+
+        for index in order {
+            let result = match &ops[index] {
+                FsOp::CreateDir(path) => match self.create_dir_all(path) {
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+                    result => result,
+                },
+                FsOp::Write(path, contents) => self.write(path, contents),
+                FsOp::RemoveFile(path) => self.remove_file(path),
+                FsOp::RemoveDirAll(path) => self.remove_dir_all(path),
+            };
+            result.map_err(|source| BatchError { index, source })?;
+        }
+
+        Ok(())
+    }
+
     fn event_receiver(&self) -> crossbeam_channel::Receiver<VfsEvent> {
         self.backend.event_receiver()
     }
 
     fn commit_event(&mut self, event: &VfsEvent) -> io::Result<()> {
-        // NOTE: We intentionally do NOT unwatch on Remove events.
+        // NOTE: We intentionally do NOT unwatch immediately on Remove events.
         // The path may be recreated immediately (e.g., editor undo), and
         // unwatching causes future events for the recreated path to be missed.
-        // Stale watches are harmless — notify silently ignores events for
-        // non-existent paths, and the watch will be cleaned up when the
-        // parent is unwatched.
-        let _ = event;
+        // If a grace period is configured, removed paths are instead queued
+        // and only unwatched once they've stayed gone for that long, so that
+        // deleting a large subtree doesn't leave its watches behind forever.
+        match event {
+            VfsEvent::Remove(path) => {
+                if let Some(grace) = self.unwatch_grace_period {
+                    self.pending_unwatches
+                        .insert(path.clone(), Instant::now() + grace);
+                }
+            }
+            VfsEvent::Create(path) | VfsEvent::Write(path) => {
+                // A recreated path (or anything inside it) means its
+                // ancestors exist again too.
+                if !self.pending_unwatches.is_empty() {
+                    for ancestor in path.ancestors() {
+                        self.pending_unwatches.remove(ancestor);
+                    }
+                }
+            }
+            VfsEvent::RescanRequired(_) => {}
+        }
+
+        self.release_expired_watches();
         Ok(())
     }
+
+    /// Unwatches every pending removed path whose grace period has elapsed
+    /// and that still doesn't exist. Returns how many watches were released.
+    fn release_expired_watches(&mut self) -> usize {
+        if self.pending_unwatches.is_empty() {
+            return 0;
+        }
+
+        let now = Instant::now();
+        let expired: Vec<PathBuf> = self
+            .pending_unwatches
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut released = 0;
+        for path in expired {
+            self.pending_unwatches.remove(&path);
+            if matches!(self.backend.exists(&path), Ok(false)) {
+                let _ = self.backend.unwatch(&path);
+                released += 1;
+            }
+        }
+
+        if released > 0 {
+            log::debug!("Released {} watches for removed paths", released);
+        }
+        released
+    }
 }
 
 /// A virtual filesystem with a configurable backend.
@@ -346,6 +571,17 @@ impl Vfs {
         (Self::new(backend), error_rx)
     }
 
+    /// Like [`Vfs::new_default_with_errors`], but the backend scans watched
+    /// paths every `interval` instead of relying on native notifications.
+    /// See [`StdBackend::new_polling`].
+    pub fn new_polling_with_errors(
+        interval: Duration,
+    ) -> (Self, crossbeam_channel::Receiver<WatcherCriticalError>) {
+        let backend = StdBackend::new_polling(interval);
+        let error_rx = backend.critical_error_receiver();
+        (Self::new(backend), error_rx)
+    }
+
     /// Creates a new `Vfs` suitable for one-shot operations like syncback.
     ///
     /// Unlike `new_default()`, this creates a backend that:
@@ -355,17 +591,34 @@ impl Vfs {
     /// This is ideal for CLI commands that don't need real-time file watching
     /// and shouldn't be terminated if the watcher thread encounters issues.
     pub fn new_oneshot() -> Self {
-        let backend = StdBackend::new_with_error_handler(Box::new(|err| {
+        let vfs = Self::new(Self::oneshot_backend());
+        vfs.set_watch_enabled(false);
+        vfs
+    }
+
+    /// Like [`Vfs::new_oneshot`], but the backend refuses to modify the
+    /// filesystem: writes, removals, and directory creation fail with
+    /// `PermissionDenied` without touching the disk.
+    ///
+    /// Useful for commands that only read a project, like sourcemap
+    /// generation.
+    pub fn new_oneshot_read_only() -> Self {
+        let mut backend = Self::oneshot_backend();
+        backend.set_read_only(true);
+        let vfs = Self::new(backend);
+        vfs.set_watch_enabled(false);
+        vfs
+    }
+
+    fn oneshot_backend() -> StdBackend {
+        StdBackend::new_with_error_handler(Box::new(|err| {
             // Log the error but don't exit - one-shot operations don't need file watching
             log::debug!(
                 "File watcher issue (non-fatal for one-shot operation): {}",
                 err
             );
             true // Stop the watcher thread, but don't exit the process
-        }));
-        let vfs = Self::new(backend);
-        vfs.set_watch_enabled(false);
-        vfs
+        }))
     }
 
     /// Creates a new `Vfs` with the given backend.
@@ -376,6 +629,10 @@ impl Vfs {
             watch_recursive: true,
             prefetch_cache: None,
             recorded_watch_paths: None,
+            watch_filter: None,
+            unwatch_grace_period: None,
+            pending_unwatches: HashMap::new(),
+            stats: VfsStats::default(),
         };
 
         Self {
@@ -388,7 +645,14 @@ impl Vfs {
     /// File reads will check the cache before hitting the backend. Call
     /// [`clear_prefetch_cache`] after the initial snapshot build to free
     /// memory and ensure live operations get fresh data.
-    pub fn set_prefetch_cache(&self, cache: PrefetchCache) {
+    ///
+    /// If `max_bytes` is given, file contents beyond that budget are dropped
+    /// from the cache. Their metadata is kept, so reads of those files fall
+    /// through to the backend.
+    pub fn set_prefetch_cache(&self, mut cache: PrefetchCache, max_bytes: Option<usize>) {
+        if let Some(max_bytes) = max_bytes {
+            cache.trim_to_budget(max_bytes);
+        }
         let mut inner = self.inner.lock().unwrap();
         inner.prefetch_cache = Some(cache);
     }
@@ -412,6 +676,11 @@ impl Vfs {
         cache.dir_init.get(dir).cloned()
     }
 
+    /// Returns how many operations this Vfs has sent to its backend so far.
+    pub fn stats(&self) -> VfsStats {
+        self.inner.lock().unwrap().stats
+    }
+
     /// Manually lock the Vfs, useful for large batches of operations.
     pub fn lock(&self) -> VfsLock<'_> {
         VfsLock {
@@ -444,6 +713,47 @@ impl Vfs {
         inner.watch_recursive = recursive;
     }
 
+    /// Restricts automatic file watching to paths matching `filter`.
+    ///
+    /// With a filter set, `read` and `read_dir` only register a watch when
+    /// the path they were given matches it, which keeps the number of watches
+    /// down on platforms where each one is expensive. Paths are matched as
+    /// passed to the `Vfs`, so filters for absolute paths usually need a
+    /// leading `**/`. Explicit calls to [`Vfs::watch`] are not filtered.
+    pub fn set_watch_filter(&self, filter: Glob) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.watch_filter = Some(filter.compile_matcher());
+    }
+
+    /// Removes the watch filter, so every path read is watched again.
+    pub fn clear_watch_filter(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.watch_filter = None;
+    }
+
+    /// Sets how long a removed path must stay gone before its watch is
+    /// released. `None`, the default, never unwatches removed paths.
+    ///
+    /// Pending unwatches are checked whenever an event is committed and by
+    /// [`Vfs::release_expired_watches`]. A path that is recreated or read
+    /// again before its grace period ends keeps its watch.
+    pub fn set_unwatch_grace_period(&self, grace_period: Option<Duration>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unwatch_grace_period = grace_period;
+        if grace_period.is_none() {
+            inner.pending_unwatches.clear();
+        }
+    }
+
+    /// Releases watches on removed paths whose grace period has elapsed.
+    /// Returns how many watches were released.
+    ///
+    /// Call this periodically when events may be sparse; committing an event
+    /// does the same check.
+    pub fn release_expired_watches(&self) -> usize {
+        self.inner.lock().unwrap().release_expired_watches()
+    }
+
     /// Begin recording mode: `read`/`read_dir` calls record touched paths
     /// into an internal set instead of calling `backend.watch()`.
     pub fn start_watch_recording(&self) {
@@ -457,10 +767,22 @@ impl Vfs {
         inner.recorded_watch_paths.take()
     }
 
+    /// Returns every path the backend currently has a watch registered on,
+    /// sorted. Backends that don't watch anything return an empty list.
+    ///
+    /// This is meant for diagnosing watcher issues, like watching far more
+    /// or fewer paths than expected.
+    pub fn watched_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.inner.lock().unwrap().backend.watched_paths();
+        paths.sort();
+        paths
+    }
+
     /// Explicitly watch a path using the current `watch_recursive` setting.
     pub fn watch<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
         let mut inner = self.inner.lock().unwrap();
         let recursive = inner.watch_recursive;
+        inner.pending_unwatches.remove(path.as_ref());
         inner.backend.watch(path.as_ref(), recursive)
     }
 
@@ -476,6 +798,23 @@ impl Vfs {
         self.inner.lock().unwrap().read(path)
     }
 
+    /// Read up to `len` bytes of a file starting at `offset`, without loading
+    /// the rest of the file into memory.
+    ///
+    /// Reads that run past the end of the file return a shorter buffer
+    /// instead of an error, so callers can stream a file in fixed-size
+    /// chunks until they get back fewer bytes than they asked for.
+    #[inline]
+    pub fn read_range<P: AsRef<Path>>(
+        &self,
+        path: P,
+        offset: u64,
+        len: usize,
+    ) -> io::Result<Vec<u8>> {
+        let path = path.as_ref();
+        self.inner.lock().unwrap().read_range(path, offset, len)
+    }
+
     /// Read a file from the VFS (or from the underlying backend if it isn't
     /// resident) into a string.
     ///
@@ -584,6 +923,40 @@ impl Vfs {
         self.inner.lock().unwrap().remove_dir_all(path)
     }
 
+    /// Rename a file or directory, replacing `to` if it already exists.
+    ///
+    /// If file watching is enabled, the watch on `from` is dropped and `to`
+    /// is watched in its place.
+    ///
+    /// Roughly equivalent to [`std::fs::rename`][std::fs::rename].
+    ///
+    /// [std::fs::rename]: https://doc.rust-lang.org/stable/std/fs/fn.rename.html
+    #[inline]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<()> {
+        self.inner.lock().unwrap().rename(from, to)
+    }
+
+    /// Apply a batch of filesystem changes under a single lock.
+    ///
+    /// `CreateDir` operations run first, shallowest first, so directories
+    /// exist before any files written into them. Everything else runs in the
+    /// order given. The first failure stops the batch and reports the index of
+    /// the operation that failed; operations applied before it are kept.
+    pub fn apply_batch(&self, ops: Vec<FsOp<'_>>) -> Result<(), BatchError> {
+        self.inner.lock().unwrap().apply_batch(&ops)
+    }
+
+    /// Copy the contents of one file to another, returning the number of
+    /// bytes copied.
+    ///
+    /// Roughly equivalent to [`std::fs::copy`][std::fs::copy].
+    ///
+    /// [std::fs::copy]: https://doc.rust-lang.org/stable/std/fs/fn.copy.html
+    #[inline]
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<u64> {
+        self.inner.lock().unwrap().copy(from, to)
+    }
+
     /// Query metadata about the given path.
     ///
     /// Roughly equivalent to [`std::fs::metadata`][std::fs::metadata].
@@ -637,6 +1010,19 @@ impl VfsLock<'_> {
         self.inner.read(path)
     }
 
+    /// Read up to `len` bytes of a file starting at `offset`. See
+    /// [`Vfs::read_range`].
+    #[inline]
+    pub fn read_range<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        offset: u64,
+        len: usize,
+    ) -> io::Result<Vec<u8>> {
+        let path = path.as_ref();
+        self.inner.read_range(path, offset, len)
+    }
+
     /// Write a file to the VFS and the underlying backend.
     ///
     /// Roughly equivalent to [`std::fs::write`][std::fs::write].
@@ -711,6 +1097,35 @@ impl VfsLock<'_> {
         self.inner.remove_dir_all(path)
     }
 
+    /// Rename a file or directory, replacing `to` if it already exists.
+    ///
+    /// If file watching is enabled, the watch on `from` is dropped and `to`
+    /// is watched in its place.
+    ///
+    /// Roughly equivalent to [`std::fs::rename`][std::fs::rename].
+    ///
+    /// [std::fs::rename]: https://doc.rust-lang.org/stable/std/fs/fn.rename.html
+    #[inline]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> io::Result<()> {
+        self.inner.rename(from, to)
+    }
+
+    /// Apply a batch of filesystem changes. See [`Vfs::apply_batch`].
+    pub fn apply_batch(&mut self, ops: &[FsOp<'_>]) -> Result<(), BatchError> {
+        self.inner.apply_batch(ops)
+    }
+
+    /// Copy the contents of one file to another, returning the number of
+    /// bytes copied.
+    ///
+    /// Roughly equivalent to [`std::fs::copy`][std::fs::copy].
+    ///
+    /// [std::fs::copy]: https://doc.rust-lang.org/stable/std/fs/fn.copy.html
+    #[inline]
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> io::Result<u64> {
+        self.inner.copy(from, to)
+    }
+
     /// Query metadata about the given path.
     ///
     /// Roughly equivalent to [`std::fs::metadata`][std::fs::metadata].
@@ -735,12 +1150,56 @@ impl VfsLock<'_> {
     }
 }
 
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Decodes the contents of a text file. A leading byte order mark is
+/// dropped, and files with a UTF-16 byte order mark are transcoded; anything
+/// else must be UTF-8.
+fn decode_text(path: &Path, mut contents: Vec<u8>) -> io::Result<String> {
+    let invalid = |encoding: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("File was not valid {encoding}: {}", path.display()),
+        )
+    };
+
+    if let Some(rest) = contents.strip_prefix(UTF16_LE_BOM) {
+        return decode_utf16(rest, u16::from_le_bytes).ok_or_else(|| invalid("UTF-16"));
+    }
+    if let Some(rest) = contents.strip_prefix(UTF16_BE_BOM) {
+        return decode_utf16(rest, u16::from_be_bytes).ok_or_else(|| invalid("UTF-16"));
+    }
+    if contents.starts_with(UTF8_BOM) {
+        contents.drain(..UTF8_BOM.len());
+    }
+
+    String::from_utf8(contents).map_err(|_| invalid("UTF-8"))
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{InMemoryFs, PrefetchCache, StdBackend, Vfs, VfsSnapshot};
-    use std::collections::HashMap;
+    use crate::{
+        FsOp, InMemoryFs, Metadata, NoopBackend, PrefetchCache, ReadDir, StdBackend, Vfs,
+        VfsBackend, VfsEvent, VfsSnapshot, VfsStats,
+    };
+    use std::collections::{HashMap, HashSet};
     use std::io;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     /// https://github.com/rojo-rbx/rojo/issues/899
     #[test]
@@ -757,6 +1216,147 @@ mod test {
         );
     }
 
+    #[test]
+    fn read_to_string_strips_utf8_bom() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "bom",
+            VfsSnapshot::file(b"\xEF\xBB\xBFreturn 1\r\n".to_vec()),
+        )
+        .unwrap();
+        imfs.load_snapshot(
+            "plain",
+            VfsSnapshot::file("a \u{feff} is kept past the start"),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        assert_eq!(vfs.read_to_string("bom").unwrap().as_str(), "return 1\r\n");
+        assert_eq!(
+            vfs.read_to_string_lf_normalized("bom").unwrap().as_str(),
+            "return 1\n"
+        );
+        assert_eq!(
+            vfs.read_to_string("plain").unwrap().as_str(),
+            "a \u{feff} is kept past the start"
+        );
+    }
+
+    #[test]
+    fn read_to_string_transcodes_utf16() {
+        let text = "local é = 1\n";
+        let mut little_endian = vec![0xFF, 0xFE];
+        let mut big_endian = vec![0xFE, 0xFF];
+        for unit in text.encode_utf16() {
+            little_endian.extend(unit.to_le_bytes());
+            big_endian.extend(unit.to_be_bytes());
+        }
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("le", VfsSnapshot::file(little_endian))
+            .unwrap();
+        imfs.load_snapshot("be", VfsSnapshot::file(big_endian))
+            .unwrap();
+        imfs.load_snapshot("odd", VfsSnapshot::file(vec![0xFF, 0xFE, 0x41]))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        assert_eq!(vfs.read_to_string("le").unwrap().as_str(), text);
+        assert_eq!(vfs.read_to_string("be").unwrap().as_str(), text);
+        assert_eq!(
+            vfs.read_to_string("odd").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn read_to_string_rejects_invalid_utf16() {
+        let mut imfs = InMemoryFs::new();
+        // A high surrogate with no low surrogate after it.
+        imfs.load_snapshot(
+            "le",
+            VfsSnapshot::file(vec![0xFF, 0xFE, 0x00, 0xD8, 0x41, 0x00]),
+        )
+        .unwrap();
+        imfs.load_snapshot("be", VfsSnapshot::file(vec![0xFE, 0xFF, 0xDC, 0x00]))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        for path in ["le", "be"] {
+            assert_eq!(
+                vfs.read_to_string(path).unwrap_err().kind(),
+                io::ErrorKind::InvalidData
+            );
+        }
+    }
+
+    #[test]
+    fn utf16_files_are_written_back_as_utf8() {
+        let mut contents = vec![0xFF, 0xFE];
+        for unit in "return 1\r\n".encode_utf16() {
+            contents.extend(unit.to_le_bytes());
+        }
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("script.luau", VfsSnapshot::file(contents))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        vfs.set_watch_enabled(false);
+
+        let text = vfs.read_to_string_lf_normalized("script.luau").unwrap();
+        vfs.write("script.luau", text.as_bytes()).unwrap();
+        assert_eq!(vfs.read("script.luau").unwrap().as_slice(), b"return 1\n");
+    }
+
+    #[test]
+    fn stats_count_backend_operations() {
+        let vfs = Vfs::new(InMemoryFs::new());
+        vfs.set_watch_enabled(false);
+
+        vfs.create_dir_all("/project/src").unwrap();
+        vfs.write("/project/src/a.luau", "a").unwrap();
+        vfs.write("/project/src/b.luau", "b").unwrap();
+        vfs.read("/project/src/a.luau").unwrap();
+        vfs.read_to_string("/project/src/b.luau").unwrap();
+        vfs.read_dir("/project/src").unwrap().for_each(drop);
+        vfs.metadata("/project/src").unwrap();
+        assert!(vfs.exists("/project/missing.luau").is_ok());
+        vfs.rename("/project/src/b.luau", "/project/src/c.luau")
+            .unwrap();
+        vfs.remove_file("/project/src/a.luau").unwrap();
+
+        assert_eq!(
+            vfs.stats(),
+            VfsStats {
+                reads: 2,
+                writes: 2,
+                read_dirs: 1,
+                metadata: 2,
+                create_dirs: 1,
+                removes: 1,
+                renames: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn stats_skip_prefetch_hits() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("test", VfsSnapshot::file("backend"))
+            .unwrap();
+        let vfs = Vfs::new(imfs);
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]), None);
+
+        vfs.read("test").unwrap();
+        assert_eq!(vfs.stats().reads, 0);
+
+        vfs.read("test").unwrap();
+        assert_eq!(vfs.stats().reads, 1);
+    }
+
     fn make_prefetch(files: Vec<(&str, &[u8])>) -> PrefetchCache {
         PrefetchCache {
             files: files
@@ -774,7 +1374,7 @@ mod test {
     fn prefetch_cache_read_hit() {
         let imfs = InMemoryFs::new();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]), None);
 
         assert_eq!(vfs.read("test").unwrap().as_slice(), b"cached");
     }
@@ -785,7 +1385,7 @@ mod test {
         imfs.load_snapshot("test", VfsSnapshot::file("backend"))
             .unwrap();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]), None);
 
         assert_eq!(vfs.read("test").unwrap().as_slice(), b"cached");
         assert_eq!(vfs.read("test").unwrap().as_slice(), b"backend");
@@ -797,7 +1397,7 @@ mod test {
         imfs.load_snapshot("other", VfsSnapshot::file("backend"))
             .unwrap();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]), None);
 
         assert_eq!(vfs.read("other").unwrap().as_slice(), b"backend");
     }
@@ -806,7 +1406,7 @@ mod test {
     fn prefetch_cache_read_to_string_hit() {
         let imfs = InMemoryFs::new();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"hello")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"hello")]), None);
 
         assert_eq!(vfs.read_to_string("test").unwrap().as_str(), "hello");
     }
@@ -815,7 +1415,7 @@ mod test {
     fn prefetch_cache_lf_normalized_hit() {
         let imfs = InMemoryFs::new();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"line1\r\nline2\r\n")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"line1\r\nline2\r\n")]), None);
 
         assert_eq!(
             vfs.read_to_string_lf_normalized("test").unwrap().as_str(),
@@ -829,7 +1429,7 @@ mod test {
         imfs.load_snapshot("test", VfsSnapshot::file("backend"))
             .unwrap();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]), None);
         vfs.clear_prefetch_cache();
 
         assert_eq!(vfs.read("test").unwrap().as_slice(), b"backend");
@@ -899,13 +1499,16 @@ mod test {
         let vfs = Vfs::new(StdBackend::new_for_testing());
         let mut cache_files = HashMap::new();
         cache_files.insert(file_path.clone(), contents.as_bytes().to_vec());
-        vfs.set_prefetch_cache(PrefetchCache {
-            files: cache_files,
-            is_file: HashMap::new(),
-            children: HashMap::new(),
-            dir_init: HashMap::new(),
-            walked_roots: Vec::new(),
-        });
+        vfs.set_prefetch_cache(
+            PrefetchCache {
+                files: cache_files,
+                is_file: HashMap::new(),
+                children: HashMap::new(),
+                dir_init: HashMap::new(),
+                walked_roots: Vec::new(),
+            },
+            None,
+        );
 
         let result = vfs.read(&file_path).unwrap();
         assert_eq!(result.as_slice(), contents.as_bytes());
@@ -915,7 +1518,7 @@ mod test {
     fn prefetch_cache_read_to_string_invalid_utf8() {
         let imfs = InMemoryFs::new();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", &[0xFF, 0xFE, 0x00, 0x80])]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", &[0x80, 0xFF])]), None);
 
         let err = vfs.read_to_string("test").unwrap_err();
         assert_eq!(err.kind(), io::ErrorKind::InvalidData);
@@ -926,8 +1529,8 @@ mod test {
         let imfs = InMemoryFs::new();
         let vfs = Vfs::new(imfs);
 
-        vfs.set_prefetch_cache(make_prefetch(vec![("a", b"first")]));
-        vfs.set_prefetch_cache(make_prefetch(vec![("a", b"second")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("a", b"first")]), None);
+        vfs.set_prefetch_cache(make_prefetch(vec![("a", b"second")]), None);
 
         assert_eq!(vfs.read("a").unwrap().as_slice(), b"second");
     }
@@ -946,13 +1549,16 @@ mod test {
         }
 
         let vfs = Arc::new(Vfs::new(StdBackend::new_for_testing()));
-        vfs.set_prefetch_cache(PrefetchCache {
-            files: cache_files,
-            is_file: HashMap::new(),
-            children: HashMap::new(),
-            dir_init: HashMap::new(),
-            walked_roots: Vec::new(),
-        });
+        vfs.set_prefetch_cache(
+            PrefetchCache {
+                files: cache_files,
+                is_file: HashMap::new(),
+                children: HashMap::new(),
+                dir_init: HashMap::new(),
+                walked_roots: Vec::new(),
+            },
+            None,
+        );
 
         let handles: Vec<_> = (0..100)
             .map(|i| {
@@ -988,13 +1594,16 @@ mod test {
         }
 
         let vfs = Vfs::new(StdBackend::new_for_testing());
-        vfs.set_prefetch_cache(PrefetchCache {
-            files: cache_files,
-            is_file: HashMap::new(),
-            children: HashMap::new(),
-            dir_init: HashMap::new(),
-            walked_roots: Vec::new(),
-        });
+        vfs.set_prefetch_cache(
+            PrefetchCache {
+                files: cache_files,
+                is_file: HashMap::new(),
+                children: HashMap::new(),
+                dir_init: HashMap::new(),
+                walked_roots: Vec::new(),
+            },
+            None,
+        );
 
         for i in 0..50 {
             let path = dir.path().join(format!("f{i}.txt"));
@@ -1036,13 +1645,16 @@ mod test {
         is_file.insert(PathBuf::from("/root/known.txt"), true);
         is_file.insert(PathBuf::from("/root/subdir"), false);
 
-        vfs.set_prefetch_cache(PrefetchCache {
-            files: HashMap::new(),
-            is_file,
-            children: HashMap::new(),
-            dir_init: HashMap::new(),
-            walked_roots: vec![PathBuf::from("/root")],
-        });
+        vfs.set_prefetch_cache(
+            PrefetchCache {
+                files: HashMap::new(),
+                is_file,
+                children: HashMap::new(),
+                dir_init: HashMap::new(),
+                walked_roots: vec![PathBuf::from("/root")],
+            },
+            None,
+        );
 
         let known = vfs.metadata("/root/known.txt").unwrap();
         assert!(
@@ -1070,13 +1682,60 @@ mod test {
         );
     }
 
+    #[test]
+    fn prefetch_cache_budget_drops_contents_but_keeps_metadata() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/root",
+            VfsSnapshot::dir(HashMap::from([
+                ("a.txt", VfsSnapshot::file("backend a")),
+                ("b.txt", VfsSnapshot::file("backend b")),
+            ])),
+        )
+        .unwrap();
+
+        let mut cache = make_prefetch(vec![("/root/a.txt", b"aaaa"), ("/root/b.txt", b"bbbb")]);
+        cache.is_file.insert(PathBuf::from("/root"), false);
+        cache.is_file.insert(PathBuf::from("/root/a.txt"), true);
+        cache.is_file.insert(PathBuf::from("/root/b.txt"), true);
+        cache.walked_roots.push(PathBuf::from("/root"));
+        assert_eq!(cache.total_bytes(), 8);
+
+        let vfs = Vfs::new(imfs);
+        vfs.set_prefetch_cache(cache, Some(4));
+
+        {
+            let inner = vfs.inner.lock().unwrap();
+            let cache = inner.prefetch_cache.as_ref().unwrap();
+            assert_eq!(cache.total_bytes(), 4);
+            assert!(cache.files.contains_key(&PathBuf::from("/root/a.txt")));
+            assert!(
+                !cache.files.contains_key(&PathBuf::from("/root/b.txt")),
+                "file beyond the budget should not be cached"
+            );
+            assert_eq!(
+                cache.is_file.get(&PathBuf::from("/root/b.txt")),
+                Some(&true),
+                "metadata for files beyond the budget should be kept"
+            );
+        }
+
+        assert!(vfs.metadata("/root/b.txt").unwrap().is_file());
+        assert_eq!(vfs.read("/root/a.txt").unwrap().as_slice(), b"aaaa");
+        assert_eq!(
+            vfs.read("/root/b.txt").unwrap().as_slice(),
+            b"backend b",
+            "read of a file beyond the budget should fall through to the backend"
+        );
+    }
+
     #[test]
     fn prefetch_cache_read_after_write_ignores_cache() {
         let mut imfs = InMemoryFs::new();
         imfs.load_snapshot("test", VfsSnapshot::file("original"))
             .unwrap();
         let vfs = Vfs::new(imfs);
-        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]));
+        vfs.set_prefetch_cache(make_prefetch(vec![("test", b"cached")]), None);
 
         vfs.write("test", b"written").unwrap();
         let data = vfs.read("test").unwrap();
@@ -1093,4 +1752,506 @@ mod test {
             "After cache depleted, should see the written data"
         );
     }
+
+    #[test]
+    fn rename_moves_directory_subtree() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/root",
+            VfsSnapshot::dir(HashMap::from([(
+                "old",
+                VfsSnapshot::dir(HashMap::from([
+                    ("a.txt", VfsSnapshot::file("a")),
+                    (
+                        "nested",
+                        VfsSnapshot::dir(HashMap::from([("b.txt", VfsSnapshot::file("b"))])),
+                    ),
+                ])),
+            )])),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        vfs.rename("/root/old", "/root/new").unwrap();
+
+        assert!(!vfs.exists("/root/old").unwrap());
+        assert!(!vfs.exists("/root/old/a.txt").unwrap());
+        assert_eq!(vfs.read("/root/new/a.txt").unwrap().as_slice(), b"a");
+        assert_eq!(vfs.read("/root/new/nested/b.txt").unwrap().as_slice(), b"b");
+
+        let root_children: Vec<PathBuf> = vfs
+            .read_dir("/root")
+            .unwrap()
+            .map(|entry| entry.unwrap().path().to_path_buf())
+            .collect();
+        assert_eq!(root_children, vec![PathBuf::from("/root/new")]);
+
+        let nested_children: Vec<PathBuf> = vfs
+            .read_dir("/root/new/nested")
+            .unwrap()
+            .map(|entry| entry.unwrap().path().to_path_buf())
+            .collect();
+        assert_eq!(
+            nested_children,
+            vec![PathBuf::from("/root/new/nested/b.txt")]
+        );
+    }
+
+    #[test]
+    fn rename_replaces_existing_file() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/root",
+            VfsSnapshot::dir(HashMap::from([
+                ("from.txt", VfsSnapshot::file("from")),
+                ("to.txt", VfsSnapshot::file("to")),
+            ])),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        vfs.rename("/root/from.txt", "/root/to.txt").unwrap();
+
+        assert!(!vfs.exists("/root/from.txt").unwrap());
+        assert_eq!(vfs.read("/root/to.txt").unwrap().as_slice(), b"from");
+        assert_eq!(vfs.read_dir("/root").unwrap().count(), 1);
+    }
+
+    #[test]
+    fn rename_rejects_mismatched_kinds_and_missing_source() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/root",
+            VfsSnapshot::dir(HashMap::from([
+                ("file.txt", VfsSnapshot::file("file")),
+                ("dir", VfsSnapshot::empty_dir()),
+            ])),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        assert!(vfs.rename("/root/file.txt", "/root/dir").is_err());
+        assert!(vfs.rename("/root/dir", "/root/file.txt").is_err());
+        assert!(vfs.rename("/root/dir", "/root/dir/inner").is_err());
+        assert_eq!(
+            vfs.rename("/root/missing", "/root/other")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(vfs.read("/root/file.txt").unwrap().as_slice(), b"file");
+    }
+
+    #[test]
+    fn copy_duplicates_file_contents() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/root",
+            VfsSnapshot::dir(HashMap::from([("a.txt", VfsSnapshot::file("hello"))])),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        assert_eq!(vfs.copy("/root/a.txt", "/root/b.txt").unwrap(), 5);
+        assert_eq!(vfs.read("/root/a.txt").unwrap().as_slice(), b"hello");
+        assert_eq!(vfs.read("/root/b.txt").unwrap().as_slice(), b"hello");
+        assert!(vfs.copy("/root", "/other").is_err());
+    }
+
+    #[test]
+    fn rename_and_copy_std_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("from.txt");
+        let copied = dir.path().join("copied.txt");
+        let renamed = dir.path().join("renamed.txt");
+        fs_err::write(&from, "contents").unwrap();
+
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        assert_eq!(vfs.copy(&from, &copied).unwrap(), 8);
+        vfs.rename(&from, &renamed).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(fs_err::read_to_string(&copied).unwrap(), "contents");
+        assert_eq!(fs_err::read_to_string(&renamed).unwrap(), "contents");
+    }
+
+    #[test]
+    fn rename_and_copy_unsupported_on_noop_backend() {
+        let vfs = Vfs::new(NoopBackend::new());
+        assert_eq!(
+            vfs.rename("a", "b").unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+        assert_eq!(
+            vfs.copy("a", "b").unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+    }
+
+    #[test]
+    fn read_range_in_memory() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/model.rbxm", VfsSnapshot::file("0123456789"))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        assert_eq!(vfs.read_range("/model.rbxm", 2, 4).unwrap(), b"2345");
+        assert_eq!(vfs.read_range("/model.rbxm", 0, 0).unwrap(), b"");
+        assert_eq!(
+            vfs.read_range("/missing", 0, 4).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn read_range_past_eof_returns_short_buffer() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/model.rbxm", VfsSnapshot::file("0123456789"))
+            .unwrap();
+        let vfs = Vfs::new(imfs);
+        assert_eq!(vfs.read_range("/model.rbxm", 8, 16).unwrap(), b"89");
+        assert_eq!(vfs.read_range("/model.rbxm", 10, 16).unwrap(), b"");
+        assert_eq!(vfs.read_range("/model.rbxm", u64::MAX, 16).unwrap(), b"");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.rbxm");
+        fs_err::write(&path, "0123456789").unwrap();
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        assert_eq!(vfs.read_range(&path, 2, 4).unwrap(), b"2345");
+        assert_eq!(vfs.read_range(&path, 8, 16).unwrap(), b"89");
+        assert_eq!(vfs.read_range(&path, 100, 16).unwrap(), b"");
+    }
+
+    #[test]
+    fn export_to_disk_matches_std_backend_reads() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/project",
+            VfsSnapshot::dir([
+                ("default.project.json5", VfsSnapshot::file("{}")),
+                (
+                    "src",
+                    VfsSnapshot::dir([
+                        ("init.luau", VfsSnapshot::file("return {}")),
+                        (
+                            "Model.rbxm",
+                            VfsSnapshot::file(vec![0u8, 159, 146, 150, 255]),
+                        ),
+                        ("empty", VfsSnapshot::empty_dir()),
+                    ]),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        imfs.export_to_disk(dir.path()).unwrap();
+
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        let exported = dir.path().join("project");
+        for relative in ["default.project.json5", "src/init.luau", "src/Model.rbxm"] {
+            let in_memory = Path::new("/project").join(relative);
+            assert_eq!(
+                vfs.read(exported.join(relative)).unwrap(),
+                Vfs::new(imfs.clone()).read(&in_memory).unwrap(),
+                "{relative} differs after export"
+            );
+        }
+        assert!(exported.join("src/empty").is_dir());
+
+        let snapshot = VfsSnapshot::from_in_memory(&imfs, "/project").unwrap();
+        let mut reloaded = InMemoryFs::new();
+        reloaded.load_snapshot("/project", snapshot).unwrap();
+        assert!(VfsSnapshot::from_in_memory(&reloaded, "/project")
+            .unwrap()
+            .diff(&VfsSnapshot::from_in_memory(&imfs, "/project").unwrap())
+            .is_empty());
+    }
+
+    #[test]
+    fn case_insensitive_lookups_only_when_enabled() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/project",
+            VfsSnapshot::dir([
+                ("foo.luau", VfsSnapshot::file("return 1")),
+                (
+                    "Dir",
+                    VfsSnapshot::dir([("init.luau", VfsSnapshot::empty_file())]),
+                ),
+            ]),
+        )
+        .unwrap();
+        let vfs = Vfs::new(imfs.clone());
+
+        assert!(vfs.exists("/project/foo.luau").unwrap());
+        assert!(!vfs.exists("/project/FOO.luau").unwrap());
+        assert!(vfs.read("/project/FOO.luau").is_err());
+
+        imfs.set_case_insensitive(true);
+
+        assert!(vfs.exists("/project/FOO.luau").unwrap());
+        assert_eq!(
+            vfs.read("/project/FOO.luau").unwrap().as_slice(),
+            b"return 1"
+        );
+        assert!(vfs.metadata("/PROJECT/Foo.Luau").unwrap().is_file());
+        assert!(vfs.exists("/project/dir/INIT.luau").unwrap());
+
+        // Directory listings keep the stored casing.
+        let children: Vec<PathBuf> = vfs
+            .read_dir("/project/DIR")
+            .unwrap()
+            .map(|entry| entry.unwrap().path().to_path_buf())
+            .collect();
+        assert_eq!(children, vec![PathBuf::from("/project/Dir/init.luau")]);
+
+        imfs.set_case_insensitive(false);
+        assert!(!vfs.exists("/project/FOO.luau").unwrap());
+    }
+
+    #[test]
+    fn pushed_events_reach_vfs_in_order() {
+        let imfs = InMemoryFs::new();
+        let vfs = Vfs::new(imfs.clone());
+        let receiver = vfs.event_receiver();
+
+        imfs.push_event(VfsEvent::Create(PathBuf::from("/a")));
+        imfs.push_event(VfsEvent::Write(PathBuf::from("/a")));
+        imfs.push_event(VfsEvent::Remove(PathBuf::from("/a")));
+
+        let received: Vec<VfsEvent> = receiver.try_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                VfsEvent::Create(PathBuf::from("/a")),
+                VfsEvent::Write(PathBuf::from("/a")),
+                VfsEvent::Remove(PathBuf::from("/a")),
+            ]
+        );
+    }
+
+    #[test]
+    fn watch_filter_skips_non_matching_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let other = dir.path().join("other");
+        fs_err::create_dir_all(&src).unwrap();
+        fs_err::create_dir_all(&other).unwrap();
+        fs_err::write(src.join("main.luau"), "main").unwrap();
+        fs_err::write(other.join("notes.txt"), "notes").unwrap();
+        fs_err::write(dir.path().join("default.project.json5"), "{}").unwrap();
+
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        vfs.set_watch_filter(globset::Glob::new("**/{src,src/**,*.project.json5}").unwrap());
+        vfs.start_watch_recording();
+
+        vfs.read_dir(&src).unwrap().for_each(drop);
+        vfs.read_dir(&other).unwrap().for_each(drop);
+        vfs.read(src.join("main.luau")).unwrap();
+        vfs.read(other.join("notes.txt")).unwrap();
+        vfs.read(dir.path().join("default.project.json5")).unwrap();
+
+        let recorded = vfs.take_recorded_paths().unwrap();
+        assert!(recorded.contains(&src));
+        assert!(recorded.contains(&src.join("main.luau")));
+        assert!(recorded.contains(&dir.path().join("default.project.json5")));
+        assert!(
+            !recorded.contains(&other),
+            "filtered-out directory should not be watched"
+        );
+        assert!(
+            !recorded.contains(&other.join("notes.txt")),
+            "filtered-out file should not be watched"
+        );
+    }
+
+    #[test]
+    fn watch_filter_cleared_watches_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        fs_err::write(&file, "notes").unwrap();
+
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        vfs.set_watch_filter(globset::Glob::new("**/src/**").unwrap());
+        vfs.clear_watch_filter();
+        vfs.start_watch_recording();
+
+        vfs.read(&file).unwrap();
+
+        let recorded = vfs.take_recorded_paths().unwrap();
+        assert!(recorded.contains(&file));
+    }
+
+    /// Backend wrapping `InMemoryFs` that keeps track of which paths are
+    /// currently watched, so tests can observe watch registration.
+    struct WatchTrackingBackend {
+        fs: InMemoryFs,
+        watched: Arc<Mutex<HashSet<PathBuf>>>,
+    }
+
+    impl crate::sealed::Sealed for WatchTrackingBackend {}
+
+    impl VfsBackend for WatchTrackingBackend {
+        fn read(&mut self, path: &Path) -> io::Result<Vec<u8>> {
+            self.fs.read(path)
+        }
+        fn read_range(&mut self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+            self.fs.read_range(path, offset, len)
+        }
+        fn write(&mut self, path: &Path, data: &[u8]) -> io::Result<()> {
+            self.fs.write(path, data)
+        }
+        fn exists(&mut self, path: &Path) -> io::Result<bool> {
+            self.fs.exists(path)
+        }
+        fn read_dir(&mut self, path: &Path) -> io::Result<ReadDir> {
+            self.fs.read_dir(path)
+        }
+        fn create_dir(&mut self, path: &Path) -> io::Result<()> {
+            self.fs.create_dir(path)
+        }
+        fn create_dir_all(&mut self, path: &Path) -> io::Result<()> {
+            self.fs.create_dir_all(path)
+        }
+        fn metadata(&mut self, path: &Path) -> io::Result<Metadata> {
+            self.fs.metadata(path)
+        }
+        fn remove_file(&mut self, path: &Path) -> io::Result<()> {
+            self.fs.remove_file(path)
+        }
+        fn remove_dir_all(&mut self, path: &Path) -> io::Result<()> {
+            self.fs.remove_dir_all(path)
+        }
+        fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+            self.fs.rename(from, to)
+        }
+        fn copy(&mut self, from: &Path, to: &Path) -> io::Result<u64> {
+            self.fs.copy(from, to)
+        }
+        fn event_receiver(&self) -> crossbeam_channel::Receiver<VfsEvent> {
+            self.fs.event_receiver()
+        }
+        fn watch(&mut self, path: &Path, _recursive: bool) -> io::Result<()> {
+            self.watched.lock().unwrap().insert(path.to_path_buf());
+            Ok(())
+        }
+        fn unwatch(&mut self, path: &Path) -> io::Result<()> {
+            self.watched.lock().unwrap().remove(path);
+            Ok(())
+        }
+        fn watched_paths(&self) -> Vec<PathBuf> {
+            self.watched.lock().unwrap().iter().cloned().collect()
+        }
+    }
+
+    fn watch_tracking_vfs() -> (Vfs, InMemoryFs, Arc<Mutex<HashSet<PathBuf>>>) {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/root",
+            VfsSnapshot::dir(HashMap::from([(
+                "big",
+                VfsSnapshot::dir(HashMap::from([("a.txt", VfsSnapshot::file("a"))])),
+            )])),
+        )
+        .unwrap();
+
+        let watched = Arc::new(Mutex::new(HashSet::new()));
+        let vfs = Vfs::new(WatchTrackingBackend {
+            fs: imfs.clone(),
+            watched: Arc::clone(&watched),
+        });
+        vfs.set_unwatch_grace_period(Some(Duration::from_millis(50)));
+
+        vfs.read_dir("/root/big").unwrap().for_each(drop);
+        vfs.read("/root/big/a.txt").unwrap();
+
+        (vfs, imfs, watched)
+    }
+
+    #[test]
+    fn removed_paths_unwatched_after_grace_period() {
+        let (vfs, mut imfs, watched) = watch_tracking_vfs();
+        assert_eq!(watched.lock().unwrap().len(), 2);
+
+        imfs.remove_dir_all(Path::new("/root/big")).unwrap();
+        vfs.commit_event(&VfsEvent::Remove(PathBuf::from("/root/big/a.txt")))
+            .unwrap();
+        vfs.commit_event(&VfsEvent::Remove(PathBuf::from("/root/big")))
+            .unwrap();
+
+        assert_eq!(
+            vfs.release_expired_watches(),
+            0,
+            "watches should survive until the grace period ends"
+        );
+        assert_eq!(watched.lock().unwrap().len(), 2);
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(vfs.release_expired_watches(), 2);
+        assert!(watched.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn recreated_paths_keep_their_watches() {
+        let (vfs, mut imfs, watched) = watch_tracking_vfs();
+
+        imfs.remove_dir_all(Path::new("/root/big")).unwrap();
+        vfs.commit_event(&VfsEvent::Remove(PathBuf::from("/root/big/a.txt")))
+            .unwrap();
+        vfs.commit_event(&VfsEvent::Remove(PathBuf::from("/root/big")))
+            .unwrap();
+
+        imfs.write(Path::new("/root/big/a.txt"), b"undo").unwrap();
+        vfs.commit_event(&VfsEvent::Create(PathBuf::from("/root/big/a.txt")))
+            .unwrap();
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(vfs.release_expired_watches(), 0);
+        assert_eq!(watched.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn apply_batch_creates_dirs_before_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a").join("b");
+
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        vfs.set_watch_enabled(false);
+        vfs.apply_batch(vec![
+            FsOp::Write(nested.join("file.txt"), b"hello"),
+            FsOp::CreateDir(nested.clone()),
+            FsOp::CreateDir(dir.path().join("a")),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            fs_err::read_to_string(nested.join("file.txt")).unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn apply_batch_reports_failing_op() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let last = dir.path().join("last.txt");
+
+        let vfs = Vfs::new(StdBackend::new_for_testing());
+        vfs.set_watch_enabled(false);
+        let err = vfs
+            .apply_batch(vec![
+                FsOp::Write(first.clone(), b"first"),
+                FsOp::RemoveFile(dir.path().join("missing.txt")),
+                FsOp::Write(last.clone(), b"last"),
+            ])
+            .unwrap_err();
+
+        assert_eq!(err.index, 1);
+        assert_eq!(err.source.kind(), io::ErrorKind::NotFound);
+        assert_eq!(fs_err::read_to_string(&first).unwrap(), "first");
+        assert!(!last.exists());
+    }
 }
@@ -1,5 +1,5 @@
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::{Metadata, ReadDir, VfsBackend, VfsEvent};
 
@@ -18,6 +18,10 @@ impl VfsBackend for NoopBackend {
         Err(io::Error::other("NoopBackend doesn't do anything"))
     }
 
+    fn read_range(&mut self, _path: &Path, _offset: u64, _len: usize) -> io::Result<Vec<u8>> {
+        Err(io::Error::other("NoopBackend doesn't do anything"))
+    }
+
     fn write(&mut self, _path: &Path, _data: &[u8]) -> io::Result<()> {
         Err(io::Error::other("NoopBackend doesn't do anything"))
     }
@@ -46,6 +50,20 @@ impl VfsBackend for NoopBackend {
         Err(io::Error::other("NoopBackend doesn't do anything"))
     }
 
+    fn rename(&mut self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "NoopBackend doesn't do anything",
+        ))
+    }
+
+    fn copy(&mut self, _from: &Path, _to: &Path) -> io::Result<u64> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "NoopBackend doesn't do anything",
+        ))
+    }
+
     fn metadata(&mut self, _path: &Path) -> io::Result<Metadata> {
         Err(io::Error::other("NoopBackend doesn't do anything"))
     }
@@ -61,6 +79,10 @@ impl VfsBackend for NoopBackend {
     fn unwatch(&mut self, _path: &Path) -> io::Result<()> {
         Err(io::Error::other("NoopBackend doesn't do anything"))
     }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 impl Default for NoopBackend {
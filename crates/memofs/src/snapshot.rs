@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{InMemoryFs, VfsEvent};
 
 /// A slice of a tree of files. Can be loaded into an
 /// [`InMemoryFs`](struct.InMemoryFs.html).
@@ -15,6 +19,37 @@ pub enum VfsSnapshot {
     },
 }
 
+/// A single difference between two [`VfsSnapshot`]s, as returned by
+/// [`VfsSnapshot::diff`]. Mirrors [`VfsEvent`]; paths are relative to the
+/// root of the diffed snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SnapshotChange {
+    Create(PathBuf),
+    Write(PathBuf),
+    Remove(PathBuf),
+}
+
+impl SnapshotChange {
+    /// Converts this change into a [`VfsEvent`] for a snapshot that lives at
+    /// `root`, suitable for passing to `Vfs::commit_event`.
+    pub fn to_event(&self, root: &Path) -> VfsEvent {
+        let join = |relative: &PathBuf| {
+            if relative.as_os_str().is_empty() {
+                root.to_path_buf()
+            } else {
+                root.join(relative)
+            }
+        };
+
+        match self {
+            SnapshotChange::Create(path) => VfsEvent::Create(join(path)),
+            SnapshotChange::Write(path) => VfsEvent::Write(join(path)),
+            SnapshotChange::Remove(path) => VfsEvent::Remove(join(path)),
+        }
+    }
+}
+
 impl VfsSnapshot {
     pub fn file<C: Into<Vec<u8>>>(contents: C) -> Self {
         Self::File {
@@ -42,4 +77,156 @@ impl VfsSnapshot {
             children: BTreeMap::new(),
         }
     }
+
+    /// Captures the subtree at `root` in `fs` as a snapshot. Loading the
+    /// result back with [`InMemoryFs::load_snapshot`] recreates the same
+    /// tree.
+    pub fn from_in_memory<P: AsRef<Path>>(fs: &InMemoryFs, root: P) -> io::Result<Self> {
+        fs.snapshot(root.as_ref())
+    }
+
+    /// Computes the changes needed to turn `self` into `other`.
+    ///
+    /// Added and removed entries are reported once at the top of the subtree
+    /// that appeared or disappeared, files whose contents differ are reported
+    /// as writes, and an entry that switched between file and directory is
+    /// reported as a removal followed by a creation. Changes are ordered by
+    /// path.
+    pub fn diff(&self, other: &VfsSnapshot) -> Vec<SnapshotChange> {
+        let mut changes = Vec::new();
+        diff_into(self, other, &mut PathBuf::new(), &mut changes);
+        changes
+    }
+}
+
+fn diff_into(
+    old: &VfsSnapshot,
+    new: &VfsSnapshot,
+    path: &mut PathBuf,
+    changes: &mut Vec<SnapshotChange>,
+) {
+    match (old, new) {
+        (VfsSnapshot::File { contents: old }, VfsSnapshot::File { contents: new }) => {
+            if old != new {
+                changes.push(SnapshotChange::Write(path.clone()));
+            }
+        }
+        (VfsSnapshot::Dir { children: old }, VfsSnapshot::Dir { children: new }) => {
+            let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+            names.sort();
+            names.dedup();
+
+            for name in names {
+                path.push(name);
+                match (old.get(name), new.get(name)) {
+                    (Some(old_child), Some(new_child)) => {
+                        diff_into(old_child, new_child, path, changes)
+                    }
+                    (Some(_), None) => changes.push(SnapshotChange::Remove(path.clone())),
+                    (None, Some(_)) => changes.push(SnapshotChange::Create(path.clone())),
+                    (None, None) => unreachable!(),
+                }
+                path.pop();
+            }
+        }
+        _ => {
+            changes.push(SnapshotChange::Remove(path.clone()));
+            changes.push(SnapshotChange::Create(path.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diff_renamed_file() {
+        let old = VfsSnapshot::dir([("old.luau", VfsSnapshot::file("print(1)"))]);
+        let new = VfsSnapshot::dir([("new.luau", VfsSnapshot::file("print(1)"))]);
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                SnapshotChange::Create(PathBuf::from("new.luau")),
+                SnapshotChange::Remove(PathBuf::from("old.luau")),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_changed_file_body() {
+        let old = VfsSnapshot::dir([(
+            "src",
+            VfsSnapshot::dir([
+                ("a.luau", VfsSnapshot::file("old")),
+                ("b.luau", VfsSnapshot::file("same")),
+            ]),
+        )]);
+        let new = VfsSnapshot::dir([(
+            "src",
+            VfsSnapshot::dir([
+                ("a.luau", VfsSnapshot::file("new")),
+                ("b.luau", VfsSnapshot::file("same")),
+            ]),
+        )]);
+
+        assert_eq!(
+            old.diff(&new),
+            vec![SnapshotChange::Write(PathBuf::from("src/a.luau"))]
+        );
+    }
+
+    #[test]
+    fn diff_directory_gained_child() {
+        let old = VfsSnapshot::dir([("src", VfsSnapshot::empty_dir())]);
+        let new = VfsSnapshot::dir([(
+            "src",
+            VfsSnapshot::dir([(
+                "nested",
+                VfsSnapshot::dir([("a.luau", VfsSnapshot::file("a"))]),
+            )]),
+        )]);
+
+        assert_eq!(
+            old.diff(&new),
+            vec![SnapshotChange::Create(PathBuf::from("src/nested"))]
+        );
+    }
+
+    #[test]
+    fn diff_directory_became_file() {
+        let old = VfsSnapshot::dir([(
+            "module",
+            VfsSnapshot::dir([("init.luau", VfsSnapshot::file("a"))]),
+        )]);
+        let new = VfsSnapshot::dir([("module", VfsSnapshot::file("a"))]);
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                SnapshotChange::Remove(PathBuf::from("module")),
+                SnapshotChange::Create(PathBuf::from("module")),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_identical_is_empty() {
+        let snapshot = VfsSnapshot::dir([("a.txt", VfsSnapshot::file("a"))]);
+        assert!(snapshot.diff(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn change_to_event_joins_root() {
+        let root = Path::new("/project");
+        assert_eq!(
+            SnapshotChange::Write(PathBuf::from("src/a.luau")).to_event(root),
+            VfsEvent::Write(PathBuf::from("/project/src/a.luau"))
+        );
+        assert_eq!(
+            SnapshotChange::Remove(PathBuf::new()).to_event(root),
+            VfsEvent::Remove(PathBuf::from("/project"))
+        );
+    }
 }
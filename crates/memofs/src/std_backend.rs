@@ -1,12 +1,16 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use std::{collections::HashSet, io};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 use crossbeam_channel::Receiver;
 use notify::event::{CreateKind, EventKind, ModifyKind, RemoveKind, RenameMode};
 use notify::RecursiveMode;
 
 #[cfg(target_os = "macos")]
-use {notify::Watcher, std::thread};
+use notify::Watcher as _;
 
 #[cfg(not(target_os = "macos"))]
 use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
@@ -54,16 +58,27 @@ impl std::error::Error for WatcherCriticalError {}
 /// Return `true` to exit the watcher thread, `false` to continue (if possible).
 pub type CriticalErrorHandler = Box<dyn Fn(WatcherCriticalError) -> bool + Send + Sync + 'static>;
 
-/// `VfsBackend` that uses `std::fs` and the `notify` crate.
-pub struct StdBackend {
+/// How a `StdBackend` finds out about changes to the paths it watches.
+enum Watcher {
     #[cfg(target_os = "macos")]
-    watcher: notify::RecommendedWatcher,
+    Native(notify::RecommendedWatcher),
     #[cfg(not(target_os = "macos"))]
-    debouncer: Debouncer<notify::RecommendedWatcher, RecommendedCache>,
+    Native(Debouncer<notify::RecommendedWatcher, RecommendedCache>),
+    Poll(Poller),
+}
+
+/// `VfsBackend` that uses `std::fs` and the `notify` crate.
+pub struct StdBackend {
+    watcher: Watcher,
     watcher_receiver: Receiver<VfsEvent>,
-    watches: HashSet<PathBuf>,
+    #[cfg(test)]
+    watcher_sender: crossbeam_channel::Sender<VfsEvent>,
+    /// Shared with the watcher thread so overflow rescans can be scoped to
+    /// the paths that are actually watched.
+    watches: Arc<Mutex<HashSet<PathBuf>>>,
     recursive_watches: HashSet<PathBuf>,
     critical_error_receiver: Receiver<WatcherCriticalError>,
+    read_only: bool,
 }
 
 impl StdBackend {
@@ -73,7 +88,21 @@ impl StdBackend {
     /// the directory tree). All other critical errors terminate the process
     /// because file watching cannot continue.
     pub fn new() -> StdBackend {
-        Self::new_with_error_handler(Box::new(|err| {
+        Self::new_with_error_handler(Self::default_error_handler())
+    }
+
+    /// Creates a new StdBackend that finds changes by scanning the watched
+    /// paths every `interval` instead of through native notifications, with
+    /// the same error handling as [`StdBackend::new`].
+    ///
+    /// Native notifications miss changes on network filesystems like NFS and
+    /// SMB; polling catches them at the cost of some latency and disk reads.
+    pub fn new_polling(interval: Duration) -> StdBackend {
+        Self::with_watch_strategy(Some(interval), Self::default_error_handler())
+    }
+
+    fn default_error_handler() -> CriticalErrorHandler {
+        Box::new(|err| {
             match &err {
                 WatcherCriticalError::RescanRequired => {
                     // Recoverable: the debouncer lost some events due to rapid
@@ -95,7 +124,7 @@ impl StdBackend {
                     std::process::exit(1);
                 }
             }
-        }))
+        })
     }
 
     /// Creates a new StdBackend with a custom error handler.
@@ -107,9 +136,46 @@ impl StdBackend {
     /// Critical errors are also sent to the `critical_error_receiver()` channel,
     /// which can be polled alongside `event_receiver()` for async error handling.
     pub fn new_with_error_handler(error_handler: CriticalErrorHandler) -> StdBackend {
+        Self::with_watch_strategy(None, error_handler)
+    }
+
+    /// Polls every `poll_interval` if one is given, and otherwise watches
+    /// through native notifications.
+    fn with_watch_strategy(
+        poll_interval: Option<Duration>,
+        error_handler: CriticalErrorHandler,
+    ) -> StdBackend {
         let (event_tx, event_rx) = crossbeam_channel::unbounded();
         let (error_tx, error_rx) = crossbeam_channel::unbounded();
+        let watches = Arc::new(Mutex::new(HashSet::new()));
+        #[cfg(test)]
+        let watcher_sender = event_tx.clone();
+
+        let watcher = match poll_interval {
+            Some(interval) => {
+                Watcher::Poll(Poller::start(interval, event_tx, error_tx, error_handler))
+            }
+            None => Self::native_watcher(event_tx, error_tx, error_handler, &watches),
+        };
+
+        Self {
+            watcher,
+            watcher_receiver: event_rx,
+            #[cfg(test)]
+            watcher_sender,
+            watches,
+            recursive_watches: HashSet::new(),
+            critical_error_receiver: error_rx,
+            read_only: false,
+        }
+    }
 
+    fn native_watcher(
+        event_tx: crossbeam_channel::Sender<VfsEvent>,
+        error_tx: crossbeam_channel::Sender<WatcherCriticalError>,
+        error_handler: CriticalErrorHandler,
+        watches: &Arc<Mutex<HashSet<PathBuf>>>,
+    ) -> Watcher {
         #[cfg(target_os = "macos")]
         let watcher = {
             let (raw_tx, raw_rx) =
@@ -125,6 +191,7 @@ impl StdBackend {
 
             let debounce_ms = std::time::Duration::from_millis(50);
 
+            let thread_watches = Arc::clone(watches);
             thread::spawn(move || {
                 use std::collections::HashMap;
                 use std::time::Instant;
@@ -136,12 +203,18 @@ impl StdBackend {
                     match raw_rx.recv_timeout(debounce_ms) {
                         Ok(Ok(event)) => {
                             last_event = Instant::now();
-                            for vfs_event in Self::convert_event(&event) {
+                            for vfs_event in Self::convert_event(&event, &thread_watches) {
                                 let path = match &vfs_event {
                                     VfsEvent::Create(p)
                                     | VfsEvent::Write(p)
-                                    | VfsEvent::Remove(p) => p.clone(),
+                                    | VfsEvent::Remove(p)
+                                    | VfsEvent::RescanRequired(p) => p.clone(),
                                 };
+                                // A pending rescan already covers any later
+                                // event for the same path.
+                                if let Some(VfsEvent::RescanRequired(_)) = pending.get(&path) {
+                                    continue;
+                                }
                                 pending.insert(path, vfs_event);
                             }
                         }
@@ -180,6 +253,7 @@ impl StdBackend {
         #[cfg(not(target_os = "macos"))]
         let debouncer = {
             let debounce_timeout = std::time::Duration::from_millis(50);
+            let thread_watches = Arc::clone(watches);
 
             new_debouncer(
                 debounce_timeout,
@@ -187,7 +261,7 @@ impl StdBackend {
                 move |result: DebounceEventResult| match result {
                     Ok(events) => {
                         for event in events {
-                            for vfs_event in Self::convert_event(&event.event) {
+                            for vfs_event in Self::convert_event(&event.event, &thread_watches) {
                                 if let Err(err) = event_tx.send(vfs_event) {
                                     let critical_err =
                                         WatcherCriticalError::ChannelSendFailed(err.to_string());
@@ -220,19 +294,60 @@ impl StdBackend {
             .expect("Failed to create file watcher debouncer")
         };
 
-        Self {
-            #[cfg(target_os = "macos")]
-            watcher,
-            #[cfg(not(target_os = "macos"))]
-            debouncer,
-            watcher_receiver: event_rx,
-            watches: HashSet::new(),
-            recursive_watches: HashSet::new(),
-            critical_error_receiver: error_rx,
+        #[cfg(target_os = "macos")]
+        let native = watcher;
+        #[cfg(not(target_os = "macos"))]
+        let native = debouncer;
+        Watcher::Native(native)
+    }
+
+    /// Creates a new StdBackend with default error handling that refuses to
+    /// modify the filesystem. See [`StdBackend::set_read_only`].
+    pub fn new_read_only() -> StdBackend {
+        let mut backend = Self::new();
+        backend.set_read_only(true);
+        backend
+    }
+
+    /// Turns read-only mode on or off. Off by default.
+    ///
+    /// While read-only, every mutating operation (`write`, `create_dir`,
+    /// `create_dir_all`, `remove_file`, `remove_dir_all`, `rename`, and `copy`)
+    /// fails with `PermissionDenied` before touching the disk.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Returns whether this backend is in read-only mode.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn check_writable(&self, operation: &str, path: &Path) -> io::Result<()> {
+        if self.read_only {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "cannot {} {}: the filesystem was opened read-only",
+                    operation,
+                    path.display()
+                ),
+            ))
+        } else {
+            Ok(())
         }
     }
 
-    fn convert_event(event: &notify::Event) -> Vec<VfsEvent> {
+    fn convert_event(event: &notify::Event, watches: &Mutex<HashSet<PathBuf>>) -> Vec<VfsEvent> {
+        if event.need_rescan() {
+            let vfs_events = Self::rescan_events(&event.paths, watches);
+            log::warn!(
+                "File watcher dropped events; requesting rescan of {} path(s)",
+                vfs_events.len()
+            );
+            return vfs_events;
+        }
+
         let mut vfs_events = Vec::new();
 
         match &event.kind {
@@ -315,6 +430,42 @@ impl StdBackend {
         vfs_events
     }
 
+    /// Builds the `RescanRequired` events for a watcher overflow. An overflow
+    /// that names paths is scoped to them; otherwise every top-level watched
+    /// path is rescanned, since events anywhere beneath them may be lost.
+    fn rescan_events(paths: &[PathBuf], watches: &Mutex<HashSet<PathBuf>>) -> Vec<VfsEvent> {
+        if !paths.is_empty() {
+            return paths
+                .iter()
+                .cloned()
+                .map(VfsEvent::RescanRequired)
+                .collect();
+        }
+
+        let watches = watches.lock().unwrap();
+        let mut roots: Vec<&PathBuf> = watches
+            .iter()
+            .filter(|path| !path.ancestors().skip(1).any(|a| watches.contains(a)))
+            .collect();
+        roots.sort();
+        roots
+            .into_iter()
+            .cloned()
+            .map(VfsEvent::RescanRequired)
+            .collect()
+    }
+
+    /// Feeds a synthetic overflow through the same conversion the watcher
+    /// thread uses, as if `notify` had reported a rescan for `paths`.
+    #[cfg(test)]
+    fn simulate_overflow(&self, paths: Vec<PathBuf>) {
+        let mut event = notify::Event::new(EventKind::Other).set_flag(notify::event::Flag::Rescan);
+        event.paths = paths;
+        for vfs_event in Self::convert_event(&event, &self.watches) {
+            self.watcher_sender.send(vfs_event).unwrap();
+        }
+    }
+
     /// Returns a receiver for critical errors from the watcher thread.
     ///
     /// Poll this alongside `event_receiver()` to detect when watching becomes unreliable.
@@ -334,6 +485,19 @@ impl StdBackend {
             true // Stop the thread without exiting the process
         }))
     }
+
+    /// Like [`StdBackend::new_for_testing`], but polls every `interval`
+    /// instead of using native notifications.
+    #[cfg(test)]
+    pub fn new_polling_for_testing(interval: Duration) -> StdBackend {
+        Self::with_watch_strategy(
+            Some(interval),
+            Box::new(|err| {
+                log::trace!("Test backend error (expected during test cleanup): {}", err);
+                true
+            }),
+        )
+    }
 }
 
 impl VfsBackend for StdBackend {
@@ -341,7 +505,18 @@ impl VfsBackend for StdBackend {
         fs_err::read(path)
     }
 
+    fn read_range(&mut self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut file = fs_err::File::open(path)?;
+        let available = file.metadata()?.len().saturating_sub(offset);
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut buffer = Vec::with_capacity(available.min(len as u64) as usize);
+        file.take(len as u64).read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
     fn write(&mut self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.check_writable("write", path)?;
         fs_err::write(path, data)
     }
 
@@ -365,19 +540,52 @@ impl VfsBackend for StdBackend {
     }
 
     fn create_dir(&mut self, path: &Path) -> io::Result<()> {
+        self.check_writable("create directory", path)?;
         fs_err::create_dir(path)
     }
 
     fn create_dir_all(&mut self, path: &Path) -> io::Result<()> {
+        self.check_writable("create directory", path)?;
         fs_err::create_dir_all(path)
     }
 
     fn remove_file(&mut self, path: &Path) -> io::Result<()> {
+        self.check_writable("remove", path)?;
         fs_err::remove_file(path)
     }
 
     fn remove_dir_all(&mut self, path: &Path) -> io::Result<()> {
-        fs_err::remove_dir_all(path)
+        self.check_writable("remove", path)?;
+        fs_err::remove_dir_all(path)?;
+
+        // The directory's own watch is dropped by the caller, but anything
+        // watched underneath it would otherwise linger in `watched_paths`.
+        let descendants: Vec<PathBuf> = self
+            .watches
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|watched| watched.starts_with(path) && watched.as_path() != path)
+            .cloned()
+            .collect();
+        for descendant in descendants {
+            if self.unwatch(&descendant).is_err() {
+                self.watches.lock().unwrap().remove(&descendant);
+                self.recursive_watches.remove(&descendant);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check_writable("rename", from)?;
+        fs_err::rename(from, to)
+    }
+
+    fn copy(&mut self, from: &Path, to: &Path) -> io::Result<u64> {
+        self.check_writable("copy to", to)?;
+        fs_err::copy(from, to)
     }
 
     fn metadata(&mut self, path: &Path) -> io::Result<Metadata> {
@@ -393,7 +601,8 @@ impl VfsBackend for StdBackend {
     }
 
     fn watch(&mut self, path: &Path, recursive: bool) -> io::Result<()> {
-        if self.watches.contains(path) && (!recursive || self.recursive_watches.contains(path)) {
+        let already_watched = self.watches.lock().unwrap().contains(path);
+        if already_watched && (!recursive || self.recursive_watches.contains(path)) {
             return Ok(());
         }
         if path
@@ -409,10 +618,12 @@ impl VfsBackend for StdBackend {
         } else {
             RecursiveMode::NonRecursive
         };
-        #[cfg(target_os = "macos")]
-        let result = self.watcher.watch(path, mode);
-        #[cfg(not(target_os = "macos"))]
-        let result = self.debouncer.watch(path, mode);
+        let result = match &mut self.watcher {
+            Watcher::Native(watcher) => watcher
+                .watch(path, mode)
+                .map_err(|err| io::Error::other(format!("{:?}", err))),
+            Watcher::Poll(poller) => poller.watch(path, recursive),
+        };
         match result {
             Ok(()) => {
                 log::debug!(
@@ -424,26 +635,29 @@ impl VfsBackend for StdBackend {
                     },
                     path.display()
                 );
-                self.watches.insert(path.to_path_buf());
+                self.watches.lock().unwrap().insert(path.to_path_buf());
                 if recursive {
                     self.recursive_watches.insert(path.to_path_buf());
                 }
                 Ok(())
             }
             Err(err) => {
-                log::warn!("Failed to watch path {}: {:?}", path.display(), err);
-                Err(io::Error::other(format!("{:?}", err)))
+                log::warn!("Failed to watch path {}: {}", path.display(), err);
+                Err(err)
             }
         }
     }
 
     fn unwatch(&mut self, path: &Path) -> io::Result<()> {
-        let was_watched = self.watches.contains(path);
+        let was_watched = self.watches.lock().unwrap().contains(path);
 
-        #[cfg(target_os = "macos")]
-        let result = self.watcher.unwatch(path);
-        #[cfg(not(target_os = "macos"))]
-        let result = self.debouncer.unwatch(path);
+        let result = match &mut self.watcher {
+            Watcher::Native(watcher) => watcher.unwatch(path),
+            Watcher::Poll(poller) => {
+                poller.unwatch(path);
+                Ok(())
+            }
+        };
         match result {
             Ok(()) => {
                 if was_watched {
@@ -454,7 +668,7 @@ impl VfsBackend for StdBackend {
                         path.display()
                     );
                 }
-                self.watches.remove(path);
+                self.watches.lock().unwrap().remove(path);
                 self.recursive_watches.remove(path);
                 Ok(())
             }
@@ -471,7 +685,7 @@ impl VfsBackend for StdBackend {
                         "Path was not directly watched (likely covered by parent): {}",
                         path.display()
                     );
-                    self.watches.remove(path);
+                    self.watches.lock().unwrap().remove(path);
                     self.recursive_watches.remove(path);
                     Ok(())
                 } else {
@@ -481,6 +695,10 @@ impl VfsBackend for StdBackend {
             }
         }
     }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        self.watches.lock().unwrap().iter().cloned().collect()
+    }
 }
 
 impl Default for StdBackend {
@@ -489,6 +707,174 @@ impl Default for StdBackend {
     }
 }
 
+/// Watches paths by scanning them on an interval and comparing what's there
+/// with the last scan: new paths become `Create` events, missing ones
+/// `Remove`, and files whose size or modification time changed `Write`.
+struct Poller {
+    state: Arc<Mutex<PollState>>,
+    /// Stops the polling thread when the poller is dropped.
+    _shutdown: crossbeam_channel::Sender<()>,
+}
+
+#[derive(Default)]
+struct PollState {
+    /// Watched paths, and whether everything beneath them is watched or
+    /// only their direct children.
+    roots: HashMap<PathBuf, bool>,
+    /// What every watched path looked like at the last scan.
+    known: HashMap<PathBuf, PollStamp>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PollStamp {
+    is_dir: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl PollStamp {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        Self {
+            is_dir: metadata.is_dir(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+impl Poller {
+    fn start(
+        interval: Duration,
+        event_tx: crossbeam_channel::Sender<VfsEvent>,
+        error_tx: crossbeam_channel::Sender<WatcherCriticalError>,
+        error_handler: CriticalErrorHandler,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(PollState::default()));
+        let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded::<()>(0);
+
+        let thread_state = Arc::clone(&state);
+        thread::spawn(move || loop {
+            match shutdown_rx.recv_timeout(interval) {
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                _ => return,
+            }
+
+            let events = thread_state.lock().unwrap().poll();
+            for vfs_event in events {
+                log::trace!("VFS event (poll): {:?}", vfs_event);
+                if let Err(err) = event_tx.send(vfs_event) {
+                    let critical_err = WatcherCriticalError::ChannelSendFailed(err.to_string());
+                    let _ = error_tx.send(critical_err.clone());
+                    if error_handler(critical_err) {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            state,
+            _shutdown: shutdown_tx,
+        }
+    }
+
+    /// Starts polling `path`. What's there now is recorded without producing
+    /// events, the same way a native watch only reports later changes.
+    fn watch(&self, path: &Path, recursive: bool) -> io::Result<()> {
+        fs_err::metadata(path)?;
+
+        let mut found = HashMap::new();
+        scan_for_poll(path, recursive, &mut found);
+
+        let mut state = self.state.lock().unwrap();
+        state.roots.insert(path.to_path_buf(), recursive);
+        for (path, stamp) in found {
+            state.known.entry(path).or_insert(stamp);
+        }
+        Ok(())
+    }
+
+    fn unwatch(&self, path: &Path) {
+        let mut state = self.state.lock().unwrap();
+        state.roots.remove(path);
+        let PollState { roots, known } = &mut *state;
+        known.retain(|known_path, _| poll_covers(roots, known_path));
+    }
+}
+
+impl PollState {
+    /// Scans every watched path and returns the events for what changed
+    /// since the last scan.
+    fn poll(&mut self) -> Vec<VfsEvent> {
+        let mut current = HashMap::new();
+        for (root, recursive) in &self.roots {
+            scan_for_poll(root, *recursive, &mut current);
+        }
+
+        let mut events = Vec::new();
+        for (path, stamp) in &current {
+            match self.known.get(path) {
+                None => events.push(VfsEvent::Create(path.clone())),
+                Some(old) if old != stamp && !stamp.is_dir => {
+                    events.push(VfsEvent::Write(path.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for path in self.known.keys() {
+            if !current.contains_key(path) {
+                events.push(VfsEvent::Remove(path.clone()));
+            }
+        }
+        events.sort_by(|a, b| poll_event_path(a).cmp(poll_event_path(b)));
+
+        self.known = current;
+        events
+    }
+}
+
+fn poll_event_path(event: &VfsEvent) -> &Path {
+    match event {
+        VfsEvent::Create(path)
+        | VfsEvent::Write(path)
+        | VfsEvent::Remove(path)
+        | VfsEvent::RescanRequired(path) => path,
+    }
+}
+
+/// Returns whether `path` is beneath one of `roots`.
+fn poll_covers(roots: &HashMap<PathBuf, bool>, path: &Path) -> bool {
+    roots.iter().any(|(root, recursive)| {
+        path == root || path.parent() == Some(root) || (*recursive && path.starts_with(root))
+    })
+}
+
+/// Records `path` in `found`, along with its direct children if it's a
+/// directory, or all of its descendants if `recursive`. Symlinked
+/// directories are recorded but not descended into.
+fn scan_for_poll(path: &Path, recursive: bool, found: &mut HashMap<PathBuf, PollStamp>) {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    found.insert(path.to_path_buf(), PollStamp::of(&metadata));
+    if !metadata.is_dir() {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = entry.path();
+        let is_symlink = entry.file_type().is_ok_and(|kind| kind.is_symlink());
+        if recursive && !is_symlink {
+            scan_for_poll(&child, true, found);
+        } else if let Ok(metadata) = std::fs::metadata(&child) {
+            found.insert(child, PollStamp::of(&metadata));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -504,6 +890,89 @@ mod tests {
         std::fs::canonicalize(dir.path()).unwrap_or_else(|_| dir.path().to_path_buf())
     }
 
+    #[test]
+    fn read_only_rejects_every_mutation() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("file.txt");
+        let sub_dir = dir.path().join("sub");
+        fs_err::write(&file_path, "original").unwrap();
+        fs_err::create_dir(&sub_dir).unwrap();
+
+        let mut backend = StdBackend::new_for_testing();
+        backend.set_read_only(true);
+        assert!(backend.is_read_only());
+
+        let new_path = dir.path().join("new");
+        let results = [
+            ("write", backend.write(&file_path, b"changed")),
+            ("create_dir", backend.create_dir(&new_path)),
+            (
+                "create_dir_all",
+                backend.create_dir_all(&new_path.join("a")),
+            ),
+            ("remove_file", backend.remove_file(&file_path)),
+            ("remove_dir_all", backend.remove_dir_all(&sub_dir)),
+            ("rename", backend.rename(&file_path, &new_path)),
+            ("copy", backend.copy(&file_path, &new_path).map(drop)),
+        ];
+        for (operation, result) in results {
+            let err = result.expect_err(operation);
+            assert_eq!(
+                err.kind(),
+                io::ErrorKind::PermissionDenied,
+                "{} should be denied",
+                operation
+            );
+        }
+
+        assert_eq!(fs_err::read_to_string(&file_path).unwrap(), "original");
+        assert!(sub_dir.is_dir());
+        assert!(!new_path.exists());
+        assert_eq!(backend.read(&file_path).unwrap(), b"original");
+    }
+
+    #[test]
+    fn overflow_emits_rescan_for_watch_roots() {
+        let dir = tempdir().unwrap();
+        let root = canonical_dir(&dir);
+        let sub_dir = root.join("sub");
+        let other_dir = tempdir().unwrap();
+        let other_root = canonical_dir(&other_dir);
+        fs_err::create_dir(&sub_dir).unwrap();
+
+        let mut backend = StdBackend::new_for_testing();
+        backend.watch(&root, false).unwrap();
+        backend.watch(&sub_dir, false).unwrap();
+        backend.watch(&other_root, false).unwrap();
+        let receiver = backend.event_receiver();
+
+        // An unscoped overflow rescans each top-level watch, but not paths
+        // nested under one.
+        backend.simulate_overflow(Vec::new());
+        let mut events: Vec<VfsEvent> = receiver.try_iter().collect();
+        events.sort_by_key(|event| format!("{:?}", event));
+        let mut expected = vec![
+            VfsEvent::RescanRequired(root.clone()),
+            VfsEvent::RescanRequired(other_root.clone()),
+        ];
+        expected.sort_by_key(|event| format!("{:?}", event));
+        assert_eq!(events, expected);
+
+        // An overflow that names a path is scoped to it.
+        backend.simulate_overflow(vec![sub_dir.clone()]);
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![VfsEvent::RescanRequired(sub_dir.clone())]
+        );
+
+        backend.unwatch(&other_root).unwrap();
+        backend.simulate_overflow(Vec::new());
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![VfsEvent::RescanRequired(root)]
+        );
+    }
+
     #[test]
     fn watch_adds_to_watches_only_on_success() {
         let dir = tempdir().unwrap();
@@ -526,6 +995,68 @@ mod tests {
         let _ = backend.watch(&nonexistent, true); // Result varies by platform
     }
 
+    #[test]
+    fn watched_paths_lists_read_directories() {
+        let dir = tempdir().unwrap();
+        let root = canonical_dir(&dir);
+        let first = root.join("first");
+        let second = root.join("second");
+        fs_err::create_dir(&first).unwrap();
+        fs_err::create_dir(&second).unwrap();
+        fs_err::write(first.join("a.txt"), "a").unwrap();
+        fs_err::write(second.join("b.txt"), "b").unwrap();
+
+        let vfs = crate::Vfs::new(StdBackend::new_for_testing());
+        for (dir, file) in [(&first, "a.txt"), (&second, "b.txt")] {
+            vfs.read_dir(dir).unwrap().for_each(drop);
+            vfs.read(dir.join(file)).unwrap();
+        }
+
+        let watched = vfs.watched_paths();
+        assert!(watched.contains(&first), "{:?}", watched);
+        assert!(watched.contains(&second), "{:?}", watched);
+        assert!(!watched.contains(&root), "{:?}", watched);
+    }
+
+    #[test]
+    fn reading_a_file_adds_it_to_watched_paths() {
+        let dir = tempdir().unwrap();
+        let root = canonical_dir(&dir);
+        let file_path = root.join("a.txt");
+        fs_err::write(&file_path, "a").unwrap();
+
+        let vfs = crate::Vfs::new(StdBackend::new_for_testing());
+        assert!(!vfs.watched_paths().contains(&file_path));
+
+        vfs.read(&file_path).unwrap();
+        assert!(vfs.watched_paths().contains(&file_path));
+
+        vfs.remove_file(&file_path).unwrap();
+        assert!(!vfs.watched_paths().contains(&file_path));
+    }
+
+    #[test]
+    fn remove_dir_all_prunes_watched_descendants() {
+        let dir = tempdir().unwrap();
+        let root = canonical_dir(&dir);
+        let subdir = root.join("sub");
+        let file_path = subdir.join("a.txt");
+        fs_err::create_dir(&subdir).unwrap();
+        fs_err::write(&file_path, "a").unwrap();
+
+        let vfs = crate::Vfs::new(StdBackend::new_for_testing());
+        vfs.read(&file_path).unwrap();
+        assert!(vfs.watched_paths().contains(&file_path));
+
+        vfs.remove_dir_all(&subdir).unwrap();
+        let watched = vfs.watched_paths();
+        assert!(
+            !watched.iter().any(|path| path.starts_with(&subdir)),
+            "{:?}",
+            watched
+        );
+    }
+
     #[test]
     fn unwatch_handles_not_found_gracefully() {
         let dir = tempdir().unwrap();
@@ -588,7 +1119,7 @@ mod tests {
 
         // Watch non-recursively first
         assert!(backend.watch(&subdir, false).is_ok());
-        assert!(backend.watches.contains(subdir.as_path()));
+        assert!(backend.watches.lock().unwrap().contains(subdir.as_path()));
         assert!(!backend.recursive_watches.contains(subdir.as_path()));
 
         // Child should NOT be covered (parent is non-recursive)
@@ -679,6 +1210,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn poll_mode_detects_changes() {
+        let dir = tempdir().unwrap();
+        let root = canonical_dir(&dir);
+        let file_path = root.join("test.txt");
+        let nested_path = root.join("nested/deep.txt");
+        fs_err::write(&file_path, "initial").unwrap();
+        fs_err::create_dir(root.join("nested")).unwrap();
+
+        let mut backend = StdBackend::new_polling_for_testing(Duration::from_millis(20));
+        let event_rx = backend.event_receiver();
+        backend.watch(&root, true).unwrap();
+
+        // Existing files are not reported.
+        let events = collect_events_with_timeout(&event_rx, Duration::from_millis(100));
+        assert_eq!(events, Vec::new());
+
+        fs_err::write(&nested_path, "new").unwrap();
+        let events = collect_events_with_timeout(&event_rx, Duration::from_millis(200));
+        assert!(
+            events.contains(&VfsEvent::Create(nested_path.clone())),
+            "expected a Create for the new file, got {:?}",
+            events
+        );
+
+        fs_err::write(&file_path, "modified content").unwrap();
+        let events = collect_events_with_timeout(&event_rx, Duration::from_millis(200));
+        assert_eq!(events, vec![VfsEvent::Write(file_path.clone())]);
+
+        fs_err::remove_file(&file_path).unwrap();
+        let events = collect_events_with_timeout(&event_rx, Duration::from_millis(200));
+        assert_eq!(events, vec![VfsEvent::Remove(file_path.clone())]);
+
+        backend.unwatch(&root).unwrap();
+        fs_err::write(&file_path, "after unwatch").unwrap();
+        let events = collect_events_with_timeout(&event_rx, Duration::from_millis(100));
+        assert_eq!(events, Vec::new());
+        assert!(backend.watched_paths().is_empty());
+    }
+
     #[test]
     fn watcher_critical_error_display() {
         // Test Display implementation for coverage
@@ -876,6 +1447,7 @@ mod tests {
             VfsEvent::Create(p) | VfsEvent::Write(p) | VfsEvent::Remove(p) => {
                 p == &original || p == &renamed
             }
+            VfsEvent::RescanRequired(_) => false,
         });
 
         assert!(
@@ -13,6 +13,9 @@ Error.Kind = {
 	Timeout = {
 		message = "HTTP request timed out.",
 	},
+	TooManyRequests = {
+		message = "The Atlas server is rate limiting requests: {{message}}",
+	},
 	Unknown = {
 		message = "Unknown HTTP error: {{message}}",
 	},
@@ -63,12 +66,30 @@ function Error.fromRobloxErrorString(message)
 	return Error.new(Error.Kind.Unknown, message)
 end
 
+local function findHeader(headers, name)
+	for key, value in pairs(headers or {}) do
+		if key:lower() == name then
+			return value
+		end
+	end
+
+	return nil
+end
+
 function Error.fromResponse(response)
 	local lower = (response.body or ""):lower()
 	if response.code == 408 or response.code == 504 or lower:find("timed? ?out") then
 		return Error.new(Error.Kind.Timeout)
 	end
 
+	if response.code == 429 then
+		local err = Error.new(Error.Kind.TooManyRequests, tostring(response.body))
+		-- Retry-After is in seconds. The server always sends it with a 429.
+		err.retryAfter = tonumber(findHeader(response.headers, "retry-after"))
+
+		return err
+	end
+
 	return Error.new(Error.Kind.Unknown, string.format("%s: %s", tostring(response.code), tostring(response.body)))
 end
 
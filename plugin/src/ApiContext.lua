@@ -24,6 +24,27 @@ local function rejectFailedRequests(response)
 	return response
 end
 
+-- How many times a write is sent before a rate limit response is given up on.
+local MAX_WRITE_ATTEMPTS = 3
+
+-- Sends a request with `makeRequest`. If the server answers that it's rate
+-- limiting requests, waits as long as it asks and sends the request again.
+local function retryWhenRateLimited(makeRequest, attemptsLeft)
+	return makeRequest():catch(function(err)
+		local rateLimited = type(err) == "table" and err.type == Http.Error.Kind.TooManyRequests
+		if not rateLimited or attemptsLeft <= 1 then
+			return Promise.reject(err)
+		end
+
+		local delaySeconds = err.retryAfter or 1
+		Log.warn("The server is rate limiting writes, retrying in {} seconds", delaySeconds)
+
+		return Promise.delay(delaySeconds):andThen(function()
+			return retryWhenRateLimited(makeRequest, attemptsLeft - 1)
+		end)
+	end)
+end
+
 local function rejectWrongVersion(infoResponseBody)
 	local pluginVersion = Version.display(Config.version)
 	local serverVersion = infoResponseBody.serverVersion
@@ -300,7 +321,11 @@ function ApiContext:write(patch, stageIds)
 
 	body = Http.msgpackEncode(body)
 
-	return Http.post(url, body)
+	local function send()
+		return Http.post(url, body)
+	end
+
+	return retryWhenRateLimited(send, MAX_WRITE_ATTEMPTS)
 		:andThen(rejectFailedRequests)
 		:andThen(Http.Response.msgpack)
 		:andThen(function(responseBody)
@@ -77,7 +77,14 @@ local ApiRefPatchResponse = t.interface({
 })
 
 local ApiError = t.interface({
-	kind = t.union(t.literal("NotFound"), t.literal("BadRequest"), t.literal("InternalError")),
+	kind = t.union(
+		t.literal("NotFound"),
+		t.literal("BadRequest"),
+		t.literal("InternalError"),
+		t.literal("Unauthorized"),
+		t.literal("TooManyRequests"),
+		t.literal("Conflict")
+	),
 	details = t.string,
 })
 
@@ -1,24 +1,32 @@
 use crossbeam_channel::{select, Receiver, RecvError, Sender};
 use jod_thread::JoinHandle;
 use memofs::{IoResultExt, Vfs, VfsEvent};
+use rayon::prelude::*;
 use rbx_dom_weak::types::{Ref, Variant};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use std::{
     fs,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 use crate::{
     message_queue::MessageQueue,
     snapshot::{
-        apply_patch_set, compute_patch_set, AppliedPatchSet, InstigatingSource, PatchSet,
-        PathIgnoreRule, RojoTree,
+        apply_patch_set, compute_patch_set, AppliedPatchSet, InstanceContext, InstanceSnapshot,
+        InstigatingSource, PatchSet, PathIgnoreRule, RojoTree,
+    },
+    snapshot_middleware::{
+        init_file_for_dir, is_init_file_name, is_script_relevant_path, snapshot_from_vfs,
+        snapshot_project_node,
     },
-    snapshot_middleware::{is_script_relevant_path, snapshot_from_vfs, snapshot_project_node},
     syncback::{
+        adjacent_meta_path,
         dedup_suffix::{compute_cleanup_action, parse_dedup_suffix, DedupCleanupAction},
         deduplicate_name, name_needs_slugify, slugify_name, strip_script_suffix,
     },
@@ -30,6 +38,36 @@ use crate::{
 /// 12-14s re-snapshot loop on large projects.
 const ENABLE_TREE_RECONCILIATION: bool = false;
 
+/// How long a removed path must stay gone before its file watch is released.
+/// Long enough to survive editor undo and delete-then-recreate saves, short
+/// enough that deleting a large directory doesn't pin its watches for the
+/// rest of the session.
+const REMOVED_PATH_UNWATCH_GRACE: Duration = Duration::from_secs(30);
+
+/// How long a newly created script's event is held while waiting for its
+/// adjacent meta file. The plugin writes a script and its `.meta.json5` back
+/// to back when it creates an Instance with non-source properties. Other
+/// events are handled without waiting for it.
+const META_COALESCE_WINDOW: Duration = Duration::from_millis(50);
+
+/// How long the change processor waits for the file system to go quiet
+/// before handling a burst of events, unless the serve session sets another
+/// window. Zero handles every event as soon as it arrives.
+pub const DEFAULT_EVENT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// The most debounce windows one burst of events is held for, so files that
+/// never stop changing still get synced.
+const MAX_DEBOUNCE_WINDOWS: u32 = 10;
+
+/// How long a removed file's event is held while waiting for a Create in the
+/// same folder. Renaming a file emits a Remove and a Create, and pairing them
+/// up lets the Instance keep its ID instead of flickering out of the plugin.
+const RENAME_WINDOW: Duration = Duration::from_millis(100);
+
+/// The most threads the change processor uses to handle VFS events for
+/// unrelated parts of the project at the same time.
+const MAX_EVENT_WORKERS: usize = 4;
+
 /// Wrapper that displays a path relative to a project root directory.
 struct RelPath<'a> {
     path: &'a Path,
@@ -76,6 +114,20 @@ pub struct ChangeProcessor {
     /// Allowed to be unused because dropping this value has side effects.
     #[allow(unused)]
     job_thread: JoinHandle<Result<(), RecvError>>,
+
+    /// Whether the message processing thread is still running. Cleared when
+    /// it exits for any reason, including a panic.
+    running: Arc<AtomicBool>,
+}
+
+/// Clears the flag it holds when dropped, so the flag tracks whether the
+/// thread holding it is still alive.
+struct ClearOnDrop(Arc<AtomicBool>);
+
+impl Drop for ClearOnDrop {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
 }
 
 impl ChangeProcessor {
@@ -93,8 +145,9 @@ impl ChangeProcessor {
         project_file_path: PathBuf,
         critical_error_receiver: Option<Receiver<memofs::WatcherCriticalError>>,
         git_repo_root: Option<PathBuf>,
-        sync_scripts_only: bool,
+        root_context: InstanceContext,
         path_ignore_rules: Vec<PathIgnoreRule>,
+        event_debounce: Duration,
     ) -> Self {
         let (shutdown_sender, shutdown_receiver) = crossbeam_channel::bounded(1);
         let vfs_receiver = vfs.event_receiver();
@@ -102,6 +155,7 @@ impl ChangeProcessor {
         // (non-serve commands). never() blocks forever without selecting.
         let critical_error_receiver =
             critical_error_receiver.unwrap_or_else(crossbeam_channel::never);
+        vfs.set_unwatch_grace_period(Some(REMOVED_PATH_UNWATCH_GRACE));
         let task = JobThreadContext {
             tree,
             vfs,
@@ -112,45 +166,107 @@ impl ChangeProcessor {
             project_file_path,
             ref_path_index,
             git_repo_root,
-            sync_scripts_only,
+            root_context,
             path_ignore_rules,
+            event_debounce,
         };
 
+        let running = Arc::new(AtomicBool::new(true));
+        let running_flag = ClearOnDrop(Arc::clone(&running));
+
         let job_thread = jod_thread::Builder::new()
             .name("ChangeProcessor thread".to_owned())
             .spawn(move || {
+                let _running_flag = running_flag;
                 log::trace!("ChangeProcessor thread started");
 
+                let workers = rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_cpus::get().clamp(1, MAX_EVENT_WORKERS))
+                    .thread_name(|index| format!("ChangeProcessor worker {index}"))
+                    .build()
+                    .expect("Could not start ChangeProcessor workers");
+
                 // Tracks when to run the next reconciliation pass. Set to
                 // Some(future_instant) after VFS events arrive, cleared
                 // after reconcile_tree() runs. This ensures we only do the
                 // full re-snapshot once per burst of activity.
                 let mut reconcile_at: Option<Instant> = None;
 
+                // Creates of new scripts held until their adjacent meta file
+                // arrives or the deadline passes. See
+                // `hold_scripts_awaiting_meta`.
+                let mut held_creates: Vec<(PathBuf, Instant)> = Vec::new();
+
                 loop {
                     // Compute the timeout for the default branch.
                     // If a reconciliation is pending, wake up when it's due
                     // (clamped to at least 50ms to avoid busy-spinning).
                     // Otherwise use the normal 500ms sweep interval.
-                    let timeout = match reconcile_at {
+                    let mut timeout = match reconcile_at {
                         Some(deadline) => {
                             let remaining = deadline.saturating_duration_since(Instant::now());
                             remaining.max(Duration::from_millis(50))
                         }
                         None => Duration::from_millis(500),
                     };
+                    if let Some(&(_, deadline)) = held_creates.iter().min_by_key(|(_, at)| at) {
+                        timeout = timeout.min(deadline.saturating_duration_since(Instant::now()));
+                    }
 
                     select! {
                         recv(vfs_receiver) -> event => {
-                            let mut all_patches = task.handle_vfs_event(event?);
+                            let mut events = vec![event?];
+                            events.extend(vfs_receiver.try_iter());
+
+                            // Wait for the burst of events to settle, so rapid
+                            // saves of the same file are handled once.
+                            if !task.event_debounce.is_zero() {
+                                collect_debounced_events(
+                                    &mut events,
+                                    task.event_debounce,
+                                    Instant::now,
+                                    |timeout| vfs_receiver.recv_timeout(timeout).ok(),
+                                );
+                                events = task.coalesce_events(events);
+                            }
+
+                            let rename_deadline = Instant::now() + RENAME_WINDOW;
+                            while task.awaiting_rename(&events) {
+                                match vfs_receiver.recv_deadline(rename_deadline) {
+                                    Ok(event) => {
+                                        events.push(event);
+                                        events.extend(vfs_receiver.try_iter());
+                                    }
+                                    Err(_) => break,
+                                }
+                            }
+
+                            let mut all_patches = task.apply_renames(&mut events);
+                            let events = hold_scripts_awaiting_meta(
+                                &task.vfs,
+                                &mut held_creates,
+                                events,
+                                Instant::now(),
+                            );
+                            all_patches.extend(task.handle_vfs_events(&workers, events));
 
                             // Drain any pending events that arrived during processing.
                             // This ensures that multi-event filesystem operations (e.g.,
                             // rename = REMOVE + CREATE on Windows) produce a single
                             // batched message instead of separate per-event messages,
                             // giving consistent behavior across platforms.
-                            while let Ok(event) = vfs_receiver.try_recv() {
-                                all_patches.extend(task.handle_vfs_event(event));
+                            loop {
+                                let pending: Vec<VfsEvent> = vfs_receiver.try_iter().collect();
+                                if pending.is_empty() {
+                                    break;
+                                }
+                                let pending = hold_scripts_awaiting_meta(
+                                    &task.vfs,
+                                    &mut held_creates,
+                                    pending,
+                                    Instant::now(),
+                                );
+                                all_patches.extend(task.handle_vfs_events(&workers, pending));
                             }
 
                             all_patches.extend(task.process_pending_recoveries());
@@ -200,10 +316,33 @@ impl ChangeProcessor {
                         },
                         recv(shutdown_receiver) -> _ => {
                             log::trace!("ChangeProcessor shutdown signal received...");
+
+                            // Finish writing changes the server already
+                            // accepted so shutting down doesn't lose them.
+                            for patch_set in tree_mutation_receiver.try_iter() {
+                                task.handle_tree_event(patch_set);
+                            }
                             return Ok(());
                         },
                         default(timeout) => {
+                            // Scripts whose meta file never arrived are added
+                            // without it.
+                            let expired = hold_scripts_awaiting_meta(
+                                &task.vfs,
+                                &mut held_creates,
+                                Vec::new(),
+                                Instant::now(),
+                            );
+                            if !expired.is_empty() {
+                                let merged =
+                                    AppliedPatchSet::merge(task.handle_vfs_events(&workers, expired));
+                                if !merged.is_empty() {
+                                    task.message_queue.push_messages(&[merged]);
+                                }
+                            }
+
                             task.process_pending_recoveries();
+                            task.vfs.release_expired_watches();
 
                             if ENABLE_TREE_RECONCILIATION {
                                 // If a reconciliation deadline has passed, run it now.
@@ -221,8 +360,14 @@ impl ChangeProcessor {
         Self {
             shutdown_sender,
             job_thread,
+            running,
         }
     }
+
+    /// Returns whether file changes are still being processed.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
 }
 
 impl Drop for ChangeProcessor {
@@ -274,11 +419,16 @@ struct JobThreadContext {
     /// Used for auto-staging Source writes.
     git_repo_root: Option<PathBuf>,
 
-    /// When true, only script-related VFS events are processed.
-    sync_scripts_only: bool,
+    /// The context the project root is snapshotted with. When its
+    /// `sync_scripts_only` is set, only script-related VFS events are
+    /// processed.
+    root_context: InstanceContext,
 
     /// Rules from `globIgnorePaths` -- VFS events matching these are discarded.
     path_ignore_rules: Vec<PathIgnoreRule>,
+
+    /// How long to wait for more events after one arrives.
+    event_debounce: Duration,
 }
 
 impl JobThreadContext {
@@ -287,29 +437,48 @@ impl JobThreadContext {
         rel_path(path, &self.project_root)
     }
 
-    /// Find the init file inside a directory-format script folder.
-    /// Returns the path to the first `init.*.luau` or `init.*.lua` found.
-    fn find_init_file(dir: &Path) -> Option<PathBuf> {
-        // Check known init file names in priority order
-        let candidates = [
-            "init.luau",
-            "init.server.luau",
-            "init.client.luau",
-            "init.local.luau",
-            "init.plugin.luau",
-            "init.legacy.luau",
-            "init.lua",
-            "init.server.lua",
-            "init.client.lua",
-            "init.local.lua",
-        ];
-        for name in &candidates {
-            let candidate = dir.join(name);
-            if candidate.exists() {
-                return Some(candidate);
-            }
+    /// Renames `from` to `to` through the VFS. When the two paths differ only
+    /// by case, the rename goes through a temporary name first: on
+    /// case-insensitive filesystems a direct case-only rename can be treated
+    /// as a no-op, leaving the old casing on disk.
+    fn rename_path(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        if !is_case_only_rename(from, to) {
+            return self.vfs.rename(from, to);
+        }
+
+        let file_name = from.file_name().and_then(|f| f.to_str()).unwrap_or("");
+        let mut temp_path = from.with_file_name(format!("{file_name}.atlas-rename"));
+        let mut attempt = 1;
+        while self.vfs.metadata(&temp_path).is_ok() {
+            attempt += 1;
+            temp_path = from.with_file_name(format!("{file_name}.atlas-rename{attempt}"));
         }
-        None
+
+        log::debug!(
+            "Case-only rename {} -> {} via {}",
+            self.display_path(from),
+            self.display_path(to),
+            self.display_path(&temp_path)
+        );
+        self.suppress_path_any(&temp_path);
+        let result = self.vfs.rename(from, &temp_path).and_then(|()| {
+            self.vfs.rename(&temp_path, to).inspect_err(|_| {
+                // Put the original back so the tree doesn't point at the temp name.
+                if let Err(err) = self.vfs.rename(&temp_path, from) {
+                    log::error!(
+                        "Failed to restore {} from {}: {}",
+                        self.display_path(from),
+                        self.display_path(&temp_path),
+                        err
+                    );
+                }
+            })
+        });
+        // The temp name is gone again by now, so any late event for it
+        // resolves to nothing. Don't let its suppression outlive this call and
+        // swallow an unrelated event for that path later.
+        self.unsuppress_path_any(&temp_path);
+        result
     }
 
     fn suppression_key(path: &Path) -> PathBuf {
@@ -594,7 +763,7 @@ impl JobThreadContext {
     /// It then computes and applies changes for each affected instance ID and
     /// returns a vector of applied patch sets.
     fn apply_patches(&self, path: PathBuf) -> Vec<AppliedPatchSet> {
-        let mut tree = self.tree.lock().unwrap();
+        let tree = self.tree.lock().unwrap();
         let mut applied_patches = Vec::new();
 
         // Find the nearest ancestor to this path that has
@@ -636,9 +805,11 @@ impl JobThreadContext {
             );
         }
 
+        drop(tree);
+
         for id in affected_ids {
             if let Some(result) =
-                compute_and_apply_changes(&mut tree, &self.vfs, id, &self.project_root)
+                compute_and_apply_changes(&self.tree, &self.vfs, id, &self.project_root)
             {
                 // If an instance was removed, schedule a recovery check
                 // in case the path is recreated momentarily.
@@ -656,11 +827,240 @@ impl JobThreadContext {
         applied_patches
     }
 
+    /// Drops every event that a later event in `events` repeats, since each
+    /// one re-reads the path from disk anyway. Events for paths with a
+    /// pending suppression are all kept, because each of them has to be seen
+    /// to use up its suppression.
+    fn coalesce_events(&self, events: Vec<VfsEvent>) -> Vec<VfsEvent> {
+        let suppressed = self.suppressed_paths.lock().unwrap();
+        let before = events.len();
+        let mut seen = HashSet::new();
+        let mut coalesced: Vec<VfsEvent> = events
+            .into_iter()
+            .rev()
+            .filter(|event| {
+                let key = match event {
+                    VfsEvent::Create(path) => (0, path.clone()),
+                    VfsEvent::Write(path) => (1, path.clone()),
+                    VfsEvent::Remove(path) => (2, path.clone()),
+                    VfsEvent::RescanRequired(path) => (3, path.clone()),
+                    _ => return true,
+                };
+                suppressed.contains_key(&key.1) || seen.insert(key)
+            })
+            .collect();
+        coalesced.reverse();
+
+        if coalesced.len() < before {
+            log::debug!("Coalesced {} VFS events into {}", before, coalesced.len());
+        }
+        coalesced
+    }
+
+    /// Returns the Instance a Remove of `path` would destroy, if it comes from
+    /// a single file that could have been renamed instead of deleted.
+    fn rename_source(&self, tree: &RojoTree, path: &Path) -> Option<Ref> {
+        if self.vfs.metadata(path).is_ok() || is_meta_path(path) || is_init_path(path) {
+            return None;
+        }
+        let &[id] = tree.get_ids_at_path(path) else {
+            return None;
+        };
+        let instance = tree.get_instance(id)?;
+        let from_path = matches!(
+            &instance.metadata().instigating_source,
+            Some(InstigatingSource::Path(source)) if source == path
+        );
+        (from_path && instance.children().is_empty()).then_some(id)
+    }
+
+    /// Returns whether `events` removes a file that could have been renamed,
+    /// without a Create in the same folder following it yet.
+    fn awaiting_rename(&self, events: &[VfsEvent]) -> bool {
+        let tree = self.tree.lock().unwrap();
+        events.iter().enumerate().any(|(index, event)| {
+            let VfsEvent::Remove(path) = event else {
+                return false;
+            };
+            self.rename_source(&tree, path).is_some()
+                && !events[index + 1..].iter().any(|later| {
+                    matches!(later, VfsEvent::Create(new_path) if new_path.parent() == path.parent())
+                })
+        })
+    }
+
+    /// Treats each Remove of a file followed by a Create of another file in
+    /// the same folder as a rename when the new file snapshots to the same
+    /// class and properties as the removed file's Instance. The Instance is
+    /// patched in place, keeping its ID, instead of being destroyed and
+    /// created again. The events for those renames are taken out of
+    /// `events`.
+    ///
+    /// Moves between folders are still handled as a removal and an addition.
+    fn apply_renames(&self, events: &mut Vec<VfsEvent>) -> Vec<AppliedPatchSet> {
+        let mut pairs = Vec::new();
+        {
+            let tree = self.tree.lock().unwrap();
+            let mut taken = HashSet::new();
+            for (index, event) in events.iter().enumerate() {
+                let VfsEvent::Remove(old_path) = event else {
+                    continue;
+                };
+                if !self.renamable(old_path) {
+                    continue;
+                }
+                let Some(id) = self.rename_source(&tree, old_path) else {
+                    continue;
+                };
+                let partner = events
+                    .iter()
+                    .enumerate()
+                    .skip(index + 1)
+                    .find(|(later, event)| {
+                        matches!(event, VfsEvent::Create(new_path)
+                        if !taken.contains(later)
+                            && new_path.parent() == old_path.parent()
+                            && self.vfs.metadata(new_path).is_ok_and(|metadata| metadata.is_file())
+                            && !is_meta_path(new_path)
+                            && !is_init_path(new_path)
+                            && tree.get_ids_at_path(new_path).is_empty()
+                            && self.renamable(new_path))
+                    });
+                if let Some((later, _)) = partner {
+                    taken.insert(later);
+                    pairs.push((index, later, id));
+                }
+            }
+        }
+
+        let mut applied = Vec::new();
+        let mut handled = Vec::new();
+        for (remove_index, create_index, id) in pairs {
+            let (VfsEvent::Remove(old_path), VfsEvent::Create(new_path)) =
+                (&events[remove_index], &events[create_index])
+            else {
+                continue;
+            };
+            if let Some(patch) = self.apply_rename(id, old_path, new_path) {
+                self.vfs
+                    .commit_event(&events[remove_index])
+                    .expect("Error applying VFS change");
+                self.vfs
+                    .commit_event(&events[create_index])
+                    .expect("Error applying VFS change");
+                applied.push(patch);
+                handled.push(remove_index);
+                handled.push(create_index);
+            }
+        }
+
+        handled.sort_unstable();
+        for index in handled.into_iter().rev() {
+            events.remove(index);
+        }
+        applied
+    }
+
+    /// Returns whether events for `path` go through the normal handling
+    /// without being suppressed or ignored, so they can be paired up as a
+    /// rename.
+    fn renamable(&self, path: &Path) -> bool {
+        let suppressed = self
+            .suppressed_paths
+            .lock()
+            .unwrap()
+            .contains_key(&Self::suppression_key(path));
+        !suppressed
+            && self.path_ignore_rules.iter().all(|rule| rule.passes(path))
+            && !(self.root_context.sync_scripts_only && !is_script_relevant_path(path))
+    }
+
+    /// Patches the Instance `id`, snapshotted from `old_path`, to match the
+    /// snapshot of `new_path` if the only differences are its name and
+    /// metadata. Returns `None` when the files hold different contents.
+    fn apply_rename(&self, id: Ref, old_path: &Path, new_path: &Path) -> Option<AppliedPatchSet> {
+        let context = self.tree.lock().unwrap().get_metadata(id)?.context.clone();
+        let snapshot = match snapshot_from_vfs(&context, &self.vfs, new_path) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return None,
+            Err(err) => {
+                log::debug!(
+                    "Could not snapshot {} to check for a rename: {:?}",
+                    self.display_path(new_path),
+                    err
+                );
+                return None;
+            }
+        };
+
+        {
+            let tree = self.tree.lock().unwrap();
+            let instance = tree.get_instance(id)?;
+            if snapshot.class_name != instance.class_name()
+                || &snapshot.properties != instance.properties()
+                || !snapshot.children.is_empty()
+            {
+                return None;
+            }
+        }
+
+        log::info!(
+            "VFS: {} was renamed to {}, keeping Instance {:?}",
+            self.display_path(old_path),
+            self.display_path(new_path),
+            id
+        );
+        apply_snapshot(&self.tree, id, Some(snapshot)).map(|result| result.applied)
+    }
+
+    /// Handles a batch of VFS events. Events under unrelated folders are
+    /// handled in parallel on `workers`, while events that could affect the
+    /// same Instances are handled one after another in the order they
+    /// arrived.
+    fn handle_vfs_events(
+        &self,
+        workers: &rayon::ThreadPool,
+        events: Vec<VfsEvent>,
+    ) -> Vec<AppliedPatchSet> {
+        let groups = {
+            let tree = self.tree.lock().unwrap();
+            group_independent_events(events, |path| self.event_scope(&tree, path))
+        };
+        if groups.len() <= 1 {
+            return groups
+                .into_iter()
+                .flatten()
+                .flat_map(|event| self.handle_vfs_event(event))
+                .collect();
+        }
+
+        log::debug!("Handling VFS events in {} independent groups", groups.len());
+        handle_groups_in_parallel(workers, groups, |event| self.handle_vfs_event(event))
+    }
+
+    /// Returns the folder whose Instances an event for `path` can change.
+    /// This mirrors the search in `apply_patches`: the event re-snapshots the
+    /// nearest path at or above `path` that has Instances, and the folder
+    /// around it covers its adjacent meta and init files.
+    fn event_scope(&self, tree: &RojoTree, path: &Path) -> PathBuf {
+        let mut current = path;
+        while tree.get_ids_at_path(current).is_empty() {
+            match current.parent() {
+                Some(parent) if parent.starts_with(&self.project_root) => current = parent,
+                _ => break,
+            }
+        }
+        current.parent().unwrap_or(current).to_path_buf()
+    }
+
     fn handle_vfs_event(&self, event: VfsEvent) -> Vec<AppliedPatchSet> {
         match &event {
             VfsEvent::Create(path) => log::debug!("VFS event: CREATE {}", self.display_path(path)),
             VfsEvent::Write(path) => log::debug!("VFS event: WRITE {}", self.display_path(path)),
             VfsEvent::Remove(path) => log::debug!("VFS event: REMOVE {}", self.display_path(path)),
+            VfsEvent::RescanRequired(path) => {
+                log::debug!("VFS event: RESCAN {}", self.display_path(path))
+            }
             _ => log::debug!("VFS event: OTHER {:?}", event),
         }
 
@@ -741,7 +1141,7 @@ impl JobThreadContext {
             }
         }
 
-        if self.sync_scripts_only {
+        if self.root_context.sync_scripts_only {
             if let Some(ref path) = event_path {
                 if path.is_file() {
                     let not_in_tree = {
@@ -840,6 +1240,21 @@ impl JobThreadContext {
                     Vec::new()
                 }
             }
+            VfsEvent::RescanRequired(path) => {
+                // The watcher lost events somewhere under this path.
+                // Re-snapshot just that subtree; a rescan covering the whole
+                // project goes through the project file so the root
+                // instance is rebuilt too.
+                log::warn!(
+                    "VFS watcher lost events under {}. Re-snapshotting that subtree.",
+                    self.display_path(&path)
+                );
+                if self.project_root.starts_with(&path) {
+                    self.apply_patches(self.project_file_path.clone())
+                } else {
+                    self.apply_patches(path)
+                }
+            }
             _ => {
                 log::warn!("Unhandled VFS event: {:?}", event);
                 Vec::new()
@@ -923,14 +1338,9 @@ impl JobThreadContext {
     /// the in-memory tree to correct any drift from missed VFS events.
     /// Called when the file watcher reports `RescanRequired`.
     fn reconcile_tree(&self) {
-        use crate::snapshot::InstanceContext;
-
         let start = Instant::now();
-        let mut instance_context = InstanceContext::new();
-        instance_context.sync_scripts_only = self.sync_scripts_only;
-
         let snapshot =
-            match snapshot_from_vfs(&instance_context, &self.vfs, &self.project_file_path) {
+            match snapshot_from_vfs(&self.root_context, &self.vfs, &self.project_file_path) {
                 Ok(s) => s,
                 Err(e) => {
                     log::error!("Tree reconciliation snapshot error: {:?}", e);
@@ -1061,8 +1471,7 @@ impl JobThreadContext {
                     // is a ProjectNode (where instigating_source is not a Path).
                     let file_dir = match source {
                         InstigatingSource::Path(p) => {
-                            let fname = p.file_name().and_then(|f| f.to_str()).unwrap_or("");
-                            if fname.starts_with("init.") {
+                            if is_init_path(p) {
                                 // Directory-format: path is dir/init.luau,
                                 // the containing dir is the grandparent
                                 p.parent().and_then(|d| d.parent()).map(|d| d.to_path_buf())
@@ -1159,7 +1568,7 @@ impl JobThreadContext {
                         );
                         self.suppress_path_any(&from);
                         self.suppress_path(&to);
-                        if let Err(e) = fs::rename(&from, &to) {
+                        if let Err(e) = self.rename_path(&from, &to) {
                             log::warn!(
                                 "Dedup cleanup rename failed: {} -> {}: {}",
                                 from.display(),
@@ -1187,7 +1596,7 @@ impl JobThreadContext {
                                             from_parent.join(format!("{}.meta.json5", to_base));
                                         self.suppress_path_any(&old_meta);
                                         self.suppress_path(&new_meta);
-                                        if fs::rename(&old_meta, &new_meta).is_err() {
+                                        if self.rename_path(&old_meta, &new_meta).is_err() {
                                             self.unsuppress_path_any(&old_meta);
                                             self.unsuppress_path(&new_meta);
                                         }
@@ -1225,12 +1634,7 @@ impl JobThreadContext {
                                         if let Some(InstigatingSource::Path(old_path)) =
                                             &meta.instigating_source
                                         {
-                                            let new_source = if old_path
-                                                .file_name()
-                                                .and_then(|f| f.to_str())
-                                                .map(|f| f.starts_with("init."))
-                                                .unwrap_or(false)
-                                            {
+                                            let new_source = if is_init_path(old_path) {
                                                 // Directory-format: old source
                                                 // is dir/init.luau; the dir was
                                                 // renamed so update the dir
@@ -1299,7 +1703,7 @@ impl JobThreadContext {
                                         // is the init file (e.g., src/MyModule/init.luau).
                                         // The instance name corresponds to the parent directory,
                                         // not the init file, so we must rename the directory.
-                                        let is_init_file = file_name.starts_with("init.");
+                                        let is_init_file = is_init_path(path);
 
                                         if is_init_file {
                                             let dir_path = path.parent().unwrap();
@@ -1335,7 +1739,7 @@ impl JobThreadContext {
                                                     self.suppress_path_any(dir_path);
                                                     self.suppress_path(&new_dir_path);
                                                     if let Err(err) =
-                                                        fs::rename(dir_path, &new_dir_path)
+                                                        self.rename_path(dir_path, &new_dir_path)
                                                     {
                                                         self.unsuppress_path_any(dir_path);
                                                         self.unsuppress_path(&new_dir_path);
@@ -1371,7 +1775,8 @@ impl JobThreadContext {
                                                                 ));
                                                             self.suppress_path_any(&old_meta);
                                                             self.suppress_path(&new_meta);
-                                                            if fs::rename(&old_meta, &new_meta)
+                                                            if self
+                                                                .rename_path(&old_meta, &new_meta)
                                                                 .is_err()
                                                             {
                                                                 self.unsuppress_path_any(&old_meta);
@@ -1507,7 +1912,8 @@ impl JobThreadContext {
                                                 );
                                                 self.suppress_path_any(path);
                                                 self.suppress_path(&new_path);
-                                                if let Err(err) = fs::rename(path, &new_path) {
+                                                if let Err(err) = self.rename_path(path, &new_path)
+                                                {
                                                     self.unsuppress_path_any(path);
                                                     self.unsuppress_path(&new_path);
                                                     log::error!(
@@ -1533,7 +1939,9 @@ impl JobThreadContext {
                                                     if old_meta.exists() {
                                                         self.suppress_path_any(&old_meta);
                                                         self.suppress_path(&new_meta);
-                                                        if fs::rename(&old_meta, &new_meta).is_err()
+                                                        if self
+                                                            .rename_path(&old_meta, &new_meta)
+                                                            .is_err()
                                                         {
                                                             self.unsuppress_path_any(&old_meta);
                                                             self.unsuppress_path(&new_meta);
@@ -1624,8 +2032,10 @@ impl JobThreadContext {
                                         // Resolve the actual file to rename. For directories,
                                         // find the init file inside; for files, use directly.
                                         let init_result = if effective_path.is_dir() {
-                                            Self::find_init_file(effective_path)
-                                                .map(|f| (f.clone(), effective_path.to_path_buf()))
+                                            init_file_for_dir(&self.vfs, effective_path)
+                                                .ok()
+                                                .flatten()
+                                                .map(|(f, _)| (f, effective_path.to_path_buf()))
                                         } else {
                                             Some((
                                                 effective_path.to_path_buf(),
@@ -1643,11 +2053,7 @@ impl JobThreadContext {
                                                 "LocalScript" => ".local",
                                                 _ => "",
                                             };
-                                            let is_init = actual_file
-                                                .file_name()
-                                                .and_then(|f| f.to_str())
-                                                .map(|f| f.starts_with("init."))
-                                                .unwrap_or(false);
+                                            let is_init = is_init_path(&actual_file);
 
                                             let new_file_name = if is_init {
                                                 if new_suffix.is_empty() {
@@ -1691,7 +2097,7 @@ impl JobThreadContext {
                                                 self.suppress_path_any(&actual_file);
                                                 self.suppress_path(&new_path);
                                                 if let Err(err) =
-                                                    fs::rename(&actual_file, &new_path)
+                                                    self.rename_path(&actual_file, &new_path)
                                                 {
                                                     self.unsuppress_path_any(&actual_file);
                                                     self.unsuppress_path(&new_path);
@@ -1904,16 +2310,25 @@ struct ComputeResult {
 }
 
 fn compute_and_apply_changes(
-    tree: &mut RojoTree,
+    tree: &Mutex<RojoTree>,
     vfs: &Vfs,
     id: Ref,
     project_root: &Path,
 ) -> Option<ComputeResult> {
     // Use rel_path(p, project_root) inline for log display.
 
-    let metadata = tree
-        .get_metadata(id)
-        .expect("metadata missing for instance present in tree");
+    // The tree is only locked to read the Instance's metadata and to apply the
+    // result, so workers handling unrelated paths can snapshot concurrently.
+    let metadata = match tree.lock().unwrap().get_metadata(id) {
+        Some(metadata) => metadata.clone(),
+        None => {
+            log::debug!(
+                "compute_and_apply_changes: instance {:?} was removed by an earlier event",
+                id
+            );
+            return None;
+        }
+    };
 
     let instigating_source = match &metadata.instigating_source {
         Some(path) => path,
@@ -1936,11 +2351,7 @@ fn compute_and_apply_changes(
             // correct file. However, snapshot_from_vfs returns None for init
             // files because they are handled as part of the parent directory
             // snapshot. Detect this case and snapshot the parent directory.
-            let is_init_file = path
-                .file_name()
-                .and_then(|f| f.to_str())
-                .map(|f| f.starts_with("init."))
-                .unwrap_or(false);
+            let is_init_file = is_init_path(path);
             let snapshot_path = if is_init_file {
                 path.parent().unwrap_or(path.as_path())
             } else {
@@ -1979,12 +2390,7 @@ fn compute_and_apply_changes(
                         }
                     };
 
-                    let patch_set = compute_patch_set(snapshot, tree, id);
-                    let applied = apply_patch_set(tree, patch_set);
-                    Some(ComputeResult {
-                        applied,
-                        removed_path: None,
-                    })
+                    apply_snapshot(tree, id, snapshot)
                 }
                 Ok(None) => {
                     // Path not found via VFS. Before removing, verify it's truly
@@ -2013,12 +2419,7 @@ fn compute_and_apply_changes(
                                 }
                             };
 
-                        let patch_set = compute_patch_set(snapshot, tree, id);
-                        let applied = apply_patch_set(tree, patch_set);
-                        Some(ComputeResult {
-                            applied,
-                            removed_path: None,
-                        })
+                        apply_snapshot(tree, id, snapshot)
                     } else if is_init_file && std::fs::metadata(snapshot_path).is_ok() {
                         // Init file was deleted but the parent directory still
                         // exists. Re-snapshot the directory — it will become a
@@ -2044,12 +2445,7 @@ fn compute_and_apply_changes(
                                 }
                             };
 
-                        let patch_set = compute_patch_set(snapshot, tree, id);
-                        let applied = apply_patch_set(tree, patch_set);
-                        Some(ComputeResult {
-                            applied,
-                            removed_path: None,
-                        })
+                        apply_snapshot(tree, id, snapshot)
                     } else {
                         // Path is genuinely gone from both VFS and real filesystem.
                         // Remove the instance, but record the path for recovery
@@ -2065,7 +2461,9 @@ fn compute_and_apply_changes(
                         let mut patch_set = PatchSet::new();
                         patch_set.removed_instances.push(id);
 
-                        let applied = apply_patch_set(tree, patch_set);
+                        let mut tree = tree.lock().unwrap();
+                        tree.get_instance(id)?;
+                        let applied = apply_patch_set(&mut tree, patch_set);
                         Some(ComputeResult {
                             applied,
                             removed_path: Some(removed_path),
@@ -2115,24 +2513,192 @@ fn compute_and_apply_changes(
                 }
             };
 
-            let patch_set = compute_patch_set(snapshot, tree, id);
-            let applied = apply_patch_set(tree, patch_set);
-            Some(ComputeResult {
-                applied,
-                removed_path: None,
-            })
+            apply_snapshot(tree, id, snapshot)
+        }
+    }
+}
+
+/// Patches the Instance `id` to match `snapshot`. Does nothing if another
+/// event removed the Instance while the snapshot was being taken.
+fn apply_snapshot(
+    tree: &Mutex<RojoTree>,
+    id: Ref,
+    snapshot: Option<InstanceSnapshot>,
+) -> Option<ComputeResult> {
+    let mut tree = tree.lock().unwrap();
+    tree.get_instance(id)?;
+
+    let patch_set = compute_patch_set(snapshot, &tree, id);
+    let applied = apply_patch_set(&mut tree, patch_set);
+    Some(ComputeResult {
+        applied,
+        removed_path: None,
+    })
+}
+
+/// Returns whether `path` names a meta file in any supported format.
+fn is_meta_path(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(n) => n.to_lowercase(),
+        None => return false,
+    };
+    name.ends_with(".meta.json5") || name.ends_with(".meta.json") || name.ends_with(".meta.toml")
+}
+
+/// Returns whether `path` names the init file of a directory-format
+/// Instance, following [`INIT_FILE_PRIORITY`](crate::INIT_FILE_PRIORITY).
+fn is_init_path(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|f| f.to_str())
+        .is_some_and(is_init_file_name)
+}
+
+/// Returns the paths the adjacent meta file of the script at `path` can
+/// have, in every format including legacy `.meta.json`.
+fn adjacent_meta_candidates(path: &Path) -> [PathBuf; 3] {
+    let meta_path = adjacent_meta_path(path);
+    [
+        meta_path.with_extension("json"),
+        meta_path.with_extension("toml"),
+        meta_path,
+    ]
+}
+
+/// Returns whether `path` is a newly created script whose adjacent meta file
+/// neither exists nor appears in `touched`, meaning the meta file may be
+/// about to arrive.
+fn awaiting_adjacent_meta(vfs: &Vfs, path: &Path, touched: &HashMap<PathBuf, usize>) -> bool {
+    let is_script = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("luau") || ext.eq_ignore_ascii_case("lua"));
+    if !is_script || is_init_path(path) {
+        return false;
+    }
+    if !vfs.metadata(path).is_ok_and(|metadata| metadata.is_file()) {
+        return false;
+    }
+    adjacent_meta_candidates(path)
+        .iter()
+        .all(|meta_path| !touched.contains_key(meta_path) && vfs.metadata(meta_path).is_err())
+}
+
+/// Moves Creates of new scripts whose meta file hasn't arrived yet out of
+/// `events` into `held`, so the script and its meta file land in the same
+/// patch instead of the Instance first appearing without the properties from
+/// its meta file. Returns the events to handle now.
+///
+/// A held Create is released in front of `events` once its meta file shows
+/// up, once another event touches the script, or once its deadline has
+/// passed at `now`. A Create that `events` follows up with other events for
+/// the same script isn't held. Every other event is returned right away.
+fn hold_scripts_awaiting_meta(
+    vfs: &Vfs,
+    held: &mut Vec<(PathBuf, Instant)>,
+    events: Vec<VfsEvent>,
+    now: Instant,
+) -> Vec<VfsEvent> {
+    let mut touched: HashMap<PathBuf, usize> = HashMap::new();
+    for event in &events {
+        if let VfsEvent::Create(path)
+        | VfsEvent::Write(path)
+        | VfsEvent::Remove(path)
+        | VfsEvent::RescanRequired(path) = event
+        {
+            *touched.entry(path.clone()).or_default() += 1;
         }
     }
+
+    let mut ready = Vec::with_capacity(events.len() + held.len());
+    held.retain(|(path, deadline)| {
+        let release = now >= *deadline
+            || touched.contains_key(path)
+            || adjacent_meta_candidates(path)
+                .iter()
+                .any(|meta_path| touched.contains_key(meta_path));
+        if release {
+            ready.push(VfsEvent::Create(path.clone()));
+        }
+        !release
+    });
+
+    for event in events {
+        match &event {
+            VfsEvent::Create(path)
+                if touched[path] == 1 && awaiting_adjacent_meta(vfs, path, &touched) =>
+            {
+                held.push((path.clone(), now + META_COALESCE_WINDOW));
+            }
+            _ => ready.push(event),
+        }
+    }
+    ready
+}
+
+/// Splits `events` into groups that touch unrelated parts of the file
+/// system, keeping the order events arrived in within each group.
+///
+/// `scope_of` returns the folder an event for a path can affect. Events whose
+/// folders are the same or nested inside one another share a group. Events
+/// without a path go into one group with everything else.
+fn group_independent_events(
+    events: Vec<VfsEvent>,
+    scope_of: impl Fn(&Path) -> PathBuf,
+) -> Vec<Vec<VfsEvent>> {
+    let scopes: Option<Vec<PathBuf>> = events
+        .iter()
+        .map(|event| match event {
+            VfsEvent::Create(path)
+            | VfsEvent::Write(path)
+            | VfsEvent::Remove(path)
+            | VfsEvent::RescanRequired(path) => Some(scope_of(path)),
+            _ => None,
+        })
+        .collect();
+    let Some(scopes) = scopes else {
+        return vec![events];
+    };
+
+    // Paths sort by component, so every folder inside another sorts directly
+    // after it, and each group is a run of nested folders.
+    let mut roots: BTreeMap<&Path, usize> = scopes.iter().map(|s| (s.as_path(), 0)).collect();
+    let mut group_count = 0;
+    let mut current_root: Option<&Path> = None;
+    for (scope, group) in roots.iter_mut() {
+        if !current_root.is_some_and(|root| scope.starts_with(root)) {
+            current_root = Some(*scope);
+            group_count += 1;
+        }
+        *group = group_count - 1;
+    }
+
+    let mut groups: Vec<Vec<VfsEvent>> = (0..group_count).map(|_| Vec::new()).collect();
+    for (event, scope) in events.into_iter().zip(&scopes) {
+        groups[roots[scope.as_path()]].push(event);
+    }
+    groups
+}
+
+/// Whether renaming `from` to `to` only changes the casing of the final path
+/// component, e.g. `foo.luau` -> `Foo.luau`.
+fn is_case_only_rename(from: &Path, to: &Path) -> bool {
+    if from == to || from.parent() != to.parent() {
+        return false;
+    }
+    match (
+        from.file_name().and_then(|f| f.to_str()),
+        to.file_name().and_then(|f| f.to_str()),
+    ) {
+        (Some(from_name), Some(to_name)) => from_name.to_lowercase() == to_name.to_lowercase(),
+        _ => false,
+    }
 }
 
 /// Rebuild the `relevant_paths` list for an instance given its new
 /// `instigating_source` path. This mirrors the logic in the snapshot
 /// middleware so that `path_to_ids` stays correct after a rename.
 fn rebuild_relevant_paths(new_path: &Path) -> Vec<PathBuf> {
-    let file_name = new_path.file_name().and_then(|f| f.to_str()).unwrap_or("");
-    let is_init = file_name.starts_with("init.");
-
-    if is_init {
+    if is_init_path(new_path) {
         // Directory-format script: relevant paths are the directory itself
         // plus every possible init file variant inside it.
         let dir_path = new_path.parent().unwrap().to_path_buf();
@@ -2167,3 +2733,323 @@ fn rebuild_relevant_paths(new_path: &Path) -> Vec<PathBuf> {
         ]
     }
 }
+
+/// Runs each group on `workers`, handling the groups in parallel and the
+/// items within a group one after another in order.
+fn handle_groups_in_parallel<T, R>(
+    workers: &rayon::ThreadPool,
+    groups: Vec<Vec<T>>,
+    handle: impl Fn(T) -> Vec<R> + Sync,
+) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+{
+    workers.install(|| {
+        groups
+            .into_par_iter()
+            .flat_map_iter(|group| group.into_iter().flat_map(&handle))
+            .collect()
+    })
+}
+
+/// Adds events to `events` until none arrive for `window`, or until
+/// [`MAX_DEBOUNCE_WINDOWS`] windows have passed since the burst started.
+/// `recv_timeout` waits at most the given time for the next event, and `now`
+/// is the clock both limits are measured against.
+fn collect_debounced_events(
+    events: &mut Vec<VfsEvent>,
+    window: Duration,
+    now: impl Fn() -> Instant,
+    mut recv_timeout: impl FnMut(Duration) -> Option<VfsEvent>,
+) {
+    let limit = now() + window * MAX_DEBOUNCE_WINDOWS;
+    while now() < limit {
+        match recv_timeout(window) {
+            Some(event) => events.push(event),
+            None => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn group_paths(events: Vec<VfsEvent>) -> Vec<Vec<PathBuf>> {
+        group_independent_events(events, |path| path.parent().unwrap().to_path_buf())
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|event| match event {
+                        VfsEvent::Create(path) | VfsEvent::Write(path) | VfsEvent::Remove(path) => {
+                            path
+                        }
+                        _ => unreachable!(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unrelated_folders_are_grouped_separately() {
+        let groups = group_paths(vec![
+            VfsEvent::Write(PathBuf::from("/project/src/a/Module.luau")),
+            VfsEvent::Write(PathBuf::from("/project/src/b/Module.luau")),
+            VfsEvent::Write(PathBuf::from("/project/src/a-b/Module.luau")),
+        ]);
+
+        assert_eq!(
+            groups,
+            vec![
+                vec![PathBuf::from("/project/src/a/Module.luau")],
+                vec![PathBuf::from("/project/src/a-b/Module.luau")],
+                vec![PathBuf::from("/project/src/b/Module.luau")],
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_folders_share_a_group_in_order() {
+        let groups = group_paths(vec![
+            VfsEvent::Remove(PathBuf::from("/project/src/a/Old.luau")),
+            VfsEvent::Create(PathBuf::from("/project/src/b/Module.luau")),
+            VfsEvent::Write(PathBuf::from("/project/src/a/inner/Module.luau")),
+            VfsEvent::Create(PathBuf::from("/project/src/a/New.luau")),
+        ]);
+
+        assert_eq!(
+            groups,
+            vec![
+                vec![
+                    PathBuf::from("/project/src/a/Old.luau"),
+                    PathBuf::from("/project/src/a/inner/Module.luau"),
+                    PathBuf::from("/project/src/a/New.luau"),
+                ],
+                vec![PathBuf::from("/project/src/b/Module.luau")],
+            ]
+        );
+    }
+
+    #[test]
+    fn independent_groups_are_handled_concurrently() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let workers = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let started = AtomicUsize::new(0);
+        let running = AtomicUsize::new(0);
+        let most_running = AtomicUsize::new(0);
+
+        // Each item waits for the other to start before finishing, so both
+        // are only running at once if the groups are handled on separate
+        // workers. The deadline keeps a serial run from hanging the test.
+        let handled = handle_groups_in_parallel(&workers, vec![vec![1], vec![2]], |item| {
+            started.fetch_add(1, Ordering::SeqCst);
+            let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most_running.fetch_max(now_running, Ordering::SeqCst);
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while started.load(Ordering::SeqCst) < 2 && Instant::now() < deadline {
+                std::thread::yield_now();
+            }
+            running.fetch_sub(1, Ordering::SeqCst);
+            vec![item]
+        });
+
+        assert_eq!(most_running.load(Ordering::SeqCst), 2);
+        assert_eq!(handled, vec![1, 2]);
+    }
+
+    #[test]
+    fn items_in_a_group_are_handled_in_order() {
+        let workers = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+
+        let handled =
+            handle_groups_in_parallel(&workers, vec![vec![1, 2, 3], vec![4, 5]], |item| vec![item]);
+
+        assert_eq!(handled, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn init_paths_follow_init_file_priority() {
+        for name in [
+            "init.luau",
+            "init.server.luau",
+            "init.client.lua",
+            "init.csv",
+            "init.model.json5",
+        ] {
+            assert!(
+                is_init_path(&Path::new("/project/src/Dir").join(name)),
+                "{name}"
+            );
+        }
+        for name in [
+            "init.meta.json5",
+            "init.txt",
+            "init.project.json5",
+            "initial.luau",
+        ] {
+            assert!(
+                !is_init_path(&Path::new("/project/src/Dir").join(name)),
+                "{name}"
+            );
+        }
+    }
+
+    /// Feeds `collect_debounced_events` events that arrive at the given
+    /// offsets on a fake clock, and returns how many of them it collected.
+    fn debounced_count(window: Duration, arrivals: &[Duration]) -> usize {
+        let start = Instant::now();
+        let clock = std::cell::Cell::new(start);
+        let mut pending = arrivals.iter().copied().peekable();
+
+        let mut events = Vec::new();
+        collect_debounced_events(
+            &mut events,
+            window,
+            || clock.get(),
+            |timeout| {
+                let deadline = clock.get() + timeout;
+                match pending.peek() {
+                    Some(&arrival) if start + arrival <= deadline => {
+                        pending.next();
+                        clock.set(clock.get().max(start + arrival));
+                        Some(VfsEvent::Write(PathBuf::from("/project/src/counter.txt")))
+                    }
+                    _ => {
+                        clock.set(deadline);
+                        None
+                    }
+                }
+            },
+        );
+        events.len()
+    }
+
+    #[test]
+    fn rapid_writes_are_collected_into_one_burst() {
+        let window = Duration::from_millis(50);
+        let mut arrivals: Vec<_> = (1..=20).map(|i| Duration::from_millis(i * 5)).collect();
+        // Arrives after the burst has been quiet for a whole window.
+        arrivals.push(Duration::from_millis(200));
+
+        assert_eq!(debounced_count(window, &arrivals), 20);
+    }
+
+    #[test]
+    fn endless_writes_stop_after_max_windows() {
+        let window = Duration::from_millis(50);
+        let arrivals: Vec<_> = (1..=1000).map(|i| Duration::from_millis(i * 10)).collect();
+
+        // Collecting stops at 500ms, 10 windows in, with a write every 10ms.
+        assert_eq!(debounced_count(window, &arrivals), 50);
+    }
+
+    fn scripts_vfs(files: &[&str]) -> Vfs {
+        let mut imfs = memofs::InMemoryFs::new();
+        imfs.load_snapshot(
+            "/project/src",
+            memofs::VfsSnapshot::dir(
+                files
+                    .iter()
+                    .map(|name| (*name, memofs::VfsSnapshot::file("return {}"))),
+            ),
+        )
+        .unwrap();
+        Vfs::new(imfs)
+    }
+
+    fn create(path: &str) -> VfsEvent {
+        VfsEvent::Create(PathBuf::from(path))
+    }
+
+    #[test]
+    fn new_script_is_held_until_its_meta_file_arrives() {
+        let vfs = scripts_vfs(&["Tagged.luau", "Tagged.meta.json5"]);
+        let start = Instant::now();
+        let mut held = Vec::new();
+
+        let vfs_without_meta = scripts_vfs(&["Tagged.luau"]);
+        let ready = hold_scripts_awaiting_meta(
+            &vfs_without_meta,
+            &mut held,
+            vec![create("/project/src/Tagged.luau")],
+            start,
+        );
+        assert!(ready.is_empty());
+        assert_eq!(held.len(), 1);
+
+        let ready = hold_scripts_awaiting_meta(
+            &vfs,
+            &mut held,
+            vec![create("/project/src/Tagged.meta.json5")],
+            start + Duration::from_millis(10),
+        );
+        assert_eq!(
+            ready,
+            vec![
+                create("/project/src/Tagged.luau"),
+                create("/project/src/Tagged.meta.json5"),
+            ]
+        );
+        assert!(held.is_empty());
+    }
+
+    #[test]
+    fn held_script_is_released_after_the_window() {
+        let vfs = scripts_vfs(&["Plain.luau"]);
+        let start = Instant::now();
+        let mut held = Vec::new();
+
+        hold_scripts_awaiting_meta(
+            &vfs,
+            &mut held,
+            vec![create("/project/src/Plain.luau")],
+            start,
+        );
+        let ready = hold_scripts_awaiting_meta(
+            &vfs,
+            &mut held,
+            Vec::new(),
+            start + META_COALESCE_WINDOW - Duration::from_millis(1),
+        );
+        assert!(ready.is_empty());
+
+        let ready =
+            hold_scripts_awaiting_meta(&vfs, &mut held, Vec::new(), start + META_COALESCE_WINDOW);
+        assert_eq!(ready, vec![create("/project/src/Plain.luau")]);
+        assert!(held.is_empty());
+    }
+
+    #[test]
+    fn other_events_are_not_held() {
+        let vfs = scripts_vfs(&[
+            "Tagged.luau",
+            "Tagged.meta.json5",
+            "Notes.txt",
+            "Written.luau",
+        ]);
+        let mut held = Vec::new();
+
+        let events = vec![
+            // Its meta file already exists.
+            create("/project/src/Tagged.luau"),
+            // Not a script.
+            create("/project/src/Notes.txt"),
+            // Followed up by another event in the same batch.
+            create("/project/src/Written.luau"),
+            VfsEvent::Write(PathBuf::from("/project/src/Written.luau")),
+        ];
+        let ready = hold_scripts_awaiting_meta(&vfs, &mut held, events.clone(), Instant::now());
+        assert_eq!(ready, events);
+        assert!(held.is_empty());
+    }
+}
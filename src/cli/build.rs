@@ -1,17 +1,23 @@
 use std::{
-    io::{BufWriter, Write},
+    io::{self, Write},
     mem::forget,
     path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
 use anyhow::{bail, Context};
 use clap::{CommandFactory, Parser};
-use fs_err::File;
 use memofs::Vfs;
+use rbx_dom_weak::WeakDom;
 use roblox_install::RobloxStudio;
+use thiserror::Error;
 use tokio::runtime::Runtime;
 
-use crate::serve_session::ServeSession;
+use crate::{
+    disk_space::check_available_space,
+    serve_session::{ServeSession, ServeSessionOptions},
+};
 
 use super::resolve_path;
 
@@ -19,6 +25,19 @@ const UNKNOWN_OUTPUT_KIND_ERR: &str = "Could not detect what kind of file to bui
                                        Expected output file to end in .rbxl, .rbxlx, .rbxm, or .rbxmx.";
 const UNKNOWN_PLUGIN_KIND_ERR: &str = "Could not detect what kind of file to build. \
                                        Expected plugin file to end in .rbxm or .rbxmx.";
+const STDOUT_FORMAT_ERR: &str = "Writing to stdout needs an explicit format. \
+                                 Pass --format rbxm, rbxmx, rbxl, or rbxlx.";
+
+/// The `--output` value that writes the built file to stdout.
+const STDOUT_OUTPUT: &str = "-";
+
+/// How long `--watch` waits for changes to stop before rebuilding, so that a
+/// burst of saves produces a single build.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The longest `--watch` holds off a rebuild while changes keep arriving, so
+/// a steady stream of saves still produces builds.
+const WATCH_MAX_WAIT: Duration = Duration::from_secs(1);
 
 /// Generates a model or place file from the Rojo project.
 #[derive(Debug, Parser)]
@@ -27,12 +46,17 @@ pub struct BuildCommand {
     #[clap(default_value = "")]
     pub project: PathBuf,
 
-    /// Where to output the result.
+    /// Where to output the result. Pass `-` to write it to stdout.
     ///
     /// Should end in .rbxm, .rbxl, .rbxmx, or .rbxlx.
     #[clap(long, short, conflicts_with = "plugin")]
     pub output: Option<PathBuf>,
 
+    /// The kind of file to build: rbxm, rbxmx, rbxl, or rbxlx. Required when
+    /// writing to stdout. Otherwise it's inferred from the output's extension.
+    #[clap(long)]
+    pub format: Option<OutputKind>,
+
     /// Alternative to the output flag that outputs the result in the local plugins folder.
     ///
     /// Should end in .rbxm or .rbxl.
@@ -42,6 +66,21 @@ pub struct BuildCommand {
     /// Whether to automatically rebuild when any input files change.
     #[clap(long)]
     pub watch: bool,
+
+    /// Don't check that the output's volume has room for the built file.
+    #[clap(long)]
+    pub skip_disk_check: bool,
+
+    /// Write .rbxlx and .rbxmx output without indentation. XML is indented by
+    /// default so that it diffs well in version control.
+    #[clap(long)]
+    pub compact_xml: bool,
+
+    /// Drop properties that aren't in the reflection database with a warning
+    /// instead of failing the build. Useful when the project sets properties
+    /// from a different version of Roblox than this build of Atlas knows.
+    #[clap(long)]
+    pub ignore_unknown_properties: bool,
 }
 
 impl BuildCommand {
@@ -56,8 +95,13 @@ impl BuildCommand {
                     .exit();
             }
             (Some(output), None) => {
-                let output_kind =
-                    OutputKind::from_output_path(&output).context(UNKNOWN_OUTPUT_KIND_ERR)?;
+                let output_kind = match self.format {
+                    Some(format) => format,
+                    None if is_stdout(&output) => bail!(STDOUT_FORMAT_ERR),
+                    None => {
+                        OutputKind::from_output_path(&output).context(UNKNOWN_OUTPUT_KIND_ERR)?
+                    }
+                };
 
                 (output, output_kind)
             }
@@ -75,16 +119,36 @@ impl BuildCommand {
             _ => unreachable!(),
         };
 
+        if self.watch && is_stdout(&output_path) {
+            bail!("--watch can't be used when writing to stdout");
+        }
+
         let project_path = resolve_path(&self.project);
 
+        let options = WriteOptions {
+            skip_disk_check: self.skip_disk_check,
+            compact_xml: self.compact_xml,
+        };
+        if options.compact_xml && !matches!(output_kind, OutputKind::Rbxmx | OutputKind::Rbxlx) {
+            log::warn!("--compact-xml only affects .rbxlx and .rbxmx output");
+        }
+
         log::trace!("Constructing in-memory filesystem");
         let vfs = Vfs::new_default();
         vfs.set_watch_enabled(self.watch);
 
-        let session = ServeSession::new(vfs, project_path, None)?;
+        let session = ServeSession::new_with_options(
+            vfs,
+            project_path,
+            None,
+            ServeSessionOptions {
+                ignore_unknown_properties: self.ignore_unknown_properties,
+                ..Default::default()
+            },
+        )?;
         let mut cursor = session.message_queue().cursor();
 
-        write_model(&session, &output_path, output_kind)?;
+        write_model(&session, &output_path, output_kind, options)?;
 
         if self.watch {
             let rt = Runtime::new().unwrap();
@@ -94,7 +158,27 @@ impl BuildCommand {
                 let (new_cursor, _patch_set) = rt.block_on(receiver).unwrap();
                 cursor = new_cursor;
 
-                write_model(&session, &output_path, output_kind)?;
+                // Keep collecting changes until none arrive for a moment, or
+                // until they've held off the rebuild for too long.
+                let deadline = Instant::now() + WATCH_MAX_WAIT;
+                loop {
+                    let wait =
+                        WATCH_DEBOUNCE.min(deadline.saturating_duration_since(Instant::now()));
+                    if wait.is_zero() {
+                        break;
+                    }
+                    match rt.block_on(tokio::time::timeout(
+                        wait,
+                        session.message_queue().subscribe(cursor),
+                    )) {
+                        Ok(Ok((new_cursor, _patch_set))) => cursor = new_cursor,
+                        _ => break,
+                    }
+                }
+
+                let start = Instant::now();
+                write_model(&session, &output_path, output_kind, options)?;
+                log::info!("Rebuilt in {:.2?}", start.elapsed());
             }
         }
 
@@ -106,9 +190,14 @@ impl BuildCommand {
     }
 }
 
+/// Returns whether `output` is the `--output` value that means stdout.
+fn is_stdout(output: &Path) -> bool {
+    output == Path::new(STDOUT_OUTPUT)
+}
+
 /// The different kinds of output that Rojo can build to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum OutputKind {
+pub enum OutputKind {
     /// An XML model file.
     Rbxmx,
 
@@ -146,6 +235,35 @@ impl OutputKind {
     }
 }
 
+impl FromStr for OutputKind {
+    type Err = OutputKindParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "rbxlx" => Ok(OutputKind::Rbxlx),
+            "rbxmx" => Ok(OutputKind::Rbxmx),
+            "rbxl" => Ok(OutputKind::Rbxl),
+            "rbxm" => Ok(OutputKind::Rbxm),
+            _ => Err(OutputKindParseError {
+                attempted: source.to_owned(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid format '{attempted}'. Valid formats are: rbxm, rbxmx, rbxl, rbxlx")]
+pub struct OutputKindParseError {
+    attempted: String,
+}
+
+/// Settings for how [`write_model`] writes the built file.
+#[derive(Debug, Clone, Copy)]
+struct WriteOptions {
+    skip_disk_check: bool,
+    compact_xml: bool,
+}
+
 fn xml_encode_config() -> rbx_xml::EncodeOptions<'static> {
     rbx_xml::EncodeOptions::new().property_behavior(rbx_xml::EncodePropertyBehavior::WriteUnknown)
 }
@@ -155,49 +273,131 @@ fn write_model(
     session: &ServeSession,
     output: &Path,
     output_kind: OutputKind,
+    options: WriteOptions,
 ) -> anyhow::Result<()> {
-    println!("Building project '{}'", session.project_name());
+    // When the file goes to stdout, nothing else may be printed there.
+    let to_stdout = is_stdout(output);
+    if to_stdout {
+        log::info!("Building project '{}'", session.project_name());
+    } else {
+        println!("Building project '{}'", session.project_name());
+    }
 
     let tree = session.tree();
-    let root_id = tree.get_root_id();
+    let compact = options.compact_xml;
+
+    if to_stdout {
+        log::trace!("Writing output to stdout");
+        let mut stdout = io::BufWriter::new(io::stdout().lock());
+        encode_model(tree.inner(), output_kind, compact, &mut stdout)?;
+        stdout.flush()?;
+        return Ok(());
+    }
 
-    log::trace!("Opening output file for write");
-    let mut file = BufWriter::new(File::create(output)?);
+    log::trace!("Writing output file");
+    if options.skip_disk_check {
+        let mut file = io::BufWriter::new(fs_err::File::create(output)?);
+        encode_model(tree.inner(), output_kind, compact, &mut file)?;
+        file.flush()?;
+    } else {
+        // The model is encoded in memory first so its exact size can be
+        // checked against the output volume before anything on disk is
+        // touched.
+        let mut buffer = Vec::new();
+        encode_model(tree.inner(), output_kind, compact, &mut buffer)?;
+        check_available_space(output, buffer.len() as u64)?;
+        fs_err::write(output, &buffer)?;
+    }
 
-    match output_kind {
-        OutputKind::Rbxm => {
-            rbx_binary::to_writer(&mut file, tree.inner(), &[root_id])?;
-        }
-        OutputKind::Rbxl => {
-            let root_instance = tree.get_instance(root_id).unwrap();
-            let top_level_ids = root_instance.children();
+    let filename = output
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("<invalid utf-8>");
+    println!("Built project to {}", filename);
 
-            rbx_binary::to_writer(&mut file, tree.inner(), top_level_ids)?;
-        }
-        OutputKind::Rbxmx => {
-            // Model files include the root instance of the tree and all its
-            // descendants.
+    Ok(())
+}
 
-            rbx_xml::to_writer(&mut file, tree.inner(), &[root_id], xml_encode_config())?;
-        }
-        OutputKind::Rbxlx => {
-            // Place files don't contain an entry for the DataModel, but our
-            // WeakDom representation does.
+/// Encodes the tree as `output_kind` straight into `writer`.
+fn encode_model<W: Write>(
+    dom: &WeakDom,
+    output_kind: OutputKind,
+    compact: bool,
+    writer: W,
+) -> anyhow::Result<()> {
+    let root_id = dom.root_ref();
 
-            let root_instance = tree.get_instance(root_id).unwrap();
-            let top_level_ids = root_instance.children();
+    // Place files don't contain an entry for the DataModel, but our WeakDom
+    // representation does. Model files include the root instance of the
+    // tree and all its descendants.
+    let ids = match output_kind {
+        OutputKind::Rbxm | OutputKind::Rbxmx => std::slice::from_ref(&root_id),
+        OutputKind::Rbxl | OutputKind::Rbxlx => dom.root().children(),
+    };
 
-            rbx_xml::to_writer(&mut file, tree.inner(), top_level_ids, xml_encode_config())?;
+    match output_kind {
+        OutputKind::Rbxm | OutputKind::Rbxl => rbx_binary::to_writer(writer, dom, ids)?,
+        OutputKind::Rbxmx | OutputKind::Rbxlx if compact => {
+            let mut pretty = Vec::new();
+            rbx_xml::to_writer(&mut pretty, dom, ids, xml_encode_config())?;
+            compact_xml(&pretty, writer)?;
+        }
+        OutputKind::Rbxmx | OutputKind::Rbxlx => {
+            rbx_xml::to_writer(writer, dom, ids, xml_encode_config())?
         }
     }
 
-    file.flush()?;
+    Ok(())
+}
 
-    let filename = output
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("<invalid utf-8>");
-    println!("Built project to {}", filename);
+/// Writes `pretty` to `output` without the indentation rbx_xml puts between
+/// elements.
+///
+/// rbx_xml always indents its output, so the document is read back with
+/// xml-rs and written out again with indentation turned off. This is the one
+/// output that is buffered in full before it's written. Whitespace that
+/// makes up the entire contents of an element (like a `string` property whose
+/// value is a single space) is kept, so the result decodes to the same DOM.
+fn compact_xml<W: Write>(pretty: &[u8], output: W) -> anyhow::Result<()> {
+    use xml::{
+        reader::{ParserConfig, XmlEvent},
+        writer::{self, EmitterConfig},
+    };
+
+    let reader = ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(false)
+        .cdata_to_characters(false)
+        .create_reader(pretty);
+    let mut writer = EmitterConfig::new()
+        .perform_indent(false)
+        .write_document_declaration(false)
+        .normalize_empty_elements(false)
+        .create_writer(output);
+
+    let mut after_start = false;
+    let mut element_text = None;
+    for event in reader {
+        let event = event?;
+        match &event {
+            XmlEvent::StartDocument { .. } | XmlEvent::EndDocument => continue,
+            XmlEvent::Whitespace(text) => {
+                element_text = after_start.then(|| text.clone());
+                after_start = false;
+                continue;
+            }
+            XmlEvent::EndElement { .. } => {
+                if let Some(text) = element_text.take() {
+                    writer.write(writer::XmlEvent::characters(&text))?;
+                }
+            }
+            _ => element_text = None,
+        }
+        after_start = matches!(event, XmlEvent::StartElement { .. });
+        if let Some(event) = event.as_writer_event() {
+            writer.write(event)?;
+        }
+    }
 
     Ok(())
 }
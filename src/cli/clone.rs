@@ -5,12 +5,13 @@ use std::time::Instant;
 use anyhow::{bail, Context};
 use clap::Parser;
 use fs_err as fs;
+use memofs::Vfs;
 
-use crate::roblox_api;
+use crate::{project::Project, roblox_api};
 
 use super::init::{setup_git_and_rules, write_if_not_exists, write_template_files, InitKind};
 use super::syncback::SyncbackCommand;
-use super::GlobalOptions;
+use super::{resolve_path, GlobalOptions};
 
 /// Initializes a new Rojo project from one or more Roblox places and syncs them back.
 ///
@@ -46,6 +47,17 @@ pub struct CloneCommand {
     /// Skip changing the working directory into the project after creation.
     #[clap(long)]
     pub skip_cd: bool,
+
+    /// Only sync back the Instance at this path, like
+    /// `ServerStorage/Modules`, and its descendants. The path is added to the
+    /// project's `syncbackRules.includeTrees`, so later syncbacks stay scoped
+    /// to it. Only supported when cloning a single place.
+    #[clap(long, value_name = "PATH")]
+    pub subtree: Option<String>,
+
+    /// Read the place from this file instead of downloading it.
+    #[clap(long, hide = true)]
+    pub input: Option<PathBuf>,
 }
 
 impl CloneCommand {
@@ -53,6 +65,9 @@ impl CloneCommand {
         if self.placeids.len() == 1 {
             return self.run_single(global);
         }
+        if self.subtree.is_some() {
+            bail!("--subtree can only be used when cloning a single place");
+        }
         self.run_multi(global)
     }
 
@@ -77,16 +92,38 @@ impl CloneCommand {
 
         init.run()?;
 
+        if let Some(subtree) = &self.subtree {
+            scope_project_to_subtree(&path.join("default.project.json5"), subtree)?;
+        }
+
+        let (input, download) = match &self.input {
+            Some(input) => (resolve_path(input).into_owned(), None),
+            None => (PathBuf::from("Project.rbxl"), Some(place_id)),
+        };
+
         let syncback = SyncbackCommand {
             project: PathBuf::from("default.project.json5"),
-            input: PathBuf::from("Project.rbxl"),
-            download: Some(place_id),
+            input,
+            download,
             list: false,
             dry_run: false,
+            diff_format: None,
+            json: false,
             interactive: false,
             incremental: false,
-            sourcemap: false,
+            only_new: false,
+            sourcemap: None,
+            backup: false,
+            backup_dir: None,
+            fail_on_conflict: false,
+            warnings_as_errors: false,
+            skip_disk_check: false,
+            skip_path_check: false,
+            summary_only: false,
+            progress: false,
+            threads_fs: None,
             working_dir: path.clone(),
+            subtree: self.subtree.clone(),
         };
 
         syncback.run(global)?;
@@ -237,16 +274,30 @@ impl CloneCommand {
                 download: None,
                 list: false,
                 dry_run: false,
+                diff_format: None,
+                json: false,
                 interactive: false,
                 incremental: false,
-                sourcemap: false,
+                only_new: false,
+                sourcemap: None,
+                backup: false,
+                backup_dir: None,
+                fail_on_conflict: false,
+                warnings_as_errors: false,
+                skip_disk_check: false,
+                skip_path_check: false,
+                summary_only: false,
+                progress: false,
+                threads_fs: None,
                 working_dir: path.clone(),
+                subtree: None,
             };
 
             syncback.run(GlobalOptions {
                 verbosity: global.verbosity,
                 color: global.color,
                 opencloud: global.opencloud.clone(),
+                reflection_db: global.reflection_db.clone(),
             })?;
         }
 
@@ -265,6 +316,24 @@ impl CloneCommand {
     }
 }
 
+/// Adds `subtree` to the includeTrees of the project at `project_path`, so
+/// syncback only ever touches that part of the place.
+fn scope_project_to_subtree(project_path: &Path, subtree: &str) -> anyhow::Result<()> {
+    let vfs = Vfs::new_oneshot();
+    let mut project =
+        Project::load_from_slice(&vfs.read(project_path)?, project_path.to_path_buf(), None)?;
+    project
+        .syncback_rules
+        .get_or_insert_with(Default::default)
+        .add_include_tree(subtree);
+
+    let serialized = crate::json::to_vec_pretty_sorted(&project)
+        .context("could not re-encode project file as JSON5")?;
+    fs::write(project_path, serialized)
+        .with_context(|| format!("Failed to write {}", project_path.display()))?;
+    Ok(())
+}
+
 struct PlaceEntry {
     place_id: u64,
     dir_name: String,
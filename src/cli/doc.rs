@@ -1,12 +1,29 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+
+use crate::project_schema::project_schema;
 
 /// Open Rojo's documentation in your browser.
 #[derive(Debug, Parser)]
-pub struct DocCommand {}
+pub struct DocCommand {
+    #[clap(subcommand)]
+    pub subcommand: Option<DocSubcommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DocSubcommand {
+    /// Print the JSON Schema for project files, for editors to validate and
+    /// complete `.project.json5` files through `$schema`.
+    Schema,
+}
 
 impl DocCommand {
     pub fn run(self) -> anyhow::Result<()> {
-        opener::open("https://rojo.space/docs")?;
+        match self.subcommand {
+            Some(DocSubcommand::Schema) => {
+                println!("{}", serde_json::to_string_pretty(&project_schema())?);
+            }
+            None => opener::open("https://rojo.space/docs")?,
+        }
         Ok(())
     }
 }
@@ -1,10 +1,10 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 use clap::Parser;
 use memofs::Vfs;
 
-use crate::project::Project;
+use crate::{project::Project, project_migration::migrate_project_value};
 
 use super::resolve_path;
 
@@ -14,6 +14,11 @@ pub struct FmtProjectCommand {
     /// Path to the project to format. Defaults to the current directory.
     #[clap(default_value = "")]
     pub project: PathBuf,
+
+    /// Upgrade a project written for an older version of the project schema
+    /// before formatting it, printing each change that was made.
+    #[clap(long)]
+    pub migrate: bool,
 }
 
 impl FmtProjectCommand {
@@ -22,8 +27,15 @@ impl FmtProjectCommand {
         let vfs = Vfs::new_oneshot();
 
         let base_path = resolve_path(&self.project);
-        let project = Project::load_fuzzy(&vfs, &base_path)?
-            .context("A project file is required to run 'atlas fmt-project'")?;
+        let project = if self.migrate {
+            migrate_project(&vfs, &base_path)?
+        } else {
+            // Load the file as written, without merging in what it `extends`,
+            // so formatting doesn't copy a base project into it.
+            let project_path = Project::locate(&base_path)
+                .context("A project file is required to run 'atlas fmt-project'")?;
+            Project::load_from_slice(&vfs.read(&project_path)?, project_path, None)?
+        };
 
         let serialized = String::from_utf8(
             crate::json::to_vec_pretty_sorted(&project)
@@ -37,3 +49,30 @@ impl FmtProjectCommand {
         Ok(())
     }
 }
+
+/// Loads the project at `base_path` without validating it, upgrades it to the
+/// current schema and then validates the result.
+fn migrate_project(vfs: &Vfs, base_path: &Path) -> anyhow::Result<Project> {
+    let project_path = Project::locate(base_path)
+        .context("A project file is required to run 'atlas fmt-project --migrate'")?;
+    let contents = vfs.read(&project_path)?;
+    let mut value = crate::json::parse_value_from_slice_with_context(&contents, || {
+        format!("Could not parse {}", project_path.display())
+    })?;
+
+    let changes = migrate_project_value(&mut value);
+    let migrated = crate::json::to_vec_pretty_sorted(&value)?;
+    let project = Project::load_from_slice(&migrated, project_path.clone(), None)
+        .context("The migrated project is still not valid")?;
+
+    if changes.is_empty() {
+        println!("{} is already up to date.", project_path.display());
+    } else {
+        println!("Migrated {}:", project_path.display());
+        for change in &changes {
+            println!("  - {change}");
+        }
+    }
+
+    Ok(project)
+}
@@ -8,11 +8,13 @@ mod doc;
 mod fmt_project;
 mod init;
 mod plugin;
+mod project;
 pub(crate) mod serve;
 mod sourcemap;
 mod studio;
 mod syncback;
 mod upload;
+mod validate;
 
 use std::{
     borrow::Cow,
@@ -21,6 +23,7 @@ use std::{
     str::FromStr,
 };
 
+use anyhow::{bail, Context};
 use clap::Parser;
 use thiserror::Error;
 
@@ -32,11 +35,13 @@ pub use self::doc::DocCommand;
 pub use self::fmt_project::FmtProjectCommand;
 pub use self::init::{InitCommand, InitKind};
 pub use self::plugin::{PluginCommand, PluginSubcommand};
+pub use self::project::{ProjectCommand, ProjectSubcommand};
 pub use self::serve::ServeCommand;
 pub use self::sourcemap::SourcemapCommand;
 pub use self::studio::StudioCommand;
 pub use self::syncback::SyncbackCommand;
 pub use self::upload::UploadCommand;
+pub use self::validate::ValidateCommand;
 
 /// Command line options that Rojo accepts, defined using the clap crate.
 #[derive(Debug, Parser)]
@@ -52,6 +57,10 @@ pub struct Options {
 
 impl Options {
     pub fn run(self) -> anyhow::Result<()> {
+        if let Some(path) = &self.global.reflection_db {
+            use_reflection_database(path)?;
+        }
+
         match self.subcommand {
             Subcommand::Clone(subcommand) => subcommand.run(self.global),
             Subcommand::Completions(subcommand) => subcommand.run(),
@@ -65,6 +74,8 @@ impl Options {
             Subcommand::Doc(subcommand) => subcommand.run(),
             Subcommand::Plugin(subcommand) => subcommand.run(),
             Subcommand::Studio(subcommand) => subcommand.run(self.global),
+            Subcommand::Project(subcommand) => subcommand.run(),
+            Subcommand::Validate(subcommand) => subcommand.run(),
             Subcommand::Syncback(subcommand) | Subcommand::Pull(subcommand) => {
                 subcommand.run(self.global)
             }
@@ -86,6 +97,50 @@ pub struct GlobalOptions {
     /// Requires the 'legacy-asset:manage' scope for downloading places.
     #[clap(long, env = "ATLAS_OPENCLOUD_KEY", global(true), hide = true)]
     pub opencloud: Option<String>,
+
+    /// Use the Roblox reflection database in this file instead of the one
+    /// built into Atlas, e.g. to target a newer Studio release.
+    #[clap(long, value_name = "PATH", global(true))]
+    pub reflection_db: Option<PathBuf>,
+}
+
+/// The environment variable `rbx_reflection_database` reads the path of a
+/// replacement database from.
+const REFLECTION_DATABASE_VAR: &str = "RBX_DATABASE";
+
+/// Makes the reflection database in `path` the one every later
+/// `rbx_reflection_database::get()` returns, which covers property
+/// resolution, filtering and default checks as well as serialization.
+///
+/// Must run before anything reads the database, because it's only loaded
+/// once per process.
+fn use_reflection_database(path: &Path) -> anyhow::Result<()> {
+    let contents = fs_err::read(path)?;
+    let database: rbx_reflection::ReflectionDatabase = rmp_serde::from_slice(&contents)
+        .with_context(|| format!("{} is not a valid reflection database", path.display()))?;
+    if database.classes.is_empty() {
+        bail!("The reflection database {} has no classes", path.display());
+    }
+
+    env::set_var(REFLECTION_DATABASE_VAR, path);
+
+    let loaded = rbx_reflection_database::get()
+        .with_context(|| format!("Could not load the reflection database {}", path.display()))?;
+    if loaded.version != database.version || loaded.classes.len() != database.classes.len() {
+        bail!(
+            "The reflection database {} could not replace the built-in one",
+            path.display()
+        );
+    }
+
+    let [major, minor, patch, build] = database.version;
+    log::info!(
+        "Using reflection database {} (version {major}.{minor}.{patch}.{build}, {} classes)",
+        path.display(),
+        database.classes.len()
+    );
+
+    Ok(())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -140,6 +195,10 @@ pub enum Subcommand {
     Doc(DocCommand),
     Plugin(PluginCommand),
     Studio(StudioCommand),
+    Project(ProjectCommand),
+    /// Alias for `project validate`.
+    #[clap(hide = true)]
+    Validate(ValidateCommand),
     Syncback(SyncbackCommand),
     /// Alias for `syncback`.
     #[clap(hide = true)]
@@ -156,11 +215,26 @@ impl Subcommand {
             Subcommand::Sourcemap(cmd) => Some(&cmd.project),
             Subcommand::FmtProject(cmd) => Some(&cmd.project),
             Subcommand::Studio(cmd) => Some(&cmd.project),
+            Subcommand::Project(ProjectCommand {
+                subcommand: ProjectSubcommand::Validate(cmd),
+            })
+            | Subcommand::Validate(cmd) => Some(&cmd.project),
             Subcommand::Syncback(cmd) | Subcommand::Pull(cmd) => Some(&cmd.project),
             _ => None,
         }
     }
 
+    /// Log targets this invocation asks to keep off the console, regardless
+    /// of verbosity.
+    pub fn silenced_log_targets(&self) -> &'static [&'static str] {
+        match self {
+            Subcommand::Syncback(cmd) | Subcommand::Pull(cmd) if cmd.summary_only => {
+                &[crate::syncback::PHASE_LOG_TARGET]
+            }
+            _ => &[],
+        }
+    }
+
     pub fn command_name(&self) -> &'static str {
         match self {
             Subcommand::Clone(_) => "clone",
@@ -175,6 +249,8 @@ impl Subcommand {
             Subcommand::Doc(_) => "doc",
             Subcommand::Plugin(_) => "plugin",
             Subcommand::Studio(_) => "studio",
+            Subcommand::Project(_) => "project",
+            Subcommand::Validate(_) => "validate",
             Subcommand::Syncback(_) => "syncback",
             Subcommand::Pull(_) => "pull",
         }
@@ -1,5 +1,9 @@
-use std::fs;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
+use anyhow::{bail, Context};
 use clap::Parser;
 use roblox_install::RobloxStudio;
 
@@ -24,7 +28,17 @@ pub enum PluginSubcommand {
     /// Install the plugin in Roblox Studio's plugins folder. If the plugin is
     /// already installed, installing it again will overwrite the current plugin
     /// file.
-    Install,
+    Install {
+        /// Install the plugin from a local `.rbxm` file instead of the one
+        /// bundled with Rojo.
+        #[clap(long, conflicts_with = "from_url")]
+        from_file: Option<PathBuf>,
+
+        /// Download the plugin `.rbxm` from a URL, like a release asset,
+        /// instead of using the one bundled with Rojo.
+        #[clap(long)]
+        from_url: Option<String>,
+    },
 
     /// Removes the plugin if it is installed.
     Uninstall,
@@ -39,23 +53,26 @@ impl PluginCommand {
 impl PluginSubcommand {
     pub fn run(self) -> anyhow::Result<()> {
         match self {
-            PluginSubcommand::Install => install_plugin(),
+            PluginSubcommand::Install {
+                from_file: Some(path),
+                ..
+            } => {
+                let contents = fs::read(&path)
+                    .with_context(|| format!("Could not read plugin {}", path.display()))?;
+                install_plugin_file(&contents)
+            }
+            PluginSubcommand::Install {
+                from_url: Some(url),
+                ..
+            } => install_plugin_file(&download_plugin(&url)?),
+            PluginSubcommand::Install { .. } => install_plugin(),
             PluginSubcommand::Uninstall => uninstall_plugin(),
         }
     }
 }
 
 fn install_plugin() -> anyhow::Result<()> {
-    let studio = RobloxStudio::locate()?;
-
-    let plugins_folder_path = studio.plugins_path();
-
-    if !plugins_folder_path.exists() {
-        log::debug!("Creating Roblox Studio plugins folder");
-        fs::create_dir(plugins_folder_path)?;
-    }
-
-    let plugin_path = plugins_folder_path.join(PLUGIN_FILE_NAME);
+    let plugin_path = plugins_folder()?.join(PLUGIN_FILE_NAME);
     log::debug!("Writing plugin to {}", plugin_path.display());
 
     #[cfg(prebuilt_plugin)]
@@ -90,6 +107,79 @@ fn install_plugin() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Returns Studio's plugins folder, creating it if it doesn't exist yet.
+fn plugins_folder() -> anyhow::Result<PathBuf> {
+    let studio = RobloxStudio::locate()?;
+    let plugins_folder_path = studio.plugins_path();
+
+    if !plugins_folder_path.exists() {
+        log::debug!("Creating Roblox Studio plugins folder");
+        fs::create_dir(plugins_folder_path)?;
+    }
+
+    Ok(plugins_folder_path.to_path_buf())
+}
+
+/// Installs a plugin model that didn't come from Rojo's binary, replacing the
+/// bundled plugin if it's installed.
+fn install_plugin_file(contents: &[u8]) -> anyhow::Result<()> {
+    let plugin_path = write_plugin_file(&plugins_folder()?, contents)?;
+    log::info!("Installed plugin to {}", plugin_path.display());
+
+    Ok(())
+}
+
+/// Checks that `contents` is a plugin model and writes it to the plugins
+/// folder, returning the path it was written to.
+fn write_plugin_file(plugins_folder_path: &Path, contents: &[u8]) -> anyhow::Result<PathBuf> {
+    validate_plugin_model(contents)?;
+
+    let plugin_path = plugins_folder_path.join(PLUGIN_FILE_NAME);
+    log::debug!("Writing plugin to {}", plugin_path.display());
+    fs::write(&plugin_path, contents)?;
+
+    Ok(plugin_path)
+}
+
+/// Checks that `contents` is a binary model containing at least one `Script`,
+/// which Studio needs to run anything from a plugin.
+fn validate_plugin_model(contents: &[u8]) -> anyhow::Result<()> {
+    let dom = rbx_binary::from_reader(contents)
+        .context("The plugin is not a valid binary model (.rbxm) file")?;
+
+    let root = dom.root();
+    if root.children().is_empty() {
+        bail!("The plugin model is empty");
+    }
+
+    let mut to_visit = root.children().to_vec();
+    while let Some(referent) = to_visit.pop() {
+        let Some(instance) = dom.get_by_ref(referent) else {
+            continue;
+        };
+        if instance.class == "Script" {
+            return Ok(());
+        }
+        to_visit.extend_from_slice(instance.children());
+    }
+
+    bail!("The plugin model does not contain any Scripts, so it cannot be a plugin")
+}
+
+fn download_plugin(url: &str) -> anyhow::Result<Vec<u8>> {
+    log::debug!("Downloading plugin from {url}");
+
+    let response = reqwest::blocking::get(url)
+        .with_context(|| format!("Could not download plugin from {url}"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        bail!("Could not download plugin from {url}: HTTP {status}");
+    }
+
+    Ok(response.bytes()?.to_vec())
+}
+
 fn uninstall_plugin() -> anyhow::Result<()> {
     let studio = RobloxStudio::locate()?;
 
@@ -124,3 +214,77 @@ fn plugin_initialize() {
     let vfs = Vfs::new(in_memory_fs);
     let _ = ServeSession::new_oneshot(vfs, "/plugin").unwrap();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    use rbx_dom_weak::{InstanceBuilder, WeakDom};
+
+    fn model_with(class_name: &str) -> Vec<u8> {
+        let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let folder = dom.insert(dom.root_ref(), InstanceBuilder::new("Folder"));
+        dom.insert(folder, InstanceBuilder::new(class_name).with_name("Main"));
+
+        let mut contents = Vec::new();
+        rbx_binary::to_writer(&mut contents, &dom, &[folder]).unwrap();
+        contents
+    }
+
+    #[test]
+    fn install_from_file_writes_to_plugins_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugins_folder = dir.path().join("Plugins");
+        fs::create_dir(&plugins_folder).unwrap();
+
+        let source = dir.path().join("MyPlugin.rbxm");
+        fs::write(&source, model_with("Script")).unwrap();
+
+        let plugin_path = write_plugin_file(&plugins_folder, &fs::read(&source).unwrap()).unwrap();
+
+        assert_eq!(plugin_path, plugins_folder.join(PLUGIN_FILE_NAME));
+        assert_eq!(fs::read(plugin_path).unwrap(), fs::read(source).unwrap());
+    }
+
+    #[test]
+    fn install_rejects_non_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(write_plugin_file(dir.path(), b"not a model").is_err());
+        assert!(write_plugin_file(dir.path(), &model_with("ModuleScript")).is_err());
+        assert!(!dir.path().join(PLUGIN_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn install_from_url_writes_to_plugins_folder() {
+        let plugin = model_with("Script");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/AtlasPlugin.rbxm", listener.local_addr().unwrap());
+
+        let body = plugin.clone();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let contents = download_plugin(&url).unwrap();
+        let plugin_path = write_plugin_file(dir.path(), &contents).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(fs::read(plugin_path).unwrap(), plugin);
+    }
+}
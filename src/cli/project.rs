@@ -0,0 +1,24 @@
+use clap::Parser;
+
+use super::ValidateCommand;
+
+/// Work with project files.
+#[derive(Debug, Parser)]
+pub struct ProjectCommand {
+    #[clap(subcommand)]
+    pub subcommand: ProjectSubcommand,
+}
+
+/// Commands that act on a project file.
+#[derive(Debug, Parser)]
+pub enum ProjectSubcommand {
+    Validate(ValidateCommand),
+}
+
+impl ProjectCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self.subcommand {
+            ProjectSubcommand::Validate(subcommand) => subcommand.run(),
+        }
+    }
+}
@@ -2,21 +2,26 @@ use std::{
     collections::HashMap,
     io::Cursor,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    ops::RangeInclusive,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context;
 use clap::Parser;
 use memofs::Vfs;
 use rbx_dom_weak::{types::Ref, types::Variant, InstanceBuilder, WeakDom};
+use thiserror::Error;
 
 use crate::{
-    serve_session::ServeSession,
-    syncback::syncback_loop,
+    change_processor::DEFAULT_EVENT_DEBOUNCE,
+    serve_session::{InitialSyncMode, ServeSession, ServeSessionOptions},
+    syncback::{syncback_loop, SyncState},
     web::{
         interface::{ServerExitReason, SyncbackPayload},
-        LiveServer,
+        ConnectionLimits, LiveServer, DEFAULT_GZIP_THRESHOLD,
     },
 };
 
@@ -24,6 +29,7 @@ use super::resolve_path;
 
 const DEFAULT_BIND_ADDRESS: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 1);
 const DEFAULT_PORT: u16 = 34873;
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Expose a Rojo project to the Rojo Studio plugin.
 #[derive(Debug, Parser)]
@@ -40,56 +46,252 @@ pub struct ServeCommand {
     /// it has none.
     #[clap(long)]
     pub port: Option<u16>,
+
+    /// Ports to fall back to when the preferred port is in use, written as
+    /// `START-END`. The first free port after the preferred one is used. If
+    /// no port is set, the range start is preferred.
+    #[clap(long, value_name = "START-END")]
+    pub port_range: Option<PortRange>,
+
+    /// Wait for a single syncback from the Studio plugin, apply it, and exit.
+    /// The exit status reflects whether the syncback succeeded.
+    #[clap(long)]
+    pub once: bool,
+
+    /// The most connections the server will hold open at once. Connections
+    /// past the limit are rejected with `503 Service Unavailable`.
+    #[clap(long, value_name = "COUNT")]
+    pub max_connections: Option<usize>,
+
+    /// Seconds an idle kept-alive connection stays open before the server
+    /// closes it. `0` turns keep-alive off.
+    #[clap(long, value_name = "SECONDS")]
+    pub keep_alive_timeout: Option<u64>,
+
+    /// Seconds a client has to send the headers of a request before the
+    /// connection is closed.
+    #[clap(long, value_name = "SECONDS")]
+    pub header_read_timeout: Option<u64>,
+
+    /// The most `/api/write` requests each connection may make per second.
+    /// Writes past the limit are rejected with `429 Too Many Requests`,
+    /// which keeps a misbehaving plugin from swamping the server.
+    #[clap(long, value_name = "COUNT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_writes_per_sec: Option<u32>,
+
+    /// Require every API and MCP request to carry an
+    /// `Authorization: Bearer <TOKEN>` header with this token. Prefer setting
+    /// `ATLAS_AUTH_TOKEN`, which keeps the token out of the process list. The
+    /// Studio plugin doesn't send a token yet, so it can't connect while this
+    /// is set.
+    #[clap(
+        long,
+        value_name = "TOKEN",
+        env = "ATLAS_AUTH_TOKEN",
+        hide_env_values = true
+    )]
+    pub auth_token: Option<String>,
+
+    /// What the Studio plugin should do with differences between Studio and
+    /// the project when it connects: `push` makes Studio match the project,
+    /// `merge` reconciles the two without overwriting Studio. Defaults to
+    /// `push`. This is only reported to clients as `initialSyncMode`; the
+    /// bundled Studio plugin doesn't act on it yet.
+    #[clap(long, value_name = "MODE")]
+    pub initial_syncback: Option<InitialSyncMode>,
+
+    /// Gzip API responses of at least this many bytes for clients that send
+    /// `Accept-Encoding: gzip`. Defaults to 8192.
+    #[clap(long, value_name = "BYTES")]
+    pub gzip_threshold: Option<usize>,
+
+    /// Milliseconds to wait for file changes to settle before syncing them.
+    /// Repeated changes to the same file within the window are synced once.
+    /// Defaults to `50`. `0` syncs every change right away.
+    #[clap(long, value_name = "MILLISECONDS")]
+    pub debounce: Option<u64>,
+
+    /// How to find out about file changes: `native` uses the operating
+    /// system's notifications, `poll` scans the project on an interval.
+    /// Polling is slower but catches changes on network filesystems like NFS
+    /// and SMB. Defaults to `native`.
+    #[clap(long, value_name = "MODE")]
+    pub watch_mode: Option<WatchMode>,
+
+    /// Milliseconds between scans with `--watch-mode poll`. Defaults to 500.
+    #[clap(long, value_name = "MILLISECONDS")]
+    pub poll_interval: Option<u64>,
+}
+
+/// How `atlas serve` finds out about file changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WatchMode {
+    #[default]
+    Native,
+    Poll,
+}
+
+impl FromStr for WatchMode {
+    type Err = WatchModeParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "native" => Ok(WatchMode::Native),
+            "poll" => Ok(WatchMode::Poll),
+            _ => Err(WatchModeParseError {
+                attempted: source.to_owned(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid watch mode '{attempted}'. Valid values are: native, poll")]
+pub struct WatchModeParseError {
+    attempted: String,
+}
+
+/// An inclusive range of ports, written as `START-END`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    pub fn ports(self) -> RangeInclusive<u16> {
+        self.start..=self.end
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = PortRangeParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let invalid = || PortRangeParseError {
+            attempted: source.to_owned(),
+        };
+        let (start, end) = source.split_once('-').ok_or_else(invalid)?;
+        let start: u16 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u16 = end.trim().parse().map_err(|_| invalid())?;
+        if start > end {
+            return Err(invalid());
+        }
+        Ok(PortRange { start, end })
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid port range '{attempted}'. Expected START-END, like 34872-34890")]
+pub struct PortRangeParseError {
+    attempted: String,
 }
 
 impl ServeCommand {
+    fn new_vfs(
+        &self,
+    ) -> (
+        Vfs,
+        crossbeam_channel::Receiver<memofs::WatcherCriticalError>,
+    ) {
+        match self.watch_mode.unwrap_or_default() {
+            WatchMode::Native => Vfs::new_default_with_errors(),
+            WatchMode::Poll => {
+                let interval = self
+                    .poll_interval
+                    .map(Duration::from_millis)
+                    .unwrap_or(DEFAULT_POLL_INTERVAL);
+                log::info!("Polling for file changes every {:?}", interval);
+                Vfs::new_polling_with_errors(interval)
+            }
+        }
+    }
+
     pub fn run(self) -> anyhow::Result<()> {
         let project_path = resolve_path(&self.project);
 
-        let (first_vfs, first_errors) = Vfs::new_default_with_errors();
-        let first_session = Arc::new(ServeSession::new(
-            first_vfs,
-            project_path.clone(),
-            Some(first_errors),
-        )?);
+        let initial_sync_mode = self.initial_syncback.unwrap_or_default();
+        let session_options = ServeSessionOptions {
+            event_debounce: self
+                .debounce
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_EVENT_DEBOUNCE),
+            ..Default::default()
+        };
+
+        if self.poll_interval.is_some() && self.watch_mode != Some(WatchMode::Poll) {
+            log::warn!("--poll-interval only applies with --watch-mode poll");
+        }
+        let (first_vfs, first_errors) = self.new_vfs();
+        let first_session = Arc::new(
+            ServeSession::new_with_options(
+                first_vfs,
+                project_path.clone(),
+                Some(first_errors),
+                session_options.clone(),
+            )?
+            .with_initial_sync_mode(initial_sync_mode),
+        );
 
         let project = first_session.root_project();
         let ip = self
             .address
             .or(project.serve_address)
             .unwrap_or(DEFAULT_BIND_ADDRESS.into());
-        let port = self.port.or(project.serve_port).unwrap_or(DEFAULT_PORT);
+        let port = self
+            .port
+            .or(project.serve_port)
+            .or(self.port_range.map(|range| range.start))
+            .unwrap_or(DEFAULT_PORT);
 
         let addr: SocketAddr = (ip, port).into();
-        let host = if ip.is_loopback() {
-            "localhost".to_owned()
-        } else {
-            ip.to_string()
+
+        let limits = ConnectionLimits {
+            max_connections: self.max_connections,
+            keep_alive_timeout: self.keep_alive_timeout.map(Duration::from_secs),
+            header_read_timeout: self.header_read_timeout.map(Duration::from_secs),
+            max_writes_per_sec: self.max_writes_per_sec,
         };
 
         let mut session = first_session;
         loop {
-            let server = LiveServer::new(session);
-
-            log::info!("Listening: http://{}:{}", host, port);
+            let server = LiveServer::new(session)
+                .with_connection_limits(limits)
+                .with_auth_token(self.auth_token.clone())
+                .with_gzip_threshold(self.gzip_threshold.unwrap_or(DEFAULT_GZIP_THRESHOLD))
+                .with_port_range(self.port_range.map(PortRange::ports));
 
             match server.start(addr) {
                 ServerExitReason::SyncbackRequested(payload) => {
                     log::info!("Live syncback requested, running...");
+                    if self.once {
+                        run_live_syncback(&project_path, payload)
+                            .context("Live syncback failed")?;
+                        log::info!("Syncback complete, exiting.");
+                        return Ok(());
+                    }
                     match run_live_syncback(&project_path, payload) {
                         Ok(_stats) => log::info!("Syncback complete, restarting serve..."),
                         Err(err) => {
                             log::error!("Live syncback failed: {err:#}. Restarting serve...")
                         }
                     }
-                    let (vfs, critical_errors) = Vfs::new_default_with_errors();
-                    session = Arc::new(ServeSession::new(
-                        vfs,
-                        project_path.clone(),
-                        Some(critical_errors),
-                    )?);
+                    let (vfs, critical_errors) = self.new_vfs();
+                    session = Arc::new(
+                        ServeSession::new_with_options(
+                            vfs,
+                            project_path.clone(),
+                            Some(critical_errors),
+                            session_options.clone(),
+                        )?
+                        .with_initial_sync_mode(initial_sync_mode),
+                    );
                     continue;
                 }
+                ServerExitReason::Shutdown => {
+                    log::info!("Server stopped, exiting.");
+                    return Ok(());
+                }
             }
         }
     }
@@ -136,6 +338,8 @@ pub(crate) fn run_live_syncback(
         .fs_snapshot
         .write_to_vfs_parallel(base_path, session_old.vfs(), git_cache.as_ref())?;
 
+    SyncState::record_written(session_old.vfs(), base_path, &result.fs_snapshot);
+
     let added = result.fs_snapshot.added_paths().len();
     let removed = result.fs_snapshot.removed_paths().len();
 
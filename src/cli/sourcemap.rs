@@ -4,13 +4,13 @@ use std::{
     mem::forget,
     path::{self, Path, PathBuf},
     process,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Context;
 use clap::Parser;
 use fs_err::File;
-use memofs::Vfs;
+use memofs::{StdBackend, Vfs};
 use rayon::prelude::*;
 use rbx_dom_weak::{types::Ref, Ustr};
 use serde::{Deserialize, Serialize};
@@ -18,20 +18,32 @@ use tokio::runtime::Runtime;
 
 use crate::{
     serve_session::ServeSession,
-    snapshot::{AppliedPatchSet, InstanceWithMeta, RojoTree},
+    snapshot::{AppliedPatchSet, InstanceWithMeta, InstigatingSource, RojoTree},
 };
 
 use super::resolve_path;
 
 const ABSOLUTE_PATH_FAILED_ERR: &str = "Failed to turn relative path into absolute path!";
 
+/// How long `--watch` waits for changes to stop before rewriting the
+/// sourcemap, so that a burst of saves produces a single write.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
 /// Representation of a node in the generated sourcemap tree.
+///
+/// Each node is written as `{ "name", "className", "filePaths"?, "children"? }`,
+/// with `filePaths` and `children` left out when empty.
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SourcemapNode<'a> {
     name: &'a str,
     class_name: Ustr,
 
+    /// The files the Instance was read from, relative to the project folder
+    /// unless `--absolute` is passed. Scripts list their source file first.
+    /// With `--include-non-script-paths`, Instances made from a folder, like
+    /// a `Folder` or a `$path` pointing at one, also list that folder after
+    /// any files.
     #[serde(
         default,
         skip_serializing_if = "Vec::is_empty",
@@ -43,6 +55,23 @@ struct SourcemapNode<'a> {
     children: Vec<SourcemapNode<'a>>,
 }
 
+impl SourcemapNode<'_> {
+    /// Sorts the children of this node and all of its descendants by name,
+    /// then by class name and file paths so siblings with the same name
+    /// still come out in the same order every time.
+    fn sort_children(&mut self) {
+        self.children.sort_by(|a, b| {
+            a.name
+                .cmp(b.name)
+                .then_with(|| a.class_name.as_str().cmp(b.class_name.as_str()))
+                .then_with(|| a.file_paths.cmp(&b.file_paths))
+        });
+        for child in &mut self.children {
+            child.sort_children();
+        }
+    }
+}
+
 /// Generates a sourcemap file from the Rojo project.
 #[derive(Debug, Parser)]
 pub struct SourcemapCommand {
@@ -69,6 +98,16 @@ pub struct SourcemapCommand {
     /// Whether the sourcemap should use absolute paths instead of relative paths.
     #[clap(long)]
     pub absolute: bool,
+
+    /// Sort each Instance's children by name, so the sourcemap is the same
+    /// from run to run and across platforms.
+    #[clap(long)]
+    pub sorted: bool,
+
+    /// Also list the folder non-script Instances were made from in their
+    /// `filePaths`, such as the directory behind a `Folder` or a `$path`.
+    #[clap(long)]
+    pub include_non_script_paths: bool,
 }
 
 impl SourcemapCommand {
@@ -76,10 +115,15 @@ impl SourcemapCommand {
         let project_path = resolve_path(&self.project);
 
         log::trace!("Constructing in-memory filesystem");
-        let vfs = Vfs::new_default();
-        vfs.set_watch_enabled(self.watch);
+        // Sourcemap generation only reads the project, so the VFS refuses
+        // writes to make accidental mutation impossible.
+        let vfs = if self.watch {
+            Vfs::new(StdBackend::new_read_only())
+        } else {
+            Vfs::new_oneshot_read_only()
+        };
 
-        let session_start = std::time::Instant::now();
+        let session_start = Instant::now();
         let session = if self.watch {
             ServeSession::new(vfs, project_path, None)?
         } else {
@@ -103,12 +147,14 @@ impl SourcemapCommand {
             .build_global()
             .ok();
 
-        let sm_start = std::time::Instant::now();
+        let sm_start = Instant::now();
         write_sourcemap(
             &session,
             self.output.as_deref(),
             filter,
             self.absolute,
+            self.include_non_script_paths,
+            self.sorted,
             false,
         )?;
         log::debug!("[PERF] write_sourcemap: {:.1?}", sm_start.elapsed());
@@ -136,15 +182,30 @@ impl SourcemapCommand {
                 let receiver = session.message_queue().subscribe(cursor);
                 let (new_cursor, patch_set) = rt.block_on(receiver).unwrap();
                 cursor = new_cursor;
+                let mut affected = patch_set_affects_sourcemap(&session, &patch_set, filter);
+
+                // Fold a burst of changes, like a rename arriving as a remove
+                // and an add, into a single rewrite.
+                while let Ok(Ok((new_cursor, patch_set))) = rt.block_on(tokio::time::timeout(
+                    WATCH_DEBOUNCE,
+                    session.message_queue().subscribe(cursor),
+                )) {
+                    cursor = new_cursor;
+                    affected |= patch_set_affects_sourcemap(&session, &patch_set, filter);
+                }
 
-                if patch_set_affects_sourcemap(&session, &patch_set, filter) {
+                if affected {
+                    let start = Instant::now();
                     write_sourcemap(
                         &session,
                         self.output.as_deref(),
                         filter,
                         self.absolute,
+                        self.include_non_script_paths,
+                        self.sorted,
                         false,
                     )?;
+                    log::info!("Updated sourcemap in {:.2?}", start.elapsed());
                 }
             }
         }
@@ -157,17 +218,33 @@ impl SourcemapCommand {
     }
 }
 
-pub(crate) fn filter_nothing(_instance: &InstanceWithMeta) -> bool {
-    true
+/// Returns the folder `instance` was made from, if any: the directory it
+/// was snapshotted from, or the `$path` of the project node that made it.
+fn source_dir(instance: &InstanceWithMeta) -> Option<PathBuf> {
+    let dir = match instance.metadata().instigating_source.as_ref()? {
+        InstigatingSource::Path(path) => path.clone(),
+        InstigatingSource::ProjectNode { path, node, .. } => {
+            path.parent()?.join(node.path.as_ref()?.path())
+        }
+    };
+    dir.is_dir().then_some(dir)
 }
 
-fn filter_non_scripts(instance: &InstanceWithMeta) -> bool {
+fn is_script(instance: &InstanceWithMeta) -> bool {
     matches!(
         instance.class_name().as_str(),
         "Script" | "LocalScript" | "ModuleScript"
     )
 }
 
+pub(crate) fn filter_nothing(_instance: &InstanceWithMeta) -> bool {
+    true
+}
+
+fn filter_non_scripts(instance: &InstanceWithMeta) -> bool {
+    is_script(instance)
+}
+
 fn patch_set_affects_sourcemap(
     session: &ServeSession,
     patch_set: &[AppliedPatchSet],
@@ -211,6 +288,7 @@ fn recurse_create_node<'a>(
     canonical_project_dir: &Path,
     filter: fn(&InstanceWithMeta) -> bool,
     use_absolute_paths: bool,
+    include_dirs: bool,
 ) -> Option<SourcemapNode<'a>> {
     let instance = tree.get_instance(referent).expect("instance did not exist");
 
@@ -224,6 +302,7 @@ fn recurse_create_node<'a>(
                 canonical_project_dir,
                 filter,
                 use_absolute_paths,
+                include_dirs,
             )
         })
         .collect();
@@ -234,15 +313,24 @@ fn recurse_create_node<'a>(
         return None;
     }
 
-    let file_paths = instance
+    let mut file_paths: Vec<&Path> = instance
         .metadata()
         .relevant_paths
         .iter()
         .filter(|path| path.is_file())
-        .map(|path| path.as_path());
+        .map(|path| path.as_path())
+        .collect();
+
+    let source_dir = if include_dirs && !is_script(&instance) {
+        source_dir(&instance)
+    } else {
+        None
+    };
+    if let Some(dir) = &source_dir {
+        file_paths.push(dir);
+    }
 
-    let mut output_file_paths: Vec<Cow<'a, Path>> =
-        Vec::with_capacity(instance.metadata().relevant_paths.len());
+    let mut output_file_paths: Vec<Cow<'a, Path>> = Vec::with_capacity(file_paths.len());
 
     for val in file_paths {
         if use_absolute_paths {
@@ -269,28 +357,36 @@ pub(crate) fn write_sourcemap(
     output: Option<&Path>,
     filter: fn(&InstanceWithMeta) -> bool,
     use_absolute_paths: bool,
+    include_dirs: bool,
+    sorted: bool,
     quiet: bool,
 ) -> anyhow::Result<()> {
-    let t0 = std::time::Instant::now();
+    let t0 = Instant::now();
     let tree = session.tree();
 
     let canonical_project_dir = session.root_dir().to_path_buf();
 
-    let root_node = recurse_create_node(
+    let mut root_node = recurse_create_node(
         &tree,
         tree.get_root_id(),
         &canonical_project_dir,
         filter,
         use_absolute_paths,
+        include_dirs,
     );
-    let t1 = std::time::Instant::now();
+    if sorted {
+        if let Some(root_node) = &mut root_node {
+            root_node.sort_children();
+        }
+    }
+    let t1 = Instant::now();
 
     if let Some(output_path) = output {
         let json_output = serde_json::to_string(&root_node)?;
-        let t2 = std::time::Instant::now();
+        let t2 = Instant::now();
 
         write_atomic(output_path, json_output.as_bytes())?;
-        let t3 = std::time::Instant::now();
+        let t3 = Instant::now();
 
         log::debug!(
             "[PERF] write_sourcemap: tree_walk={:.1?}, json_serialize={:.1?} ({} bytes), write={:.1?}",
@@ -428,7 +524,7 @@ mod test {
     use crate::cli::sourcemap::SourcemapNode;
     use crate::cli::SourcemapCommand;
     use insta::internals::Content;
-    use std::path::Path;
+    use std::{borrow::Cow, path::Path};
 
     #[test]
     fn maps_relative_paths() {
@@ -447,6 +543,8 @@ mod test {
             include_non_scripts: false,
             watch: false,
             absolute: false,
+            sorted: false,
+            include_non_script_paths: false,
         };
         assert!(sourcemap_command.run().is_ok());
 
@@ -473,6 +571,8 @@ mod test {
             include_non_scripts: false,
             watch: false,
             absolute: true,
+            sorted: false,
+            include_non_script_paths: false,
         };
         assert!(sourcemap_command.run().is_ok());
 
@@ -498,4 +598,171 @@ mod test {
             })
         });
     }
+
+    #[test]
+    fn lists_associated_files() {
+        let sourcemap_dir = tempfile::tempdir().unwrap();
+        let sourcemap_output = sourcemap_dir.path().join("sourcemap.json");
+        let project_path = fs_err::canonicalize(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("test-projects")
+                .join("associated_files"),
+        )
+        .unwrap();
+        let sourcemap_command = SourcemapCommand {
+            project: project_path,
+            output: Some(sourcemap_output.clone()),
+            include_non_scripts: false,
+            watch: false,
+            absolute: false,
+            sorted: false,
+            include_non_script_paths: false,
+        };
+        assert!(sourcemap_command.run().is_ok());
+
+        let raw_sourcemap_contents = fs_err::read_to_string(sourcemap_output.as_path()).unwrap();
+        let sourcemap_contents: serde_json::Value =
+            serde_json::from_str(&raw_sourcemap_contents).unwrap();
+        let config = &sourcemap_contents["children"][0]["children"][0];
+        assert_eq!(config["name"], "Config");
+
+        let file_paths: Vec<_> = config["filePaths"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|path| Path::new(path.as_str().unwrap()).to_path_buf())
+            .collect();
+        assert_eq!(
+            file_paths,
+            vec![
+                Path::new("src").join("Config.luau"),
+                Path::new("data").join("Config.dat"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sorted_output_is_identical_between_runs() {
+        let sourcemap_dir = tempfile::tempdir().unwrap();
+        let project_path = fs_err::canonicalize(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("test-projects")
+                .join("relative_paths")
+                .join("project"),
+        )
+        .unwrap();
+
+        let run = |file_name: &str| {
+            let output = sourcemap_dir.path().join(file_name);
+            let sourcemap_command = SourcemapCommand {
+                project: project_path.clone(),
+                output: Some(output.clone()),
+                include_non_scripts: true,
+                watch: false,
+                absolute: false,
+                sorted: true,
+                include_non_script_paths: false,
+            };
+            sourcemap_command.run().unwrap();
+            fs_err::read(output).unwrap()
+        };
+
+        assert_eq!(run("first.json"), run("second.json"));
+    }
+
+    #[test]
+    fn sorts_duplicate_names_by_class_then_path() {
+        let node = |name, class_name: &str, path: &'static str| SourcemapNode {
+            name,
+            class_name: class_name.into(),
+            file_paths: vec![Cow::Borrowed(Path::new(path))],
+            children: Vec::new(),
+        };
+
+        let mut root = SourcemapNode {
+            name: "Root",
+            class_name: "Folder".into(),
+            file_paths: Vec::new(),
+            children: vec![
+                node("Util", "ModuleScript", "src/b/Util.luau"),
+                node("Util", "Folder", "src/Util"),
+                node("Alpha", "Script", "src/Alpha.server.luau"),
+                node("Util", "ModuleScript", "src/a/Util.luau"),
+            ],
+        };
+        root.sort_children();
+
+        let order: Vec<_> = root
+            .children
+            .iter()
+            .map(|child| {
+                (
+                    child.name,
+                    child.class_name.as_str(),
+                    child.file_paths[0].as_ref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("Alpha", "Script", Path::new("src/Alpha.server.luau")),
+                ("Util", "Folder", Path::new("src/Util")),
+                ("Util", "ModuleScript", Path::new("src/a/Util.luau")),
+                ("Util", "ModuleScript", Path::new("src/b/Util.luau")),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_folders_of_non_scripts() {
+        let project_dir = tempfile::tempdir().unwrap();
+        let project_path = fs_err::canonicalize(project_dir.path()).unwrap();
+        fs_err::create_dir_all(project_path.join("shared/Utils")).unwrap();
+        fs_err::write(project_path.join("shared/Utils/Math.luau"), "return {}").unwrap();
+        fs_err::write(
+            project_path.join("default.project.json5"),
+            r#"{
+                "name": "folders",
+                "tree": {
+                    "$className": "DataModel",
+                    "ReplicatedStorage": { "$path": "shared" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let sourcemap_output = project_path.join("sourcemap.json");
+        let sourcemap_command = SourcemapCommand {
+            project: project_path.clone(),
+            output: Some(sourcemap_output.clone()),
+            include_non_scripts: true,
+            watch: false,
+            absolute: false,
+            sorted: false,
+            include_non_script_paths: true,
+        };
+        sourcemap_command.run().unwrap();
+
+        let sourcemap: serde_json::Value =
+            serde_json::from_str(&fs_err::read_to_string(sourcemap_output).unwrap()).unwrap();
+        let replicated_storage = &sourcemap["children"][0];
+        assert_eq!(replicated_storage["name"], "ReplicatedStorage");
+        assert_eq!(
+            replicated_storage["filePaths"],
+            serde_json::json!(["shared"])
+        );
+
+        let utils = &replicated_storage["children"][0];
+        assert_eq!(utils["name"], "Utils");
+        assert_eq!(utils["className"], "Folder");
+        assert_eq!(utils["filePaths"], serde_json::json!(["shared/Utils"]));
+
+        // Scripts only list their files.
+        let math = &utils["children"][0];
+        assert_eq!(
+            math["filePaths"],
+            serde_json::json!(["shared/Utils/Math.luau"])
+        );
+    }
 }
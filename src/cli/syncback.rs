@@ -14,11 +14,16 @@ use tempfile::NamedTempFile;
 use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
 
 use crate::{
+    disk_space::check_available_space,
     path_serializer::display_absolute,
     project::Project,
     roblox_api,
     serve_session::ServeSession,
-    syncback::{syncback_loop_with_walked_paths, FsSnapshot},
+    syncback::{
+        backup_project, check_path_lengths, new_backup_dir, planned_changes, render_changes,
+        syncback_loop_with_stats, Conflict, DiffFormat, DiffOp, FsSnapshot, SyncState,
+        SyncbackStats, DEFAULT_MAX_FS_THREADS, HASH_CACHE_PATH, PHASE_LOG_TARGET,
+    },
 };
 
 use super::{resolve_path, sourcemap::write_sourcemap_from_syncback, GlobalOptions};
@@ -55,10 +60,21 @@ pub struct SyncbackCommand {
     pub list: bool,
 
     /// If provided, syncback will not actually write anything to the file
-    /// system. The command will otherwise run normally.
+    /// system. Instead, every planned addition, modification and removal is
+    /// printed to stdout, followed by a summary count.
     #[clap(long)]
     pub dry_run: bool,
 
+    /// Prints the planned changes to stdout in the given format instead of
+    /// the plain `--list` output. Valid values are unified and json. Pair
+    /// with `--dry-run` to preview a syncback without writing anything.
+    #[clap(long, value_name = "FORMAT")]
+    pub diff_format: Option<DiffFormat>,
+
+    /// Shorthand for `--diff-format json`.
+    #[clap(long, conflicts_with = "diff_format")]
+    pub json: bool,
+
     /// If provided, prompts before writing to the file system.
     /// By default, syncback runs non-interactively.
     #[clap(long, short = 'i')]
@@ -70,14 +86,77 @@ pub struct SyncbackCommand {
     #[clap(long, short = 'n')]
     pub incremental: bool,
 
-    /// Generate a sourcemap.json file after syncback.
+    /// If provided, syncback only adds and updates files and never removes
+    /// any, even for Instances missing from the input. Same as setting
+    /// `neverRemove` in the project's `syncbackRules`.
+    #[clap(long)]
+    pub only_new: bool,
+
+    /// Write a sourcemap of the synced-back tree to the given path, or to
+    /// sourcemap.json in the project folder if no path is given. A path must
+    /// be given as `--sourcemap=PATH` so it isn't mistaken for the project.
+    /// It's built from the syncback result, so the project isn't read back
+    /// from disk.
+    #[clap(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+    pub sourcemap: Option<Option<PathBuf>>,
+
+    /// Before writing, copy the project file and every `$path` it references
+    /// to a timestamped backup directory so the previous state can be
+    /// restored. Backups are stored in `~/.atlas/backups/` by default.
+    #[clap(long)]
+    pub backup: bool,
+
+    /// Directory to store syncback backups in. Implies `--backup`.
+    #[clap(long, value_name = "DIR")]
+    pub backup_dir: Option<PathBuf>,
+
+    /// Abort instead of only warning when syncback would overwrite or remove
+    /// a file that was edited since syncback last wrote it.
+    #[clap(long)]
+    pub fail_on_conflict: bool,
+
+    /// Fail instead of writing anything if syncback warned about anything,
+    /// such as Instances with duplicate names or that fell back to a binary
+    /// model. Every warning is reported together at the end.
+    #[clap(long)]
+    pub warnings_as_errors: bool,
+
+    /// Don't check that the project's volume has room for the files syncback
+    /// is about to write.
     #[clap(long)]
-    pub sourcemap: bool,
+    pub skip_disk_check: bool,
+
+    /// Don't check that every path syncback is about to write fits within
+    /// the platform's path length limit, e.g. because long paths are enabled
+    /// on Windows.
+    #[clap(long)]
+    pub skip_path_check: bool,
+
+    /// Only log the final summary: hides the per-phase `[PERF]` timing lines
+    /// that syncback emits at debug verbosity.
+    #[clap(long)]
+    pub summary_only: bool,
+
+    /// Log how far syncback has gotten at the end of each phase and every
+    /// thousand Instances, with the time elapsed so far.
+    #[clap(long)]
+    pub progress: bool,
+
+    /// How many threads write and remove files once syncback has decided
+    /// what to change. Defaults to the number of CPUs, up to 4, which keeps
+    /// spinning disks from thrashing. Fast SSDs may benefit from more.
+    #[clap(long, value_name = "N")]
+    pub threads_fs: Option<usize>,
 
     /// Base directory for resolving relative paths (project, input).
     /// Defaults to the current working directory.
     #[clap(long, hide = true, default_value = ".")]
     pub working_dir: PathBuf,
+
+    /// Set by `clone --subtree`: fail before writing anything unless the
+    /// input has an Instance at this `/`-separated path.
+    #[clap(skip)]
+    pub subtree: Option<String>,
 }
 
 impl SyncbackCommand {
@@ -158,8 +237,11 @@ impl SyncbackCommand {
         let input_kind = FileKind::from_path(&path_new).context(UNKNOWN_INPUT_KIND_ERR)?;
         let dom_start_timer = Instant::now();
         let dom_new = read_dom(&path_new, input_kind)?;
+        if let Some(subtree) = &self.subtree {
+            check_subtree_exists(&dom_new, subtree)?;
+        }
         let dom_elapsed = dom_start_timer.elapsed();
-        log::debug!("[PERF] parse rbxl: {:.3}s", dom_elapsed.as_secs_f64());
+        log::debug!(target: PHASE_LOG_TARGET, "[PERF] parse rbxl: {:.3}s", dom_elapsed.as_secs_f64());
 
         let vfs = Vfs::new_oneshot();
 
@@ -167,6 +249,7 @@ impl SyncbackCommand {
         let mut session_old = ServeSession::new_oneshot(vfs, path_old.clone())?;
         let project_elapsed = project_start_timer.elapsed();
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] init old tree (prefetch+snapshot+patch): {:.3}s",
             project_elapsed.as_secs_f64()
         );
@@ -190,33 +273,98 @@ impl SyncbackCommand {
             }
         }
 
+        let only_new_project;
+        let project = if self.only_new {
+            let mut project = session_old.root_project().clone();
+            project
+                .syncback_rules
+                .get_or_insert_with(Default::default)
+                .set_never_remove(true);
+            only_new_project = project;
+            &only_new_project
+        } else {
+            session_old.root_project()
+        };
+
         let syncback_timer = Instant::now();
         if self.incremental {
             log::info!("Beginning incremental syncback...");
         } else {
             log::info!("Beginning syncback (clean mode)...");
         }
-        let result = syncback_loop_with_walked_paths(
+        let stats = if self.progress {
+            SyncbackStats::new().with_progress(|progress| {
+                log::info!(
+                    "Syncback {}: {} processed ({:.1}s)",
+                    progress.phase,
+                    progress.processed,
+                    progress.elapsed.as_secs_f64()
+                )
+            })
+        } else {
+            SyncbackStats::new()
+        };
+        let result = syncback_loop_with_stats(
             session_old.vfs(),
             &mut dom_old,
             dom_new,
-            session_old.root_project(),
+            project,
             self.incremental,
+            Some(&stats),
             pre_walked_paths,
         )?;
+        stats.log_summary();
+        if self.warnings_as_errors && stats.has_issues() {
+            let issues = stats.issues();
+            let mut message = format!(
+                "Syncback produced {} warning(s) and --warnings-as-errors is set, \
+                 so nothing was written:",
+                issues.len()
+            );
+            for issue in issues {
+                message.push_str("\n  - ");
+                message.push_str(&issue);
+            }
+            anyhow::bail!(message);
+        }
         let syncback_elapsed = syncback_timer.elapsed();
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] syncback_loop total: {:.3}s",
             syncback_elapsed.as_secs_f64()
         );
 
         let base_path = session_old.root_project().folder_location();
-        if self.list {
+        // A dry run always prints its plan, since that's the only output it
+        // has. Otherwise the plan is only printed when a format was asked for.
+        let diff_format = if self.json {
+            Some(DiffFormat::Json)
+        } else if self.dry_run {
+            Some(self.diff_format.unwrap_or_default())
+        } else {
+            self.diff_format
+        };
+
+        let mut changes = Vec::new();
+        if let Some(format) = diff_format {
+            changes = planned_changes(
+                &result.fs_snapshot,
+                session_old.vfs(),
+                &result.new_tree,
+                &result.instance_paths,
+                base_path,
+            );
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(render_changes(&changes, format).as_bytes())?;
+            stdout.flush()?;
+        } else if self.list {
             list_files(&result.fs_snapshot, global.color.into(), base_path)?;
         }
 
         drop(dom_old);
 
+        report_conflicts(&result.conflicts, self.fail_on_conflict)?;
+
         if !self.dry_run {
             if self.interactive {
                 eprintln!(
@@ -235,26 +383,74 @@ impl SyncbackCommand {
                 }
             }
 
+            if !self.skip_disk_check {
+                check_available_space(base_path, result.fs_snapshot.estimated_bytes())?;
+            }
+
+            if !self.skip_path_check {
+                check_path_lengths(
+                    &result.fs_snapshot,
+                    &result.new_tree,
+                    &result.instance_paths,
+                )?;
+            }
+
+            if self.backup || self.backup_dir.is_some() {
+                let backup_root = match &self.backup_dir {
+                    Some(dir) if dir.is_absolute() => dir.clone(),
+                    Some(dir) => base.join(dir),
+                    None => dirs::home_dir()
+                        .context("Could not locate the home directory for --backup")?
+                        .join(".atlas")
+                        .join("backups"),
+                };
+                let project_name = session_old.project_name().to_owned();
+                let backup_dir = new_backup_dir(session_old.vfs(), &backup_root.join(project_name));
+
+                let backup_timer = Instant::now();
+                let copied =
+                    backup_project(session_old.vfs(), session_old.root_project(), &backup_dir)
+                        .with_context(|| {
+                            format!("Could not back up project to {}", backup_dir.display())
+                        })?;
+                log::info!(
+                    "Backed up {} files to {} in {:.02}s",
+                    copied,
+                    backup_dir.display(),
+                    backup_timer.elapsed().as_secs_f32()
+                );
+            }
+
             log::info!("Writing to the file system...");
 
             let git_cache_timer = Instant::now();
             let git_cache = crate::git::GitIndexCache::new(base_path);
             log::debug!(
+                target: PHASE_LOG_TARGET,
                 "[PERF] git index cache build: {:.3}s (entries: {})",
                 git_cache_timer.elapsed().as_secs_f64(),
                 git_cache.as_ref().map_or(0, |c| c.len()),
             );
 
+            let fs_threads = self
+                .threads_fs
+                .unwrap_or_else(|| num_cpus::get().min(DEFAULT_MAX_FS_THREADS));
+
             let write_timer = Instant::now();
-            if self.sourcemap {
-                let sourcemap_path = base_path.join("sourcemap.json");
+            if let Some(sourcemap) = &self.sourcemap {
+                let sourcemap_path = match sourcemap {
+                    Some(path) if path.is_absolute() => path.clone(),
+                    Some(path) => base.join(path),
+                    None => base_path.join("sourcemap.json"),
+                };
 
                 let (write_result, sourcemap_result) = std::thread::scope(|s| {
                     let write_handle = s.spawn(|| {
-                        result.fs_snapshot.write_to_vfs_parallel(
+                        result.fs_snapshot.write_to_vfs_with_threads(
                             base_path,
                             session_old.vfs(),
                             git_cache.as_ref(),
+                            fs_threads,
                         )
                     });
 
@@ -280,17 +476,26 @@ impl SyncbackCommand {
                     Err(e) => log::warn!("Could not generate sourcemap: {}", e),
                 }
             } else {
-                result.fs_snapshot.write_to_vfs_parallel(
+                result.fs_snapshot.write_to_vfs_with_threads(
                     base_path,
                     session_old.vfs(),
                     git_cache.as_ref(),
+                    fs_threads,
                 )?;
             }
             log::debug!(
-                "[PERF] write_to_vfs_parallel: {:.3}s",
+                target: PHASE_LOG_TARGET,
+                "[PERF] write_to_vfs_with_threads: {:.3}s",
                 write_timer.elapsed().as_secs_f64()
             );
 
+            SyncState::record_written(session_old.vfs(), base_path, &result.fs_snapshot);
+            if let Some(hash_cache) = &result.hash_cache {
+                if let Err(err) = hash_cache.save(base_path) {
+                    log::warn!("Could not update {}: {}", HASH_CACHE_PATH, err);
+                }
+            }
+
             log::info!(
                 "Finished syncback: wrote {} files/folders, removed {}.",
                 result.fs_snapshot.added_paths().len(),
@@ -309,15 +514,18 @@ impl SyncbackCommand {
                 }
             }
         } else {
+            let count = |op| changes.iter().filter(|change| change.op == op).count();
             log::info!(
-                "Would write {} files/folders and remove {} files/folders.",
-                result.fs_snapshot.added_paths().len(),
-                result.fs_snapshot.removed_paths().len()
+                "Would add {} paths, modify {} and remove {}.",
+                count(DiffOp::Add),
+                count(DiffOp::Modify),
+                count(DiffOp::Remove)
             );
             log::info!("Aborting before writing to file system due to `--dry-run`");
         }
 
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] TOTAL syncback command: {:.3}s",
             total_timer.elapsed().as_secs_f64()
         );
@@ -330,6 +538,36 @@ impl SyncbackCommand {
     }
 }
 
+/// Warns about every conflict, or fails if `fail_on_conflict` is set and there
+/// are any.
+fn report_conflicts(conflicts: &[Conflict], fail_on_conflict: bool) -> anyhow::Result<()> {
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+
+    for conflict in conflicts {
+        log::warn!(
+            "{} was edited since the last syncback and will be {}",
+            conflict.path.display(),
+            if conflict.removed {
+                "removed"
+            } else {
+                "overwritten"
+            }
+        );
+    }
+
+    if fail_on_conflict {
+        anyhow::bail!(
+            "Aborting because syncback would overwrite {} locally edited file(s). \
+             Remove --fail-on-conflict to write anyway.",
+            conflicts.len()
+        );
+    }
+
+    Ok(())
+}
+
 /// Gets the first place ID from the project's servePlaceIds field.
 fn get_place_id_from_project(project_path: &Path) -> anyhow::Result<u64> {
     // Use oneshot Vfs to avoid file watching issues
@@ -347,6 +585,50 @@ fn get_place_id_from_project(project_path: &Path) -> anyhow::Result<u64> {
         .context("servePlaceIds is empty in project file")
 }
 
+/// Returns an error naming the children of the deepest Instance that was
+/// found if `dom` has nothing at `path`.
+fn check_subtree_exists(dom: &WeakDom, path: &str) -> anyhow::Result<()> {
+    let mut current = dom.root();
+    let mut found = Vec::new();
+
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        let child = current
+            .children()
+            .iter()
+            .map(|child| dom.get_by_ref(*child).unwrap())
+            .find(|child| child.name == segment);
+
+        let Some(child) = child else {
+            let parent = if found.is_empty() {
+                "The place".to_owned()
+            } else {
+                found.join("/")
+            };
+            let mut siblings: Vec<&str> = current
+                .children()
+                .iter()
+                .map(|child| dom.get_by_ref(*child).unwrap().name.as_str())
+                .collect();
+            siblings.sort_unstable();
+            siblings.dedup();
+
+            if siblings.is_empty() {
+                anyhow::bail!("There is no Instance at {path}: {parent} has no children");
+            }
+            anyhow::bail!(
+                "There is no Instance at {path}: {parent} has no child named '{segment}'. \
+                 Its children are: {}",
+                siblings.join(", ")
+            );
+        };
+
+        found.push(segment);
+        current = child;
+    }
+
+    Ok(())
+}
+
 fn read_dom(path: &Path, file_kind: FileKind) -> anyhow::Result<WeakDom> {
     let content = BufReader::new(File::open(path)?);
     match file_kind {
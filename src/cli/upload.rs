@@ -8,6 +8,7 @@ use reqwest::{
     header::{ACCEPT, CONTENT_TYPE, COOKIE, USER_AGENT},
     StatusCode,
 };
+use serde::Serialize;
 use serde_json::Value;
 
 use crate::serve_session::ServeSession;
@@ -47,6 +48,21 @@ pub struct UploadCommand {
     /// Asset ID to upload to.
     #[clap(long = "asset_id")]
     pub asset_id: u64,
+
+    /// Print the uploaded asset's ID and version to stdout as JSON once the
+    /// upload finishes, for scripts to read. Logs still go to stderr.
+    #[clap(long)]
+    pub output_json: bool,
+}
+
+/// What `--output-json` prints after a successful upload.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadOutput {
+    asset_id: u64,
+    /// The version the upload created, or `None` if the API used doesn't
+    /// report one.
+    asset_version_number: Option<u64>,
 }
 
 impl UploadCommand {
@@ -73,7 +89,7 @@ impl UploadCommand {
 
         let api_key = self.api_key.or(global.opencloud);
 
-        match (self.cookie, api_key, self.universe_id) {
+        let output = match (self.cookie, api_key, self.universe_id) {
             (cookie, None, universe) => {
                 // Legacy cookie auth
                 if universe.is_some() {
@@ -107,12 +123,18 @@ impl UploadCommand {
 
                 do_upload_place(buffer, universe_id, self.asset_id, &api_key)
             }
+        }?;
+
+        if self.output_json {
+            println!("{}", serde_json::to_string(&output)?);
         }
+
+        Ok(())
     }
 }
 
 /// Legacy upload via Data/Upload.ashx with cookie auth.
-fn do_upload_legacy(buffer: Vec<u8>, asset_id: u64, cookie: &str) -> anyhow::Result<()> {
+fn do_upload_legacy(buffer: Vec<u8>, asset_id: u64, cookie: &str) -> anyhow::Result<UploadOutput> {
     let url = format!(
         "https://data.roblox.com/Data/Upload.ashx?assetid={}",
         asset_id
@@ -157,7 +179,11 @@ fn do_upload_legacy(buffer: Vec<u8>, asset_id: u64, cookie: &str) -> anyhow::Res
         );
     }
 
-    Ok(())
+    // Upload.ashx doesn't say which version it created.
+    Ok(UploadOutput {
+        asset_id,
+        asset_version_number: None,
+    })
 }
 
 /// Upload via the Open Cloud Assets API (PATCH /assets/v1/assets/{assetId}).
@@ -168,7 +194,7 @@ fn do_upload_legacy(buffer: Vec<u8>, asset_id: u64, cookie: &str) -> anyhow::Res
 ///   2. Poll the returned operation until completion
 ///
 /// See https://create.roblox.com/docs/reference/cloud/assets/v1
-fn do_upload_asset(buffer: Vec<u8>, asset_id: u64, api_key: &str) -> anyhow::Result<()> {
+fn do_upload_asset(buffer: Vec<u8>, asset_id: u64, api_key: &str) -> anyhow::Result<UploadOutput> {
     let client = reqwest::blocking::Client::new();
 
     // Step 1: PATCH the asset with new content
@@ -219,7 +245,7 @@ fn do_upload_asset(buffer: Vec<u8>, asset_id: u64, api_key: &str) -> anyhow::Res
     if let Some(true) = body["done"].as_bool() {
         // Operation completed immediately
         log_asset_result(&body);
-        return Ok(());
+        return Ok(asset_output(asset_id, &body));
     }
 
     let operation_path = body["path"]
@@ -271,7 +297,7 @@ fn do_upload_asset(buffer: Vec<u8>, asset_id: u64, api_key: &str) -> anyhow::Res
             }
 
             log_asset_result(&op_body);
-            return Ok(());
+            return Ok(asset_output(asset_id, &op_body));
         }
 
         log::debug!(
@@ -317,6 +343,32 @@ fn log_asset_result(body: &Value) {
     }
 }
 
+/// Reads the uploaded asset's ID and revision from a finished Assets API
+/// operation, falling back to `asset_id` if the response doesn't include one.
+fn asset_output(asset_id: u64, body: &Value) -> UploadOutput {
+    let response = &body["response"];
+    UploadOutput {
+        asset_id: json_u64(&response["assetId"]).unwrap_or(asset_id),
+        asset_version_number: json_u64(&response["revisionId"]),
+    }
+}
+
+/// Reads the new version from a Places API response, which looks like
+/// `{ "versionNumber": 7 }`.
+fn place_output(asset_id: u64, body: &Value) -> UploadOutput {
+    UploadOutput {
+        asset_id,
+        asset_version_number: json_u64(&body["versionNumber"]),
+    }
+}
+
+/// Roblox APIs return IDs as either numbers or numeric strings.
+fn json_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
 /// Upload via the Open Cloud Places API.
 /// See https://create.roblox.com/docs/cloud/guides/usage-place-publishing
 fn do_upload_place(
@@ -324,7 +376,7 @@ fn do_upload_place(
     universe_id: u64,
     asset_id: u64,
     api_key: &str,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<UploadOutput> {
     let url = format!(
         "https://apis.roblox.com/universes/v1/{}/places/{}/versions?versionType=Published",
         universe_id, asset_id
@@ -355,5 +407,49 @@ fn do_upload_place(
         );
     }
 
-    Ok(())
+    // The upload already succeeded, so a body we can't read only costs us
+    // the version number.
+    let body: Value = response.json().unwrap_or_default();
+    Ok(place_output(asset_id, &body))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn place_response_to_json() {
+        let response = serde_json::json!({ "versionNumber": 42 });
+        let output = serde_json::to_value(place_output(123, &response)).unwrap();
+
+        assert_eq!(
+            output,
+            serde_json::json!({ "assetId": 123, "assetVersionNumber": 42 })
+        );
+    }
+
+    #[test]
+    fn asset_operation_to_json() {
+        let operation = serde_json::json!({
+            "path": "operations/abc",
+            "done": true,
+            "response": {
+                "assetId": "456",
+                "revisionId": "7",
+                "moderationResult": { "moderationState": "Approved" },
+            },
+        });
+        let output = serde_json::to_value(asset_output(1, &operation)).unwrap();
+
+        assert_eq!(
+            output,
+            serde_json::json!({ "assetId": 456, "assetVersionNumber": 7 })
+        );
+    }
+
+    #[test]
+    fn missing_version_is_null() {
+        let output = serde_json::to_string(&place_output(123, &serde_json::json!({}))).unwrap();
+        assert_eq!(output, r#"{"assetId":123,"assetVersionNumber":null}"#);
+    }
 }
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use memofs::Vfs;
+use rbx_reflection::ReflectionDatabase;
+
+use crate::project::{Project, ProjectNode};
+
+use super::resolve_path;
+
+/// Check a project file for problems without building or serving it.
+#[derive(Debug, Parser)]
+pub struct ValidateCommand {
+    /// Path to the project to validate. Defaults to the current directory.
+    #[clap(default_value = "")]
+    pub project: PathBuf,
+}
+
+impl ValidateCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        let vfs = Vfs::new_oneshot();
+
+        let base_path = resolve_path(&self.project);
+        let project = Project::load_fuzzy(&vfs, &base_path)?
+            .context("A project file is required to run 'atlas project validate'")?;
+        let database = rbx_reflection_database::get()?;
+
+        let problems = find_problems(&project, database);
+        if problems.is_empty() {
+            println!("{} is valid.", project.file_location.display());
+            return Ok(());
+        }
+
+        println!("Found problems in {}:", project.file_location.display());
+        for problem in &problems {
+            println!("  - {problem}");
+        }
+        bail!(
+            "{} has {} problem(s)",
+            project.file_location.display(),
+            problems.len()
+        );
+    }
+}
+
+/// Returns a description of everything wrong with `project` that loading it
+/// doesn't already catch.
+fn find_problems(project: &Project, database: &ReflectionDatabase) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let root_name = project.name.as_deref().unwrap_or("tree");
+    check_node(
+        &project.tree,
+        root_name,
+        project.folder_location(),
+        database,
+        &mut problems,
+    );
+
+    if let Some(rules) = &project.syncback_rules {
+        if let Err(err) = rules.compile_globs() {
+            problems.push(format!("syncbackRules.ignorePaths: {err:#}"));
+        }
+        if let Err(err) = rules.compile_tree_globs() {
+            problems.push(format!("syncbackRules.ignoreTrees: {err:#}"));
+        }
+        if let Err(err) = rules.compile_include_trees() {
+            problems.push(format!("syncbackRules.includeTrees: {err:#}"));
+        }
+        if let Err(err) = rules.compile_ignore_properties() {
+            problems.push(format!("syncbackRules.ignoreProperties: {err:#}"));
+        }
+    }
+
+    problems
+}
+
+fn check_node(
+    node: &ProjectNode,
+    node_path: &str,
+    folder: &Path,
+    database: &ReflectionDatabase,
+    problems: &mut Vec<String>,
+) {
+    if let Some(class_name) = &node.class_name {
+        if !database.classes.contains_key(class_name.as_str()) {
+            problems.push(format!(
+                "{node_path}: class '{class_name}' is not in the reflection database"
+            ));
+        }
+    }
+
+    if let Some(path_node) = &node.path {
+        // A glob matching nothing is fine, but the folder it looks in has to
+        // be there.
+        let path = folder.join(path_node.literal_path());
+        if !path.exists() && !path_node.is_optional() {
+            problems.push(format!(
+                "{node_path}: $path {} does not exist",
+                path.display()
+            ));
+        }
+    }
+
+    for (name, child) in &node.children {
+        check_node(
+            child,
+            &format!("{node_path}/{name}"),
+            folder,
+            database,
+            problems,
+        );
+    }
+}
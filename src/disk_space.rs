@@ -0,0 +1,211 @@
+//! Pre-flight checks that a large write will fit on its target volume, so
+//! `syncback` and `build` can refuse up front instead of failing halfway
+//! through and leaving a partially written project behind.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+/// Returned when the target volume doesn't have room for a write.
+#[derive(Debug, Error)]
+#[error(
+    "Not enough disk space to write to {}: about {} is needed but only {} is available. \
+     Free up some space or pass --skip-disk-check to write anyway.",
+    .target.display(),
+    format_bytes(*.required),
+    format_bytes(*.available)
+)]
+pub struct InsufficientSpaceError {
+    pub target: PathBuf,
+    pub required: u64,
+    pub available: u64,
+}
+
+/// Checks that writing `required` bytes under `target` will fit, using the
+/// operating system's view of the volume's free space.
+pub fn check_available_space(target: &Path, required: u64) -> Result<(), InsufficientSpaceError> {
+    check_available_space_with(target, required, available_space)
+}
+
+/// Like [`check_available_space`], but asks `query` for the free space of a
+/// path instead of the operating system.
+///
+/// `target` doesn't need to exist yet; the query is made against its nearest
+/// existing ancestor. If the free space can't be determined the check passes,
+/// since refusing to write on a platform we can't query would be worse than
+/// not checking at all.
+pub fn check_available_space_with<F>(
+    target: &Path,
+    required: u64,
+    query: F,
+) -> Result<(), InsufficientSpaceError>
+where
+    F: FnOnce(&Path) -> io::Result<u64>,
+{
+    if required == 0 {
+        return Ok(());
+    }
+
+    let Some(existing) = target.ancestors().find(|path| path.exists()) else {
+        return Ok(());
+    };
+
+    let available = match query(existing) {
+        Ok(available) => available,
+        Err(err) => {
+            log::debug!(
+                "Could not query free space for {}, skipping disk check: {}",
+                existing.display(),
+                err
+            );
+            return Ok(());
+        }
+    };
+
+    if required > available {
+        return Err(InsufficientSpaceError {
+            target: target.to_path_buf(),
+            required,
+            available,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the number of bytes available to the current user on the volume
+/// containing `path`.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `c_path` is a valid NUL-terminated string and `stat` is only
+    // read after statvfs reports success.
+    let stat = unsafe {
+        if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+
+    Ok((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+/// Returns the number of bytes available to the current user on the volume
+/// containing `path`.
+#[cfg(windows)]
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory_name: *const u16,
+            free_bytes_available_to_caller: *mut u64,
+            total_number_of_bytes: *mut u64,
+            total_number_of_free_bytes: *mut u64,
+        ) -> i32;
+    }
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+
+    // SAFETY: `wide` is NUL-terminated and the out-pointers are either valid
+    // or null, which the API allows for the totals we don't need.
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(available)
+}
+
+/// Returns the number of bytes available to the current user on the volume
+/// containing `path`.
+#[cfg(not(any(unix, windows)))]
+pub fn available_space(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "free space queries are not supported on this platform",
+    ))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aborts_when_space_is_short() {
+        let target = std::env::temp_dir();
+        let err = check_available_space_with(&target, 4096, |_| Ok(1024)).unwrap_err();
+
+        assert_eq!(err.required, 4096);
+        assert_eq!(err.available, 1024);
+        assert!(err.to_string().contains("--skip-disk-check"));
+    }
+
+    #[test]
+    fn passes_when_space_is_sufficient() {
+        let target = std::env::temp_dir();
+        check_available_space_with(&target, 1024, |_| Ok(4096)).unwrap();
+    }
+
+    #[test]
+    fn queries_nearest_existing_ancestor() {
+        let existing = std::env::temp_dir();
+        let target = existing.join("atlas-disk-space-test/does/not/exist.rbxl");
+        check_available_space_with(&target, 1, |queried| {
+            assert_eq!(queried, existing.as_path());
+            Ok(1)
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn unknown_space_does_not_abort() {
+        let target = std::env::temp_dir();
+        check_available_space_with(&target, u64::MAX, |_| {
+            Err(io::Error::new(io::ErrorKind::Unsupported, "nope"))
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
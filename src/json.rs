@@ -4,9 +4,10 @@
 //! to reduce boilerplate and improve ergonomics when working with JSONC files.
 
 use anyhow::Context as _;
+use indexmap::IndexMap;
 use lexical_write_float::{format::STANDARD, Options, RoundMode, ToLexicalWithOptions};
 use serde::{de::DeserializeOwned, Serialize};
-use std::num::{NonZeroI32, NonZeroUsize};
+use std::num::NonZeroI32;
 
 /// Parse JSONC text into a `serde_json::Value`.
 ///
@@ -137,7 +138,6 @@ const F32_FLOAT_OPTIONS: Options = Options::builder()
     .inf_string(Some(b"Infinity"))
     .positive_exponent_break(SCI_POSITIVE_BREAK)
     .negative_exponent_break(SCI_NEGATIVE_BREAK)
-    .max_significant_digits(NonZeroUsize::new(6))
     .round_mode(RoundMode::Round)
     .build_strict();
 
@@ -158,6 +158,21 @@ pub(crate) const F64_DISK_OPTIONS: &Options = &F64_FLOAT_OPTIONS;
 pub(crate) const F32_DISK_BUF_SIZE: usize = F32_BUF_SIZE;
 pub(crate) const F64_DISK_BUF_SIZE: usize = F64_BUF_SIZE;
 
+fn round_to_decimals(v: f64, decimals: u8) -> f64 {
+    if !v.is_finite() {
+        return v;
+    }
+    let factor = 10f64.powi(decimals as i32);
+    let rounded = (v * factor).round() / factor;
+    // Rounding huge values can overflow; those have no fractional digits to
+    // lose anyway.
+    if rounded.is_finite() {
+        rounded
+    } else {
+        v
+    }
+}
+
 pub(crate) fn format_f32(v: f32) -> String {
     let mut buffer = [0u8; F32_BUF_SIZE];
     let digits = v.to_lexical_with_options::<STANDARD>(&mut buffer, &F32_FLOAT_OPTIONS);
@@ -174,7 +189,29 @@ pub(crate) fn format_f64(v: f64) -> String {
         .into()
 }
 
-/// A JSON5 value that uses BTreeMap for sorted keys and supports NaN/Infinity.
+/// Formats `v` like [`format_f32`], first rounding it to at most `decimals`
+/// decimal places when set.
+fn format_f32_rounded(v: f32, decimals: Option<u8>) -> String {
+    match decimals {
+        Some(decimals) => format_f32(round_to_decimals(v as f64, decimals) as f32),
+        None => format_f32(v),
+    }
+}
+
+/// Formats `v` like [`format_f64`], first rounding it to at most `decimals`
+/// decimal places when set.
+fn format_f64_rounded(v: f64, decimals: Option<u8>) -> String {
+    match decimals {
+        Some(decimals) => format_f64(round_to_decimals(v, decimals)),
+        None => format_f64(v),
+    }
+}
+
+/// Object entries in the order they were serialized. Keys are sorted at write
+/// time unless the caller asked for insertion order.
+type Json5Map = IndexMap<String, Json5Value>;
+
+/// A JSON5 value that supports NaN/Infinity.
 /// Uses String for numbers to preserve exact representation (including scientific notation).
 #[derive(Debug, Clone, PartialEq)]
 enum Json5Value {
@@ -183,7 +220,7 @@ enum Json5Value {
     Number(String), // Store as string to preserve exact representation
     String(String),
     Array(Vec<Json5Value>),
-    Object(std::collections::BTreeMap<String, Json5Value>),
+    Object(Json5Map),
 }
 
 impl Json5Value {
@@ -205,7 +242,9 @@ impl Json5Value {
     }
 
     /// Write this value to a string with proper JSON5 formatting and indentation.
-    fn write_to(&self, output: &mut String, indent: usize) {
+    /// Object keys are written alphabetically when `sort_keys` is set, and in
+    /// serialization order otherwise.
+    fn write_to(&self, output: &mut String, indent: usize, sort_keys: bool) {
         let indent_str = "  ".repeat(indent);
         let inner_indent = "  ".repeat(indent + 1);
 
@@ -235,14 +274,14 @@ impl Json5Value {
                         if i > 0 {
                             output.push_str(", ");
                         }
-                        item.write_to(output, 0);
+                        item.write_to(output, 0, sort_keys);
                     }
                     output.push_str(" ]");
                 } else {
                     output.push_str("[\n");
                     for (i, item) in arr.iter().enumerate() {
                         output.push_str(&inner_indent);
-                        item.write_to(output, indent + 1);
+                        item.write_to(output, indent + 1, sort_keys);
                         if i < arr.len() - 1 {
                             output.push(',');
                         }
@@ -257,7 +296,10 @@ impl Json5Value {
                     output.push_str("{}");
                 } else {
                     output.push_str("{\n");
-                    let entries: Vec<_> = map.iter().collect();
+                    let mut entries: Vec<_> = map.iter().collect();
+                    if sort_keys {
+                        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+                    }
                     for (i, (key, value)) in entries.iter().enumerate() {
                         output.push_str(&inner_indent);
                         // Use unquoted keys if valid identifier, otherwise quote with escaping
@@ -267,7 +309,7 @@ impl Json5Value {
                             write_escaped_string(output, key);
                         }
                         output.push_str(": ");
-                        value.write_to(output, indent + 1);
+                        value.write_to(output, indent + 1, sort_keys);
                         if i < entries.len() - 1 {
                             output.push(',');
                         }
@@ -694,20 +736,72 @@ impl<'a> serde::ser::SerializeStructVariant for &mut CompactJson5Serializer<'a>
 ///
 /// Returns an error if the value cannot be serialized.
 pub fn to_vec_pretty_sorted<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
-    // Serialize directly to Json5Value tree (with BTreeMap for sorted keys)
+    to_vec_pretty_impl(value, true, None)
+}
+
+/// Like [`to_vec_pretty_sorted`], but rounds floats to at most
+/// `number_precision` decimal places when set. This is how the project's
+/// `numberPrecision` setting reaches model and meta files.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized.
+pub fn to_vec_pretty_sorted_rounded<T: Serialize>(
+    value: &T,
+    number_precision: Option<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    to_vec_pretty_impl(value, true, number_precision)
+}
+
+/// Serialize a value to a JSON5 byte vector, keeping object keys in the order
+/// they were serialized in.
+///
+/// Prefer [`to_vec_pretty_sorted`] for anything written to disk; this exists
+/// for callers that have opted out of stable ordering.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized.
+pub fn to_vec_pretty<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+    to_vec_pretty_impl(value, false, None)
+}
+
+/// Like [`to_vec_pretty`], but rounds floats to at most `number_precision`
+/// decimal places when set.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized.
+pub fn to_vec_pretty_rounded<T: Serialize>(
+    value: &T,
+    number_precision: Option<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    to_vec_pretty_impl(value, false, number_precision)
+}
+
+fn to_vec_pretty_impl<T: Serialize>(
+    value: &T,
+    sort_keys: bool,
+    number_precision: Option<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    // Serialize directly to a Json5Value tree, then write it out.
     let tree = value
-        .serialize(Json5ValueSerializer)
+        .serialize(Json5ValueSerializer { number_precision })
         .map_err(|e| anyhow::anyhow!("Failed to serialize: {}", e))?;
 
     let mut output = String::with_capacity(tree.size_hint());
-    tree.write_to(&mut output, 0);
+    tree.write_to(&mut output, 0, sort_keys);
     output.push('\n');
     Ok(output.into_bytes())
 }
 
 /// A serde Serializer that builds a Json5Value tree directly.
 /// This avoids the need to parse - we serialize directly to the intermediate representation.
-struct Json5ValueSerializer;
+#[derive(Clone, Copy)]
+struct Json5ValueSerializer {
+    /// Decimal places floats are rounded to, or `None` to write them as-is.
+    number_precision: Option<u8>,
+}
 
 impl serde::Serializer for Json5ValueSerializer {
     type Ok = Json5Value;
@@ -757,11 +851,17 @@ impl serde::Serializer for Json5ValueSerializer {
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        Ok(Json5Value::Number(format_f32(v)))
+        Ok(Json5Value::Number(format_f32_rounded(
+            v,
+            self.number_precision,
+        )))
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-        Ok(Json5Value::Number(format_f64(v)))
+        Ok(Json5Value::Number(format_f64_rounded(
+            v,
+            self.number_precision,
+        )))
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
@@ -820,8 +920,8 @@ impl serde::Serializer for Json5ValueSerializer {
         variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        let inner = value.serialize(Json5ValueSerializer)?;
-        let mut map = std::collections::BTreeMap::new();
+        let inner = value.serialize(self)?;
+        let mut map = Json5Map::new();
         map.insert(variant.to_string(), inner);
         Ok(Json5Value::Object(map))
     }
@@ -829,6 +929,7 @@ impl serde::Serializer for Json5ValueSerializer {
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         Ok(Json5SeqSerializer {
             items: Vec::with_capacity(len.unwrap_or(0)),
+            ser: self,
         })
     }
 
@@ -854,14 +955,16 @@ impl serde::Serializer for Json5ValueSerializer {
         Ok(Json5TupleVariantSerializer {
             variant: variant.to_string(),
             items: Vec::with_capacity(len),
+            ser: self,
         })
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         Ok(Json5MapSerializer {
-            map: std::collections::BTreeMap::new(),
+            map: Json5Map::new(),
             next_key: None,
             _capacity: len.unwrap_or(0),
+            ser: self,
         })
     }
 
@@ -882,8 +985,9 @@ impl serde::Serializer for Json5ValueSerializer {
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         Ok(Json5StructVariantSerializer {
             variant: variant.to_string(),
-            map: std::collections::BTreeMap::new(),
+            map: Json5Map::new(),
             _capacity: len,
+            ser: self,
         })
     }
 }
@@ -907,6 +1011,7 @@ impl serde::ser::Error for Json5SerError {
 
 struct Json5SeqSerializer {
     items: Vec<Json5Value>,
+    ser: Json5ValueSerializer,
 }
 
 impl serde::ser::SerializeSeq for Json5SeqSerializer {
@@ -914,7 +1019,7 @@ impl serde::ser::SerializeSeq for Json5SeqSerializer {
     type Error = Json5SerError;
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        self.items.push(value.serialize(Json5ValueSerializer)?);
+        self.items.push(value.serialize(self.ser)?);
         Ok(())
     }
 
@@ -952,6 +1057,7 @@ impl serde::ser::SerializeTupleStruct for Json5SeqSerializer {
 struct Json5TupleVariantSerializer {
     variant: String,
     items: Vec<Json5Value>,
+    ser: Json5ValueSerializer,
 }
 
 impl serde::ser::SerializeTupleVariant for Json5TupleVariantSerializer {
@@ -959,21 +1065,22 @@ impl serde::ser::SerializeTupleVariant for Json5TupleVariantSerializer {
     type Error = Json5SerError;
 
     fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        self.items.push(value.serialize(Json5ValueSerializer)?);
+        self.items.push(value.serialize(self.ser)?);
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        let mut map = std::collections::BTreeMap::new();
+        let mut map = Json5Map::new();
         map.insert(self.variant, Json5Value::Array(self.items));
         Ok(Json5Value::Object(map))
     }
 }
 
 struct Json5MapSerializer {
-    map: std::collections::BTreeMap<String, Json5Value>,
+    map: Json5Map,
     next_key: Option<String>,
     _capacity: usize,
+    ser: Json5ValueSerializer,
 }
 
 impl serde::ser::SerializeMap for Json5MapSerializer {
@@ -982,7 +1089,7 @@ impl serde::ser::SerializeMap for Json5MapSerializer {
 
     fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
         // Serialize key to Json5Value, then extract string
-        let key_value = key.serialize(Json5ValueSerializer)?;
+        let key_value = key.serialize(self.ser)?;
         let key_str = match key_value {
             Json5Value::String(s) => s,
             Json5Value::Number(n) => n,
@@ -1000,7 +1107,7 @@ impl serde::ser::SerializeMap for Json5MapSerializer {
         let key = self.next_key.take().ok_or_else(|| {
             Json5SerError("serialize_value called without serialize_key".to_string())
         })?;
-        self.map.insert(key, value.serialize(Json5ValueSerializer)?);
+        self.map.insert(key, value.serialize(self.ser)?);
         Ok(())
     }
 
@@ -1018,8 +1125,7 @@ impl serde::ser::SerializeStruct for Json5MapSerializer {
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.map
-            .insert(key.to_string(), value.serialize(Json5ValueSerializer)?);
+        self.map.insert(key.to_string(), value.serialize(self.ser)?);
         Ok(())
     }
 
@@ -1030,8 +1136,9 @@ impl serde::ser::SerializeStruct for Json5MapSerializer {
 
 struct Json5StructVariantSerializer {
     variant: String,
-    map: std::collections::BTreeMap<String, Json5Value>,
+    map: Json5Map,
     _capacity: usize,
+    ser: Json5ValueSerializer,
 }
 
 impl serde::ser::SerializeStructVariant for Json5StructVariantSerializer {
@@ -1043,13 +1150,12 @@ impl serde::ser::SerializeStructVariant for Json5StructVariantSerializer {
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.map
-            .insert(key.to_string(), value.serialize(Json5ValueSerializer)?);
+        self.map.insert(key.to_string(), value.serialize(self.ser)?);
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        let mut outer = std::collections::BTreeMap::new();
+        let mut outer = Json5Map::new();
         outer.insert(self.variant, Json5Value::Object(self.map));
         Ok(Json5Value::Object(outer))
     }
@@ -1076,9 +1182,10 @@ mod tests {
         assert_eq!(format_f32(0.0), "0");
         assert_eq!(format_f32(-0.0), "-0");
 
-        // Rounding to 6 significant digits
+        // Shortest representation that reads back as the same f32
         assert_eq!(format_f32(0.5), "0.5");
-        assert_eq!(format_f32(1.0 / 3.0), "0.333333");
+        assert_eq!(format_f32(1.0 / 3.0), "0.33333334");
+        assert_eq!(format_f32(1.0 / 3.0).parse::<f32>().unwrap(), 1.0 / 3.0);
 
         // Small values preserved
         assert_eq!(format_f32(1e-10).parse::<f32>().unwrap(), 1e-10_f32);
@@ -1132,6 +1239,57 @@ mod tests {
         assert_eq!(format_f64(f64::NEG_INFINITY), "-Infinity");
     }
 
+    #[test]
+    fn shortest_float_round_trips() {
+        let value = 0.1 + 0.2;
+        let formatted = format_f64(value);
+        assert_eq!(formatted, "0.30000000000000004");
+        assert_eq!(formatted.parse::<f64>().unwrap(), value);
+
+        // Without a precision set, nothing changes.
+        assert_eq!(format_f64_rounded(value, None), formatted);
+        assert_eq!(format_f32_rounded(1.0 / 3.0, None), "0.33333334");
+    }
+
+    #[test]
+    fn number_precision_only_applies_when_requested() {
+        #[derive(Serialize)]
+        struct Floats {
+            single: f32,
+            double: f64,
+            nested: Vec<f64>,
+        }
+
+        let value = Floats {
+            single: 1.23456,
+            double: 0.1 + 0.2,
+            nested: vec![2.71828],
+        };
+
+        let rounded =
+            String::from_utf8(to_vec_pretty_sorted_rounded(&value, Some(3)).unwrap()).unwrap();
+        assert!(rounded.contains("double: 0.3,"), "{rounded}");
+        assert!(rounded.contains("nested: [ 2.718 ]"), "{rounded}");
+        assert!(rounded.contains("single: 1.235"), "{rounded}");
+
+        let unrounded = String::from_utf8(to_vec_pretty_sorted(&value).unwrap()).unwrap();
+        assert!(
+            unrounded.contains("double: 0.30000000000000004,"),
+            "{unrounded}"
+        );
+        assert!(unrounded.contains("nested: [ 2.71828 ]"), "{unrounded}");
+        assert!(unrounded.contains("single: 1.23456"), "{unrounded}");
+    }
+
+    #[test]
+    fn round_to_decimals_trims_noise() {
+        assert_eq!(round_to_decimals(0.1 + 0.2, 3), 0.3);
+        assert_eq!(round_to_decimals(1.23456, 2), 1.23);
+        assert_eq!(round_to_decimals(-1.005, 0), -1.0);
+        assert_eq!(round_to_decimals(f64::MAX, 3), f64::MAX);
+        assert!(round_to_decimals(f64::NAN, 3).is_nan());
+    }
+
     #[test]
     fn test_parse_value() {
         let value = parse_value(r#"{"foo": "bar"}"#).unwrap();
@@ -2178,7 +2336,7 @@ mod tests {
         fn test_write_to_escapes_special_chars() {
             let value = Json5Value::String("hello\nworld\t\"test\"".to_string());
             let mut output = String::new();
-            value.write_to(&mut output, 0);
+            value.write_to(&mut output, 0, true);
             assert_eq!(output, r#""hello\nworld\t\"test\"""#);
         }
 
@@ -8,6 +8,7 @@ pub mod cli;
 mod tree_view;
 
 mod change_processor;
+pub mod disk_space;
 pub mod git;
 mod glob;
 pub mod hungarian;
@@ -18,6 +19,8 @@ mod message_queue;
 mod multimap;
 mod path_serializer;
 mod project;
+mod project_migration;
+mod project_schema;
 mod resolution;
 pub mod roblox_api;
 mod rojo_ref;
@@ -34,7 +37,7 @@ mod web;
 pub use git::{git_add, git_add_all_and_commit, git_init_repo, git_is_staged};
 pub use project::*;
 pub use rojo_ref::*;
-pub use serve_session::TreeFreshnessReport;
+pub use serve_session::{Divergence, DivergenceKind, InitialSyncMode, TreeFreshnessReport};
 pub use session_id::SessionId;
 pub use snapshot::{
     InstanceContext, InstanceMetadata, InstanceSnapshot, InstanceWithMeta, InstanceWithMetaMut,
@@ -1,6 +1,6 @@
 use std::{
     io::{self, IsTerminal, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use tracing_subscriber::{
@@ -16,13 +16,18 @@ pub struct LogGuard {
     _file_guard: Option<tracing_appender::non_blocking::WorkerGuard>,
 }
 
-/// Generates a session log filename like `atlas-serve.2026-03-01_14-32-05.log`.
-/// Uses UTC time, hyphens and underscores only (safe on all OSes).
-fn session_log_filename(command_name: &str) -> String {
+/// Generates a session log filename like `atlas-serve.2026-03-01_14-32-05.log`,
+/// or `atlas-serve.MyGame.2026-03-01_14-32-05.log` when the project name is
+/// known. Uses UTC time, hyphens and underscores only (safe on all OSes).
+fn session_log_filename(command_name: &str, project_name: Option<&str>) -> String {
     let now = time::OffsetDateTime::now_utc();
+    let prefix = match project_name {
+        Some(name) => format!("{command_name}.{}", sanitize_log_name(name)),
+        None => command_name.to_owned(),
+    };
     format!(
         "{}.{:04}-{:02}-{:02}_{:02}-{:02}-{:02}.log",
-        command_name,
+        prefix,
         now.year(),
         now.month() as u8,
         now.day(),
@@ -32,12 +37,32 @@ fn session_log_filename(command_name: &str) -> String {
     )
 }
 
-fn write_log_header(file: &mut std::fs::File, command_name: &str, project_dir: Option<&Path>) {
+/// Replaces everything but ASCII letters, digits, `-` and `_` in a project
+/// name so it can go in a file name.
+fn sanitize_log_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_log_header(
+    file: &mut std::fs::File,
+    command_name: &str,
+    project_dir: Option<&Path>,
+    project_name: Option<&str>,
+) {
     let now = time::OffsetDateTime::now_utc();
     let version = env!("CARGO_PKG_VERSION");
     let project = project_dir
         .map(|p| p.display().to_string())
         .unwrap_or_else(|| "none".into());
+    let project_name = project_name.unwrap_or("none");
     let cwd = std::env::current_dir()
         .map(|p| p.display().to_string())
         .unwrap_or_else(|_| "unknown".into());
@@ -47,6 +72,7 @@ fn write_log_header(file: &mut std::fs::File, command_name: &str, project_dir: O
         "=== Atlas v{version} ===\n\
          Command: {command_name}\n\
          Project: {project}\n\
+         Project name: {project_name}\n\
          Working dir: {cwd}\n\
          Started: {now}\n\
          ==="
@@ -57,8 +83,10 @@ pub fn init_logging(
     verbosity: u8,
     color: ColorChoice,
     project_dir: Option<&Path>,
+    project_name: Option<&str>,
     file_log_level: Option<tracing::level_filters::LevelFilter>,
     command_name: &str,
+    silenced_targets: &[&str],
 ) -> LogGuard {
     let console_filter = match verbosity {
         0 => "info",
@@ -67,8 +95,14 @@ pub fn init_logging(
         _ => "trace",
     };
 
-    let console_env_filter =
+    let mut console_env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(console_filter));
+    for target in silenced_targets {
+        match format!("{target}=off").parse() {
+            Ok(directive) => console_env_filter = console_env_filter.add_directive(directive),
+            Err(e) => eprintln!("Warning: could not silence log target {target}: {e}"),
+        }
+    }
 
     let use_ansi = match color {
         ColorChoice::Always => true,
@@ -98,7 +132,7 @@ pub fn init_logging(
                     compress_old_logs(&compress_dir);
                 });
 
-                let log_filename = session_log_filename(command_name);
+                let log_filename = session_log_filename(command_name, project_name);
                 let log_path = log_dir.join(&log_filename);
 
                 match std::fs::OpenOptions::new()
@@ -107,7 +141,7 @@ pub fn init_logging(
                     .open(&log_path)
                 {
                     Ok(mut file) => {
-                        write_log_header(&mut file, command_name, project_dir);
+                        write_log_header(&mut file, command_name, project_dir, project_name);
 
                         let (non_blocking, guard) = tracing_appender::non_blocking(file);
                         file_guard = Some(guard);
@@ -271,6 +305,40 @@ pub fn quick_read_file_log_level(
 ) -> Option<Option<tracing::level_filters::LevelFilter>> {
     use tracing::level_filters::LevelFilter;
 
+    let (_, val) = quick_read_project_value(path)?;
+    let level_str = val.get("fileLogLevel")?.as_str()?;
+
+    Some(match level_str.to_lowercase().as_str() {
+        "none" | "off" => None,
+        "error" => Some(LevelFilter::ERROR),
+        "warn" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => Some(LevelFilter::TRACE),
+    })
+}
+
+/// Lightweight pre-read of a project's resolved name: its `name` field, or
+/// the name of its folder for a default project without one. Accepts either a
+/// project file path or a directory (searches for default project).
+pub fn quick_read_project_name(path: &Path) -> Option<String> {
+    let (project_file, val) = quick_read_project_value(path)?;
+    if let Some(name) = val.get("name").and_then(|name| name.as_str()) {
+        return Some(name.to_owned());
+    }
+
+    let file_name = project_file.file_name()?.to_str()?;
+    if file_name.starts_with("default.project.") {
+        let folder = project_file.parent()?.file_name()?.to_str()?;
+        Some(folder.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Finds the project file for `path` and parses it without validating it.
+fn quick_read_project_value(path: &Path) -> Option<(PathBuf, serde_json::Value)> {
     let project_file = if path.is_file() {
         path.to_path_buf()
     } else {
@@ -288,18 +356,8 @@ pub fn quick_read_file_log_level(
     };
 
     let contents = std::fs::read_to_string(&project_file).ok()?;
-    let val: serde_json::Value = json5::from_str(&contents).ok()?;
-    let level_str = val.get("fileLogLevel")?.as_str()?;
-
-    Some(match level_str.to_lowercase().as_str() {
-        "none" | "off" => None,
-        "error" => Some(LevelFilter::ERROR),
-        "warn" => Some(LevelFilter::WARN),
-        "info" => Some(LevelFilter::INFO),
-        "debug" => Some(LevelFilter::DEBUG),
-        "trace" => Some(LevelFilter::TRACE),
-        _ => Some(LevelFilter::TRACE),
-    })
+    let val = json5::from_str(&contents).ok()?;
+    Some((project_file, val))
 }
 
 #[cfg(test)]
@@ -580,7 +638,7 @@ mod tests {
 
     #[test]
     fn session_log_filename_format() {
-        let name = session_log_filename("atlas-serve");
+        let name = session_log_filename("atlas-serve", None);
         assert!(
             name.starts_with("atlas-serve."),
             "should start with command prefix"
@@ -602,4 +660,42 @@ mod tests {
         assert_eq!(&middle[13..14], "-");
         assert_eq!(&middle[16..17], "-");
     }
+
+    #[test]
+    fn session_log_filename_includes_project_name() {
+        let name = session_log_filename("atlas-serve", Some("My Game/v2"));
+        assert!(
+            name.starts_with("atlas-serve.My_Game_v2."),
+            "project name should follow the command, sanitized: {name}"
+        );
+        assert!(name.ends_with(".log"));
+    }
+
+    #[test]
+    fn quick_read_project_name_resolves_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let named = dir.path().join("named");
+        std::fs::create_dir(&named).unwrap();
+        std::fs::write(
+            named.join("default.project.json5"),
+            r#"{ "name": "Explicit", "tree": { "$className": "Folder" } }"#,
+        )
+        .unwrap();
+        assert_eq!(quick_read_project_name(&named).as_deref(), Some("Explicit"));
+
+        // Without a `name`, a default project is named after its folder.
+        let unnamed = dir.path().join("FolderName");
+        std::fs::create_dir(&unnamed).unwrap();
+        std::fs::write(
+            unnamed.join("default.project.json5"),
+            r#"{ "tree": { "$className": "Folder" } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            quick_read_project_name(&unnamed).as_deref(),
+            Some("FolderName")
+        );
+
+        assert_eq!(quick_read_project_name(dir.path()), None);
+    }
 }
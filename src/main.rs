@@ -65,13 +65,18 @@ fn main() {
     };
 
     let command_name = format!("atlas-{}", options.subcommand.command_name());
+    let project_name = project_dir
+        .as_deref()
+        .and_then(logging::quick_read_project_name);
 
     let _log_guard = logging::init_logging(
         options.global.verbosity,
         options.global.color,
         project_dir.as_deref(),
+        project_name.as_deref(),
         file_log_level,
         &command_name,
+        options.subcommand.silenced_log_targets(),
     );
 
     if let Err(err) = options.run() {
@@ -1,9 +1,11 @@
 use std::{
-    collections::{BTreeMap, HashSet},
+    borrow::Cow,
+    collections::{btree_map, BTreeMap, HashSet},
+    env,
     ffi::OsStr,
     fs, io,
     net::IpAddr,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 use memofs::Vfs;
@@ -16,6 +18,7 @@ use crate::{
     json,
     resolution::UnresolvedValue,
     snapshot::{PathIgnoreRule, SyncRule},
+    snapshot_middleware::Middleware,
     syncback::SyncbackRules,
 };
 
@@ -57,6 +60,27 @@ enum Error {
         source: anyhow::Error,
         path: PathBuf,
     },
+
+    #[error("Could not read the project {} extended by {}", .base.display(), .path.display())]
+    ExtendsUnreadable {
+        #[source]
+        source: io::Error,
+        path: PathBuf,
+        base: PathBuf,
+    },
+
+    #[error("Projects can't extend themselves, but these extend each other in a cycle: {chain}")]
+    CyclicExtends { chain: String },
+
+    #[error(
+        "The project {} uses the environment variable {name}, but it isn't set.\n\
+         Set it, or give it a default like ${{{name}:-default}}.",
+        .path.display()
+    )]
+    MissingEnvVar { name: String, path: PathBuf },
+
+    #[error("The project {} has a `${{` with no closing `}}` in \"{text}\"", .path.display())]
+    UnclosedEnvVar { text: String, path: PathBuf },
 }
 
 /// Contains all of the configuration for a Rojo-managed project.
@@ -71,6 +95,12 @@ pub struct Project {
     /// The name of the top-level instance described by the project.
     pub name: Option<String>,
 
+    /// A project file, relative to this one's folder, whose settings this
+    /// project inherits. See [`Project::inherit_from`] for how the two are
+    /// merged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<PathBuf>,
+
     /// The tree of instances described by this project. Projects always
     /// describe at least one instance.
     pub tree: ProjectNode,
@@ -157,6 +187,17 @@ pub struct Project {
     /// given by `Project::folder_location`.
     #[serde(skip)]
     pub file_location: PathBuf,
+
+    /// Every project file merged into this one through `extends`, nearest
+    /// first. Changes to these files affect the project too.
+    #[serde(skip)]
+    pub extended_files: Vec<PathBuf>,
+
+    /// Whether any string in the project file had `${VAR}` environment
+    /// variables expanded when it was loaded. Writing such a project back out
+    /// would replace the variables with their values.
+    #[serde(skip)]
+    pub has_env_vars: bool,
 }
 
 impl Project {
@@ -172,7 +213,7 @@ impl Project {
     ///
     /// This will find a project if the path refers to a `.project.json5` file,
     /// or is a folder that contains a `default.project.json5` file.
-    fn locate(path: &Path) -> Option<PathBuf> {
+    pub(crate) fn locate(path: &Path) -> Option<PathBuf> {
         let meta = fs::metadata(path).ok()?;
 
         if meta.is_file() {
@@ -184,9 +225,11 @@ impl Project {
         } else {
             for filename in DEFAULT_PROJECT_NAMES {
                 let child_path = path.join(filename);
-                let child_meta = fs::metadata(&child_path).ok()?;
+                let is_file = fs::metadata(&child_path)
+                    .map(|meta| meta.is_file())
+                    .unwrap_or(false);
 
-                if child_meta.is_file() {
+                if is_file {
                     return Some(child_path);
                 }
             }
@@ -247,11 +290,11 @@ impl Project {
 
     /// Loads a Project file from the provided contents with its source set as
     /// the provided location.
-    fn load_from_slice(
+    pub(crate) fn load_from_slice(
         contents: &[u8],
         project_file_location: PathBuf,
         fallback_name: Option<&str>,
-    ) -> Result<Self, Error> {
+    ) -> Result<Self, ProjectError> {
         let mut project: Self = json::from_slice(contents).map_err(|e| Error::Json {
             source: e,
             path: project_file_location.clone(),
@@ -280,7 +323,12 @@ impl Project {
                 _ => e.into(),
             })?;
 
-            Ok(Some(Self::load_from_slice(&contents, project_path, None)?))
+            let expanded = expand_env_vars(&contents, &project_path, &|name| env::var(name).ok())?;
+            let has_env_vars = matches!(expanded, Cow::Owned(_));
+            let mut project = Self::load_from_slice(&expanded, project_path, None)?;
+            project.has_env_vars = has_env_vars;
+            project.resolve_extends(vfs)?;
+            Ok(Some(project))
         } else {
             Ok(None)
         }
@@ -304,11 +352,120 @@ impl Project {
             _ => e.into(),
         })?;
 
-        Ok(Self::load_from_slice(
-            &contents,
-            project_path,
-            fallback_name,
-        )?)
+        let expanded = expand_env_vars(&contents, &project_path, &|name| env::var(name).ok())?;
+        let has_env_vars = matches!(expanded, Cow::Owned(_));
+        let mut project = Self::load_from_slice(&expanded, project_path, fallback_name)?;
+        project.has_env_vars = has_env_vars;
+        project.resolve_extends(vfs)?;
+        Ok(project)
+    }
+
+    /// Merges the project this one `extends`, and any project that one
+    /// extends in turn, into this project.
+    fn resolve_extends(&mut self, vfs: &Vfs) -> Result<(), ProjectError> {
+        let mut chain = vec![normalize_path(&self.file_location)];
+        self.resolve_extends_inner(vfs, &mut chain)?;
+        if !self.extended_files.is_empty() {
+            self.check_compatibility();
+        }
+        Ok(())
+    }
+
+    fn resolve_extends_inner(&mut self, vfs: &Vfs, chain: &mut Vec<PathBuf>) -> Result<(), Error> {
+        let Some(extends) = &self.extends else {
+            return Ok(());
+        };
+        let base_path = normalize_path(&self.folder_location().join(extends));
+
+        if chain.contains(&base_path) {
+            chain.push(base_path);
+            let chain = chain
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(Error::CyclicExtends { chain });
+        }
+
+        log::debug!("Loading extended project file from {}", base_path.display());
+        let contents = vfs
+            .read(&base_path)
+            .map_err(|source| Error::ExtendsUnreadable {
+                source,
+                path: self.file_location.clone(),
+                base: base_path.clone(),
+            })?;
+        let contents = expand_env_vars(&contents, &base_path, &|name| env::var(name).ok())?;
+        let mut base: Project = json::from_slice(&contents).map_err(|e| Error::Json {
+            source: e,
+            path: base_path.clone(),
+        })?;
+        base.file_location = base_path.clone();
+
+        chain.push(base_path.clone());
+        base.resolve_extends_inner(vfs, chain)?;
+        chain.pop();
+
+        self.extended_files.push(base_path);
+        self.extended_files.append(&mut base.extended_files);
+        self.inherit_from(base);
+        Ok(())
+    }
+
+    /// Fills in everything this project doesn't set from `base`, a project
+    /// it extends. The rules are:
+    ///
+    /// - Settings this project sets win over the base's.
+    /// - `tree` nodes merge by name; see [`ProjectNode::inherit_from`].
+    /// - `globIgnorePaths` from both projects apply.
+    /// - `syncRules` from both projects apply, with this project's checked
+    ///   first so that they can override the base's.
+    /// - `name` and `$schema` are never inherited.
+    ///
+    /// Everything inherited behaves as if it were written in this project,
+    /// so relative paths in it, like `$path`s and globs, are relative to this
+    /// project's folder rather than the base's.
+    fn inherit_from(&mut self, base: Project) {
+        // Destructured so that a new field can't be added without deciding
+        // how it's inherited.
+        let Project {
+            schema: _,
+            name: _,
+            extends: _,
+            tree,
+            serve_port,
+            serve_place_ids,
+            blocked_place_ids,
+            place_id,
+            game_id,
+            serve_address,
+            mut glob_ignore_paths,
+            syncback_rules,
+            sync_rules,
+            sync_scripts_only,
+            ignore_hidden_services,
+            file_log_level,
+            file_location: _,
+            extended_files: _,
+            has_env_vars,
+        } = base;
+
+        self.has_env_vars |= has_env_vars;
+
+        self.tree.inherit_from(tree);
+        self.serve_port = self.serve_port.or(serve_port);
+        self.serve_place_ids = self.serve_place_ids.take().or(serve_place_ids);
+        self.blocked_place_ids = self.blocked_place_ids.take().or(blocked_place_ids);
+        self.place_id = self.place_id.or(place_id);
+        self.game_id = self.game_id.or(game_id);
+        self.serve_address = self.serve_address.or(serve_address);
+        glob_ignore_paths.append(&mut self.glob_ignore_paths);
+        self.glob_ignore_paths = glob_ignore_paths;
+        self.syncback_rules = self.syncback_rules.take().or(syncback_rules);
+        self.sync_rules.extend(sync_rules);
+        self.sync_scripts_only = self.sync_scripts_only.or(sync_scripts_only);
+        self.ignore_hidden_services = self.ignore_hidden_services.or(ignore_hidden_services);
+        self.file_log_level = self.file_log_level.take().or(file_log_level);
     }
 
     pub(crate) fn load_initial_project(vfs: &Vfs, path: &Path) -> Result<Self, ProjectError> {
@@ -339,6 +496,66 @@ impl Project {
         self.file_location.parent().unwrap()
     }
 
+    /// Returns how many decimal places floats written to model and meta files
+    /// are rounded to, from the `numberPrecision` syncback rule. `None`
+    /// leaves floats unrounded.
+    pub fn number_precision(&self) -> Option<u8> {
+        self.syncback_rules
+            .as_ref()
+            .and_then(SyncbackRules::number_precision)
+    }
+
+    /// Returns every `$path` in the project tree resolved against
+    /// [`folder_location`](Self::folder_location), in tree order.
+    pub fn resolved_path_roots(&self) -> Vec<PathBuf> {
+        let mut roots = Vec::new();
+        self.tree
+            .collect_path_roots(self.folder_location(), &mut roots);
+        roots
+    }
+
+    /// Returns whether the root of the project tree is backed by a directory
+    /// middleware (e.g. `"$path": "src"` pointing at a folder). Projects whose
+    /// root has no `$path` are never considered directories.
+    pub fn root_is_directory(&self, vfs: &Vfs) -> bool {
+        let Some(path_node) = &self.tree.path else {
+            return false;
+        };
+        let resolved = self.folder_location().join(path_node.path());
+
+        match Middleware::middleware_for_path(vfs, &self.sync_rules, &resolved) {
+            Ok(Some(middleware)) => middleware.is_dir(),
+            Ok(None) => false,
+            Err(err) => {
+                log::debug!(
+                    "Could not determine middleware for project root {}: {err:?}",
+                    resolved.display()
+                );
+                false
+            }
+        }
+    }
+
+    /// Converts a filesystem path into the instance path it syncs to, using
+    /// the `$path` entries of the project tree. For example, if
+    /// `ReplicatedStorage` has `"$path": "src"`, then `src/Foo` maps to
+    /// `ReplicatedStorage/Foo`.
+    ///
+    /// Returns `None` if the path isn't inside any non-root `$path`.
+    pub fn instance_path_for_fs_path(&self, fs_path: &Path) -> Option<String> {
+        instance_path_for_fs_path(&self.path_instance_prefixes(), fs_path)
+    }
+
+    /// Returns `(resolved $path, instance path)` pairs for every non-root
+    /// node with a `$path`, in tree order. Used with
+    /// [`instance_path_for_fs_path`] when many lookups are needed.
+    pub(crate) fn path_instance_prefixes(&self) -> Vec<(PathBuf, String)> {
+        let mut prefixes = Vec::new();
+        self.tree
+            .collect_instance_prefixes(self.folder_location(), "", &mut prefixes);
+        prefixes
+    }
+
     pub fn path_ignore_rules(&self) -> Vec<PathIgnoreRule> {
         let base = self.folder_location().to_path_buf();
         self.glob_ignore_paths
@@ -351,6 +568,150 @@ impl Project {
     }
 }
 
+/// Expands environment variables in every string value of the project file
+/// `contents`, read from `path`. `${VAR}` is replaced with the value `lookup`
+/// gives for `VAR`, and is an error if it has none. `${VAR:-default}` falls
+/// back to `default` instead, and `$${` is a literal `${`.
+///
+/// Files without a `${` in any string value, including ones that only have it
+/// in comments, are returned as they are. Otherwise, they're returned as
+/// plain JSON, without their comments.
+fn expand_env_vars<'a>(
+    contents: &'a [u8],
+    path: &Path,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<Cow<'a, [u8]>, Error> {
+    if !contents.windows(2).any(|window| window == b"${") {
+        return Ok(Cow::Borrowed(contents));
+    }
+
+    let json_error = |source| Error::Json {
+        source,
+        path: path.to_path_buf(),
+    };
+    let mut value: serde_json::Value = json::from_slice(contents).map_err(json_error)?;
+    if !expand_env_vars_in_value(&mut value, path, lookup)? {
+        return Ok(Cow::Borrowed(contents));
+    }
+    let expanded = serde_json::to_vec(&value).map_err(|e| json_error(e.into()))?;
+    Ok(Cow::Owned(expanded))
+}
+
+/// Expands environment variables in every string inside `value`, returning
+/// whether any string had a `${` in it.
+fn expand_env_vars_in_value(
+    value: &mut serde_json::Value,
+    path: &Path,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<bool, Error> {
+    let mut expanded = false;
+    match value {
+        serde_json::Value::String(text) => {
+            if text.contains("${") {
+                *text = expand_env_vars_in_str(text, path, lookup)?;
+                expanded = true;
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                expanded |= expand_env_vars_in_value(value, path, lookup)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                expanded |= expand_env_vars_in_value(value, path, lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(expanded)
+}
+
+fn expand_env_vars_in_str(
+    text: &str,
+    path: &Path,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, Error> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            // `$${` is an escaped, literal `${`.
+            expanded.push_str(&rest[..start - 1]);
+            expanded.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| Error::UnclosedEnvVar {
+            text: text.to_owned(),
+            path: path.to_path_buf(),
+        })?;
+        let token = &after[..end];
+
+        let (name, default) = match token.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (token, None),
+        };
+        match (lookup(name), default) {
+            (Some(value), _) => expanded.push_str(&value),
+            (None, Some(default)) => expanded.push_str(default),
+            (None, None) => {
+                return Err(Error::MissingEnvVar {
+                    name: name.to_owned(),
+                    path: path.to_path_buf(),
+                })
+            }
+        }
+
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Resolves `.` and `..` components in `path` without touching the file
+/// system, so the same project file is recognized however it's referred to.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Converts `fs_path` into an instance path using prefixes produced by
+/// [`Project::path_instance_prefixes`]. The first matching prefix wins.
+pub(crate) fn instance_path_for_fs_path(
+    prefixes: &[(PathBuf, String)],
+    fs_path: &Path,
+) -> Option<String> {
+    for (fs_prefix, inst_prefix) in prefixes {
+        if let Ok(relative) = fs_path.strip_prefix(fs_prefix) {
+            let relative_str = relative
+                .components()
+                .filter_map(|c| c.as_os_str().to_str())
+                .collect::<Vec<_>>()
+                .join("/");
+            if relative_str.is_empty() {
+                return Some(inst_prefix.clone());
+            } else {
+                return Some(format!("{}/{}", inst_prefix, relative_str));
+            }
+        }
+    }
+    None
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct OptionalPathNode {
     #[serde(serialize_with = "crate::path_serializer::serialize_absolute")]
@@ -387,6 +748,30 @@ impl PathNode {
     pub fn is_optional(&self) -> bool {
         matches!(self, PathNode::Optional(_))
     }
+
+    /// Returns whether the path is a glob like `src/*.luau`, which turns
+    /// every file it matches into a child of the node instead of naming a
+    /// single file. Paths are globs if they contain `*` or `?`.
+    pub fn is_glob(&self) -> bool {
+        self.path()
+            .to_str()
+            .is_some_and(|path| path.contains(['*', '?']))
+    }
+
+    /// Returns the path up to the first component with a wildcard in it. For
+    /// a glob, that's the folder everything it matches is inside. Paths that
+    /// aren't globs are returned whole.
+    pub fn literal_path(&self) -> PathBuf {
+        self.path()
+            .components()
+            .take_while(|component| {
+                !component
+                    .as_os_str()
+                    .to_str()
+                    .is_some_and(|component| component.contains(['*', '?']))
+            })
+            .collect()
+    }
 }
 
 /// Describes an instance and its descendants in a project.
@@ -427,6 +812,11 @@ pub struct ProjectNode {
     )]
     pub attributes: BTreeMap<String, UnresolvedValue>,
 
+    /// The CollectionService tags that will be given to the resulting
+    /// instance, as a shorthand for setting its `Tags` property.
+    #[serde(rename = "$tags", default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     /// Defines the behavior when Rojo encounters unknown instances in Roblox
     /// Studio during live sync. `$ignoreUnknownInstances` should be considered
     /// a large hammer and used with care.
@@ -453,9 +843,95 @@ pub struct ProjectNode {
     /// spreadsheets (`.csv`).
     #[serde(rename = "$path", skip_serializing_if = "Option::is_none")]
     pub path: Option<PathNode>,
+
+    /// If set, forces the middleware used to read and write `$path` instead
+    /// of picking one from the file extension or the instance's class. Useful
+    /// for pinning a node to a specific representation, like always storing
+    /// a model as `.rbxmx`.
+    ///
+    /// The middleware must be able to represent the node's ClassName.
+    #[serde(rename = "$middleware", skip_serializing_if = "Option::is_none")]
+    pub middleware: Option<Middleware>,
 }
 
 impl ProjectNode {
+    /// Fills in everything this node doesn't set from `base`, the node at the
+    /// same place in a project this one extends. `$properties` and
+    /// `$attributes` merge by key and children merge by name, recursively.
+    /// Whatever this node sets wins.
+    fn inherit_from(&mut self, base: ProjectNode) {
+        let ProjectNode {
+            class_name,
+            id,
+            children,
+            properties,
+            attributes,
+            tags,
+            ignore_unknown_instances,
+            path,
+            middleware,
+        } = base;
+
+        self.class_name = self.class_name.or(class_name);
+        self.id = self.id.take().or(id);
+        for (key, value) in properties {
+            self.properties.entry(key).or_insert(value);
+        }
+        for (key, value) in attributes {
+            self.attributes.entry(key).or_insert(value);
+        }
+        if self.tags.is_empty() {
+            self.tags = tags;
+        }
+        self.ignore_unknown_instances = self.ignore_unknown_instances.or(ignore_unknown_instances);
+        self.path = self.path.take().or(path);
+        self.middleware = self.middleware.or(middleware);
+
+        for (name, base_child) in children {
+            match self.children.entry(name) {
+                btree_map::Entry::Occupied(mut entry) => entry.get_mut().inherit_from(base_child),
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(base_child);
+                }
+            }
+        }
+    }
+
+    /// Recursively collects the resolved `$path` of this node and all of its
+    /// descendants.
+    fn collect_path_roots(&self, base: &Path, out: &mut Vec<PathBuf>) {
+        if let Some(path_node) = &self.path {
+            out.push(base.join(path_node.literal_path()));
+        }
+        for child in self.children.values() {
+            child.collect_path_roots(base, out);
+        }
+    }
+
+    fn collect_instance_prefixes(
+        &self,
+        base: &Path,
+        instance_path: &str,
+        out: &mut Vec<(PathBuf, String)>,
+    ) {
+        if let Some(path_node) = &self.path {
+            if !instance_path.is_empty() {
+                out.push((
+                    base.join(path_node.literal_path()),
+                    instance_path.to_string(),
+                ));
+            }
+        }
+        for (name, child) in &self.children {
+            let child_instance_path = if instance_path.is_empty() {
+                name.clone()
+            } else {
+                format!("{instance_path}/{name}")
+            };
+            child.collect_instance_prefixes(base, &child_instance_path, out);
+        }
+    }
+
     fn validate_reserved_names(&self) {
         for (name, child) in &self.children {
             if name.starts_with('$') {
@@ -582,6 +1058,90 @@ mod test {
         assert_eq!(serialized, "{\n  $path: \"../src\",\n}");
     }
 
+    fn accessor_fixture() -> Project {
+        Project::load_from_slice(
+            br#"{
+                "name": "Accessors",
+                "tree": {
+                    "$path": "src",
+                    "ReplicatedStorage": {
+                        "$path": "shared",
+                        "Vendor": {
+                            "$path": "vendor/packages"
+                        }
+                    },
+                    "Workspace": {
+                        "$className": "Workspace"
+                    }
+                }
+            }"#,
+            PathBuf::from("/test/default.project.json5"),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolved_path_roots_in_tree_order() {
+        let project = accessor_fixture();
+
+        assert_eq!(
+            project.resolved_path_roots(),
+            vec![
+                PathBuf::from("/test/src"),
+                PathBuf::from("/test/shared"),
+                PathBuf::from("/test/vendor/packages"),
+            ]
+        );
+    }
+
+    #[test]
+    fn root_is_directory_detects_dir_and_file_roots() {
+        use memofs::{InMemoryFs, VfsSnapshot};
+
+        let project = accessor_fixture();
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/test/src", VfsSnapshot::empty_dir())
+            .unwrap();
+        assert!(project.root_is_directory(&Vfs::new(imfs)));
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/test/src", VfsSnapshot::file("return nil"))
+            .unwrap();
+        assert!(!project.root_is_directory(&Vfs::new(imfs)));
+
+        let missing = Vfs::new(InMemoryFs::new());
+        assert!(!project.root_is_directory(&missing));
+    }
+
+    #[test]
+    fn instance_path_for_fs_path_maps_through_path_nodes() {
+        let project = accessor_fixture();
+
+        assert_eq!(
+            project.instance_path_for_fs_path(Path::new("/test/shared")),
+            Some("ReplicatedStorage".to_string())
+        );
+        assert_eq!(
+            project.instance_path_for_fs_path(Path::new("/test/shared/Util/init.luau")),
+            Some("ReplicatedStorage/Util/init.luau".to_string())
+        );
+        assert_eq!(
+            project.instance_path_for_fs_path(Path::new("/test/vendor/packages/Roact")),
+            Some("ReplicatedStorage/Vendor/Roact".to_string())
+        );
+        // The root `$path` has no instance path prefix.
+        assert_eq!(
+            project.instance_path_for_fs_path(Path::new("/test/src/Foo.luau")),
+            None
+        );
+        assert_eq!(
+            project.instance_path_for_fs_path(Path::new("/elsewhere/Foo.luau")),
+            None
+        );
+    }
+
     #[test]
     fn project_with_jsonc_features() {
         // Test that JSONC features (comments and trailing commas) are properly handled
@@ -632,4 +1192,206 @@ mod test {
         assert!(project.sync_rules[0].include.is_match("data.data.json5"));
         assert!(project.sync_rules[1].include.is_match("init.module.luau"));
     }
+
+    fn load_with_files(files: &[(&str, &str)], path: &str) -> Result<Project, ProjectError> {
+        let mut imfs = memofs::InMemoryFs::new();
+        for (file_path, contents) in files {
+            imfs.load_snapshot(file_path, memofs::VfsSnapshot::file(*contents))
+                .unwrap();
+        }
+        Project::load_exact(&Vfs::new(imfs), Path::new(path), None)
+    }
+
+    #[test]
+    fn extends_child_overrides_base() {
+        let project = load_with_files(
+            &[
+                (
+                    "/base.project.json5",
+                    r#"{
+                        "name": "Base",
+                        "servePort": 1000,
+                        "placeId": 42,
+                        "tree": {
+                            "$className": "DataModel",
+                            "Lighting": {
+                                "$properties": { "Ambient": "base", "Brightness": "base" }
+                            }
+                        }
+                    }"#,
+                ),
+                (
+                    "/game/default.project.json5",
+                    r#"{
+                        "name": "Game",
+                        "extends": "../base.project.json5",
+                        "servePort": 2000,
+                        "tree": {
+                            "Lighting": {
+                                "$properties": { "Ambient": "child" }
+                            }
+                        }
+                    }"#,
+                ),
+            ],
+            "/game/default.project.json5",
+        )
+        .unwrap();
+
+        assert_eq!(project.name.as_deref(), Some("Game"));
+        assert_eq!(project.serve_port, Some(2000));
+        assert_eq!(project.place_id, Some(42));
+        assert_eq!(project.tree.class_name.as_deref(), Some("DataModel"));
+        assert_eq!(
+            project.extended_files,
+            vec![PathBuf::from("/base.project.json5")]
+        );
+
+        let properties = &project.tree.children["Lighting"].properties;
+        let value = |name: &str| serde_json::to_value(&properties[&Ustr::from(name)]).unwrap();
+        assert_eq!(value("Ambient"), serde_json::json!("child"));
+        assert_eq!(value("Brightness"), serde_json::json!("base"));
+    }
+
+    #[test]
+    fn extends_merges_tree_children_by_name() {
+        let project = load_with_files(
+            &[
+                (
+                    "/base.project.json5",
+                    r#"{
+                        "tree": {
+                            "$className": "DataModel",
+                            "ReplicatedStorage": {
+                                "Shared": { "$path": "src/shared" }
+                            }
+                        }
+                    }"#,
+                ),
+                (
+                    "/default.project.json5",
+                    r#"{
+                        "name": "Game",
+                        "extends": "base.project.json5",
+                        "tree": {
+                            "ReplicatedStorage": {
+                                "Client": { "$path": "src/client" }
+                            }
+                        }
+                    }"#,
+                ),
+            ],
+            "/default.project.json5",
+        )
+        .unwrap();
+
+        let storage = &project.tree.children["ReplicatedStorage"];
+        assert_eq!(
+            storage.children.keys().collect::<Vec<_>>(),
+            vec!["Client", "Shared"]
+        );
+        assert_eq!(
+            storage.children["Shared"].path,
+            Some(PathNode::Required(PathBuf::from("src/shared")))
+        );
+    }
+
+    #[test]
+    fn extends_rejects_cycles() {
+        let err = load_with_files(
+            &[
+                (
+                    "/a.project.json5",
+                    r#"{ "extends": "b.project.json5", "tree": { "$className": "Folder" } }"#,
+                ),
+                (
+                    "/b.project.json5",
+                    r#"{ "extends": "a.project.json5", "tree": { "$className": "Folder" } }"#,
+                ),
+            ],
+            "/a.project.json5",
+        )
+        .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("cycle"), "{message}");
+        assert!(message.contains("b.project.json5"), "{message}");
+    }
+
+    fn expand(text: &str) -> Result<String, Error> {
+        let lookup = |name: &str| (name == "ASSET_ID").then(|| "12345".to_owned());
+        expand_env_vars_in_str(text, Path::new("/default.project.json5"), &lookup)
+    }
+
+    #[test]
+    fn env_var_is_expanded() {
+        assert_eq!(
+            expand("rbxassetid://${ASSET_ID}").unwrap(),
+            "rbxassetid://12345"
+        );
+        assert_eq!(expand("no variables here").unwrap(), "no variables here");
+    }
+
+    #[test]
+    fn missing_env_var_is_an_error() {
+        let err = expand("${CHANNEL}").unwrap_err();
+        assert!(
+            matches!(&err, Error::MissingEnvVar { name, .. } if name == "CHANNEL"),
+            "{err}"
+        );
+        assert!(err.to_string().contains("${CHANNEL:-default}"), "{err}");
+
+        assert!(matches!(
+            expand("${ASSET_ID"),
+            Err(Error::UnclosedEnvVar { .. })
+        ));
+    }
+
+    #[test]
+    fn env_var_default_is_used_when_unset() {
+        assert_eq!(expand("${CHANNEL:-beta}").unwrap(), "beta");
+        assert_eq!(expand("${ASSET_ID:-0}").unwrap(), "12345");
+        assert_eq!(expand("${CHANNEL:-}").unwrap(), "");
+    }
+
+    #[test]
+    fn escaped_env_var_is_left_alone() {
+        assert_eq!(expand("$${ASSET_ID}").unwrap(), "${ASSET_ID}");
+        assert_eq!(expand("$${CHANNEL").unwrap(), "${CHANNEL");
+        assert_eq!(
+            expand("$${ASSET_ID} is ${ASSET_ID}").unwrap(),
+            "${ASSET_ID} is 12345"
+        );
+    }
+
+    #[test]
+    fn env_vars_are_expanded_when_loading() {
+        let path = Path::new("/default.project.json5");
+        let contents = br#"{
+            // Comments are fine alongside variables.
+            "name": "Game-${CHANNEL}",
+            "tree": { "$className": "DataModel" },
+        }"#;
+        let lookup = |name: &str| (name == "CHANNEL").then(|| "nightly".to_owned());
+
+        let expanded = expand_env_vars(contents, path, &lookup).unwrap();
+        assert!(matches!(expanded, Cow::Owned(_)));
+
+        let project = Project::load_from_slice(&expanded, path.to_path_buf(), None).unwrap();
+        assert_eq!(project.name.as_deref(), Some("Game-nightly"));
+    }
+
+    #[test]
+    fn env_vars_in_comments_are_ignored() {
+        let contents = br#"{
+            // Set the name with ${CHANNEL} once it's supported.
+            "name": "Game",
+            "tree": { "$className": "DataModel" },
+        }"#;
+        let lookup = |_: &str| None;
+
+        let expanded =
+            expand_env_vars(contents, Path::new("/default.project.json5"), &lookup).unwrap();
+        assert!(matches!(expanded, Cow::Borrowed(_)));
+    }
 }
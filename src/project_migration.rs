@@ -0,0 +1,192 @@
+//! Upgrades project files written for older versions of the project schema.
+//!
+//! `Project` denies unknown fields, so a project using a shape we've since
+//! dropped fails to load outright. The migrations here work on the raw JSON
+//! value instead, before it's ever parsed as a `Project`.
+
+use serde_json::{Map, Value};
+
+/// Applies every known migration to `project` in place, returning a
+/// human-readable description of each change that was made.
+pub fn migrate_project_value(project: &mut Value) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    let Some(root) = project.as_object_mut() else {
+        return changes;
+    };
+
+    migrate_partitions(root, &mut changes);
+    migrate_emit_legacy_scripts(root, &mut changes);
+    migrate_syncback_hidden_services(root, &mut changes);
+
+    if !root.contains_key("tree") {
+        let mut tree = Map::new();
+        tree.insert("$className".to_owned(), "DataModel".into());
+        root.insert("tree".to_owned(), Value::Object(tree));
+        changes.push("Added an empty DataModel `tree`, which is required".to_owned());
+    }
+
+    changes
+}
+
+/// Projects from Rojo 0.4 described their contents as a flat map of
+/// `partitions`, each with a `path` and a dotted `target` like
+/// `ReplicatedStorage.Shared`. These become nested `tree` nodes.
+fn migrate_partitions(root: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    let Some(partitions) = root.remove("partitions") else {
+        return;
+    };
+
+    if root.contains_key("tree") {
+        changes.push("Removed `partitions`, which `tree` replaces".to_owned());
+        return;
+    }
+
+    let mut tree = Map::new();
+    tree.insert("$className".to_owned(), "DataModel".into());
+
+    if let Value::Object(partitions) = partitions {
+        for (name, partition) in partitions {
+            let path = partition.get("path").cloned();
+            let target = partition
+                .get("target")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+
+            let mut node = &mut tree;
+            for segment in target.split('.').filter(|segment| !segment.is_empty()) {
+                node = node
+                    .entry(segment.to_owned())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .expect("partition target nodes are always objects");
+            }
+            if let Some(path) = path {
+                node.insert("$path".to_owned(), path);
+            }
+
+            changes.push(format!(
+                "Moved partition '{name}' to `tree` at '{}'",
+                if target.is_empty() { "<root>" } else { target }
+            ));
+        }
+    }
+
+    root.insert("tree".to_owned(), Value::Object(tree));
+}
+
+/// `emitLegacyScripts` chose whether `.server.lua` files became legacy
+/// `Script`s or `Script`s with a `RunContext`. Script classes now come from
+/// the file extension alone.
+fn migrate_emit_legacy_scripts(root: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    if root.remove("emitLegacyScripts").is_some() {
+        changes.push(
+            "Removed `emitLegacyScripts`; script classes now come from file extensions \
+             (e.g. `.server.luau`, `.legacy.luau`)"
+                .to_owned(),
+        );
+    }
+}
+
+/// `ignoreHiddenServices` used to live in `syncbackRules`. It now applies to
+/// live sync as well, so it belongs at the root of the project.
+fn migrate_syncback_hidden_services(root: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    let Some(Value::Object(rules)) = root.get_mut("syncbackRules") else {
+        return;
+    };
+    let Some(value) = rules.remove("ignoreHiddenServices") else {
+        return;
+    };
+
+    if root.contains_key("ignoreHiddenServices") {
+        changes.push(
+            "Removed `syncbackRules.ignoreHiddenServices`, which the root-level setting overrides"
+                .to_owned(),
+        );
+    } else {
+        root.insert("ignoreHiddenServices".to_owned(), value);
+        changes.push(
+            "Moved `syncbackRules.ignoreHiddenServices` to the root of the project".to_owned(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::path::{Path, PathBuf};
+
+    use crate::project::Project;
+
+    fn migrate(source: &str) -> (Project, Vec<String>) {
+        let mut value: Value = serde_json::from_str(source).unwrap();
+        let changes = migrate_project_value(&mut value);
+        let bytes = serde_json::to_vec(&value).unwrap();
+        let project =
+            Project::load_from_slice(&bytes, PathBuf::from("/test/default.project.json"), None)
+                .unwrap();
+
+        (project, changes)
+    }
+
+    #[test]
+    fn migrates_legacy_fixture() {
+        let (project, changes) = migrate(
+            r#"{
+                "name": "legacy",
+                "emitLegacyScripts": true,
+                "partitions": {
+                    "shared": { "path": "src/shared", "target": "ReplicatedStorage.Shared" },
+                    "server": { "path": "src/server", "target": "ServerScriptService" }
+                },
+                "syncbackRules": { "ignoreHiddenServices": false }
+            }"#,
+        );
+
+        assert_eq!(project.name.as_deref(), Some("legacy"));
+        assert_eq!(project.ignore_hidden_services, Some(false));
+        assert_eq!(project.tree.class_name.unwrap().as_str(), "DataModel");
+
+        let shared = &project.tree.children["ReplicatedStorage"].children["Shared"];
+        assert_eq!(
+            shared.path.as_ref().unwrap().path(),
+            Path::new("src/shared")
+        );
+        let server = &project.tree.children["ServerScriptService"];
+        assert_eq!(
+            server.path.as_ref().unwrap().path(),
+            Path::new("src/server")
+        );
+
+        assert_eq!(changes.len(), 4, "{changes:#?}");
+    }
+
+    #[test]
+    fn current_project_is_unchanged() {
+        let source = r#"{
+            "name": "current",
+            "tree": { "$className": "DataModel" },
+            "ignoreHiddenServices": true
+        }"#;
+        let mut value: Value = serde_json::from_str(source).unwrap();
+        let original = value.clone();
+
+        assert!(migrate_project_value(&mut value).is_empty());
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn root_hidden_services_wins() {
+        let (project, changes) = migrate(
+            r#"{
+                "tree": { "$className": "DataModel" },
+                "ignoreHiddenServices": true,
+                "syncbackRules": { "ignoreHiddenServices": false }
+            }"#,
+        );
+
+        assert_eq!(project.ignore_hidden_services, Some(true));
+        assert_eq!(changes.len(), 1);
+    }
+}
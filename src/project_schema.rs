@@ -0,0 +1,431 @@
+//! A JSON Schema for project files, so editors can offer completion and
+//! validation through `$schema`. It's written by hand to match the serde
+//! definitions of [`Project`](crate::Project), [`ProjectNode`](crate::ProjectNode)
+//! and [`SyncbackRules`](crate::syncback::SyncbackRules); the tests below
+//! check that every key those types accept is described.
+
+use serde_json::{json, Value};
+
+const SCHEMA_ID: &str = "https://github.com/UserGeneratedLLC/rojo/project.schema.json";
+
+/// The names sync rules and `$middleware` accept, as written in project
+/// files.
+const MIDDLEWARE_NAMES: &[&str] = &[
+    "csv",
+    "jsonModel",
+    "json",
+    "serverScript",
+    "clientScript",
+    "moduleScript",
+    "pluginScript",
+    "localScript",
+    "legacyScript",
+    "project",
+    "rbxm",
+    "rbxmx",
+    "toml",
+    "text",
+    "yaml",
+    "yamlString",
+    "markdown",
+    "ignore",
+];
+
+/// Returns the JSON Schema for `.project.json5` files.
+pub fn project_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": SCHEMA_ID,
+        "title": "Atlas project",
+        "description": "Configuration for a project, stored in a .project.json5 file.",
+        "type": "object",
+        "required": ["tree"],
+        "additionalProperties": false,
+        "properties": {
+            "$schema": {
+                "description": "The schema this file is checked against.",
+                "type": "string",
+            },
+            "name": {
+                "description": "The name of the top-level Instance described by the project.",
+                "type": "string",
+            },
+            "extends": {
+                "description": "A project file, relative to this one's folder, whose settings this project inherits.",
+                "type": "string",
+            },
+            "tree": {
+                "description": "The tree of Instances described by this project.",
+                "$ref": "#/$defs/projectNode",
+            },
+            "servePort": {
+                "description": "The default port `atlas serve` uses for this project.",
+                "type": "integer",
+                "minimum": 0,
+                "maximum": 65535,
+            },
+            "servePlaceIds": {
+                "description": "The place IDs this project may be live synced into.",
+                "$ref": "#/$defs/placeIds",
+            },
+            "blockedPlaceIds": {
+                "description": "Place IDs this project must never be live synced into.",
+                "$ref": "#/$defs/placeIds",
+            },
+            "placeId": {
+                "description": "Sets the current place's place ID when Studio connects.",
+                "type": "integer",
+                "minimum": 0,
+            },
+            "gameId": {
+                "description": "Sets the current place's game ID when Studio connects.",
+                "type": "integer",
+                "minimum": 0,
+            },
+            "serveAddress": {
+                "description": "The address `atlas serve` listens on when --address isn't given.",
+                "type": "string",
+            },
+            "globIgnorePaths": {
+                "description": "Globs, relative to the project folder, matching files to leave out of the tree.",
+                "$ref": "#/$defs/globs",
+            },
+            "syncbackRules": {
+                "$ref": "#/$defs/syncbackRules",
+            },
+            "syncRules": {
+                "description": "Maps globs to the middleware files matching them are read with.",
+                "type": "array",
+                "items": { "$ref": "#/$defs/syncRule" },
+            },
+            "syncScriptsOnly": {
+                "description": "Only sync scripts to Studio, leaving everything else to the place. Defaults to false.",
+                "type": "boolean",
+            },
+            "ignoreHiddenServices": {
+                "description": "Leave internal services like Chat and HttpService out of sync. Defaults to true.",
+                "type": "boolean",
+            },
+            "fileLogLevel": {
+                "description": "The level of the logs written to ~/.atlas/logs/. Defaults to trace.",
+                "enum": ["trace", "debug", "info", "warn", "error", "none"],
+            },
+        },
+        "$defs": {
+            "projectNode": project_node_schema(),
+            "syncbackRules": syncback_rules_schema(),
+            "syncRule": {
+                "type": "object",
+                "required": ["pattern", "use"],
+                "additionalProperties": false,
+                "properties": {
+                    "pattern": {
+                        "description": "A glob matching the files this rule applies to.",
+                        "type": "string",
+                    },
+                    "exclude": {
+                        "description": "A glob matching files this rule doesn't apply to.",
+                        "type": "string",
+                    },
+                    "use": {
+                        "description": "The middleware matching files are read with.",
+                        "$ref": "#/$defs/middleware",
+                    },
+                    "suffix": {
+                        "description": "The suffix, including the extension, trimmed off file names to get Instance names.",
+                        "type": "string",
+                    },
+                },
+            },
+            "middleware": {
+                "enum": MIDDLEWARE_NAMES,
+            },
+            "modelFormat": {
+                "enum": ["rbxm", "rbxmx", "json", "jsonDir"],
+            },
+            "globs": {
+                "type": "array",
+                "items": { "type": "string" },
+            },
+            "placeIds": {
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0 },
+                "uniqueItems": true,
+            },
+            "value": {
+                "description": "A property or attribute value, either implicit like `[1, 2, 3]` or explicit like `{ \"Vector3\": [1, 2, 3] }`.",
+            },
+        },
+    })
+}
+
+fn project_node_schema() -> Value {
+    json!({
+        "description": "An Instance and its descendants. Keys that don't start with $ are children.",
+        "type": "object",
+        "properties": {
+            "$className": {
+                "description": "The ClassName of the Instance. Required if $path isn't set.",
+                "type": "string",
+            },
+            "$id": {
+                "description": "An ID other Instances' referent properties can point to.",
+                "type": "string",
+            },
+            "$path": {
+                "description": "The file or folder the Instance comes from, relative to the project folder.",
+                "anyOf": [
+                    { "type": "string" },
+                    {
+                        "type": "object",
+                        "required": ["optional"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "optional": {
+                                "description": "A path that's allowed to not exist.",
+                                "type": "string",
+                            },
+                        },
+                    },
+                ],
+            },
+            "$properties": {
+                "description": "Properties to set on the Instance.",
+                "type": "object",
+                "additionalProperties": { "$ref": "#/$defs/value" },
+            },
+            "$attributes": {
+                "description": "Attributes to set on the Instance.",
+                "type": "object",
+                "additionalProperties": { "$ref": "#/$defs/value" },
+            },
+            "$tags": {
+                "description": "CollectionService tags to give the Instance.",
+                "type": "array",
+                "items": { "type": "string" },
+            },
+            "$ignoreUnknownInstances": {
+                "description": "Whether live sync leaves Instances it doesn't know about alone. Defaults to true without $path and false with it.",
+                "type": "boolean",
+            },
+            "$middleware": {
+                "description": "Forces the middleware $path is read and written with.",
+                "$ref": "#/$defs/middleware",
+            },
+        },
+        "patternProperties": {
+            "^[^$]": { "$ref": "#/$defs/projectNode" },
+        },
+        "additionalProperties": false,
+    })
+}
+
+fn syncback_rules_schema() -> Value {
+    json!({
+        "description": "Settings for syncback with this project.",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "ignoreTrees": {
+                "description": "Globs matching Instance paths syncback leaves alone.",
+                "$ref": "#/$defs/globs",
+            },
+            "includeTrees": {
+                "description": "Globs matching the only Instance paths syncback writes or removes.",
+                "$ref": "#/$defs/globs",
+            },
+            "ignorePaths": {
+                "description": "Globs matching file paths syncback doesn't write.",
+                "$ref": "#/$defs/globs",
+            },
+            "ignoreProperties": {
+                "description": "Properties to leave out, by class name.",
+                "type": "object",
+                "additionalProperties": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                },
+            },
+            "ignoreClasses": {
+                "description": "Classes syncback never adds, removes or changes.",
+                "type": "array",
+                "items": { "type": "string" },
+            },
+            "syncCurrentCamera": {
+                "description": "Whether Workspace.CurrentCamera is synced back. Defaults to false.",
+                "type": "boolean",
+            },
+            "syncUnscriptable": {
+                "description": "Whether properties scripts can't change are synced back. Defaults to false.",
+                "type": "boolean",
+            },
+            "ignoreReferents": {
+                "description": "Whether referent properties like Model.PrimaryPart are left out. Defaults to false.",
+                "type": "boolean",
+            },
+            "createIgnoreDirPaths": {
+                "description": "Whether ignorePaths globs ending in /** also match the folder itself. Defaults to true.",
+                "type": "boolean",
+            },
+            "ignoreHiddenServices": {
+                "description": "Whether only visible services are synced back. Defaults to true.",
+                "type": "boolean",
+            },
+            "warnDuplicateNames": {
+                "description": "Whether to warn about children with duplicate names. Defaults to false.",
+                "type": "boolean",
+            },
+            "dedupDuplicateNames": {
+                "description": "Whether children with duplicate names are written with numbered suffixes instead of skipped. Defaults to false.",
+                "type": "boolean",
+            },
+            "stablePropertyOrder": {
+                "description": "Whether .model.json5 keys are written in alphabetical order. Defaults to true.",
+                "type": "boolean",
+            },
+            "modelFormat": {
+                "description": "The model format to write Instances of each class (or its subclasses) as.",
+                "type": "object",
+                "additionalProperties": { "$ref": "#/$defs/modelFormat" },
+            },
+            "defaultModelFormat": {
+                "description": "The model format used when an Instance can't be written as a folder. Defaults to rbxm.",
+                "$ref": "#/$defs/modelFormat",
+            },
+            "metaFormat": {
+                "description": "The format meta files are written in. Defaults to json5.",
+                "enum": ["json5", "toml"],
+            },
+            "alwaysEmitMeta": {
+                "description": "Whether every script gets a meta file, even an empty one. Defaults to false.",
+                "type": "boolean",
+            },
+            "floatEpsilon": {
+                "description": "How far apart float values can be while still counting as unchanged. Defaults to 0.0001.",
+                "type": "number",
+                "minimum": 0,
+            },
+            "neverRemove": {
+                "description": "Whether syncback only adds and updates files, never removing any. Defaults to false.",
+                "type": "boolean",
+            },
+            "numberPrecision": {
+                "description": "Rounds floats written to JSON files during syncback to at most this many decimal places.",
+                "type": "integer",
+                "minimum": 0,
+                "maximum": 255,
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::Project;
+
+    /// A project that sets every key a project file can have.
+    const EVERY_KEY: &str = r#"{
+        "$schema": "./project.schema.json",
+        "name": "everything",
+        "servePort": 34872,
+        "servePlaceIds": [1],
+        "blockedPlaceIds": [2],
+        "placeId": 1,
+        "gameId": 3,
+        "serveAddress": "127.0.0.1",
+        "globIgnorePaths": ["**/*.spec.luau"],
+        "syncRules": [{ "pattern": "*.frag", "exclude": "*.old.frag", "use": "text", "suffix": ".frag" }],
+        "syncScriptsOnly": false,
+        "ignoreHiddenServices": true,
+        "fileLogLevel": "info",
+        "syncbackRules": {
+            "ignoreTrees": ["ServerStorage/Secret"],
+            "includeTrees": ["ReplicatedStorage"],
+            "ignorePaths": ["src/generated/**"],
+            "ignoreProperties": { "Part": ["Color"] },
+            "ignoreClasses": ["Camera"],
+            "syncCurrentCamera": false,
+            "syncUnscriptable": false,
+            "ignoreReferents": false,
+            "createIgnoreDirPaths": true,
+            "ignoreHiddenServices": true,
+            "warnDuplicateNames": false,
+            "dedupDuplicateNames": false,
+            "stablePropertyOrder": true,
+            "modelFormat": { "MeshPart": "rbxmx" },
+            "defaultModelFormat": "rbxm",
+            "metaFormat": "toml",
+            "alwaysEmitMeta": false,
+            "floatEpsilon": 0.001,
+            "neverRemove": false,
+            "numberPrecision": 3
+        },
+        "tree": {
+            "$className": "DataModel",
+            "$id": "root",
+            "$properties": {},
+            "$attributes": { "Version": 1.5 },
+            "$tags": ["Root"],
+            "$ignoreUnknownInstances": true,
+            "ReplicatedStorage": {
+                "$path": { "optional": "src" },
+                "$middleware": "rbxmx"
+            }
+        }
+    }"#;
+
+    fn property_names(schema: &Value) -> Vec<&str> {
+        schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn describes_every_project_key() {
+        let project = Project::load_from_slice(
+            EVERY_KEY.as_bytes(),
+            PathBuf::from("/everything/default.project.json5"),
+            None,
+        )
+        .unwrap();
+        let serialized = serde_json::to_value(&project).unwrap();
+        let schema = project_schema();
+
+        let top_level = property_names(&schema);
+        for key in serialized.as_object().unwrap().keys() {
+            assert!(top_level.contains(&key.as_str()), "{key} isn't described");
+        }
+
+        let rules = property_names(&schema["$defs"]["syncbackRules"]);
+        for key in serialized["syncbackRules"].as_object().unwrap().keys() {
+            assert!(
+                rules.contains(&key.as_str()),
+                "syncbackRules.{key} isn't described"
+            );
+        }
+
+        let node = property_names(&schema["$defs"]["projectNode"]);
+        let tree = serialized["tree"].as_object().unwrap();
+        let child = tree["ReplicatedStorage"].as_object().unwrap();
+        for key in tree.keys().chain(child.keys()) {
+            if key.starts_with('$') {
+                assert!(node.contains(&key.as_str()), "{key} isn't described");
+            }
+        }
+    }
+
+    #[test]
+    fn lists_every_middleware() {
+        for name in MIDDLEWARE_NAMES {
+            let middleware: Result<crate::Middleware, _> =
+                serde_json::from_value(Value::String(name.to_string()));
+            assert!(middleware.is_ok(), "{name} isn't a middleware");
+        }
+    }
+}
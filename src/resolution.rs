@@ -33,6 +33,27 @@ impl UnresolvedValue {
         }
     }
 
+    /// Like [`resolve`](Self::resolve), but when `ignore_unknown` is set,
+    /// returns `None` for an ambiguous value of a property the reflection
+    /// database doesn't know so the caller can drop it. This lets a project
+    /// keep building when it sets properties from a newer (or older) version
+    /// of Roblox than the bundled database describes.
+    pub fn resolve_property(
+        self,
+        class_name: &str,
+        prop_name: &str,
+        ignore_unknown: bool,
+    ) -> anyhow::Result<Option<Variant>> {
+        if let UnresolvedValue::Ambiguous(_) = &self {
+            if ignore_unknown && find_descriptor(class_name, prop_name).is_none() {
+                log::warn!("Dropping unknown property {class_name}.{prop_name}");
+                return Ok(None);
+            }
+        }
+
+        self.resolve(class_name, prop_name).map(Some)
+    }
+
     pub fn resolve_unambiguous(self) -> anyhow::Result<Variant> {
         match self {
             UnresolvedValue::FullyQualified(full) => Ok(full),
@@ -487,6 +508,24 @@ mod test {
         unresolved.resolve_unambiguous().unwrap()
     }
 
+    /// Writes `variant` the same way meta files and JSON models do, then
+    /// reads it back and resolves it again.
+    fn round_trip(class: &str, prop: &str, variant: Variant) -> Variant {
+        let unresolved = UnresolvedValue::from_variant(variant, class, prop);
+        let bytes = json::to_vec_pretty_sorted(&unresolved).unwrap();
+        let read_back: UnresolvedValue = json::from_slice(&bytes).unwrap();
+        read_back.resolve(class, prop).unwrap()
+    }
+
+    /// Asserts that `variant` reads back exactly as it was written, and
+    /// returns what was read back.
+    #[track_caller]
+    fn assert_round_trips(class: &str, prop: &str, variant: Variant) -> Variant {
+        let read_back = round_trip(class, prop, variant.clone());
+        assert_eq!(read_back, variant, "{class}.{prop} did not round-trip");
+        read_back
+    }
+
     #[test]
     fn bools() {
         assert_eq!(resolve("BoolValue", "Value", "false"), Variant::Bool(false));
@@ -633,4 +672,143 @@ mod test {
             Variant::MaterialColors(material_colors)
         )
     }
+
+    #[test]
+    fn font_round_trip() {
+        use rbx_dom_weak::types::{FontStyle, FontWeight};
+
+        assert_round_trips(
+            "TextLabel",
+            "FontFace",
+            Variant::Font(Font {
+                family: "rbxasset://fonts/families/GothamSSm.json".into(),
+                weight: FontWeight::Bold,
+                style: FontStyle::Italic,
+                cached_face_id: None,
+            }),
+        );
+        let read_back = assert_round_trips(
+            "TextLabel",
+            "FontFace",
+            Variant::Font(Font {
+                family: "rbxassetid://12187365364".into(),
+                weight: FontWeight::ExtraLight,
+                style: FontStyle::Normal,
+                cached_face_id: Some("rbxasset://fonts/Montserrat-ExtraLight.ttf".into()),
+            }),
+        );
+        let Variant::Font(font) = read_back else {
+            panic!("FontFace read back as {read_back:?}");
+        };
+        assert_eq!(font.family, "rbxassetid://12187365364");
+        assert_eq!(font.weight, FontWeight::ExtraLight);
+        assert_eq!(font.style, FontStyle::Normal);
+        assert_eq!(
+            font.cached_face_id.as_deref(),
+            Some("rbxasset://fonts/Montserrat-ExtraLight.ttf")
+        );
+    }
+
+    #[test]
+    fn color_sequence_round_trip() {
+        assert_round_trips(
+            "UIGradient",
+            "Color",
+            Variant::ColorSequence(ColorSequence {
+                keypoints: vec![
+                    ColorSequenceKeypoint::new(0.0, Color3::new(1.0, 0.0, 0.0)),
+                    ColorSequenceKeypoint::new(
+                        0.333_333_34,
+                        Color3::new(17.0 / 255.0, 128.0 / 255.0, 0.123_456_79),
+                    ),
+                    ColorSequenceKeypoint::new(1.0, Color3::new(0.0, 0.0, 1.0)),
+                ],
+            }),
+        );
+    }
+
+    #[test]
+    fn number_sequence_round_trip() {
+        assert_round_trips(
+            "UIGradient",
+            "Transparency",
+            Variant::NumberSequence(NumberSequence {
+                keypoints: vec![
+                    NumberSequenceKeypoint::new(0.0, 0.0, 0.0),
+                    NumberSequenceKeypoint::new(0.142_857_15, 0.666_666_7, 0.05),
+                    NumberSequenceKeypoint::new(1.0, 1.0, 0.0),
+                ],
+            }),
+        );
+        assert_round_trips(
+            "ParticleEmitter",
+            "Size",
+            Variant::NumberSequence(NumberSequence {
+                keypoints: vec![
+                    NumberSequenceKeypoint::new(0.0, 1234.567_9, 12.5),
+                    NumberSequenceKeypoint::new(1.0, -0.000_1, 0.0),
+                ],
+            }),
+        );
+    }
+
+    #[test]
+    fn udim2_round_trip() {
+        assert_round_trips(
+            "Frame",
+            "Size",
+            Variant::UDim2(UDim2::new(
+                UDim::new(0.333_333_34, -12),
+                UDim::new(1.0, 2_147_483_647),
+            )),
+        );
+        assert_round_trips(
+            "Frame",
+            "Position",
+            Variant::UDim2(UDim2::new(
+                UDim::new(-0.5, 0),
+                UDim::new(0.0, -2_147_483_648),
+            )),
+        );
+    }
+
+    #[test]
+    fn round_trip_inside_attributes() {
+        use rbx_dom_weak::types::{FontStyle, FontWeight};
+
+        let mut attributes = Attributes::new();
+        attributes.insert(
+            "Font".into(),
+            Variant::Font(Font {
+                family: "rbxasset://fonts/families/SourceSansPro.json".into(),
+                weight: FontWeight::Medium,
+                style: FontStyle::Normal,
+                cached_face_id: None,
+            }),
+        );
+        attributes.insert(
+            "Gradient".into(),
+            Variant::ColorSequence(ColorSequence {
+                keypoints: vec![
+                    ColorSequenceKeypoint::new(0.0, Color3::new(0.25, 0.5, 0.75)),
+                    ColorSequenceKeypoint::new(1.0, Color3::new(0.1, 0.2, 0.3)),
+                ],
+            }),
+        );
+        attributes.insert(
+            "Fade".into(),
+            Variant::NumberSequence(NumberSequence {
+                keypoints: vec![
+                    NumberSequenceKeypoint::new(0.0, 0.1, 0.0),
+                    NumberSequenceKeypoint::new(1.0, 0.9, 0.0),
+                ],
+            }),
+        );
+        attributes.insert(
+            "Size".into(),
+            Variant::UDim2(UDim2::new(UDim::new(0.5, 10), UDim::new(0.25, -4))),
+        );
+
+        assert_round_trips("Folder", "Attributes", Variant::Attributes(attributes));
+    }
 }
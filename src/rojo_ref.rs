@@ -12,12 +12,13 @@ use rbx_dom_weak::{
 };
 use serde::{Deserialize, Serialize};
 
-/// Returns `true` if the filename is a meta or model JSON file.
+/// Returns `true` if the filename is a meta or model file.
 pub(crate) fn is_meta_or_model_name(name: &str) -> bool {
     name.ends_with(".meta.json5")
         || name.ends_with(".model.json5")
         || name.ends_with(".meta.json")
         || name.ends_with(".model.json")
+        || name.ends_with(".meta.toml")
 }
 
 /// Legacy: ID attribute on target instances (kept for backwards compatibility)
@@ -388,7 +389,7 @@ impl RefPathIndex {
     }
 
     /// Scan all `.meta.json5`, `.model.json5`, `.meta.json`, `.model.json`
-    /// files under `root` for existing `Rojo_Ref_*` attributes and index them.
+    /// and `.meta.toml` files under `root` for existing `Rojo_Ref_*` attributes and index them.
     ///
     /// Relative paths (prefixed with `@self/`, `./`, `../`) are resolved to
     /// absolute using `tree` so the index always stores absolute target paths.
@@ -418,7 +419,7 @@ impl RefPathIndex {
 
                 let mut results = Vec::new();
                 if let Ok(bytes) = std::fs::read(path) {
-                    if let Ok(val) = crate::json::from_slice::<serde_json::Value>(&bytes) {
+                    if let Ok(val) = crate::syncback::meta::parse_meta_or_model(path, &bytes) {
                         if let Some(attrs) = val.get("attributes").and_then(|a| a.as_object()) {
                             for (key, value) in attrs {
                                 if key.starts_with(crate::REF_PATH_ATTRIBUTE_PREFIX) {
@@ -2,8 +2,12 @@ use std::{
     collections::HashSet,
     io,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex, MutexGuard},
-    time::Instant,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
 };
 
 use crossbeam_channel::Sender;
@@ -12,6 +16,7 @@ use thiserror::Error;
 
 use crate::{
     change_processor::ChangeProcessor,
+    glob::Glob,
     message_queue::MessageQueue,
     project::{Project, ProjectError},
     session_id::SessionId,
@@ -19,12 +24,47 @@ use crate::{
         apply_patch_set, compute_patch_set, AppliedPatchSet, InstanceContext, InstanceSnapshot,
         PatchSet, RojoTree,
     },
-    snapshot_middleware::{is_script_relevant_path, snapshot_from_vfs, INIT_FILE_PRIORITY},
+    snapshot_middleware::{
+        default_sync_rules, is_script_relevant_path, snapshot_from_vfs, INIT_FILE_PRIORITY,
+    },
 };
 
 /// Set to `true` to validate on plugin connect (useful for testing, do not enable on production).
 const VALIDATE_TREE_ON_CONNECT: bool = false;
 
+/// What the plugin should do with differences between Studio and the project
+/// when it first connects to a serve session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InitialSyncMode {
+    /// The project on disk is authoritative: Studio is made to match it.
+    #[default]
+    Push,
+    /// Studio and the project are reconciled, keeping changes made on either
+    /// side instead of overwriting Studio.
+    Merge,
+}
+
+impl FromStr for InitialSyncMode {
+    type Err = InitialSyncModeParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "push" => Ok(InitialSyncMode::Push),
+            "merge" => Ok(InitialSyncMode::Merge),
+            _ => Err(InitialSyncModeParseError {
+                attempted: source.to_owned(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid initial sync mode '{attempted}'. Valid values are: push, merge")]
+pub struct InitialSyncModeParseError {
+    attempted: String,
+}
+
 /// Result of a read-only tree freshness check. Reports how many instances
 /// differ between the in-memory tree and the real filesystem.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -37,6 +77,48 @@ pub struct TreeFreshnessReport {
     pub elapsed_ms: f64,
 }
 
+/// How an Instance in the in-memory tree differs from the project on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DivergenceKind {
+    /// The Instance exists on disk but not in the tree.
+    MissingFromTree,
+    /// The Instance exists in the tree but not on disk.
+    MissingFromDisk,
+    /// The Instance exists in both, but its name, class, or properties
+    /// differ.
+    Changed,
+}
+
+/// A single difference between the in-memory tree and the project on disk,
+/// reported by [`ServeSession::verify`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// The `/`-separated path of the Instance from the root of the tree.
+    pub path: String,
+    pub class_name: String,
+    /// For `Changed` Instances, the names of the properties that differ.
+    /// `Name` and `ClassName` are included when those differ.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<String>,
+}
+
+/// Options that change how a [`ServeSession`] builds and updates its tree. Passed to
+/// [`ServeSession::new_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct ServeSessionOptions {
+    /// Drop properties the reflection database doesn't know with a warning
+    /// instead of failing to build the tree.
+    pub ignore_unknown_properties: bool,
+
+    /// How long file events are held for more events to arrive before
+    /// they're applied to the tree. Repeated events for the same path within
+    /// the window are handled once. Zero handles every event right away.
+    pub event_debounce: Duration,
+}
+
 /// Contains all of the state for a Rojo serve session. A serve session is used
 /// when we need to build a Rojo tree and possibly rebuild it when input files
 /// change.
@@ -75,6 +157,10 @@ pub struct ServeSession {
     /// path to a `.project.json5` file.
     root_project: Project,
 
+    /// The context the root of the tree was snapshotted with. Reused when
+    /// re-snapshotting the whole project.
+    root_context: InstanceContext,
+
     /// A randomly generated ID for this serve session. It's used to ensure that
     /// a client doesn't begin connecting to a different server part way through
     /// an operation that needs to be atomic.
@@ -137,21 +223,97 @@ pub struct ServeSession {
     /// Available for syncback to reuse for orphan detection, avoiding a
     /// redundant walkdir.
     prefetch_walked_paths: Option<HashSet<PathBuf>>,
+
+    /// How the plugin should reconcile Studio with the project when it
+    /// connects. Reported through `/api/rojo`.
+    initial_sync_mode: InitialSyncMode,
+
+    /// Whether a syncback that keeps the server running is in progress.
+    /// Used to stop two of them from writing the project at once.
+    live_syncback_running: AtomicBool,
+}
+
+/// Marks a live syncback as in progress until it's dropped. Returned by
+/// [`ServeSession::try_begin_live_syncback`].
+pub struct LiveSyncbackGuard<'a> {
+    running: &'a AtomicBool,
+}
+
+impl Drop for LiveSyncbackGuard<'_> {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+/// Upper bound on the file contents held by the prefetch cache. Large places
+/// can otherwise hold hundreds of megabytes before the initial snapshot
+/// consumes them. Files beyond the budget are read from disk as usual.
+const PREFETCH_MAX_BYTES: usize = 512 * 1024 * 1024;
+
+/// Builds the glob of files worth reading during prefetch: anything matched
+/// by a default sync rule or one of the project's own sync rules. Other files
+/// (images, audio, ...) are never read by a middleware, so there's no point
+/// holding their contents in memory.
+///
+/// Patterns are matched against paths relative to the project folder.
+/// Returns `None` if the patterns can't be combined into one glob, in which
+/// case every file should be read.
+fn prefetch_include_glob(project: &Project) -> Option<Glob> {
+    let patterns: Vec<String> = default_sync_rules()
+        .iter()
+        .chain(&project.sync_rules)
+        .flat_map(|rule| expand_alternates(rule.include.as_str()))
+        .collect();
+
+    match Glob::new(&format!("{{{}}}", patterns.join(","))) {
+        Ok(glob) => Some(glob),
+        Err(err) => {
+            log::debug!("Prefetch will read all files, sync rule globs can't be combined: {err}");
+            None
+        }
+    }
+}
+
+/// Expands `{a,b}` groups in a glob pattern into separate patterns, since
+/// alternate groups can't be nested when patterns are combined.
+fn expand_alternates(pattern: &str) -> Vec<String> {
+    let (Some(open), Some(close)) = (pattern.find('{'), pattern.find('}')) else {
+        return vec![pattern.to_owned()];
+    };
+    if close < open {
+        return vec![pattern.to_owned()];
+    }
+
+    let (prefix, rest) = (&pattern[..open], &pattern[close + 1..]);
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|alternate| expand_alternates(&format!("{prefix}{alternate}{rest}")))
+        .collect()
 }
 
 /// Collect all filesystem paths reachable from the project tree's `$path`
 /// entries, then read file contents in parallel.
-fn prefetch_project_files(project: &Project, sync_scripts_only: bool) -> io::Result<PrefetchCache> {
+///
+/// Only files matching `include` have their contents read; every entry still
+/// gets its metadata recorded. Once the running total of file contents
+/// exceeds `max_bytes`, the walker stops reading contents as well. In both
+/// cases, VFS reads of the skipped files fall through to the backend.
+fn prefetch_project_files(
+    project: &Project,
+    sync_scripts_only: bool,
+    include: Option<&Glob>,
+    max_bytes: usize,
+) -> io::Result<PrefetchCache> {
     use rayon::prelude::*;
     use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
     use walkdir::WalkDir;
 
     let folder = project.folder_location();
 
     let ignore_rules = project.path_ignore_rules();
 
-    let mut roots: Vec<std::path::PathBuf> = Vec::new();
-    collect_path_roots(&project.tree, folder, &mut roots);
+    let mut roots = project.resolved_path_roots();
 
     roots.sort();
     let mut deduped: Vec<std::path::PathBuf> = Vec::new();
@@ -245,23 +407,49 @@ fn prefetch_project_files(project: &Project, sync_scripts_only: bool) -> io::Res
 
     let read_start = Instant::now();
 
+    let total_bytes = AtomicUsize::new(0);
+    let budget_exceeded = AtomicBool::new(false);
+
     let file_data: Vec<_> = entries
         .par_iter()
         .filter(|e| e.file_type().is_file())
         .filter(|e| !sync_scripts_only || is_script_relevant_path(e.path()))
+        .filter(|e| {
+            include
+                .is_none_or(|glob| glob.is_match(e.path().strip_prefix(folder).unwrap_or(e.path())))
+        })
         .filter_map(|e| {
+            if budget_exceeded.load(Ordering::Relaxed) {
+                return None;
+            }
             let path = e.path().to_path_buf();
-            std::fs::read(&path).ok().map(|c| (path, c))
+            let contents = std::fs::read(&path).ok()?;
+            let reserved = total_bytes.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |t| {
+                let next = t + contents.len();
+                (next <= max_bytes).then_some(next)
+            });
+            if reserved.is_err() {
+                budget_exceeded.store(true, Ordering::Relaxed);
+                return None;
+            }
+            Some((path, contents))
         })
         .collect();
 
     let read_elapsed = read_start.elapsed();
 
     log::debug!(
-        "Prefetch I/O: read {} files in {:.1?}",
+        "Prefetch I/O: read {} files ({} bytes) in {:.1?}",
         file_data.len(),
+        total_bytes.load(Ordering::Relaxed),
         read_elapsed,
     );
+    if budget_exceeded.load(Ordering::Relaxed) {
+        log::debug!(
+            "Prefetch budget of {} bytes exceeded; remaining files will be read on demand",
+            max_bytes
+        );
+    }
 
     let mut is_file_map: HashMap<std::path::PathBuf, bool> = HashMap::with_capacity(entries.len());
     let mut children_map: HashMap<std::path::PathBuf, Vec<std::path::PathBuf>> =
@@ -317,17 +505,6 @@ fn prefetch_project_files(project: &Project, sync_scripts_only: bool) -> io::Res
     })
 }
 
-/// Recursively collect all `$path` directories from the project tree.
-pub fn collect_path_roots(node: &crate::project::ProjectNode, base: &Path, out: &mut Vec<PathBuf>) {
-    if let Some(path_node) = &node.path {
-        let resolved = base.join(path_node.path());
-        out.push(resolved);
-    }
-    for child_node in node.children.values() {
-        collect_path_roots(child_node, base, out);
-    }
-}
-
 impl ServeSession {
     /// Shared initialization: loads the project and builds the initial
     /// snapshot tree. Used by both `new()` and `new_oneshot()`.
@@ -337,9 +514,11 @@ impl ServeSession {
     fn init_tree(
         vfs: &Vfs,
         start_path: &Path,
+        options: &ServeSessionOptions,
     ) -> Result<
         (
             Project,
+            InstanceContext,
             RojoTree,
             Option<HashSet<PathBuf>>,
             Vec<(String, std::path::PathBuf)>,
@@ -355,7 +534,13 @@ impl ServeSession {
 
         if std::env::var("ATLAS_SEQUENTIAL").is_err() {
             let prefetch_start = Instant::now();
-            match prefetch_project_files(&root_project, sync_scripts_only) {
+            let include = prefetch_include_glob(&root_project);
+            match prefetch_project_files(
+                &root_project,
+                sync_scripts_only,
+                include.as_ref(),
+                PREFETCH_MAX_BYTES,
+            ) {
                 Ok(cache) => {
                     let count = cache.files.len();
                     log::debug!(
@@ -404,7 +589,7 @@ impl ServeSession {
                             );
                             walked_paths = Some(paths);
                         }
-                        vfs.set_prefetch_cache(cache);
+                        vfs.set_prefetch_cache(cache, Some(PREFETCH_MAX_BYTES));
                     }
                 }
                 Err(err) => {
@@ -417,6 +602,7 @@ impl ServeSession {
         let root_id = tree.get_root_id();
         let mut instance_context = InstanceContext::new();
         instance_context.sync_scripts_only = sync_scripts_only;
+        instance_context.ignore_unknown_properties = options.ignore_unknown_properties;
 
         let snap_start = Instant::now();
         log::trace!("Generating snapshot of instances from VFS");
@@ -434,7 +620,13 @@ impl ServeSession {
         let ref_path_entries = applied.ref_path_index_entries;
         log::debug!("Patch computed + applied in {:.1?}", patch_start.elapsed());
 
-        Ok((root_project, tree, walked_paths, ref_path_entries))
+        Ok((
+            root_project,
+            instance_context,
+            tree,
+            walked_paths,
+            ref_path_entries,
+        ))
     }
 
     /// Start a new serve session from the given in-memory filesystem and start
@@ -447,13 +639,28 @@ impl ServeSession {
         vfs: Vfs,
         start_path: P,
         critical_error_receiver: Option<crossbeam_channel::Receiver<memofs::WatcherCriticalError>>,
+    ) -> Result<Self, ServeSessionError> {
+        Self::new_with_options(
+            vfs,
+            start_path,
+            critical_error_receiver,
+            ServeSessionOptions::default(),
+        )
+    }
+
+    /// Like [`new`](Self::new), but builds the tree with the given options.
+    pub fn new_with_options<P: AsRef<Path>>(
+        vfs: Vfs,
+        start_path: P,
+        critical_error_receiver: Option<crossbeam_channel::Receiver<memofs::WatcherCriticalError>>,
+        options: ServeSessionOptions,
     ) -> Result<Self, ServeSessionError> {
         let start_path = start_path.as_ref();
         let start_time = Instant::now();
 
         let t_init_start = Instant::now();
-        let (root_project, tree, _walked_paths, ref_path_entries) =
-            Self::init_tree(&vfs, start_path)?;
+        let (root_project, root_context, tree, _walked_paths, ref_path_entries) =
+            Self::init_tree(&vfs, start_path, &options)?;
         let t_init_tree = Instant::now();
 
         let session_id = SessionId::new();
@@ -497,8 +704,9 @@ impl ServeSession {
             root_project.file_location.clone(),
             critical_error_receiver,
             git_repo_root.clone(),
-            root_project.sync_scripts_only.unwrap_or(false),
+            root_context.clone(),
             path_ignore_rules,
+            options.event_debounce,
         );
 
         Ok(Self {
@@ -506,6 +714,7 @@ impl ServeSession {
             start_time,
             session_id,
             root_project,
+            root_context,
             tree,
             message_queue,
             tree_mutation_sender: Some(tree_mutation_sender),
@@ -516,6 +725,8 @@ impl ServeSession {
             initial_head_commit,
             git_metadata_cache: Arc::new(Mutex::new(None)),
             prefetch_walked_paths: None,
+            initial_sync_mode: InitialSyncMode::default(),
+            live_syncback_running: AtomicBool::new(false),
         })
     }
 
@@ -528,13 +739,15 @@ impl ServeSession {
         let start_path = start_path.as_ref();
         let start_time = Instant::now();
 
-        let (root_project, tree, walked_paths, _ref_entries) = Self::init_tree(&vfs, start_path)?;
+        let (root_project, root_context, tree, walked_paths, _ref_entries) =
+            Self::init_tree(&vfs, start_path, &ServeSessionOptions::default())?;
 
         Ok(Self {
             change_processor: None,
             start_time,
             session_id: SessionId::new(),
             root_project,
+            root_context,
             tree: Arc::new(Mutex::new(tree)),
             message_queue: Arc::new(MessageQueue::new()),
             tree_mutation_sender: None,
@@ -545,9 +758,18 @@ impl ServeSession {
             initial_head_commit: None,
             git_metadata_cache: Arc::new(Mutex::new(None)),
             prefetch_walked_paths: walked_paths,
+            initial_sync_mode: InitialSyncMode::default(),
+            live_syncback_running: AtomicBool::new(false),
         })
     }
 
+    /// Sets how the plugin should reconcile Studio with the project when it
+    /// connects.
+    pub fn with_initial_sync_mode(mut self, initial_sync_mode: InitialSyncMode) -> Self {
+        self.initial_sync_mode = initial_sync_mode;
+        self
+    }
+
     pub fn take_walked_paths(&mut self) -> Option<HashSet<PathBuf>> {
         self.prefetch_walked_paths.take()
     }
@@ -649,6 +871,10 @@ impl ServeSession {
         &self.root_project
     }
 
+    pub fn initial_sync_mode(&self) -> InitialSyncMode {
+        self.initial_sync_mode
+    }
+
     /// Returns whether sync should only include script instances.
     /// When enabled, only Script, LocalScript, and ModuleScript are synced.
     pub fn sync_scripts_only(&self) -> bool {
@@ -675,17 +901,24 @@ impl ServeSession {
             .unwrap_or(true)
     }
 
+    /// Returns whether the session is serving a usable tree: the tree isn't
+    /// poisoned by a panic and file changes are still being applied to it.
+    /// Cheap enough to call on every health check.
+    pub fn is_ready(&self) -> bool {
+        !self.tree.is_poisoned()
+            && self
+                .change_processor
+                .as_ref()
+                .is_some_and(ChangeProcessor::is_running)
+    }
+
     /// Read-only check: re-snapshots from disk and returns how many
     /// instances differ between the in-memory tree and the real filesystem.
     /// Does NOT apply corrections — the tree is left unchanged.
     pub fn check_tree_freshness(&self) -> TreeFreshnessReport {
         let start = Instant::now();
-        let start_path: &Path = &self.root_project.file_location;
-        let mut instance_context = InstanceContext::new();
-        instance_context.sync_scripts_only = self.sync_scripts_only();
-
-        let snapshot = match snapshot_from_vfs(&instance_context, &self.vfs, start_path) {
-            Ok(s) => s,
+        let (_tree, patch_set) = match self.compute_drift() {
+            Ok(drift) => drift,
             Err(e) => {
                 log::error!("Tree freshness check snapshot error: {:?}", e);
                 return TreeFreshnessReport {
@@ -698,10 +931,6 @@ impl ServeSession {
             }
         };
 
-        let tree = self.tree.lock().unwrap();
-        let root_id = tree.get_root_id();
-        let patch_set = compute_patch_set(snapshot, &tree, root_id);
-
         let added = patch_set.added_instances.len();
         let removed = patch_set.removed_instances.len();
         let updated = patch_set.updated_instances.len();
@@ -715,6 +944,96 @@ impl ServeSession {
         }
     }
 
+    /// Read-only consistency check: re-snapshots the project from the VFS and
+    /// lists every Instance that differs from the in-memory tree, sorted by
+    /// path. The tree is left unchanged.
+    pub fn verify(&self) -> anyhow::Result<Vec<Divergence>> {
+        let (tree, patch_set) = self.compute_drift()?;
+        let mut divergences = Vec::new();
+
+        for add in &patch_set.added_instances {
+            let parent_path = crate::ref_target_path_from_tree(&tree, add.parent_id);
+            let name = crate::escape_ref_path_segment(&add.instance.name);
+            divergences.push(Divergence {
+                kind: DivergenceKind::MissingFromTree,
+                path: if parent_path.is_empty() {
+                    name.into_owned()
+                } else {
+                    format!("{parent_path}/{name}")
+                },
+                class_name: add.instance.class_name.to_string(),
+                changed: Vec::new(),
+            });
+        }
+
+        for &id in &patch_set.removed_instances {
+            let Some(instance) = tree.get_instance(id) else {
+                continue;
+            };
+            divergences.push(Divergence {
+                kind: DivergenceKind::MissingFromDisk,
+                path: crate::ref_target_path_from_tree(&tree, id),
+                class_name: instance.class_name().to_string(),
+                changed: Vec::new(),
+            });
+        }
+
+        for update in &patch_set.updated_instances {
+            let Some(instance) = tree.get_instance(update.id) else {
+                continue;
+            };
+            let mut changed: Vec<String> = update
+                .changed_properties
+                .keys()
+                .map(|key| key.to_string())
+                .collect();
+            changed.sort();
+            if update.changed_class_name.is_some() {
+                changed.insert(0, "ClassName".to_owned());
+            }
+            if update.changed_name.is_some() {
+                changed.insert(0, "Name".to_owned());
+            }
+            // Metadata-only updates don't change anything a client can see.
+            if changed.is_empty() {
+                continue;
+            }
+            divergences.push(Divergence {
+                kind: DivergenceKind::Changed,
+                path: crate::ref_target_path_from_tree(&tree, update.id),
+                class_name: instance.class_name().to_string(),
+                changed,
+            });
+        }
+
+        divergences.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(divergences)
+    }
+
+    /// Marks a live syncback as started, or returns `None` if one is already
+    /// running. The syncback counts as running until the guard is dropped.
+    pub fn try_begin_live_syncback(&self) -> Option<LiveSyncbackGuard<'_>> {
+        self.live_syncback_running
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        Some(LiveSyncbackGuard {
+            running: &self.live_syncback_running,
+        })
+    }
+
+    /// Re-snapshots the project from the VFS and computes the patch that
+    /// would bring the in-memory tree up to date, without applying it. The
+    /// tree stays locked so the patch's IDs remain valid for the caller.
+    fn compute_drift(&self) -> anyhow::Result<(MutexGuard<'_, RojoTree>, PatchSet)> {
+        let start_path: &Path = &self.root_project.file_location;
+        let snapshot = snapshot_from_vfs(&self.root_context, &self.vfs, start_path)?;
+
+        let tree = self.tree.lock().unwrap();
+        let root_id = tree.get_root_id();
+        let patch_set = compute_patch_set(snapshot, &tree, root_id);
+        Ok((tree, patch_set))
+    }
+
     /// Re-snapshots the project tree from the real filesystem and patches
     /// the in-memory tree to correct any drift caused by missed VFS watcher
     /// events. Called on plugin connect to guarantee the tree is fresh.
@@ -745,10 +1064,7 @@ impl ServeSession {
 
         let start = Instant::now();
         let start_path: &Path = &self.root_project.file_location;
-        let mut instance_context = InstanceContext::new();
-        instance_context.sync_scripts_only = self.sync_scripts_only();
-
-        let snapshot = match snapshot_from_vfs(&instance_context, &self.vfs, start_path) {
+        let snapshot = match snapshot_from_vfs(&self.root_context, &self.vfs, start_path) {
             Ok(s) => s,
             Err(e) => {
                 log::error!("Tree validation snapshot error: {:?}", e);
@@ -798,3 +1114,192 @@ pub enum ServeSessionError {
         source: anyhow::Error,
     },
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use memofs::{InMemoryFs, VfsEvent, VfsSnapshot};
+    use rbx_dom_weak::{types::Variant, ustr};
+
+    /// Waits for the change processor to publish a patch after `cursor`.
+    fn wait_for_patch(session: &ServeSession, cursor: u32) {
+        let receiver = session.message_queue().subscribe(cursor);
+        let (sender, done) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = sender.send(futures::executor::block_on(receiver));
+        });
+        done.recv_timeout(Duration::from_secs(10))
+            .expect("change processor did not publish a patch")
+            .expect("message queue listener was dropped");
+    }
+
+    #[test]
+    fn live_syncback_guard_allows_one_at_a_time() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/guard",
+            VfsSnapshot::dir([(
+                "default.project.json5",
+                VfsSnapshot::file(r#"{ "name": "test", "tree": { "$className": "Folder" } }"#),
+            )]),
+        )
+        .unwrap();
+        let session = ServeSession::new_oneshot(Vfs::new(imfs), "/guard").unwrap();
+
+        let guard = session.try_begin_live_syncback();
+        assert!(guard.is_some());
+        assert!(session.try_begin_live_syncback().is_none());
+
+        drop(guard);
+        assert!(session.try_begin_live_syncback().is_some());
+    }
+
+    #[test]
+    fn prefetch_skips_files_no_middleware_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(
+            dir.path().join("default.project.json5"),
+            r#"{
+                "name": "test",
+                "tree": { "$path": "src" },
+                "syncRules": [{ "pattern": "*.dat", "use": "text" }],
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(src.join("main.luau"), "return nil").unwrap();
+        std::fs::write(src.join("notes.dat"), "notes").unwrap();
+        std::fs::write(src.join("icon.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let project = Project::load_initial_project(&Vfs::new_default(), dir.path()).unwrap();
+        let include = prefetch_include_glob(&project);
+        assert!(include.is_some());
+
+        let cache =
+            prefetch_project_files(&project, false, include.as_ref(), PREFETCH_MAX_BYTES).unwrap();
+
+        let png = src.join("icon.png");
+        assert_eq!(cache.is_file.get(&png), Some(&true));
+        assert!(!cache.files.contains_key(&png));
+        assert!(cache.children[&src].contains(&png));
+
+        assert!(cache.files.contains_key(&src.join("main.luau")));
+        assert!(cache.files.contains_key(&src.join("notes.dat")));
+    }
+
+    #[test]
+    fn expand_alternates_splits_groups() {
+        assert_eq!(expand_alternates("*.luau"), vec!["*.luau"]);
+        assert_eq!(expand_alternates("*.{yml,yaml}"), vec!["*.yml", "*.yaml"]);
+        assert_eq!(
+            expand_alternates("{a,b}/*.{x,y}"),
+            vec!["a/*.x", "a/*.y", "b/*.x", "b/*.y"]
+        );
+    }
+
+    #[test]
+    fn injected_write_updates_tree() {
+        let root = Path::new("/atlas-in-memory-serve-test");
+        let value_path = root.join("src/greeting.txt");
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            root,
+            VfsSnapshot::dir([
+                (
+                    "default.project.json5",
+                    VfsSnapshot::file(r#"{ "name": "test", "tree": { "$path": "src" } }"#),
+                ),
+                (
+                    "src",
+                    VfsSnapshot::dir([("greeting.txt", VfsSnapshot::file("hello"))]),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let session = ServeSession::new(Vfs::new(imfs.clone()), root, None).unwrap();
+        let value_of_greeting = || {
+            let tree = session.tree();
+            let id = tree.get_ids_at_path(&value_path)[0];
+            match tree
+                .get_instance(id)
+                .unwrap()
+                .properties()
+                .get(&ustr("Value"))
+            {
+                Some(Variant::String(value)) => value.clone(),
+                other => panic!("unexpected Value {other:?}"),
+            }
+        };
+        assert_eq!(value_of_greeting(), "hello");
+
+        let cursor = session.message_queue().cursor();
+        imfs.load_snapshot(&value_path, VfsSnapshot::file("goodbye"))
+            .unwrap();
+        imfs.push_event(VfsEvent::Write(value_path.clone()));
+        wait_for_patch(&session, cursor);
+
+        assert_eq!(value_of_greeting(), "goodbye");
+    }
+
+    #[test]
+    fn verify_reports_divergence_without_fixing_it() {
+        let root = Path::new("/atlas-in-memory-verify-test");
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            root,
+            VfsSnapshot::dir([
+                (
+                    "default.project.json5",
+                    VfsSnapshot::file(r#"{ "name": "test", "tree": { "$path": "src" } }"#),
+                ),
+                (
+                    "src",
+                    VfsSnapshot::dir([
+                        ("greeting.txt", VfsSnapshot::file("hello")),
+                        ("farewell.txt", VfsSnapshot::file("goodbye")),
+                    ]),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let session = ServeSession::new(Vfs::new(imfs), root, None).unwrap();
+        assert_eq!(session.verify().unwrap(), Vec::new());
+
+        // Desync the tree behind the change processor's back.
+        {
+            let mut tree = session.tree();
+            let greeting = tree.get_ids_at_path(&root.join("src/greeting.txt"))[0];
+            let farewell = tree.get_ids_at_path(&root.join("src/farewell.txt"))[0];
+            tree.get_instance_mut(greeting)
+                .unwrap()
+                .properties_mut()
+                .insert(ustr("Value"), Variant::String("stale".to_owned()));
+            tree.remove(farewell);
+        }
+
+        let expected = vec![
+            Divergence {
+                kind: DivergenceKind::MissingFromTree,
+                path: "farewell".to_owned(),
+                class_name: "StringValue".to_owned(),
+                changed: Vec::new(),
+            },
+            Divergence {
+                kind: DivergenceKind::Changed,
+                path: "greeting".to_owned(),
+                class_name: "StringValue".to_owned(),
+                changed: vec!["Value".to_owned()],
+            },
+        ];
+        assert_eq!(session.verify().unwrap(), expected);
+
+        // Verifying is read-only, so the divergence is still there.
+        assert_eq!(session.verify().unwrap(), expected);
+    }
+}
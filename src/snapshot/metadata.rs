@@ -170,6 +170,11 @@ pub struct InstanceContext {
     pub sync_rules: Vec<SyncRule>,
     #[serde(skip)]
     pub sync_scripts_only: bool,
+    /// Drop properties the reflection database doesn't know with a warning
+    /// instead of failing to snapshot. Set by `build
+    /// --ignore-unknown-properties`.
+    #[serde(skip)]
+    pub ignore_unknown_properties: bool,
 }
 
 impl InstanceContext {
@@ -178,6 +183,7 @@ impl InstanceContext {
             path_ignore_rules: Arc::new(Vec::new()),
             sync_rules: Vec::new(),
             sync_scripts_only: false,
+            ignore_unknown_properties: false,
         }
     }
 
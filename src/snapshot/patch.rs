@@ -1,6 +1,9 @@
 //! Defines the data structures used for describing instance patches.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use rbx_dom_weak::{
     types::{Ref, Variant},
@@ -86,6 +89,12 @@ pub struct AppliedPatchSet {
     /// Used to build `RefPathIndex` without re-walking the directory tree.
     #[serde(skip)]
     pub ref_path_index_entries: Vec<(String, PathBuf)>,
+
+    /// Maps the snapshot ID of each added instance that had one to the ID it
+    /// was given in the tree. Lets `/api/write` tell the plugin which
+    /// instances its placeholder IDs became.
+    #[serde(skip)]
+    pub added_ids: HashMap<Ref, Ref>,
 }
 
 impl AppliedPatchSet {
@@ -95,6 +104,7 @@ impl AppliedPatchSet {
             added: Vec::new(),
             updated: Vec::new(),
             ref_path_index_entries: Vec::new(),
+            added_ids: HashMap::new(),
         }
     }
 
@@ -115,6 +125,7 @@ impl AppliedPatchSet {
             merged.removed.extend(patch.removed);
             merged.added.extend(patch.added);
             merged.updated.extend(patch.updated);
+            merged.added_ids.extend(patch.added_ids);
         }
         merged
     }
@@ -303,6 +303,7 @@ fn apply_add_child(
 
     if snapshot_id.is_some() {
         context.snapshot_id_to_instance_id.insert(snapshot_id, id);
+        context.applied_patch_set.added_ids.insert(snapshot_id, id);
     }
 
     for child in children {
@@ -95,6 +95,29 @@ impl RojoTree {
             .expect("RojoTrees should have a root")
     }
 
+    /// Captures the subtree rooted at `id` as an [`InstanceSnapshot`],
+    /// including each instance's metadata. Each snapshot's `snapshot_id` is
+    /// the instance's current `Ref`.
+    ///
+    /// Passing the result to [`RojoTree::new`] rebuilds an equivalent tree,
+    /// which makes this useful for capturing a live tree for bug reports.
+    pub fn to_snapshot(&self, id: Ref) -> Option<InstanceSnapshot> {
+        let instance = self.get_instance(id)?;
+
+        Some(InstanceSnapshot {
+            snapshot_id: id,
+            metadata: instance.metadata().clone(),
+            name: instance.name().to_owned().into(),
+            class_name: instance.class_name(),
+            properties: instance.properties().clone(),
+            children: instance
+                .children()
+                .iter()
+                .filter_map(|&child| self.to_snapshot(child))
+                .collect(),
+        })
+    }
+
     pub fn get_instance(&self, id: Ref) -> Option<InstanceWithMeta<'_>> {
         if let Some(instance) = self.inner.get_by_ref(id) {
             let metadata = self.metadata_map.get(&id).unwrap();
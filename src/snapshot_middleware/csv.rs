@@ -5,7 +5,7 @@ use std::{
 };
 
 use anyhow::Context;
-use memofs::Vfs;
+use memofs::{IoResultExt as _, Vfs};
 use rbx_dom_weak::{types::Variant, ustr};
 use serde::{Deserialize, Serialize};
 
@@ -104,8 +104,12 @@ pub fn syncback_csv<'sync>(
         } else {
             anyhow::bail!("LocalizationTables must have a `Contents` property that is a String")
         };
+    let locale_order = existing_locale_order(snapshot, &snapshot.path)?;
     let mut fs_snapshot = FsSnapshot::new();
-    fs_snapshot.add_file(&snapshot.path, localization_to_csv(contents)?);
+    fs_snapshot.add_file(
+        &snapshot.path,
+        localization_to_csv(contents, &locale_order)?,
+    );
 
     let meta = AdjacentMetadata::from_syncback_snapshot(snapshot, snapshot.path.clone())?;
     if let Some(mut meta) = meta {
@@ -130,9 +134,12 @@ pub fn syncback_csv<'sync>(
             } else {
                 meta_name.to_string()
             };
+            let meta_format = snapshot.meta_format();
             fs_snapshot.add_file(
-                parent.join(format!("{}.meta.json5", meta_name)),
-                crate::json::to_vec_pretty_sorted(&meta).context("cannot serialize metadata")?,
+                parent.join(format!("{}.{}", meta_name, meta_format.extension())),
+                meta_format
+                    .serialize(&meta, snapshot.number_precision())
+                    .context("cannot serialize metadata")?,
             )
         }
     }
@@ -156,11 +163,13 @@ pub fn syncback_csv_init<'sync>(
             anyhow::bail!("LocalizationTables must have a `Contents` property that is a String")
         };
 
+    let init_path = snapshot.path.join("init.csv");
+    let locale_order = existing_locale_order(snapshot, &init_path)?;
+
     let mut dir_syncback = syncback_dir_no_meta(snapshot)?;
-    dir_syncback.fs_snapshot.add_file(
-        snapshot.path.join("init.csv"),
-        localization_to_csv(contents)?,
-    );
+    dir_syncback
+        .fs_snapshot
+        .add_file(&init_path, localization_to_csv(contents, &locale_order)?);
 
     let meta = DirectoryMetadata::from_syncback_snapshot(snapshot, snapshot.path.clone())?;
     if let Some(mut meta) = meta {
@@ -168,10 +177,14 @@ pub fn syncback_csv_init<'sync>(
         // about, so shifting is fine.
         meta.properties.shift_remove(&ustr("Contents"));
         if !meta.is_empty() {
+            let meta_format = snapshot.meta_format();
             dir_syncback.fs_snapshot.add_file(
-                snapshot.path.join("init.meta.json5"),
-                crate::json::to_vec_pretty_sorted(&meta)
-                    .context("could not serialize new init.meta.json5")?,
+                snapshot
+                    .path
+                    .join(format!("init.{}", meta_format.extension())),
+                meta_format
+                    .serialize(&meta, snapshot.number_precision())
+                    .context("could not serialize new init meta file")?,
             );
         }
     }
@@ -179,6 +192,38 @@ pub fn syncback_csv_init<'sync>(
     Ok(dir_syncback)
 }
 
+/// Columns of a localization CSV that hold entry fields rather than locales.
+const FIXED_HEADERS: [&str; 4] = ["Key", "Source", "Context", "Example"];
+
+/// Returns the locale columns of the CSV file at `path` in the order they
+/// appear in its header, if the Instance being synced back already exists
+/// there. These files are often edited in spreadsheets, so keeping their
+/// column order avoids reshuffling every row on syncback.
+fn existing_locale_order(snapshot: &SyncbackSnapshot, path: &Path) -> anyhow::Result<Vec<String>> {
+    if snapshot.old_inst().is_none() {
+        return Ok(Vec::new());
+    }
+    let Some(contents) = snapshot.vfs().read(path).with_not_found()? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(locale_headers(&contents))
+}
+
+/// Reads the locale column names from the header of a localization CSV.
+/// A header that can't be read is treated as having no locales.
+fn locale_headers(contents: &[u8]) -> Vec<String> {
+    let mut reader = csv::Reader::from_reader(contents);
+    match reader.headers() {
+        Ok(headers) => headers
+            .iter()
+            .filter(|header| !header.is_empty() && !FIXED_HEADERS.contains(header))
+            .map(str::to_owned)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
 /// Struct that holds any valid row from a Roblox CSV translation table.
 ///
 /// We manually deserialize into this table from CSV, but let serde_json handle
@@ -265,8 +310,11 @@ fn convert_localization_csv(contents: &[u8]) -> Result<String, csv::Error> {
 
 /// Takes a localization table (as a string) and converts it into a CSV file.
 ///
-/// The CSV file is ordered, so it should be deterministic.
-fn localization_to_csv(csv_contents: &str) -> anyhow::Result<Vec<u8>> {
+/// Locale columns listed in `locale_order` are written first and in that
+/// order; any other locales follow alphabetically. Locales in `locale_order`
+/// that the table no longer has are dropped. The CSV file is otherwise
+/// ordered, so it should be deterministic.
+fn localization_to_csv(csv_contents: &str, locale_order: &[String]) -> anyhow::Result<Vec<u8>> {
     let mut out = Vec::new();
     let mut writer = csv::Writer::from_writer(&mut out);
 
@@ -276,14 +324,21 @@ fn localization_to_csv(csv_contents: &str) -> anyhow::Result<Vec<u8>> {
     // TODO sort this better
     csv.sort_by(|a, b| a.source.partial_cmp(&b.source).unwrap());
 
-    let mut headers = vec!["Key", "Source", "Context", "Example"];
+    let mut headers: Vec<&str> = FIXED_HEADERS.to_vec();
     // We want both order and a lack of duplicates, so we use a BTreeSet.
-    let mut extra_headers = BTreeSet::new();
+    let mut locales = BTreeSet::new();
     for entry in &csv {
         for lang in entry.values.keys() {
-            extra_headers.insert(lang.as_ref());
+            locales.insert(lang.as_ref());
+        }
+    }
+    let mut extra_headers: Vec<&str> = Vec::with_capacity(locales.len());
+    for lang in locale_order {
+        if locales.remove(lang.as_str()) {
+            extra_headers.push(lang);
         }
     }
+    extra_headers.extend(locales);
     headers.extend(extra_headers.iter());
 
     writer
@@ -448,4 +503,32 @@ Ack,Ack!,,An exclamation of despair,¡Ay!"#,
             insta::assert_yaml_snapshot!(instance_snapshot);
         });
     }
+
+    #[test]
+    fn csv_round_trip_keeps_locale_order() {
+        let original = "Key,Source,Context,Example,en,fr,es\n\
+                        Greeting,Hello,,,Hello,Bonjour,Hola\n";
+
+        let table = convert_localization_csv(original.as_bytes()).unwrap();
+        let written = localization_to_csv(&table, &locale_headers(original.as_bytes())).unwrap();
+
+        assert_eq!(String::from_utf8(written).unwrap(), original);
+    }
+
+    #[test]
+    fn csv_new_locales_are_appended() {
+        let table = convert_localization_csv(
+            "Key,Source,Context,Example,de,es,fr,en\n\
+             Greeting,Hello,,,Hallo,Hola,Bonjour,Hello\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let order = vec!["en".to_owned(), "fr".to_owned(), "ja".to_owned()];
+        let written = String::from_utf8(localization_to_csv(&table, &order).unwrap()).unwrap();
+
+        assert_eq!(
+            written.lines().next(),
+            Some("Key,Source,Context,Example,en,fr,de,es")
+        );
+    }
 }
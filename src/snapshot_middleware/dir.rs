@@ -12,10 +12,19 @@ use crate::{
     syncback::{hash_instance, FsSnapshot, SyncbackReturn, SyncbackSnapshot},
 };
 
-use super::{meta_file::DirectoryMetadata, snapshot_from_vfs};
+use super::{meta_file::DirectoryMetadata, snapshot_from_vfs, Middleware, INIT_FILE_PRIORITY};
 
 const EMPTY_DIR_KEEP_NAME: &str = ".gitkeep";
 
+/// Directories with at least this many children have them snapshotted on the
+/// rayon pool. Below this, the cost of fanning out outweighs the work saved.
+/// The value is an estimate that hasn't been measured yet;
+/// `benches/snapshot.rs` compares a wide tree on the rayon pool and on a
+/// single thread.
+///
+/// Parallelism can be disabled entirely by setting `ATLAS_SEQUENTIAL`.
+const PARALLEL_CHILD_THRESHOLD: usize = 16;
+
 pub fn snapshot_dir(
     context: &InstanceContext,
     vfs: &Vfs,
@@ -36,6 +45,10 @@ pub fn snapshot_dir(
 /// directory's ClassName will change before metadata should be applied. For
 /// example, this can happen if the directory contains an `init.client.luau`
 /// file.
+///
+/// Children come out sorted by their file path, not in the order the file
+/// system lists them or by Instance name. `a-b.luau` comes before `a.luau`,
+/// for example, because `-` sorts before `.`.
 pub fn snapshot_dir_no_meta(
     context: &InstanceContext,
     vfs: &Vfs,
@@ -49,65 +62,35 @@ pub fn snapshot_dir_no_meta(
             .all(|rule| rule.passes(child.path()))
     };
 
+    let mut entries = Vec::new();
     let mut any_child_glob_ignored = false;
-
-    let snapshot_children = if std::env::var("ATLAS_SEQUENTIAL").is_ok() {
-        let mut children = Vec::new();
-        for entry in vfs.read_dir(path)? {
-            let entry = entry?;
-            if !passes_filter_rules(&entry) {
-                any_child_glob_ignored = true;
-                continue;
-            }
-            if let Some(child_snapshot) = snapshot_from_vfs(context, vfs, entry.path())? {
-                children.push(child_snapshot);
-            }
+    for entry in vfs.read_dir(path)? {
+        let entry = entry?;
+        if passes_filter_rules(&entry) {
+            entries.push(entry);
+        } else {
+            any_child_glob_ignored = true;
         }
-        children
-    } else {
-        use rayon::prelude::*;
-
-        let entries: Vec<_> = vfs.read_dir(path)?.filter_map(|e| e.ok()).collect();
-
-        any_child_glob_ignored = entries.iter().any(|e| !passes_filter_rules(e));
-
-        let results: Vec<anyhow::Result<Option<InstanceSnapshot>>> = entries
-            .par_iter()
-            .map(|entry| {
-                if !passes_filter_rules(entry) {
-                    return Ok(None);
-                }
-                snapshot_from_vfs(context, vfs, entry.path())
-            })
-            .collect();
+    }
+    // Backends don't agree on read_dir order, so sort here to keep the
+    // children of the resulting snapshot stable regardless of whether they
+    // were snapshotted in parallel.
+    entries.sort_by(|a, b| a.path().cmp(b.path()));
 
-        let mut children = Vec::new();
-        for result in results {
-            if let Some(snapshot) = result? {
-                children.push(snapshot);
-            }
-        }
-        children
-    };
+    let parallel =
+        entries.len() >= PARALLEL_CHILD_THRESHOLD && std::env::var("ATLAS_SEQUENTIAL").is_err();
+    let snapshot_children = snapshot_children(context, vfs, &entries, parallel)?;
 
     let normalized_path = path.to_path_buf();
-    let relevant_paths = vec![
-        normalized_path.clone(),
-        // TODO: We shouldn't need to know about Lua existing in this
-        // middleware. Should we figure out a way for that function to add
-        // relevant paths to this middleware?
-        // Modern extensions (preferred)
-        normalized_path.join("init.luau"),
-        normalized_path.join("init.server.luau"),
-        normalized_path.join("init.client.luau"),
-        normalized_path.join("init.local.luau"),
-        normalized_path.join("init.legacy.luau"),
-        normalized_path.join("init.csv"),
-        // Legacy extensions (for backwards compatibility)
-        normalized_path.join("init.lua"),
-        normalized_path.join("init.server.lua"),
-        normalized_path.join("init.client.lua"),
-    ];
+    // Creating any init file turns this directory into a different instance,
+    // so all of them are relevant.
+    let mut relevant_paths = vec![normalized_path.clone()];
+    relevant_paths.extend(
+        INIT_FILE_PRIORITY
+            .iter()
+            .filter(|(middleware, _)| *middleware != Middleware::Project)
+            .map(|(_, name)| normalized_path.join(name)),
+    );
 
     let snapshot = InstanceSnapshot::new()
         .name(name)
@@ -124,6 +107,41 @@ pub fn snapshot_dir_no_meta(
     Ok(Some(snapshot))
 }
 
+/// Snapshots each of `entries`, in order, skipping any that don't produce an
+/// Instance.
+///
+/// In parallel mode every child is snapshotted on the rayon pool. `Vfs` only
+/// holds its lock for the duration of a single read, so workers contend for
+/// it briefly per file rather than for a whole subtree.
+fn snapshot_children(
+    context: &InstanceContext,
+    vfs: &Vfs,
+    entries: &[DirEntry],
+    parallel: bool,
+) -> anyhow::Result<Vec<InstanceSnapshot>> {
+    let results: Vec<anyhow::Result<Option<InstanceSnapshot>>> = if parallel {
+        use rayon::prelude::*;
+
+        entries
+            .par_iter()
+            .map(|entry| snapshot_from_vfs(context, vfs, entry.path()))
+            .collect()
+    } else {
+        entries
+            .iter()
+            .map(|entry| snapshot_from_vfs(context, vfs, entry.path()))
+            .collect()
+    };
+
+    let mut children = Vec::with_capacity(results.len());
+    for result in results {
+        if let Some(snapshot) = result? {
+            children.push(snapshot);
+        }
+    }
+    Ok(children)
+}
+
 pub fn syncback_dir<'sync>(
     snapshot: &SyncbackSnapshot<'sync>,
 ) -> anyhow::Result<SyncbackReturn<'sync>> {
@@ -138,10 +156,14 @@ pub fn syncback_dir<'sync>(
         }
 
         if !meta.is_empty() {
+            let meta_format = snapshot.meta_format();
             dir_syncback.fs_snapshot.add_file(
-                snapshot.path.join("init.meta.json5"),
-                crate::json::to_vec_pretty_sorted(&meta)
-                    .context("could not serialize new init.meta.json5")?,
+                snapshot
+                    .path
+                    .join(format!("init.{}", meta_format.extension())),
+                meta_format
+                    .serialize(&meta, snapshot.number_precision())
+                    .context("could not serialize new init meta file")?,
             );
         }
     }
@@ -459,6 +481,68 @@ mod test {
         }
     }
 
+    #[test]
+    fn parallel_and_sequential_children_are_identical() {
+        let children: Vec<_> = (0..PARALLEL_CHILD_THRESHOLD * 4)
+            .map(|i| match i % 4 {
+                0 => (
+                    format!("dir_{i:03}"),
+                    VfsSnapshot::dir([
+                        ("init.luau", VfsSnapshot::file(format!("return {i}"))),
+                        ("child.server.luau", VfsSnapshot::file("-- server")),
+                    ]),
+                ),
+                1 => (
+                    format!("value_{i:03}.txt"),
+                    VfsSnapshot::file(format!("text {i}")),
+                ),
+                _ => (
+                    format!("module_{i:03}.luau"),
+                    VfsSnapshot::file(format!("return {i}")),
+                ),
+            })
+            .collect();
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/wide", VfsSnapshot::dir(children))
+            .unwrap();
+        let vfs = Vfs::new(imfs);
+
+        let context = InstanceContext::default();
+        let entries: Vec<_> = vfs.read_dir("/wide").unwrap().map(Result::unwrap).collect();
+
+        let parallel = snapshot_children(&context, &vfs, &entries, true).unwrap();
+        let sequential = snapshot_children(&context, &vfs, &entries, false).unwrap();
+
+        assert_eq!(parallel.len(), PARALLEL_CHILD_THRESHOLD * 4);
+        assert_eq!(
+            serde_json::to_vec(&parallel).unwrap(),
+            serde_json::to_vec(&sequential).unwrap()
+        );
+    }
+
+    #[test]
+    fn children_are_sorted_by_file_path() {
+        let dir = tempfile::tempdir().unwrap();
+        // Created out of order, so the file system has no reason to list them
+        // sorted.
+        for name in ["c.txt", "a.luau", "B.luau", "a-b.luau"] {
+            fs_err::write(dir.path().join(name), "").unwrap();
+        }
+
+        let snap = snapshot_dir(
+            &InstanceContext::default(),
+            &Vfs::new_default(),
+            dir.path(),
+            "root",
+        )
+        .unwrap()
+        .unwrap();
+
+        let names: Vec<&str> = snap.children.iter().map(|c| c.name.as_ref()).collect();
+        assert_eq!(names, ["B", "a-b", "a", "c"]);
+    }
+
     #[test]
     fn parallel_deep_wide_tree() {
         fn make_tree(depth: usize, width: usize) -> VfsSnapshot {
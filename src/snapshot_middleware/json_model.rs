@@ -1,4 +1,4 @@
-use std::{borrow::Cow, path::Path, str};
+use std::{borrow::Cow, collections::HashMap, path::Path, str};
 
 use anyhow::Context;
 use indexmap::IndexMap;
@@ -17,6 +17,11 @@ use crate::{
     RojoRef,
 };
 
+use super::dir::{snapshot_dir_no_meta, syncback_dir_no_meta};
+
+/// The init file of a JSON model directory.
+const INIT_FILE_NAME: &str = "init.model.json5";
+
 pub fn snapshot_json_model(
     context: &InstanceContext,
     vfs: &Vfs,
@@ -47,7 +52,7 @@ pub fn snapshot_json_model(
     let schema = instance.schema.take();
 
     let mut snapshot = instance
-        .into_snapshot()
+        .into_snapshot(context.ignore_unknown_properties)
         .with_context(|| format!("Could not load JSON model: {}", path.display()))?;
 
     snapshot.metadata = snapshot
@@ -62,6 +67,65 @@ pub fn snapshot_json_model(
     Ok(Some(snapshot))
 }
 
+/// Snapshots a JSON model directory: a directory with an `init.model.json5`
+/// file describing the Instance itself, and a file or directory for each of
+/// its children. Children are ordered by the model's `manifest`, which lists
+/// their file names. Any that aren't listed come after, in path order.
+pub fn snapshot_json_model_init(
+    context: &InstanceContext,
+    vfs: &Vfs,
+    init_path: &Path,
+    name: &str,
+) -> anyhow::Result<Option<InstanceSnapshot>> {
+    let folder_path = init_path.parent().unwrap();
+    let dir_snapshot = snapshot_dir_no_meta(context, vfs, folder_path, name)?.unwrap();
+
+    let contents = vfs.read(init_path)?;
+    let mut instance: JsonModel = json::from_slice_with_context(&contents, || {
+        format!("File is not a valid JSON model: {}", init_path.display())
+    })?;
+
+    let specified_name = instance.name.clone();
+    if instance.name.is_none() {
+        instance.name = Some(dir_snapshot.name.to_string());
+    }
+
+    let id = instance.id.take().map(RojoRef::new);
+    let schema = instance.schema.take();
+    let manifest = std::mem::take(&mut instance.manifest);
+
+    let mut snapshot = instance
+        .into_snapshot(context.ignore_unknown_properties)
+        .with_context(|| format!("Could not load JSON model: {}", init_path.display()))?;
+
+    let mut children = dir_snapshot.children;
+    let position = |child: &InstanceSnapshot| {
+        child
+            .metadata
+            .relevant_paths
+            .first()
+            .and_then(|path| path.file_name())
+            .and_then(|file_name| {
+                manifest
+                    .iter()
+                    .position(|entry| file_name == entry.as_str())
+            })
+            .unwrap_or(manifest.len())
+    };
+    children.sort_by_cached_key(position);
+    snapshot.children.extend(children);
+
+    let mut metadata = dir_snapshot.metadata;
+    metadata.relevant_paths.push(init_path.to_path_buf());
+    snapshot.metadata = metadata
+        .instigating_source(init_path)
+        .specified_id(id)
+        .schema(schema)
+        .specified_name(specified_name);
+
+    Ok(Some(snapshot))
+}
+
 pub fn syncback_json_model<'sync>(
     snapshot: &SyncbackSnapshot<'sync>,
 ) -> anyhow::Result<SyncbackReturn<'sync>> {
@@ -86,7 +150,12 @@ pub fn syncback_json_model<'sync>(
         model.name = Some(snapshot.new_inst().name.clone());
     }
 
-    let serialized = match crate::json::to_vec_pretty_sorted(&model) {
+    let serialized = if snapshot.stable_property_order() {
+        crate::json::to_vec_pretty_sorted_rounded(&model, snapshot.number_precision())
+    } else {
+        crate::json::to_vec_pretty_rounded(&model, snapshot.number_precision())
+    };
+    let serialized = match serialized {
         Ok(bytes) => bytes,
         Err(e) => {
             // Provide more detail about what's in the model
@@ -117,14 +186,92 @@ pub fn syncback_json_model<'sync>(
     })
 }
 
+pub fn syncback_json_model_init<'sync>(
+    snapshot: &SyncbackSnapshot<'sync>,
+) -> anyhow::Result<SyncbackReturn<'sync>> {
+    let new_inst = snapshot.new_inst();
+    let mut dir_syncback = syncback_dir_no_meta(snapshot)?;
+
+    let mut property_buffer = Vec::with_capacity(new_inst.properties.len());
+    let mut model = json_model_without_children(snapshot, &mut property_buffer, snapshot.new);
+    model.name = None;
+    if let Some(old_inst) = snapshot.old_inst() {
+        model.schema = old_inst.metadata().schema.clone();
+        model.name = old_inst.metadata().specified_name.clone();
+    }
+    if model.name.is_none() && snapshot.needs_meta_name {
+        model.name = Some(new_inst.name.clone());
+    }
+
+    // The children are written by the syncback loop, which doesn't keep
+    // their order, so the manifest records it.
+    let order: HashMap<Ref, usize> = new_inst
+        .children()
+        .iter()
+        .enumerate()
+        .map(|(index, child)| (*child, index))
+        .collect();
+    let mut children: Vec<_> = dir_syncback
+        .children
+        .iter()
+        .filter_map(|child| {
+            let file_name = child.path.file_name()?.to_str()?;
+            Some((order.get(&child.new), file_name.to_owned()))
+        })
+        .collect();
+    children.sort();
+    model.manifest = children
+        .into_iter()
+        .map(|(_, file_name)| file_name)
+        .collect();
+
+    let serialized = if snapshot.stable_property_order() {
+        crate::json::to_vec_pretty_sorted_rounded(&model, snapshot.number_precision())
+    } else {
+        crate::json::to_vec_pretty_rounded(&model, snapshot.number_precision())
+    }
+    .with_context(|| {
+        format!(
+            "failed to serialize new JSON Model for class '{}'",
+            model.class_name
+        )
+    })?;
+    dir_syncback
+        .fs_snapshot
+        .add_file(snapshot.path.join(INIT_FILE_NAME), serialized);
+
+    Ok(dir_syncback)
+}
+
 fn json_model_from_pair<'sync>(
     snapshot: &SyncbackSnapshot<'sync>,
     prop_buffer: &mut Vec<(Ustr, &'sync Variant)>,
     new: Ref,
 ) -> JsonModel {
+    let mut model = json_model_without_children(snapshot, prop_buffer, new);
+
     let new_inst = snapshot
         .get_new_instance(new)
         .expect("all new referents passed to json_model_from_pair should exist");
+    model.children.reserve(new_inst.children().len());
+    for new_child_ref in new_inst.children() {
+        model
+            .children
+            .push(json_model_from_pair(snapshot, prop_buffer, *new_child_ref))
+    }
+
+    model
+}
+
+/// Builds the JSON model for just the Instance `new`, without its children.
+fn json_model_without_children<'sync>(
+    snapshot: &SyncbackSnapshot<'sync>,
+    prop_buffer: &mut Vec<(Ustr, &'sync Variant)>,
+    new: Ref,
+) -> JsonModel {
+    let new_inst = snapshot
+        .get_new_instance(new)
+        .expect("all new referents passed to json_model_without_children should exist");
 
     snapshot.filter_properties_cached(new_inst, prop_buffer);
 
@@ -154,20 +301,15 @@ fn json_model_from_pair<'sync>(
         }
     }
 
-    let mut children = Vec::with_capacity(new_inst.children().len());
-
-    for new_child_ref in new_inst.children() {
-        children.push(json_model_from_pair(snapshot, prop_buffer, *new_child_ref))
-    }
-
     JsonModel {
         name: Some(new_inst.name.clone()),
         class_name: new_inst.class,
-        children,
+        children: Vec::new(),
         properties,
         attributes,
         id: None,
         schema: None,
+        manifest: Vec::new(),
     }
 }
 
@@ -202,22 +344,30 @@ struct JsonModel {
 
     #[serde(default = "IndexMap::new", skip_serializing_if = "IndexMap::is_empty")]
     attributes: IndexMap<String, UnresolvedValue>,
+
+    /// The file names of the children of a JSON model directory, in order.
+    /// Only used in `init.model.json5` files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    manifest: Vec<String>,
 }
 
 impl JsonModel {
-    fn into_snapshot(self) -> anyhow::Result<InstanceSnapshot> {
+    fn into_snapshot(self, ignore_unknown_properties: bool) -> anyhow::Result<InstanceSnapshot> {
         let name = self.name.unwrap_or_else(|| self.class_name.to_owned());
         let class_name = self.class_name;
 
         let mut children = Vec::with_capacity(self.children.len());
         for child in self.children {
-            children.push(child.into_snapshot()?);
+            children.push(child.into_snapshot(ignore_unknown_properties)?);
         }
 
         let mut properties = UstrMap::with_capacity(self.properties.len());
         for (key, unresolved) in self.properties {
-            let value = unresolved.resolve(&class_name, &key)?;
-            properties.insert(key, value);
+            if let Some(value) =
+                unresolved.resolve_property(&class_name, &key, ignore_unknown_properties)?
+            {
+                properties.insert(key, value);
+            }
         }
 
         if !self.attributes.is_empty() {
@@ -323,4 +473,58 @@ mod test {
 
         insta::assert_yaml_snapshot!(instance_snapshot);
     }
+
+    fn model_with_properties(order: &[(&str, Variant)]) -> JsonModel {
+        let mut properties = IndexMap::new();
+        for (name, value) in order {
+            properties.insert(
+                Ustr::from(*name),
+                UnresolvedValue::from_variant(value.clone(), "Part", name),
+            );
+        }
+        let mut attributes = IndexMap::new();
+        for (name, value) in order.iter().rev() {
+            attributes.insert(
+                name.to_string(),
+                UnresolvedValue::from_variant_unambiguous(value.clone()),
+            );
+        }
+
+        JsonModel {
+            schema: None,
+            name: None,
+            class_name: Ustr::from("Part"),
+            id: None,
+            children: Vec::new(),
+            properties,
+            attributes,
+            manifest: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn stable_order_ignores_property_iteration_order() {
+        let props = [
+            ("Transparency", Variant::Float32(0.5)),
+            ("Anchored", Variant::Bool(true)),
+            ("Name", Variant::String("Handle".into())),
+            ("CanCollide", Variant::Bool(false)),
+        ];
+        let mut reversed = props.clone();
+        reversed.reverse();
+
+        let first = model_with_properties(&props);
+        let second = model_with_properties(&reversed);
+
+        assert_eq!(
+            json::to_vec_pretty_sorted(&first).unwrap(),
+            json::to_vec_pretty_sorted(&second).unwrap(),
+        );
+
+        // Without stable ordering the property order leaks into the output.
+        assert_ne!(
+            json::to_vec_pretty(&first).unwrap(),
+            json::to_vec_pretty(&second).unwrap(),
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use std::{path::Path, str};
+use std::{
+    path::{Component, Path, PathBuf},
+    str,
+};
 
 use anyhow::Context as _;
 use memofs::Vfs;
@@ -18,7 +21,7 @@ use super::{
     PathExt as _,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScriptType {
     Server, // Script + RunContext.Server
     Client, // Script + RunContext.Client
@@ -65,6 +68,9 @@ pub fn snapshot_lua(
         );
     }
 
+    let mut relevant_paths = vec![path.to_path_buf()];
+    relevant_paths.extend(associated_paths(path, contents_str));
+
     let mut snapshot = InstanceSnapshot::new()
         .name(name)
         .class_name(class_name)
@@ -72,7 +78,7 @@ pub fn snapshot_lua(
         .metadata(
             InstanceMetadata::new()
                 .instigating_source(path)
-                .relevant_paths(vec![path.to_path_buf()])
+                .relevant_paths(relevant_paths)
                 .context(context),
         );
 
@@ -114,9 +120,16 @@ pub fn snapshot_lua_init(
     // Preserve the init script's instigating_source (the actual file path)
     // before copying the directory's metadata (which has the folder path)
     let script_instigating_source = init_snapshot.metadata.instigating_source.take();
+    // Files associated through the script's header aren't known to the
+    // directory, so carry them over too.
+    let associated = match init_snapshot.properties.get(&ustr("Source")) {
+        Some(Variant::String(source)) => associated_paths(init_path, source),
+        _ => Vec::new(),
+    };
 
     init_snapshot.children = dir_snapshot.children;
     init_snapshot.metadata = dir_snapshot.metadata;
+    init_snapshot.metadata.relevant_paths.extend(associated);
 
     // Restore the init script's instigating_source so two-way sync writes
     // to the actual file (e.g., init.luau) instead of the directory
@@ -127,6 +140,66 @@ pub fn snapshot_lua_init(
     Ok(Some(init_snapshot))
 }
 
+/// The comment directive scripts use to declare files that belong with them.
+const ASSOCIATE_DIRECTIVE: &str = "@associate";
+
+/// Returns the files a script declares as associated with it through
+/// `-- @associate <path>` comments in its header, resolved relative to the
+/// script's directory.
+///
+/// The header is the run of comment lines at the top of the file; parsing
+/// stops at the first line of code. These paths are tracked alongside the
+/// script so tools like the sourcemap can list them under its Instance.
+fn associated_paths(path: &Path, source: &str) -> Vec<PathBuf> {
+    let Some(parent) = path.parent() else {
+        return Vec::new();
+    };
+
+    let mut paths = Vec::new();
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix("--") else {
+            break;
+        };
+        if comment.starts_with("[[") || comment.starts_with("[=") {
+            break;
+        }
+        let Some(associated) = comment.trim_start().strip_prefix(ASSOCIATE_DIRECTIVE) else {
+            continue;
+        };
+        if !associated.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let associated = associated.trim();
+        if !associated.is_empty() {
+            paths.push(normalize_path(&parent.join(associated)));
+        }
+    }
+
+    paths
+}
+
+/// Resolves `.` and `..` components in `path` without touching the file
+/// system, so associated paths like `../data/Config.json` display cleanly.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
 pub fn syncback_lua<'sync>(
     snapshot: &SyncbackSnapshot<'sync>,
 ) -> anyhow::Result<SyncbackReturn<'sync>> {
@@ -146,7 +219,7 @@ pub fn syncback_lua<'sync>(
         // is fine.
         meta.properties.shift_remove(&ustr("Source"));
 
-        if !meta.is_empty() {
+        if !meta.is_empty() || snapshot.always_emit_meta() {
             let parent_location = snapshot.path.parent_err()?;
             // Use the base name from the script path, stripping the script type suffix
             // (e.g., "Foo" from "Foo.server.luau") to match how AdjacentMetadata::read_and_apply_all
@@ -173,9 +246,12 @@ pub fn syncback_lua<'sync>(
             } else {
                 meta_name.to_string()
             };
+            let meta_format = snapshot.meta_format();
             fs_snapshot.add_file(
-                parent_location.join(format!("{}.meta.json5", meta_name)),
-                crate::json::to_vec_pretty_sorted(&meta).context("cannot serialize metadata")?,
+                parent_location.join(format!("{}.{}", meta_name, meta_format.extension())),
+                meta_format
+                    .serialize(&meta, snapshot.number_precision())
+                    .context("cannot serialize metadata")?,
             );
         }
     }
@@ -217,11 +293,15 @@ pub fn syncback_lua_init<'sync>(
         // is fine.
         meta.properties.shift_remove(&ustr("Source"));
 
-        if !meta.is_empty() {
+        if !meta.is_empty() || snapshot.always_emit_meta() {
+            let meta_format = snapshot.meta_format();
             dir_syncback.fs_snapshot.add_file(
-                snapshot.path.join("init.meta.json5"),
-                crate::json::to_vec_pretty_sorted(&meta)
-                    .context("could not serialize new init.meta.json5")?,
+                snapshot
+                    .path
+                    .join(format!("init.{}", meta_format.extension())),
+                meta_format
+                    .serialize(&meta, snapshot.number_precision())
+                    .context("could not serialize new init meta file")?,
             );
         }
     }
@@ -534,4 +614,54 @@ mod test {
             insta::assert_yaml_snapshot!(instance_snapshot);
         });
     }
+
+    #[test]
+    fn associated_paths_from_header() {
+        let source = "--!strict\n\
+                      -- @associate Config.data\n\
+                      --   @associate ../shared/Items.csv  \n\
+                      -- @associated NotADirective.txt\n\
+                      \n\
+                      local x = 1\n\
+                      -- @associate AfterCode.data\n";
+
+        assert_eq!(
+            associated_paths(Path::new("/project/src/Config.luau"), source),
+            vec![
+                PathBuf::from("/project/src/Config.data"),
+                PathBuf::from("/project/shared/Items.csv"),
+            ]
+        );
+    }
+
+    #[test]
+    fn associated_paths_are_relevant() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/foo.luau",
+            VfsSnapshot::file("-- @associate foo.data\nreturn nil"),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        let instance_snapshot = snapshot_lua(
+            &InstanceContext::new(),
+            &vfs,
+            Path::new("/foo.luau"),
+            "foo",
+            ScriptType::Module,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            instance_snapshot.metadata.relevant_paths,
+            vec![
+                PathBuf::from("/foo.luau"),
+                PathBuf::from("/foo.data"),
+                PathBuf::from("/foo.meta.json5"),
+            ]
+        );
+    }
 }
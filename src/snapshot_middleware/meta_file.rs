@@ -7,7 +7,7 @@ use rbx_dom_weak::{
     types::{Attributes, Variant},
     Ustr,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
     json, resolution::UnresolvedValue, snapshot::InstanceSnapshot, syncback::SyncbackSnapshot,
@@ -70,6 +70,7 @@ impl AdjacentMetadata {
         // Try modern extension first, then fall back to legacy
         let meta_path_json5 = path.with_file_name(format!("{base_name}.meta.json5"));
         let meta_path_json = path.with_file_name(format!("{base_name}.meta.json"));
+        let meta_path_toml = path.with_file_name(format!("{base_name}.meta.toml"));
 
         if let Some(meta_contents) = vfs.read(&meta_path_json5).with_not_found()? {
             let mut metadata = Self::from_slice(&meta_contents, meta_path_json5.clone())?;
@@ -78,6 +79,10 @@ impl AdjacentMetadata {
             // Legacy fallback: .meta.json
             let mut metadata = Self::from_slice(&meta_contents, meta_path_json.clone())?;
             metadata.apply_all(snapshot)?;
+        } else if let Some(meta_contents) = vfs.read(&meta_path_toml).with_not_found()? {
+            let mut metadata = Self::from_slice(&meta_contents, meta_path_toml.clone())?;
+            metadata.apply_all(snapshot)?;
+            snapshot.metadata.relevant_paths.push(meta_path_toml);
         }
 
         // Rather than pushing these in the snapshot middleware, we can just do it here.
@@ -87,9 +92,9 @@ impl AdjacentMetadata {
     }
 
     fn from_slice(slice: &[u8], path: PathBuf) -> anyhow::Result<Self> {
-        let mut meta: Self = json::from_slice_with_context(slice, || {
+        let mut meta: Self = parse_meta_file(slice, &path, || {
             format!(
-                "File contained malformed .meta.json5 data: {}",
+                "File contained malformed meta file data: {}",
                 path.display()
             )
         })?;
@@ -201,10 +206,16 @@ impl AdjacentMetadata {
         // very cheap.
         for (key, unresolved) in std::mem::take(&mut self.properties) {
             let value = unresolved
-                .resolve(&snapshot.class_name, &key)
+                .resolve_property(
+                    &snapshot.class_name,
+                    &key,
+                    snapshot.metadata.context.ignore_unknown_properties,
+                )
                 .with_context(|| format!("error applying meta file {}", path.display()))?;
 
-            snapshot.properties.insert(key, value);
+            if let Some(value) = value {
+                snapshot.properties.insert(key, value);
+            }
         }
 
         if !self.attributes.is_empty() {
@@ -329,6 +340,7 @@ impl DirectoryMetadata {
         // Try modern extension first, then fall back to legacy
         let meta_path_json5 = path.join("init.meta.json5");
         let meta_path_json = path.join("init.meta.json");
+        let meta_path_toml = path.join("init.meta.toml");
 
         if let Some(meta_contents) = vfs.read(&meta_path_json5).with_not_found()? {
             let mut metadata = Self::from_slice(&meta_contents, meta_path_json5.clone())?;
@@ -337,6 +349,10 @@ impl DirectoryMetadata {
             // Legacy fallback: init.meta.json
             let mut metadata = Self::from_slice(&meta_contents, meta_path_json.clone())?;
             metadata.apply_all(snapshot)?;
+        } else if let Some(meta_contents) = vfs.read(&meta_path_toml).with_not_found()? {
+            let mut metadata = Self::from_slice(&meta_contents, meta_path_toml.clone())?;
+            metadata.apply_all(snapshot)?;
+            snapshot.metadata.relevant_paths.push(meta_path_toml);
         }
 
         // Rather than pushing these in the snapshot middleware, we can just do it here.
@@ -346,9 +362,9 @@ impl DirectoryMetadata {
     }
 
     fn from_slice(slice: &[u8], path: PathBuf) -> anyhow::Result<Self> {
-        let mut meta: Self = json::from_slice_with_context(slice, || {
+        let mut meta: Self = parse_meta_file(slice, &path, || {
             format!(
-                "File contained malformed init.meta.json5 data: {}",
+                "File contained malformed init meta file data: {}",
                 path.display()
             )
         })?;
@@ -488,10 +504,16 @@ impl DirectoryMetadata {
 
         for (key, unresolved) in std::mem::take(&mut self.properties) {
             let value = unresolved
-                .resolve(&snapshot.class_name, &key)
+                .resolve_property(
+                    &snapshot.class_name,
+                    &key,
+                    snapshot.metadata.context.ignore_unknown_properties,
+                )
                 .with_context(|| format!("error applying meta file {}", path.display()))?;
 
-            snapshot.properties.insert(key, value);
+            if let Some(value) = value {
+                snapshot.properties.insert(key, value);
+            }
         }
 
         if !self.attributes.is_empty() {
@@ -581,6 +603,12 @@ pub fn dir_meta(vfs: &Vfs, path: &Path) -> anyhow::Result<Option<DirectoryMetada
         return Ok(Some(metadata));
     }
 
+    let meta_path_toml = path.join("init.meta.toml");
+    if let Some(meta_contents) = vfs.read(&meta_path_toml).with_not_found()? {
+        let metadata = DirectoryMetadata::from_slice(&meta_contents, meta_path_toml)?;
+        return Ok(Some(metadata));
+    }
+
     Ok(None)
 }
 
@@ -605,9 +633,36 @@ pub fn file_meta(vfs: &Vfs, path: &Path, name: &str) -> anyhow::Result<Option<Ad
         return Ok(Some(metadata));
     }
 
+    let mut meta_path_toml = path.with_file_name(name);
+    meta_path_toml.set_extension("meta.toml");
+    if let Some(meta_contents) = vfs.read(&meta_path_toml).with_not_found()? {
+        let metadata = AdjacentMetadata::from_slice(&meta_contents, meta_path_toml)?;
+        return Ok(Some(metadata));
+    }
+
     Ok(None)
 }
 
+/// Parses the contents of a meta file, as TOML if `path` is a `.toml` file
+/// and as JSON5 otherwise.
+fn parse_meta_file<T: DeserializeOwned>(
+    slice: &[u8],
+    path: &Path,
+    context: impl Fn() -> String,
+) -> anyhow::Result<T> {
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        let text = std::str::from_utf8(slice)
+            .with_context(|| format!("{}: File is not valid UTF-8", context()))?;
+        toml::from_str(text).with_context(context)
+    } else if slice.iter().all(u8::is_ascii_whitespace) {
+        // An empty meta file has no metadata in it rather than being
+        // malformed, so tools can create placeholder meta files.
+        json::from_slice_with_context(b"{}", context)
+    } else {
+        json::from_slice_with_context(slice, context)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use memofs::{InMemoryFs, VfsSnapshot};
@@ -649,4 +704,46 @@ mod test {
 
         insta::assert_yaml_snapshot!(snapshot);
     }
+
+    #[test]
+    fn adjacent_read_toml() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/foo/bar.meta.toml",
+            VfsSnapshot::file("id = \"manually specified\"\nignoreUnknownInstances = true\n"),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        let path = Path::new("/foo/bar.rojo");
+        let mut snapshot = InstanceSnapshot::new();
+
+        AdjacentMetadata::read_and_apply_all(&vfs, path, "bar", &mut snapshot).unwrap();
+
+        assert_eq!(
+            snapshot.metadata.specified_id,
+            Some(RojoRef::new("manually specified".to_owned()))
+        );
+        assert!(snapshot.metadata.ignore_unknown_instances);
+        assert!(snapshot
+            .metadata
+            .relevant_paths
+            .contains(&PathBuf::from("/foo/bar.meta.toml")));
+    }
+
+    #[test]
+    fn adjacent_read_empty() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/foo/bar.meta.json5", VfsSnapshot::file(""))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        let path = Path::new("/foo/bar.rojo");
+        let mut snapshot = InstanceSnapshot::new();
+
+        AdjacentMetadata::read_and_apply_all(&vfs, path, "bar", &mut snapshot).unwrap();
+
+        assert!(snapshot.properties.is_empty());
+        assert_eq!(snapshot.metadata.specified_id, None);
+    }
 }
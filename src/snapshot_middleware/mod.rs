@@ -41,14 +41,17 @@ use self::{
     csv::{snapshot_csv, snapshot_csv_init, syncback_csv, syncback_csv_init},
     dir::{snapshot_dir, syncback_dir},
     json::snapshot_json,
-    json_model::{snapshot_json_model, syncback_json_model},
+    json_model::{
+        snapshot_json_model, snapshot_json_model_init, syncback_json_model,
+        syncback_json_model_init,
+    },
     lua::{snapshot_lua, snapshot_lua_init, syncback_lua, syncback_lua_init},
     project::{snapshot_project, syncback_project},
     rbxm::{snapshot_rbxm, syncback_rbxm},
     rbxmx::{snapshot_rbxmx, syncback_rbxmx},
     toml::snapshot_toml,
     txt::{snapshot_txt, syncback_txt},
-    yaml::snapshot_yaml,
+    yaml::{snapshot_yaml, snapshot_yaml_string},
 };
 
 pub use self::{lua::ScriptType, project::snapshot_project_node, util::PathExt};
@@ -95,14 +98,9 @@ pub fn snapshot_from_vfs(
             .and_then(|n| n.to_str())
             .with_context(|| format!("file name of {} is invalid", path.display()))?;
 
-        // TODO: Is this even necessary anymore?
-        match file_name {
-            // Modern extensions
-            "init.server.luau" | "init.client.luau" | "init.local.luau" | "init.legacy.luau"
-            | "init.plugin.luau" | "init.luau" | "init.csv" |
-            // Legacy extensions (for backwards compatibility)
-            "init.server.lua" | "init.client.lua" | "init.lua" => return Ok(None),
-            _ => {}
+        // Init files are snapshotted as part of their parent directory.
+        if is_init_file_name(file_name) {
+            return Ok(None);
         }
 
         snapshot_from_path(context, vfs, path)
@@ -126,6 +124,8 @@ pub static INIT_FILE_PRIORITY: &[(Middleware, &str)] = &[
     (Middleware::LocalScriptDir, "init.local.luau"),
     (Middleware::LegacyScriptDir, "init.legacy.luau"),
     (Middleware::CsvDir, "init.csv"),
+    (Middleware::JsonModelDir, "init.model.json5"),
+    (Middleware::JsonModelDir, "init.model.json"),
     // Legacy extensions (for backwards compatibility)
     // init.server.lua → Script with RunContext.Legacy (old emitLegacyScripts behavior)
     // init.client.lua → LocalScript (old emitLegacyScripts behavior)
@@ -134,6 +134,29 @@ pub static INIT_FILE_PRIORITY: &[(Middleware, &str)] = &[
     (Middleware::LocalScriptDir, "init.client.lua"),
 ];
 
+/// Returns whether `file_name` is one of the non-project init files in
+/// [`INIT_FILE_PRIORITY`].
+pub fn is_init_file_name(file_name: &str) -> bool {
+    INIT_FILE_PRIORITY
+        .iter()
+        .any(|(middleware, name)| *middleware != Middleware::Project && *name == file_name)
+}
+
+/// Finds the init script for a directory, if it has one, along with the kind
+/// of script it becomes. This follows the same priority as directory
+/// snapshotting, so e.g. a directory containing both `init.luau` and
+/// `init.server.luau` resolves to `init.luau`.
+///
+/// Returns `None` for directories without an init script, including ones
+/// that are projects, `init.csv` or `init.model.json5` directories, or that
+/// only contain an `init.meta.*` file.
+pub fn init_file_for_dir(vfs: &Vfs, dir: &Path) -> anyhow::Result<Option<(PathBuf, ScriptType)>> {
+    let (middleware, _, init_path) = get_dir_middleware(vfs, dir)?;
+    Ok(middleware
+        .script_type()
+        .map(|script_type| (init_path, script_type)))
+}
+
 /// Gets the appropriate middleware for a directory by checking for `init`
 /// files. This uses an intrinsic priority list and for compatibility,
 /// that order should be left unchanged.
@@ -234,6 +257,10 @@ pub enum Middleware {
     Toml,
     Text,
     Yaml,
+    /// A YAML file as a `StringValue` holding its text, instead of the
+    /// ModuleScript `Yaml` makes. Only used through sync rules.
+    YamlString,
+    Markdown,
     Ignore,
 
     #[serde(skip_deserializing)]
@@ -252,6 +279,8 @@ pub enum Middleware {
     LegacyScriptDir,
     #[serde(skip_deserializing)]
     CsvDir,
+    #[serde(skip_deserializing)]
+    JsonModelDir,
 }
 
 impl Middleware {
@@ -280,6 +309,9 @@ impl Middleware {
             Self::Toml => snapshot_toml(context, vfs, path, name),
             Self::Text => snapshot_txt(context, vfs, path, name),
             Self::Yaml => snapshot_yaml(context, vfs, path, name),
+            Self::YamlString => snapshot_yaml_string(context, vfs, path, name),
+            // Markdown is plain text as far as a StringValue is concerned.
+            Self::Markdown => snapshot_txt(context, vfs, path, name),
             Self::Ignore => Ok(None),
 
             Self::Dir => snapshot_dir(context, vfs, path, name),
@@ -300,6 +332,7 @@ impl Middleware {
                 snapshot_lua_init(context, vfs, path, name, ScriptType::Legacy)
             }
             Self::CsvDir => snapshot_csv_init(context, vfs, path, name),
+            Self::JsonModelDir => snapshot_json_model_init(context, vfs, path, name),
         };
         if let Ok(Some(ref mut snapshot)) = output {
             snapshot.metadata.middleware = Some(*self);
@@ -337,6 +370,8 @@ impl Middleware {
             Middleware::Toml => anyhow::bail!("cannot syncback Toml middleware"),
             Middleware::Text => syncback_txt(snapshot),
             Middleware::Yaml => anyhow::bail!("cannot syncback Yaml middleware"),
+            Middleware::YamlString => anyhow::bail!("cannot syncback YamlString middleware"),
+            Middleware::Markdown => syncback_txt(snapshot),
             Middleware::Ignore => anyhow::bail!("cannot syncback Ignore middleware"),
             Middleware::Dir => syncback_dir(snapshot),
             Middleware::ServerScriptDir => syncback_lua_init(ScriptType::Server, snapshot),
@@ -346,6 +381,7 @@ impl Middleware {
             Middleware::LegacyScriptDir => syncback_lua_init(ScriptType::Legacy, snapshot),
             Middleware::PluginScriptDir => syncback_lua_init(ScriptType::Plugin, snapshot),
             Middleware::CsvDir => syncback_csv_init(snapshot),
+            Middleware::JsonModelDir => syncback_json_model_init(snapshot),
         }
     }
 
@@ -370,6 +406,20 @@ impl Middleware {
         )
     }
 
+    /// Returns the kind of script this middleware produces from an init file,
+    /// or `None` if it isn't a script directory middleware.
+    pub fn script_type(&self) -> Option<ScriptType> {
+        match self {
+            Self::ServerScriptDir => Some(ScriptType::Server),
+            Self::ClientScriptDir => Some(ScriptType::Client),
+            Self::ModuleScriptDir => Some(ScriptType::Module),
+            Self::PluginScriptDir => Some(ScriptType::Plugin),
+            Self::LocalScriptDir => Some(ScriptType::Local),
+            Self::LegacyScriptDir => Some(ScriptType::Legacy),
+            _ => None,
+        }
+    }
+
     /// Returns whether this particular middleware would become a directory.
     #[inline]
     pub fn is_dir(&self) -> bool {
@@ -383,9 +433,33 @@ impl Middleware {
                 | Middleware::LocalScriptDir
                 | Middleware::LegacyScriptDir
                 | Middleware::CsvDir
+                | Middleware::JsonModelDir
         )
     }
 
+    /// Returns whether this middleware is able to represent an Instance of the
+    /// given class. Model formats and directories can hold anything, while
+    /// the remaining middleware only produce a fixed set of classes.
+    pub fn supports_class(&self, class: &str) -> bool {
+        match self {
+            Self::JsonModel | Self::JsonModelDir | Self::Rbxm | Self::Rbxmx | Self::Dir => true,
+            Self::ServerScript
+            | Self::ClientScript
+            | Self::PluginScript
+            | Self::LegacyScript
+            | Self::ServerScriptDir
+            | Self::ClientScriptDir
+            | Self::PluginScriptDir
+            | Self::LegacyScriptDir => class == "Script",
+            Self::LocalScript | Self::LocalScriptDir => class == "LocalScript",
+            Self::ModuleScript | Self::ModuleScriptDir => class == "ModuleScript",
+            Self::Json | Self::Toml | Self::Yaml => class == "ModuleScript",
+            Self::Csv | Self::CsvDir => class == "LocalizationTable",
+            Self::Text | Self::YamlString | Self::Markdown => class == "StringValue",
+            Self::Project | Self::Ignore => false,
+        }
+    }
+
     /// Returns whether this particular middleware sets its own properties.
     /// This applies to things like `JsonModel` and `Project`, since they
     /// set properties without needing a meta.json5 file.
@@ -397,7 +471,11 @@ impl Middleware {
     pub fn handles_own_properties(&self) -> bool {
         matches!(
             self,
-            Middleware::JsonModel | Middleware::Project | Middleware::Rbxm | Middleware::Rbxmx
+            Middleware::JsonModel
+                | Middleware::JsonModelDir
+                | Middleware::Project
+                | Middleware::Rbxm
+                | Middleware::Rbxmx
         )
     }
 
@@ -496,7 +574,7 @@ pub fn default_sync_rules() -> &'static [SyncRule] {
             sync_rule!("*.model.json", JsonModel, ".model.json"),
             sync_rule!("*.json", Json, ".json", "*.meta.json"),
             // Other formats
-            sync_rule!("*.toml", Toml),
+            sync_rule!("*.toml", Toml, ".toml", "*.meta.toml"),
             sync_rule!("*.csv", Csv),
             sync_rule!("*.txt", Text),
             sync_rule!("*.rbxmx", Rbxmx),
@@ -509,7 +587,8 @@ pub fn default_sync_rules() -> &'static [SyncRule] {
 /// Returns whether a filesystem path is relevant in scripts-only mode.
 ///
 /// Matches script files (`.luau`, `.lua`), meta files (`.meta.json5`,
-/// `.meta.json`), and project files (`.project.json5`, `.project.json`).
+/// `.meta.json`, `.meta.toml`), and project files (`.project.json5`,
+/// `.project.json`).
 pub fn is_script_relevant_path(path: &Path) -> bool {
     let name = match path.file_name().and_then(|n| n.to_str()) {
         Some(n) => n.to_lowercase(),
@@ -519,6 +598,7 @@ pub fn is_script_relevant_path(path: &Path) -> bool {
         || name.ends_with(".lua")
         || name.ends_with(".meta.json5")
         || name.ends_with(".meta.json")
+        || name.ends_with(".meta.toml")
         || name.ends_with(".project.json5")
         || name.ends_with(".project.json")
 }
@@ -529,6 +609,7 @@ mod test {
     use std::collections::HashMap;
 
     use memofs::{InMemoryFs, VfsSnapshot};
+    use rbx_dom_weak::{types::Variant, ustr};
 
     #[test]
     fn init_file_priority_includes_all_project_names() {
@@ -552,6 +633,67 @@ mod test {
         }
     }
 
+    fn init_file_for(files: &[&str]) -> Option<(PathBuf, ScriptType)> {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/foo",
+            VfsSnapshot::dir(
+                files
+                    .iter()
+                    .map(|&name| (name, VfsSnapshot::file("")))
+                    .collect::<HashMap<_, _>>(),
+            ),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        init_file_for_dir(&vfs, Path::new("/foo")).unwrap()
+    }
+
+    #[test]
+    fn init_file_for_dir_resolves_each_script_type() {
+        let cases = [
+            ("init.luau", ScriptType::Module),
+            ("init.server.luau", ScriptType::Server),
+            ("init.client.luau", ScriptType::Client),
+            ("init.plugin.luau", ScriptType::Plugin),
+            ("init.local.luau", ScriptType::Local),
+            ("init.legacy.luau", ScriptType::Legacy),
+            ("init.lua", ScriptType::Module),
+            ("init.server.lua", ScriptType::Legacy),
+            ("init.client.lua", ScriptType::Local),
+        ];
+        for (file_name, script_type) in cases {
+            assert_eq!(
+                init_file_for(&[file_name, "init.meta.json5"]),
+                Some((Path::new("/foo").join(file_name), script_type)),
+                "{file_name} resolved incorrectly"
+            );
+        }
+    }
+
+    #[test]
+    fn init_file_for_dir_follows_priority() {
+        assert_eq!(
+            init_file_for(&["init.server.luau", "init.luau"]),
+            Some((PathBuf::from("/foo/init.luau"), ScriptType::Module))
+        );
+        assert_eq!(
+            init_file_for(&["init.server.lua", "init.server.luau"]),
+            Some((PathBuf::from("/foo/init.server.luau"), ScriptType::Server))
+        );
+    }
+
+    #[test]
+    fn init_file_for_dir_ignores_non_scripts() {
+        assert_eq!(init_file_for(&[]), None);
+        assert_eq!(init_file_for(&["init.meta.json5"]), None);
+        assert_eq!(init_file_for(&["init.meta.toml"]), None);
+        assert_eq!(init_file_for(&["init.csv"]), None);
+        assert_eq!(init_file_for(&["init.model.json5"]), None);
+        assert_eq!(init_file_for(&["default.project.json5", "init.luau"]), None);
+    }
+
     #[test]
     fn is_script_covers_all_script_types() {
         assert!(Middleware::ServerScript.is_script());
@@ -677,6 +819,37 @@ mod test {
         assert!(script_result.is_some());
     }
 
+    #[test]
+    fn markdown_needs_a_sync_rule() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/project",
+            VfsSnapshot::dir(HashMap::from([(
+                "Help.md",
+                VfsSnapshot::file("# Help\n\nPress **E** to interact.\n"),
+            )])),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+        let path = Path::new("/project/Help.md");
+
+        let default_result = snapshot_from_vfs(&InstanceContext::new(), &vfs, path).unwrap();
+        assert!(default_result.is_none());
+
+        let mut context = InstanceContext::new();
+        context.add_sync_rules([sync_rule!("*.md", Markdown)]);
+        let snapshot = snapshot_from_vfs(&context, &vfs, path).unwrap().unwrap();
+        assert_eq!(snapshot.name, "Help");
+        assert_eq!(snapshot.class_name, "StringValue");
+        assert_eq!(
+            snapshot.properties.get(&ustr("Value")),
+            Some(&Variant::String(
+                "# Help\n\nPress **E** to interact.\n".to_owned()
+            ))
+        );
+    }
+
     #[test]
     fn is_script_relevant_path_accepts_scripts_and_meta() {
         assert!(is_script_relevant_path(Path::new("/src/main.luau")));
@@ -685,6 +858,7 @@ mod test {
         assert!(is_script_relevant_path(Path::new("/src/old.server.lua")));
         assert!(is_script_relevant_path(Path::new("/src/file.meta.json5")));
         assert!(is_script_relevant_path(Path::new("/src/file.meta.json")));
+        assert!(is_script_relevant_path(Path::new("/src/file.meta.toml")));
         assert!(is_script_relevant_path(Path::new("/nested.project.json5")));
         assert!(is_script_relevant_path(Path::new("/nested.project.json")));
     }
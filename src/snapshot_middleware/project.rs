@@ -1,18 +1,19 @@
 use std::{
     borrow::Cow,
     collections::{BTreeMap, HashMap, HashSet, VecDeque},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use anyhow::{bail, Context};
-use memofs::Vfs;
+use memofs::{IoResultExt, Vfs};
 use rbx_dom_weak::{
-    types::{Attributes, Ref, Variant},
+    types::{Attributes, Ref, Tags, Variant},
     ustr, HashMapExt as _, Instance, Ustr, UstrMap,
 };
 use rbx_reflection::ClassTag;
 
 use crate::{
+    glob::Glob,
     project::{PathNode, Project, ProjectNode},
     resolution::UnresolvedValue,
     snapshot::{
@@ -24,7 +25,7 @@ use crate::{
         filter_properties, inst_path, name_needs_slugify, slugify_name, FsSnapshot, SyncbackReturn,
         SyncbackSnapshot,
     },
-    variant_eq::variant_eq,
+    variant_eq::{variant_eq, variant_eq_approx, DEFAULT_FLOAT_EPSILON},
     RojoRef,
 };
 
@@ -144,6 +145,13 @@ pub fn snapshot_project(
             // We SHOULD NOT mark the project file as a relevant path for any
             // nodes that aren't roots. They'll be updated as part of the project
             // file being updated.
+            //
+            // The same goes for the project files this one extends. The
+            // project file itself stays last, since syncback relies on that.
+            snapshot
+                .metadata
+                .relevant_paths
+                .extend(project.extended_files.iter().cloned());
             snapshot.metadata.relevant_paths.push(path.to_path_buf());
 
             Ok(Some(snapshot))
@@ -169,7 +177,28 @@ pub fn snapshot_project_node(
     let mut children = Vec::new();
     let mut metadata = InstanceMetadata::new().context(context);
 
-    if let Some(path_node) = &node.path {
+    if let Some(path_node) = node.path.as_ref().filter(|path| path.is_glob()) {
+        // A glob node is a Folder holding everything the glob matches.
+        let (glob_base, glob_children) =
+            snapshot_glob_children(context, vfs, project_folder, path_node).with_context(|| {
+                format!(
+                    "Could not expand the $path glob {} of Instance \"{}\" in project {}",
+                    path_node.path().display(),
+                    instance_name,
+                    project_path.display()
+                )
+            })?;
+
+        class_name_from_path = Some(ustr("Folder"));
+        // Children listed in the project win over matched files with the
+        // same name.
+        children.extend(
+            glob_children
+                .into_iter()
+                .filter(|child| !node.children.contains_key(child.name.as_ref())),
+        );
+        metadata.relevant_paths.push(glob_base);
+    } else if let Some(path_node) = &node.path {
         let path = path_node.path();
 
         // If the path specified in the project is relative, we assume it's
@@ -180,7 +209,27 @@ pub fn snapshot_project_node(
             Cow::Borrowed(path)
         };
 
-        if let Some(snapshot) = snapshot_from_vfs(context, vfs, &full_path)? {
+        let snapshot = match node.middleware {
+            Some(middleware) => {
+                if middleware == Middleware::Project || middleware == Middleware::Ignore {
+                    bail!(
+                        "$middleware cannot be set to {:?} on Instance \"{}\".\n\
+                         \n\
+                         Project path: {}",
+                        middleware,
+                        instance_name,
+                        project_path.display(),
+                    );
+                }
+                match vfs.metadata(&full_path).with_not_found()? {
+                    Some(_) => middleware.snapshot(context, vfs, &full_path, instance_name)?,
+                    None => None,
+                }
+            }
+            None => snapshot_from_vfs(context, vfs, &full_path)?,
+        };
+
+        if let Some(snapshot) = snapshot {
             class_name_from_path = Some(snapshot.class_name);
 
             // Properties from the snapshot are pulled in unchanged, and
@@ -281,6 +330,20 @@ pub fn snapshot_project_node(
         }
     };
 
+    if let Some(middleware) = node.middleware {
+        if !middleware.supports_class(&class_name) {
+            bail!(
+                "$middleware {:?} cannot represent Instance \"{}\" of class {}.\n\
+                 \n\
+                 Project path: {}",
+                middleware,
+                instance_name,
+                class_name,
+                project_path.display(),
+            );
+        }
+    }
+
     for (child_name, child_project_node) in &node.children {
         if let Some(child) = snapshot_project_node(
             context,
@@ -295,15 +358,18 @@ pub fn snapshot_project_node(
     }
 
     for (key, unresolved) in &node.properties {
-        let value = unresolved
+        let Some(value) = unresolved
             .clone()
-            .resolve(&class_name, key)
+            .resolve_property(&class_name, key, context.ignore_unknown_properties)
             .with_context(|| {
                 format!(
                     "Unresolvable property in project at path {}",
                     project_path.display()
                 )
-            })?;
+            })?
+        else {
+            continue;
+        };
 
         match key.as_str() {
             "Name" | "Parent" => {
@@ -339,6 +405,10 @@ pub fn snapshot_project_node(
         properties.insert("Attributes".into(), attributes.into());
     }
 
+    if !node.tags.is_empty() {
+        properties.insert("Tags".into(), Variant::Tags(tags_from_node(&node.tags)));
+    }
+
     // If the user specified $ignoreUnknownInstances, overwrite the existing
     // value.
     //
@@ -374,6 +444,72 @@ pub fn snapshot_project_node(
     }))
 }
 
+/// Snapshots every file or folder matched by `path_node`, a glob `$path`
+/// like `src/*.luau`, in path order. Returns the folder the glob is rooted in,
+/// which is everything before its first wildcard, along with the snapshots.
+///
+/// Each wildcard-free component of the pattern matches a single level, so
+/// `src/*.luau` only matches files directly inside `src`. Use `**` to match
+/// at any depth.
+fn snapshot_glob_children(
+    context: &InstanceContext,
+    vfs: &Vfs,
+    project_folder: &Path,
+    path_node: &PathNode,
+) -> anyhow::Result<(PathBuf, Vec<InstanceSnapshot>)> {
+    let literal = path_node.literal_path();
+    let base = project_folder.join(&literal);
+    let rest: Vec<String> = path_node
+        .path()
+        .components()
+        .skip(literal.components().count())
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let max_depth = if rest.iter().any(|component| component.contains("**")) {
+        usize::MAX
+    } else {
+        rest.len()
+    };
+    let glob = Glob::new(&rest.join("/"))?;
+
+    let mut matches = Vec::new();
+    let mut pending = vec![(base.clone(), 1)];
+    while let Some((dir, depth)) = pending.pop() {
+        let Some(entries) = vfs.read_dir(&dir).with_not_found()? else {
+            continue;
+        };
+        for entry in entries {
+            let path = entry?.path().to_path_buf();
+            if !context
+                .path_ignore_rules
+                .iter()
+                .all(|rule| rule.passes(&path))
+            {
+                continue;
+            }
+
+            let relative = path
+                .strip_prefix(&base)
+                .expect("entries are inside the base");
+            if glob.is_match(relative) {
+                matches.push(path);
+            } else if depth < max_depth && vfs.metadata(&path)?.is_dir() {
+                pending.push((path, depth + 1));
+            }
+        }
+    }
+    matches.sort();
+
+    let mut children = Vec::with_capacity(matches.len());
+    for path in matches {
+        if let Some(child) = snapshot_from_vfs(context, vfs, &path)? {
+            children.push(child);
+        }
+    }
+
+    Ok((base, children))
+}
+
 pub fn syncback_project<'sync>(
     snapshot: &SyncbackSnapshot<'sync>,
 ) -> anyhow::Result<SyncbackReturn<'sync>> {
@@ -436,13 +572,50 @@ pub fn syncback_project<'sync>(
             }
         }
 
-        // TODO handle meta.json5 files in this branch. Right now, we perform
-        // syncback if a node has `$path` set but the Middleware aren't aware
-        // that the Instances they're running on originate in a project.json5.
-        // As a result, the `meta.json5` syncback code is hardcoded to not work
-        // if the Instance originates from a project file. However, we should
-        // ideally use a .meta.json5 over the project node if it exists already.
-        if node.path.is_some() {
+        if node.path.as_ref().is_some_and(PathNode::is_glob) {
+            // Files matched by a glob `$path` are synced back in place. The
+            // glob doesn't say where a new Instance's file should go, so
+            // those are left out with a warning.
+            project_node_property_syncback_no_path(snapshot, new_inst, node);
+            for child_ref in new_inst.children() {
+                let new_child = snapshot
+                    .get_new_instance(*child_ref)
+                    .expect("all children of Instances should be in new DOM");
+                if node.children.contains_key(new_child.name.as_str()) {
+                    continue;
+                }
+                let old_child = old_inst.children().iter().find_map(|old_ref| {
+                    snapshot
+                        .get_old_instance(*old_ref)
+                        .filter(|old_child| old_child.name() == new_child.name)
+                });
+                let old_file = old_child.and_then(|old_child| {
+                    let path = old_child.metadata().relevant_paths.first()?;
+                    Some((old_child.id(), path.clone()))
+                });
+                match old_file {
+                    Some((old_ref, path)) => descendant_snapshots.push(snapshot.with_new_path(
+                        path,
+                        new_child.referent(),
+                        Some(old_ref),
+                    )),
+                    None => snapshot.stats().record_warning(format!(
+                        "Not syncing back {} because it would be a new file under the \
+                         $path glob {} in project {}",
+                        inst_path(snapshot.new_tree(), new_child.referent()),
+                        node.path.as_ref().unwrap().path().display(),
+                        project_path.display()
+                    )),
+                }
+            }
+        } else if node.path.is_some() {
+            // TODO handle meta.json5 files in this branch. Right now, we perform
+            // syncback if a node has `$path` set but the Middleware aren't aware
+            // that the Instances they're running on originate in a project.json5.
+            // As a result, the `meta.json5` syncback code is hardcoded to not work
+            // if the Instance originates from a project file. However, we should
+            // ideally use a .meta.json5 over the project node if it exists already.
+            //
             // Since the node has a path, we have to run syncback on it.
             let node_path = node.path.as_ref().map(PathNode::path).expect(
                 "Project nodes with a path must have a path \
@@ -454,47 +627,65 @@ pub fn syncback_project<'sync>(
                 base_path.join(node_path)
             };
 
-            let mut middleware = match Middleware::middleware_for_path(
-                snapshot.vfs(),
-                &project.sync_rules,
-                &full_path,
-            )? {
-                Some(middleware) => middleware,
-                None => {
-                    // Path doesn't exist on filesystem. In clean mode, we can
-                    // determine the middleware from the new instance and let
-                    // syncback create the necessary files/directories.
-                    if !snapshot.data.is_incremental() {
-                        // Determine middleware based on new instance class (and RunContext for Scripts)
-                        let inferred_middleware = match new_inst.class.as_str() {
-                            "ModuleScript" => Middleware::ModuleScriptDir,
-                            "Script" => middleware_for_script(new_inst),
-                            "LocalScript" => Middleware::LocalScriptDir,
-                            "Folder" => Middleware::Dir,
-                            // For other classes, default to Dir
-                            _ => Middleware::Dir,
-                        };
-                        log::debug!(
-                            "Clean mode: path {} doesn't exist, inferring {:?} middleware for class {}",
-                            full_path.display(),
-                            inferred_middleware,
-                            new_inst.class
-                        );
-                        inferred_middleware
-                    } else {
-                        anyhow::bail!(
-                            "Rojo project referred to a file using $path that could not be turned \
-                            into a Roblox Instance by Rojo.\n\
-                            File $path: {}",
-                            full_path.display()
-                        )
-                    }
+            let forced_middleware = node.middleware;
+            if let Some(forced) = forced_middleware {
+                if !forced.supports_class(&new_inst.class) {
+                    anyhow::bail!(
+                        "$middleware {:?} cannot represent {} in project file {}, \
+                        it is a {} in the input file.",
+                        forced,
+                        old_inst.name(),
+                        project_path.display(),
+                        new_inst.class
+                    );
                 }
+            }
+
+            let mut middleware = match forced_middleware {
+                Some(forced) => forced,
+                None => match Middleware::middleware_for_path(
+                    snapshot.vfs(),
+                    &project.sync_rules,
+                    &full_path,
+                )? {
+                    Some(middleware) => middleware,
+                    None => {
+                        // Path doesn't exist on filesystem. In clean mode, we can
+                        // determine the middleware from the new instance and let
+                        // syncback create the necessary files/directories.
+                        if !snapshot.data.is_incremental() {
+                            // Determine middleware based on new instance class (and RunContext for Scripts)
+                            let inferred_middleware = match new_inst.class.as_str() {
+                                "ModuleScript" => Middleware::ModuleScriptDir,
+                                "Script" => middleware_for_script(new_inst),
+                                "LocalScript" => Middleware::LocalScriptDir,
+                                "Folder" => Middleware::Dir,
+                                // For other classes, default to Dir
+                                _ => Middleware::Dir,
+                            };
+                            log::debug!(
+                                "Clean mode: path {} doesn't exist, inferring {:?} middleware for class {}",
+                                full_path.display(),
+                                inferred_middleware,
+                                new_inst.class
+                            );
+                            inferred_middleware
+                        } else {
+                            anyhow::bail!(
+                                "Rojo project referred to a file using $path that could not be turned \
+                                into a Roblox Instance by Rojo.\n\
+                                File $path: {}",
+                                full_path.display()
+                            )
+                        }
+                    }
+                },
             };
 
             // In clean mode, we may need to override middleware when the filesystem
-            // structure doesn't match the new instance type.
-            if !snapshot.data.is_incremental() {
+            // structure doesn't match the new instance type. A `$middleware`
+            // override always wins, though.
+            if forced_middleware.is_none() && !snapshot.data.is_incremental() {
                 // Case 1: Filesystem has a directory (Dir middleware) but new instance
                 // is a script - use script-dir middleware to create the init file.
                 if middleware == Middleware::Dir {
@@ -581,7 +772,12 @@ pub fn syncback_project<'sync>(
         // Detect duplicate children using case-insensitive comparison.
         // Project KEYS are unique by JSON definition; only filesystem children
         // under project nodes may collide. Duplicates are skipped with a warning
-        // for now -- the dedup system (Phase 1c/1d) will handle them properly.
+        // unless `dedupDuplicateNames` is set, in which case they're synced
+        // back below as new children and given `~N` suffixes like any other
+        // colliding file name.
+        let dedup_duplicate_names = snapshot.dedup_duplicate_names();
+        let mut duplicate_new_children = Vec::new();
+        let mut duplicate_old_children = Vec::new();
         let mut new_duplicate_names: HashSet<String> = HashSet::new();
         {
             let mut seen: HashSet<String> = HashSet::new();
@@ -595,13 +791,17 @@ pub fn syncback_project<'sync>(
                 }
             }
         }
-        if !new_duplicate_names.is_empty() {
+        if !new_duplicate_names.is_empty() && !dedup_duplicate_names {
             let parent_path = inst_path(snapshot.new_tree(), new_inst.referent());
             for child_ref in new_inst.children() {
                 let child = snapshot
                     .get_new_instance(*child_ref)
                     .expect("all children of Instances should be in new DOM");
                 if new_duplicate_names.contains(&child.name.to_lowercase()) {
+                    snapshot.stats().record_duplicate_name(
+                        &format!("{parent_path}/{}", child.name),
+                        &child.name,
+                    );
                     log::warn!(
                         "Skipping duplicate-named child '{}' under ProjectNode '{}' -- \
                         cannot reliably sync yet. Full path: {}/{}",
@@ -619,6 +819,8 @@ pub fn syncback_project<'sync>(
                 .expect("all children of Instances should be in new DOM");
             if !new_duplicate_names.contains(&child.name.to_lowercase()) {
                 new_child_map.insert(&child.name, child);
+            } else if dedup_duplicate_names {
+                duplicate_new_children.push(child);
             }
         }
 
@@ -649,13 +851,17 @@ pub fn syncback_project<'sync>(
                 }
             }
         }
-        if !old_duplicate_names.is_empty() {
+        if !old_duplicate_names.is_empty() && !dedup_duplicate_names {
             let parent_path = inst_path(snapshot.old_tree(), old_inst.id());
             for child_ref in old_inst.children() {
                 let child = snapshot
                     .get_old_instance(*child_ref)
                     .expect("all children of Instances should be in old DOM");
                 if old_duplicate_names.contains(&child.name().to_lowercase()) {
+                    snapshot.stats().record_duplicate_name(
+                        &format!("{parent_path}/{}", child.name()),
+                        child.name(),
+                    );
                     log::warn!(
                         "Skipping duplicate-named child '{}' under ProjectNode '{}' -- \
                         cannot reliably sync yet. Full path: {}/{}",
@@ -673,6 +879,8 @@ pub fn syncback_project<'sync>(
                 .expect("all children of Instances should be in old DOM");
             if !old_duplicate_names.contains(&child.name().to_lowercase()) {
                 old_child_map.insert(child.name(), child);
+            } else if dedup_duplicate_names {
+                duplicate_old_children.push(child);
             }
         }
 
@@ -683,7 +891,7 @@ pub fn syncback_project<'sync>(
             // If a node's path is optional, we want to skip it if the path
             // doesn't exist since it isn't in the current old DOM.
             if let Some(path) = &child_node.path {
-                if path.is_optional() {
+                if path.is_optional() && !path.is_glob() {
                     let real_path = if path.path().is_absolute() {
                         path.path().to_path_buf()
                     } else {
@@ -733,7 +941,7 @@ pub fn syncback_project<'sync>(
         if snapshot.data.is_incremental() {
             if let Some(parent_path) = ref_to_path_map.get(&new_inst.referent()) {
                 let taken = taken_names_per_dir.entry(parent_path.clone()).or_default();
-                for old_child in old_child_map.values() {
+                for old_child in old_child_map.values().chain(&duplicate_old_children) {
                     if let Some(path) = old_child.metadata().relevant_paths.first() {
                         if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
                             // Use the full filename (including extension) as the
@@ -759,6 +967,11 @@ pub fn syncback_project<'sync>(
         // Without this, HashMap iteration order determines which sibling gets
         // the base slug vs ~2, causing non-deterministic output across runs.
         let mut remaining_children: Vec<_> = new_child_map.drain().collect();
+        remaining_children.extend(
+            duplicate_new_children
+                .into_iter()
+                .map(|child| (&child.name, child)),
+        );
         remaining_children.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
         for (name, new_child) in remaining_children {
             // Skip instances of ignored classes
@@ -784,7 +997,16 @@ pub fn syncback_project<'sync>(
             // If a child also exists in the old tree, it will be caught in the
             // syncback on the project node path above (or is itself a node).
             // So the only things we need to run seperately is new children.
-            if old_child_map.remove(name.as_str()).is_none() {
+            //
+            // Deduplicated children are matched to an old duplicate with the
+            // exact same name, so they keep the file they already have.
+            let in_old_tree = old_child_map.remove(name.as_str()).is_some()
+                || duplicate_old_children
+                    .iter()
+                    .position(|old| old.name() == name.as_str())
+                    .map(|index| duplicate_old_children.swap_remove(index))
+                    .is_some();
+            if !in_old_tree {
                 let parent_middleware =
                     Middleware::middleware_for_path(vfs, &project.sync_rules, &parent_path)?
                         .expect("project nodes should have a middleware if they have children.");
@@ -806,25 +1028,62 @@ pub fn syncback_project<'sync>(
             }
         }
         // Filter out instances of ignored classes from removal
-        removed_descendants.extend(old_child_map.drain().filter_map(|(_, inst)| {
-            if snapshot.should_ignore_class(inst.class_name().as_str()) {
-                log::debug!(
-                    "Not removing instance {} because its class {} is ignored",
-                    inst.name(),
-                    inst.class_name()
-                );
-                None
-            } else {
-                Some(inst)
-            }
-        }));
-        node_changed_map.push((&node.properties, &node.attributes, old_inst))
+        removed_descendants.extend(
+            old_child_map
+                .drain()
+                .map(|(_, inst)| inst)
+                .chain(duplicate_old_children)
+                .filter_map(|inst| {
+                    if snapshot.should_ignore_class(inst.class_name().as_str()) {
+                        log::debug!(
+                            "Not removing instance {} because its class {} is ignored",
+                            inst.name(),
+                            inst.class_name()
+                        );
+                        None
+                    } else {
+                        Some(inst)
+                    }
+                }),
+        );
+        node_changed_map.push((&node.properties, &node.attributes, &node.tags, old_inst))
     }
     let mut fs_snapshot = FsSnapshot::new();
-
-    for (node_properties, node_attributes, old_inst) in node_changed_map {
-        if project_node_should_reserialize(node_properties, node_attributes, old_inst)? {
-            fs_snapshot.add_file(project_path, crate::json::to_vec_pretty_sorted(&project)?);
+    let float_epsilon = project
+        .syncback_rules
+        .as_ref()
+        .map_or(DEFAULT_FLOAT_EPSILON, |rules| rules.float_epsilon());
+
+    for (node_properties, node_attributes, node_tags, old_inst) in node_changed_map {
+        if project_node_should_reserialize(
+            node_properties,
+            node_attributes,
+            node_tags,
+            old_inst,
+            float_epsilon,
+        )? {
+            // The loaded project has everything it extends merged in and its
+            // environment variables expanded, so writing it out would copy
+            // the base project into this file or bake in the variables.
+            let reason = if project.extends.is_some() {
+                Some("it extends another project")
+            } else if project.has_env_vars {
+                Some("it uses environment variables")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                snapshot.stats().record_warning(format!(
+                    "Not updating properties in {} because {reason}. \
+                     Edit the project files by hand to keep these changes.",
+                    project_path.display()
+                ));
+                break;
+            }
+            fs_snapshot.add_file(
+                project_path,
+                crate::json::to_vec_pretty_sorted_rounded(&project, snapshot.number_precision())?,
+            );
             break;
         }
     }
@@ -844,8 +1103,14 @@ fn project_node_property_syncback(
 ) {
     let properties = &mut node.properties;
     let mut attributes = BTreeMap::new();
+    let mut tags = Vec::new();
     for (name, value) in filtered_properties {
         match value {
+            // Tags set through `$properties` stay there, everything else is
+            // written as `$tags`.
+            Variant::Tags(inst_tags) if !properties.contains_key(&name) => {
+                tags = inst_tags.iter().map(str::to_owned).collect();
+            }
             Variant::Attributes(attrs) => {
                 for (attr_name, attr_value) in attrs.iter() {
                     // We (probably) don't want to preserve internal attributes,
@@ -868,6 +1133,7 @@ fn project_node_property_syncback(
         }
     }
     node.attributes = attributes;
+    node.tags = tags;
 }
 
 fn project_node_property_syncback_path(
@@ -893,14 +1159,16 @@ fn project_node_property_syncback_no_path(
 fn project_node_should_reserialize(
     node_properties: &BTreeMap<Ustr, UnresolvedValue>,
     node_attributes: &BTreeMap<String, UnresolvedValue>,
+    node_tags: &[String],
     instance: InstanceWithMeta,
+    float_epsilon: f64,
 ) -> anyhow::Result<bool> {
     for (prop_name, unresolved_node_value) in node_properties {
         if let Some(inst_value) = instance.properties().get(prop_name) {
             let node_value = unresolved_node_value
                 .clone()
                 .resolve(&instance.class_name(), prop_name)?;
-            if !variant_eq(inst_value, &node_value) {
+            if !variant_eq_approx(inst_value, &node_value, float_epsilon) {
                 return Ok(true);
             }
         } else {
@@ -908,6 +1176,16 @@ fn project_node_should_reserialize(
         }
     }
 
+    if !node_properties.contains_key(&ustr("Tags")) {
+        let tags_changed = match instance.properties().get(&ustr("Tags")) {
+            Some(inst_tags) => !variant_eq(inst_tags, &Variant::Tags(tags_from_node(node_tags))),
+            None => !node_tags.is_empty(),
+        };
+        if tags_changed {
+            return Ok(true);
+        }
+    }
+
     match instance.properties().get(&ustr("Attributes")) {
         Some(Variant::Attributes(inst_attributes)) => {
             // This will also catch if one is empty but the other isn't
@@ -917,7 +1195,7 @@ fn project_node_should_reserialize(
                 for (attr_name, unresolved_node_value) in node_attributes {
                     if let Some(inst_value) = inst_attributes.get(attr_name.as_str()) {
                         let node_value = unresolved_node_value.clone().resolve_unambiguous()?;
-                        if !variant_eq(inst_value, &node_value) {
+                        if !variant_eq_approx(inst_value, &node_value, float_epsilon) {
                             return Ok(true);
                         }
                     } else {
@@ -938,6 +1216,14 @@ fn project_node_should_reserialize(
     }
 }
 
+fn tags_from_node(node_tags: &[String]) -> Tags {
+    let mut tags = Tags::new();
+    for tag in node_tags {
+        tags.push(tag);
+    }
+    tags
+}
+
 fn infer_class_name(name: &str, parent_class: Option<&str>) -> Option<Ustr> {
     // If className wasn't defined from another source, we may be able
     // to infer one.
@@ -1409,4 +1695,188 @@ mod test {
 
         insta::assert_yaml_snapshot!(instance_snapshot);
     }
+
+    #[test]
+    fn project_with_attributes_and_tags() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/foo",
+            VfsSnapshot::dir([(
+                "hello.project.json5",
+                VfsSnapshot::file(
+                    r#"
+                    {
+                        "name": "tagged",
+                        "tree": {
+                            "$className": "Configuration",
+                            "$attributes": {
+                                "Difficulty": "Hard",
+                                "MaxPlayers": 12
+                            },
+                            "$tags": ["Persistent", "Settings"]
+                        }
+                    }
+                "#,
+                ),
+            )]),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        let instance_snapshot = snapshot_project(
+            &InstanceContext::default(),
+            &vfs,
+            Path::new("/foo/hello.project.json5"),
+            "NOT_IN_SNAPSHOT",
+        )
+        .expect("snapshot error")
+        .expect("snapshot returned no instances");
+
+        let Some(Variant::Attributes(attributes)) =
+            instance_snapshot.properties.get(&ustr("Attributes"))
+        else {
+            panic!("the snapshot should have attributes");
+        };
+        assert_eq!(
+            attributes.get("Difficulty"),
+            Some(&Variant::String("Hard".into()))
+        );
+        assert_eq!(attributes.get("MaxPlayers"), Some(&Variant::Float64(12.0)));
+
+        let Some(Variant::Tags(tags)) = instance_snapshot.properties.get(&ustr("Tags")) else {
+            panic!("the snapshot should have tags");
+        };
+        assert_eq!(tags.iter().collect::<Vec<_>>(), ["Persistent", "Settings"]);
+    }
+
+    #[test]
+    fn sync_rule_maps_custom_extension() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/foo",
+            VfsSnapshot::dir([
+                (
+                    "default.project.json5",
+                    VfsSnapshot::file(
+                        r#"
+                        {
+                            "name": "shaders",
+                            "tree": {
+                                "$path": "src"
+                            },
+                            "syncRules": [
+                                {
+                                    "pattern": "*.frag",
+                                    "use": "text",
+                                    "suffix": ".frag"
+                                }
+                            ]
+                        }
+                    "#,
+                    ),
+                ),
+                (
+                    "src",
+                    VfsSnapshot::dir([("Water.frag", VfsSnapshot::file("void main() {}"))]),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        let instance_snapshot = snapshot_project(
+            &InstanceContext::default(),
+            &vfs,
+            Path::new("/foo/default.project.json5"),
+            "NOT_IN_SNAPSHOT",
+        )
+        .expect("snapshot error")
+        .expect("snapshot returned no instances");
+
+        let [water] = instance_snapshot.children.as_slice() else {
+            panic!("expected one child, got {:?}", instance_snapshot.children);
+        };
+        assert_eq!(water.name, "Water");
+        assert_eq!(water.class_name, "StringValue");
+        assert_eq!(
+            water.properties.get(&ustr("Value")),
+            Some(&Variant::String("void main() {}".to_owned()))
+        );
+    }
+
+    #[test]
+    fn glob_path_makes_a_child_per_file() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/foo",
+            VfsSnapshot::dir([
+                (
+                    "default.project.json5",
+                    VfsSnapshot::file(
+                        r#"
+                        {
+                            "name": "glob_project",
+                            "tree": {
+                                "$path": "src/*.luau",
+                                "Beta": {
+                                    "$className": "Configuration"
+                                }
+                            }
+                        }
+                    "#,
+                    ),
+                ),
+                (
+                    "src",
+                    VfsSnapshot::dir([
+                        ("Alpha.luau", VfsSnapshot::file("return 1")),
+                        ("Beta.luau", VfsSnapshot::file("return 2")),
+                        ("Gamma.luau", VfsSnapshot::file("return 3")),
+                        ("notes.txt", VfsSnapshot::file("not a module")),
+                        (
+                            "nested",
+                            VfsSnapshot::dir([("Delta.luau", VfsSnapshot::file("return 4"))]),
+                        ),
+                    ]),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        let instance_snapshot = snapshot_project(
+            &InstanceContext::default(),
+            &vfs,
+            Path::new("/foo/default.project.json5"),
+            "NOT_IN_SNAPSHOT",
+        )
+        .expect("snapshot error")
+        .expect("snapshot returned no instances");
+
+        assert_eq!(instance_snapshot.class_name.as_str(), "Folder");
+
+        // `Beta` is listed in the project, so it wins over `Beta.luau`.
+        let children: Vec<(&str, &str)> = instance_snapshot
+            .children
+            .iter()
+            .map(|child| (child.name.as_ref(), child.class_name.as_str()))
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                ("Alpha", "ModuleScript"),
+                ("Gamma", "ModuleScript"),
+                ("Beta", "Configuration"),
+            ]
+        );
+    }
 }
@@ -93,6 +93,7 @@ mod test {
     use super::*;
 
     use memofs::{InMemoryFs, VfsSnapshot};
+    use rbx_dom_weak::{types::Variant, ustr};
 
     #[test]
     fn plain_folder() {
@@ -129,4 +130,63 @@ mod test {
         assert_eq!(instance_snapshot.properties, Default::default());
         assert_eq!(instance_snapshot.children, Vec::new());
     }
+
+    #[test]
+    fn nested_instances() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot(
+            "/Legacy.rbxmx",
+            VfsSnapshot::file(
+                r#"
+                    <roblox version="4">
+                        <Item class="Folder" referent="0">
+                            <Properties>
+                                <string name="Name">Legacy</string>
+                            </Properties>
+                            <Item class="Model" referent="1">
+                                <Properties>
+                                    <string name="Name">Car</string>
+                                </Properties>
+                                <Item class="StringValue" referent="2">
+                                    <Properties>
+                                        <string name="Name">Plate</string>
+                                        <string name="Value">ROJO-1</string>
+                                    </Properties>
+                                </Item>
+                            </Item>
+                        </Item>
+                    </roblox>
+                "#,
+            ),
+        )
+        .unwrap();
+
+        let vfs = Vfs::new(imfs);
+
+        let instance_snapshot = snapshot_rbxmx(
+            &InstanceContext::default(),
+            &vfs,
+            Path::new("/Legacy.rbxmx"),
+            "Legacy",
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(instance_snapshot.class_name, "Folder");
+        assert_eq!(instance_snapshot.children.len(), 1);
+
+        let car = &instance_snapshot.children[0];
+        assert_eq!(car.name, "Car");
+        assert_eq!(car.class_name, "Model");
+        assert_eq!(car.children.len(), 1);
+
+        let plate = &car.children[0];
+        assert_eq!(plate.name, "Plate");
+        assert_eq!(plate.class_name, "StringValue");
+        assert_eq!(
+            plate.properties.get(&ustr("Value")),
+            Some(&Variant::String("ROJO-1".into()))
+        );
+        assert!(plate.children.is_empty());
+    }
 }
@@ -73,9 +73,12 @@ pub fn syncback_txt<'sync>(
             } else {
                 meta_name.to_string()
             };
+            let meta_format = snapshot.meta_format();
             fs_snapshot.add_file(
-                parent.join(format!("{}.meta.json5", meta_name)),
-                crate::json::to_vec_pretty_sorted(&meta).context("could not serialize metadata")?,
+                parent.join(format!("{}.{}", meta_name, meta_format.extension())),
+                meta_format
+                    .serialize(&meta, snapshot.number_precision())
+                    .context("could not serialize metadata")?,
             );
         }
     }
@@ -19,16 +19,7 @@ pub fn snapshot_yaml(
     name: &str,
 ) -> anyhow::Result<Option<InstanceSnapshot>> {
     let contents = vfs.read_to_string(path)?;
-
-    let mut values = YamlLoader::load_from_str(&contents)?;
-    let value = values
-        .pop()
-        .context("all YAML documents must contain a document")?;
-    if !values.is_empty() {
-        anyhow::bail!("Rojo does not currently support multiple documents in a YAML file")
-    }
-
-    let as_lua = Statement::Return(yaml_to_luau(value)?);
+    let as_lua = Statement::Return(yaml_to_luau(parse_document(&contents)?)?);
 
     let mut snapshot = InstanceSnapshot::new()
         .name(name)
@@ -46,6 +37,46 @@ pub fn snapshot_yaml(
     Ok(Some(snapshot))
 }
 
+/// Snapshots a YAML file as a `StringValue` holding the file's text, for
+/// data that's read at runtime rather than required. The file is still parsed
+/// so that malformed YAML is caught when syncing instead of in game.
+pub fn snapshot_yaml_string(
+    context: &InstanceContext,
+    vfs: &Vfs,
+    path: &Path,
+    name: &str,
+) -> anyhow::Result<Option<InstanceSnapshot>> {
+    let contents = vfs.read_to_string(path)?;
+    parse_document(&contents)
+        .with_context(|| format!("File contains malformed YAML: {}", path.display()))?;
+
+    let mut snapshot = InstanceSnapshot::new()
+        .name(name)
+        .class_name("StringValue")
+        .property(ustr("Value"), contents.as_str())
+        .metadata(
+            InstanceMetadata::new()
+                .instigating_source(path)
+                .relevant_paths(vec![path.to_path_buf()])
+                .context(context),
+        );
+
+    AdjacentMetadata::read_and_apply_all(vfs, path, name, &mut snapshot)?;
+
+    Ok(Some(snapshot))
+}
+
+fn parse_document(contents: &str) -> anyhow::Result<Yaml> {
+    let mut values = YamlLoader::load_from_str(contents)?;
+    let value = values
+        .pop()
+        .context("all YAML documents must contain a document")?;
+    if !values.is_empty() {
+        anyhow::bail!("Rojo does not currently support multiple documents in a YAML file")
+    }
+    Ok(value)
+}
+
 fn yaml_to_luau(value: Yaml) -> anyhow::Result<Expression> {
     const MAX_FLOAT_INT: i64 = 1 << 53;
 
@@ -227,6 +258,50 @@ value: 9007199254740993
         .unwrap();
     }
 
+    #[test]
+    fn string_value_from_vfs() {
+        let contents = "name: Atlas\nmaxPlayers: 12\n";
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/config.yml", VfsSnapshot::file(contents))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs.clone());
+
+        let instance_snapshot = snapshot_yaml_string(
+            &InstanceContext::default(),
+            &vfs,
+            Path::new("/config.yml"),
+            "config",
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(instance_snapshot.name, "config");
+        assert_eq!(instance_snapshot.class_name, "StringValue");
+        assert_eq!(
+            instance_snapshot.properties.get(&ustr("Value")),
+            Some(&Variant::String(contents.to_owned()))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "malformed YAML")]
+    fn string_value_rejects_malformed_yaml() {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/config.yml", VfsSnapshot::file("key: [unclosed"))
+            .unwrap();
+
+        let vfs = Vfs::new(imfs.clone());
+
+        snapshot_yaml_string(
+            &InstanceContext::default(),
+            &vfs,
+            Path::new("/config.yml"),
+            "config",
+        )
+        .unwrap();
+    }
+
     #[test]
     fn with_metadata() {
         let mut imfs = InMemoryFs::new();
@@ -0,0 +1,128 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use memofs::Vfs;
+
+use crate::Project;
+
+/// Name of the directory (inside the backup root) that holds copies of
+/// `$path` entries living outside of the project folder.
+const EXTERNAL_DIR_NAME: &str = "_external";
+
+/// Generates a backup directory name like `2026-03-01_14-32-05`.
+/// Uses UTC time, hyphens and underscores only (safe on all OSes).
+pub fn backup_timestamp() -> String {
+    let now = time::OffsetDateTime::now_utc();
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+    )
+}
+
+/// Returns the directory in `parent` to write a new backup to, named by
+/// [`backup_timestamp`]. When a backup from the same second already exists,
+/// a counter is appended (`2026-03-01_14-32-05_2`) so it isn't overwritten.
+pub fn new_backup_dir(vfs: &Vfs, parent: &Path) -> PathBuf {
+    let timestamp = backup_timestamp();
+    let mut dir = parent.join(&timestamp);
+    let mut counter = 1;
+    while vfs.metadata(&dir).is_ok() {
+        counter += 1;
+        dir = parent.join(format!("{timestamp}_{counter}"));
+    }
+    dir
+}
+
+/// Copies the project file and every `$path` referenced by the project into
+/// `backup_dir`, preserving their layout relative to the project folder.
+///
+/// `$path` entries that live outside of the project folder are copied into
+/// an `_external` subdirectory, keyed by their file name. Hidden entries
+/// (other than `.gitkeep`) are skipped, matching the orphan scan used by
+/// clean mode: syncback never touches them, so there's nothing to restore.
+///
+/// Returns the number of files copied.
+pub fn backup_project(vfs: &Vfs, project: &Project, backup_dir: &Path) -> io::Result<usize> {
+    let folder = project.folder_location();
+
+    let mut roots = project.resolved_path_roots();
+    roots.push(project.file_location.clone());
+
+    roots.sort();
+    let mut deduped: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        if !deduped.iter().any(|existing| root.starts_with(existing)) {
+            deduped.push(root);
+        }
+    }
+
+    vfs.create_dir_all(backup_dir)?;
+
+    let mut copied = 0;
+    for root in &deduped {
+        if !root.exists() {
+            continue;
+        }
+        let destination = match root.strip_prefix(folder) {
+            Ok(relative) => backup_dir.join(relative),
+            Err(_) => match root.file_name() {
+                Some(name) => backup_dir.join(EXTERNAL_DIR_NAME).join(name),
+                None => continue,
+            },
+        };
+        log::debug!("Backing up {} to {}", root.display(), destination.display());
+        copied += copy_tree(vfs, root, &destination)?;
+    }
+
+    Ok(copied)
+}
+
+/// Recursively copies `from` to `to`, skipping hidden entries and `to`
+/// itself (in case the backup lives inside the tree being copied). Works for
+/// both single files and directories.
+fn copy_tree(vfs: &Vfs, from: &Path, to: &Path) -> io::Result<usize> {
+    let mut copied = 0;
+
+    for entry in walkdir::WalkDir::new(from)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| {
+            if e.depth() == 0 {
+                return true;
+            }
+            if e.path().starts_with(to) {
+                return false;
+            }
+            e.file_name()
+                .to_str()
+                .is_none_or(|n| !n.starts_with('.') || n == ".gitkeep")
+        })
+    {
+        let entry = entry.map_err(io::Error::other)?;
+        let relative = entry.path().strip_prefix(from).unwrap();
+        let destination = if relative.as_os_str().is_empty() {
+            to.to_path_buf()
+        } else {
+            to.join(relative)
+        };
+
+        if entry.file_type().is_dir() {
+            vfs.create_dir_all(&destination)?;
+        } else {
+            if let Some(parent) = destination.parent() {
+                vfs.create_dir_all(parent)?;
+            }
+            vfs.copy(entry.path(), &destination)?;
+            copied += 1;
+        }
+    }
+
+    Ok(copied)
+}
@@ -0,0 +1,270 @@
+//! Rendering of planned syncback changes for the preview-style commands
+//! (`--list`, `--dry-run`).
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use memofs::Vfs;
+use rbx_dom_weak::{types::Ref, WeakDom};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::path_serializer::display_absolute;
+
+use super::{inst_path, FsSnapshot};
+
+/// How planned changes are presented to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffFormat {
+    /// A human-readable listing in the style of a unified diff.
+    #[default]
+    Unified,
+    /// A JSON array of [`PlannedChange`]s, for tooling.
+    Json,
+}
+
+impl FromStr for DiffFormat {
+    type Err = DiffFormatParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "unified" => Ok(DiffFormat::Unified),
+            "json" => Ok(DiffFormat::Json),
+            _ => Err(DiffFormatParseError {
+                attempted: source.to_owned(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid diff format '{attempted}'. Valid values are: unified, json")]
+pub struct DiffFormatParseError {
+    attempted: String,
+}
+
+/// The kind of filesystem operation a [`PlannedChange`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffOp {
+    /// The path does not exist yet and will be created.
+    Add,
+    /// The path exists and will be overwritten.
+    Modify,
+    /// The path exists and will be removed.
+    Remove,
+}
+
+impl DiffOp {
+    fn marker(self) -> char {
+        match self {
+            DiffOp::Add => '+',
+            DiffOp::Modify => '~',
+            DiffOp::Remove => '-',
+        }
+    }
+}
+
+/// A single filesystem change that syncback plans to make.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChange {
+    /// The path being changed, relative to the project folder and using
+    /// forward slashes.
+    pub path: String,
+    pub op: DiffOp,
+    /// The path of the Instance that produced this file, if known. Removed
+    /// paths have no Instance in the new tree, so this is `None` for them.
+    pub instance_path: Option<String>,
+}
+
+/// Collects the changes described by `snapshot` into a list sorted by path.
+///
+/// `vfs` is used to tell additions apart from overwrites, and
+/// `instance_paths` (as returned by syncback) to attribute each written path
+/// to the Instance in `new_tree` that produced it.
+pub fn planned_changes(
+    snapshot: &FsSnapshot,
+    vfs: &Vfs,
+    new_tree: &WeakDom,
+    instance_paths: &HashMap<Ref, Vec<PathBuf>>,
+    base_path: &Path,
+) -> Vec<PlannedChange> {
+    let mut owners: HashMap<&Path, Ref> = HashMap::new();
+    for (referent, paths) in instance_paths {
+        for path in paths {
+            owners.insert(path.as_path(), *referent);
+        }
+    }
+
+    let relative = |path: &Path| display_absolute(path.strip_prefix(base_path).unwrap_or(path));
+
+    let mut changes = Vec::new();
+    for path in snapshot.added_paths() {
+        let op = if vfs.exists(path).unwrap_or(false) {
+            DiffOp::Modify
+        } else {
+            DiffOp::Add
+        };
+        changes.push(PlannedChange {
+            path: relative(path),
+            op,
+            instance_path: owners
+                .get(path)
+                .map(|referent| inst_path(new_tree, *referent)),
+        });
+    }
+    for path in snapshot.removed_paths() {
+        changes.push(PlannedChange {
+            path: relative(path),
+            op: DiffOp::Remove,
+            instance_path: None,
+        });
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path).then(a.op.cmp(&b.op)));
+    changes
+}
+
+/// Renders `changes` in the requested format.
+pub fn render_changes(changes: &[PlannedChange], format: DiffFormat) -> String {
+    match format {
+        DiffFormat::Unified => render_unified(changes),
+        DiffFormat::Json => {
+            let mut output = serde_json::to_string_pretty(changes)
+                .expect("planned changes should always serialize");
+            output.push('\n');
+            output
+        }
+    }
+}
+
+fn render_unified(changes: &[PlannedChange]) -> String {
+    let mut output = String::new();
+    for change in changes {
+        let (old, new) = match change.op {
+            DiffOp::Add => ("/dev/null".to_owned(), format!("b/{}", change.path)),
+            DiffOp::Modify => (format!("a/{}", change.path), format!("b/{}", change.path)),
+            DiffOp::Remove => (format!("a/{}", change.path), "/dev/null".to_owned()),
+        };
+        writeln!(output, "--- {old}").unwrap();
+        writeln!(output, "+++ {new}").unwrap();
+        match &change.instance_path {
+            Some(instance_path) => {
+                writeln!(output, "{} {instance_path}", change.op.marker()).unwrap()
+            }
+            None => writeln!(output, "{}", change.op.marker()).unwrap(),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use memofs::InMemoryFs;
+    use rbx_dom_weak::InstanceBuilder;
+
+    fn fixture() -> Vec<PlannedChange> {
+        let mut imfs = InMemoryFs::new();
+        imfs.load_snapshot("/project", memofs::VfsSnapshot::empty_dir())
+            .unwrap();
+        imfs.load_snapshot(
+            "/project/src/Existing.luau",
+            memofs::VfsSnapshot::file("return nil"),
+        )
+        .unwrap();
+        imfs.load_snapshot(
+            "/project/src/Stale.luau",
+            memofs::VfsSnapshot::file("return nil"),
+        )
+        .unwrap();
+        let vfs = Vfs::new(imfs);
+
+        let mut tree = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let root = tree.root_ref();
+        let new_module = tree.insert(root, InstanceBuilder::new("ModuleScript").with_name("New"));
+        let existing = tree.insert(
+            root,
+            InstanceBuilder::new("ModuleScript").with_name("Existing"),
+        );
+
+        let mut instance_paths = HashMap::new();
+        instance_paths.insert(new_module, vec![PathBuf::from("/project/src/New.luau")]);
+        instance_paths.insert(existing, vec![PathBuf::from("/project/src/Existing.luau")]);
+
+        let mut snapshot = FsSnapshot::new()
+            .with_added_file("/project/src/New.luau", b"return 1".to_vec())
+            .with_added_file("/project/src/Existing.luau", b"return 2".to_vec());
+        snapshot.remove_file("/project/src/Stale.luau");
+
+        planned_changes(
+            &snapshot,
+            &vfs,
+            &tree,
+            &instance_paths,
+            Path::new("/project"),
+        )
+    }
+
+    #[test]
+    fn planned_changes_are_sorted_and_attributed() {
+        let changes = fixture();
+        assert_eq!(
+            changes,
+            vec![
+                PlannedChange {
+                    path: "src/Existing.luau".into(),
+                    op: DiffOp::Modify,
+                    instance_path: Some("Existing".into()),
+                },
+                PlannedChange {
+                    path: "src/New.luau".into(),
+                    op: DiffOp::Add,
+                    instance_path: Some("New".into()),
+                },
+                PlannedChange {
+                    path: "src/Stale.luau".into(),
+                    op: DiffOp::Remove,
+                    instance_path: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unified_format() {
+        let rendered = render_changes(&fixture(), DiffFormat::Unified);
+        assert_eq!(
+            rendered,
+            "--- a/src/Existing.luau\n\
+             +++ b/src/Existing.luau\n\
+             ~ Existing\n\
+             --- /dev/null\n\
+             +++ b/src/New.luau\n\
+             + New\n\
+             --- a/src/Stale.luau\n\
+             +++ /dev/null\n\
+             -\n"
+        );
+    }
+
+    #[test]
+    fn json_format() {
+        let rendered = render_changes(&fixture(), DiffFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                { "path": "src/Existing.luau", "op": "modify", "instancePath": "Existing" },
+                { "path": "src/New.luau", "op": "add", "instancePath": "New" },
+                { "path": "src/Stale.luau", "op": "remove", "instancePath": null },
+            ])
+        );
+    }
+}
@@ -9,6 +9,8 @@ use rbx_dom_weak::Instance;
 
 use crate::{snapshot::InstanceWithMeta, snapshot_middleware::Middleware};
 
+use super::MetaFormat;
+
 /// Generates a filesystem name for an instance.
 /// Returns `(filename, needs_meta_name, dedup_key)`.
 ///
@@ -38,6 +40,20 @@ pub fn name_for_inst<'a>(
                 .file_name()
                 .and_then(|s| s.to_str())
                 .context("sources on the file system should be valid unicode and not be stubs")?;
+            // An Instance that was a single file but now needs a directory
+            // keeps its name, minus the file's extension.
+            if let Some(old_middleware) = old_inst.metadata().middleware {
+                if middleware.is_dir() && !old_middleware.is_dir() {
+                    let mut slug = strip_middleware_extension(name, old_middleware);
+                    if slug == name {
+                        slug = strip_script_suffix(
+                            source.file_stem().and_then(|s| s.to_str()).unwrap_or(name),
+                        )
+                        .to_string();
+                    }
+                    return Ok((Cow::Owned(slug.clone()), false, slug));
+                }
+            }
             // dedup_key is the full filesystem name (including extension).
             // For Dir middleware the filename IS the directory name (no extension).
             let dedup_key = name.to_string();
@@ -61,6 +77,7 @@ pub fn name_for_inst<'a>(
             middleware,
             Middleware::Dir
                 | Middleware::CsvDir
+                | Middleware::JsonModelDir
                 | Middleware::ServerScriptDir
                 | Middleware::ClientScriptDir
                 | Middleware::ModuleScriptDir
@@ -92,6 +109,7 @@ pub fn strip_middleware_extension(filename: &str, middleware: Middleware) -> Str
     match middleware {
         Middleware::Dir
         | Middleware::CsvDir
+        | Middleware::JsonModelDir
         | Middleware::ServerScriptDir
         | Middleware::ClientScriptDir
         | Middleware::ModuleScriptDir
@@ -126,13 +144,15 @@ pub fn extension_for_middleware(middleware: Middleware) -> &'static str {
         Middleware::Rbxmx => "rbxmx",
         Middleware::Toml => "toml",
         Middleware::Text => "txt",
-        Middleware::Yaml => "yml",
+        Middleware::Yaml | Middleware::YamlString => "yml",
+        Middleware::Markdown => "md",
 
         // These are manually specified and not `_` to guard against future
         // middleware additions missing this function.
         Middleware::Ignore => unimplemented!("syncback does not work on Ignore middleware"),
         Middleware::Dir
         | Middleware::CsvDir
+        | Middleware::JsonModelDir
         | Middleware::ServerScriptDir
         | Middleware::ClientScriptDir
         | Middleware::ModuleScriptDir
@@ -385,12 +405,21 @@ pub fn strip_script_suffix(stem: &str) -> &str {
 /// Strips the file extension and any known script suffix (`.server`, `.client`,
 /// etc.) to derive the base name, then appends `.meta.json5`.
 pub fn adjacent_meta_path(script_path: &std::path::Path) -> std::path::PathBuf {
+    adjacent_meta_path_with_format(script_path, MetaFormat::Json5)
+}
+
+/// Like [`adjacent_meta_path`], but returns the path of a meta file written
+/// in `format`, e.g. `parent/Foo_Bar.meta.toml`.
+pub fn adjacent_meta_path_with_format(
+    script_path: &std::path::Path,
+    format: MetaFormat,
+) -> std::path::PathBuf {
     let stem = script_path
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("");
     let base = strip_script_suffix(stem);
-    script_path.with_file_name(format!("{}.meta.json5", base))
+    script_path.with_file_name(format!("{}.{}", base, format.extension()))
 }
 
 #[cfg(test)]
@@ -2365,6 +2394,13 @@ mod tests {
         assert_eq!(meta, std::path::PathBuf::from("src/Old.meta.json5"));
     }
 
+    #[test]
+    fn adjacent_meta_toml() {
+        let path = std::path::Path::new("src/Key_Script.server.luau");
+        let meta = adjacent_meta_path_with_format(path, MetaFormat::Toml);
+        assert_eq!(meta, std::path::PathBuf::from("src/Key_Script.meta.toml"));
+    }
+
     // ── tilde dedup end-to-end (unit level) ──────────────────────────
 
     #[test]
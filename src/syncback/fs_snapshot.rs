@@ -8,11 +8,18 @@ use std::{
 #[cfg(windows)]
 use std::{thread, time::Duration};
 
-use memofs::Vfs;
+use memofs::{FsOp, Vfs};
 use rayon::prelude::*;
 
 use crate::git::GitIndexCache;
 
+use super::PHASE_LOG_TARGET;
+
+/// The most threads [`FsSnapshot::write_to_vfs_with_threads`] uses by
+/// default. File writes stop getting faster well before the CPU runs out of
+/// cores, and spinning disks slow down when too many writes compete.
+pub const DEFAULT_MAX_FS_THREADS: usize = 4;
+
 /// Maximum number of retry attempts for filesystem operations on Windows.
 /// Windows can have transient "Access denied" errors due to antivirus scanning,
 /// filesystem timing, or file handle release delays.
@@ -198,55 +205,53 @@ impl FsSnapshot {
     ///
     /// This includes removals, but makes no effort to minimize work done.
     pub fn write_to_vfs<P: AsRef<Path>>(&self, base: P, vfs: &Vfs) -> io::Result<()> {
-        let mut lock = vfs.lock();
-
         let base_path = base.as_ref();
-        for dir_path in &self.added_dirs {
-            match lock.create_dir_all(base_path.join(dir_path)) {
-                Ok(_) => (),
-                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => (),
-                Err(err) => return Err(err),
-            };
-        }
-        for (path, contents) in &self.added_files {
-            lock.write(base_path.join(path), contents)?;
-        }
-        for dir_path in &self.removed_dirs {
-            let full_path = base_path.join(dir_path);
-            match lock.remove_dir_all(&full_path) {
-                Ok(()) => (),
-                // Directory might have already been removed (e.g., added twice via different code paths)
-                Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                    log::debug!(
-                        "Directory already removed or doesn't exist: {}",
-                        full_path.display()
-                    );
-                }
-                Err(err) => return Err(err),
-            }
-        }
+        let mut ops = Vec::with_capacity(
+            self.added_dirs.len()
+                + self.added_files.len()
+                + self.removed_dirs.len()
+                + self.removed_files.len(),
+        );
+        ops.extend(
+            self.added_dirs
+                .iter()
+                .map(|dir_path| FsOp::CreateDir(base_path.join(dir_path))),
+        );
+        ops.extend(
+            self.added_files
+                .iter()
+                .map(|(path, contents)| FsOp::Write(base_path.join(path), contents)),
+        );
+        ops.extend(
+            self.removed_dirs
+                .iter()
+                .map(|dir_path| FsOp::RemoveDirAll(base_path.join(dir_path))),
+        );
         // Only remove files that aren't already inside a directory we're removing.
         // remove_dir_all already deleted those files recursively.
-        // Also handle the case where the same file might be listed twice (absolute vs relative path)
-        // by gracefully handling "file not found" errors.
-        for path in &self.removed_files {
-            let is_inside_removed_dir = self.removed_dirs.iter().any(|dir| path.starts_with(dir));
-            if is_inside_removed_dir {
-                continue;
-            }
-            let full_path = base_path.join(path);
-            match lock.remove_file(&full_path) {
-                Ok(()) => (),
-                // File might have already been removed (e.g., by remove_dir_all, or listed twice
-                // with different path formats like absolute vs relative)
-                Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                    log::debug!(
-                        "File already removed or doesn't exist: {}",
-                        full_path.display()
-                    );
+        ops.extend(
+            self.removed_files
+                .iter()
+                .filter(|path| !self.removed_dirs.iter().any(|dir| path.starts_with(dir)))
+                .map(|path| FsOp::RemoveFile(base_path.join(path))),
+        );
+
+        let mut lock = vfs.lock();
+        let mut next = 0;
+        while let Err(err) = lock.apply_batch(&ops[next..]) {
+            let failed = &ops[next + err.index];
+            match failed {
+                // Paths might have already been removed (e.g., added twice via different
+                // code paths, or listed twice with different path formats like absolute
+                // vs relative), so removing something that isn't there is fine.
+                FsOp::RemoveDirAll(path) | FsOp::RemoveFile(path)
+                    if err.source.kind() == io::ErrorKind::NotFound =>
+                {
+                    log::debug!("Already removed or doesn't exist: {}", path.display());
                 }
-                Err(err) => return Err(err),
+                _ => return Err(err.into()),
             }
+            next += err.index + 1;
         }
         drop(lock);
 
@@ -270,6 +275,30 @@ impl FsSnapshot {
         Ok(())
     }
 
+    /// Like [`write_to_vfs_parallel`](Self::write_to_vfs_parallel), but writes
+    /// and removes files on a dedicated pool of `threads` threads instead of
+    /// rayon's global pool, which is sized for CPU-bound work.
+    pub fn write_to_vfs_with_threads<P: AsRef<Path>>(
+        &self,
+        base: P,
+        vfs: &Vfs,
+        git_cache: Option<&GitIndexCache>,
+        threads: usize,
+    ) -> io::Result<()> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .thread_name(|index| format!("syncback-fs-{index}"))
+            .build()
+            .map_err(io::Error::other)?;
+
+        log::debug!(
+            target: PHASE_LOG_TARGET,
+            "[PERF] write_to_vfs_with_threads: {} threads",
+            pool.current_num_threads()
+        );
+        pool.install(|| self.write_to_vfs_parallel(base, vfs, git_cache))
+    }
+
     /// Writes the `FsSnapshot` to the provided VFS using parallel file writes.
     ///
     /// This is optimized for syncback operations where many files need to be written.
@@ -292,6 +321,7 @@ impl FsSnapshot {
         let base_path = base.as_ref();
 
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] write_to_vfs_parallel: {} files, {} dirs to add; {} files, {} dirs to remove",
             self.added_files.len(),
             self.added_dirs.len(),
@@ -319,6 +349,7 @@ impl FsSnapshot {
             }
         } // Release lock before parallel phase
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF]   phase1 create dirs: {:.3}s",
             phase1_timer.elapsed().as_secs_f64()
         );
@@ -371,6 +402,7 @@ impl FsSnapshot {
         });
 
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF]   phase2 write files: {:.3}s (git_skip={}, byte_skip={}, size_diff={})",
             phase2_timer.elapsed().as_secs_f64(),
             git_skipped.load(Ordering::Relaxed),
@@ -411,6 +443,7 @@ impl FsSnapshot {
         });
 
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF]   phase3 remove files: {:.3}s",
             phase3_timer.elapsed().as_secs_f64()
         );
@@ -448,6 +481,7 @@ impl FsSnapshot {
         }
 
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF]   phase4 remove dirs: {:.3}s",
             phase4_timer.elapsed().as_secs_f64()
         );
@@ -476,6 +510,14 @@ impl FsSnapshot {
         Ok(())
     }
 
+    /// Drops every file addition and every removal from this `FsSnapshot`,
+    /// keeping only the directories it adds.
+    pub fn retain_added_dirs(&mut self) {
+        self.added_files.clear();
+        self.removed_files.clear();
+        self.removed_dirs.clear();
+    }
+
     /// Returns whether this `FsSnapshot` is empty or not.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -485,6 +527,15 @@ impl FsSnapshot {
             && self.removed_dirs.is_empty()
     }
 
+    /// Returns an estimate of how many bytes writing this `FsSnapshot` will
+    /// need. Overwritten files are counted in full, so this errs high.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.added_files
+            .values()
+            .map(|contents| contents.len() as u64)
+            .sum()
+    }
+
     /// Returns a list of paths that would be added by this `FsSnapshot`.
     #[inline]
     pub fn added_paths(&self) -> Vec<&Path> {
@@ -505,6 +556,13 @@ impl FsSnapshot {
         list
     }
 
+    /// Returns the contents this `FsSnapshot` would write to `path`, if it
+    /// adds that file.
+    #[inline]
+    pub fn added_file_contents(&self, path: &Path) -> Option<&[u8]> {
+        self.added_files.get(path).map(Vec::as_slice)
+    }
+
     /// Returns a list of file paths that would be added by this `FsSnapshot`
     #[inline]
     pub fn added_files(&self) -> Vec<&Path> {
@@ -572,7 +630,7 @@ impl FsSnapshot {
     /// Post-process `Rojo_Ref_*` attribute paths in meta/model JSON files.
     ///
     /// For each entry in `substitutions` (old_path → new_path), finds all
-    /// `.meta.json5` and `.model.json5` files in the snapshot and replaces
+    /// `.meta.json5`, `.meta.toml` and `.model.json5` files in the snapshot and replaces
     /// occurrences of the old path with the new path **only on lines that
     /// contain a `Rojo_Ref_` key**. This prevents unrelated string values
     /// (property values, comments, etc.) from being altered by the
@@ -594,6 +652,7 @@ impl FsSnapshot {
                 let name = p.file_name().and_then(|f| f.to_str()).unwrap_or("");
                 name.ends_with(".meta.json5")
                     || name.ends_with(".meta.json")
+                    || name.ends_with(".meta.toml")
                     || name.ends_with(".model.json5")
                     || name.ends_with(".model.json")
             })
@@ -647,6 +706,57 @@ impl FsSnapshot {
 mod tests {
     use super::*;
 
+    /// Reads every file under `dir` into a map keyed by its path relative to
+    /// `dir`.
+    fn read_tree(dir: &Path) -> std::collections::BTreeMap<PathBuf, Vec<u8>> {
+        let mut files = std::collections::BTreeMap::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in std::fs::read_dir(&current).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    let contents = std::fs::read(&path).unwrap();
+                    files.insert(path.strip_prefix(dir).unwrap().to_path_buf(), contents);
+                }
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn thread_count_does_not_change_written_files() {
+        let mut snapshot = FsSnapshot::new();
+        for i in 0..8 {
+            let dir = PathBuf::from(format!("dir{i}"));
+            snapshot.add_dir(&dir);
+            for j in 0..16 {
+                snapshot.add_file(
+                    dir.join(format!("file{j}.luau")),
+                    format!("return {i} + {j}").into_bytes(),
+                );
+            }
+        }
+        snapshot.remove_file("stale.luau");
+
+        let mut results = Vec::new();
+        for threads in [1, 2, 8] {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join("stale.luau"), "return nil").unwrap();
+
+            snapshot
+                .write_to_vfs_with_threads(dir.path(), &Vfs::new_oneshot(), None, threads)
+                .unwrap();
+            results.push(read_tree(dir.path()));
+        }
+
+        assert_eq!(results[0].len(), 8 * 16);
+        assert!(!results[0].contains_key(Path::new("stale.luau")));
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[0], results[2]);
+    }
+
     #[test]
     fn fix_ref_paths_only_touches_ref_lines() {
         let mut snap = FsSnapshot::new();
@@ -0,0 +1,154 @@
+//! Light support for `.gitattributes`, so clean syncback doesn't remove files
+//! git handles specially, like Git LFS pointers and generated assets, just
+//! because the project didn't produce them.
+
+use std::path::{Path, PathBuf};
+
+use memofs::{IoResultExt, Vfs};
+
+use crate::glob::Glob;
+
+const GIT_ATTRIBUTES_FILE_NAME: &str = ".gitattributes";
+
+struct AttributeRule {
+    /// The folder of the `.gitattributes` file the rule came from. Patterns
+    /// are matched against paths relative to it.
+    base: PathBuf,
+    glob: Glob,
+}
+
+/// The patterns from the `.gitattributes` files that apply to a project
+/// which mark files as stored through a filter like Git LFS, as binary, or
+/// as generated.
+#[derive(Default)]
+pub struct GitAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+impl GitAttributes {
+    /// Reads the `.gitattributes` file in `project_path` and in each of its
+    /// parents, stopping at the root of the git repository it's in.
+    pub fn load(vfs: &Vfs, project_path: &Path) -> Self {
+        let mut attributes = Self::default();
+
+        for dir in project_path.ancestors() {
+            let path = dir.join(GIT_ATTRIBUTES_FILE_NAME);
+            match vfs.read_to_string(&path).with_not_found() {
+                Ok(Some(contents)) => attributes.add_file(dir, &contents),
+                Ok(None) => {}
+                Err(err) => log::warn!("Could not read {}: {}", path.display(), err),
+            }
+
+            if let Ok(true) = vfs.exists(&dir.join(".git")) {
+                break;
+            }
+        }
+
+        attributes
+    }
+
+    fn add_file(&mut self, base: &Path, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            if !fields.any(is_special_attribute) {
+                continue;
+            }
+            // Git doesn't allow negative patterns in .gitattributes, and
+            // patterns ending in a slash never match anything.
+            if pattern.starts_with('!') || pattern.ends_with('/') {
+                continue;
+            }
+
+            // Like .gitignore, a pattern without a slash matches a file name
+            // at any depth, and one with a slash is relative to the folder
+            // the .gitattributes file is in.
+            let glob_pattern = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_owned(),
+                None if pattern.contains('/') => pattern.to_owned(),
+                None => format!("**/{pattern}"),
+            };
+
+            match Glob::new(&glob_pattern) {
+                Ok(glob) => self.rules.push(AttributeRule {
+                    base: base.to_path_buf(),
+                    glob,
+                }),
+                Err(err) => log::warn!(
+                    "Ignoring the pattern '{}' in {}: {}",
+                    pattern,
+                    base.join(GIT_ATTRIBUTES_FILE_NAME).display(),
+                    err
+                ),
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns whether `path` is marked by any of the loaded patterns.
+    pub fn is_marked(&self, path: &Path) -> bool {
+        self.rules.iter().any(|rule| {
+            path.strip_prefix(&rule.base)
+                .is_ok_and(|relative| rule.glob.is_match(relative))
+        })
+    }
+}
+
+/// Returns whether `attribute` marks a file as something git handles
+/// specially: stored through a filter such as Git LFS, binary, or generated.
+fn is_special_attribute(attribute: &str) -> bool {
+    attribute.starts_with("filter=")
+        || matches!(
+            attribute,
+            "binary" | "-text" | "linguist-generated" | "linguist-generated=true"
+        )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attributes(contents: &str) -> GitAttributes {
+        let mut attributes = GitAttributes::default();
+        attributes.add_file(Path::new("/project"), contents);
+        attributes
+    }
+
+    #[test]
+    fn lfs_patterns_mark_files_at_any_depth() {
+        let attributes = attributes(
+            "# Assets\n\
+             *.png filter=lfs diff=lfs merge=lfs -text\n\
+             *.luau text eol=lf\n",
+        );
+
+        assert!(attributes.is_marked(Path::new("/project/Logo.png")));
+        assert!(attributes.is_marked(Path::new("/project/src/ui/Icon.png")));
+        assert!(!attributes.is_marked(Path::new("/project/src/Main.luau")));
+        assert!(!attributes.is_marked(Path::new("/elsewhere/Logo.png")));
+    }
+
+    #[test]
+    fn patterns_with_slashes_are_relative() {
+        let attributes = attributes(
+            "/generated/** linguist-generated\n\
+             assets/*.bin binary\n\
+             build/ binary\n",
+        );
+
+        assert!(attributes.is_marked(Path::new("/project/generated/Types.luau")));
+        assert!(attributes.is_marked(Path::new("/project/assets/blob.bin")));
+        assert!(!attributes.is_marked(Path::new("/project/src/generated/Types.luau")));
+        assert!(!attributes.is_marked(Path::new("/project/build/out.bin")));
+    }
+}
@@ -0,0 +1,327 @@
+//! Keeps the hashes of the old tree between incremental syncbacks, so
+//! subtrees whose files haven't changed on disk since the last run don't have
+//! to be hashed again.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use blake3::Hash;
+use rbx_dom_weak::types::Ref;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    snapshot::RojoTree,
+    syncback::{descendants, inst_path},
+    Project,
+};
+
+use super::{add_children, hash_inst_filtered};
+
+/// Where the cache is kept, relative to the project folder.
+pub const HASH_CACHE_PATH: &str = ".rojo/syncback-hashes.bin";
+
+/// Bumped whenever the layout of the cache or the way Instances are hashed
+/// changes, so caches written by other versions are ignored.
+const HASH_CACHE_VERSION: u32 = 1;
+
+/// The size and modification time of a file, used to tell whether it changed
+/// without reading it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    len: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl FileStamp {
+    /// Returns the stamp of the file or directory at `path`, or `None` if it
+    /// doesn't exist or its modification time isn't available.
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs_err::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedHash {
+    hash: [u8; 32],
+    child_count: usize,
+    /// Every path the Instance was snapshotted from, with its stamp when it
+    /// was hashed.
+    files: Vec<(PathBuf, Option<FileStamp>)>,
+}
+
+/// The hashes of the old tree from the last incremental syncback, keyed by
+/// Instance path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HashCache {
+    version: u32,
+    /// Identifies the settings the hashes were made with. Hashes only cover
+    /// the properties syncback keeps, so they depend on the project's
+    /// syncback rules and on the reflection database.
+    fingerprint: [u8; 32],
+    entries: HashMap<String, CachedHash>,
+    /// Whether this cache differs from the one it was built from, and so
+    /// needs to be saved.
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl HashCache {
+    /// Reads the cache for `project`. A missing or unreadable cache, or one
+    /// made with different settings, is treated as empty.
+    pub fn load(project: &Project) -> Self {
+        let fingerprint = fingerprint(project);
+        let path = project.folder_location().join(HASH_CACHE_PATH);
+        let contents = match fs_err::read(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Self::empty(fingerprint),
+            Err(err) => {
+                log::warn!("Could not read {}: {}", path.display(), err);
+                return Self::empty(fingerprint);
+            }
+        };
+
+        match bincode::serde::decode_from_slice::<Self, _>(&contents, bincode::config::standard()) {
+            Ok((cache, _))
+                if cache.version == HASH_CACHE_VERSION && cache.fingerprint == fingerprint =>
+            {
+                cache
+            }
+            Ok(_) => {
+                log::debug!("Ignoring {} because it is out of date", path.display());
+                Self::empty(fingerprint)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Ignoring {} because it could not be parsed: {}",
+                    path.display(),
+                    err
+                );
+                Self::empty(fingerprint)
+            }
+        }
+    }
+
+    /// Writes the cache for the project in `base_path`, unless it's the same
+    /// as the one already there.
+    pub fn save(&self, base_path: &Path) -> io::Result<()> {
+        let path = base_path.join(HASH_CACHE_PATH);
+        if !self.changed {
+            log::debug!("{} is up to date", path.display());
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let contents = bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .map_err(io::Error::other)?;
+        fs_err::write(path, contents)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn empty(fingerprint: [u8; 32]) -> Self {
+        Self {
+            version: HASH_CACHE_VERSION,
+            fingerprint,
+            entries: HashMap::new(),
+            changed: false,
+        }
+    }
+}
+
+/// Like [`hash_tree`](super::hash_tree) for the old tree, but reuses the hash
+/// from `cache` for every subtree whose files all have the same size and
+/// modification time they had when it was cached.
+///
+/// Returns the hashes, a cache to save for the next run, and how many hashes
+/// were reused.
+pub fn hash_tree_cached(
+    project: &Project,
+    tree: &RojoTree,
+    cache: &HashCache,
+) -> (HashMap<Ref, Hash>, HashCache, usize) {
+    let dom = tree.inner();
+    let mut order = descendants(dom, tree.get_root_id());
+    let mut map: HashMap<Ref, Hash> = HashMap::with_capacity(order.len());
+    let mut updated = HashCache::empty(cache.fingerprint);
+    let mut reused = 0;
+
+    // Siblings with the same name share a path, so neither can be cached.
+    let paths: HashMap<Ref, String> = order
+        .iter()
+        .map(|referent| (*referent, inst_path(dom, *referent)))
+        .collect();
+    let mut path_counts: HashMap<&str, usize> = HashMap::with_capacity(paths.len());
+    for path in paths.values() {
+        *path_counts.entry(path.as_str()).or_default() += 1;
+    }
+
+    // Whether the hash of each subtree so far came from the cache.
+    let mut unchanged: HashMap<Ref, bool> = HashMap::with_capacity(order.len());
+
+    let mut prop_list = Vec::with_capacity(2);
+    let mut child_hashes = Vec::new();
+    let mut stamps: HashMap<PathBuf, Option<FileStamp>> = HashMap::new();
+
+    while let Some(referent) = order.pop() {
+        let inst = dom.get_by_ref(referent).unwrap();
+        let path = &paths[&referent];
+        let files = stamped_files(tree, referent, &mut stamps);
+        // Without any files there's nothing to tell a stale hash by.
+        let cacheable = path_counts[path.as_str()] == 1 && !files.is_empty();
+
+        let cached = cache.entries.get(path).filter(|cached| {
+            cacheable
+                && cached.child_count == inst.children().len()
+                && cached.files == files
+                && inst.children().iter().all(|child| unchanged[child])
+        });
+
+        let hash = match cached {
+            Some(cached) => {
+                reused += 1;
+                Hash::from_bytes(cached.hash)
+            }
+            None => {
+                let mut hasher = hash_inst_filtered(project, inst, &mut prop_list);
+                add_children(inst, &map, &mut child_hashes, &mut hasher);
+                hasher.finalize()
+            }
+        };
+        unchanged.insert(referent, cached.is_some());
+        map.insert(referent, hash);
+
+        if cacheable {
+            updated.entries.insert(
+                path.clone(),
+                CachedHash {
+                    hash: *hash.as_bytes(),
+                    child_count: inst.children().len(),
+                    files,
+                },
+            );
+        }
+    }
+
+    // Every reused hash is carried over unchanged, so the cache only changed
+    // if something wasn't reused or an old entry was dropped.
+    updated.changed = reused != updated.entries.len() || cache.entries.len() != reused;
+
+    (map, updated, reused)
+}
+
+/// Returns the files `referent` was snapshotted from, with their stamps.
+/// Instances inside a model file don't record any files of their own, so
+/// they're stamped with the files of the nearest ancestor that does. Stamps
+/// are looked up once per path and kept in `stamps`.
+fn stamped_files(
+    tree: &RojoTree,
+    referent: Ref,
+    stamps: &mut HashMap<PathBuf, Option<FileStamp>>,
+) -> Vec<(PathBuf, Option<FileStamp>)> {
+    let dom = tree.inner();
+    let mut current = referent;
+    loop {
+        let relevant_paths = tree
+            .get_metadata(current)
+            .map(|metadata| metadata.relevant_paths.as_slice())
+            .unwrap_or_default();
+        if !relevant_paths.is_empty() {
+            return relevant_paths
+                .iter()
+                .map(|path| {
+                    let stamp = *stamps
+                        .entry(path.clone())
+                        .or_insert_with(|| FileStamp::of(path));
+                    (path.clone(), stamp)
+                })
+                .collect();
+        }
+        match dom.get_by_ref(current).map(|inst| inst.parent()) {
+            Some(parent) if parent.is_some() => current = parent,
+            _ => return Vec::new(),
+        }
+    }
+}
+
+/// Identifies everything besides the files themselves that the hashes depend
+/// on.
+fn fingerprint(project: &Project) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    if let Ok(database) = rbx_reflection_database::get() {
+        for part in database.version {
+            hasher.update(&part.to_le_bytes());
+        }
+    }
+    if let Ok(rules) = serde_json::to_vec(&project.syncback_rules) {
+        hasher.update(&rules);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cache_round_trips_through_bincode() {
+        let mut cache = HashCache::empty([7; 32]);
+        cache.entries.insert(
+            "ReplicatedStorage/Module".to_owned(),
+            CachedHash {
+                hash: [1; 32],
+                child_count: 2,
+                files: vec![(
+                    PathBuf::from("/project/src/Module.luau"),
+                    Some(FileStamp {
+                        len: 12,
+                        modified_secs: 1_700_000_000,
+                        modified_nanos: 5,
+                    }),
+                )],
+            },
+        );
+
+        let encoded = bincode::serde::encode_to_vec(&cache, bincode::config::standard()).unwrap();
+        let (decoded, _): (HashCache, _) =
+            bincode::serde::decode_from_slice(&encoded, bincode::config::standard()).unwrap();
+
+        assert_eq!(decoded.fingerprint, [7; 32]);
+        let entry = &decoded.entries["ReplicatedStorage/Module"];
+        assert_eq!(entry.hash, [1; 32]);
+        assert_eq!(entry.child_count, 2);
+        assert_eq!(entry.files, cache.entries["ReplicatedStorage/Module"].files);
+    }
+
+    #[test]
+    fn unchanged_cache_is_not_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HASH_CACHE_PATH);
+
+        HashCache::empty([7; 32]).save(dir.path()).unwrap();
+        assert!(!path.exists());
+
+        let mut cache = HashCache::empty([7; 32]);
+        cache.changed = true;
+        cache.save(dir.path()).unwrap();
+        assert!(path.is_file());
+    }
+}
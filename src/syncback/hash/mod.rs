@@ -1,6 +1,8 @@
 //! Hashing utilities for a WeakDom.
+mod cache;
 mod variant;
 
+pub use cache::{hash_tree_cached, HashCache, HASH_CACHE_PATH};
 pub use variant::*;
 
 use blake3::{Hash, Hasher};
@@ -10,7 +12,10 @@ use rbx_dom_weak::{
 };
 use std::collections::HashMap;
 
-use crate::{variant_eq::variant_eq, Project};
+use crate::{
+    variant_eq::{variant_eq, variant_eq_approx},
+    Project,
+};
 
 use super::{descendants, filter_properties_preallocated};
 
@@ -50,6 +55,142 @@ pub fn hash_instance(project: &Project, dom: &WeakDom, referent: Ref) -> Option<
     Some(hash_inst_filtered(project, inst, &mut prop_list).finalize())
 }
 
+/// Hashes the contents of a file on disk, as recorded in the sync state.
+#[inline]
+pub fn hash_file_contents(contents: &[u8]) -> Hash {
+    blake3::hash(contents)
+}
+
+/// Two hashed trees whose subtrees can be compared with a float tolerance.
+///
+/// Hashes only tell whether two subtrees are exactly the same, so float noise
+/// from a round trip through a model or meta file makes them differ. This
+/// decides whether such a subtree really changed.
+pub struct ApproxMatcher<'a> {
+    pub project: &'a Project,
+    pub old_dom: &'a WeakDom,
+    pub old_hashes: &'a HashMap<Ref, Hash>,
+    pub new_dom: &'a WeakDom,
+    pub new_hashes: &'a HashMap<Ref, Hash>,
+    /// How far apart float components can be while still counting as equal.
+    pub epsilon: f64,
+}
+
+impl ApproxMatcher<'_> {
+    /// Returns whether the subtrees at `old_ref` and `new_ref` are the same
+    /// when float components within `epsilon` of each other are treated as
+    /// equal.
+    ///
+    /// Children whose hashes match are equal without being visited, so this
+    /// only walks the parts of the subtrees that actually differ.
+    pub fn subtree_eq(&self, old_ref: Ref, new_ref: Ref) -> bool {
+        let (Some(old_inst), Some(new_inst)) = (
+            self.old_dom.get_by_ref(old_ref),
+            self.new_dom.get_by_ref(new_ref),
+        ) else {
+            return false;
+        };
+        if old_inst.children().len() != new_inst.children().len()
+            || !self.instance_eq(old_inst, new_inst)
+        {
+            return false;
+        }
+
+        let mut old_unmatched: Vec<(&Hash, &Instance)> = Vec::new();
+        for child_ref in old_inst.children() {
+            match (
+                self.old_hashes.get(child_ref),
+                self.old_dom.get_by_ref(*child_ref),
+            ) {
+                (Some(hash), Some(child)) => old_unmatched.push((hash, child)),
+                _ => return false,
+            }
+        }
+        let mut new_unmatched: Vec<&Instance> = Vec::new();
+        for child_ref in new_inst.children() {
+            let (Some(hash), Some(child)) = (
+                self.new_hashes.get(child_ref),
+                self.new_dom.get_by_ref(*child_ref),
+            ) else {
+                return false;
+            };
+            match old_unmatched
+                .iter()
+                .position(|(old_hash, _)| *old_hash == hash)
+            {
+                Some(index) => {
+                    old_unmatched.swap_remove(index);
+                }
+                None => new_unmatched.push(child),
+            }
+        }
+
+        // What's left has to pair up by name and class. Siblings sharing both
+        // can't be told apart, so they count as changed.
+        let mut old_unmatched: Vec<&Instance> =
+            old_unmatched.into_iter().map(|(_, child)| child).collect();
+        let by_key = |a: &&Instance, b: &&Instance| {
+            (a.name.as_str(), a.class.as_str()).cmp(&(b.name.as_str(), b.class.as_str()))
+        };
+        old_unmatched.sort_by(by_key);
+        new_unmatched.sort_by(by_key);
+        let ambiguous = |children: &[&Instance]| {
+            children
+                .windows(2)
+                .any(|pair| pair[0].name == pair[1].name && pair[0].class == pair[1].class)
+        };
+        if ambiguous(&old_unmatched) || ambiguous(&new_unmatched) {
+            return false;
+        }
+
+        old_unmatched
+            .iter()
+            .zip(&new_unmatched)
+            .all(|(old_child, new_child)| {
+                self.subtree_eq(old_child.referent(), new_child.referent())
+            })
+    }
+
+    /// Compares the name, class and filtered properties of two Instances,
+    /// ignoring their children.
+    fn instance_eq(&self, old_inst: &Instance, new_inst: &Instance) -> bool {
+        if old_inst.name != new_inst.name || old_inst.class != new_inst.class {
+            return false;
+        }
+
+        let old_props = self.non_default_properties(old_inst);
+        let new_props = self.non_default_properties(new_inst);
+        old_props.len() == new_props.len()
+            && old_props.iter().zip(&new_props).all(
+                |((old_name, old_value), (new_name, new_value))| {
+                    old_name == new_name && variant_eq_approx(old_value, new_value, self.epsilon)
+                },
+            )
+    }
+
+    /// Returns the properties of `inst` that [`hash_inst_prefilled`] would
+    /// hash, sorted by name.
+    fn non_default_properties<'inst>(&self, inst: &'inst Instance) -> Vec<(Ustr, &'inst Variant)> {
+        let mut prop_list = Vec::new();
+        filter_properties_preallocated(self.project, inst, &mut prop_list);
+
+        let descriptor = rbx_reflection_database::get()
+            .unwrap()
+            .classes
+            .get(inst.class.as_str());
+        if let Some(descriptor) = descriptor {
+            prop_list.retain(|(name, value)| {
+                descriptor
+                    .default_properties
+                    .get(name.as_str())
+                    .is_none_or(|default| !variant_eq(default, value))
+            });
+        }
+        prop_list.sort_unstable_by_key(|(name, _)| *name);
+        prop_list
+    }
+}
+
 /// Adds the hashes of children for an Instance to the provided Hasher.
 fn add_children(
     inst: &Instance,
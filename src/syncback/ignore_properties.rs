@@ -0,0 +1,64 @@
+//! Support for `syncbackRules.ignoreProperties`, which keeps properties out
+//! of syncback by class. Entries may be exact property names or globs, and
+//! the class key `*` applies to every class.
+
+use anyhow::Context;
+use indexmap::IndexMap;
+use rbx_dom_weak::{ustr, Instance, Ustr, UstrMap, UstrSet};
+
+use crate::glob::Glob;
+
+/// The class key whose entries apply to every class.
+pub const ANY_CLASS: &str = "*";
+
+#[derive(Default)]
+struct ClassRules {
+    names: Vec<Ustr>,
+    globs: Vec<Glob>,
+}
+
+/// The compiled `ignoreProperties` rules for a project.
+pub struct IgnoreProperties {
+    classes: UstrMap<ClassRules>,
+}
+
+impl IgnoreProperties {
+    pub fn compile(rules: &IndexMap<Ustr, Vec<Ustr>>) -> anyhow::Result<Self> {
+        let mut classes = UstrMap::default();
+
+        for (class_name, patterns) in rules {
+            let mut class_rules = ClassRules::default();
+            for pattern in patterns {
+                if pattern.contains(['*', '?', '[', '{']) {
+                    let glob = Glob::new(pattern).with_context(|| {
+                        format!("the pattern '{pattern}' for {class_name} is not a valid glob")
+                    })?;
+                    class_rules.globs.push(glob);
+                } else {
+                    class_rules.names.push(*pattern);
+                }
+            }
+            classes.insert(*class_name, class_rules);
+        }
+
+        Ok(Self { classes })
+    }
+
+    /// Adds the properties of `inst` that the rules for `class_name` ignore
+    /// to `set`. Exact names are added whether or not `inst` has them.
+    pub fn extend_for_class(&self, class_name: &str, inst: &Instance, set: &mut UstrSet) {
+        let Some(class_rules) = self.classes.get(&ustr(class_name)) else {
+            return;
+        };
+
+        set.extend(class_rules.names.iter().copied());
+        if !class_rules.globs.is_empty() {
+            set.extend(inst.properties.keys().copied().filter(|name| {
+                class_rules
+                    .globs
+                    .iter()
+                    .any(|glob| glob.is_match(name.as_str()))
+            }));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Support for `syncbackRules.includeTrees`, which restricts syncback to a
+//! set of subtrees of the place.
+
+use anyhow::Context;
+
+use crate::glob::Glob;
+
+/// How an Instance relates to the configured include set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inclusion {
+    /// The Instance matches an include pattern, or is a descendant of one
+    /// that does. It's synced back as normal.
+    Included,
+    /// The Instance isn't included itself, but an included Instance may be
+    /// below it. It's walked so its children can be reached, but none of its
+    /// own files are written or removed.
+    Ancestor,
+    /// Nothing at or below the Instance can be included. It's skipped.
+    Excluded,
+}
+
+struct IncludePattern {
+    glob: Glob,
+    /// The leading path segments of the pattern that contain no wildcards.
+    literal_prefix: Vec<String>,
+}
+
+/// The compiled `includeTrees` patterns for a project.
+pub struct IncludeTrees {
+    patterns: Vec<IncludePattern>,
+}
+
+impl IncludeTrees {
+    /// Compiles `patterns`, returning `None` if there aren't any, which means
+    /// everything is included.
+    pub fn compile(patterns: &[String]) -> anyhow::Result<Option<Self>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut compiled = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let glob = Glob::new(pattern).with_context(|| {
+                format!("the pattern '{pattern}' is not a valid includeTrees glob")
+            })?;
+            let literal_prefix = pattern
+                .split('/')
+                .take_while(|segment| !segment.contains(['*', '?', '[', '{']))
+                .map(str::to_owned)
+                .collect();
+            compiled.push(IncludePattern {
+                glob,
+                literal_prefix,
+            });
+        }
+
+        Ok(Some(Self { patterns: compiled }))
+    }
+
+    /// Classifies the Instance at `inst_path`, a `/`-separated path like the
+    /// ones `ignoreTrees` matches against.
+    pub fn classify(&self, inst_path: &str) -> Inclusion {
+        if inst_path.is_empty() {
+            return Inclusion::Ancestor;
+        }
+
+        let segments: Vec<&str> = inst_path.split('/').collect();
+
+        for end in 1..=segments.len() {
+            let prefix = segments[..end].join("/");
+            if self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.glob.is_match(&prefix))
+            {
+                return Inclusion::Included;
+            }
+        }
+
+        let could_contain_match = self.patterns.iter().any(|pattern| {
+            pattern
+                .literal_prefix
+                .iter()
+                .zip(&segments)
+                .all(|(literal, segment)| literal == segment)
+        });
+        if could_contain_match {
+            Inclusion::Ancestor
+        } else {
+            Inclusion::Excluded
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn include(patterns: &[&str]) -> IncludeTrees {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        IncludeTrees::compile(&patterns).unwrap().unwrap()
+    }
+
+    #[test]
+    fn empty_includes_everything() {
+        assert!(IncludeTrees::compile(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn literal_pattern() {
+        let include = include(&["ReplicatedStorage/Shared"]);
+
+        assert_eq!(include.classify(""), Inclusion::Ancestor);
+        assert_eq!(include.classify("ReplicatedStorage"), Inclusion::Ancestor);
+        assert_eq!(
+            include.classify("ReplicatedStorage/Shared"),
+            Inclusion::Included
+        );
+        assert_eq!(
+            include.classify("ReplicatedStorage/Shared/Util"),
+            Inclusion::Included
+        );
+        assert_eq!(
+            include.classify("ReplicatedStorage/Client"),
+            Inclusion::Excluded
+        );
+        assert_eq!(include.classify("ServerScriptService"), Inclusion::Excluded);
+    }
+
+    #[test]
+    fn wildcard_pattern() {
+        let include = include(&["ReplicatedStorage/*/Config"]);
+
+        assert_eq!(include.classify("ReplicatedStorage"), Inclusion::Ancestor);
+        assert_eq!(
+            include.classify("ReplicatedStorage/Shared"),
+            Inclusion::Ancestor
+        );
+        assert_eq!(
+            include.classify("ReplicatedStorage/Shared/Config/Values"),
+            Inclusion::Included
+        );
+        assert_eq!(include.classify("Workspace"), Inclusion::Excluded);
+    }
+}
@@ -8,6 +8,8 @@ use anyhow::Context;
 use std::fs;
 use std::path::Path;
 
+use super::MetaFormat;
+
 /// Upsert the `name` field in a `.meta.json5` file.
 ///
 /// If the file exists, parses it and merges the `name` key (preserving other
@@ -91,11 +93,18 @@ pub fn remove_model_name(model_path: &Path) -> anyhow::Result<RemoveNameOutcome>
     Ok(RemoveNameOutcome::FieldRemoved)
 }
 
-/// Update `Rojo_Ref_*` attribute paths in a meta/model JSON5 file.
-///
-/// For each attribute whose key starts with `Rojo_Ref_` and whose string
-/// value starts with `old_prefix`, replaces the prefix with `new_prefix`.
-/// Returns true if any attribute was updated.
+/// Parses a meta or model file, as TOML for `.meta.toml` files and as JSON5
+/// otherwise.
+pub(crate) fn parse_meta_or_model(path: &Path, bytes: &[u8]) -> anyhow::Result<serde_json::Value> {
+    match MetaFormat::for_path(path) {
+        MetaFormat::Toml => {
+            let text = std::str::from_utf8(bytes).context("File is not valid UTF-8")?;
+            Ok(toml::from_str(text)?)
+        }
+        MetaFormat::Json5 => crate::json::from_slice(bytes),
+    }
+}
+
 /// Update `Rojo_Ref_*` attributes in a meta/model file after a rename.
 ///
 /// For each attribute, resolves the on-disk relative path to absolute using
@@ -116,11 +125,11 @@ pub fn update_ref_paths_in_file(
 
     let bytes =
         fs::read(file_path).with_context(|| format!("Failed to read {}", file_path.display()))?;
-    let mut val: serde_json::Value = crate::json::from_slice(&bytes)
-        .with_context(|| format!("Failed to parse JSON5 in {}", file_path.display()))?;
+    let mut val = parse_meta_or_model(file_path, &bytes)
+        .with_context(|| format!("Failed to parse {}", file_path.display()))?;
     if !val.is_object() {
         anyhow::bail!(
-            "{} is not a JSON object, cannot update Rojo_Ref_* attributes",
+            "{} is not an object, cannot update Rojo_Ref_* attributes",
             file_path.display()
         );
     }
@@ -148,7 +157,7 @@ pub fn update_ref_paths_in_file(
     }
 
     if updated {
-        let content = crate::json::to_vec_pretty_sorted(&val)?;
+        let content = MetaFormat::for_path(file_path).serialize(&val, None)?;
         fs::write(file_path, &content)?;
     }
 
@@ -183,3 +192,35 @@ pub fn remove_meta_name(meta_path: &Path) -> anyhow::Result<RemoveNameOutcome> {
         Ok(RemoveNameOutcome::FieldRemoved)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update_ref_paths_in_toml_meta() {
+        let dir = tempfile::tempdir().unwrap();
+        let meta_path = dir.path().join("Model.meta.toml");
+        fs::write(
+            &meta_path,
+            "[attributes]\nRojo_Ref_PrimaryPart = \"@game/Workspace/Old/Part\"\n",
+        )
+        .unwrap();
+
+        let updated = update_ref_paths_in_file(
+            &meta_path,
+            "Workspace/Old",
+            "Workspace/New",
+            "Workspace/Model",
+        )
+        .unwrap();
+        assert!(updated);
+
+        let meta: toml::Value = toml::from_str(&fs::read_to_string(&meta_path).unwrap()).unwrap();
+        let path = meta["attributes"]["Rojo_Ref_PrimaryPart"].as_str().unwrap();
+        assert_eq!(
+            crate::resolve_ref_path_to_absolute(path, "Workspace/Model").as_deref(),
+            Some("Workspace/New/Part")
+        );
+    }
+}
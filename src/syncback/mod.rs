@@ -1,13 +1,20 @@
+mod backup;
 pub mod dedup_suffix;
+mod diff;
 mod file_names;
 mod fs_snapshot;
+mod git_attributes;
 mod hash;
+mod ignore_properties;
+mod include_trees;
 pub mod matching;
 pub mod meta;
+mod path_length;
 mod property_filter;
 mod ref_properties;
 mod snapshot;
 mod stats;
+mod sync_state;
 
 use anyhow::Context;
 use indexmap::IndexMap;
@@ -25,26 +32,39 @@ use std::{
 };
 
 use crate::{
+    escape_ref_path_segment,
     glob::Glob,
     snapshot::{InstanceWithMeta, RojoTree},
     snapshot_middleware::Middleware,
     syncback::ref_properties::{collect_all_paths, collect_referents, link_referents},
+    variant_eq::DEFAULT_FLOAT_EPSILON,
     Project,
 };
 
+pub use backup::{backup_project, backup_timestamp, new_backup_dir};
+pub use diff::{
+    planned_changes, render_changes, DiffFormat, DiffFormatParseError, DiffOp, PlannedChange,
+};
 pub use file_names::{
-    adjacent_meta_path, deduplicate_name, extension_for_middleware, name_for_inst,
-    name_needs_slugify, slugify_name, strip_middleware_extension, strip_script_suffix,
-    validate_file_name,
+    adjacent_meta_path, adjacent_meta_path_with_format, deduplicate_name, extension_for_middleware,
+    name_for_inst, name_needs_slugify, slugify_name, strip_middleware_extension,
+    strip_script_suffix, validate_file_name,
 };
-pub use fs_snapshot::FsSnapshot;
+pub use fs_snapshot::{FsSnapshot, DEFAULT_MAX_FS_THREADS};
+pub use git_attributes::GitAttributes;
 pub use hash::*;
+pub use ignore_properties::IgnoreProperties;
+pub use include_trees::{IncludeTrees, Inclusion};
+pub use path_length::{
+    check_path_lengths, check_path_lengths_with, OverlongPath, PathTooLongError, MAX_PATH_LENGTH,
+};
 pub use property_filter::{
     filter_properties, filter_properties_preallocated, should_property_serialize,
     PropertyFilterCache,
 };
 pub use snapshot::{inst_path, SyncbackData, SyncbackSnapshot};
-pub use stats::SyncbackStats;
+pub use stats::{SyncbackPhase, SyncbackProgress, SyncbackStats};
+pub use sync_state::{Conflict, SyncState, SYNC_STATE_PATH};
 
 /// Result of a syncback operation, containing everything needed for
 /// post-processing (file writes, sourcemap generation, etc.).
@@ -57,6 +77,13 @@ pub struct SyncbackResult {
     /// Maps each instance Ref (in `new_tree`) to the file paths written for it.
     /// Used to generate sourcemaps from in-memory data.
     pub instance_paths: HashMap<Ref, Vec<PathBuf>>,
+    /// Files this syncback would overwrite or remove that were edited on
+    /// disk since syncback last wrote them, according to `.rojo/sync-state.json`.
+    pub conflicts: Vec<Conflict>,
+    /// Hashes of the tree on disk to save to [`HASH_CACHE_PATH`] once the
+    /// write finishes, so the next incremental syncback can skip rehashing
+    /// unchanged files. `None` for clean syncbacks, which don't hash.
+    pub hash_cache: Option<HashCache>,
 }
 
 /// The name of an enviroment variable to use to override the behavior of
@@ -69,6 +96,14 @@ pub struct SyncbackResult {
 /// new files.
 const DEBUG_MODEL_FORMAT_VAR: &str = "ROJO_SYNCBACK_DEBUG";
 
+/// Log target used for syncback's per-phase `[PERF]` timing lines, so they can
+/// be filtered out (e.g. by `syncback --summary-only`) without touching the
+/// rest of the debug output.
+pub const PHASE_LOG_TARGET: &str = "librojo::syncback::phase";
+
+/// How many Instances the walk gets through between progress reports.
+const WALK_PROGRESS_INTERVAL: usize = 1000;
+
 /// Services that are considered "visible" and will be included when
 /// `ignoreHiddenServices` is enabled. All other services will be ignored.
 pub const VISIBLE_SERVICES: &[&str] = &[
@@ -138,6 +173,7 @@ pub fn syncback_loop_with_stats(
     // Create internal stats if not provided externally
     let internal_stats = SyncbackStats::new();
     let stats = external_stats.unwrap_or(&internal_stats);
+    let syncback_timer = std::time::Instant::now();
 
     let ignore_patterns = project
         .syncback_rules
@@ -151,34 +187,35 @@ pub fn syncback_loop_with_stats(
         .map(|rules| rules.compile_tree_globs())
         .transpose()?;
 
+    let include_trees = project
+        .syncback_rules
+        .as_ref()
+        .map(|rules| rules.compile_include_trees())
+        .transpose()?
+        .flatten();
+    let inclusion_of = |inst_path: &str| match &include_trees {
+        Some(include_trees) => include_trees.classify(inst_path),
+        None => Inclusion::Included,
+    };
+
+    let never_remove = project
+        .syncback_rules
+        .as_ref()
+        .is_some_and(|rules| rules.never_remove());
+
     let phase_timer = std::time::Instant::now();
 
     // Collect all instance paths BEFORE pruning so we can track external references
     // (references to instances that will be pruned, like SoundGroups in SoundService).
-    log::debug!("[PERF] syncback_loop entered");
+    log::debug!(target: PHASE_LOG_TARGET, "[PERF] syncback_loop entered");
     let pre_prune_paths = collect_all_paths(&new_tree);
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] collect_all_paths: {:.3}s",
         phase_timer.elapsed().as_secs_f64()
     );
 
-    // TODO: Add a better way to tell if the root of a project is a directory
-    let skip_pruning = if let Some(path) = &project.tree.path {
-        let resolved = if path.path().is_absolute() {
-            path.path().to_path_buf()
-        } else {
-            project.folder_location().join(path.path())
-        };
-        let middleware =
-            Middleware::middleware_for_path(vfs, &project.sync_rules, &resolved).unwrap();
-        if let Some(middleware) = middleware {
-            middleware.is_dir()
-        } else {
-            false
-        }
-    } else {
-        false
-    };
+    let skip_pruning = project.root_is_directory(vfs);
     let phase_timer = std::time::Instant::now();
     if !skip_pruning {
         strip_unknown_root_children(&mut new_tree, old_tree);
@@ -197,32 +234,42 @@ pub fn syncback_loop_with_stats(
         strip_hidden_services(&mut new_tree);
     }
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] prune + filter: {:.3}s",
         phase_timer.elapsed().as_secs_f64()
     );
+    stats.report_progress(
+        SyncbackPhase::Prune,
+        pre_prune_paths.len(),
+        syncback_timer.elapsed(),
+    );
 
     let phase_timer = std::time::Instant::now();
-    let mut deferred_referents = collect_referents(&new_tree, &pre_prune_paths, None);
+    let mut deferred_referents = collect_referents(&new_tree, &pre_prune_paths, None, stats);
     let placeholder_map = std::mem::take(&mut deferred_referents.placeholder_to_source_and_target);
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] collect_referents: {:.3}s",
         phase_timer.elapsed().as_secs_f64()
     );
 
     let phase_timer = std::time::Instant::now();
-    for referent in descendants(&new_tree, new_tree.root_ref()) {
-        let new_inst = new_tree.get_by_ref_mut(referent).unwrap();
-        if let Some(filter) = get_property_filter(project, new_inst) {
-            for prop in filter {
+    let ignore_properties = project
+        .syncback_rules
+        .as_ref()
+        .map(|rules| rules.compile_ignore_properties())
+        .transpose()?;
+    if let Some(ignore_properties) = &ignore_properties {
+        for referent in descendants(&new_tree, new_tree.root_ref()) {
+            let new_inst = new_tree.get_by_ref_mut(referent).unwrap();
+            for prop in get_property_filter(ignore_properties, new_inst) {
                 new_inst.properties.remove(&prop);
             }
         }
-    }
-    for referent in descendants(old_tree.inner(), old_tree.get_root_id()) {
-        let mut old_inst_rojo = old_tree.get_instance_mut(referent).unwrap();
-        let old_inst = old_inst_rojo.inner_mut();
-        if let Some(filter) = get_property_filter(project, old_inst) {
-            for prop in filter {
+        for referent in descendants(old_tree.inner(), old_tree.get_root_id()) {
+            let mut old_inst_rojo = old_tree.get_instance_mut(referent).unwrap();
+            let old_inst = old_inst_rojo.inner_mut();
+            for prop in get_property_filter(ignore_properties, old_inst) {
                 old_inst.properties.remove(&prop);
             }
         }
@@ -264,6 +311,7 @@ pub fn syncback_loop_with_stats(
         link_referents(deferred_referents, &mut new_tree)?;
     }
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] filter props + link refs: {:.3}s",
         phase_timer.elapsed().as_secs_f64()
     );
@@ -271,19 +319,32 @@ pub fn syncback_loop_with_stats(
     new_tree.root_mut().name = old_tree.root().name().to_string();
 
     let phase_timer = std::time::Instant::now();
-    let (old_hashes, new_hashes) = if incremental {
-        let result = rayon::join(
-            || hash_tree(project, old_tree.inner(), old_tree.get_root_id()),
+    let (old_hashes, new_hashes, hash_cache) = if incremental {
+        let cache = HashCache::load(project);
+        let ((old_hashes, hash_cache, reused), new_hashes) = rayon::join(
+            || hash_tree_cached(project, old_tree, &cache),
             || hash_tree(project, &new_tree, new_tree.root_ref()),
         );
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] hash both trees (parallel): {:.3}s",
             phase_timer.elapsed().as_secs_f64()
         );
-        result
+        log::debug!(
+            "Reused {} of {} cached hashes from {}",
+            reused,
+            old_hashes.len(),
+            HASH_CACHE_PATH
+        );
+        stats.report_progress(
+            SyncbackPhase::Hash,
+            new_hashes.len(),
+            syncback_timer.elapsed(),
+        );
+        (old_hashes, new_hashes, Some(hash_cache))
     } else {
-        log::debug!("[PERF] hash skipped (clean mode)");
-        (HashMap::new(), HashMap::new())
+        log::debug!(target: PHASE_LOG_TARGET, "[PERF] hash skipped (clean mode)");
+        (HashMap::new(), HashMap::new(), None)
     };
 
     let project_path = project.folder_location();
@@ -362,7 +423,7 @@ pub fn syncback_loop_with_stats(
     }
 
     let phase_timer = std::time::Instant::now();
-    let existing_paths: HashSet<PathBuf> = if !incremental {
+    let existing_paths: HashSet<PathBuf> = if !incremental && !never_remove {
         // Alternate-file orphan candidates and instigating_source dirs are
         // cheap to collect (a few exists() calls) and needed by both paths.
         let mut orphan_files_to_check: Vec<PathBuf> = Vec::new();
@@ -373,6 +434,7 @@ pub fn syncback_loop_with_stats(
 
         if let Some(pre_walked) = pre_walked_paths.filter(|p| p.len() > 100) {
             let before = pre_walked.len();
+            let mut visited_dirs = HashSet::new();
             let mut filtered: HashSet<PathBuf> = pre_walked
                 .into_iter()
                 .filter(|p| is_valid_path(&ignore_patterns, project_path, p))
@@ -397,30 +459,15 @@ pub fn syncback_loop_with_stats(
                     }
                     continue;
                 }
-                for entry in walkdir::WalkDir::new(dir)
-                    .follow_links(true)
-                    .into_iter()
-                    .filter_entry(|e| {
-                        if e.depth() == 0 {
-                            return true;
-                        }
-                        e.file_name()
-                            .to_str()
-                            .is_none_or(|n| !n.starts_with('.') || n == ".gitkeep")
-                    })
-                    .flatten()
-                {
-                    if entry.depth() == 0 {
-                        continue;
-                    }
-                    let path = entry.path().to_path_buf();
+                walk_existing_paths(dir, &mut visited_dirs, |path| {
                     if is_valid_path(&ignore_patterns, project_path, &path) {
                         filtered.insert(path);
                     }
-                }
+                });
             }
 
             log::debug!(
+                target: PHASE_LOG_TARGET,
                 "[PERF] orphan scan: reusing pre-walked paths ({} -> {} after filters + supplements)",
                 before,
                 filtered.len()
@@ -432,28 +479,14 @@ pub fn syncback_loop_with_stats(
             // Get the source directories from the project's tree structure.
             // We need to collect ALL $path directories defined in the project,
             // not just instigating_source metadata (which may point to project file).
-            let mut dirs_to_scan: Vec<PathBuf> = Vec::new();
-
-            // Recursively collect $path DIRECTORY entries from project tree.
             // Only directories need to be scanned for orphan detection.
-            fn collect_paths_from_project(
-                node: &crate::project::ProjectNode,
-                base_path: &Path,
-                paths: &mut Vec<PathBuf>,
-            ) {
-                if let Some(ref path_node) = node.path {
-                    let resolved = base_path.join(path_node.path());
-                    if resolved.is_dir() && !paths.contains(&resolved) {
-                        log::trace!("Found $path directory in project: {}", resolved.display());
-                        paths.push(resolved);
-                    }
-                }
-                for child in node.children.values() {
-                    collect_paths_from_project(child, base_path, paths);
+            let mut dirs_to_scan: Vec<PathBuf> = Vec::new();
+            for resolved in project.resolved_path_roots() {
+                if resolved.is_dir() && !dirs_to_scan.contains(&resolved) {
+                    log::trace!("Found $path directory in project: {}", resolved.display());
+                    dirs_to_scan.push(resolved);
                 }
             }
-
-            collect_paths_from_project(&project.tree, project_path, &mut dirs_to_scan);
             dirs_to_scan.extend(extra_dirs);
 
             if log::log_enabled!(log::Level::Trace) {
@@ -464,6 +497,7 @@ pub fn syncback_loop_with_stats(
                 log::trace!("dirs_to_scan: {:?}", dirs_str);
             }
 
+            let mut visited_dirs = HashSet::new();
             for dir in &dirs_to_scan {
                 if !dir.is_dir() {
                     continue;
@@ -473,28 +507,11 @@ pub fn syncback_loop_with_stats(
                         continue;
                     }
                 }
-                for entry in walkdir::WalkDir::new(dir)
-                    .follow_links(true)
-                    .into_iter()
-                    .filter_entry(|e| {
-                        if e.depth() == 0 {
-                            return true;
-                        }
-                        e.file_name()
-                            .to_str()
-                            .is_none_or(|n| !n.starts_with('.') || n == ".gitkeep")
-                    })
-                    .flatten()
-                {
-                    if entry.depth() == 0 {
-                        continue;
-                    }
-                    let path = entry.path().to_path_buf();
-                    if !is_valid_path(&ignore_patterns, project_path, &path) {
-                        continue;
+                walk_existing_paths(dir, &mut visited_dirs, |path| {
+                    if is_valid_path(&ignore_patterns, project_path, &path) {
+                        paths.insert(path);
                     }
-                    paths.insert(path);
-                }
+                });
             }
 
             for file in &orphan_files_to_check {
@@ -512,10 +529,16 @@ pub fn syncback_loop_with_stats(
         HashSet::new()
     };
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] orphan scan: {:.3}s ({} paths)",
         phase_timer.elapsed().as_secs_f64(),
         existing_paths.len()
     );
+    stats.report_progress(
+        SyncbackPhase::OrphanScan,
+        existing_paths.len(),
+        syncback_timer.elapsed(),
+    );
 
     let phase_timer = std::time::Instant::now();
     let ref_path_map = std::sync::Mutex::new(HashMap::new());
@@ -542,6 +565,18 @@ pub fn syncback_loop_with_stats(
         needs_meta_name: false,
     }];
 
+    let approx_matcher = ApproxMatcher {
+        project,
+        old_dom: old_tree.inner(),
+        old_hashes: &old_hashes,
+        new_dom: &new_tree,
+        new_hashes: &new_hashes,
+        epsilon: project
+            .syncback_rules
+            .as_ref()
+            .map_or(DEFAULT_FLOAT_EPSILON, |rules| rules.float_epsilon()),
+    };
+
     let mut fs_snapshot = FsSnapshot::new();
     let mut instance_paths: HashMap<Ref, Vec<PathBuf>> = HashMap::new();
     let mut walk_count: usize = 0;
@@ -551,20 +586,36 @@ pub fn syncback_loop_with_stats(
     struct WaveItem<'sync> {
         snapshot: SyncbackSnapshot<'sync>,
         middleware: Middleware,
+        inclusion: Inclusion,
+        inst_path: String,
     }
 
+    // Instance paths of the previous wave's snapshots. A snapshot's parent
+    // is almost always in the previous wave, so its path is the parent's
+    // plus one segment instead of a walk up to the root.
+    let mut parent_inst_paths: HashMap<Ref, String> = HashMap::new();
+
     while !snapshots.is_empty() {
         // Phase 1: Sequential pre-filter to build this wave's work items.
         let mut wave: Vec<WaveItem> = Vec::with_capacity(snapshots.len());
         let mut next_snapshots: Vec<SyncbackSnapshot> = Vec::new();
+        let mut wave_inst_paths: HashMap<Ref, String> = HashMap::with_capacity(snapshots.len());
 
         'filter: for snapshot in snapshots.drain(..) {
             walk_count += 1;
+            if walk_count % WALK_PROGRESS_INTERVAL == 0 {
+                stats.report_progress(SyncbackPhase::Walk, walk_count, syncback_timer.elapsed());
+            }
 
             if incremental {
                 if let Some(old_ref) = snapshot.old {
                     match (old_hashes.get(&old_ref), new_hashes.get(&snapshot.new)) {
                         (Some(old), Some(new)) if old == new => continue,
+                        // Float noise changes the hash without changing
+                        // anything worth rewriting a file for.
+                        (Some(_), Some(_)) if approx_matcher.subtree_eq(old_ref, snapshot.new) => {
+                            continue
+                        }
                         _ => {}
                     }
                 }
@@ -573,30 +624,54 @@ pub fn syncback_loop_with_stats(
             if !is_valid_path(&ignore_patterns, project_path, &snapshot.path) {
                 continue;
             }
+            let new_inst = snapshot.new_inst();
+            let inst_path = match parent_inst_paths.get(&new_inst.parent()) {
+                Some(parent_path) if parent_path.is_empty() => {
+                    escape_ref_path_segment(&new_inst.name).into_owned()
+                }
+                Some(parent_path) => {
+                    format!("{parent_path}/{}", escape_ref_path_segment(&new_inst.name))
+                }
+                None => snapshot.get_new_inst_path(snapshot.new),
+            };
             if let Some(ref globs) = tree_globs {
-                let inst_path = snapshot.get_new_inst_path(snapshot.new);
                 for (glob, _pattern) in globs {
                     if glob.is_match(&inst_path) {
                         continue 'filter;
                     }
                 }
             }
+            let inclusion = inclusion_of(&inst_path);
+            if inclusion == Inclusion::Excluded {
+                continue;
+            }
 
             let middleware = get_best_middleware(&snapshot);
 
-            if matches!(middleware, Middleware::Json | Middleware::Toml) {
+            if matches!(
+                middleware,
+                Middleware::Json | Middleware::Toml | Middleware::Yaml | Middleware::YamlString
+            ) {
+                stats.record_warning(format!(
+                    "Skipping {} because syncback can't write {:?} files",
+                    inst_path, middleware
+                ));
                 continue;
             }
 
+            wave_inst_paths.insert(snapshot.new, inst_path.clone());
             wave.push(WaveItem {
                 snapshot,
                 middleware,
+                inclusion,
+                inst_path,
             });
         }
 
         if wave.is_empty() {
             break;
         }
+        parent_inst_paths = wave_inst_paths;
 
         // Phase 2: Parallel middleware execution.
         let results: Vec<_> = wave
@@ -605,15 +680,43 @@ pub fn syncback_loop_with_stats(
                 let WaveItem {
                     snapshot,
                     middleware,
+                    inclusion,
+                    inst_path,
                 } = item;
                 let mut dir_to_remove: Option<PathBuf> = None;
+                let mut file_to_remove: Option<PathBuf> = None;
                 let result = match middleware.syncback(&snapshot) {
-                    Ok(syncback) => Ok(syncback),
+                    Ok(syncback) => {
+                        // An Instance that was a single file and is now a
+                        // directory, because it gained children, leaves its
+                        // old file behind.
+                        if middleware.is_dir() {
+                            file_to_remove = snapshot
+                                .old_inst()
+                                .filter(|old| {
+                                    old.metadata().middleware.is_some_and(|m| !m.is_dir())
+                                })
+                                .and_then(|old| old.metadata().instigating_source.as_ref())
+                                .map(|source| source.path().to_path_buf());
+                            // Clean mode doesn't carry the old Instance, and
+                            // with removals disabled there is no orphan scan
+                            // to catch the file either, so find it directly.
+                            if file_to_remove.is_none() && never_remove {
+                                file_to_remove = file_replaced_by_dir(&snapshot);
+                            }
+                        }
+                        Ok(syncback)
+                    }
                     Err(err) if middleware == Middleware::Dir => {
                         let new_middleware = match env::var(DEBUG_MODEL_FORMAT_VAR) {
                             Ok(value) if value == "1" => Middleware::Rbxmx,
                             Ok(value) if value == "2" => Middleware::JsonModel,
-                            _ => Middleware::Rbxm,
+                            _ => match &project.syncback_rules {
+                                Some(rules) => rules
+                                    .fallback_model_format(&snapshot.new_inst().class)
+                                    .middleware(),
+                                None => Middleware::Rbxm,
+                            },
                         };
                         let file_name = snapshot
                             .path
@@ -625,7 +728,6 @@ pub fn syncback_loop_with_stats(
                             "{file_name}.{}",
                             extension_for_middleware(new_middleware)
                         ));
-                        let inst_path = snapshot.get_new_inst_path(snapshot.new);
                         let new_snapshot = snapshot.with_new_path(path, snapshot.new, snapshot.old);
                         stats.record_rbxm_fallback(&inst_path, &err.to_string());
                         let new_syncback_result = new_middleware
@@ -636,24 +738,47 @@ pub fn syncback_loop_with_stats(
                         }
                         new_syncback_result
                     }
-                    Err(err) => {
-                        let inst_path = snapshot.get_new_inst_path(snapshot.new);
-                        Err(err).with_context(|| format!("Failed to syncback {inst_path}"))
-                    }
+                    Err(err) => Err(err).with_context(|| format!("Failed to syncback {inst_path}")),
                 };
-                (snapshot, result, dir_to_remove)
+                (snapshot, result, dir_to_remove, file_to_remove, inclusion)
             })
             .collect();
 
-        // Phase 3: Sequential merge of results.
-        for (snapshot, result, dir_to_remove) in results {
-            let syncback = result?;
+        // Phase 3: Sequential merge of results. `collect` keeps them in wave
+        // order, so the merged output doesn't depend on how rayon scheduled
+        // the middleware calls.
+        for (snapshot, result, dir_to_remove, file_to_remove, inclusion) in results {
+            let mut syncback = result?;
+
+            // Instances above an included subtree are only walked to reach
+            // it. Keep the directories its children need and nothing else.
+            if inclusion == Inclusion::Ancestor {
+                syncback.fs_snapshot.retain_added_dirs();
+            }
 
             if let Some(ref dir_path) = dir_to_remove {
-                fs_snapshot.remove_dir(dir_path);
+                if never_remove {
+                    stats.record_warning(format!(
+                        "Leaving {} in place because removals are disabled; it was replaced by a model file",
+                        dir_path.display()
+                    ));
+                } else if inclusion == Inclusion::Included {
+                    fs_snapshot.remove_dir(dir_path);
+                }
+            }
+
+            if let Some(ref file_path) = file_to_remove {
+                if never_remove {
+                    stats.record_warning(format!(
+                        "Leaving {} in place because removals are disabled; it was replaced by a directory",
+                        file_path.display()
+                    ));
+                } else if inclusion == Inclusion::Included {
+                    fs_snapshot.remove_file(file_path);
+                }
             }
 
-            if !syncback.removed_children.is_empty() {
+            if !never_remove && !syncback.removed_children.is_empty() {
                 'remove: for inst in &syncback.removed_children {
                     let path = inst.metadata().instigating_source.as_ref().unwrap().path();
                     let inst_path = snapshot.get_old_inst_path(inst.id());
@@ -672,6 +797,9 @@ pub fn syncback_loop_with_stats(
                             }
                         }
                     }
+                    if inclusion_of(&inst_path) != Inclusion::Included {
+                        continue;
+                    }
                     if path.is_dir() {
                         fs_snapshot.remove_dir(path)
                     } else {
@@ -700,10 +828,12 @@ pub fn syncback_loop_with_stats(
         snapshots = next_snapshots;
     }
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] main walk loop: {:.3}s ({} instances)",
         phase_timer.elapsed().as_secs_f64(),
         walk_count,
     );
+    stats.report_progress(SyncbackPhase::Walk, walk_count, syncback_timer.elapsed());
 
     let phase_timer = std::time::Instant::now();
     {
@@ -724,6 +854,7 @@ pub fn syncback_loop_with_stats(
             substitutions.push((placeholder.clone(), relative));
         }
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] build ref substitutions: {:.3}s (count={})",
             phase_timer.elapsed().as_secs_f64(),
             substitutions.len()
@@ -734,6 +865,7 @@ pub fn syncback_loop_with_stats(
             fs_snapshot.fix_ref_paths(&substitutions);
         }
         log::debug!(
+            target: PHASE_LOG_TARGET,
             "[PERF] apply ref substitutions: {:.3}s",
             sub_timer.elapsed().as_secs_f64()
         );
@@ -764,50 +896,13 @@ pub fn syncback_loop_with_stats(
         // Collect ALL paths explicitly referenced via $path in the project.
         // These paths should NOT be removed during orphan cleanup because they
         // are explicitly part of the project structure.
-        let mut protected_paths: HashSet<PathBuf> = HashSet::new();
+        let protected_paths: HashSet<PathBuf> = project.resolved_path_roots().into_iter().collect();
 
         // Also build a mapping from filesystem path prefix to instance path prefix.
         // This is needed to convert filesystem paths to instance paths for ignoreTrees checking.
         // e.g., if project has "$path": "src" on "ReplicatedStorage", then
         // filesystem "src/Foo" maps to instance "ReplicatedStorage/Foo"
-        let mut path_to_instance_prefix: Vec<(PathBuf, String)> = Vec::new();
-
-        fn collect_all_path_refs_and_mappings(
-            node: &crate::project::ProjectNode,
-            base_path: &Path,
-            instance_path: &str,
-            protected: &mut HashSet<PathBuf>,
-            mappings: &mut Vec<(PathBuf, String)>,
-        ) {
-            if let Some(ref path_node) = node.path {
-                let resolved = base_path.join(path_node.path());
-                protected.insert(resolved.clone());
-                if !instance_path.is_empty() {
-                    mappings.push((resolved, instance_path.to_string()));
-                }
-            }
-            for (name, child) in &node.children {
-                let child_inst_path = if instance_path.is_empty() {
-                    name.clone()
-                } else {
-                    format!("{}/{}", instance_path, name)
-                };
-                collect_all_path_refs_and_mappings(
-                    child,
-                    base_path,
-                    &child_inst_path,
-                    protected,
-                    mappings,
-                );
-            }
-        }
-        collect_all_path_refs_and_mappings(
-            &project.tree,
-            project_path,
-            "",
-            &mut protected_paths,
-            &mut path_to_instance_prefix,
-        );
+        let path_to_instance_prefix = project.path_instance_prefixes();
         if log::log_enabled!(log::Level::Trace) {
             log::trace!("Protected $path references: {:?}", protected_paths);
             log::trace!("Path to instance mappings: {:?}", path_to_instance_prefix);
@@ -815,27 +910,40 @@ pub fn syncback_loop_with_stats(
 
         // Helper to convert a filesystem path to an instance path using the mappings
         let fs_path_to_instance_path = |fs_path: &Path| -> Option<String> {
-            for (fs_prefix, inst_prefix) in &path_to_instance_prefix {
-                if let Ok(relative) = fs_path.strip_prefix(fs_prefix) {
-                    let relative_str = relative
-                        .components()
-                        .filter_map(|c| c.as_os_str().to_str())
-                        .collect::<Vec<_>>()
-                        .join("/");
-                    if relative_str.is_empty() {
-                        return Some(inst_prefix.clone());
-                    } else {
-                        return Some(format!("{}/{}", inst_prefix, relative_str));
-                    }
-                }
-            }
-            None
+            crate::project::instance_path_for_fs_path(&path_to_instance_prefix, fs_path)
+        };
+
+        // Files .gitattributes marks as LFS, binary or generated that no
+        // Instance came from aren't something the project produces, so
+        // they're never orphans. Marked files the old tree was built from
+        // are removed like any other when their Instance is gone.
+        let git_attributes = GitAttributes::load(vfs, project_path);
+        let marked_files: Vec<&PathBuf> = if git_attributes.is_empty() {
+            Vec::new()
+        } else {
+            existing_paths
+                .iter()
+                .filter(|path| {
+                    !added_paths.contains(*path)
+                        && old_tree.get_ids_at_path(path).is_empty()
+                        && git_attributes.is_marked(path)
+                })
+                .collect()
         };
 
         let mut paths_to_remove: HashSet<PathBuf> = HashSet::new();
         for old_path in &existing_paths {
             let old_path_norm = old_path.clone();
 
+            // Folders holding a marked file are kept along with it.
+            if marked_files
+                .iter()
+                .any(|marked| marked.starts_with(&old_path_norm))
+            {
+                log::trace!("Skipping {} (marked by .gitattributes)", old_path.display());
+                continue;
+            }
+
             if old_path_norm == project_file {
                 log::trace!("Skipping root project file: {}", old_path.display());
                 continue;
@@ -929,6 +1037,17 @@ pub fn syncback_loop_with_stats(
                 }
             }
 
+            // With includeTrees set, only paths inside an included subtree
+            // may be removed. Paths we can't map to an Instance are kept.
+            if include_trees.is_some() {
+                let included = fs_path_to_instance_path(&old_path_norm)
+                    .is_some_and(|inst_path| inclusion_of(&inst_path) == Inclusion::Included);
+                if !included {
+                    log::trace!("Skipping {} (outside includeTrees)", old_path.display());
+                    continue;
+                }
+            }
+
             if added_paths.contains(&old_path_norm) {
                 continue;
             }
@@ -965,10 +1084,19 @@ pub fn syncback_loop_with_stats(
     }
 
     log::debug!(
+        target: PHASE_LOG_TARGET,
         "[PERF] orphan removal: {:.3}s",
         phase_timer.elapsed().as_secs_f64()
     );
 
+    // Now that every write and removal is known, check them against what
+    // the last syncback wrote to catch files edited by hand in between.
+    let conflicts = if fs_snapshot.is_empty() {
+        Vec::new()
+    } else {
+        SyncState::load(vfs, project_path).conflicts(vfs, project_path, &fs_snapshot)
+    };
+
     if external_stats.is_none() {
         stats.log_summary();
     }
@@ -977,6 +1105,8 @@ pub fn syncback_loop_with_stats(
         fs_snapshot,
         new_tree,
         instance_paths,
+        conflicts,
+        hash_cache,
     })
 }
 
@@ -994,6 +1124,12 @@ pub fn get_best_middleware(snapshot: &SyncbackSnapshot) -> Middleware {
 
     let mut middleware;
 
+    let configured_format = snapshot
+        .project()
+        .syncback_rules
+        .as_ref()
+        .and_then(|rules| rules.model_format_for_class(&inst.class));
+
     if let Some(override_middleware) = snapshot.middleware {
         return override_middleware;
     } else if let Some(old_middleware) = old_middleware {
@@ -1001,6 +1137,10 @@ pub fn get_best_middleware(snapshot: &SyncbackSnapshot) -> Middleware {
         // This handles cases where the old file was a single file (e.g., Csv)
         // but the new instance has children (needs CsvDir)
         middleware = old_middleware;
+    } else if let Some(format) = configured_format {
+        // A model format configured for this class wins over the defaults
+        // below. JSON models still become directories if they have children.
+        middleware = format.middleware();
     } else {
         // Specific classes that need special middleware, everything else defaults to JsonModel
         middleware = match inst.class.as_str() {
@@ -1038,7 +1178,7 @@ pub fn get_best_middleware(snapshot: &SyncbackSnapshot) -> Middleware {
             Middleware::LegacyScript => Middleware::LegacyScriptDir,
             Middleware::LocalScript => Middleware::LocalScriptDir,
             Middleware::Csv => Middleware::CsvDir,
-            Middleware::JsonModel | Middleware::Text => Middleware::Dir,
+            Middleware::JsonModel | Middleware::Text | Middleware::Markdown => Middleware::Dir,
             _ => middleware,
         }
     }
@@ -1046,18 +1186,25 @@ pub fn get_best_middleware(snapshot: &SyncbackSnapshot) -> Middleware {
     middleware
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncbackRules {
     /// A list of subtrees in a file that will be ignored by Syncback.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     ignore_trees: Vec<String>,
+    /// A list of subtrees in a file to restrict syncback to. When non-empty,
+    /// only Instances matching one of these globs (and their descendants)
+    /// are written or removed. Instances above them are walked but left as
+    /// they are on disk.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include_trees: Vec<String>,
     /// A list of patterns to check against the path an Instance would serialize
     /// to. If a path matches one of these, the Instance won't be syncbacked.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     ignore_paths: Vec<String>,
     /// A map of classes to properties to ignore for that class when doing
-    /// syncback.
+    /// syncback. Property names may be globs like `*Color*`, and the class
+    /// `*` applies to every class.
     #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
     ignore_properties: IndexMap<Ustr, Vec<Ustr>>,
     /// A list of class names to ignore entirely during syncback.
@@ -1096,6 +1243,141 @@ pub struct SyncbackRules {
     /// Defaults to `false` (warnings are suppressed).
     #[serde(skip_serializing_if = "Option::is_none")]
     warn_duplicate_names: Option<bool>,
+    /// Whether children whose names collide case-insensitively are written
+    /// with numbered suffixes (`Part`, `Part~2`, ...) instead of being
+    /// skipped, so that every Instance makes it to the file system.
+    /// Defaults to `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dedup_duplicate_names: Option<bool>,
+    /// Whether `.model.json5` files are written with their keys, including
+    /// property and attribute names, in alphabetical order. When `false`,
+    /// properties are written in whatever order they're read from the
+    /// Instance, which can differ between runs. Defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stable_property_order: Option<bool>,
+    /// A map of class names to the model format Instances of that class (or
+    /// its subclasses) should be written as, e.g. `{"MeshPart": "rbxm"}`.
+    /// Takes priority over the default choice for new files and over the
+    /// fallback used when an Instance can't be written as a directory.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    model_format: IndexMap<Ustr, ModelFormat>,
+    /// The model format used when an Instance can't be written as a
+    /// directory and its class isn't listed in `model_format`. Defaults to
+    /// `rbxm`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_model_format: Option<ModelFormat>,
+    /// The format adjacent and `init` meta files are written in. Defaults to
+    /// `json5`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meta_format: Option<MetaFormat>,
+    /// Whether scripts always get a meta file next to their source, even
+    /// when it would be empty, so every script has the same pair of files.
+    /// Defaults to `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    always_emit_meta: Option<bool>,
+    /// How far apart two float values (including the components of vectors,
+    /// `CFrame`s and colors) can be while still counting as unchanged, so
+    /// round-trip noise doesn't rewrite files. Defaults to `0.0001`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    float_epsilon: Option<f64>,
+    /// Whether syncback only adds and updates files, never removing any.
+    /// Instances missing from the place and orphaned files are left on
+    /// disk. Defaults to `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    never_remove: Option<bool>,
+    /// Rounds floats written to JSON files during syncback, including the
+    /// components of vectors and `CFrame`s, to at most this many decimal
+    /// places. Reading is unaffected, so files with more precision still load exactly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    number_precision: Option<u8>,
+}
+
+/// A file format an Instance can be written to as a self-contained model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelFormat {
+    /// A binary `.rbxm` file.
+    Rbxm,
+    /// An XML `.rbxmx` file.
+    Rbxmx,
+    /// A `.model.json5` file.
+    Json,
+    /// A directory with an `init.model.json5` file for the Instance itself
+    /// and a file or directory for each child, in the order its manifest
+    /// lists. Unlike `json`, children keep their own representation.
+    JsonDir,
+}
+
+impl ModelFormat {
+    /// Returns the middleware that writes this format.
+    pub fn middleware(self) -> Middleware {
+        match self {
+            ModelFormat::Rbxm => Middleware::Rbxm,
+            ModelFormat::Rbxmx => Middleware::Rbxmx,
+            ModelFormat::Json => Middleware::JsonModel,
+            ModelFormat::JsonDir => Middleware::JsonModelDir,
+        }
+    }
+}
+
+/// A file format meta files can be written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MetaFormat {
+    /// `.meta.json5` files.
+    #[default]
+    Json5,
+    /// `.meta.toml` files.
+    Toml,
+}
+
+impl MetaFormat {
+    /// Every format meta files can be written in.
+    pub const ALL: [MetaFormat; 2] = [MetaFormat::Json5, MetaFormat::Toml];
+
+    /// Returns the format `rules` asks meta files to be written in, or
+    /// `json5` without any rules.
+    #[inline]
+    pub fn for_rules(rules: Option<&SyncbackRules>) -> MetaFormat {
+        rules.map(SyncbackRules::meta_format).unwrap_or_default()
+    }
+
+    /// Returns the format of the meta file at `path`: `toml` for `.toml`
+    /// files and `json5` for anything else.
+    pub fn for_path(path: &Path) -> MetaFormat {
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            MetaFormat::Toml
+        } else {
+            MetaFormat::Json5
+        }
+    }
+
+    /// Returns the extension of meta files in this format, without a leading
+    /// period, e.g. `meta.json5`.
+    pub fn extension(self) -> &'static str {
+        match self {
+            MetaFormat::Json5 => "meta.json5",
+            MetaFormat::Toml => "meta.toml",
+        }
+    }
+
+    /// Serializes `meta` in this format, with keys in alphabetical order.
+    /// JSON5 floats are rounded to `number_precision` places when set; TOML
+    /// floats are always written as-is.
+    pub fn serialize<T: Serialize>(
+        self,
+        meta: &T,
+        number_precision: Option<u8>,
+    ) -> anyhow::Result<Vec<u8>> {
+        match self {
+            MetaFormat::Json5 => crate::json::to_vec_pretty_sorted_rounded(meta, number_precision),
+            MetaFormat::Toml => {
+                // Going through `toml::Value` sorts the keys.
+                let value = toml::Value::try_from(meta)?;
+                Ok(toml::to_string_pretty(&value)?.into_bytes())
+            }
+        }
+    }
 }
 
 impl SyncbackRules {
@@ -1136,6 +1418,28 @@ impl SyncbackRules {
         Ok(globs)
     }
 
+    /// Compiles the ignoreProperties rules.
+    pub fn compile_ignore_properties(&self) -> anyhow::Result<IgnoreProperties> {
+        IgnoreProperties::compile(&self.ignore_properties)
+    }
+
+    /// Compiles the includeTrees patterns. Returns `None` if none are
+    /// configured, in which case everything is included.
+    pub fn compile_include_trees(&self) -> anyhow::Result<Option<IncludeTrees>> {
+        IncludeTrees::compile(&self.include_trees)
+    }
+
+    /// Adds `pattern` to the includeTrees patterns if it isn't already there.
+    pub fn add_include_tree(&mut self, pattern: &str) {
+        if !self
+            .include_trees
+            .iter()
+            .any(|existing| existing == pattern)
+        {
+            self.include_trees.push(pattern.to_owned());
+        }
+    }
+
     /// Returns whether hidden/internal services should be ignored during
     /// syncback. When `true`, only visible services like Workspace,
     /// ReplicatedStorage, etc. will be synced. Defaults to `true`.
@@ -1150,6 +1454,197 @@ impl SyncbackRules {
     pub fn warn_duplicate_names(&self) -> bool {
         self.warn_duplicate_names.unwrap_or(false)
     }
+
+    /// Returns whether duplicate child names are deduplicated with numbered
+    /// suffixes rather than skipped. Defaults to `false`.
+    #[inline]
+    pub fn dedup_duplicate_names(&self) -> bool {
+        self.dedup_duplicate_names.unwrap_or(false)
+    }
+
+    /// Returns whether JSON models should be written with their keys sorted.
+    /// Defaults to `true`.
+    #[inline]
+    pub fn stable_property_order(&self) -> bool {
+        self.stable_property_order.unwrap_or(true)
+    }
+
+    /// Returns the format meta files are written in. Defaults to `json5`.
+    #[inline]
+    pub fn meta_format(&self) -> MetaFormat {
+        self.meta_format.unwrap_or_default()
+    }
+
+    /// Returns whether scripts always get a meta file. Defaults to `false`.
+    #[inline]
+    pub fn always_emit_meta(&self) -> bool {
+        self.always_emit_meta.unwrap_or(false)
+    }
+
+    /// Returns the tolerance for comparing float values. Defaults to
+    /// `0.0001`.
+    #[inline]
+    pub fn float_epsilon(&self) -> f64 {
+        self.float_epsilon.unwrap_or(DEFAULT_FLOAT_EPSILON)
+    }
+
+    /// Returns whether syncback should leave every existing file in place.
+    /// Defaults to `false`.
+    #[inline]
+    pub fn never_remove(&self) -> bool {
+        self.never_remove.unwrap_or(false)
+    }
+
+    /// Sets whether syncback should leave every existing file in place.
+    pub fn set_never_remove(&mut self, never_remove: bool) {
+        self.never_remove = Some(never_remove);
+    }
+
+    /// Returns how many decimal places floats written to JSON files are
+    /// rounded to, if they should be rounded at all.
+    #[inline]
+    pub fn number_precision(&self) -> Option<u8> {
+        self.number_precision
+    }
+
+    /// Returns the model format configured for `class_name` in
+    /// `model_format`, checking superclasses if the class itself isn't
+    /// listed.
+    pub fn model_format_for_class(&self, class_name: &str) -> Option<ModelFormat> {
+        if self.model_format.is_empty() {
+            return None;
+        }
+
+        let database = rbx_reflection_database::get().unwrap();
+        let mut current_class_name = class_name;
+
+        loop {
+            if let Some(format) = self.model_format.get(&ustr(current_class_name)) {
+                return Some(*format);
+            }
+
+            let class = database.classes.get(current_class_name)?;
+            match class.superclass.as_ref() {
+                Some(super_class) => current_class_name = super_class,
+                None => return None,
+            }
+        }
+    }
+
+    /// Returns the model format to fall back to for an Instance of
+    /// `class_name` that can't be written as a directory.
+    pub fn fallback_model_format(&self, class_name: &str) -> ModelFormat {
+        // The fallback stands in for a directory that couldn't be written, so
+        // it has to be a single file.
+        match self
+            .model_format_for_class(class_name)
+            .or(self.default_model_format)
+        {
+            Some(ModelFormat::JsonDir) => ModelFormat::Json,
+            Some(format) => format,
+            None => ModelFormat::Rbxm,
+        }
+    }
+}
+
+/// Calls `found` with every path below `dir`, skipping hidden entries other
+/// than `.gitkeep`. Symlinks are followed, but a symlinked directory whose
+/// target was already walked (through `visited_dirs`, which is shared between
+/// calls) is skipped, and symlink cycles are reported once and not entered.
+fn walk_existing_paths(
+    dir: &Path,
+    visited_dirs: &mut HashSet<PathBuf>,
+    mut found: impl FnMut(PathBuf),
+) {
+    let mut reported_loops = HashSet::new();
+    let walker = walkdir::WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| {
+            if e.depth() > 0
+                && !e
+                    .file_name()
+                    .to_str()
+                    .is_none_or(|n| !n.starts_with('.') || n == ".gitkeep")
+            {
+                return false;
+            }
+            if !e.file_type().is_dir() {
+                return true;
+            }
+            match std::fs::canonicalize(e.path()) {
+                Ok(canonical) => {
+                    let first_visit = visited_dirs.insert(canonical);
+                    if !first_visit && e.path_is_symlink() {
+                        log::debug!(
+                            "Skipping {} because its target was already scanned",
+                            e.path().display()
+                        );
+                    }
+                    first_visit || !e.path_is_symlink()
+                }
+                Err(_) => true,
+            }
+        });
+
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                if let Some(ancestor) = err.loop_ancestor() {
+                    if reported_loops.insert(ancestor.to_path_buf()) {
+                        log::warn!(
+                            "Skipping {} because it's a symlink loop back to {}",
+                            err.path().unwrap_or(ancestor).display(),
+                            ancestor.display()
+                        );
+                    }
+                }
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        found(entry.into_path());
+    }
+}
+
+/// Returns the file next to `snapshot`'s directory that the old tree loaded
+/// an Instance with the same name from, if there is one. That file is what an
+/// Instance which used to be a single file leaves behind once it becomes a
+/// directory.
+fn file_replaced_by_dir(snapshot: &SyncbackSnapshot) -> Option<PathBuf> {
+    let dir_name = snapshot.path.file_name()?.to_str()?;
+    let parent = snapshot.path.parent()?;
+    let name = snapshot.new_inst().name.as_str();
+    let old_tree = snapshot.data.old_tree;
+
+    snapshot
+        .vfs()
+        .read_dir(parent)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().to_path_buf())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|file_name| file_name.to_str())
+                .and_then(|file_name| file_name.strip_prefix(dir_name))
+                .is_some_and(|rest| rest.starts_with('.'))
+        })
+        .find(|path| {
+            old_tree.get_ids_at_path(path).iter().any(|&id| {
+                old_tree.get_instance(id).is_some_and(|old| {
+                    let metadata = old.metadata();
+                    old.name() == name
+                        && metadata.middleware.is_some_and(|m| !m.is_dir())
+                        && metadata
+                            .instigating_source
+                            .as_ref()
+                            .is_some_and(|source| source.path() == path.as_path())
+                })
+            })
+        })
 }
 
 fn is_valid_path(globs: &Option<Vec<Glob>>, base_path: &Path, path: &Path) -> bool {
@@ -1171,26 +1666,27 @@ fn is_valid_path(globs: &Option<Vec<Glob>>, base_path: &Path, path: &Path) -> bo
     true
 }
 
-/// Returns a set of properties that should not be written with syncback if
-/// one exists. This list is read directly from the Project and takes
-/// inheritance into effect.
+/// Returns the set of properties that should not be written with syncback.
+/// This list is read from the Project's `ignoreProperties` and takes
+/// inheritance into effect. Glob entries are matched against the properties
+/// `new_inst` has.
 ///
 /// It **does not** handle properties that should not serialize for other
 /// reasons, such as being defaults or being marked as not serializing in the
 /// ReflectionDatabase.
-fn get_property_filter(project: &Project, new_inst: &Instance) -> Option<UstrSet> {
-    let filter = &project.syncback_rules.as_ref()?.ignore_properties;
+fn get_property_filter(ignore_properties: &IgnoreProperties, new_inst: &Instance) -> UstrSet {
     let mut set = UstrSet::default();
+    ignore_properties.extend_for_class(ignore_properties::ANY_CLASS, new_inst, &mut set);
 
     let database = rbx_reflection_database::get().unwrap();
     let mut current_class_name = new_inst.class.as_str();
 
     loop {
-        if let Some(list) = filter.get(&ustr(current_class_name)) {
-            set.extend(list)
-        }
+        ignore_properties.extend_for_class(current_class_name, new_inst, &mut set);
 
-        let class = database.classes.get(current_class_name)?;
+        let Some(class) = database.classes.get(current_class_name) else {
+            break;
+        };
         if let Some(super_class) = class.superclass.as_ref() {
             current_class_name = super_class;
         } else {
@@ -1198,7 +1694,7 @@ fn get_property_filter(project: &Project, new_inst: &Instance) -> Option<UstrSet
         }
     }
 
-    Some(set)
+    set
 }
 
 /// Produces a list of descendants in the WeakDom such that all children come
@@ -1292,3 +1788,167 @@ fn strip_unknown_root_children(new: &mut WeakDom, old: &RojoTree) {
         new.destroy(child_ref);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rules_from_json(json: &str) -> SyncbackRules {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn model_format_round_trips() {
+        let rules = rules_from_json(
+            r#"{
+                "modelFormat": { "MeshPart": "rbxmx", "Model": "json" },
+                "defaultModelFormat": "rbxm"
+            }"#,
+        );
+        let serialized = serde_json::to_string(&rules).unwrap();
+        assert_eq!(rules, rules_from_json(&serialized));
+        assert_eq!(
+            serialized,
+            r#"{"modelFormat":{"MeshPart":"rbxmx","Model":"json"},"defaultModelFormat":"rbxm"}"#
+        );
+    }
+
+    #[test]
+    fn configured_class_uses_chosen_extension() {
+        let rules =
+            rules_from_json(r#"{ "modelFormat": { "BasePart": "rbxmx", "Model": "json" } }"#);
+
+        // MeshPart inherits from BasePart.
+        let format = rules.model_format_for_class("MeshPart").unwrap();
+        assert_eq!(extension_for_middleware(format.middleware()), "rbxmx");
+        let format = rules.model_format_for_class("Model").unwrap();
+        assert_eq!(extension_for_middleware(format.middleware()), "model.json5");
+
+        assert_eq!(rules.model_format_for_class("Sound"), None);
+        assert_eq!(rules.fallback_model_format("Sound"), ModelFormat::Rbxm);
+
+        let rules = rules_from_json(r#"{ "defaultModelFormat": "rbxmx" }"#);
+        assert_eq!(rules.fallback_model_format("Sound"), ModelFormat::Rbxmx);
+    }
+
+    #[test]
+    fn get_best_middleware_uses_configured_model_format() {
+        let project = Project::load_from_slice(
+            br#"{
+                "tree": { "$className": "DataModel" },
+                "syncbackRules": { "modelFormat": { "BasePart": "rbxmx" } }
+            }"#,
+            PathBuf::from("/place/default.project.json5"),
+            None,
+        )
+        .unwrap();
+        let vfs = Vfs::new(memofs::InMemoryFs::new());
+        let old_tree = RojoTree::new(crate::snapshot::InstanceSnapshot::new());
+        let mut new_tree = WeakDom::new(rbx_dom_weak::InstanceBuilder::new("DataModel"));
+        let root = new_tree.root_ref();
+        let mesh_part = new_tree.insert(root, rbx_dom_weak::InstanceBuilder::new("MeshPart"));
+        let sound = new_tree.insert(root, rbx_dom_weak::InstanceBuilder::new("Sound"));
+
+        let stats = SyncbackStats::new();
+        let ref_path_map = std::sync::Mutex::new(HashMap::new());
+        let prop_filter_cache = std::sync::Mutex::new(PropertyFilterCache::new(&project));
+        let data = SyncbackData {
+            vfs: &vfs,
+            old_tree: &old_tree,
+            new_tree: &new_tree,
+            project: &project,
+            incremental: false,
+            stats: &stats,
+            ref_path_map: &ref_path_map,
+            prop_filter_cache: &prop_filter_cache,
+        };
+        let middleware_for = |new| {
+            get_best_middleware(&SyncbackSnapshot {
+                data,
+                old: None,
+                new,
+                path: PathBuf::from("/place"),
+                middleware: None,
+                needs_meta_name: false,
+            })
+        };
+
+        assert_eq!(middleware_for(mesh_part), Middleware::Rbxmx);
+        assert_eq!(middleware_for(sound), Middleware::JsonModel);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn existing_path_walk_skips_symlink_loops() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs_err::create_dir_all(src.join("nested")).unwrap();
+        fs_err::write(src.join("nested/module.luau"), "return 1").unwrap();
+        std::os::unix::fs::symlink(&src, src.join("self")).unwrap();
+        std::os::unix::fs::symlink(&src, src.join("nested/parent")).unwrap();
+
+        let mut found = Vec::new();
+        walk_existing_paths(&src, &mut HashSet::new(), |path| found.push(path));
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![src.join("nested"), src.join("nested/module.luau")]
+        );
+    }
+
+    #[test]
+    fn ignore_properties_globs_apply_across_classes() {
+        use rbx_dom_weak::{types::Color3, InstanceBuilder};
+
+        let rules = rules_from_json(
+            r#"{ "ignoreProperties": { "*": ["*Color*"], "BasePart": ["Anchored"] } }"#,
+        );
+        let ignore_properties = rules.compile_ignore_properties().unwrap();
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let part = dom.insert(
+            dom.root_ref(),
+            InstanceBuilder::new("Part")
+                .with_property("Color", Color3::new(1.0, 0.0, 0.0))
+                .with_property("Color3uint8", Color3::new(1.0, 0.0, 0.0))
+                .with_property("Transparency", 0.5f32)
+                .with_property("Anchored", true),
+        );
+        let label = dom.insert(
+            dom.root_ref(),
+            InstanceBuilder::new("TextLabel")
+                .with_property("TextColor3", Color3::new(0.0, 0.0, 0.0))
+                .with_property("BackgroundColor3", Color3::new(1.0, 1.0, 1.0))
+                .with_property("Text", "Hello".to_owned()),
+        );
+
+        let part_filter = get_property_filter(&ignore_properties, dom.get_by_ref(part).unwrap());
+        assert_eq!(
+            part_filter,
+            UstrSet::from_iter([ustr("Color"), ustr("Color3uint8"), ustr("Anchored")])
+        );
+
+        let label_filter = get_property_filter(&ignore_properties, dom.get_by_ref(label).unwrap());
+        assert_eq!(
+            label_filter,
+            UstrSet::from_iter([ustr("TextColor3"), ustr("BackgroundColor3")])
+        );
+    }
+
+    #[test]
+    fn ignore_properties_exact_names_are_not_globs() {
+        let rules = rules_from_json(r#"{ "ignoreProperties": { "Part": ["Color"] } }"#);
+        let ignore_properties = rules.compile_ignore_properties().unwrap();
+
+        let dom = WeakDom::new(
+            rbx_dom_weak::InstanceBuilder::new("Part")
+                .with_property("Color", Variant::Float32(0.0))
+                .with_property("BrickColor", Variant::Float32(0.0)),
+        );
+        assert_eq!(
+            get_property_filter(&ignore_properties, dom.root()),
+            UstrSet::from_iter([ustr("Color")])
+        );
+    }
+}
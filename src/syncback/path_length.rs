@@ -0,0 +1,216 @@
+//! Pre-flight check that every path syncback is about to write fits within
+//! the platform's path length limits. Deeply nested directory-format
+//! Instances can easily run past Windows' `MAX_PATH`, which otherwise fails
+//! partway through the write with a raw OS error that doesn't say which
+//! Instance was responsible.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use rbx_dom_weak::{types::Ref, WeakDom};
+use thiserror::Error;
+
+use super::{inst_path, FsSnapshot};
+
+/// The longest full path, in UTF-16 units, Windows accepts when long paths
+/// aren't enabled. This is `MAX_PATH` without its terminating NUL.
+#[cfg(windows)]
+pub const MAX_PATH_LENGTH: usize = 259;
+/// The longest full path, in bytes, macOS accepts (`PATH_MAX` without its
+/// terminating NUL).
+#[cfg(target_os = "macos")]
+pub const MAX_PATH_LENGTH: usize = 1023;
+/// The longest full path, in bytes, most other systems accept (`PATH_MAX`
+/// without its terminating NUL).
+#[cfg(not(any(windows, target_os = "macos")))]
+pub const MAX_PATH_LENGTH: usize = 4095;
+
+/// The longest single file or directory name common file systems accept.
+pub const MAX_COMPONENT_LENGTH: usize = 255;
+
+/// A path syncback would write that's longer than the platform allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlongPath {
+    pub path: PathBuf,
+    /// The length of the path, measured the way the platform measures it.
+    pub length: usize,
+    /// The path of the Instance that produced this file, if known.
+    pub instance_path: Option<String>,
+}
+
+/// Returned when syncback would write paths longer than the platform allows.
+#[derive(Debug, Error)]
+#[error("{}", describe(.paths, *.max_length))]
+pub struct PathTooLongError {
+    pub paths: Vec<OverlongPath>,
+    pub max_length: usize,
+}
+
+/// Checks every file and directory in `snapshot` against this platform's
+/// path length limits, attributing any that are too long to the Instance in
+/// `new_tree` that produced them using `instance_paths`.
+pub fn check_path_lengths(
+    snapshot: &FsSnapshot,
+    new_tree: &WeakDom,
+    instance_paths: &HashMap<Ref, Vec<PathBuf>>,
+) -> Result<(), PathTooLongError> {
+    check_path_lengths_with(snapshot, new_tree, instance_paths, MAX_PATH_LENGTH)
+}
+
+/// Like [`check_path_lengths`], but with an explicit limit on the length of
+/// a full path.
+pub fn check_path_lengths_with(
+    snapshot: &FsSnapshot,
+    new_tree: &WeakDom,
+    instance_paths: &HashMap<Ref, Vec<PathBuf>>,
+    max_length: usize,
+) -> Result<(), PathTooLongError> {
+    let mut owners: HashMap<&Path, Ref> = HashMap::new();
+    for (referent, paths) in instance_paths {
+        for path in paths {
+            owners.insert(path.as_path(), *referent);
+        }
+    }
+
+    let mut paths: Vec<OverlongPath> = snapshot
+        .added_paths()
+        .into_iter()
+        .filter_map(|path| {
+            let length = path_length(path.as_os_str());
+            let component_too_long = path
+                .components()
+                .any(|component| path_length(component.as_os_str()) > MAX_COMPONENT_LENGTH);
+            if length <= max_length && !component_too_long {
+                return None;
+            }
+
+            // Files inside a directory-format Instance's folder belong to it
+            // even if they weren't recorded individually.
+            let instance_path = path
+                .ancestors()
+                .find_map(|ancestor| owners.get(ancestor))
+                .map(|referent| inst_path(new_tree, *referent));
+
+            Some(OverlongPath {
+                path: path.to_path_buf(),
+                length,
+                instance_path,
+            })
+        })
+        .collect();
+
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    paths.sort_by(|a, b| a.path.cmp(&b.path));
+    Err(PathTooLongError { paths, max_length })
+}
+
+/// Returns the length of `path` the way this platform limits it: UTF-16
+/// units on Windows and bytes everywhere else.
+#[cfg(windows)]
+fn path_length(path: &std::ffi::OsStr) -> usize {
+    use std::os::windows::ffi::OsStrExt;
+    path.encode_wide().count()
+}
+
+/// Returns the length of `path` the way this platform limits it: UTF-16
+/// units on Windows and bytes everywhere else.
+#[cfg(not(windows))]
+fn path_length(path: &std::ffi::OsStr) -> usize {
+    path.len()
+}
+
+fn describe(paths: &[OverlongPath], max_length: usize) -> String {
+    let mut message = format!(
+        "Syncback would write {} path(s) longer than this platform allows \
+         ({max_length} characters per path, {MAX_COMPONENT_LENGTH} per name):",
+        paths.len()
+    );
+    for path in paths {
+        write!(
+            message,
+            "\n  {} ({} characters)",
+            path.path.display(),
+            path.length
+        )
+        .unwrap();
+        if let Some(instance_path) = &path.instance_path {
+            write!(message, "\n    from Instance {instance_path}").unwrap();
+        }
+    }
+    message.push_str(
+        "\nOn Windows, enable long path support (the LongPathsEnabled registry setting). \
+         Otherwise, move the project closer to the root of the drive or flatten deeply \
+         nested Instances, e.g. by writing them as models with the `modelFormat` syncback \
+         rule. Pass --skip-path-check to write anyway.",
+    );
+    message
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::InstanceBuilder;
+
+    #[test]
+    fn reports_overlong_paths_with_instances() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let workspace = dom.insert(dom.root_ref(), InstanceBuilder::new("Workspace"));
+        let deep = dom.insert(
+            workspace,
+            InstanceBuilder::new("Folder").with_name("VeryDeeplyNested"),
+        );
+
+        let deep_dir = PathBuf::from("/project/src/Workspace/VeryDeeplyNested");
+        let long_file = deep_dir.join("a".repeat(40) + ".luau");
+        let short_file = PathBuf::from("/project/src/short.luau");
+
+        let snapshot = FsSnapshot::new()
+            .with_added_dir(&deep_dir)
+            .with_added_file(&long_file, Vec::new())
+            .with_added_file(&short_file, Vec::new());
+
+        let mut instance_paths = HashMap::new();
+        instance_paths.insert(deep, vec![deep_dir.clone()]);
+
+        let limit = deep_dir.as_os_str().len() + 1;
+        let err = check_path_lengths_with(&snapshot, &dom, &instance_paths, limit).unwrap_err();
+
+        assert_eq!(err.paths.len(), 1);
+        assert_eq!(err.paths[0].path, long_file);
+        assert_eq!(
+            err.paths[0].instance_path.as_deref(),
+            Some("Workspace/VeryDeeplyNested")
+        );
+
+        let message = err.to_string();
+        assert!(message.contains("Workspace/VeryDeeplyNested"), "{message}");
+        assert!(message.contains("long path"), "{message}");
+    }
+
+    #[test]
+    fn reports_overlong_names() {
+        let dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let long_name = PathBuf::from("/project/src").join("a".repeat(300) + ".luau");
+        let snapshot = FsSnapshot::new().with_added_file(&long_name, Vec::new());
+
+        let err =
+            check_path_lengths_with(&snapshot, &dom, &HashMap::new(), usize::MAX).unwrap_err();
+        assert_eq!(err.paths[0].path, long_name);
+        assert_eq!(err.paths[0].instance_path, None);
+    }
+
+    #[test]
+    fn short_paths_pass() {
+        let dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let snapshot = FsSnapshot::new().with_added_file("/project/src/init.luau", Vec::new());
+
+        check_path_lengths(&snapshot, &dom, &HashMap::new()).unwrap();
+    }
+}
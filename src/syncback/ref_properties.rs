@@ -12,7 +12,7 @@ use rbx_dom_weak::{
 use crate::{
     ref_attribute_name, ref_target_attribute_name,
     snapshot::is_script_class,
-    syncback::{name_needs_slugify, slugify_name},
+    syncback::{name_needs_slugify, slugify_name, SyncbackStats},
     REF_ID_ATTRIBUTE_NAME, REF_PATH_ATTRIBUTE_PREFIX, REF_POINTER_ATTRIBUTE_PREFIX,
 };
 
@@ -209,10 +209,13 @@ pub fn collect_all_paths(dom: &WeakDom) -> HashMap<Ref, String> {
 /// The `final_paths` parameter, when provided, contains the definitive
 /// filesystem-name-based paths assigned during the syncback walk (including
 /// dedup suffixes like `~2`). These take priority over `tentative_fs_path()`.
+///
+/// References that can't be written are recorded as warnings in `stats`.
 pub fn collect_referents(
     dom: &WeakDom,
     pre_prune_paths: &HashMap<Ref, String>,
     final_paths: Option<&HashMap<Ref, String>>,
+    stats: &SyncbackStats,
 ) -> RefLinks {
     let mut path_links: HashMap<Ref, Vec<PathRefLink>> = HashMap::new();
     let id_links: HashMap<Ref, Vec<IdRefLink>> = HashMap::new();
@@ -267,11 +270,11 @@ pub fn collect_referents(
                     path: format!("@game/{external_path}"),
                 });
             } else {
-                log::warn!(
+                stats.record_warning(format!(
                     "Property {}.{} will be `nil` on disk because the referenced instance does not exist",
                     tentative_fs_path(dom, inst_ref),
                     prop_name
-                );
+                ));
             }
         }
     }
@@ -472,11 +475,30 @@ mod tests {
         (dom, container, attachments, beam)
     }
 
+    #[test]
+    fn collect_referents_records_dangling_refs_as_warnings() {
+        let (mut dom, container, _, _) = make_beam_attachment_dom();
+        dom.insert(
+            container,
+            InstanceBuilder::new("Beam")
+                .with_name("Dangling")
+                .with_property("Attachment1", Variant::Ref(Ref::new())),
+        );
+
+        let stats = SyncbackStats::new();
+        collect_referents(&dom, &HashMap::new(), None, &stats);
+
+        let warnings = stats.warnings();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains("Attachment1"), "{warnings:?}");
+        assert!(stats.has_issues());
+    }
+
     #[test]
     fn collect_referents_uses_source_aware_placeholders() {
         let (dom, _, attachments, beam) = make_beam_attachment_dom();
 
-        let links = collect_referents(&dom, &HashMap::new(), None);
+        let links = collect_referents(&dom, &HashMap::new(), None, &SyncbackStats::new());
 
         let beam_links = links.path_links.get(&beam).expect("beam should have refs");
         assert_eq!(beam_links.len(), 1);
@@ -514,7 +536,7 @@ mod tests {
                 .with_property("Attachment1", Variant::Ref(att_b)),
         );
 
-        let links = collect_referents(&dom2, &HashMap::new(), None);
+        let links = collect_referents(&dom2, &HashMap::new(), None, &SyncbackStats::new());
 
         let placeholders: Vec<&String> = links.placeholder_to_source_and_target.keys().collect();
 
@@ -553,7 +575,12 @@ mod tests {
         final_paths.insert(beam, "Beams1/BeamA.model.json5".to_string());
         final_paths.insert(attachments[2], "Beams1/001~3.model.json5".to_string());
 
-        let links = collect_referents(&dom, &HashMap::new(), Some(&final_paths));
+        let links = collect_referents(
+            &dom,
+            &HashMap::new(),
+            Some(&final_paths),
+            &SyncbackStats::new(),
+        );
 
         assert!(
             links.placeholder_to_source_and_target.is_empty(),
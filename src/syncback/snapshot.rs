@@ -15,7 +15,7 @@ use rbx_dom_weak::{
     Instance, Ustr, UstrMap, WeakDom,
 };
 
-use super::{get_best_middleware, name_for_inst, PropertyFilterCache, SyncbackStats};
+use super::{get_best_middleware, name_for_inst, MetaFormat, PropertyFilterCache, SyncbackStats};
 
 #[derive(Clone, Copy)]
 pub struct SyncbackData<'sync> {
@@ -396,6 +396,56 @@ impl<'sync> SyncbackSnapshot<'sync> {
             .unwrap_or(false)
     }
 
+    /// Returns whether duplicate child names are deduplicated with numbered
+    /// suffixes rather than skipped. Defaults to `false`.
+    #[inline]
+    pub fn dedup_duplicate_names(&self) -> bool {
+        self.data
+            .project
+            .syncback_rules
+            .as_ref()
+            .map(|rules| rules.dedup_duplicate_names())
+            .unwrap_or(false)
+    }
+
+    /// Returns whether JSON models should be written with their keys sorted.
+    /// Defaults to `true`.
+    #[inline]
+    pub fn stable_property_order(&self) -> bool {
+        self.data
+            .project
+            .syncback_rules
+            .as_ref()
+            .map(|rules| rules.stable_property_order())
+            .unwrap_or(true)
+    }
+
+    /// Returns how many decimal places floats in model and meta files are
+    /// rounded to, if any.
+    #[inline]
+    pub fn number_precision(&self) -> Option<u8> {
+        self.data.project.number_precision()
+    }
+
+    /// Returns the format meta files should be written in. Defaults to
+    /// `json5`.
+    #[inline]
+    pub fn meta_format(&self) -> MetaFormat {
+        MetaFormat::for_rules(self.data.project.syncback_rules.as_ref())
+    }
+
+    /// Returns whether scripts should always get a meta file, even an empty
+    /// one. Defaults to `false`.
+    #[inline]
+    pub fn always_emit_meta(&self) -> bool {
+        self.data
+            .project
+            .syncback_rules
+            .as_ref()
+            .map(|rules| rules.always_emit_meta())
+            .unwrap_or(false)
+    }
+
     /// Returns a reference to the syncback statistics tracker.
     #[inline]
     pub fn stats(&self) -> &'sync SyncbackStats {
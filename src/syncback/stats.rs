@@ -6,10 +6,54 @@
 //! - Instances that fell back to rbxm/rbxmx format
 //! - Unknown classes not in the reflection database
 //! - Unknown properties not in the reflection database
+//! - Anything else syncback warned about and carried on past
+//!
+//! It can also carry a progress callback that the syncback loop reports to as
+//! it moves through its phases.
 
 use std::collections::HashSet;
+use std::fmt;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
+use std::time::Duration;
+
+/// A phase of the syncback loop, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncbackPhase {
+    /// Removing services and root children that won't be synced back.
+    Prune,
+    /// Hashing both trees to find unchanged subtrees (incremental mode only).
+    Hash,
+    /// Listing the files already on disk so orphans can be removed (clean
+    /// mode only).
+    OrphanScan,
+    /// Running middleware over every Instance.
+    Walk,
+}
+
+impl fmt::Display for SyncbackPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SyncbackPhase::Prune => "prune",
+            SyncbackPhase::Hash => "hash",
+            SyncbackPhase::OrphanScan => "orphan scan",
+            SyncbackPhase::Walk => "walk",
+        })
+    }
+}
+
+/// How far a syncback has gotten, passed to the progress callback.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncbackProgress {
+    pub phase: SyncbackPhase,
+    /// How many items the phase has processed: Instances for prune, hash and
+    /// walk, and paths for the orphan scan.
+    pub processed: usize,
+    /// Time since the syncback started.
+    pub elapsed: Duration,
+}
+
+type ProgressCallback = Box<dyn Fn(&SyncbackProgress) + Send + Sync>;
 
 /// Statistics collected during a syncback operation.
 ///
@@ -30,6 +74,13 @@ pub struct SyncbackStats {
     unknown_classes: Mutex<HashSet<String>>,
     /// Set of unknown property names encountered (class.property format).
     unknown_properties: Mutex<HashSet<String>>,
+
+    /// Warnings that don't fit any of the counters above, in the order they
+    /// were recorded.
+    warnings: Mutex<Vec<String>>,
+
+    /// Called at phase boundaries and periodically during the walk.
+    progress: Option<ProgressCallback>,
 }
 
 impl SyncbackStats {
@@ -38,6 +89,27 @@ impl SyncbackStats {
         Self::default()
     }
 
+    /// Sets a callback to report syncback progress to. It's called at the end
+    /// of every phase, and every so often during the walk.
+    pub fn with_progress(
+        mut self,
+        callback: impl Fn(&SyncbackProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Reports progress to the callback set with `with_progress`, if any.
+    pub fn report_progress(&self, phase: SyncbackPhase, processed: usize, elapsed: Duration) {
+        if let Some(callback) = &self.progress {
+            callback(&SyncbackProgress {
+                phase,
+                processed,
+                elapsed,
+            });
+        }
+    }
+
     /// Records that an instance was skipped due to having a duplicate name.
     ///
     /// When debug logging is enabled, logs the individual instance path.
@@ -126,6 +198,15 @@ impl SyncbackStats {
         }
     }
 
+    /// Records a warning that doesn't fit any of the other categories, and
+    /// logs it.
+    pub fn record_warning(&self, message: String) {
+        log::warn!("{}", message);
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(message);
+        }
+    }
+
     /// Returns the count of instances skipped due to duplicate names.
     pub fn duplicate_name_count(&self) -> usize {
         self.duplicate_name_count.load(Ordering::Relaxed)
@@ -162,12 +243,65 @@ impl SyncbackStats {
             .unwrap_or_default()
     }
 
+    /// Returns the warnings recorded with `record_warning`.
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.lock().map(|g| g.clone()).unwrap_or_default()
+    }
+
+    /// Returns how many warnings were recorded with `record_warning`.
+    pub fn warning_count(&self) -> usize {
+        self.warnings.lock().map(|g| g.len()).unwrap_or_default()
+    }
+
     /// Returns true if any issues were recorded.
     pub fn has_issues(&self) -> bool {
         self.duplicate_name_count() > 0
             || self.rbxm_fallback_count() > 0
             || self.unknown_class_count() > 0
             || self.unknown_property_count() > 0
+            || self.warning_count() > 0
+    }
+
+    /// Returns one line for every kind of issue that was recorded, followed
+    /// by every warning recorded with `record_warning`. Used to report
+    /// everything at once when warnings are treated as errors.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        let duplicate_count = self.duplicate_name_count();
+        if duplicate_count > 0 {
+            issues.push(format!(
+                "{duplicate_count} instance(s) could not be synced due to duplicate/indistinguishable names"
+            ));
+        }
+
+        let rbxm_count = self.rbxm_fallback_count();
+        if rbxm_count > 0 {
+            issues.push(format!(
+                "{rbxm_count} instance(s) fell back to binary model format (rbxm/rbxmx)"
+            ));
+        }
+
+        if self.unknown_class_count() > 0 {
+            let mut classes = self.unknown_classes();
+            classes.sort();
+            issues.push(format!(
+                "unknown classes not in reflection database: {}",
+                classes.join(", ")
+            ));
+        }
+
+        if self.unknown_property_count() > 0 {
+            let mut properties = self.unknown_properties();
+            properties.sort();
+            issues.push(format!(
+                "unknown properties not in reflection database: {}",
+                properties.join(", ")
+            ));
+        }
+
+        issues.extend(self.warnings());
+        issues
     }
 
     /// Returns true if there are unknown classes or properties that should be
@@ -238,6 +372,11 @@ impl SyncbackStats {
             }
         }
 
+        let warning_count = self.warning_count();
+        if warning_count > 0 {
+            log::warn!("  - {} other warning(s), logged above", warning_count);
+        }
+
         // Helpful hint about debug logging
         if duplicate_count > 0 || rbxm_count > 0 {
             log::warn!(
@@ -277,6 +416,12 @@ impl SyncbackStats {
         ) {
             self_props.extend(other_props.iter().cloned());
         }
+
+        if let (Ok(mut self_warnings), Ok(other_warnings)) =
+            (self.warnings.lock(), other.warnings.lock())
+        {
+            self_warnings.extend(other_warnings.iter().cloned());
+        }
     }
 }
 
@@ -309,6 +454,27 @@ mod tests {
         assert!(stats.has_unknown_types());
     }
 
+    #[test]
+    fn test_issues_list_every_kind_of_warning() {
+        let stats = SyncbackStats::new();
+        assert!(stats.issues().is_empty());
+
+        stats.record_duplicate_name("Root/Folder", "Script");
+        stats.record_unknown_property("Part", "CustomProp");
+        stats.record_warning("Skipped Root/Data".to_string());
+
+        assert!(stats.has_issues());
+        assert_eq!(
+            stats.issues(),
+            vec![
+                "1 instance(s) could not be synced due to duplicate/indistinguishable names"
+                    .to_string(),
+                "unknown properties not in reflection database: Part.CustomProp".to_string(),
+                "Skipped Root/Data".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn test_batch_recording() {
         let stats = SyncbackStats::new();
@@ -317,6 +483,25 @@ mod tests {
         assert_eq!(stats.duplicate_name_count(), 4);
     }
 
+    #[test]
+    fn test_progress_callback() {
+        let reports = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let stats = SyncbackStats::new().with_progress({
+            let reports = std::sync::Arc::clone(&reports);
+            move |progress| reports.lock().unwrap().push(progress.phase)
+        });
+
+        stats.report_progress(SyncbackPhase::Prune, 10, Duration::ZERO);
+        stats.report_progress(SyncbackPhase::Walk, 5, Duration::ZERO);
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![SyncbackPhase::Prune, SyncbackPhase::Walk]
+        );
+
+        // Without a callback, reporting does nothing.
+        SyncbackStats::new().report_progress(SyncbackPhase::Hash, 1, Duration::ZERO);
+    }
+
     #[test]
     fn test_merge() {
         let stats1 = SyncbackStats::new();
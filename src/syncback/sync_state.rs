@@ -0,0 +1,258 @@
+//! Tracks the contents of every file syncback last wrote, so a later syncback
+//! can tell when a file was edited on disk in the meantime instead of
+//! silently overwriting the edit.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use memofs::{IoResultExt, Vfs};
+use serde::{Deserialize, Serialize};
+
+use crate::path_serializer::display_absolute;
+
+use super::{hash_file_contents, FsSnapshot};
+
+/// Where the manifest is kept, relative to the project folder. It sits next
+/// to the syncback hash cache.
+pub const SYNC_STATE_PATH: &str = ".rojo/sync-state.json";
+
+/// A file that was changed on disk since syncback last wrote it, and that the
+/// current syncback would overwrite or remove.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conflict {
+    /// The path of the file, relative to the project folder.
+    pub path: PathBuf,
+    /// Whether syncback would remove the file rather than overwrite it.
+    pub removed: bool,
+}
+
+/// The `.rojo/sync-state.json` manifest: a map of every file syncback wrote,
+/// relative to the project folder, to the hash of what it wrote.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    files: BTreeMap<String, String>,
+}
+
+impl SyncState {
+    /// Reads the manifest for the project in `base_path`. A missing or
+    /// unreadable manifest is treated as empty, so every file is assumed to
+    /// be untouched.
+    pub fn load(vfs: &Vfs, base_path: &Path) -> Self {
+        let path = base_path.join(SYNC_STATE_PATH);
+        let contents = match vfs.read(&path).with_not_found() {
+            Ok(Some(contents)) => contents,
+            Ok(None) => return Self::default(),
+            Err(err) => {
+                log::warn!("Could not read {}: {}", path.display(), err);
+                return Self::default();
+            }
+        };
+
+        serde_json::from_slice(&contents).unwrap_or_else(|err| {
+            log::warn!(
+                "Ignoring {} because it could not be parsed: {}",
+                path.display(),
+                err
+            );
+            Self::default()
+        })
+    }
+
+    /// Writes the manifest for the project in `base_path` through `vfs`.
+    pub fn save(&self, vfs: &Vfs, base_path: &Path) -> io::Result<()> {
+        let path = base_path.join(SYNC_STATE_PATH);
+        if let Some(parent) = path.parent() {
+            vfs.create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        vfs.write(path, contents)
+    }
+
+    /// Loads the manifest for the project in `base_path`, records `snapshot`
+    /// in it and saves it again. Failing to save is only logged, since the
+    /// files themselves were already written.
+    pub fn record_written(vfs: &Vfs, base_path: &Path, snapshot: &FsSnapshot) {
+        let mut sync_state = Self::load(vfs, base_path);
+        sync_state.record(base_path, snapshot);
+        if let Err(err) = sync_state.save(vfs, base_path) {
+            log::warn!("Could not update {}: {}", SYNC_STATE_PATH, err);
+        }
+    }
+
+    /// Updates the manifest to match the file system after `snapshot` has
+    /// been written to `base_path`.
+    pub fn record(&mut self, base_path: &Path, snapshot: &FsSnapshot) {
+        for path in snapshot.removed_files() {
+            self.files.remove(&state_key(base_path, path));
+        }
+        for dir in snapshot.removed_dirs() {
+            let prefix = format!("{}/", state_key(base_path, dir));
+            self.files.retain(|key, _| !key.starts_with(&prefix));
+        }
+        for path in snapshot.added_files() {
+            if let Some(contents) = snapshot.added_file_contents(path) {
+                self.files.insert(
+                    state_key(base_path, path),
+                    hash_file_contents(contents).to_hex().to_string(),
+                );
+            }
+        }
+    }
+
+    /// Returns every file that `snapshot` would overwrite or remove whose
+    /// contents on disk no longer match what syncback last wrote there.
+    ///
+    /// Overwriting a file with exactly what's on disk isn't a conflict, and
+    /// neither is touching a file the manifest doesn't know about. Only the
+    /// files `snapshot` touches are read, and they're read through `vfs`.
+    pub fn conflicts(&self, vfs: &Vfs, base_path: &Path, snapshot: &FsSnapshot) -> Vec<Conflict> {
+        if self.files.is_empty() || snapshot.is_empty() {
+            return Vec::new();
+        }
+
+        let mut conflicts = Vec::new();
+        let mut check = |key: &str, new_contents: Option<&[u8]>| {
+            let Some(recorded) = self.files.get(key) else {
+                return;
+            };
+            let Ok(Some(on_disk)) = vfs.read(base_path.join(key)).with_not_found() else {
+                return;
+            };
+            if new_contents == Some(on_disk.as_slice()) {
+                return;
+            }
+            if hash_file_contents(&on_disk).to_hex().as_str() != recorded {
+                conflicts.push(Conflict {
+                    path: PathBuf::from(key),
+                    removed: new_contents.is_none(),
+                });
+            }
+        };
+
+        for path in snapshot.added_files() {
+            check(
+                &state_key(base_path, path),
+                snapshot.added_file_contents(path),
+            );
+        }
+        for path in snapshot.removed_files() {
+            check(&state_key(base_path, path), None);
+        }
+        for dir in snapshot.removed_dirs() {
+            let prefix = format!("{}/", state_key(base_path, dir));
+            for key in self.files.keys().filter(|key| key.starts_with(&prefix)) {
+                check(key, None);
+            }
+        }
+
+        conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+        conflicts.dedup();
+        conflicts
+    }
+}
+
+/// Returns the manifest key for `path`, which may be absolute or relative to
+/// `base_path`.
+fn state_key(base_path: &Path, path: &Path) -> String {
+    display_absolute(path.strip_prefix(base_path).unwrap_or(path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use memofs::{InMemoryFs, VfsSnapshot};
+
+    fn vfs_with(files: &[(&str, &str)]) -> Vfs {
+        let mut imfs = InMemoryFs::new();
+        for (path, contents) in files {
+            imfs.load_snapshot(
+                Path::new("/project").join(path),
+                VfsSnapshot::file(*contents),
+            )
+            .unwrap();
+        }
+        Vfs::new(imfs)
+    }
+
+    fn state_after_writing(files: &[(&str, &str)]) -> SyncState {
+        let base = Path::new("/project");
+        let mut snapshot = FsSnapshot::new();
+        for (path, contents) in files {
+            snapshot.add_file(base.join(path), contents.as_bytes().to_vec());
+        }
+        let mut state = SyncState::default();
+        state.record(base, &snapshot);
+        state
+    }
+
+    #[test]
+    fn out_of_band_edit_is_a_conflict() {
+        let base = Path::new("/project");
+        let state = state_after_writing(&[("a.luau", "old"), ("b.luau", "old")]);
+        let vfs = vfs_with(&[("a.luau", "edited by hand"), ("b.luau", "old")]);
+
+        let snapshot = FsSnapshot::new()
+            .with_added_file(base.join("a.luau"), b"new".to_vec())
+            .with_added_file(base.join("b.luau"), b"new".to_vec());
+
+        assert_eq!(
+            state.conflicts(&vfs, base, &snapshot),
+            vec![Conflict {
+                path: PathBuf::from("a.luau"),
+                removed: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn removing_an_edited_file_is_a_conflict() {
+        let base = Path::new("/project");
+        let state = state_after_writing(&[("dir/a.luau", "old")]);
+        let vfs = vfs_with(&[("dir/a.luau", "edited by hand")]);
+
+        let mut snapshot = FsSnapshot::new();
+        snapshot.remove_dir(base.join("dir"));
+
+        assert_eq!(
+            state.conflicts(&vfs, base, &snapshot),
+            vec![Conflict {
+                path: PathBuf::from("dir/a.luau"),
+                removed: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn untracked_and_identical_files_are_not_conflicts() {
+        let base = Path::new("/project");
+        let state = state_after_writing(&[("a.luau", "old")]);
+        let vfs = vfs_with(&[("a.luau", "same"), ("untracked.luau", "edited")]);
+
+        let snapshot = FsSnapshot::new()
+            .with_added_file(base.join("a.luau"), b"same".to_vec())
+            .with_added_file(base.join("untracked.luau"), b"new".to_vec());
+
+        assert!(state.conflicts(&vfs, base, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn save_and_load_go_through_the_vfs() {
+        let base = Path::new("/project");
+        let state = state_after_writing(&[("a.luau", "old")]);
+        let vfs = vfs_with(&[("a.luau", "edited by hand")]);
+        state.save(&vfs, base).unwrap();
+
+        let snapshot = FsSnapshot::new().with_added_file(base.join("a.luau"), b"new".to_vec());
+        assert_eq!(
+            SyncState::load(&vfs, base)
+                .conflicts(&vfs, base, &snapshot)
+                .len(),
+            1
+        );
+    }
+}
@@ -7,10 +7,13 @@ use crate::resolution::cleanup_f32;
 const EPSILON_F32: f32 = 0.0001;
 const EPSILON_F64: f64 = 0.0001;
 
+/// The float tolerance [`variant_eq`] uses.
+pub const DEFAULT_FLOAT_EPSILON: f64 = EPSILON_F64;
+
 /// Fuzzy float equality matching Lua trueEquals: absolute OR relative epsilon.
 /// NaN == NaN is true.
 #[inline(always)]
-fn fuzzy_eq_f32(a: f32, b: f32) -> bool {
+fn approx_eq_f32(a: f32, b: f32, epsilon: f32) -> bool {
     if a.is_nan() {
         return b.is_nan();
     }
@@ -19,11 +22,11 @@ fn fuzzy_eq_f32(a: f32, b: f32) -> bool {
     }
     let diff = (a - b).abs();
     let max_val = a.abs().max(b.abs()).max(1.0);
-    diff < EPSILON_F32 || diff < max_val * EPSILON_F32
+    diff < epsilon || diff < max_val * epsilon
 }
 
 #[inline(always)]
-fn fuzzy_eq_f64(a: f64, b: f64) -> bool {
+fn approx_eq_f64(a: f64, b: f64, epsilon: f64) -> bool {
     if a.is_nan() {
         return b.is_nan();
     }
@@ -32,13 +35,24 @@ fn fuzzy_eq_f64(a: f64, b: f64) -> bool {
     }
     let diff = (a - b).abs();
     let max_val = a.abs().max(b.abs()).max(1.0);
-    diff < EPSILON_F64 || diff < max_val * EPSILON_F64
+    diff < epsilon || diff < max_val * epsilon
 }
 
 /// Compares two variants to determine if they're equal. This correctly takes
-/// float comparisons into account.
+/// float comparisons into account, allowing a difference of `EPSILON_F32` or
+/// `EPSILON_F64`.
 #[inline]
 pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
+    variant_eq_approx(variant_a, variant_b, DEFAULT_FLOAT_EPSILON)
+}
+
+/// Like [`variant_eq`], but float components (including those of vectors,
+/// `CFrame`s and colors) may differ by up to `epsilon`, either absolutely or
+/// relative to their magnitude.
+#[inline]
+pub fn variant_eq_approx(variant_a: &Variant, variant_b: &Variant, epsilon: f64) -> bool {
+    let epsilon_f32 = epsilon as f32;
+
     if variant_a.ty() != variant_b.ty() {
         return false;
     }
@@ -53,7 +67,7 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
             // Since Attributes are stored with a BTreeMap, the keys are sorted
             // and we can compare each map's keys in order.
             for ((a_name, a_value), (b_name, b_value)) in a.iter().zip(b.iter()) {
-                if !(a_name == b_name && variant_eq(a_value, b_value)) {
+                if !(a_name == b_name && variant_eq_approx(a_value, b_value, epsilon)) {
                     return false;
                 }
             }
@@ -65,13 +79,15 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
         (Variant::Bool(a), Variant::Bool(b)) => a == b,
         (Variant::BrickColor(a), Variant::BrickColor(b)) => a == b,
         (Variant::CFrame(a), Variant::CFrame(b)) => {
-            vector_eq(&a.position, &b.position)
-                && vector_eq(&a.orientation.x, &b.orientation.x)
-                && vector_eq(&a.orientation.y, &b.orientation.y)
-                && vector_eq(&a.orientation.z, &b.orientation.z)
+            vector_eq_approx(&a.position, &b.position, epsilon_f32)
+                && vector_eq_approx(&a.orientation.x, &b.orientation.x, epsilon_f32)
+                && vector_eq_approx(&a.orientation.y, &b.orientation.y, epsilon_f32)
+                && vector_eq_approx(&a.orientation.z, &b.orientation.z, epsilon_f32)
         }
         (Variant::Color3(a), Variant::Color3(b)) => {
-            fuzzy_eq_f32(a.r, b.r) && fuzzy_eq_f32(a.g, b.g) && fuzzy_eq_f32(a.b, b.b)
+            approx_eq_f32(a.r, b.r, epsilon_f32)
+                && approx_eq_f32(a.g, b.g, epsilon_f32)
+                && approx_eq_f32(a.b, b.b, epsilon_f32)
         }
         (Variant::Color3uint8(a), Variant::Color3uint8(b)) => a == b,
         (Variant::ColorSequence(a), Variant::ColorSequence(b)) => {
@@ -84,10 +100,10 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
             b_keypoints.sort_unstable_by(|k1, k2| k1.time.partial_cmp(&k2.time).unwrap());
 
             for (a_kp, b_kp) in a_keypoints.iter().zip(b_keypoints) {
-                if !(fuzzy_eq_f32(a_kp.time, b_kp.time)
-                    && fuzzy_eq_f32(a_kp.color.r, b_kp.color.r)
-                    && fuzzy_eq_f32(a_kp.color.g, b_kp.color.g)
-                    && fuzzy_eq_f32(a_kp.color.b, b_kp.color.b))
+                if !(approx_eq_f32(a_kp.time, b_kp.time, epsilon_f32)
+                    && approx_eq_f32(a_kp.color.r, b_kp.color.r, epsilon_f32)
+                    && approx_eq_f32(a_kp.color.g, b_kp.color.g, epsilon_f32)
+                    && approx_eq_f32(a_kp.color.b, b_kp.color.b, epsilon_f32))
                 {
                     return false;
                 }
@@ -99,8 +115,8 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
         (Variant::Enum(a), Variant::Enum(b)) => a == b,
         (Variant::EnumItem(a), Variant::EnumItem(b)) => a == b,
         (Variant::Faces(a), Variant::Faces(b)) => a == b,
-        (Variant::Float32(a), Variant::Float32(b)) => fuzzy_eq_f32(*a, *b),
-        (Variant::Float64(a), Variant::Float64(b)) => fuzzy_eq_f64(*a, *b),
+        (Variant::Float32(a), Variant::Float32(b)) => approx_eq_f32(*a, *b, epsilon_f32),
+        (Variant::Float64(a), Variant::Float64(b)) => approx_eq_f64(*a, *b, epsilon),
         (Variant::Font(a), Variant::Font(b)) => {
             a.weight == b.weight && a.style == b.style && a.family == b.family
         }
@@ -109,7 +125,7 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
         (Variant::MaterialColors(a), Variant::MaterialColors(b)) => a.encode() == b.encode(),
         (Variant::NetAssetRef(a), Variant::NetAssetRef(b)) => a == b,
         (Variant::NumberRange(a), Variant::NumberRange(b)) => {
-            fuzzy_eq_f32(a.max, b.max) && fuzzy_eq_f32(a.min, b.min)
+            approx_eq_f32(a.max, b.max, epsilon_f32) && approx_eq_f32(a.min, b.min, epsilon_f32)
         }
         (Variant::NumberSequence(a), Variant::NumberSequence(b)) => {
             if a.keypoints.len() != b.keypoints.len() {
@@ -121,9 +137,9 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
             b_keypoints.sort_unstable_by(|k1, k2| k1.time.partial_cmp(&k2.time).unwrap());
 
             for (a_kp, b_kp) in a_keypoints.iter().zip(b_keypoints) {
-                if !(fuzzy_eq_f32(a_kp.time, b_kp.time)
-                    && fuzzy_eq_f32(a_kp.value, b_kp.value)
-                    && fuzzy_eq_f32(a_kp.envelope, b_kp.envelope))
+                if !(approx_eq_f32(a_kp.time, b_kp.time, epsilon_f32)
+                    && approx_eq_f32(a_kp.value, b_kp.value, epsilon_f32)
+                    && approx_eq_f32(a_kp.envelope, b_kp.envelope, epsilon_f32))
                 {
                     return false;
                 }
@@ -132,10 +148,10 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
         }
         (Variant::OptionalCFrame(a), Variant::OptionalCFrame(b)) => match (a, b) {
             (Some(a), Some(b)) => {
-                vector_eq(&a.position, &b.position)
-                    && vector_eq(&a.orientation.x, &b.orientation.x)
-                    && vector_eq(&a.orientation.y, &b.orientation.y)
-                    && vector_eq(&a.orientation.z, &b.orientation.z)
+                vector_eq_approx(&a.position, &b.position, epsilon_f32)
+                    && vector_eq_approx(&a.orientation.x, &b.orientation.x, epsilon_f32)
+                    && vector_eq_approx(&a.orientation.y, &b.orientation.y, epsilon_f32)
+                    && vector_eq_approx(&a.orientation.z, &b.orientation.z, epsilon_f32)
             }
             (None, None) => true,
             _ => false,
@@ -143,27 +159,33 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
         (Variant::PhysicalProperties(a), Variant::PhysicalProperties(b)) => match (a, b) {
             (PhysicalProperties::Default, PhysicalProperties::Default) => true,
             (PhysicalProperties::Custom(a2), PhysicalProperties::Custom(b2)) => {
-                fuzzy_eq_f32(a2.density(), b2.density())
-                    && fuzzy_eq_f32(a2.elasticity(), b2.elasticity())
-                    && fuzzy_eq_f32(a2.friction(), b2.friction())
-                    && fuzzy_eq_f32(a2.elasticity_weight(), b2.elasticity_weight())
-                    && fuzzy_eq_f32(a2.friction_weight(), b2.friction_weight())
-                    && fuzzy_eq_f32(a2.acoustic_absorption(), b2.acoustic_absorption())
+                approx_eq_f32(a2.density(), b2.density(), epsilon_f32)
+                    && approx_eq_f32(a2.elasticity(), b2.elasticity(), epsilon_f32)
+                    && approx_eq_f32(a2.friction(), b2.friction(), epsilon_f32)
+                    && approx_eq_f32(a2.elasticity_weight(), b2.elasticity_weight(), epsilon_f32)
+                    && approx_eq_f32(a2.friction_weight(), b2.friction_weight(), epsilon_f32)
+                    && approx_eq_f32(
+                        a2.acoustic_absorption(),
+                        b2.acoustic_absorption(),
+                        epsilon_f32,
+                    )
             }
             _ => false,
         },
         (Variant::Ray(a), Variant::Ray(b)) => {
-            vector_eq(&a.direction, &b.direction) && vector_eq(&a.origin, &b.origin)
+            vector_eq_approx(&a.direction, &b.direction, epsilon_f32)
+                && vector_eq_approx(&a.origin, &b.origin, epsilon_f32)
         }
         (Variant::Rect(a), Variant::Rect(b)) => {
-            fuzzy_eq_f32(a.max.x, b.max.x)
-                && fuzzy_eq_f32(a.max.y, b.max.y)
-                && fuzzy_eq_f32(a.min.x, b.min.x)
-                && fuzzy_eq_f32(a.min.y, b.min.y)
+            approx_eq_f32(a.max.x, b.max.x, epsilon_f32)
+                && approx_eq_f32(a.max.y, b.max.y, epsilon_f32)
+                && approx_eq_f32(a.min.x, b.min.x, epsilon_f32)
+                && approx_eq_f32(a.min.y, b.min.y, epsilon_f32)
         }
         (Variant::Ref(a), Variant::Ref(b)) => a == b,
         (Variant::Region3(a), Variant::Region3(b)) => {
-            vector_eq(&a.max, &b.max) && vector_eq(&a.min, &b.min)
+            vector_eq_approx(&a.max, &b.max, epsilon_f32)
+                && vector_eq_approx(&a.min, &b.min, epsilon_f32)
         }
         (Variant::Region3int16(a), Variant::Region3int16(b)) => a == b,
         (Variant::SecurityCapabilities(a), Variant::SecurityCapabilities(b)) => a == b,
@@ -185,21 +207,21 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
             }
         }
         (Variant::UDim(a), Variant::UDim(b)) => {
-            fuzzy_eq_f32(a.scale, b.scale) && a.offset == b.offset
+            approx_eq_f32(a.scale, b.scale, epsilon_f32) && a.offset == b.offset
         }
         (Variant::UDim2(a), Variant::UDim2(b)) => {
-            fuzzy_eq_f32(a.x.scale, b.x.scale)
+            approx_eq_f32(a.x.scale, b.x.scale, epsilon_f32)
                 && a.x.offset == b.x.offset
-                && fuzzy_eq_f32(a.y.scale, b.y.scale)
+                && approx_eq_f32(a.y.scale, b.y.scale, epsilon_f32)
                 && a.y.offset == b.y.offset
         }
         (Variant::UniqueId(a), Variant::UniqueId(b)) => a == b,
         (Variant::String(a), Variant::String(b)) => a == b,
         (Variant::Vector2(a), Variant::Vector2(b)) => {
-            fuzzy_eq_f32(a.x, b.x) && fuzzy_eq_f32(a.y, b.y)
+            approx_eq_f32(a.x, b.x, epsilon_f32) && approx_eq_f32(a.y, b.y, epsilon_f32)
         }
         (Variant::Vector2int16(a), Variant::Vector2int16(b)) => a == b,
-        (Variant::Vector3(a), Variant::Vector3(b)) => vector_eq(a, b),
+        (Variant::Vector3(a), Variant::Vector3(b)) => vector_eq_approx(a, b, epsilon_f32),
         (Variant::Vector3int16(a), Variant::Vector3int16(b)) => a == b,
         (a, b) => panic!(
             "unsupport variant comparison: {:?} and {:?}",
@@ -210,8 +232,10 @@ pub fn variant_eq(variant_a: &Variant, variant_b: &Variant) -> bool {
 }
 
 #[inline(always)]
-fn vector_eq(a: &Vector3, b: &Vector3) -> bool {
-    fuzzy_eq_f32(a.x, b.x) && fuzzy_eq_f32(a.y, b.y) && fuzzy_eq_f32(a.z, b.z)
+fn vector_eq_approx(a: &Vector3, b: &Vector3, epsilon: f32) -> bool {
+    approx_eq_f32(a.x, b.x, epsilon)
+        && approx_eq_f32(a.y, b.y, epsilon)
+        && approx_eq_f32(a.z, b.z, epsilon)
 }
 
 // ============================================================================
@@ -439,33 +463,33 @@ mod tests {
 
     #[test]
     fn fuzzy_eq_matches_lua_absolute_epsilon() {
-        assert!(fuzzy_eq_f32(1.0, 1.0 + 0.000099));
-        assert!(!fuzzy_eq_f32(1.0, 1.0 + 0.00011));
+        assert!(approx_eq_f32(1.0, 1.0 + 0.000099, EPSILON_F32));
+        assert!(!approx_eq_f32(1.0, 1.0 + 0.00011, EPSILON_F32));
     }
 
     #[test]
     fn fuzzy_eq_matches_lua_relative_epsilon() {
-        assert!(fuzzy_eq_f32(10000.0, 10000.0 + 0.9));
-        assert!(!fuzzy_eq_f32(10000.0, 10000.0 + 1.1));
+        assert!(approx_eq_f32(10000.0, 10000.0 + 0.9, EPSILON_F32));
+        assert!(!approx_eq_f32(10000.0, 10000.0 + 1.1, EPSILON_F32));
     }
 
     #[test]
     fn fuzzy_eq_nan_handling() {
-        assert!(fuzzy_eq_f32(f32::NAN, f32::NAN));
-        assert!(!fuzzy_eq_f32(f32::NAN, 0.0));
-        assert!(!fuzzy_eq_f32(0.0, f32::NAN));
+        assert!(approx_eq_f32(f32::NAN, f32::NAN, EPSILON_F32));
+        assert!(!approx_eq_f32(f32::NAN, 0.0, EPSILON_F32));
+        assert!(!approx_eq_f32(0.0, f32::NAN, EPSILON_F32));
     }
 
     #[test]
     fn fuzzy_eq_zero_and_negative_zero() {
-        assert!(fuzzy_eq_f32(0.0, -0.0));
+        assert!(approx_eq_f32(0.0, -0.0, EPSILON_F32));
     }
 
     #[test]
     fn fuzzy_eq_f64_basic() {
-        assert!(fuzzy_eq_f64(1.0, 1.0 + 0.000099));
-        assert!(!fuzzy_eq_f64(1.0, 1.0 + 0.00011));
-        assert!(fuzzy_eq_f64(f64::NAN, f64::NAN));
+        assert!(approx_eq_f64(1.0, 1.0 + 0.000099, EPSILON_F64));
+        assert!(!approx_eq_f64(1.0, 1.0 + 0.00011, EPSILON_F64));
+        assert!(approx_eq_f64(f64::NAN, f64::NAN, EPSILON_F64));
     }
 
     #[test]
@@ -480,6 +504,110 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn variant_eq_approx_scalar_floats() {
+        let epsilon = 0.01;
+        assert!(variant_eq_approx(
+            &Variant::Float32(1.0),
+            &Variant::Float32(1.009),
+            epsilon
+        ));
+        assert!(!variant_eq_approx(
+            &Variant::Float32(1.0),
+            &Variant::Float32(1.011),
+            epsilon
+        ));
+        assert!(variant_eq_approx(
+            &Variant::Float64(1.0),
+            &Variant::Float64(1.009),
+            epsilon
+        ));
+        assert!(!variant_eq_approx(
+            &Variant::Float64(1.0),
+            &Variant::Float64(1.011),
+            epsilon
+        ));
+    }
+
+    #[test]
+    fn variant_eq_approx_vectors_and_colors() {
+        use rbx_dom_weak::types::Color3;
+        let epsilon = 0.01;
+        let base = Vector3::new(1.0, -2.0, 0.5);
+        assert!(variant_eq_approx(
+            &Variant::Vector3(base),
+            &Variant::Vector3(Vector3::new(1.009, -2.0, 0.5)),
+            epsilon
+        ));
+        assert!(!variant_eq_approx(
+            &Variant::Vector3(base),
+            &Variant::Vector3(Vector3::new(1.0, -2.0, 0.511)),
+            epsilon
+        ));
+        assert!(variant_eq_approx(
+            &Variant::Color3(Color3::new(0.5, 0.5, 0.5)),
+            &Variant::Color3(Color3::new(0.509, 0.5, 0.5)),
+            epsilon
+        ));
+        assert!(!variant_eq_approx(
+            &Variant::Color3(Color3::new(0.5, 0.5, 0.5)),
+            &Variant::Color3(Color3::new(0.5, 0.5, 0.489)),
+            epsilon
+        ));
+    }
+
+    #[test]
+    fn variant_eq_approx_cframes() {
+        use rbx_dom_weak::types::{CFrame, Matrix3};
+        let epsilon = 0.01;
+        let identity = Matrix3::new(
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let nudged_rotation = Matrix3::new(
+            Vector3::new(1.0, 0.009, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let tilted_rotation = Matrix3::new(
+            Vector3::new(1.0, 0.011, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let position = Vector3::new(1.0, 2.0, 3.0);
+        let cframe = Variant::CFrame(CFrame::new(position, identity));
+
+        assert!(variant_eq_approx(
+            &cframe,
+            &Variant::CFrame(CFrame::new(Vector3::new(1.009, 2.0, 3.0), identity)),
+            epsilon
+        ));
+        assert!(!variant_eq_approx(
+            &cframe,
+            &Variant::CFrame(CFrame::new(Vector3::new(1.011, 2.0, 3.0), identity)),
+            epsilon
+        ));
+        assert!(variant_eq_approx(
+            &cframe,
+            &Variant::CFrame(CFrame::new(position, nudged_rotation)),
+            epsilon
+        ));
+        assert!(!variant_eq_approx(
+            &cframe,
+            &Variant::CFrame(CFrame::new(position, tilted_rotation)),
+            epsilon
+        ));
+    }
+
+    #[test]
+    fn variant_eq_uses_default_epsilon() {
+        let a = Variant::Vector3(Vector3::new(1.0, 1.0, 1.0));
+        let b = Variant::Vector3(Vector3::new(1.0, 1.005, 1.0));
+        assert!(!variant_eq(&a, &b));
+        assert!(variant_eq_approx(&a, &b, 0.01));
+    }
+
     // ================================================================
     // disk_eq / variant_eq_disk tests
     // ================================================================
@@ -516,12 +644,12 @@ mod tests {
         let a: f32 = 10000.0;
         let b: f32 = 10000.5;
         assert!(
-            fuzzy_eq_f32(a, b),
+            approx_eq_f32(a, b, EPSILON_F32),
             "precondition: fuzzy should say equal (diff=0.5, relative threshold=1.0)"
         );
         assert!(
             !disk_eq_f32(a, b),
-            "disk representations differ: 6 sig digits gives '10000' vs '10000.5'"
+            "disk representations differ: '10000' vs '10000.5'"
         );
     }
 
@@ -7,7 +7,7 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use bytes::Bytes;
@@ -25,17 +25,22 @@ use crate::{
     serve_session::ServeSession,
     snapshot::{
         is_script_class, InstanceSnapshot, InstanceWithMeta, InstigatingSource, PatchAdd, PatchSet,
-        PatchUpdate,
+        PatchUpdate, RojoTree,
     },
-    syncback::{slugify_name, VISIBLE_SERVICES},
+    syncback::{slugify_name, MetaFormat, VISIBLE_SERVICES},
     web::{
         interface::{
-            ErrorResponse, Instance, InstanceMetadata, MessagesPacket, OpenResponse, ReadResponse,
-            ServerInfoResponse, SocketPacket, SocketPacketBody, SocketPacketType, SubscribeMessage,
-            SyncbackPayload, SyncbackRequest, WriteRequest, WriteResponse, PROTOCOL_VERSION,
-            SERVER_VERSION,
+            ErrorResponse, HealthResponse, Instance, InstanceMetadata, MessagesPacket,
+            OpenResponse, ReadBatchRequest, ReadBatchResponse, ReadResponse, ServerExitReason,
+            ServerInfoResponse, ShutdownRequest, SocketPacket, SocketPacketBody, SocketPacketType,
+            SubscribeMessage, SyncbackPayload, SyncbackRequest, VerifyResponse, WriteProblem,
+            WriteProblemKind, WriteRequest, WriteResponse, PROTOCOL_VERSION, SERVER_VERSION,
         },
-        util::{deserialize_msgpack, msgpack, msgpack_ok, serialize_msgpack},
+        util::{
+            accepts_gzip, deserialize_msgpack, gzip_response, is_authorized, json, msgpack,
+            msgpack_ok, serialize_msgpack,
+        },
+        Connections,
     },
     web_api::{InstanceUpdate, RefPatchResponse, SerializeResponse},
 };
@@ -125,24 +130,61 @@ fn variant_to_json(variant: &Variant) -> Option<serde_json::Value> {
 pub async fn call(
     serve_session: Arc<ServeSession>,
     mut request: Request<Incoming>,
-    syncback_signal: Arc<super::SyncbackSignal>,
+    exit_signal: Arc<super::ExitSignal>,
     mcp_state: Arc<super::mcp::McpState>,
-    active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+    context: &super::RequestContext,
 ) -> Response<Full<Bytes>> {
+    let connections = Arc::clone(&context.connections);
+    let auth_token = context.auth_token.as_deref();
+
+    // The health check is for scripts waiting on the server to come up, so
+    // it's answered without a token.
+    if request.method() == Method::GET && request.uri().path() == "/api/health" {
+        return ApiService::new(serve_session).handle_api_health().await;
+    }
+
+    if !is_authorized(&request, auth_token) {
+        let mut response = msgpack(
+            ErrorResponse::unauthorized("A valid `Authorization: Bearer` token is required"),
+            StatusCode::UNAUTHORIZED,
+        );
+        response.headers_mut().insert(
+            hyper::header::WWW_AUTHENTICATE,
+            hyper::header::HeaderValue::from_static("Bearer"),
+        );
+        return response;
+    }
+
+    // A runaway plugin flooding writes would otherwise keep the change
+    // processor busy and starve everything else.
+    if request.method() == Method::POST && request.uri().path() == "/api/write" {
+        if let Some(limiter) = &context.write_limiter {
+            let acquired = limiter
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .try_acquire();
+            if let Err(retry_after) = acquired {
+                return too_many_requests(retry_after);
+            }
+        }
+    }
+
     let service = ApiService::new(serve_session);
+    let accepts_gzip = accepts_gzip(&request);
 
-    match (request.method(), request.uri().path()) {
-        (&Method::GET, "/api/rojo") => service.handle_api_rojo().await,
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/api/rojo") => service.handle_api_rojo(auth_token).await,
         (&Method::GET, path) if path.starts_with("/api/read/") => {
             service.handle_api_read(request).await
         }
+        (&Method::POST, "/api/read-batch") => service.handle_api_read_batch(request).await,
         (&Method::GET, "/api/mcp/stream") => {
             if is_upgrade_request(&request) {
                 handle_mcp_stream_upgrade(
                     &mut request,
                     Arc::clone(&service.serve_session),
                     mcp_state,
-                    active_api_connections,
+                    connections,
                 )
                 .await
             } else {
@@ -157,7 +199,7 @@ pub async fn call(
         (&Method::GET, path) if path.starts_with("/api/socket/") => {
             if is_upgrade_request(&request) {
                 service
-                    .handle_api_socket(&mut request, Arc::clone(&active_api_connections))
+                    .handle_api_socket(&mut request, Arc::clone(&connections))
                     .await
             } else {
                 msgpack(
@@ -168,6 +210,20 @@ pub async fn call(
                 )
             }
         }
+        (&Method::GET, "/api/subscribe") => {
+            if is_upgrade_request(&request) {
+                service
+                    .handle_api_subscribe(&mut request, Arc::clone(&connections))
+                    .await
+            } else {
+                msgpack(
+                    ErrorResponse::bad_request(
+                        "/api/subscribe must be called as a websocket upgrade request",
+                    ),
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        }
         (&Method::GET, path) if path.starts_with("/api/serialize/") => {
             service.handle_api_serialize(request).await
         }
@@ -180,111 +236,69 @@ pub async fn call(
         }
         (&Method::POST, "/api/write") => service.handle_api_write(request).await,
         (&Method::POST, "/api/syncback") => {
-            handle_api_syncback(request, &service, syncback_signal).await
+            handle_api_syncback(request, &service, exit_signal).await
+        }
+        (&Method::POST, "/api/shutdown") => {
+            handle_api_shutdown(request, &service, exit_signal).await
+        }
+        (&Method::POST, "/api/mcp/syncback") => {
+            handle_mcp_syncback(request, &service, exit_signal).await
         }
-        (&Method::POST, "/api/mcp/syncback") => handle_mcp_syncback(request, &service).await,
         (&Method::GET, "/api/validate-tree") => service.handle_api_validate_tree().await,
+        (&Method::GET, "/api/verify") => service.handle_api_verify().await,
+        (&Method::GET, "/api/snapshot") => service.handle_api_snapshot().await,
+        (&Method::GET, "/api/watched-paths") => service.handle_api_watched_paths().await,
         (&Method::GET, "/api/git-metadata") => service.handle_api_git_metadata().await,
 
         (_method, path) => msgpack(
             ErrorResponse::not_found(format!("Route not found: {}", path)),
             StatusCode::NOT_FOUND,
         ),
+    };
+
+    if accepts_gzip {
+        gzip_response(response, context.gzip_threshold).await
+    } else {
+        response
     }
 }
 
 async fn handle_api_syncback(
     request: Request<Incoming>,
     service: &ApiService,
-    syncback_signal: Arc<super::SyncbackSignal>,
+    exit_signal: Arc<super::ExitSignal>,
 ) -> Response<Full<Bytes>> {
-    let body = match request.into_body().collect().await {
-        Ok(collected) => collected.to_bytes(),
-        Err(err) => {
-            return msgpack(
-                ErrorResponse::bad_request(format!("Failed to read request body: {err}")),
-                StatusCode::BAD_REQUEST,
-            );
-        }
+    let syncback_request = match read_syncback_request(request, service).await {
+        Ok(syncback_request) => syncback_request,
+        Err(response) => return response,
     };
 
-    let syncback_request: SyncbackRequest = match deserialize_msgpack(&body) {
-        Ok(req) => req,
-        Err(err) => {
-            return msgpack(
-                ErrorResponse::bad_request(format!(
-                    "Failed to deserialize syncback request: {err}"
-                )),
-                StatusCode::BAD_REQUEST,
-            );
-        }
-    };
-
-    let client_protocol = syncback_request.protocol_version as u64;
-    if client_protocol != PROTOCOL_VERSION {
-        return msgpack(
-            ErrorResponse::bad_request(format!(
-                "Protocol version mismatch: expected {}, got {}",
-                PROTOCOL_VERSION, client_protocol
-            )),
-            StatusCode::BAD_REQUEST,
-        );
-    }
-
-    let server_semver = SERVER_VERSION;
-    let server_parts: Vec<&str> = server_semver.split('.').collect();
-    let client_parts: Vec<&str> = syncback_request.server_version.split('.').collect();
-    let server_major_minor = server_parts.get(..2);
-    let client_major_minor = client_parts.get(..2);
-    if server_major_minor != client_major_minor {
-        return msgpack(
-            ErrorResponse::bad_request(format!(
-                "Server version mismatch: server is {}, plugin expects {}",
-                SERVER_VERSION, syncback_request.server_version
-            )),
-            StatusCode::BAD_REQUEST,
-        );
-    }
-
-    if let Some(place_id_f64) = syncback_request.place_id {
-        let place_id = place_id_f64 as u64;
-        if let Some(expected) = service.serve_session.serve_place_ids() {
-            if !expected.contains(&place_id) {
-                return msgpack(
-                    ErrorResponse::bad_request(format!(
-                        "Place ID {} is not in the servePlaceIds whitelist",
-                        place_id
-                    )),
-                    StatusCode::FORBIDDEN,
-                );
-            }
-        }
-        if let Some(blocked) = service.serve_session.blocked_place_ids() {
-            if blocked.contains(&place_id) {
-                return msgpack(
-                    ErrorResponse::bad_request(format!(
-                        "Place ID {} is in the blockedPlaceIds list",
-                        place_id
-                    )),
-                    StatusCode::FORBIDDEN,
-                );
-            }
-        }
-    }
-
     log::info!(
         "Live syncback requested with {} service chunks, {} bytes of rbxm data",
         syncback_request.services.len(),
         syncback_request.data.len()
     );
 
+    let keep_running = syncback_request.keep_running;
     let payload = SyncbackPayload {
         data: syncback_request.data,
         services: syncback_request.services,
     };
-    if !syncback_signal.fire(payload) {
+    if keep_running {
+        return run_syncback_in_place(service, &exit_signal, payload).await;
+    }
+
+    // The serve loop writes the project after the server stops, so it must
+    // not overlap a syncback that's already writing it in place.
+    let Some(_running) = service.serve_session.try_begin_live_syncback() else {
         return msgpack(
-            ErrorResponse::bad_request("A syncback is already in progress"),
+            ErrorResponse::conflict("A syncback is already in progress"),
+            StatusCode::CONFLICT,
+        );
+    };
+    if !exit_signal.fire(ServerExitReason::SyncbackRequested(payload)) {
+        return msgpack(
+            ErrorResponse::conflict("A syncback is already in progress"),
             StatusCode::CONFLICT,
         );
     }
@@ -292,41 +306,43 @@ async fn handle_api_syncback(
     msgpack_ok(serde_json::json!({"status": "syncback_initiated"}))
 }
 
-pub(super) async fn handle_mcp_syncback(
+/// Reads and validates the body of a syncback request, answering with the
+/// error response when the request can't go ahead.
+async fn read_syncback_request(
     request: Request<Incoming>,
     service: &ApiService,
-) -> Response<Full<Bytes>> {
+) -> Result<SyncbackRequest, Response<Full<Bytes>>> {
     let body = match request.into_body().collect().await {
         Ok(collected) => collected.to_bytes(),
         Err(err) => {
-            return msgpack(
+            return Err(msgpack(
                 ErrorResponse::bad_request(format!("Failed to read request body: {err}")),
                 StatusCode::BAD_REQUEST,
-            );
+            ));
         }
     };
 
     let syncback_request: SyncbackRequest = match deserialize_msgpack(&body) {
         Ok(req) => req,
         Err(err) => {
-            return msgpack(
+            return Err(msgpack(
                 ErrorResponse::bad_request(format!(
                     "Failed to deserialize syncback request: {err}"
                 )),
                 StatusCode::BAD_REQUEST,
-            );
+            ));
         }
     };
 
     let client_protocol = syncback_request.protocol_version as u64;
     if client_protocol != PROTOCOL_VERSION {
-        return msgpack(
+        return Err(msgpack(
             ErrorResponse::bad_request(format!(
                 "Protocol version mismatch: expected {}, got {}",
                 PROTOCOL_VERSION, client_protocol
             )),
             StatusCode::BAD_REQUEST,
-        );
+        ));
     }
 
     let server_semver = SERVER_VERSION;
@@ -335,51 +351,67 @@ pub(super) async fn handle_mcp_syncback(
     let server_major_minor = server_parts.get(..2);
     let client_major_minor = client_parts.get(..2);
     if server_major_minor != client_major_minor {
-        return msgpack(
+        return Err(msgpack(
             ErrorResponse::bad_request(format!(
                 "Server version mismatch: server is {}, plugin expects {}",
                 SERVER_VERSION, syncback_request.server_version
             )),
             StatusCode::BAD_REQUEST,
-        );
+        ));
     }
 
     if let Some(place_id_f64) = syncback_request.place_id {
         let place_id = place_id_f64 as u64;
         if let Some(expected) = service.serve_session.serve_place_ids() {
             if !expected.contains(&place_id) {
-                return msgpack(
+                return Err(msgpack(
                     ErrorResponse::bad_request(format!(
                         "Place ID {} is not in the servePlaceIds whitelist",
                         place_id
                     )),
                     StatusCode::FORBIDDEN,
-                );
+                ));
             }
         }
         if let Some(blocked) = service.serve_session.blocked_place_ids() {
             if blocked.contains(&place_id) {
-                return msgpack(
+                return Err(msgpack(
                     ErrorResponse::bad_request(format!(
                         "Place ID {} is in the blockedPlaceIds list",
                         place_id
                     )),
                     StatusCode::FORBIDDEN,
-                );
+                ));
             }
         }
     }
 
-    log::info!(
-        "MCP syncback requested with {} service chunks, {} bytes of rbxm data",
-        syncback_request.services.len(),
-        syncback_request.data.len()
-    );
+    Ok(syncback_request)
+}
 
-    let payload = SyncbackPayload {
-        data: syncback_request.data,
-        services: syncback_request.services,
+/// Runs a syncback against the project while the server keeps running, and
+/// answers with how many files were written and removed.
+async fn run_syncback_in_place(
+    service: &ApiService,
+    exit_signal: &super::ExitSignal,
+    payload: SyncbackPayload,
+) -> Response<Full<Bytes>> {
+    // Two syncbacks writing the project at the same time would race each
+    // other's file writes and removals.
+    let Some(_running) = service.serve_session.try_begin_live_syncback() else {
+        return msgpack(
+            ErrorResponse::conflict("A syncback is already in progress"),
+            StatusCode::CONFLICT,
+        );
     };
+    // A restart-style syncback gives up the guard once the server starts
+    // exiting, but still writes the project afterwards.
+    if exit_signal.is_fired() {
+        return msgpack(
+            ErrorResponse::conflict("The server is exiting"),
+            StatusCode::CONFLICT,
+        );
+    }
 
     let project_path = service.serve_session.root_project().file_location.clone();
 
@@ -390,7 +422,7 @@ pub(super) async fn handle_mcp_syncback(
     {
         Ok(Ok(stats)) => {
             log::info!(
-                "MCP syncback complete: wrote {} files/folders, removed {}",
+                "Syncback complete: wrote {} files/folders, removed {}",
                 stats.added,
                 stats.removed
             );
@@ -401,22 +433,108 @@ pub(super) async fn handle_mcp_syncback(
             }))
         }
         Ok(Err(err)) => {
-            log::error!("MCP syncback failed: {err:#}");
+            log::error!("Syncback failed: {err:#}");
             msgpack(
-                ErrorResponse::bad_request(format!("Syncback failed: {err:#}")),
+                ErrorResponse::internal_error(format!("Syncback failed: {err:#}")),
                 StatusCode::INTERNAL_SERVER_ERROR,
             )
         }
         Err(err) => {
-            log::error!("MCP syncback task panicked: {err}");
+            log::error!("Syncback task panicked: {err}");
             msgpack(
-                ErrorResponse::bad_request("Syncback task panicked"),
+                ErrorResponse::internal_error("Syncback task panicked"),
                 StatusCode::INTERNAL_SERVER_ERROR,
             )
         }
     }
 }
 
+/// Answers a write over the connection's rate limit with a `429` saying how
+/// many seconds to wait before trying again.
+fn too_many_requests(retry_after: Duration) -> Response<Full<Bytes>> {
+    let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = msgpack(
+        ErrorResponse::too_many_requests(format!("Too many writes, retry in {retry_after_secs}s")),
+        StatusCode::TOO_MANY_REQUESTS,
+    );
+    response.headers_mut().insert(
+        hyper::header::RETRY_AFTER,
+        hyper::header::HeaderValue::from(retry_after_secs),
+    );
+    response
+}
+
+/// Stops the server once in-flight requests finish, the same way a syncback
+/// does. The request must name the current session, so that a page in a
+/// browser can't stop the server with a blind cross-origin POST.
+async fn handle_api_shutdown(
+    request: Request<Incoming>,
+    service: &ApiService,
+    exit_signal: Arc<super::ExitSignal>,
+) -> Response<Full<Bytes>> {
+    let body = match request.into_body().collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(err) => {
+            return msgpack(
+                ErrorResponse::bad_request(format!("Failed to read request body: {err}")),
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    };
+
+    let request: ShutdownRequest = match deserialize_msgpack(&body) {
+        Ok(request) => request,
+        Err(err) => {
+            return msgpack(
+                ErrorResponse::bad_request(format!("Invalid body: {}", err)),
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    };
+
+    if request.session_id != service.serve_session.session_id() {
+        return msgpack(
+            ErrorResponse::bad_request("Wrong session ID"),
+            StatusCode::BAD_REQUEST,
+        );
+    }
+
+    if !exit_signal.fire(ServerExitReason::Shutdown) {
+        return msgpack(
+            ErrorResponse::conflict("The server is already exiting"),
+            StatusCode::CONFLICT,
+        );
+    }
+
+    log::info!("Shutdown requested, finishing in-flight requests...");
+    msgpack_ok(serde_json::json!({"status": "shutting_down"}))
+}
+
+/// Like `POST /api/syncback` with `keepRunning` set: syncs back without
+/// stopping the server and answers with a summary.
+pub(super) async fn handle_mcp_syncback(
+    request: Request<Incoming>,
+    service: &ApiService,
+    exit_signal: Arc<super::ExitSignal>,
+) -> Response<Full<Bytes>> {
+    let syncback_request = match read_syncback_request(request, service).await {
+        Ok(syncback_request) => syncback_request,
+        Err(response) => return response,
+    };
+
+    log::info!(
+        "MCP syncback requested with {} service chunks, {} bytes of rbxm data",
+        syncback_request.services.len(),
+        syncback_request.data.len()
+    );
+
+    let payload = SyncbackPayload {
+        data: syncback_request.data,
+        services: syncback_request.services,
+    };
+    run_syncback_in_place(service, &exit_signal, payload).await
+}
+
 pub struct ApiService {
     serve_session: Arc<ServeSession>,
     suppressed_paths: Arc<Mutex<HashMap<PathBuf, (usize, usize)>>>,
@@ -503,7 +621,7 @@ impl ApiService {
     }
 
     /// Get a summary of information about the server
-    async fn handle_api_rojo(&self) -> Response<Full<Bytes>> {
+    async fn handle_api_rojo(&self, auth_token: Option<&str>) -> Response<Full<Bytes>> {
         let handler_start = Instant::now();
 
         let root_instance_id = self.serve_session.tree().get_root_id();
@@ -535,7 +653,9 @@ impl ApiService {
             sync_scripts_only: self.serve_session.sync_scripts_only(),
             ignore_hidden_services,
             visible_services,
+            initial_sync_mode: self.serve_session.initial_sync_mode(),
             git_metadata: None,
+            auth_token: auth_token.map(str::to_owned),
         })
     }
 
@@ -562,12 +682,7 @@ impl ApiService {
                 .initial_head_commit()
                 .map(|s| s.to_owned());
 
-            let mut project_roots = Vec::new();
-            crate::serve_session::collect_path_roots(
-                &self.serve_session.root_project().tree,
-                self.serve_session.root_dir(),
-                &mut project_roots,
-            );
+            let project_roots = self.serve_session.root_project().resolved_path_roots();
             let project_prefixes: Vec<String> = project_roots
                 .iter()
                 .filter_map(|abs| abs.strip_prefix(&repo_root_owned).ok())
@@ -619,6 +734,21 @@ impl ApiService {
         msgpack_ok(&git_metadata)
     }
 
+    /// Reports whether the server is ready, as JSON so CI scripts can poll it
+    /// without a msgpack decoder. Anyone can call this, so it must stay cheap:
+    /// drift checks live behind the authenticated `/api/validate-tree`.
+    async fn handle_api_health(&self) -> Response<Full<Bytes>> {
+        json(
+            HealthResponse {
+                session_id: self.serve_session.session_id(),
+                ready: self.serve_session.is_ready(),
+                uptime_secs: self.serve_session.start_time().elapsed().as_secs_f64(),
+                message_cursor: self.serve_session.message_queue().cursor(),
+            },
+            StatusCode::OK,
+        )
+    }
+
     /// Read-only tree freshness check for test infrastructure.
     /// Re-snapshots from disk and returns drift counts without applying corrections.
     async fn handle_api_validate_tree(&self) -> Response<Full<Bytes>> {
@@ -626,11 +756,47 @@ impl ApiService {
         msgpack_ok(&report)
     }
 
+    /// Lists every divergence between the in-memory tree and the project on
+    /// disk, as JSON. Unlike `/api/validate-tree`, this names the Instances
+    /// involved, so it's useful for diagnosing desyncs in the field.
+    async fn handle_api_verify(&self) -> Response<Full<Bytes>> {
+        match self.serve_session.verify() {
+            Ok(divergences) => json(
+                VerifyResponse {
+                    session_id: self.serve_session.session_id(),
+                    divergences,
+                },
+                StatusCode::OK,
+            ),
+            Err(err) => json(
+                ErrorResponse::internal_error(format!("Could not snapshot the project: {err:?}")),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        }
+    }
+
+    /// Dumps the whole tree, including metadata the plugin never sees (like
+    /// instigating sources and relevant paths), as JSON. The response
+    /// deserializes into an `InstanceSnapshot` that `RojoTree::new` can load,
+    /// so a user's tree can be attached to a bug report and replayed in tests.
+    async fn handle_api_snapshot(&self) -> Response<Full<Bytes>> {
+        let tree = self.serve_session.tree();
+        let snapshot = tree.to_snapshot(tree.get_root_id());
+        json(snapshot, StatusCode::OK)
+    }
+
+    /// Lists every path the file watcher currently has a watch on, for
+    /// diagnosing watcher issues.
+    async fn handle_api_watched_paths(&self) -> Response<Full<Bytes>> {
+        let watched_paths = self.serve_session.vfs().watched_paths();
+        json(watched_paths, StatusCode::OK)
+    }
+
     /// Handle WebSocket upgrade for real-time message streaming
     async fn handle_api_socket(
         &self,
         request: &mut Request<Incoming>,
-        active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+        connections: Arc<Connections>,
     ) -> Response<Full<Bytes>> {
         let argument = &request.uri().path()["/api/socket/".len()..];
         let input_cursor: u32 = match argument.parse() {
@@ -643,6 +809,29 @@ impl ApiService {
             }
         };
 
+        self.upgrade_to_subscription(request, input_cursor, connections)
+    }
+
+    /// Handle WebSocket upgrade for `/api/subscribe`, which pushes every
+    /// patch produced after the socket connects. Unlike `/api/socket`, the
+    /// client doesn't need a message cursor from an earlier `/api/read`.
+    async fn handle_api_subscribe(
+        &self,
+        request: &mut Request<Incoming>,
+        connections: Arc<Connections>,
+    ) -> Response<Full<Bytes>> {
+        let cursor = self.serve_session.message_queue().cursor();
+        self.upgrade_to_subscription(request, cursor, connections)
+    }
+
+    /// Upgrades `request` to a WebSocket that streams messages from the
+    /// message queue, starting at `input_cursor`.
+    fn upgrade_to_subscription(
+        &self,
+        request: &mut Request<Incoming>,
+        input_cursor: u32,
+        connections: Arc<Connections>,
+    ) -> Response<Full<Bytes>> {
         // Upgrade the connection to WebSocket
         let (response, websocket) = match upgrade(request, None) {
             Ok(result) => result,
@@ -658,10 +847,10 @@ impl ApiService {
 
         // Spawn a task to handle the WebSocket connection
         tokio::spawn(async move {
-            active_api_connections.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let live_sync = connections.enter_live_sync();
             let result =
                 handle_websocket_subscription(serve_session, websocket, input_cursor).await;
-            active_api_connections.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            drop(live_sync);
             if let Err(e) = result {
                 log::error!("Error in websocket subscription: {}", e);
             }
@@ -709,12 +898,37 @@ impl ApiService {
             }
         }
 
+        // The same checks back both modes: a dry run only reports them, and a
+        // real write also skips whatever they say can't be applied.
+        let problems = self.validate_write(&request);
+        if request.dry_run {
+            return msgpack_ok(WriteResponse {
+                session_id,
+                problems,
+                added_ids: HashMap::new(),
+            });
+        }
+        let skipped: HashSet<Ref> = problems
+            .iter()
+            .inspect(|problem| log::warn!("Syncback: {}", problem.message))
+            .filter(|problem| problem.kind.blocks_write())
+            .map(|problem| problem.id)
+            .collect();
+        if !skipped.is_empty() {
+            request.removed.retain(|id| !skipped.contains(id));
+            request.added.retain(|guid, _| !skipped.contains(guid));
+            request
+                .updated
+                .retain(|update| !skipped.contains(&update.id));
+        }
+
         // Process removed instances (syncback: delete files from Rojo filesystem)
         // Phase 1: Gather paths with the tree lock held.
         // Phase 2: Delete files without the lock.
         // Only IDs that are actually removable (have a Path instigating source)
-        // are included in the PatchSet. ProjectNode instances are skipped so
-        // they are NOT removed from the in-memory tree.
+        // are included in the PatchSet. ProjectNode instances were already
+        // reported and dropped by validate_write, so they are NOT removed from
+        // the in-memory tree.
         let mut actually_removed: Vec<Ref> = Vec::new();
         if !request.removed.is_empty() {
             let removal_actions: Vec<(Ref, Option<PathBuf>)> = {
@@ -724,23 +938,10 @@ impl ApiService {
                     .iter()
                     .map(|&id| {
                         let action = tree.get_instance(id).and_then(|inst| {
-                            inst.metadata().instigating_source.as_ref().and_then(|src| {
-                                match src {
-                                    crate::snapshot::InstigatingSource::Path(p) => {
-                                        Some(p.clone())
-                                    }
-                                    crate::snapshot::InstigatingSource::ProjectNode {
-                                        name,
-                                        ..
-                                    } => {
-                                        log::warn!(
-                                            "Syncback: Cannot remove '{}' — defined in project file",
-                                            name
-                                        );
-                                        None
-                                    }
-                                }
-                            })
+                            match inst.metadata().instigating_source.as_ref()? {
+                                InstigatingSource::Path(p) => Some(p.clone()),
+                                InstigatingSource::ProjectNode { .. } => None,
+                            }
                         });
                         (id, action)
                     })
@@ -1142,6 +1343,18 @@ impl ApiService {
             })
             .collect();
 
+        // The placeholders the client can look up in the response: the ID of
+        // every added instance, plus the ones given to nested children.
+        let mut placeholders = HashSet::new();
+        for added in &added_instances {
+            placeholders.insert(added.instance.snapshot_id);
+        }
+        for (guid, added) in &request.added {
+            if !updated_in_place.contains(guid) && added.parent.is_some() {
+                collect_child_ids(added, &mut placeholders);
+            }
+        }
+
         let stage_ids: HashSet<Ref> = request.stage_ids.into_iter().collect();
 
         let paths_to_stage: Vec<PathBuf> = if !stage_ids.is_empty() {
@@ -1161,17 +1374,197 @@ impl ApiService {
             Vec::new()
         };
 
-        tree_mutation_sender
-            .send(PatchSet {
-                removed_instances: actually_removed,
-                added_instances,
-                updated_instances,
-                stage_ids,
-                stage_paths: paths_to_stage,
-            })
-            .unwrap();
+        let message_cursor = self.serve_session.message_queue().cursor();
+
+        tree_mutation_sender
+            .send(PatchSet {
+                removed_instances: actually_removed,
+                added_instances,
+                updated_instances,
+                stage_ids,
+                stage_paths: paths_to_stage,
+            })
+            .unwrap();
+
+        let added_ids = self.wait_for_added_ids(message_cursor, placeholders).await;
+
+        msgpack_ok(WriteResponse {
+            session_id,
+            problems,
+            added_ids,
+        })
+    }
+
+    /// Waits for the change processor to apply the patch sent after
+    /// `message_cursor`, and returns the tree ID each placeholder in
+    /// `placeholders` was given.
+    async fn wait_for_added_ids(
+        &self,
+        mut message_cursor: u32,
+        mut placeholders: HashSet<Ref>,
+    ) -> HashMap<Ref, Ref> {
+        let mut added_ids = HashMap::new();
+        if placeholders.is_empty() {
+            return added_ids;
+        }
+
+        let message_queue = self.serve_session.message_queue();
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !placeholders.is_empty() {
+            let receiver = message_queue.subscribe(message_cursor);
+            match tokio::time::timeout_at(deadline, receiver).await {
+                Ok(Ok((new_cursor, patches))) => {
+                    message_cursor = new_cursor;
+                    for patch in patches {
+                        for (placeholder, id) in patch.added_ids {
+                            if placeholders.remove(&placeholder) {
+                                added_ids.insert(placeholder, id);
+                            }
+                        }
+                    }
+                }
+                Ok(Err(_)) => break,
+                Err(_) => {
+                    log::warn!(
+                        "Timed out waiting for {} added instance(s) to reach the tree",
+                        placeholders.len()
+                    );
+                    break;
+                }
+            }
+        }
+
+        added_ids
+    }
+
+    /// Checks a write request without applying any of it, returning the IDs
+    /// that aren't in the tree, added instances without a valid parent, class
+    /// names the reflection database doesn't know, removals of instances
+    /// defined in a project file, and renames that collide with a sibling.
+    ///
+    /// This is the write path's only validation: `handle_api_write` runs it
+    /// for every request and skips the entries whose problems block a write.
+    fn validate_write(&self, request: &WriteRequest) -> Vec<WriteProblem> {
+        let tree = self.serve_session.tree();
+        let database = rbx_reflection_database::get().unwrap();
+        let mut problems = Vec::new();
+        let mut report = |id: Ref, kind: WriteProblemKind, message: String| {
+            problems.push(WriteProblem { id, kind, message });
+        };
+
+        for &id in &request.removed {
+            let Some(instance) = tree.get_instance(id) else {
+                report(
+                    id,
+                    WriteProblemKind::UnknownInstance,
+                    format!("Removed instance {id} is not in the tree"),
+                );
+                continue;
+            };
+            if let Some(InstigatingSource::ProjectNode { name, .. }) =
+                &instance.metadata().instigating_source
+            {
+                report(
+                    id,
+                    WriteProblemKind::ProjectNode,
+                    format!("Cannot remove '{name}' — defined in project file"),
+                );
+            }
+        }
+
+        for (&guid, added) in &request.added {
+            match added.parent {
+                Some(parent)
+                    if tree.get_instance(parent).is_some()
+                        || request.added.contains_key(&parent) => {}
+                Some(parent) => report(
+                    guid,
+                    WriteProblemKind::InvalidParent,
+                    format!(
+                        "Parent {parent} of added instance '{}' is not in the tree",
+                        added.name
+                    ),
+                ),
+                None => report(
+                    guid,
+                    WriteProblemKind::InvalidParent,
+                    format!("Added instance '{}' has no parent", added.name),
+                ),
+            }
+
+            let mut pending = vec![added];
+            while let Some(instance) = pending.pop() {
+                if !database.classes.contains_key(instance.class_name.as_str()) {
+                    report(
+                        guid,
+                        WriteProblemKind::UnknownClass,
+                        format!(
+                            "Class '{}' of added instance '{}' is not in the reflection database",
+                            instance.class_name, instance.name
+                        ),
+                    );
+                }
+                pending.extend(&instance.children);
+            }
+        }
+
+        let renamed: HashMap<Ref, &str> = request
+            .updated
+            .iter()
+            .filter_map(|update| Some((update.id, update.changed_name.as_deref()?)))
+            .collect();
+
+        for update in &request.updated {
+            let Some(instance) = tree.get_instance(update.id) else {
+                report(
+                    update.id,
+                    WriteProblemKind::UnknownInstance,
+                    format!("Updated instance {} is not in the tree", update.id),
+                );
+                continue;
+            };
+
+            if let Some(class_name) = &update.changed_class_name {
+                if !database.classes.contains_key(class_name.as_str()) {
+                    report(
+                        update.id,
+                        WriteProblemKind::UnknownClass,
+                        format!("Class '{class_name}' is not in the reflection database"),
+                    );
+                }
+            }
+
+            let Some(new_name) = update.changed_name.as_deref() else {
+                continue;
+            };
+            let Some(parent) = tree.get_instance(instance.parent()) else {
+                continue;
+            };
+            let conflict = parent.children().iter().find_map(|&sibling_id| {
+                if sibling_id == update.id {
+                    return None;
+                }
+                let sibling_name = match renamed.get(&sibling_id) {
+                    Some(name) => *name,
+                    None => tree.get_instance(sibling_id)?.name(),
+                };
+                sibling_name
+                    .eq_ignore_ascii_case(new_name)
+                    .then_some(sibling_name)
+            });
+            if let Some(sibling_name) = conflict {
+                report(
+                    update.id,
+                    WriteProblemKind::NameConflict,
+                    format!(
+                        "Renaming '{}' to '{new_name}' collides with its sibling '{sibling_name}'",
+                        instance.name()
+                    ),
+                );
+            }
+        }
 
-        msgpack_ok(WriteResponse { session_id })
+        problems
     }
 
     /// Convert an `AddedInstance` (from the plugin's write request) to an
@@ -1179,6 +1572,7 @@ impl ApiService {
     /// GUID is used as the `snapshot_id` so that `apply_patch_set` can map
     /// it to the tree ID assigned during insertion. This allows Ref properties
     /// in the same request to reference newly-added instances immediately.
+    /// Nested children use their `id` the same way when the client gave one.
     fn added_instance_to_snapshot(
         guid: Ref,
         added: &crate::web::interface::AddedInstance,
@@ -1200,7 +1594,9 @@ impl ApiService {
             children: added
                 .children
                 .iter()
-                .map(|child| Self::added_instance_to_snapshot(Ref::new(), child))
+                .map(|child| {
+                    Self::added_instance_to_snapshot(child.id.unwrap_or_else(Ref::new), child)
+                })
                 .collect(),
             metadata: Default::default(),
         }
@@ -1411,6 +1807,17 @@ impl ApiService {
             }
         }
 
+        // An explicit target path skips the parent's directory entirely. The
+        // new file maps to wherever that directory sits in the project, so
+        // the watcher adds it to the tree rather than a PatchAdd under
+        // `parent`.
+        if let Some(target_path) = &added.target_path {
+            let target_dir = self.resolve_added_target_path(added, target_path)?;
+            fs_err::create_dir_all(&target_dir)?;
+            self.syncback_instance_to_path_with_stats(added, &target_dir, stats, &HashSet::new())?;
+            return Ok(true);
+        }
+
         // Instance doesn't exist in tree - create new files
         // Get the parent's filesystem path from its metadata.
         // For ProjectNode sources, resolve the $path field relative to the project file.
@@ -1509,6 +1916,74 @@ impl ApiService {
         Ok(false)
     }
 
+    /// Resolves `AddedInstance::target_path` to an absolute directory.
+    /// Rejects paths that climb out with `..`, that fall outside every `$path`
+    /// root of the project, or that already contain a file or directory for
+    /// the instance.
+    fn resolve_added_target_path(
+        &self,
+        added: &crate::web::interface::AddedInstance,
+        target_path: &std::path::Path,
+    ) -> anyhow::Result<PathBuf> {
+        use crate::syncback::name_needs_slugify;
+        use std::path::Component;
+
+        if target_path
+            .components()
+            .any(|component| matches!(component, Component::ParentDir))
+        {
+            anyhow::bail!(
+                "Cannot add '{}' at {}: target paths may not contain '..'",
+                added.name,
+                target_path.display()
+            );
+        }
+
+        let project = self.serve_session.root_project();
+        let target_dir = project.folder_location().join(target_path);
+        if !project
+            .resolved_path_roots()
+            .iter()
+            .any(|root| target_dir.starts_with(root))
+        {
+            anyhow::bail!(
+                "Cannot add '{}' at {}: it is not inside any $path in the project",
+                added.name,
+                target_path.display()
+            );
+        }
+        if target_dir.exists() && !target_dir.is_dir() {
+            anyhow::bail!(
+                "Cannot add '{}' at {}: it is a file, not a directory",
+                added.name,
+                target_path.display()
+            );
+        }
+
+        let slug = if name_needs_slugify(&added.name) {
+            slugify_name(&added.name)
+        } else {
+            added.name.clone()
+        };
+        if let Ok(entries) = fs_err::read_dir(&target_dir) {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let stem = file_name.to_string_lossy();
+                let stem = stem.split('.').next().unwrap_or_default();
+                if stem.eq_ignore_ascii_case(&slug) {
+                    anyhow::bail!(
+                        "Cannot add '{}' at {}: {} already exists there",
+                        added.name,
+                        target_path.display(),
+                        file_name.to_string_lossy()
+                    );
+                }
+            }
+        }
+
+        Ok(target_dir)
+    }
+
     /// Update an existing instance in place instead of creating new files.
     /// This is called when the plugin sends an "added" instance that already
     /// exists in the tree. We update the existing file at its instigating_source
@@ -1665,7 +2140,7 @@ impl ApiService {
             // For non-script types, update the appropriate file
             _ => {
                 if existing_path.is_dir() {
-                    // Update init.meta.json5 if needed
+                    // Update the init meta file if needed
                     if !added.properties.is_empty() {
                         // Preserve the name field if the directory slug differs
                         // from the instance name (due to slugification/dedup).
@@ -1679,11 +2154,6 @@ impl ApiService {
                             None
                         };
                         self.write_init_meta_json(existing_path, added, instance_name, true)?;
-                        log::info!(
-                            "Syncback: Updated existing {} at {}/init.meta.json5",
-                            class_name,
-                            existing_path.display()
-                        );
                     }
 
                     // Handle children
@@ -1781,10 +2251,14 @@ impl ApiService {
             })?;
         }
 
-        // Move adjacent meta file into directory if it exists
-        let meta_path = containing_dir.join(format!("{}.meta.json5", dir_name));
-        if meta_path.exists() {
-            let init_meta_path = new_dir.join("init.meta.json5");
+        // Move adjacent meta files into the directory if they exist, keeping
+        // whichever format they were written in.
+        for format in MetaFormat::ALL {
+            let meta_path = containing_dir.join(format!("{}.{}", dir_name, format.extension()));
+            if !meta_path.exists() {
+                continue;
+            }
+            let init_meta_path = new_dir.join(format!("init.{}", format.extension()));
             self.suppress_path_remove(&meta_path);
             self.suppress_path(&init_meta_path);
             fs::rename(&meta_path, &init_meta_path).with_context(|| {
@@ -1811,7 +2285,8 @@ impl ApiService {
     }
 
     /// Converts a standalone non-script file (e.g., `MyPart.model.json5`, `MyValue.txt`)
-    /// into directory format (e.g., `MyPart/init.meta.json5`). This is needed when a
+    /// into directory format (e.g., `MyPart/init.meta.json5`, or `init.meta.toml`
+    /// with `metaFormat: "toml"`). This is needed when a
     /// child is being added to any non-script instance that is currently a standalone file.
     ///
     /// Returns the path to the new directory.
@@ -1848,35 +2323,33 @@ impl ApiService {
             .unwrap_or("");
 
         if file_name.ends_with(".model.json5") || file_name.ends_with(".model.json") {
-            // .model.json5 → init.meta.json5 with className and properties.
+            // .model.json5 → init meta file with className and properties.
             // IMPORTANT: .model.json5 supports a "children" field for inline child
-            // instances, but init.meta.json5 (DirectoryMetadata) does not. We must
+            // instances, but init meta files (DirectoryMetadata) do not. We must
             // parse the model and extract only the compatible fields, warning if
             // inline children would be lost.
             if standalone_path.exists() {
                 let raw = fs::read(standalone_path).unwrap_or_default();
-                let init_meta_path = new_dir.join("init.meta.json5");
 
                 // Parse the model JSON5 to extract only meta-compatible fields.
                 // Use json5::from_str (not serde_json) because .model.json5 files
                 // can contain JSON5 features (comments, trailing commas, unquoted keys).
                 let raw_str = String::from_utf8_lossy(&raw);
-                let meta_content = if let Ok(model) = json5::from_str::<serde_json::Value>(&raw_str)
-                {
+                if let Ok(model) = json5::from_str::<serde_json::Value>(&raw_str) {
                     // Warn about inline children that will be lost
                     if let Some(children) = model.get("children").or(model.get("Children")) {
                         if children.is_array() && children.as_array().is_some_and(|a| !a.is_empty())
                         {
                             log::warn!(
                                 "Syncback: .model.json5 at {} contains inline children \
-                                     that cannot be represented in init.meta.json5 — \
+                                     that cannot be represented in an init meta file — \
                                      these children will be lost during directory conversion",
                                 standalone_path.display()
                             );
                         }
                     }
 
-                    // Build init.meta.json5 with only the supported fields
+                    // Build the init meta file with only the supported fields
                     let mut meta = serde_json::Map::new();
                     if let Some(cn) = model.get("className").or(model.get("ClassName")).cloned() {
                         meta.insert("className".to_string(), cn);
@@ -1902,24 +2375,32 @@ impl ApiService {
                         meta.insert("id".to_string(), id);
                     }
 
-                    crate::json::to_vec_pretty_sorted(&serde_json::Value::Object(meta))
-                        .unwrap_or(raw.clone())
-                } else {
-                    // Parse failed — copy raw as fallback (best-effort)
+                    self.write_meta_file(&new_dir, "init", &serde_json::Value::Object(meta), true)?;
+                } else if self.meta_format() == MetaFormat::Json5 {
+                    // Parse failed — copy raw as fallback (best-effort).
                     log::warn!(
                         "Syncback: Could not parse {} as JSON — \
                              copying raw content to init.meta.json5",
                         standalone_path.display()
                     );
-                    raw
-                };
-
-                self.suppress_path(&init_meta_path);
-                fs::write(&init_meta_path, &meta_content)
-                    .with_context(|| format!("Failed to write {}", init_meta_path.display()))?;
+                    let init_meta_path = new_dir.join("init.meta.json5");
+                    self.suppress_path(&init_meta_path);
+                    fs::write(&init_meta_path, &raw)
+                        .with_context(|| format!("Failed to write {}", init_meta_path.display()))?;
+                } else {
+                    // Raw JSON can't go in a TOML meta file, so only the
+                    // class name survives.
+                    log::warn!(
+                        "Syncback: Could not parse {} as JSON — \
+                             writing only its class name to init.meta.toml",
+                        standalone_path.display()
+                    );
+                    let meta = serde_json::json!({ "className": class_name });
+                    self.write_meta_file(&new_dir, "init", &meta, true)?;
+                }
             }
         } else if file_ext == "txt" {
-            // StringValue .txt → init.meta.json5 with className and Value property
+            // StringValue .txt → init meta file with className and Value property
             let value = if standalone_path.exists() {
                 fs::read_to_string(standalone_path).unwrap_or_default()
             } else {
@@ -1931,12 +2412,7 @@ impl ApiService {
                     "Value": value
                 }
             });
-            let init_meta_path = new_dir.join("init.meta.json5");
-            let content = crate::json::to_vec_pretty_sorted(&meta)
-                .context("Failed to serialize init.meta.json5")?;
-            self.suppress_path(&init_meta_path);
-            fs::write(&init_meta_path, &content)
-                .with_context(|| format!("Failed to write {}", init_meta_path.display()))?;
+            self.write_meta_file(&new_dir, "init", &meta, true)?;
         } else if file_ext == "csv" {
             // LocalizationTable .csv → init.csv
             if standalone_path.exists() {
@@ -1947,16 +2423,11 @@ impl ApiService {
                     .with_context(|| format!("Failed to write {}", init_csv_path.display()))?;
             }
         } else {
-            // Generic fallback: create init.meta.json5 with className
+            // Generic fallback: create an init meta file with className
             let meta = serde_json::json!({
                 "className": class_name
             });
-            let init_meta_path = new_dir.join("init.meta.json5");
-            let content = crate::json::to_vec_pretty_sorted(&meta)
-                .context("Failed to serialize init.meta.json5")?;
-            self.suppress_path(&init_meta_path);
-            fs::write(&init_meta_path, &content)
-                .with_context(|| format!("Failed to write {}", init_meta_path.display()))?;
+            self.write_meta_file(&new_dir, "init", &meta, true)?;
         }
 
         // Remove the old standalone file
@@ -1986,12 +2457,14 @@ impl ApiService {
     /// - Directory: `remove_dir_all`
     /// - Init file (`init.luau`, `init.server.luau`, etc.): remove parent directory
     ///   (which IS the instance) + grandparent-level adjacent meta
-    /// - Regular file: remove the file + adjacent `.meta.json5`
+    /// - Regular file: remove the file + adjacent meta file
+    ///
+    /// Meta files are removed in every meta format, not just the project's.
     ///
     /// Returns `true` if the removal succeeded (or the path was already gone),
     /// `false` if the filesystem operation failed (logged as warning).
     fn remove_instance_at_path(&self, path: &Path) -> bool {
-        use crate::syncback::adjacent_meta_path;
+        use crate::syncback::adjacent_meta_path_with_format;
 
         if !path.exists() {
             log::info!(
@@ -2014,17 +2487,7 @@ impl ApiService {
             // init-file case below.
             if let Some(parent) = path.parent() {
                 if let Some(dir_name) = path.file_name().and_then(|f| f.to_str()) {
-                    let dir_meta = parent.join(format!("{}.meta.json5", dir_name));
-                    if dir_meta.exists() {
-                        self.suppress_path_remove(&dir_meta);
-                        if let Err(err) = fs::remove_file(&dir_meta) {
-                            log::warn!(
-                                "Failed to remove dir-level meta file {}: {}",
-                                dir_meta.display(),
-                                err
-                            );
-                        }
-                    }
+                    self.remove_meta_files(parent, dir_name);
                 }
             }
 
@@ -2059,17 +2522,7 @@ impl ApiService {
             // exists (e.g. grandparent/MyModule.meta.json5).
             if let Some(grandparent) = dir_path.parent() {
                 if let Some(dir_name) = dir_path.file_name().and_then(|f| f.to_str()) {
-                    let dir_meta = grandparent.join(format!("{}.meta.json5", dir_name));
-                    if dir_meta.exists() {
-                        self.suppress_path_remove(&dir_meta);
-                        if let Err(err) = fs::remove_file(&dir_meta) {
-                            log::warn!(
-                                "Failed to remove dir-level meta file {}: {}",
-                                dir_meta.display(),
-                                err
-                            );
-                        }
-                    }
+                    self.remove_meta_files(grandparent, dir_name);
                 }
             }
         } else {
@@ -2081,24 +2534,11 @@ impl ApiService {
             }
             log::info!("Syncback: Removed file at {}", path.display());
 
-            // Remove the adjacent meta file if it exists. The meta file is
-            // named after the script file's base stem (the slugified name),
-            // not the raw instance name.
-            let meta_path = adjacent_meta_path(path);
-            if meta_path.exists() {
-                self.suppress_path_remove(&meta_path);
-                if let Err(err) = fs::remove_file(&meta_path) {
-                    log::warn!(
-                        "Failed to remove adjacent meta file {}: {}",
-                        meta_path.display(),
-                        err
-                    );
-                } else {
-                    log::info!(
-                        "Syncback: Removed adjacent meta file at {}",
-                        meta_path.display()
-                    );
-                }
+            // Remove the adjacent meta files if they exist. They're named
+            // after the script file's base stem (the slugified name), not the
+            // raw instance name.
+            for format in MetaFormat::ALL {
+                self.remove_meta_file(&adjacent_meta_path_with_format(path, format));
             }
         }
 
@@ -2138,7 +2578,12 @@ impl ApiService {
                     "init.client.lua", // Legacy only (no .luau!)
                 ],
                 // For non-scripts, check for init.model.json5 or similar
-                _ => vec!["init.model.json5", "init.model.json", "init.meta.json5"],
+                _ => vec![
+                    "init.model.json5",
+                    "init.model.json",
+                    "init.meta.json5",
+                    "init.meta.toml",
+                ],
             };
 
             for init_file in init_files {
@@ -2276,10 +2721,7 @@ impl ApiService {
                         if old_path.exists() {
                             let _ = fs::remove_file(old_path);
                         }
-                        let meta_path = parent_dir.join(format!("{}.meta.json5", encoded_name));
-                        if meta_path.exists() {
-                            let _ = fs::remove_file(&meta_path);
-                        }
+                        self.remove_meta_files(parent_dir, &encoded_name);
                     }
 
                     let dir_path = parent_dir.join(&encoded_name);
@@ -2326,10 +2768,7 @@ impl ApiService {
                         if old_path.exists() {
                             let _ = fs::remove_file(old_path);
                         }
-                        let meta_path = parent_dir.join(format!("{}.meta.json5", encoded_name));
-                        if meta_path.exists() {
-                            let _ = fs::remove_file(&meta_path);
-                        }
+                        self.remove_meta_files(parent_dir, &encoded_name);
                     }
 
                     let dir_path = parent_dir.join(&encoded_name);
@@ -2376,10 +2815,7 @@ impl ApiService {
                         if old_path.exists() {
                             let _ = fs::remove_file(old_path);
                         }
-                        let meta_path = parent_dir.join(format!("{}.meta.json5", encoded_name));
-                        if meta_path.exists() {
-                            let _ = fs::remove_file(&meta_path);
-                        }
+                        self.remove_meta_files(parent_dir, &encoded_name);
                     }
 
                     let dir_path = parent_dir.join(&encoded_name);
@@ -2416,7 +2852,7 @@ impl ApiService {
                     format!("Failed to create directory: {}", dir_path.display())
                 })?;
 
-                // Write init.meta.json5 if has ANY properties or needs a name field
+                // Write the init meta file if has ANY properties or needs a name field
                 let has_metadata = if added.properties.is_empty() && meta_name_field.is_none() {
                     false
                 } else {
@@ -2441,10 +2877,10 @@ impl ApiService {
                 self.process_children_incremental(&children_refs, &dir_path, stats)?;
             }
 
-            // StringValue: .txt file if no children, directory with init.meta.json5 if has children
+            // StringValue: .txt file if no children, directory with an init meta file if has children
             "StringValue" => {
                 if has_children {
-                    // Must become directory - store StringValue data in init.meta.json5
+                    // Must become directory - store StringValue data in the init meta file
                     let dir_path = parent_dir.join(&encoded_name);
                     fs::create_dir_all(&dir_path).with_context(|| {
                         format!("Failed to create directory: {}", dir_path.display())
@@ -2476,12 +2912,7 @@ impl ApiService {
                             indexmap::IndexMap::new(),
                             indexmap::IndexMap::new(),
                         );
-                        let meta_path = parent_dir.join(format!("{}.meta.json5", encoded_name));
-                        let content = crate::json::to_vec_pretty_sorted(&meta)
-                            .context("Failed to serialize meta")?;
-                        fs::write(&meta_path, &content).with_context(|| {
-                            format!("Failed to write meta: {}", meta_path.display())
-                        })?;
+                        self.write_meta_file(parent_dir, &encoded_name, &meta, false)?;
                     }
                     log::info!("Syncback: Created StringValue at {}", file_path.display());
                 }
@@ -2502,7 +2933,7 @@ impl ApiService {
                     fs::write(&init_path, content.as_bytes()).with_context(|| {
                         format!("Failed to write file: {}", init_path.display())
                     })?;
-                    // Write init.meta.json5 for className and name preservation
+                    // Write the init meta file for className and name preservation
                     self.write_init_meta_json(&dir_path, added, meta_name_field, false)?;
                     log::info!(
                         "Syncback: Created LocalizationTable directory at {}",
@@ -2522,12 +2953,7 @@ impl ApiService {
                             indexmap::IndexMap::new(),
                             indexmap::IndexMap::new(),
                         );
-                        let meta_path = parent_dir.join(format!("{}.meta.json5", encoded_name));
-                        let content = crate::json::to_vec_pretty_sorted(&meta)
-                            .context("Failed to serialize meta")?;
-                        fs::write(&meta_path, &content).with_context(|| {
-                            format!("Failed to write meta: {}", meta_path.display())
-                        })?;
+                        self.write_meta_file(parent_dir, &encoded_name, &meta, false)?;
                     }
                     log::info!(
                         "Syncback: Created LocalizationTable at {}",
@@ -2562,15 +2988,9 @@ impl ApiService {
                         format!("Failed to create directory: {}", dir_path.display())
                     })?;
 
-                    // Write init.meta.json5 with class and properties
+                    // Write the init meta file with class and properties
                     self.write_init_meta_json(&dir_path, added, meta_name_field, false)?;
 
-                    log::info!(
-                        "Syncback: Updated {} at {}/init.meta.json5",
-                        added.class_name,
-                        dir_path.display()
-                    );
-
                     // Recursively process children
                     self.process_children_incremental(&children_refs, &dir_path, stats)?;
                 } else {
@@ -2614,6 +3034,7 @@ impl ApiService {
             .or_else(|| filename.strip_suffix(".project.json"))
             .or_else(|| filename.strip_suffix(".meta.json5"))
             .or_else(|| filename.strip_suffix(".meta.json"))
+            .or_else(|| filename.strip_suffix(".meta.toml"))
         {
             return stem;
         }
@@ -2676,7 +3097,7 @@ impl ApiService {
         Ok(())
     }
 
-    /// Write init.meta.json5 for script directories if they have non-Source properties
+    /// Write the init meta file for script directories if they have non-Source properties
     /// This matches the behavior of the dedicated syncback system.
     fn write_script_meta_json_if_needed(
         &self,
@@ -2684,8 +3105,6 @@ impl ApiService {
         added: &crate::web::interface::AddedInstance,
         instance_name: Option<&str>,
     ) -> anyhow::Result<()> {
-        use anyhow::Context;
-
         // Check if there are any properties besides Source that need to be saved
         // Use IndexMap for consistent ordering (like dedicated syncback)
         let (properties, attributes) =
@@ -2696,20 +3115,16 @@ impl ApiService {
         }
 
         let meta = self.build_meta_object(None, instance_name, properties, attributes);
-        let meta_path = dir_path.join("init.meta.json5");
-        let content = crate::json::to_vec_pretty_sorted(&meta)
-            .context("Failed to serialize init.meta.json5")?;
-        fs::write(&meta_path, &content)
-            .with_context(|| format!("Failed to write meta file: {}", meta_path.display()))?;
+        let meta_path = self.write_meta_file(dir_path, "init", &meta, false)?;
         log::info!(
-            "Syncback: Created init.meta.json5 for script at {}",
+            "Syncback: Created init meta file for script at {}",
             meta_path.display()
         );
 
         Ok(())
     }
 
-    /// Write init.meta.json5 for directory-native classes (Folder, Configuration, etc.)
+    /// Write the init meta file for directory-native classes (Folder, Configuration, etc.)
     /// Includes all properties (Attributes, Tags, etc.)
     /// This matches the behavior of the dedicated syncback system.
     fn write_directory_meta_json(
@@ -2718,8 +3133,6 @@ impl ApiService {
         added: &crate::web::interface::AddedInstance,
         instance_name: Option<&str>,
     ) -> anyhow::Result<()> {
-        use anyhow::Context;
-
         let (properties, attributes) =
             self.filter_properties_for_meta(&added.class_name, &added.properties, None);
 
@@ -2728,20 +3141,16 @@ impl ApiService {
         }
 
         let meta = self.build_meta_object(None, instance_name, properties, attributes);
-        let meta_path = dir_path.join("init.meta.json5");
-        let content = crate::json::to_vec_pretty_sorted(&meta)
-            .context("Failed to serialize init.meta.json5")?;
-        fs::write(&meta_path, &content)
-            .with_context(|| format!("Failed to write meta file: {}", meta_path.display()))?;
+        let meta_path = self.write_meta_file(dir_path, "init", &meta, false)?;
         log::info!(
-            "Syncback: Created init.meta.json5 at {}",
+            "Syncback: Created init meta file at {}",
             meta_path.display()
         );
 
         Ok(())
     }
 
-    /// Write an init.meta.json5 file for non-standard instances that have children
+    /// Write an init meta file for non-standard instances that have children
     /// This matches the behavior of the dedicated syncback system.
     fn write_init_meta_json(
         &self,
@@ -2750,8 +3159,6 @@ impl ApiService {
         instance_name: Option<&str>,
         suppress_vfs: bool,
     ) -> anyhow::Result<()> {
-        use anyhow::Context;
-
         let (properties, attributes) =
             self.filter_properties_for_meta(&added.class_name, &added.properties, None);
 
@@ -2762,16 +3169,9 @@ impl ApiService {
             properties,
             attributes,
         );
-        let meta_path = dir_path.join("init.meta.json5");
-        let content = crate::json::to_vec_pretty_sorted(&meta)
-            .context("Failed to serialize init.meta.json5")?;
-        if suppress_vfs {
-            self.suppress_path(&meta_path);
-        }
-        fs::write(&meta_path, &content)
-            .with_context(|| format!("Failed to write meta file: {}", meta_path.display()))?;
+        let meta_path = self.write_meta_file(dir_path, "init", &meta, suppress_vfs)?;
         log::info!(
-            "Syncback: Created init.meta.json5 for {} at {}",
+            "Syncback: Created init meta file for {} at {}",
             added.class_name,
             meta_path.display()
         );
@@ -2899,6 +3299,99 @@ impl ApiService {
         serde_json::Value::Object(obj)
     }
 
+    /// Serializes a meta or model file the way dedicated syncback does,
+    /// rounding floats to the project's `numberPrecision`.
+    fn serialize_json5<T: serde::Serialize>(&self, value: &T) -> anyhow::Result<Vec<u8>> {
+        crate::json::to_vec_pretty_sorted_rounded(
+            value,
+            self.serve_session.root_project().number_precision(),
+        )
+    }
+
+    /// Returns the format meta files are written in, following the project's
+    /// `metaFormat` syncback rule the way dedicated syncback does.
+    fn meta_format(&self) -> MetaFormat {
+        MetaFormat::for_rules(self.serve_session.root_project().syncback_rules.as_ref())
+    }
+
+    /// Returns the path of the meta file for `base` in `dir` in the project's
+    /// meta format, e.g. `dir/init.meta.toml` for `init`.
+    fn meta_path(&self, dir: &Path, base: &str) -> PathBuf {
+        dir.join(format!("{base}.{}", self.meta_format().extension()))
+    }
+
+    /// Returns the meta file for `base` in `dir` that properties should be
+    /// merged from: the one in the project's meta format if it exists,
+    /// otherwise one left over in another format.
+    fn existing_meta_path(&self, dir: &Path, base: &str) -> PathBuf {
+        let meta_path = self.meta_path(dir, base);
+        if meta_path.exists() {
+            return meta_path;
+        }
+        MetaFormat::ALL
+            .iter()
+            .map(|format| dir.join(format!("{base}.{}", format.extension())))
+            .find(|path| path.exists())
+            .unwrap_or(meta_path)
+    }
+
+    /// Writes `meta` as the meta file for `base` in `dir` in the project's
+    /// meta format, then removes the meta file for `base` in any other
+    /// format. Readers prefer `.meta.json5`, so a leftover one would shadow
+    /// a `.meta.toml` file.
+    ///
+    /// Returns the path that was written.
+    fn write_meta_file<T: serde::Serialize>(
+        &self,
+        dir: &Path,
+        base: &str,
+        meta: &T,
+        suppress_vfs: bool,
+    ) -> anyhow::Result<PathBuf> {
+        use anyhow::Context;
+
+        let format = self.meta_format();
+        let meta_path = self.meta_path(dir, base);
+        let content = format
+            .serialize(meta, self.serve_session.root_project().number_precision())
+            .with_context(|| format!("Failed to serialize {}", meta_path.display()))?;
+        if suppress_vfs {
+            self.suppress_path(&meta_path);
+        }
+        fs::write(&meta_path, &content)
+            .with_context(|| format!("Failed to write meta file: {}", meta_path.display()))?;
+
+        for other in MetaFormat::ALL.into_iter().filter(|other| *other != format) {
+            self.remove_meta_file(&dir.join(format!("{base}.{}", other.extension())));
+        }
+
+        Ok(meta_path)
+    }
+
+    /// Removes the meta file for `base` in `dir` in every meta format.
+    fn remove_meta_files(&self, dir: &Path, base: &str) {
+        for format in MetaFormat::ALL {
+            self.remove_meta_file(&dir.join(format!("{base}.{}", format.extension())));
+        }
+    }
+
+    /// Removes the meta file at `meta_path` if it exists, logging a warning
+    /// if it can't be removed.
+    fn remove_meta_file(&self, meta_path: &Path) {
+        if !meta_path.exists() {
+            return;
+        }
+        self.suppress_path_remove(meta_path);
+        match fs::remove_file(meta_path) {
+            Ok(()) => log::info!("Syncback: Removed meta file at {}", meta_path.display()),
+            Err(err) => log::warn!(
+                "Failed to remove meta file {}: {}",
+                meta_path.display(),
+                err
+            ),
+        }
+    }
+
     /// Extract the Source property from an added instance, defaulting to empty string.
     fn get_source_property(&self, added: &crate::web::interface::AddedInstance) -> String {
         added
@@ -2955,8 +3448,8 @@ impl ApiService {
 
     /// Write an adjacent meta file for scripts without children.
     ///
-    /// Creates `{name}.meta.json5` next to the script file if there are
-    /// non-Source properties that need to be preserved.
+    /// Creates `{name}.meta.json5` (or `.meta.toml`) next to the script file
+    /// if there are non-Source properties that need to be preserved.
     ///
     /// This matches the dedicated syncback behavior in lua.rs::syncback_lua.
     fn write_adjacent_script_meta_if_needed(
@@ -2966,8 +3459,6 @@ impl ApiService {
         added: &crate::web::interface::AddedInstance,
         instance_name: Option<&str>,
     ) -> anyhow::Result<()> {
-        use anyhow::Context;
-
         // Filter properties, skipping Source (it's in the .luau file)
         let (properties, attributes) =
             self.filter_properties_for_meta(&added.class_name, &added.properties, Some("Source"));
@@ -2978,11 +3469,7 @@ impl ApiService {
 
         // Build meta object (no className needed for scripts - it's determined by file extension)
         let meta = self.build_meta_object(None, instance_name, properties, attributes);
-        let meta_path = parent_dir.join(format!("{}.meta.json5", name));
-        let content =
-            crate::json::to_vec_pretty_sorted(&meta).context("Failed to serialize meta.json5")?;
-        fs::write(&meta_path, &content)
-            .with_context(|| format!("Failed to write meta file: {}", meta_path.display()))?;
+        let meta_path = self.write_meta_file(parent_dir, name, &meta, false)?;
         log::info!(
             "Syncback: Created adjacent meta file at {}",
             meta_path.display()
@@ -3022,7 +3509,8 @@ impl ApiService {
         }
 
         // Use sorted JSON5 serialization to match dedicated syncback
-        crate::json::to_vec_pretty_sorted(&model).context("Failed to serialize model.json5")
+        self.serialize_json5(&model)
+            .context("Failed to serialize model.json5")
     }
 
     /// Persist non-Source property changes to the appropriate meta/model file.
@@ -3163,22 +3651,16 @@ impl ApiService {
 
         // Determine which meta file to write based on file structure
         if inst_path.is_dir() {
-            // Directory format: write to init.meta.json5
-            let meta_path = inst_path.join("init.meta.json5");
-
+            // Directory format: write to the init meta file
             // Read existing meta if present, merge with new properties
             let meta = self.merge_or_build_meta(
-                &meta_path,
+                &self.existing_meta_path(inst_path, "init"),
                 None,
                 properties,
                 attributes,
                 &remove_attributes,
             )?;
-            let content = crate::json::to_vec_pretty_sorted(&meta)
-                .context("Failed to serialize init.meta.json5")?;
-            self.suppress_path(&meta_path);
-            fs::write(&meta_path, &content)
-                .with_context(|| format!("Failed to write {}", meta_path.display()))?;
+            let meta_path = self.write_meta_file(inst_path, "init", &meta, true)?;
 
             log::info!(
                 "Syncback: Persisted non-Source properties to {}",
@@ -3186,7 +3668,7 @@ impl ApiService {
             );
             written_meta_path = meta_path;
         } else if is_script {
-            // Standalone script: write to adjacent Name.meta.json5
+            // Standalone script: write to adjacent Name.meta.json5 (or .meta.toml)
             // Derive the base name from the filesystem path (not instance.name())
             // to preserve Windows-invalid character encoding (e.g., %3F for ?).
             // This matches AdjacentMetadata::read_and_apply_all which uses file_stem().
@@ -3199,20 +3681,15 @@ impl ApiService {
                 .or_else(|| file_stem.strip_suffix(".local"))
                 .or_else(|| file_stem.strip_suffix(".legacy"))
                 .unwrap_or(file_stem);
-            let meta_path = parent_dir.join(format!("{}.meta.json5", base_name));
 
             let meta = self.merge_or_build_meta(
-                &meta_path,
+                &self.existing_meta_path(parent_dir, base_name),
                 None,
                 properties,
                 attributes,
                 &remove_attributes,
             )?;
-            let content = crate::json::to_vec_pretty_sorted(&meta)
-                .context("Failed to serialize meta.json5")?;
-            self.suppress_path(&meta_path);
-            fs::write(&meta_path, &content)
-                .with_context(|| format!("Failed to write {}", meta_path.display()))?;
+            let meta_path = self.write_meta_file(parent_dir, base_name, &meta, true)?;
 
             log::info!(
                 "Syncback: Persisted non-Source properties to {}",
@@ -3222,7 +3699,7 @@ impl ApiService {
         } else {
             // Non-script standalone file. Only .model.json5/.model.json support
             // in-place JSON property updates. Other file types (.txt, .csv, .toml,
-            // .yaml, etc.) require an adjacent .meta.json5 file — writing JSON
+            // .yaml, etc.) require an adjacent meta file — writing JSON
             // directly to them would corrupt their content.
             let file_name = inst_path.file_name().and_then(|f| f.to_str()).unwrap_or("");
             let is_model_file =
@@ -3236,7 +3713,8 @@ impl ApiService {
                     attributes,
                     &remove_attributes,
                 )?;
-                let content = crate::json::to_vec_pretty_sorted(&meta)
+                let content = self
+                    .serialize_json5(&meta)
                     .context("Failed to serialize model file")?;
                 self.suppress_path(inst_path);
                 fs::write(inst_path, &content)
@@ -3251,20 +3729,15 @@ impl ApiService {
                 // For .txt, .csv, .toml, .yaml, etc. — use adjacent meta file
                 let parent_dir = inst_path.parent().context("No parent directory")?;
                 let file_stem = inst_path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-                let meta_path = parent_dir.join(format!("{}.meta.json5", file_stem));
 
                 let meta = self.merge_or_build_meta(
-                    &meta_path,
+                    &self.existing_meta_path(parent_dir, file_stem),
                     None,
                     properties,
                     attributes,
                     &remove_attributes,
                 )?;
-                let content = crate::json::to_vec_pretty_sorted(&meta)
-                    .context("Failed to serialize meta.json5")?;
-                self.suppress_path(&meta_path);
-                fs::write(&meta_path, &content)
-                    .with_context(|| format!("Failed to write {}", meta_path.display()))?;
+                let meta_path = self.write_meta_file(parent_dir, file_stem, &meta, true)?;
 
                 log::info!(
                     "Syncback: Persisted non-Source properties to {}",
@@ -3325,9 +3798,14 @@ impl ApiService {
             // Read and parse existing file
             let content = fs::read(existing_path)
                 .with_context(|| format!("Failed to read {}", existing_path.display()))?;
-            let mut existing: serde_json::Value =
-                json5::from_str(&String::from_utf8_lossy(&content))
-                    .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new()));
+            let content = String::from_utf8_lossy(&content);
+            let parsed = if existing_path.extension().is_some_and(|ext| ext == "toml") {
+                toml::from_str::<serde_json::Value>(&content).ok()
+            } else {
+                json5::from_str::<serde_json::Value>(&content).ok()
+            };
+            let mut existing =
+                parsed.unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new()));
 
             // Ensure the existing value is an object
             if !existing.is_object() {
@@ -3395,73 +3873,65 @@ impl ApiService {
         let message_cursor = message_queue.cursor();
 
         let tree = self.serve_session.tree();
-        let scripts_only = self.serve_session.sync_scripts_only();
-
-        let mut instances = HashMap::new();
-
-        if scripts_only {
-            // Use the script index to avoid walking the entire tree.
-            // For each script, walk UP to see if it's a descendant of a
-            // requested ID, collecting the ancestor chain along the way.
-            let mut included_ids: HashSet<Ref> = HashSet::new();
-            let requested_set: HashSet<Ref> = requested_ids.iter().copied().collect();
-
-            for &script_id in tree.script_refs() {
-                let mut chain = Vec::new();
-                let mut current = script_id;
-                let mut is_descendant = false;
-
-                while let Some(inst) = tree.get_instance(current) {
-                    if included_ids.contains(&current) {
-                        is_descendant = true;
-                        break;
-                    }
-                    if requested_set.contains(&current) {
-                        is_descendant = true;
-                        chain.push(current);
-                        break;
-                    }
-                    chain.push(current);
-                    let parent = inst.parent();
-                    if parent.is_none() {
-                        break;
-                    }
-                    current = parent;
-                }
+        let instances = read_subtrees(
+            &tree,
+            &requested_ids,
+            self.serve_session.sync_scripts_only(),
+        );
 
-                if is_descendant {
-                    included_ids.extend(chain);
-                }
-            }
+        msgpack_ok(ReadResponse {
+            session_id: self.serve_session.session_id(),
+            message_cursor,
+            instances,
+        })
+    }
 
-            for &id in &requested_ids {
-                if tree.get_instance(id).is_some() {
-                    included_ids.insert(id);
-                }
+    /// Reads several subtrees in one request. Each requested ID maps to the
+    /// same set of Instances `/api/read/{id}` would return for it, all taken
+    /// at a single message cursor.
+    async fn handle_api_read_batch(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        let body = match request.into_body().collect().await {
+            Ok(collected) => collected.to_bytes(),
+            Err(err) => {
+                return msgpack(
+                    ErrorResponse::bad_request(format!("Failed to read request body: {err}")),
+                    StatusCode::BAD_REQUEST,
+                );
             }
+        };
 
-            for &id in &included_ids {
-                if let Some(instance) = tree.get_instance(id) {
-                    instances.insert(id, instance_for_scripts_only(instance, &included_ids));
-                }
+        let request: ReadBatchRequest = match deserialize_msgpack(&body) {
+            Ok(request) => request,
+            Err(err) => {
+                return msgpack(
+                    ErrorResponse::bad_request(format!("Invalid body: {}", err)),
+                    StatusCode::BAD_REQUEST,
+                );
             }
-        } else {
-            // Normal mode: include all instances
-            for id in requested_ids {
-                if let Some(instance) = tree.get_instance(id) {
-                    instances.insert(id, Instance::from_rojo_instance(instance));
+        };
 
-                    for descendant in tree.descendants(id) {
-                        instances.insert(descendant.id(), Instance::from_rojo_instance(descendant));
-                    }
-                }
-            }
+        if request.session_id != self.serve_session.session_id() {
+            return msgpack(
+                ErrorResponse::bad_request("Wrong session ID"),
+                StatusCode::BAD_REQUEST,
+            );
         }
 
-        msgpack_ok(ReadResponse {
+        let message_cursor = self.serve_session.message_queue().cursor();
+
+        let tree = self.serve_session.tree();
+        let scripts_only = self.serve_session.sync_scripts_only();
+        let subtrees = request
+            .ids
+            .iter()
+            .filter(|id| tree.get_instance(**id).is_some())
+            .map(|&id| (id, read_subtrees(&tree, &[id], scripts_only)))
+            .collect();
+
+        msgpack_ok(ReadBatchResponse {
             session_id: self.serve_session.session_id(),
             message_cursor,
-            instances,
+            subtrees,
         })
     }
 
@@ -3856,13 +4326,13 @@ async fn handle_mcp_stream_upgrade(
     request: &mut Request<Incoming>,
     serve_session: Arc<ServeSession>,
     mcp_state: Arc<super::mcp::McpState>,
-    active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+    connections: Arc<Connections>,
 ) -> Response<Full<Bytes>> {
     use std::sync::atomic::Ordering;
 
     if mcp_state.plugin_stream_connected.load(Ordering::Relaxed) {
         return msgpack(
-            ErrorResponse::bad_request("Another plugin is already connected to the MCP stream"),
+            ErrorResponse::conflict("Another plugin is already connected to the MCP stream"),
             StatusCode::CONFLICT,
         );
     }
@@ -3878,13 +4348,8 @@ async fn handle_mcp_stream_upgrade(
     };
 
     tokio::spawn(async move {
-        if let Err(e) = handle_mcp_stream_connection(
-            websocket,
-            serve_session,
-            mcp_state,
-            active_api_connections,
-        )
-        .await
+        if let Err(e) =
+            handle_mcp_stream_connection(websocket, serve_session, mcp_state, connections).await
         {
             log::error!("MCP stream WebSocket error: {e}");
         }
@@ -3897,7 +4362,7 @@ async fn handle_mcp_stream_connection(
     websocket: HyperWebsocket,
     serve_session: Arc<ServeSession>,
     mcp_state: Arc<super::mcp::McpState>,
-    active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+    connections: Arc<Connections>,
 ) -> anyhow::Result<()> {
     use super::mcp::PluginConfig;
     use std::sync::atomic::Ordering;
@@ -4009,7 +4474,7 @@ async fn handle_mcp_stream_connection(
 
                 // For sync commands, block if a live API connection is active.
                 // getScript is a read-only operation that can proceed regardless.
-                if cmd_type == "sync" && active_api_connections.load(Ordering::Relaxed) > 0 {
+                if cmd_type == "sync" && connections.live_sync() > 0 {
                     let result = serde_json::json!({
                         "requestId": cmd_value.get("requestId").and_then(|v| v.as_str()).unwrap_or(""),
                         "status": "already_connected",
@@ -4369,6 +4834,78 @@ fn filter_subscribe_message_for_scripts<'a>(
     // tree (they're already gone), and the plugin ignores unknown IDs.
 }
 
+/// Collects the Instances `/api/read` returns for `requested_ids`: each
+/// requested Instance and its descendants, or in scripts-only mode just the
+/// scripts below them and the ancestors needed to reach them.
+fn read_subtrees<'a>(
+    tree: &'a RojoTree,
+    requested_ids: &[Ref],
+    scripts_only: bool,
+) -> HashMap<Ref, Instance<'a>> {
+    let mut instances = HashMap::new();
+
+    if scripts_only {
+        // Use the script index to avoid walking the entire tree.
+        // For each script, walk UP to see if it's a descendant of a
+        // requested ID, collecting the ancestor chain along the way.
+        let mut included_ids: HashSet<Ref> = HashSet::new();
+        let requested_set: HashSet<Ref> = requested_ids.iter().copied().collect();
+
+        for &script_id in tree.script_refs() {
+            let mut chain = Vec::new();
+            let mut current = script_id;
+            let mut is_descendant = false;
+
+            while let Some(inst) = tree.get_instance(current) {
+                if included_ids.contains(&current) {
+                    is_descendant = true;
+                    break;
+                }
+                if requested_set.contains(&current) {
+                    is_descendant = true;
+                    chain.push(current);
+                    break;
+                }
+                chain.push(current);
+                let parent = inst.parent();
+                if parent.is_none() {
+                    break;
+                }
+                current = parent;
+            }
+
+            if is_descendant {
+                included_ids.extend(chain);
+            }
+        }
+
+        for &id in requested_ids {
+            if tree.get_instance(id).is_some() {
+                included_ids.insert(id);
+            }
+        }
+
+        for &id in &included_ids {
+            if let Some(instance) = tree.get_instance(id) {
+                instances.insert(id, instance_for_scripts_only(instance, &included_ids));
+            }
+        }
+    } else {
+        // Normal mode: include all instances
+        for &id in requested_ids {
+            if let Some(instance) = tree.get_instance(id) {
+                instances.insert(id, Instance::from_rojo_instance(instance));
+
+                for descendant in tree.descendants(id) {
+                    instances.insert(descendant.id(), Instance::from_rojo_instance(descendant));
+                }
+            }
+        }
+    }
+
+    instances
+}
+
 /// Creates an Instance for scripts-only mode.
 /// - Scripts get their properties synced normally
 /// - Non-scripts only provide tree structure (no properties synced)
@@ -4422,6 +4959,16 @@ fn instance_for_scripts_only<'a>(
     }
 }
 
+/// Adds the placeholder ID of every descendant of `added` that was given one.
+fn collect_child_ids(added: &crate::web::interface::AddedInstance, ids: &mut HashSet<Ref>) {
+    for child in &added.children {
+        if let Some(id) = child.id {
+            ids.insert(id);
+        }
+        collect_child_ids(child, ids);
+    }
+}
+
 /// Compute the tentative filesystem name segment for an `AddedInstance`.
 ///
 /// Mirrors the logic of `tentative_fs_name` in `ref_properties.rs` but
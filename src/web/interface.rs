@@ -5,6 +5,7 @@
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
+    path::PathBuf,
 };
 
 use rbx_dom_weak::{
@@ -15,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use strum::Display;
 
 use crate::{
+    serve_session::{Divergence, InitialSyncMode},
     session_id::SessionId,
     snapshot::{
         AppliedPatchSet, InstanceMetadata as RojoInstanceMetadata, InstanceWithMeta, RojoTree,
@@ -67,6 +69,10 @@ pub struct SyncbackRequest {
     #[serde(with = "serde_bytes", default)]
     pub data: Vec<u8>,
     pub services: Vec<ServiceChunk>,
+    /// Sync back while the server keeps running and answer with a summary,
+    /// instead of stopping the server to sync back and restarting it.
+    #[serde(default)]
+    pub keep_running: bool,
 }
 
 /// Payload passed from the API handler to the serve loop after validation.
@@ -79,6 +85,8 @@ pub struct SyncbackPayload {
 /// Why the live server exited its accept loop.
 pub enum ServerExitReason {
     SyncbackRequested(SyncbackPayload),
+    /// `POST /api/shutdown` or SIGTERM asked the server to stop.
+    Shutdown,
 }
 
 /// Message returned by Rojo API when a change has occurred.
@@ -278,8 +286,39 @@ pub struct ServerInfoResponse {
     /// Services not in this list should be ignored during sync operations.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub visible_services: Vec<String>,
+    /// Whether the plugin should make Studio match the project when it
+    /// connects (`push`) or reconcile the two (`merge`).
+    #[serde(default)]
+    pub initial_sync_mode: InitialSyncMode,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub git_metadata: Option<GitMetadata>,
+    /// The bearer token the server was started with, if any. Only ever sent
+    /// to callers that already presented it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+}
+
+/// Response body from /api/health
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthResponse {
+    pub session_id: SessionId,
+    /// Whether the tree can be read and file changes are being applied to
+    /// it.
+    pub ready: bool,
+    /// Seconds since the serve session started.
+    pub uptime_secs: f64,
+    pub message_cursor: u32,
+}
+
+/// Response body from /api/verify
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResponse {
+    pub session_id: SessionId,
+    /// Every Instance that differs between the in-memory tree and the
+    /// project on disk. Empty when the two agree.
+    pub divergences: Vec<Divergence>,
 }
 
 // Serialize place IDs as f64 to avoid msgpack uint64 encoding issues with Lua
@@ -335,6 +374,32 @@ pub struct ReadResponse<'a> {
     pub instances: HashMap<Ref, Instance<'a>>,
 }
 
+/// Request body for /api/read-batch
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadBatchRequest {
+    pub session_id: SessionId,
+    pub ids: Vec<Ref>,
+}
+
+/// Request body for /api/shutdown
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownRequest {
+    pub session_id: SessionId,
+}
+
+/// Response body from /api/read-batch
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadBatchResponse<'a> {
+    pub session_id: SessionId,
+    pub message_cursor: u32,
+    /// Each requested ID that exists, mapped to the Instances `/api/read/{id}`
+    /// would return for it. IDs that don't exist are left out.
+    pub subtrees: HashMap<Ref, HashMap<Ref, Instance<'a>>>,
+}
+
 /// Instance data for creating new instances via the write API
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -354,6 +419,17 @@ pub struct AddedInstance {
     /// Children instances (recursively encoded for hierarchical syncback)
     #[serde(default)]
     pub children: Vec<AddedInstance>,
+    /// The plugin's placeholder ID for a nested child, so the response can
+    /// say which tree ID it was given. Top-level instances are identified
+    /// by their key in `WriteRequest::added` instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Ref>,
+    /// Directory to create the instance's file in, relative to the project
+    /// folder, instead of the parent instance's directory. It must be inside
+    /// one of the project's `$path` roots and must not already hold a file
+    /// for this instance. Only honored for top-level added instances.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -368,12 +444,65 @@ pub struct WriteRequest {
     /// Instance IDs whose backing files should be staged via git add.
     #[serde(default)]
     pub stage_ids: Vec<Ref>,
+    /// Only check the request and report its problems in
+    /// `WriteResponse::problems`, without touching the tree or the disk.
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WriteResponse {
     pub session_id: SessionId,
+    /// Problems found in the request. A `dry_run` request only reports them;
+    /// otherwise the entries they block were skipped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<WriteProblem>,
+    /// Maps the placeholder ID of each added instance, including nested
+    /// children that were given an `id`, to the ID it has in the tree.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub added_ids: HashMap<Ref, Ref>,
+}
+
+/// Something in a `WriteRequest` that would be skipped or fail if the
+/// request were applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteProblem {
+    /// The tree ID of an updated or removed instance, or the plugin's ID of
+    /// an added one.
+    pub id: Ref,
+    pub kind: WriteProblemKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WriteProblemKind {
+    /// The instance isn't in the tree.
+    UnknownInstance,
+    /// An added instance's parent is missing from both the tree and the
+    /// request.
+    InvalidParent,
+    /// A class name isn't in the reflection database.
+    UnknownClass,
+    /// The instance is defined in a project file, so it can't be removed.
+    ProjectNode,
+    /// A rename would give the instance the same name as a sibling.
+    NameConflict,
+}
+
+impl WriteProblemKind {
+    /// Whether a real write skips the entry with this problem. Unknown
+    /// classes and name conflicts are still written, since the reflection
+    /// database can lag behind Studio and colliding names get deduplicated
+    /// file names.
+    pub fn blocks_write(self) -> bool {
+        matches!(
+            self,
+            Self::UnknownInstance | Self::InvalidParent | Self::ProjectNode
+        )
+    }
 }
 
 /// Packet type enum for different websocket message types
@@ -479,6 +608,27 @@ impl ErrorResponse {
             details: details.into(),
         }
     }
+
+    pub fn unauthorized<S: Into<String>>(details: S) -> Self {
+        Self {
+            kind: ErrorResponseKind::Unauthorized,
+            details: details.into(),
+        }
+    }
+
+    pub fn too_many_requests<S: Into<String>>(details: S) -> Self {
+        Self {
+            kind: ErrorResponseKind::TooManyRequests,
+            details: details.into(),
+        }
+    }
+
+    pub fn conflict<S: Into<String>>(details: S) -> Self {
+        Self {
+            kind: ErrorResponseKind::Conflict,
+            details: details.into(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -486,4 +636,7 @@ pub enum ErrorResponseKind {
     NotFound,
     BadRequest,
     InternalError,
+    Unauthorized,
+    TooManyRequests,
+    Conflict,
 }
@@ -1,16 +1,28 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 use bytes::Bytes;
 use http_body_util::{BodyExt, Full};
 use hyper::{body::Incoming, header::CONTENT_TYPE, Method, Request, Response, StatusCode};
+use rbx_dom_weak::{types::Variant, ustr};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::interface::SERVER_VERSION;
+use crate::{
+    path_serializer::display_absolute,
+    resolution::UnresolvedValue,
+    serve_session::ServeSession,
+    snapshot::{InstigatingSource, RojoTree},
+    variant_eq::variant_eq,
+};
+
+use super::{interface::SERVER_VERSION, util::is_authorized, Connections};
 
 /// Plugin config received via the MCP stream WebSocket greeting.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -152,6 +164,33 @@ struct InsertModelArgs {
     query: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct SearchInstancesArgs {
+    /// Only match instances of this class or one of its subclasses
+    /// (e.g. 'BasePart').
+    class_name: Option<String>,
+    /// Only match instances that have this property, either set on them or
+    /// through their class's default value.
+    property: Option<String>,
+    /// Only match instances whose `property` equals this value, written
+    /// the same way as in a .model.json5 file.
+    value: Option<Value>,
+    /// The most results to return. Defaults to 100.
+    limit: Option<usize>,
+}
+
+/// A single match returned by search_instances.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchInstancesMatch {
+    pub id: String,
+    pub class_name: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_path: Option<String>,
+}
+
 #[derive(JsonSchema)]
 struct NoArgs {}
 
@@ -317,9 +356,20 @@ fn json_response(body: &JsonRpcResponse, status: StatusCode) -> Response<Full<By
 
 pub async fn call(
     request: Request<Incoming>,
+    serve_session: Arc<ServeSession>,
     mcp_state: Arc<McpState>,
-    active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+    connections: Arc<Connections>,
+    auth_token: Option<&str>,
 ) -> Response<Full<Bytes>> {
+    if !is_authorized(&request, auth_token) {
+        let resp = JsonRpcResponse::error(
+            None,
+            -32001,
+            "A valid `Authorization: Bearer` token is required",
+        );
+        return json_response(&resp, StatusCode::UNAUTHORIZED);
+    }
+
     if request.method() != Method::POST {
         let resp = JsonRpcResponse::error(None, -32600, "MCP endpoint only accepts POST requests");
         return json_response(&resp, StatusCode::METHOD_NOT_ALLOWED);
@@ -356,8 +406,9 @@ pub async fn call(
             handle_tools_call(
                 rpc_request.id,
                 rpc_request.params,
+                serve_session,
                 mcp_state,
-                active_api_connections,
+                connections,
             )
             .await
         }
@@ -435,6 +486,10 @@ fn handle_tools_list(id: Option<Value>) -> Response<Full<Bytes>> {
             include_str!("mcp_docs/run_script_in_play_mode.md"),
         ),
         tool_def::<SyncbackArgs>("syncback", include_str!("mcp_docs/syncback.md")),
+        tool_def::<SearchInstancesArgs>(
+            "search_instances",
+            include_str!("mcp_docs/search_instances.md"),
+        ),
     ];
 
     let result = serde_json::json!({ "tools": tools });
@@ -445,8 +500,9 @@ fn handle_tools_list(id: Option<Value>) -> Response<Full<Bytes>> {
 async fn handle_tools_call(
     id: Option<Value>,
     params: Option<Value>,
+    serve_session: Arc<ServeSession>,
     mcp_state: Arc<McpState>,
-    active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+    connections: Arc<Connections>,
 ) -> Response<Full<Bytes>> {
     let tool_name = params
         .as_ref()
@@ -467,9 +523,10 @@ async fn handle_tools_call(
     );
 
     match tool_name {
-        "atlas_sync" => handle_atlas_sync(id, arguments, mcp_state, active_api_connections).await,
+        "atlas_sync" => handle_atlas_sync(id, arguments, mcp_state, connections).await,
         "get_script" => handle_get_script(id, arguments, mcp_state).await,
         "syncback" => dispatch_to_plugin(id, "syncback", arguments, mcp_state).await,
+        "search_instances" => handle_search_instances(id, arguments, &serve_session),
         "run_code"
         | "insert_model"
         | "get_console_output"
@@ -489,7 +546,7 @@ async fn handle_atlas_sync(
     id: Option<Value>,
     arguments: Value,
     mcp_state: Arc<McpState>,
-    active_api_connections: Arc<std::sync::atomic::AtomicUsize>,
+    connections: Arc<Connections>,
 ) -> Response<Full<Bytes>> {
     let mode = arguments
         .get("mode")
@@ -502,7 +559,7 @@ async fn handle_atlas_sync(
         .and_then(|v| serde_json::from_value(v.clone()).ok())
         .unwrap_or_default();
 
-    if active_api_connections.load(Ordering::Relaxed) > 0 {
+    if connections.live_sync() > 0 {
         let config_info = {
             let config = mcp_state
                 .plugin_config
@@ -787,6 +844,145 @@ async fn handle_get_script(
     tool_response(id, false, &text)
 }
 
+fn handle_search_instances(
+    id: Option<Value>,
+    arguments: Value,
+    serve_session: &ServeSession,
+) -> Response<Full<Bytes>> {
+    let args: SearchInstancesArgs = match serde_json::from_value(arguments) {
+        Ok(args) => args,
+        Err(err) => return tool_response(id, true, &format!("Invalid arguments: {err}")),
+    };
+
+    let tree = serve_session.tree();
+    let matches = match search_instances(&tree, serve_session.root_dir(), &args) {
+        Ok(matches) => matches,
+        Err(message) => return tool_response(id, true, &message),
+    };
+
+    let mut text = format!("Found {} matching instance(s).", matches.len());
+    text.push_str(&format!(
+        "\n\n<json>\n{}\n</json>",
+        serde_json::to_string(&matches).unwrap_or_default()
+    ));
+
+    tool_response(id, false, &text)
+}
+
+/// Walks `tree` for instances matching `args`. `value` is resolved against
+/// each matching class's reflection data, so ambiguous values like `false`
+/// or an enum item's name work the same way they do in project files. An
+/// instance that doesn't set `property` is compared using its class's
+/// default value for it.
+fn search_instances(
+    tree: &RojoTree,
+    root_dir: &std::path::Path,
+    args: &SearchInstancesArgs,
+) -> Result<Vec<SearchInstancesMatch>, String> {
+    if args.class_name.is_none() && args.property.is_none() {
+        return Err("At least one of 'className' or 'property' must be provided.".to_owned());
+    }
+    if args.value.is_some() && args.property.is_none() {
+        return Err("'value' requires 'property'.".to_owned());
+    }
+
+    let unresolved = match &args.value {
+        Some(value) => Some(
+            serde_json::from_value::<UnresolvedValue>(value.clone())
+                .map_err(|err| format!("Invalid value: {err}"))?,
+        ),
+        None => None,
+    };
+
+    let limit = args.limit.unwrap_or(100);
+    let mut resolved_by_class = HashMap::new();
+    let mut matches = Vec::new();
+
+    for instance in tree.descendants(tree.get_root_id()) {
+        if matches.len() >= limit {
+            break;
+        }
+
+        let class_name = instance.class_name();
+        if let Some(wanted) = &args.class_name {
+            if !is_a(class_name.as_str(), wanted) {
+                continue;
+            }
+        }
+
+        if let Some(property) = &args.property {
+            let actual = instance
+                .properties()
+                .get(&ustr(property))
+                .or_else(|| class_default(class_name.as_str(), property));
+            let Some(actual) = actual else {
+                continue;
+            };
+            if let Some(unresolved) = &unresolved {
+                let expected = resolved_by_class
+                    .entry(class_name)
+                    .or_insert_with(|| unresolved.clone().resolve(&class_name, property).ok());
+                if !expected
+                    .as_ref()
+                    .is_some_and(|expected| variant_eq(actual, expected))
+                {
+                    continue;
+                }
+            }
+        }
+
+        let fs_path = match &instance.metadata().instigating_source {
+            Some(InstigatingSource::Path(path)) => Some(display_absolute(
+                path.strip_prefix(root_dir).unwrap_or(path),
+            )),
+            _ => None,
+        };
+
+        matches.push(SearchInstancesMatch {
+            id: instance.id().to_string(),
+            class_name: class_name.to_string(),
+            path: crate::ref_target_path(tree.inner(), instance.id()),
+            fs_path,
+        });
+    }
+
+    Ok(matches)
+}
+
+/// Returns the reflection database's default value of `property` for
+/// `class_name`, looking through its superclasses.
+fn class_default(class_name: &str, property: &str) -> Option<&'static Variant> {
+    let database = rbx_reflection_database::get().unwrap();
+    let mut current = database.classes.get(class_name)?;
+
+    loop {
+        if let Some(default) = current.default_properties.get(property) {
+            return Some(default);
+        }
+        current = database.classes.get(current.superclass.as_ref()?)?;
+    }
+}
+
+/// Returns whether `class_name` is `wanted` or inherits from it.
+fn is_a(class_name: &str, wanted: &str) -> bool {
+    let database = rbx_reflection_database::get().unwrap();
+    let mut current = class_name;
+
+    loop {
+        if current == wanted {
+            return true;
+        }
+        match database
+            .classes
+            .get(current)
+            .and_then(|class| class.superclass.as_ref())
+        {
+            Some(superclass) => current = superclass,
+            None => return false,
+        }
+    }
+}
+
 async fn dispatch_to_plugin(
     id: Option<Value>,
     tool_name: &str,
@@ -889,7 +1085,6 @@ fn tool_response(id: Option<Value>, is_error: bool, text: &str) -> Response<Full
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::atomic::AtomicUsize;
 
     // -- JSON-RPC type tests --------------------------------------------------
 
@@ -1141,7 +1336,7 @@ mod tests {
             let bytes = rt.block_on(async { resp.into_body().collect().await.unwrap().to_bytes() });
             let json: Value = serde_json::from_slice(&bytes).unwrap();
             let tools = json["result"]["tools"].as_array().unwrap();
-            assert_eq!(tools.len(), 9);
+            assert_eq!(tools.len(), 10);
             let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
             assert_eq!(
                 names,
@@ -1155,6 +1350,7 @@ mod tests {
                     "start_stop_play",
                     "run_script_in_play_mode",
                     "syncback",
+                    "search_instances",
                 ]
             );
             for tool in tools {
@@ -1198,7 +1394,8 @@ mod tests {
         #[tokio::test]
         async fn rejects_when_api_connected() {
             let state = Arc::new(McpState::new());
-            let conns = Arc::new(AtomicUsize::new(1));
+            let conns = Arc::new(Connections::default());
+            let _live_sync = conns.enter_live_sync();
 
             let resp = handle_atlas_sync(Some(Value::from(1)), empty_args(), state, conns).await;
             let bytes = resp.into_body().collect().await.unwrap().to_bytes();
@@ -1218,7 +1415,8 @@ mod tests {
                 confirmation_behavior: "Always".to_string(),
                 place_id: None,
             });
-            let conns = Arc::new(AtomicUsize::new(1));
+            let conns = Arc::new(Connections::default());
+            let _live_sync = conns.enter_live_sync();
 
             let resp = handle_atlas_sync(Some(Value::from(1)), empty_args(), state, conns).await;
             let bytes = resp.into_body().collect().await.unwrap().to_bytes();
@@ -1233,7 +1431,7 @@ mod tests {
         async fn rejects_when_command_in_progress() {
             let state = Arc::new(McpState::new());
             state.command_in_progress.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let resp = handle_atlas_sync(Some(Value::from(2)), empty_args(), state, conns).await;
             let bytes = resp.into_body().collect().await.unwrap().to_bytes();
@@ -1248,7 +1446,7 @@ mod tests {
         async fn rejects_when_no_plugin_connected() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(false, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let resp =
                 handle_atlas_sync(Some(Value::from(3)), empty_args(), state.clone(), conns).await;
@@ -1266,7 +1464,7 @@ mod tests {
         async fn sends_command_and_returns_success_result() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1301,7 +1499,7 @@ mod tests {
         async fn returns_rejection_with_presented_changes() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1340,7 +1538,7 @@ mod tests {
         async fn handles_plugin_disconnect_during_sync() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1365,7 +1563,7 @@ mod tests {
         async fn empty_status_is_not_an_error() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1397,7 +1595,7 @@ mod tests {
         async fn dryrun_status_is_not_an_error() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1437,7 +1635,7 @@ mod tests {
         async fn fastfail_unresolved_is_error() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1474,7 +1672,7 @@ mod tests {
         async fn mode_and_overrides_forwarded_to_command() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1517,7 +1715,7 @@ mod tests {
         async fn mode_defaults_to_standard() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1549,7 +1747,7 @@ mod tests {
         async fn enriched_changes_include_json_block() {
             let state = Arc::new(McpState::new());
             state.plugin_stream_connected.store(true, Ordering::SeqCst);
-            let conns = Arc::new(AtomicUsize::new(0));
+            let conns = Arc::new(Connections::default());
 
             let state2 = Arc::clone(&state);
             tokio::spawn(async move {
@@ -1880,7 +2078,7 @@ mod tests {
             let bytes = rt.block_on(async { resp.into_body().collect().await.unwrap().to_bytes() });
             let json: Value = serde_json::from_slice(&bytes).unwrap();
             let tools = json["result"]["tools"].as_array().unwrap();
-            assert_eq!(tools.len(), 9);
+            assert_eq!(tools.len(), 10);
 
             let get_script = tools.iter().find(|t| t["name"] == "get_script").unwrap();
             assert!(get_script["description"]
@@ -2403,4 +2601,122 @@ mod tests {
             assert!(has_integer, "timeout should accept integer: {timeout}");
         }
     }
+
+    // -- search_instances ------------------------------------------------------
+
+    mod search_instances_tests {
+        use super::*;
+        use crate::snapshot::InstanceSnapshot;
+        use rbx_dom_weak::types::Variant;
+        use std::path::Path;
+
+        fn part(class_name: &str, name: &str, anchored: bool) -> InstanceSnapshot {
+            InstanceSnapshot::new()
+                .class_name(class_name)
+                .name(name)
+                .property(ustr("Anchored"), Variant::Bool(anchored))
+        }
+
+        fn fixture_tree() -> RojoTree {
+            RojoTree::new(
+                InstanceSnapshot::new()
+                    .class_name("DataModel")
+                    .name("Game")
+                    .children(vec![InstanceSnapshot::new()
+                        .class_name("Workspace")
+                        .name("Workspace")
+                        .children(vec![
+                            part("Part", "Floating", false),
+                            part("Part", "Ground", true),
+                            part("MeshPart", "Rock", false),
+                            InstanceSnapshot::new().class_name("Folder").name("Props"),
+                        ])]),
+            )
+        }
+
+        fn search(args: Value) -> Result<Vec<SearchInstancesMatch>, String> {
+            let args: SearchInstancesArgs = serde_json::from_value(args).unwrap();
+            search_instances(&fixture_tree(), Path::new("/project"), &args)
+        }
+
+        fn paths(matches: &[SearchInstancesMatch]) -> Vec<&str> {
+            let mut paths: Vec<&str> = matches.iter().map(|m| m.path.as_str()).collect();
+            paths.sort();
+            paths
+        }
+
+        #[test]
+        fn class_and_property_value() {
+            let matches = search(serde_json::json!({
+                "className": "BasePart",
+                "property": "Anchored",
+                "value": false,
+            }))
+            .unwrap();
+            assert_eq!(
+                paths(&matches),
+                vec!["Workspace/Floating", "Workspace/Rock"]
+            );
+
+            let matches = search(serde_json::json!({
+                "className": "Part",
+                "property": "Anchored",
+                "value": false,
+            }))
+            .unwrap();
+            assert_eq!(paths(&matches), vec!["Workspace/Floating"]);
+            assert_eq!(matches[0].class_name, "Part");
+        }
+
+        #[test]
+        fn class_only() {
+            let matches = search(serde_json::json!({ "className": "Folder" })).unwrap();
+            assert_eq!(paths(&matches), vec!["Workspace/Props"]);
+        }
+
+        #[test]
+        fn limit_caps_results() {
+            let matches = search(serde_json::json!({
+                "className": "BasePart",
+                "limit": 1,
+            }))
+            .unwrap();
+            assert_eq!(matches.len(), 1);
+        }
+
+        #[test]
+        fn unset_property_uses_class_default() {
+            let tree = RojoTree::new(
+                InstanceSnapshot::new()
+                    .class_name("Folder")
+                    .name("Root")
+                    .children(vec![
+                        InstanceSnapshot::new().class_name("Part").name("Loose"),
+                        part("Part", "Ground", true),
+                    ]),
+            );
+            let search = |args: Value| {
+                let args: SearchInstancesArgs = serde_json::from_value(args).unwrap();
+                search_instances(&tree, Path::new("/project"), &args).unwrap()
+            };
+
+            let matches = search(serde_json::json!({
+                "property": "Anchored",
+                "value": false,
+            }));
+            assert_eq!(paths(&matches), vec!["Loose"]);
+
+            let matches = search(serde_json::json!({
+                "className": "Part",
+                "property": "Anchored",
+            }));
+            assert_eq!(paths(&matches), vec!["Ground", "Loose"]);
+        }
+
+        #[test]
+        fn requires_a_filter() {
+            assert!(search(serde_json::json!({})).is_err());
+            assert!(search(serde_json::json!({ "value": true })).is_err());
+        }
+    }
 }
@@ -11,174 +11,581 @@ mod util;
 
 use std::convert::Infallible;
 use std::net::SocketAddr;
-use std::sync::atomic::AtomicUsize;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use bytes::Bytes;
+use http_body_util::Full;
 use hyper::body::Incoming;
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
-use hyper::Request;
-use hyper_util::rt::TokioIo;
-use tokio::net::TcpListener;
+use hyper::{Request, Response};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::runtime::Runtime;
-use tokio::sync::Notify;
+use tokio::sync::{watch, Notify};
 
 use crate::serve_session::ServeSession;
 
-use self::interface::{ServerExitReason, SyncbackPayload};
+use self::interface::ServerExitReason;
 
-/// Shared signal for the syncback endpoint to deposit its payload and notify
-/// the accept loop to shut down.
-pub struct SyncbackSignal {
-    payload: Mutex<Option<SyncbackPayload>>,
+/// How long the server waits for open connections to finish their requests
+/// after it stops accepting new ones.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Shared signal for the syncback and shutdown endpoints to deposit why the
+/// server should exit and notify the accept loop to stop.
+pub struct ExitSignal {
+    reason: Mutex<Option<ServerExitReason>>,
     notify: Notify,
 }
 
-impl SyncbackSignal {
+impl ExitSignal {
     pub fn new() -> Self {
         Self {
-            payload: Mutex::new(None),
+            reason: Mutex::new(None),
             notify: Notify::new(),
         }
     }
 
-    pub fn fire(&self, payload: SyncbackPayload) -> bool {
-        let mut guard = self.payload.lock().unwrap_or_else(|e| e.into_inner());
+    /// Asks the server to exit. Returns `false` if an exit was already
+    /// requested.
+    pub fn fire(&self, reason: ServerExitReason) -> bool {
+        let mut guard = self.reason.lock().unwrap_or_else(|e| e.into_inner());
         if guard.is_some() {
             return false;
         }
-        *guard = Some(payload);
+        *guard = Some(reason);
         self.notify.notify_one();
         true
     }
 
-    pub fn take_payload(&self) -> Option<SyncbackPayload> {
-        self.payload
+    /// Returns whether an exit has been requested.
+    pub fn is_fired(&self) -> bool {
+        self.reason
             .lock()
             .unwrap_or_else(|e| e.into_inner())
-            .take()
+            .is_some()
+    }
+
+    pub fn take_reason(&self) -> Option<ServerExitReason> {
+        self.reason.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+/// Limits on the connections the server will hold open. Every limit is off by
+/// default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectionLimits {
+    /// The most connections that may be open at once. Connections past the
+    /// limit are answered with `503 Service Unavailable` and closed.
+    pub max_connections: Option<usize>,
+    /// How long a kept-alive connection may sit between requests before it's
+    /// closed. Zero turns keep-alive off.
+    pub keep_alive_timeout: Option<Duration>,
+    /// How long a client has to send the headers of a request.
+    pub header_read_timeout: Option<Duration>,
+    /// How many `/api/write` requests each connection may make per second.
+    /// Writes past the limit are answered with `429 Too Many Requests`.
+    pub max_writes_per_sec: Option<u32>,
+}
+
+/// API responses at least this many bytes long are gzipped for clients that
+/// accept it, unless the server is given a different threshold.
+pub const DEFAULT_GZIP_THRESHOLD: usize = 8 * 1024;
+
+const SERVICE_UNAVAILABLE_RESPONSE: &[u8] =
+    b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// Counts what's connected to the server. One is shared by every
+/// connection.
+#[derive(Debug, Default)]
+pub struct Connections {
+    /// Every open TCP connection, used to cap how many may be open and to
+    /// wait for them to close on shutdown.
+    open: AtomicUsize,
+    /// Plugins subscribed to live sync over a WebSocket.
+    live_sync: AtomicUsize,
+}
+
+impl Connections {
+    /// Returns how many plugins are subscribed to live sync.
+    pub fn live_sync(&self) -> usize {
+        self.live_sync.load(Ordering::SeqCst)
+    }
+
+    /// Counts a live sync subscription until the returned guard is dropped.
+    pub fn enter_live_sync(self: &Arc<Self>) -> ConnectionGuard {
+        self.live_sync.fetch_add(1, Ordering::SeqCst);
+        ConnectionGuard {
+            connections: Arc::clone(self),
+            live_sync: true,
+        }
+    }
+
+    /// Counts an open connection until the returned guard is dropped, and
+    /// returns how many were open before it.
+    fn enter_open(self: &Arc<Self>) -> (usize, ConnectionGuard) {
+        let open = self.open.fetch_add(1, Ordering::SeqCst);
+        let guard = ConnectionGuard {
+            connections: Arc::clone(self),
+            live_sync: false,
+        };
+        (open, guard)
+    }
+
+    fn open(&self) -> usize {
+        self.open.load(Ordering::SeqCst)
     }
 }
 
+/// Takes a connection back out of [`Connections`] when dropped.
+pub struct ConnectionGuard {
+    connections: Arc<Connections>,
+    live_sync: bool,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let counter = if self.live_sync {
+            &self.connections.live_sync
+        } else {
+            &self.connections.open
+        };
+        counter.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Tracks when a connection last did any work, so idle kept-alive
+/// connections can be closed.
+struct ConnectionActivity {
+    in_flight: AtomicUsize,
+    last_active: Mutex<Instant>,
+}
+
+impl ConnectionActivity {
+    fn new() -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_active.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    fn begin(&self) {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        self.touch();
+    }
+
+    fn end(&self) {
+        self.touch();
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// How long the connection has been idle, or `None` if a request is
+    /// being handled.
+    fn idle_for(&self) -> Option<Duration> {
+        if self.in_flight.load(Ordering::SeqCst) > 0 {
+            return None;
+        }
+        Some(
+            self.last_active
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .elapsed(),
+        )
+    }
+}
+
+/// A token bucket that allows `rate` operations per second, with bursts of up
+/// to one second's worth.
+struct RateLimiter {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> Self {
+        let rate = f64::from(rate.max(1));
+        Self {
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token if one is available. Otherwise, returns how long until
+    /// the next one is.
+    fn try_acquire(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// What a connection's requests need besides the session. One is made for
+/// each connection.
+struct RequestContext {
+    connections: Arc<Connections>,
+    auth_token: Option<Arc<str>>,
+    gzip_threshold: usize,
+    /// Limits `/api/write` requests on this connection, if writes are
+    /// rate limited.
+    write_limiter: Option<Mutex<RateLimiter>>,
+}
+
 pub struct LiveServer {
     serve_session: Arc<ServeSession>,
-    syncback_signal: Arc<SyncbackSignal>,
+    exit_signal: Arc<ExitSignal>,
     mcp_state: Arc<mcp::McpState>,
-    active_api_connections: Arc<AtomicUsize>,
+    connections: Arc<Connections>,
+    limits: ConnectionLimits,
+    auth_token: Option<Arc<str>>,
+    gzip_threshold: usize,
+    port_range: Option<RangeInclusive<u16>>,
 }
 
 impl LiveServer {
     pub fn new(serve_session: Arc<ServeSession>) -> Self {
         LiveServer {
             serve_session,
-            syncback_signal: Arc::new(SyncbackSignal::new()),
+            exit_signal: Arc::new(ExitSignal::new()),
             mcp_state: Arc::new(mcp::McpState::new()),
-            active_api_connections: Arc::new(AtomicUsize::new(0)),
+            connections: Arc::new(Connections::default()),
+            limits: ConnectionLimits::default(),
+            auth_token: None,
+            gzip_threshold: DEFAULT_GZIP_THRESHOLD,
+            port_range: None,
         }
     }
 
+    pub fn with_connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Requires every `/api` and `/mcp` request to carry
+    /// `Authorization: Bearer <auth_token>`.
+    pub fn with_auth_token(mut self, auth_token: Option<String>) -> Self {
+        self.auth_token = auth_token.map(Arc::from);
+        self
+    }
+
+    /// Gzips API responses of at least `threshold` bytes for clients that
+    /// accept it.
+    pub fn with_gzip_threshold(mut self, threshold: usize) -> Self {
+        self.gzip_threshold = threshold;
+        self
+    }
+
+    /// Listens on the next free port in `port_range` when the port passed to
+    /// [`LiveServer::start`] is taken, instead of retrying that port.
+    pub fn with_port_range(mut self, port_range: Option<RangeInclusive<u16>>) -> Self {
+        self.port_range = port_range;
+        self
+    }
+
     pub fn start(self, address: SocketAddr) -> ServerExitReason {
         let serve_session = Arc::clone(&self.serve_session);
-        let syncback_signal = Arc::clone(&self.syncback_signal);
+        let exit_signal = Arc::clone(&self.exit_signal);
         let mcp_state = Arc::clone(&self.mcp_state);
-        let active_api_connections = Arc::clone(&self.active_api_connections);
+        let connections = Arc::clone(&self.connections);
+        let limits = self.limits;
+        let auth_token = self.auth_token.clone();
+        let gzip_threshold = self.gzip_threshold;
+        let port_range = self.port_range.clone();
 
         let rt = Runtime::new().unwrap();
         let exit_reason = rt.block_on(async move {
-            let listener = {
-                const MAX_BIND_ATTEMPTS: u32 = 5;
-                const BASE_BACKOFF_MS: u64 = 200;
-                let mut attempts = 0u32;
-                loop {
-                    attempts += 1;
-                    match TcpListener::bind(address).await {
-                        Ok(listener) => break listener,
-                        Err(err)
-                            if err.kind() == std::io::ErrorKind::AddrInUse
-                                && attempts < MAX_BIND_ATTEMPTS =>
-                        {
-                            let delay = BASE_BACKOFF_MS * 2u64.pow(attempts - 1);
-                            log::warn!(
-                                "Port {} in use, retrying in {}ms (attempt {}/{})",
-                                address.port(),
-                                delay,
-                                attempts,
-                                MAX_BIND_ATTEMPTS
-                            );
-                            tokio::time::sleep(Duration::from_millis(delay)).await;
-                        }
+            let listener = match port_range {
+                Some(port_range) => bind_in_range(address, port_range).await,
+                None => bind_with_retries(address).await,
+            };
+
+            let local_address = listener.local_addr().unwrap_or(address);
+            let host = if local_address.ip().is_loopback() {
+                "localhost".to_owned()
+            } else {
+                local_address.ip().to_string()
+            };
+            log::info!("Listening: http://{}:{}", host, local_address.port());
+
+            #[cfg(unix)]
+            {
+                let exit_signal = Arc::clone(&exit_signal);
+                tokio::spawn(async move {
+                    use tokio::signal::unix::{signal, SignalKind};
+
+                    let mut terminate = match signal(SignalKind::terminate()) {
+                        Ok(terminate) => terminate,
                         Err(err) => {
-                            panic!(
-                                "Failed to bind to {}: {} (after {} attempts)",
-                                address, err, attempts
-                            );
+                            log::warn!("Could not listen for SIGTERM: {err}");
+                            return;
                         }
+                    };
+                    if terminate.recv().await.is_some() {
+                        log::info!("Received SIGTERM, shutting down...");
+                        exit_signal.fire(ServerExitReason::Shutdown);
                     }
-                }
-            };
+                });
+            }
+
+            // Flipped to `true` once the accept loop stops, telling every open
+            // connection to finish its in-flight requests and close.
+            let (draining_sender, draining_receiver) = watch::channel(false);
 
             loop {
                 tokio::select! {
                     result = listener.accept() => {
                         let (stream, _) = result.unwrap();
+
+                        let (open, open_connection) = connections.enter_open();
+                        if limits.max_connections.is_some_and(|max| open >= max) {
+                            log::warn!(
+                                "Rejecting connection: {} connections are already open",
+                                open
+                            );
+                            tokio::spawn(async move {
+                                reject_connection(stream).await;
+                                drop(open_connection);
+                            });
+                            continue;
+                        }
+
                         let io = TokioIo::new(stream);
                         let serve_session = Arc::clone(&serve_session);
-                        let syncback_signal = Arc::clone(&syncback_signal);
+                        let exit_signal = Arc::clone(&exit_signal);
                         let mcp_state = Arc::clone(&mcp_state);
-                        let active_api_connections = Arc::clone(&active_api_connections);
+                        let context = Arc::new(RequestContext {
+                            connections: Arc::clone(&connections),
+                            auth_token: auth_token.clone(),
+                            gzip_threshold,
+                            write_limiter: limits
+                                .max_writes_per_sec
+                                .map(|rate| Mutex::new(RateLimiter::new(rate))),
+                        });
+                        let activity = Arc::new(ConnectionActivity::new());
+                        let mut draining = draining_receiver.clone();
 
                         tokio::spawn(async move {
+                            let _open_connection = open_connection;
+                            let service_activity = Arc::clone(&activity);
                             let service = service_fn(move |req: Request<Incoming>| {
                                 let serve_session = Arc::clone(&serve_session);
-                                let syncback_signal = Arc::clone(&syncback_signal);
+                                let exit_signal = Arc::clone(&exit_signal);
                                 let mcp_state = Arc::clone(&mcp_state);
-                                let active_api_connections = Arc::clone(&active_api_connections);
+                                let context = Arc::clone(&context);
+                                let activity = Arc::clone(&service_activity);
 
                                 async move {
-                                    if req.uri().path().starts_with("/mcp") {
-                                        Ok::<_, Infallible>(
-                                            mcp::call(req, mcp_state, active_api_connections)
-                                                .await,
-                                        )
-                                    } else if req.uri().path().starts_with("/api") {
-                                        Ok::<_, Infallible>(
-                                            api::call(
-                                                serve_session,
-                                                req,
-                                                syncback_signal,
-                                                mcp_state,
-                                                active_api_connections,
-                                            )
-                                            .await,
-                                        )
-                                    } else {
-                                        Ok::<_, Infallible>(ui::call(serve_session, req).await)
-                                    }
+                                    activity.begin();
+                                    let response =
+                                        route(req, serve_session, exit_signal, mcp_state, &context)
+                                            .await;
+                                    activity.end();
+                                    response
                                 }
                             });
 
-                            if let Err(err) = http1::Builder::new()
-                                .serve_connection(io, service)
-                                .with_upgrades()
-                                .await
-                            {
+                            let mut builder = http1::Builder::new();
+                            if let Some(timeout) = limits.header_read_timeout {
+                                builder.timer(TokioTimer::new()).header_read_timeout(timeout);
+                            }
+                            if limits.keep_alive_timeout == Some(Duration::ZERO) {
+                                builder.keep_alive(false);
+                            }
+                            let connection = builder.serve_connection(io, service).with_upgrades();
+                            tokio::pin!(connection);
+
+                            let idle_timeout = limits
+                                .keep_alive_timeout
+                                .filter(|timeout| !timeout.is_zero());
+                            let mut closing = false;
+                            let result = loop {
+                                tokio::select! {
+                                    result = connection.as_mut() => break result,
+                                    _ = draining.wait_for(|draining| *draining), if !closing => {
+                                        closing = true;
+                                        connection.as_mut().graceful_shutdown();
+                                    }
+                                    _ = tokio::time::sleep(idle_timeout.unwrap_or_default() / 4),
+                                        if idle_timeout.is_some() && !closing =>
+                                    {
+                                        if activity
+                                            .idle_for()
+                                            .is_some_and(|idle| Some(idle) >= idle_timeout)
+                                        {
+                                            closing = true;
+                                            connection.as_mut().graceful_shutdown();
+                                        }
+                                    }
+                                }
+                            };
+                            if let Err(err) = result {
                                 log::error!("Error serving connection: {err}");
                             }
                         });
                     }
-                    _ = syncback_signal.notify.notified() => {
+                    _ = exit_signal.notify.notified() => {
                         break;
                     }
                 }
             }
 
-            let payload = syncback_signal
-                .take_payload()
-                .expect("Syncback signal fired but no payload was deposited");
-            ServerExitReason::SyncbackRequested(payload)
+            // Stop accepting connections and let the open ones finish what
+            // they're doing, so no request is cut off halfway through.
+            drop(listener);
+            let _ = draining_sender.send(true);
+            let drain_deadline = tokio::time::Instant::now() + DRAIN_TIMEOUT;
+            while connections.open() > 0 {
+                if tokio::time::Instant::now() >= drain_deadline {
+                    log::warn!(
+                        "Gave up waiting for {} connections to close",
+                        connections.open()
+                    );
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+
+            exit_signal
+                .take_reason()
+                .expect("Exit signal fired but no reason was deposited")
         });
 
         exit_reason
     }
 }
+
+/// Binds `address`, retrying with backoff while the port is in use.
+async fn bind_with_retries(address: SocketAddr) -> TcpListener {
+    const MAX_BIND_ATTEMPTS: u32 = 5;
+    const BASE_BACKOFF_MS: u64 = 200;
+    let mut attempts = 0u32;
+    loop {
+        attempts += 1;
+        match TcpListener::bind(address).await {
+            Ok(listener) => return listener,
+            Err(err)
+                if err.kind() == std::io::ErrorKind::AddrInUse && attempts < MAX_BIND_ATTEMPTS =>
+            {
+                let delay = BASE_BACKOFF_MS * 2u64.pow(attempts - 1);
+                log::warn!(
+                    "Port {} in use, retrying in {}ms (attempt {}/{})",
+                    address.port(),
+                    delay,
+                    attempts,
+                    MAX_BIND_ATTEMPTS
+                );
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            Err(err) => {
+                panic!(
+                    "Failed to bind to {}: {} (after {} attempts)",
+                    address, err, attempts
+                );
+            }
+        }
+    }
+}
+
+/// Binds the port of `address`, or the first free port in `port_range`
+/// after it, wrapping around to the start of the range.
+async fn bind_in_range(address: SocketAddr, port_range: RangeInclusive<u16>) -> TcpListener {
+    for port in port_candidates(address.port(), &port_range) {
+        let candidate = SocketAddr::new(address.ip(), port);
+        match TcpListener::bind(candidate).await {
+            Ok(listener) => {
+                if port != address.port() {
+                    log::warn!(
+                        "Port {} in use, using port {} instead",
+                        address.port(),
+                        port
+                    );
+                }
+                return listener;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AddrInUse => {
+                log::debug!("Port {} in use, trying the next one", port);
+            }
+            Err(err) => panic!("Failed to bind to {}: {}", candidate, err),
+        }
+    }
+    panic!(
+        "Failed to bind to {}: every port from {} to {} is in use",
+        address.ip(),
+        port_range.start(),
+        port_range.end()
+    );
+}
+
+/// The ports to try, in order: `preferred`, then the ports in `port_range`
+/// after it, then the ones before it.
+fn port_candidates(preferred: u16, port_range: &RangeInclusive<u16>) -> Vec<u16> {
+    let mut candidates = vec![preferred];
+    candidates.extend(port_range.clone().filter(|port| *port > preferred));
+    candidates.extend(port_range.clone().filter(|port| *port < preferred));
+    candidates
+}
+
+/// Sends each request to the MCP server, the API or the web UI.
+async fn route(
+    req: Request<Incoming>,
+    serve_session: Arc<ServeSession>,
+    exit_signal: Arc<ExitSignal>,
+    mcp_state: Arc<mcp::McpState>,
+    context: &RequestContext,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    if req.uri().path().starts_with("/mcp") {
+        Ok(mcp::call(
+            req,
+            serve_session,
+            mcp_state,
+            Arc::clone(&context.connections),
+            context.auth_token.as_deref(),
+        )
+        .await)
+    } else if req.uri().path().starts_with("/api") {
+        Ok(api::call(serve_session, req, exit_signal, mcp_state, context).await)
+    } else {
+        Ok(ui::call(serve_session, req).await)
+    }
+}
+
+/// Answers a connection past `ConnectionLimits::max_connections` with a `503`
+/// and closes it.
+async fn reject_connection(stream: TcpStream) {
+    if stream.writable().await.is_ok() {
+        let _ = stream.try_write(SERVICE_UNAVAILABLE_RESPONSE);
+    }
+
+    // Closing a socket with unread data resets it, which can throw away the
+    // response before the client reads it. Drain the request for a moment
+    // first.
+    let drain = async {
+        let mut buffer = [0; 1024];
+        while stream.readable().await.is_ok() {
+            match stream.try_read(&mut buffer) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(_) => break,
+            }
+        }
+    };
+    let _ = tokio::time::timeout(Duration::from_secs(1), drain).await;
+}
@@ -1,6 +1,15 @@
+use std::io::Write;
+
 use bytes::Bytes;
-use http_body_util::Full;
-use hyper::{header::CONTENT_TYPE, Response, StatusCode};
+use flate2::{write::GzEncoder, Compression};
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    header::{
+        HeaderValue, ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH,
+        CONTENT_TYPE, VARY,
+    },
+    Request, Response, StatusCode,
+};
 use serde::{Deserialize, Serialize};
 
 pub fn msgpack_ok<T: Serialize>(value: T) -> Response<Full<Bytes>> {
@@ -65,3 +74,89 @@ pub fn json<T: Serialize>(value: T, code: StatusCode) -> Response<Full<Bytes>> {
         .body(Full::new(Bytes::from(serialized)))
         .unwrap()
 }
+
+/// Returns whether `request` lists gzip, or `*`, in its `Accept-Encoding`
+/// header without ruling it out with `q=0`.
+pub fn accepts_gzip<B>(request: &Request<B>) -> bool {
+    request
+        .headers()
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|coding| {
+            let mut parts = coding.split(';');
+            let name = parts.next().unwrap_or("").trim();
+            let refused = parts.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    == Some(0.0)
+            });
+            (name.eq_ignore_ascii_case("gzip") || name == "*") && !refused
+        })
+}
+
+/// Compresses the body of `response` with gzip if it's at least `threshold`
+/// bytes long. Upgrade responses and responses that already have a
+/// `Content-Encoding` are passed through untouched.
+pub async fn gzip_response(
+    response: Response<Full<Bytes>>,
+    threshold: usize,
+) -> Response<Full<Bytes>> {
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS
+        || response.headers().contains_key(CONTENT_ENCODING)
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match body.collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(never) => match never {},
+    };
+    parts
+        .headers
+        .insert(VARY, HeaderValue::from_static("Accept-Encoding"));
+
+    if body.len() < threshold {
+        return Response::from_parts(parts, Full::new(body));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    match encoder.write_all(&body).and_then(|()| encoder.finish()) {
+        Ok(compressed) => {
+            parts
+                .headers
+                .insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+            parts.headers.remove(CONTENT_LENGTH);
+            Response::from_parts(parts, Full::new(Bytes::from(compressed)))
+        }
+        Err(err) => {
+            log::warn!("Could not gzip response, sending it uncompressed: {err}");
+            Response::from_parts(parts, Full::new(body))
+        }
+    }
+}
+
+/// Returns whether `request` carries an `Authorization: Bearer` header with
+/// `auth_token` in it. Every request is authorized when there's no token.
+pub fn is_authorized<B>(request: &Request<B>, auth_token: Option<&str>) -> bool {
+    let Some(expected) = auth_token else {
+        return true;
+    };
+
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes()))
+}
+
+/// Compares two byte strings without returning early on the first
+/// difference, so response timing doesn't leak how much of a token matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
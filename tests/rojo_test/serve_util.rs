@@ -15,9 +15,12 @@ use rbx_dom_weak::types::Ref;
 use serde::Deserialize;
 use tempfile::{tempdir, TempDir};
 
-use librojo::web_api::{
-    GitMetadata, ReadResponse, SerializeResponse, ServerInfoResponse, SocketPacket,
-    SocketPacketBody, SocketPacketType,
+use librojo::{
+    web_api::{
+        GitMetadata, HealthResponse, ReadBatchRequest, ReadBatchResponse, ReadResponse,
+        SerializeResponse, ServerInfoResponse, SocketPacket, SocketPacketBody, SocketPacketType,
+    },
+    SessionId,
 };
 use rojo_insta_ext::RedactionMap;
 
@@ -73,10 +76,16 @@ pub struct TestServeSession {
 
     port: usize,
     project_path: PathBuf,
+    extra_args: Vec<String>,
 }
 
 impl TestServeSession {
     pub fn new(name: &str) -> Self {
+        Self::new_with_args(name, &[])
+    }
+
+    /// Like `new`, but passes `extra_args` to `atlas serve` after the port.
+    pub fn new_with_args(name: &str, extra_args: &[&str]) -> Self {
         let working_dir = get_working_dir_path();
 
         let source_path = Path::new(SERVE_TESTS_PATH).join(name);
@@ -117,6 +126,7 @@ impl TestServeSession {
                 "--port",
                 port_string.as_str(),
             ])
+            .args(extra_args)
             .current_dir(working_dir)
             .stderr(Stdio::piped())
             .spawn()
@@ -127,6 +137,7 @@ impl TestServeSession {
             _dir: dir,
             port,
             project_path,
+            extra_args: extra_args.iter().map(|arg| arg.to_string()).collect(),
         }
     }
 
@@ -180,6 +191,7 @@ impl TestServeSession {
             _dir: dir,
             port,
             project_path: fixture_dir,
+            extra_args: Vec::new(),
         }
     }
 
@@ -234,6 +246,7 @@ impl TestServeSession {
             _dir: dir,
             port,
             project_path,
+            extra_args: Vec::new(),
         }
     }
 
@@ -258,6 +271,7 @@ impl TestServeSession {
                 "--port",
                 port_string.as_str(),
             ])
+            .args(&self.extra_args)
             .current_dir(working_dir)
             .stderr(Stdio::piped())
             .spawn()
@@ -327,6 +341,49 @@ impl TestServeSession {
         }
     }
 
+    /// Asks the server to shut down the given session and returns the
+    /// response status.
+    pub fn post_api_shutdown(
+        &self,
+        session_id: SessionId,
+    ) -> Result<reqwest::StatusCode, reqwest::Error> {
+        use serde::Serialize;
+
+        let url = format!("http://localhost:{}/api/shutdown", self.port);
+
+        let mut body = Vec::new();
+        let mut serializer = rmp_serde::Serializer::new(&mut body)
+            .with_human_readable()
+            .with_struct_map();
+        librojo::web_api::ShutdownRequest { session_id }
+            .serialize(&mut serializer)
+            .expect("Failed to serialize ShutdownRequest");
+
+        let response = reqwest::blocking::Client::new()
+            .post(url)
+            .body(body)
+            .send()?;
+        Ok(response.status())
+    }
+
+    /// Waits up to `timeout` for the `atlas serve` process to exit on its own,
+    /// returning `None` if it's still running.
+    pub fn try_wait_for_exit(&mut self, timeout: Duration) -> Option<std::process::ExitStatus> {
+        let start = std::time::Instant::now();
+        while start.elapsed() < timeout {
+            if let Some(status) = self
+                .rojo_process
+                .0
+                .try_wait()
+                .expect("Failed to wait on Rojo")
+            {
+                return Some(status);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        None
+    }
+
     pub fn get_api_rojo(&self) -> Result<ServerInfoResponse, reqwest::Error> {
         let url = format!("http://localhost:{}/api/rojo", self.port);
         let body = reqwest::blocking::get(url)?.bytes()?;
@@ -334,6 +391,23 @@ impl TestServeSession {
         Ok(deserialize_msgpack(&body).expect("Server returned malformed response"))
     }
 
+    /// Like `get_api_rojo`, but authenticates with `token` for servers
+    /// started with `--auth-token`.
+    pub fn get_api_rojo_with_token(
+        &self,
+        token: &str,
+    ) -> Result<ServerInfoResponse, reqwest::Error> {
+        let url = format!("http://localhost:{}/api/rojo", self.port);
+        let body = reqwest::blocking::Client::new()
+            .get(url)
+            .bearer_auth(token)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+
+        Ok(deserialize_msgpack(&body).expect("Server returned malformed response"))
+    }
+
     pub fn get_api_git_metadata(&self) -> Result<Option<GitMetadata>, reqwest::Error> {
         let url = format!("http://localhost:{}/api/git-metadata", self.port);
         let body = reqwest::blocking::get(url)?.bytes()?;
@@ -341,6 +415,11 @@ impl TestServeSession {
         Ok(deserialize_msgpack(&body).expect("Server returned malformed response"))
     }
 
+    pub fn get_api_snapshot(&self) -> Result<librojo::InstanceSnapshot, reqwest::Error> {
+        let url = format!("http://localhost:{}/api/snapshot", self.port);
+        reqwest::blocking::get(url)?.json()
+    }
+
     pub fn get_api_read(&self, id: Ref) -> Result<ReadResponse<'_>, reqwest::Error> {
         let url = format!("http://localhost:{}/api/read/{}", self.port, id);
         let body = reqwest::blocking::get(url)?.bytes()?;
@@ -348,6 +427,41 @@ impl TestServeSession {
         Ok(deserialize_msgpack(&body).expect("Server returned malformed response"))
     }
 
+    /// Fetches `/api/health`. Unlike the other endpoints it answers in JSON.
+    pub fn get_api_health(&self) -> Result<HealthResponse, Box<dyn std::error::Error>> {
+        let url = format!("http://localhost:{}/api/health", self.port);
+        let body = reqwest::blocking::get(url)?.bytes()?;
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    pub fn post_api_read_batch(
+        &self,
+        session_id: SessionId,
+        ids: &[Ref],
+    ) -> Result<ReadBatchResponse<'_>, reqwest::Error> {
+        use serde::Serialize;
+
+        let url = format!("http://localhost:{}/api/read-batch", self.port);
+
+        let request = ReadBatchRequest {
+            session_id,
+            ids: ids.to_vec(),
+        };
+        let mut body = Vec::new();
+        let mut serializer = rmp_serde::Serializer::new(&mut body)
+            .with_human_readable()
+            .with_struct_map();
+        request
+            .serialize(&mut serializer)
+            .expect("Failed to serialize ReadBatchRequest");
+
+        let client = reqwest::blocking::Client::new();
+        let body = client.post(url).body(body).send()?.bytes()?;
+
+        Ok(deserialize_msgpack(&body).expect("Server returned malformed response"))
+    }
+
     pub fn get_api_socket_packet(
         &self,
         packet_type: SocketPacketType,
@@ -374,88 +488,18 @@ impl TestServeSession {
         action: impl FnOnce(),
     ) -> Result<SocketPacket<'static>, Box<dyn std::error::Error>> {
         let url = format!("ws://localhost:{}/api/socket/{}", self.port, cursor);
+        recv_packet_from(&url, packet_type, action)
+    }
 
-        let (mut socket, _response) = connect(url)?;
-
-        // Set a read timeout on the underlying TCP stream to prevent blocking forever.
-        // Without this, socket.read() blocks indefinitely if no data arrives.
-        let timeout = Duration::from_secs(10);
-        if let hyper_tungstenite::tungstenite::stream::MaybeTlsStream::Plain(ref stream) =
-            socket.get_ref()
-        {
-            stream.set_read_timeout(Some(Duration::from_millis(100)))?;
-        }
-
-        // Now that the WebSocket is connected and listening, perform the action
-        // that should trigger the change (e.g. writing/deleting a file).
-        action();
-
-        let start = std::time::Instant::now();
-        let mut collected: Option<SocketPacket<'static>> = None;
-        let mut last_received: Option<std::time::Instant> = None;
-
-        // After receiving the first message, keep collecting for this duration
-        // after the LAST received message. This handles split events on Windows
-        // where rename = REMOVE + CREATE may produce separate messages.
-        // Must be shorter than the 200ms reconciliation timer to avoid
-        // capturing tree-correction messages as part of the same batch.
-        let settle = Duration::from_millis(100);
-
-        loop {
-            // Hard timeout: no messages at all within 10 seconds
-            if start.elapsed() > timeout && collected.is_none() {
-                return Err("Timeout waiting for packet from WebSocket".into());
-            }
-
-            // Settle timeout: we have at least one message, and no new
-            // messages arrived for `settle` duration — return collected.
-            if let Some(last) = last_received {
-                if last.elapsed() >= settle {
-                    let _ = socket.close(None);
-                    return Ok(collected.unwrap());
-                }
-            }
-
-            match socket.read() {
-                Ok(Message::Binary(binary)) => {
-                    let packet: SocketPacket = deserialize_msgpack(&binary)?;
-                    if packet.packet_type != packet_type {
-                        continue;
-                    }
-
-                    match collected.as_mut() {
-                        Some(existing) => {
-                            merge_socket_packets(existing, packet);
-                        }
-                        None => {
-                            collected = Some(packet);
-                        }
-                    }
-                    last_received = Some(std::time::Instant::now());
-                }
-                Ok(Message::Close(_)) => {
-                    if let Some(packet) = collected {
-                        return Ok(packet);
-                    }
-                    return Err("WebSocket closed before receiving messages".into());
-                }
-                Ok(_) => {
-                    // Ignore other message types (ping, pong, text)
-                    continue;
-                }
-                Err(hyper_tungstenite::tungstenite::Error::Io(e))
-                    if e.kind() == std::io::ErrorKind::WouldBlock
-                        || e.kind() == std::io::ErrorKind::TimedOut
-                        || e.kind() == std::io::ErrorKind::Interrupted =>
-                {
-                    // No data available yet, read timed out, or interrupted by signal - try again
-                    continue;
-                }
-                Err(e) => {
-                    return Err(e.into());
-                }
-            }
-        }
+    /// Like `recv_socket_packet`, but listens on `/api/subscribe`, which
+    /// starts from whatever the message cursor is when the socket connects.
+    pub fn recv_subscribe_packet(
+        &self,
+        packet_type: SocketPacketType,
+        action: impl FnOnce(),
+    ) -> Result<SocketPacket<'static>, Box<dyn std::error::Error>> {
+        let url = format!("ws://localhost:{}/api/subscribe", self.port);
+        recv_packet_from(&url, packet_type, action)
     }
 
     pub fn get_api_serialize(&self, ids: &[Ref]) -> Result<SerializeResponse, reqwest::Error> {
@@ -535,6 +579,149 @@ impl TestServeSession {
 
         Ok(())
     }
+
+    /// Posts a `dry_run` write request and returns the problems the server
+    /// found in it.
+    pub fn post_api_write_dry_run(
+        &self,
+        request: &librojo::web_api::WriteRequest,
+    ) -> Result<librojo::web_api::WriteResponse, Box<dyn std::error::Error>> {
+        assert!(request.dry_run, "request should be a dry run");
+        self.post_api_write_with_response(request)
+    }
+
+    /// Posts a write request over `client`, so that requests sent with the
+    /// same client share a connection, and returns the raw response.
+    pub fn post_api_write_raw(
+        &self,
+        client: &reqwest::blocking::Client,
+        request: &librojo::web_api::WriteRequest,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        use serde::Serialize;
+
+        let url = format!("http://localhost:{}/api/write", self.port);
+
+        let mut body = Vec::new();
+        let mut serializer = rmp_serde::Serializer::new(&mut body)
+            .with_human_readable()
+            .with_struct_map();
+        request
+            .serialize(&mut serializer)
+            .expect("Failed to serialize WriteRequest");
+
+        client.post(url).body(body).send()
+    }
+
+    /// Posts a write request and returns the server's response.
+    pub fn post_api_write_with_response(
+        &self,
+        request: &librojo::web_api::WriteRequest,
+    ) -> Result<librojo::web_api::WriteResponse, Box<dyn std::error::Error>> {
+        use serde::Serialize;
+
+        let url = format!("http://localhost:{}/api/write", self.port);
+
+        let mut body = Vec::new();
+        let mut serializer = rmp_serde::Serializer::new(&mut body)
+            .with_human_readable()
+            .with_struct_map();
+        request.serialize(&mut serializer)?;
+
+        let client = reqwest::blocking::Client::new();
+        let body = client.post(url).body(body).send()?.bytes()?;
+
+        Ok(deserialize_msgpack(&body)?)
+    }
+}
+
+/// Connects a WebSocket to `url`, runs `action`, and collects the packets of
+/// `packet_type` that arrive. See `TestServeSession::recv_socket_packet`.
+fn recv_packet_from(
+    url: &str,
+    packet_type: SocketPacketType,
+    action: impl FnOnce(),
+) -> Result<SocketPacket<'static>, Box<dyn std::error::Error>> {
+    let (mut socket, _response) = connect(url)?;
+
+    // Set a read timeout on the underlying TCP stream to prevent blocking forever.
+    // Without this, socket.read() blocks indefinitely if no data arrives.
+    let timeout = Duration::from_secs(10);
+    if let hyper_tungstenite::tungstenite::stream::MaybeTlsStream::Plain(ref stream) =
+        socket.get_ref()
+    {
+        stream.set_read_timeout(Some(Duration::from_millis(100)))?;
+    }
+
+    // Now that the WebSocket is connected and listening, perform the action
+    // that should trigger the change (e.g. writing/deleting a file).
+    action();
+
+    let start = std::time::Instant::now();
+    let mut collected: Option<SocketPacket<'static>> = None;
+    let mut last_received: Option<std::time::Instant> = None;
+
+    // After receiving the first message, keep collecting for this duration
+    // after the LAST received message. This handles split events on Windows
+    // where rename = REMOVE + CREATE may produce separate messages.
+    // Must be shorter than the 200ms reconciliation timer to avoid
+    // capturing tree-correction messages as part of the same batch.
+    let settle = Duration::from_millis(100);
+
+    loop {
+        // Hard timeout: no messages at all within 10 seconds
+        if start.elapsed() > timeout && collected.is_none() {
+            return Err("Timeout waiting for packet from WebSocket".into());
+        }
+
+        // Settle timeout: we have at least one message, and no new
+        // messages arrived for `settle` duration — return collected.
+        if let Some(last) = last_received {
+            if last.elapsed() >= settle {
+                let _ = socket.close(None);
+                return Ok(collected.unwrap());
+            }
+        }
+
+        match socket.read() {
+            Ok(Message::Binary(binary)) => {
+                let packet: SocketPacket = deserialize_msgpack(&binary)?;
+                if packet.packet_type != packet_type {
+                    continue;
+                }
+
+                match collected.as_mut() {
+                    Some(existing) => {
+                        merge_socket_packets(existing, packet);
+                    }
+                    None => {
+                        collected = Some(packet);
+                    }
+                }
+                last_received = Some(std::time::Instant::now());
+            }
+            Ok(Message::Close(_)) => {
+                if let Some(packet) = collected {
+                    return Ok(packet);
+                }
+                return Err("WebSocket closed before receiving messages".into());
+            }
+            Ok(_) => {
+                // Ignore other message types (ping, pong, text)
+                continue;
+            }
+            Err(hyper_tungstenite::tungstenite::Error::Io(e))
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut
+                    || e.kind() == std::io::ErrorKind::Interrupted =>
+            {
+                // No data available yet, read timed out, or interrupted by signal - try again
+                continue;
+            }
+            Err(e) => {
+                return Err(e.into());
+            }
+        }
+    }
 }
 
 fn deserialize_msgpack<'a, T: Deserialize<'a>>(
@@ -811,6 +998,47 @@ pub fn run_cli_syncback_on_chunks(
     (dir, project_path)
 }
 
+/// Encodes a syncback request with correct version/protocol info.
+/// `data` is the single rbxm blob; `services` are the metadata chunks.
+fn syncback_request_body(
+    place_id: Option<u64>,
+    data: Vec<u8>,
+    services: Vec<librojo::web_api::ServiceChunk>,
+    keep_running: bool,
+) -> Vec<u8> {
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Req {
+        protocol_version: u64,
+        server_version: String,
+        place_id: Option<u64>,
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        services: Vec<librojo::web_api::ServiceChunk>,
+        keep_running: bool,
+    }
+
+    let request = Req {
+        protocol_version: librojo::web_api::PROTOCOL_VERSION,
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        place_id,
+        data,
+        services,
+        keep_running,
+    };
+
+    let mut body = Vec::new();
+    let mut serializer = rmp_serde::Serializer::new(&mut body)
+        .with_human_readable()
+        .with_struct_map();
+    request
+        .serialize(&mut serializer)
+        .expect("Failed to serialize syncback request");
+    body
+}
+
 impl TestServeSession {
     /// Post to /api/syncback with raw bytes. Returns the full response
     /// for status code inspection.
@@ -836,35 +1064,7 @@ impl TestServeSession {
         data: Vec<u8>,
         services: Vec<librojo::web_api::ServiceChunk>,
     ) {
-        use serde::Serialize;
-
-        #[derive(Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct Req {
-            protocol_version: u64,
-            server_version: String,
-            place_id: Option<u64>,
-            #[serde(with = "serde_bytes")]
-            data: Vec<u8>,
-            services: Vec<librojo::web_api::ServiceChunk>,
-        }
-
-        let request = Req {
-            protocol_version: librojo::web_api::PROTOCOL_VERSION,
-            server_version: env!("CARGO_PKG_VERSION").to_string(),
-            place_id,
-            data,
-            services,
-        };
-
-        let mut body = Vec::new();
-        let mut serializer = rmp_serde::Serializer::new(&mut body)
-            .with_human_readable()
-            .with_struct_map();
-        request
-            .serialize(&mut serializer)
-            .expect("Failed to serialize syncback request");
-
+        let body = syncback_request_body(place_id, data, services, false);
         let response = self.post_api_syncback_raw(body);
         assert!(
             response.status().is_success(),
@@ -874,6 +1074,70 @@ impl TestServeSession {
         );
     }
 
+    /// Post to /api/mcp/syncback, which syncs back without stopping the
+    /// server. Returns the status code and the decoded response body.
+    pub fn post_api_mcp_syncback(
+        &self,
+        data: Vec<u8>,
+        services: Vec<librojo::web_api::ServiceChunk>,
+    ) -> (reqwest::StatusCode, serde_json::Value) {
+        self.post_syncback_in_place("/api/mcp/syncback", data, services)
+    }
+
+    /// Post to /api/syncback with `keepRunning` set, so the server syncs back
+    /// without stopping. Returns the status code and the decoded response
+    /// body.
+    pub fn post_api_syncback_keep_running(
+        &self,
+        data: Vec<u8>,
+        services: Vec<librojo::web_api::ServiceChunk>,
+    ) -> (reqwest::StatusCode, serde_json::Value) {
+        self.post_syncback_in_place("/api/syncback", data, services)
+    }
+
+    fn post_syncback_in_place(
+        &self,
+        route: &str,
+        data: Vec<u8>,
+        services: Vec<librojo::web_api::ServiceChunk>,
+    ) -> (reqwest::StatusCode, serde_json::Value) {
+        let url = format!("http://localhost:{}{route}", self.port);
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to build reqwest client");
+        let response = client
+            .post(url)
+            .body(syncback_request_body(None, data, services, true))
+            .send()
+            .expect("Failed to send syncback request");
+
+        let status = response.status();
+        let body = response.bytes().expect("Failed to read syncback response");
+        let value = deserialize_msgpack(&body).expect("Failed to decode syncback response");
+        (status, value)
+    }
+
+    /// Waits for the serve process to exit on its own, panicking if it's
+    /// still running after `timeout`.
+    pub fn wait_for_exit(&mut self, timeout: Duration) -> std::process::ExitStatus {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Some(status) = self
+                .rojo_process
+                .0
+                .try_wait()
+                .expect("Failed to poll serve process")
+            {
+                return status;
+            }
+            if std::time::Instant::now() >= deadline {
+                panic!("Serve process did not exit within {:?}", timeout);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
     /// Wait for the server to come back online after a syncback-triggered
     /// restart. Uses longer timeouts than initial startup since syncback
     /// runs between teardown and restart.
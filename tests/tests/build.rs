@@ -114,6 +114,62 @@ fn run_build_test(test_name: &str) {
     });
 }
 
+/// Builds `test_name` to an XML model, passing `extra_args` to `rojo build`,
+/// and returns the file's contents.
+fn build_xml_model(test_name: &str, extra_args: &[&str]) -> String {
+    let input_path = Path::new(BUILD_TESTS_PATH).join(test_name);
+
+    let output_dir = tempdir().expect("couldn't create temporary directory");
+    let output_path = output_dir.path().join(format!("{}.rbxmx", test_name));
+
+    let output = atlas_command()
+        .args([
+            "build",
+            input_path.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+        ])
+        .args(extra_args)
+        .env("RUST_LOG", "error")
+        .current_dir(get_working_dir_path())
+        .output()
+        .expect("Couldn't start Rojo");
+
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "Rojo did not exit successfully");
+
+    fs::read_to_string(&output_path).expect("Couldn't read output file")
+}
+
+/// Decodes `xml` and encodes it again, which gives DOMs with the same
+/// contents the same text regardless of how the input was formatted.
+fn normalize_xml_model(xml: &str) -> String {
+    let dom = rbx_xml::from_str_default(xml).expect("built model should decode");
+    let children = dom.root().children().to_vec();
+
+    let mut output = Vec::new();
+    rbx_xml::to_writer_default(&mut output, &dom, &children).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn compact_xml_decodes_like_indented_xml() {
+    for test_name in ["rbxmx_ref", "init_meta_name_override"] {
+        let indented = build_xml_model(test_name, &[]);
+        let compact = build_xml_model(test_name, &["--compact-xml"]);
+
+        assert!(indented.contains("\n  <"), "{test_name} should be indented");
+        assert!(!compact.contains("\n  <"), "{test_name} should be compact");
+        assert!(compact.len() < indented.len());
+
+        assert_eq!(
+            normalize_xml_model(&compact),
+            normalize_xml_model(&indented),
+            "{test_name} should decode to the same DOM"
+        );
+    }
+}
+
 fn snapshot_debug(snap: &librojo::InstanceSnapshot) -> String {
     fn recurse(snap: &librojo::InstanceSnapshot, depth: usize, out: &mut String) {
         use std::fmt::Write;
@@ -491,3 +547,236 @@ fn nested_path_detection_correctness() {
     let result: Vec<PathBuf> = top_level_paths(&[]);
     assert!(result.is_empty(), "Empty input should produce empty output");
 }
+
+#[test]
+fn reflection_db_override_resolves_custom_class() {
+    use rbx_dom_weak::types::VariantType;
+    use rbx_reflection::{ClassDescriptor, DataType, PropertyDescriptor, ReflectionDatabase};
+
+    let dir = tempdir().expect("couldn't create temporary directory");
+
+    let mut database = ReflectionDatabase::new();
+    database.version = [0, 700, 0, 1];
+    let mut instance = ClassDescriptor::new("Instance");
+    instance.properties.insert(
+        "Name".into(),
+        PropertyDescriptor::new("Name", DataType::Value(VariantType::String)),
+    );
+    let mut widget = ClassDescriptor::new("AtlasTestWidget");
+    widget.superclass = Some("Instance".into());
+    widget.properties.insert(
+        "Charge".into(),
+        PropertyDescriptor::new("Charge", DataType::Value(VariantType::Float32)),
+    );
+    database.classes.insert("Instance".into(), instance);
+    database.classes.insert("AtlasTestWidget".into(), widget);
+
+    let database_path = dir.path().join("database.msgpack");
+    fs::write(&database_path, rmp_serde::to_vec_named(&database).unwrap()).unwrap();
+
+    let project_path = dir.path().join("default.project.json5");
+    fs::write(
+        &project_path,
+        r#"{
+            "name": "Widget",
+            "tree": {
+                "$className": "AtlasTestWidget",
+                "$properties": { "Charge": 5 }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let output_path = dir.path().join("widget.rbxmx");
+    let build = |extra_args: &[&str]| {
+        let output = atlas_command()
+            .args([
+                "build",
+                project_path.to_str().unwrap(),
+                "-o",
+                output_path.to_str().unwrap(),
+            ])
+            .args(extra_args)
+            .env("RUST_LOG", "error")
+            .current_dir(get_working_dir_path())
+            .output()
+            .expect("Couldn't start Rojo");
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        output.status.success()
+    };
+
+    assert!(
+        !build(&[]),
+        "AtlasTestWidget should be unknown to the built-in database"
+    );
+    assert!(
+        build(&["--reflection-db", database_path.to_str().unwrap()]),
+        "build with the custom database should succeed"
+    );
+
+    let model = fs::read_to_string(&output_path).unwrap();
+    assert!(model.contains(r#"class="AtlasTestWidget""#), "{model}");
+    assert!(
+        model.contains(r#"<float name="Charge">5</float>"#),
+        "{model}"
+    );
+}
+
+#[test]
+fn watch_rebuilds_after_source_change() {
+    use std::{
+        process::Stdio,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use crate::rojo_test::io_util::KillOnDrop;
+
+    let dir = tempdir().expect("couldn't create temporary directory");
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("main.luau"), "return 1").unwrap();
+    fs::write(
+        dir.path().join("default.project.json5"),
+        r#"{ "name": "watched", "tree": { "$path": "src" } }"#,
+    )
+    .unwrap();
+
+    let output_path = dir.path().join("watched.rbxmx");
+    let child = atlas_command()
+        .args([
+            "build",
+            dir.path().to_str().unwrap(),
+            "--watch",
+            "-o",
+            output_path.to_str().unwrap(),
+        ])
+        .env("RUST_LOG", "error")
+        .current_dir(get_working_dir_path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't start Rojo");
+    let _child = KillOnDrop(child);
+
+    let wait_for_output = |needle: &str| {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(10) {
+            if let Ok(contents) = fs::read_to_string(&output_path) {
+                if contents.contains(needle) {
+                    return;
+                }
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!("output never contained {needle:?}");
+    };
+
+    wait_for_output("return 1");
+    fs::write(src_dir.join("main.luau"), "return 2").unwrap();
+    wait_for_output("return 2");
+}
+
+#[test]
+fn build_to_stdout_writes_only_the_model() {
+    let dir = tempdir().expect("couldn't create temporary directory");
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("main.luau"), "return 1").unwrap();
+    fs::write(
+        dir.path().join("default.project.json5"),
+        r#"{ "name": "piped", "tree": { "$path": "src" } }"#,
+    )
+    .unwrap();
+
+    let output = atlas_command()
+        .args([
+            "build",
+            dir.path().to_str().unwrap(),
+            "--output",
+            "-",
+            "--format",
+            "rbxm",
+        ])
+        .env("RUST_LOG", "info")
+        .current_dir(get_working_dir_path())
+        .output()
+        .expect("Couldn't start Rojo");
+    assert!(
+        output.status.success(),
+        "build failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let dom = rbx_binary::from_reader(output.stdout.as_slice())
+        .expect("stdout should contain only the binary model");
+    let root = dom.get_by_ref(dom.root().children()[0]).unwrap();
+    assert_eq!(root.name, "piped");
+    let main = dom.get_by_ref(root.children()[0]).unwrap();
+    assert_eq!(main.name, "main");
+    assert_eq!(main.class.as_str(), "ModuleScript");
+}
+
+#[test]
+fn build_to_stdout_requires_format() {
+    let output = atlas_command()
+        .args([
+            "build",
+            Path::new(BUILD_TESTS_PATH)
+                .join("module_init")
+                .to_str()
+                .unwrap(),
+            "--output",
+            "-",
+        ])
+        .current_dir(get_working_dir_path())
+        .output()
+        .expect("Couldn't start Rojo");
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--format"));
+}
+
+#[test]
+fn ignore_unknown_properties_drops_them() {
+    let project = Path::new(BUILD_TESTS_PATH).join("unknown_property");
+    let build = |extra_args: &[&str]| {
+        atlas_command()
+            .args([
+                "build",
+                project.to_str().unwrap(),
+                "--output",
+                "-",
+                "--format",
+                "rbxm",
+            ])
+            .args(extra_args)
+            .current_dir(get_working_dir_path())
+            .output()
+            .expect("Couldn't start Rojo")
+    };
+
+    let output = build(&[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("NotARealProperty"));
+
+    let output = build(&["--ignore-unknown-properties"]);
+    assert!(
+        output.status.success(),
+        "build failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let dom = rbx_binary::from_reader(output.stdout.as_slice())
+        .expect("stdout should contain only the binary model");
+    let root = dom.get_by_ref(dom.root().children()[0]).unwrap();
+    let part = dom.get_by_ref(root.children()[0]).unwrap();
+    assert_eq!(part.class.as_str(), "Part");
+    assert!(!part
+        .properties
+        .contains_key(&rbx_dom_weak::ustr("NotARealProperty")));
+    assert!(part
+        .properties
+        .contains_key(&rbx_dom_weak::ustr("Anchored")));
+}
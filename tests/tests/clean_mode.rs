@@ -5,6 +5,7 @@
 //! 2. Clean mode doesn't remove files that are being written to
 //! 3. Clean mode doesn't remove parent directories of files being written
 //! 4. Incremental mode preserves existing file structure
+//! 5. `--only-new` never removes files, and warns about files it leaves behind
 
 use std::path::Path;
 
@@ -16,6 +17,12 @@ const SYNCBACK_TESTS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/rojo-tes
 
 /// Helper to run syncback and return success status
 fn run_syncback(project_path: &Path, input_path: &Path, incremental: bool) -> bool {
+    let extra_args: &[&str] = if incremental { &["--incremental"] } else { &[] };
+    run_syncback_with_args(project_path, input_path, extra_args)
+}
+
+/// Helper to run syncback with additional arguments and return success status
+fn run_syncback_with_args(project_path: &Path, input_path: &Path, extra_args: &[&str]) -> bool {
     let mut args = vec![
         "--color",
         "never",
@@ -24,10 +31,7 @@ fn run_syncback(project_path: &Path, input_path: &Path, incremental: bool) -> bo
         "--input",
         input_path.to_str().unwrap(),
     ];
-
-    if incremental {
-        args.push("--incremental");
-    }
+    args.extend_from_slice(extra_args);
 
     let output = atlas_command()
         .args(args)
@@ -197,6 +201,61 @@ fn incremental_mode_preserves_structure() {
     );
 }
 
+/// Test that `--only-new` leaves files for Instances missing from the input,
+/// which clean mode removes.
+#[test]
+fn only_new_never_removes_files() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let source_path = Path::new(SYNCBACK_TESTS_PATH)
+        .join("sync_rules")
+        .join("input-project");
+    let input_file = Path::new(SYNCBACK_TESTS_PATH)
+        .join("sync_rules")
+        .join("input.rbxm");
+
+    for only_new in [true, false] {
+        let test_dir = tempdir().expect("Couldn't create temporary directory");
+        let project_path = test_dir.path().join("test_project");
+
+        fs_err::create_dir(&project_path).expect("Couldn't create project directory");
+        copy_recursive(&source_path, &project_path).expect("Couldn't copy project");
+
+        // Not in the input, so this is an Instance the place doesn't have.
+        let extra = project_path.join("src").join("NotInPlace.luau");
+        fs_err::write(&extra, "return nil\n").unwrap();
+
+        let extra_args: &[&str] = if only_new { &["--only-new"] } else { &[] };
+        assert!(
+            run_syncback_with_args(&project_path, &input_file, extra_args),
+            "Syncback should succeed"
+        );
+
+        assert!(
+            project_path.join("src").join("module.luau").exists(),
+            "module.luau should be created by syncback"
+        );
+        if only_new {
+            assert!(
+                extra.exists(),
+                "NotInPlace.luau should be kept with --only-new"
+            );
+            assert!(
+                project_path
+                    .join("src")
+                    .join("module.modulescript")
+                    .exists(),
+                "module.modulescript should be kept with --only-new"
+            );
+        } else {
+            assert!(
+                !extra.exists(),
+                "NotInPlace.luau should be removed in clean mode"
+            );
+        }
+    }
+}
+
 /// Test that clean mode uses fresh file extensions (not preserving old ones)
 #[test]
 fn clean_mode_uses_fresh_extensions() {
@@ -380,3 +439,183 @@ fn clean_mode_preserves_hidden_directories() {
         ".hidden/nested/deep.txt should survive"
     );
 }
+
+/// Test that `--backup-dir` copies the pre-syncback contents of every `$path`
+/// before clean mode removes orphaned files.
+#[test]
+fn clean_mode_backup_contains_previous_contents() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let source_path = Path::new(SYNCBACK_TESTS_PATH)
+        .join("sync_rules")
+        .join("input-project");
+    let input_file = Path::new(SYNCBACK_TESTS_PATH)
+        .join("sync_rules")
+        .join("input.rbxm");
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("test_project");
+    let backup_root = test_dir.path().join("backups");
+
+    fs_err::create_dir(&project_path).expect("Couldn't create project directory");
+    copy_recursive(&source_path, &project_path).expect("Couldn't copy project");
+
+    let original_module =
+        fs_err::read_to_string(project_path.join("src").join("module.modulescript")).unwrap();
+    let original_text = fs_err::read_to_string(project_path.join("src").join("text.text")).unwrap();
+
+    assert!(
+        run_syncback_with_args(
+            &project_path,
+            &input_file,
+            &["--backup-dir", backup_root.to_str().unwrap()],
+        ),
+        "Syncback should succeed"
+    );
+
+    assert!(
+        !project_path
+            .join("src")
+            .join("module.modulescript")
+            .exists(),
+        "module.modulescript should be removed in clean mode"
+    );
+
+    // Backups are stored as <backup dir>/<project name>/<timestamp>/
+    let project_backups: Vec<_> = fs_err::read_dir(backup_root.join("sync_rules"))
+        .expect("backup directory for the project should exist")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(
+        project_backups.len(),
+        1,
+        "exactly one timestamped backup should be created"
+    );
+    let backup = &project_backups[0];
+
+    assert!(
+        backup.join("default.project.json5").is_file(),
+        "project file should be backed up"
+    );
+    assert_eq!(
+        fs_err::read_to_string(backup.join("src").join("module.modulescript")).unwrap(),
+        original_module,
+        "backup should contain the pre-syncback module.modulescript"
+    );
+    assert_eq!(
+        fs_err::read_to_string(backup.join("src").join("text.text")).unwrap(),
+        original_text,
+        "backup should contain the pre-syncback text.text"
+    );
+    assert!(
+        !backup.join("src").join("module.luau").exists(),
+        "backup should not contain files written by syncback"
+    );
+}
+
+/// Test that back-to-back backups each get their own directory, even when
+/// they land in the same second.
+#[test]
+fn clean_mode_backups_in_the_same_second_are_kept() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let source_path = Path::new(SYNCBACK_TESTS_PATH)
+        .join("sync_rules")
+        .join("input-project");
+    let input_file = Path::new(SYNCBACK_TESTS_PATH)
+        .join("sync_rules")
+        .join("input.rbxm");
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("test_project");
+    let backup_root = test_dir.path().join("backups");
+
+    fs_err::create_dir(&project_path).expect("Couldn't create project directory");
+    copy_recursive(&source_path, &project_path).expect("Couldn't copy project");
+
+    for _ in 0..2 {
+        assert!(
+            run_syncback_with_args(
+                &project_path,
+                &input_file,
+                &["--backup-dir", backup_root.to_str().unwrap()],
+            ),
+            "Syncback should succeed"
+        );
+    }
+
+    let project_backups = fs_err::read_dir(backup_root.join("sync_rules"))
+        .expect("backup directory for the project should exist")
+        .count();
+    assert_eq!(project_backups, 2, "each run should keep its own backup");
+}
+
+/// Test that `--only-new` warns about the file a script leaves behind when it
+/// gains children and becomes a directory, since it can't remove that file.
+#[test]
+fn only_new_warns_about_file_replaced_by_directory() {
+    use rbx_dom_weak::{InstanceBuilder, WeakDom};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("test_project");
+    fs_err::create_dir_all(project_path.join("src")).expect("Couldn't create project directory");
+    fs_err::write(
+        project_path.join("default.project.json5"),
+        r#"{
+            "name": "test_project",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": { "$path": "src" }
+            }
+        }"#,
+    )
+    .unwrap();
+    fs_err::write(project_path.join("src/Shared.luau"), "return 1").unwrap();
+
+    let mut place = WeakDom::new(InstanceBuilder::new("DataModel"));
+    let replicated_storage =
+        place.insert(place.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+    let shared = place.insert(
+        replicated_storage,
+        InstanceBuilder::new("ModuleScript")
+            .with_name("Shared")
+            .with_property("Source", "return 1"),
+    );
+    place.insert(
+        shared,
+        InstanceBuilder::new("ModuleScript")
+            .with_name("Child")
+            .with_property("Source", "return 2"),
+    );
+    let input_path = test_dir.path().join("input.rbxl");
+    let mut input = Vec::new();
+    rbx_binary::to_writer(&mut input, &place, &[replicated_storage]).unwrap();
+    fs_err::write(&input_path, input).unwrap();
+
+    let output = atlas_command()
+        .args([
+            "--color",
+            "never",
+            "syncback",
+            project_path.to_str().unwrap(),
+            "--input",
+            input_path.to_str().unwrap(),
+            "--only-new",
+        ])
+        .output()
+        .expect("Couldn't spawn syncback process");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "Syncback failed: {stderr}");
+
+    assert!(project_path.join("src/Shared/Child.luau").is_file());
+    assert!(
+        project_path.join("src/Shared.luau").is_file(),
+        "Shared.luau should be kept with --only-new"
+    );
+    assert!(
+        stderr.contains("Shared.luau in place because removals are disabled"),
+        "the leftover file should be reported: {stderr}"
+    );
+}
@@ -0,0 +1,84 @@
+use std::{path::Path, process::Output};
+
+use tempfile::tempdir;
+
+use crate::rojo_test::io_util::{atlas_command, SYNCBACK_TESTS_PATH};
+
+fn clone_subtree(project_path: &Path, subtree: &str) -> Output {
+    let input = Path::new(SYNCBACK_TESTS_PATH)
+        .join("include_trees")
+        .join("input.rbxl");
+
+    atlas_command()
+        .args([
+            "--color",
+            "never",
+            "clone",
+            "1",
+            "--path",
+            project_path.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+            "--subtree",
+            subtree,
+            "--skip-git",
+            "--skip-rules",
+            "--skip-cd",
+        ])
+        .output()
+        .expect("Couldn't spawn clone process")
+}
+
+#[test]
+fn subtree_clone_only_writes_the_subtree() {
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("cloned");
+
+    let output = clone_subtree(&project_path, "ReplicatedStorage/OnlyOneCopy");
+    assert!(
+        output.status.success(),
+        "Clone failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let src = project_path.join("src");
+    assert!(src
+        .join("ReplicatedStorage/OnlyOneCopy/child_of_one.luau")
+        .is_file());
+    assert!(!src
+        .join("ReplicatedStorage/child_replicated_storage.luau")
+        .exists());
+
+    // Everything else in the place is left out, so src holds only the
+    // folders leading to the subtree.
+    let top_level: Vec<_> = fs_err::read_dir(&src)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .filter(|name| !name.to_string_lossy().starts_with('.'))
+        .collect();
+    assert_eq!(top_level, ["ReplicatedStorage"]);
+
+    // Later syncbacks stay scoped to the subtree.
+    let project = fs_err::read_to_string(project_path.join("default.project.json5")).unwrap();
+    assert!(project.contains("includeTrees"), "{project}");
+    assert!(
+        project.contains("ReplicatedStorage/OnlyOneCopy"),
+        "{project}"
+    );
+}
+
+#[test]
+fn subtree_clone_lists_siblings_of_a_missing_path() {
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("cloned");
+
+    let output = clone_subtree(&project_path, "ReplicatedStorage/Missing");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "Clone should have failed");
+    assert!(
+        stderr.contains("ReplicatedStorage has no child named 'Missing'"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("OnlyOneCopy"), "{stderr}");
+    assert!(!project_path.join("src/ReplicatedStorage").exists());
+}
@@ -17,7 +17,7 @@ use crate::rojo_test::{
     serve_util::{assert_round_trip, get_message_cursor, run_serve_test},
 };
 
-use librojo::web_api::SocketPacketType;
+use librojo::web_api::{SocketPacketBody, SocketPacketType};
 
 // ---------------------------------------------------------------------------
 // Phase 1: Format Transitions
@@ -285,6 +285,47 @@ fn create_edit_rename_delete_sequence() {
     });
 }
 
+/// Renaming a file keeps its Instance: the plugin gets a name change for the
+/// same ID instead of a removal and an addition.
+#[test]
+fn rename_keeps_instance_id() {
+    run_serve_test("connected_scripts", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        let read_response = session.get_api_read(info.root_instance_id).unwrap();
+        let id = read_response
+            .instances
+            .values()
+            .find(|instance| instance.name == "standalone")
+            .map(|instance| instance.id)
+            .expect("standalone should be in the tree");
+
+        let src = session.path().join("src");
+        let packet = session
+            .recv_socket_packet(SocketPacketType::Messages, 0, || {
+                fs::rename(src.join("standalone.luau"), src.join("Renamed.luau")).unwrap();
+            })
+            .unwrap();
+
+        let SocketPacketBody::Messages(body) = packet.body;
+        for message in &body.messages {
+            assert!(
+                message.removed.is_empty() && message.added.is_empty(),
+                "The rename should not remove or add Instances"
+            );
+        }
+        let update = body
+            .messages
+            .iter()
+            .flat_map(|message| &message.updated)
+            .find(|update| update.id == id)
+            .expect("The renamed Instance should be updated");
+        assert_eq!(update.changed_name.as_deref(), Some("Renamed"));
+
+        let read_response = session.get_api_read(id).unwrap();
+        assert_eq!(read_response.instances[&id].name, "Renamed");
+    });
+}
+
 /// Test 7: Edit multiple files sequentially, verify each patch only
 /// contains the changed file (no cross-contamination).
 #[test]
@@ -850,6 +891,7 @@ fn api_write_then_filesystem_change() {
                 changed_metadata: None,
             }],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         std::thread::sleep(std::time::Duration::from_millis(500));
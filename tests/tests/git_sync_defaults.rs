@@ -417,6 +417,7 @@ fn stage_ids_in_write_request_triggers_git_add() {
         added: HashMap::new(),
         updated: vec![],
         stage_ids: vec![module_id],
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
 
@@ -445,6 +446,7 @@ fn empty_stage_ids_does_not_stage_anything() {
         added: HashMap::new(),
         updated: vec![],
         stage_ids: vec![],
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
     thread::sleep(Duration::from_millis(300));
@@ -475,6 +477,7 @@ fn stage_ids_only_stages_specified_files() {
         added: HashMap::new(),
         updated: vec![],
         stage_ids: vec![module_id],
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
     thread::sleep(Duration::from_millis(500));
@@ -525,6 +528,7 @@ fn source_write_with_stage_ids_stages_after_write() {
             changed_metadata: None,
         }],
         stage_ids: vec![module_id],
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
 
@@ -577,6 +581,7 @@ fn source_write_without_stage_ids_does_not_stage() {
             changed_metadata: None,
         }],
         stage_ids: vec![],
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
     thread::sleep(Duration::from_millis(1000));
@@ -670,6 +675,7 @@ fn init_style_script_staging_targets_init_file() {
             added: HashMap::new(),
             updated: vec![],
             stage_ids: vec![dir_id],
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -755,6 +761,7 @@ fn batch_stage_20_files_no_index_lock_race() {
         added: HashMap::new(),
         updated: vec![],
         stage_ids,
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
 
@@ -807,6 +814,7 @@ fn batch_source_write_20_files_all_staged() {
         added: HashMap::new(),
         updated: updates,
         stage_ids,
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
 
@@ -887,6 +895,7 @@ fn batch_mixed_stage_and_source_write() {
         added: HashMap::new(),
         updated: updates,
         stage_ids,
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
 
@@ -937,6 +946,7 @@ fn stage_ids_stages_new_path_after_rename() {
             changed_metadata: None,
         }],
         stage_ids: vec![module_id],
+        dry_run: false,
     };
     session.post_api_write(&write_request).unwrap();
     thread::sleep(Duration::from_millis(1000));
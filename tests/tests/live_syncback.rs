@@ -1,4 +1,4 @@
-use std::{fs, thread, time::Duration};
+use std::{fs, sync::Barrier, thread, time::Duration};
 
 use rbx_dom_weak::InstanceBuilder;
 
@@ -6,7 +6,7 @@ use crate::rojo_test::{
     roundtrip_util::assert_dirs_equal,
     serve_util::{
         build_syncback_request, make_service_chunk, make_service_chunk_full,
-        run_cli_syncback_on_chunks, run_serve_test, ServiceEntry,
+        run_cli_syncback_on_chunks, run_serve_test, ServiceEntry, TestServeSession,
     },
 };
 
@@ -948,6 +948,164 @@ fn recovers_from_malformed_chunks() {
     });
 }
 
+// ── Syncback without restarting ────────────────────────────────
+
+#[test]
+fn mcp_syncback_keeps_server_running() {
+    let (data, chunks) = build_syncback_request(vec![make_service_chunk(
+        "ReplicatedStorage",
+        vec![InstanceBuilder::new("ModuleScript")
+            .with_name("KeptAlive")
+            .with_property(
+                "Source",
+                rbx_dom_weak::types::Variant::String("return {}".into()),
+            )],
+    )]);
+
+    run_serve_test("live_syncback", |session, _| {
+        let initial_info = session.get_api_rojo().unwrap();
+
+        let (status, summary) = session.post_api_mcp_syncback(data, chunks);
+        assert!(status.is_success(), "Syncback failed: {summary}");
+        assert_eq!(summary["status"], "syncback_complete");
+        assert!(
+            summary["added"].as_u64().unwrap() >= 1,
+            "Summary should count the written script: {summary}"
+        );
+        assert!(summary["removed"].as_u64().is_some(), "{summary}");
+
+        let script = session.path().join("src/shared/KeptAlive.luau");
+        assert_eq!(fs::read_to_string(script).unwrap().trim_end(), "return {}");
+
+        let info = session.get_api_rojo().unwrap();
+        assert_eq!(
+            initial_info.session_id, info.session_id,
+            "Server should still be running the same session"
+        );
+    });
+}
+
+#[test]
+fn syncback_keep_running_skips_restart() {
+    let (data, chunks) = build_syncback_request(vec![make_service_chunk(
+        "ReplicatedStorage",
+        vec![InstanceBuilder::new("Folder").with_name("InPlaceFolder")],
+    )]);
+
+    run_serve_test("live_syncback", |session, _| {
+        let initial_info = session.get_api_rojo().unwrap();
+
+        let (status, summary) = session.post_api_syncback_keep_running(data, chunks);
+        assert!(status.is_success(), "Syncback failed: {summary}");
+        assert_eq!(summary["status"], "syncback_complete");
+        assert!(summary["added"].as_u64().unwrap() >= 1, "{summary}");
+        assert!(session.path().join("src/shared/InPlaceFolder").is_dir());
+
+        let info = session.get_api_rojo().unwrap();
+        assert_eq!(
+            initial_info.session_id, info.session_id,
+            "Server should still be running the same session"
+        );
+    });
+}
+
+#[test]
+fn concurrent_in_place_syncbacks_conflict() {
+    let (data, chunks) = build_syncback_request(vec![make_service_chunk(
+        "ReplicatedStorage",
+        (0..200)
+            .map(|index| InstanceBuilder::new("Folder").with_name(format!("Folder{index}")))
+            .collect(),
+    )]);
+
+    run_serve_test("live_syncback", |session, _| {
+        // Every request is sent at once, so they overlap the first one to
+        // get the guard. Those are turned away instead of writing the
+        // project at the same time.
+        let barrier = Barrier::new(4);
+        let results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4usize)
+                .map(|index| {
+                    let (session, barrier) = (&session, &barrier);
+                    let (data, chunks) = (data.clone(), chunks.clone());
+                    scope.spawn(move || {
+                        barrier.wait();
+                        if index.is_multiple_of(2) {
+                            session.post_api_syncback_keep_running(data, chunks)
+                        } else {
+                            session.post_api_mcp_syncback(data, chunks)
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        let completed = results
+            .iter()
+            .filter(|(status, _)| status.is_success())
+            .count();
+        let conflicts: Vec<_> = results
+            .iter()
+            .filter(|(status, _)| *status == reqwest::StatusCode::CONFLICT)
+            .collect();
+        assert!(completed >= 1, "{results:?}");
+        assert!(!conflicts.is_empty(), "{results:?}");
+        assert_eq!(completed + conflicts.len(), results.len(), "{results:?}");
+        for (_, body) in conflicts {
+            assert_eq!(body["kind"], "Conflict", "{body}");
+        }
+
+        assert!(session.path().join("src/shared/Folder199").is_dir());
+    });
+}
+
+// ── One-shot mode ──────────────────────────────────────────────
+
+#[test]
+fn once_exits_after_applying_syncback() {
+    let (data, chunks) = build_syncback_request(vec![make_service_chunk(
+        "ReplicatedStorage",
+        vec![InstanceBuilder::new("Folder").with_name("OnceFolder")],
+    )]);
+
+    let mut session = TestServeSession::new_with_args("live_syncback", &["--once"]);
+    session.wait_to_come_online();
+
+    session.post_api_syncback(None, data, chunks);
+    let status = session.wait_for_exit(Duration::from_secs(60));
+
+    assert!(status.success(), "serve --once exited with {status}");
+    assert!(
+        session.path().join("src/shared/OnceFolder").exists(),
+        "Syncback should have been applied before exiting"
+    );
+}
+
+#[test]
+fn once_exits_with_failure_on_bad_syncback() {
+    let bad_chunk = librojo::web_api::ServiceChunk {
+        class_name: "ReplicatedStorage".to_string(),
+        child_count: 999,
+        ref_target_count: 0,
+        properties: std::collections::HashMap::new(),
+        refs: std::collections::HashMap::new(),
+    };
+
+    let mut session = TestServeSession::new_with_args("live_syncback", &["--once"]);
+    session.wait_to_come_online();
+
+    let body = build_raw_syncback_body(None, &[], &[bad_chunk]);
+    let response = session.post_api_syncback_raw(body);
+    assert!(response.status().is_success());
+
+    let status = session.wait_for_exit(Duration::from_secs(60));
+    assert!(!status.success(), "serve --once should report the failure");
+}
+
 fn build_raw_syncback_body(
     place_id: Option<u64>,
     data: &[u8],
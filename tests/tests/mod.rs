@@ -1,13 +1,17 @@
 mod build;
 mod clean_mode;
 mod clean_mode_stress;
+mod clone;
 mod connected_mode;
 mod git_sync_defaults;
 mod live_syncback;
 mod matching_fixtures;
+mod project_schema;
 mod scripts_only_pruning;
 mod serve;
+mod sourcemap;
 mod syncback;
 mod syncback_format_transitions;
 mod syncback_roundtrip;
 mod two_way_sync;
+mod validate;
@@ -0,0 +1,27 @@
+use crate::rojo_test::io_util::atlas_command;
+
+#[test]
+fn doc_schema_prints_project_schema() {
+    let output = atlas_command()
+        .args(["--color", "never", "doc", "schema"])
+        .output()
+        .expect("Couldn't spawn doc process");
+    assert!(
+        output.status.success(),
+        "doc schema failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let schema: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("the schema should be valid JSON");
+    let properties = schema["properties"]
+        .as_object()
+        .expect("the schema should describe the project's properties");
+    for key in ["tree", "syncbackRules", "globIgnorePaths"] {
+        assert!(properties.contains_key(key), "missing {key}");
+    }
+    assert_eq!(
+        schema["$defs"]["projectNode"]["properties"]["$path"]["anyOf"][0]["type"],
+        "string"
+    );
+}
@@ -158,6 +158,7 @@ fn scripts_only_write_filters_non_script_updates() {
                 changed_metadata: None,
             }],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
 
         session
@@ -305,6 +306,8 @@ fn scripts_only_write_resolves_intermediate_temp_ids() {
             class_name: "Folder".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut script_props = HashMap::new();
@@ -318,6 +321,8 @@ fn scripts_only_write_resolves_intermediate_temp_ids() {
             class_name: "ModuleScript".to_string(),
             properties: script_props,
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut added_map = HashMap::new();
@@ -330,6 +335,7 @@ fn scripts_only_write_resolves_intermediate_temp_ids() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
 
         session
@@ -381,6 +387,8 @@ fn normal_mode_addition_works_without_phase0() {
             class_name: "Script".to_string(),
             properties: props,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(temp_ref, added);
@@ -391,6 +399,7 @@ fn normal_mode_addition_works_without_phase0() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
 
         session
@@ -438,6 +447,8 @@ fn scripts_only_phase0_resolves_matching_class() {
             class_name: "Folder".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut script_props = HashMap::new();
@@ -451,6 +462,8 @@ fn scripts_only_phase0_resolves_matching_class() {
             class_name: "ModuleScript".to_string(),
             properties: script_props,
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut added_map = HashMap::new();
@@ -463,6 +476,7 @@ fn scripts_only_phase0_resolves_matching_class() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
 
         session
@@ -1,4 +1,9 @@
-use std::fs;
+use std::{
+    fs,
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
 
 use insta::{assert_snapshot, assert_yaml_snapshot, with_settings};
 use tempfile::tempdir;
@@ -8,7 +13,9 @@ use crate::rojo_test::{
     serve_util::{run_serve_test, serialize_to_xml_model, TestServeSession},
 };
 
-use librojo::web_api::SocketPacketType;
+use librojo::{
+    snapshot::InstigatingSource, web_api::SocketPacketType, InitialSyncMode, Middleware, RojoTree,
+};
 
 #[test]
 fn empty() {
@@ -63,6 +70,46 @@ fn scripts() {
     });
 }
 
+#[test]
+fn snapshot_dump_includes_metadata() {
+    run_serve_test("scripts", |session, _| {
+        let snapshot = session.get_api_snapshot().unwrap();
+        assert_eq!(snapshot.name, "scripts");
+        assert!(matches!(
+            snapshot.metadata.instigating_source,
+            Some(InstigatingSource::ProjectNode { .. })
+        ));
+
+        let foo = snapshot
+            .children
+            .iter()
+            .find(|child| child.name == "foo")
+            .expect("foo should be in the dumped tree");
+        assert_eq!(foo.class_name.as_str(), "ModuleScript");
+        assert_eq!(foo.metadata.middleware, Some(Middleware::ModuleScript));
+        match &foo.metadata.instigating_source {
+            Some(InstigatingSource::Path(path)) => assert!(path.ends_with("src/foo.luau")),
+            other => panic!("foo should come from a path, got {other:?}"),
+        }
+        assert!(foo
+            .metadata
+            .relevant_paths
+            .iter()
+            .any(|path| path.ends_with("src/foo.luau")));
+
+        let bar = snapshot
+            .children
+            .iter()
+            .find(|child| child.name == "bar")
+            .expect("bar should be in the dumped tree");
+        assert_eq!(bar.class_name.as_str(), "Script");
+
+        // The dump can be reloaded into a tree.
+        let tree = RojoTree::new(snapshot);
+        assert_eq!(tree.root().children().len(), 2);
+    });
+}
+
 #[test]
 fn add_folder() {
     run_serve_test("add_folder", |session, mut redactions| {
@@ -364,6 +411,21 @@ fn no_name_project() {
     });
 }
 
+/// The info response names the project next to the session ID, so clients
+/// talking to several servers can tell them apart.
+#[test]
+fn info_includes_project_name() {
+    run_serve_test("empty", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        assert_eq!(info.project_name, "empty");
+
+        let mut other = TestServeSession::new("no_name_project");
+        let other_info = other.wait_to_come_online();
+        assert_eq!(other_info.project_name, "no_name_project");
+        assert_ne!(info.session_id, other_info.session_id);
+    });
+}
+
 #[test]
 fn no_name_top_level_project() {
     run_serve_test("no_name_top_level_project", |session, mut redactions| {
@@ -774,7 +836,11 @@ fn api_write_existing_instance() {
                     p
                 },
                 children: vec![],
+                target_path: None,
+                id: None,
             }],
+            target_path: None,
+            id: None,
         };
 
         let instance_ref = rbx_dom_weak::types::Ref::new();
@@ -787,6 +853,7 @@ fn api_write_existing_instance() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
 
         session
@@ -1013,3 +1080,451 @@ fn ref_path_multiple_attributes() {
         assert_yaml_snapshot!("ref_path_multiple_attrs_patch", redacted);
     });
 }
+
+/// Sends a bare request for `/api/rojo` over `stream` and returns the status
+/// line of the response.
+fn raw_status_line(stream: &mut TcpStream) -> String {
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    stream
+        .write_all(b"GET /api/rojo HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+
+    let mut response = Vec::new();
+    let mut buffer = [0; 1024];
+    while !response.windows(2).any(|window| window == b"\r\n") {
+        let read = stream.read(&mut buffer).unwrap();
+        if read == 0 {
+            break;
+        }
+        response.extend_from_slice(&buffer[..read]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    response.lines().next().unwrap_or_default().to_owned()
+}
+
+#[test]
+fn max_connections_rejects_excess() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut session = TestServeSession::new_with_args("empty", &["--max-connections", "2"]);
+    let _info = session.wait_to_come_online();
+    let address = format!("127.0.0.1:{}", session.port());
+
+    // Hold every allowed connection open. The client used to check that the
+    // server came online may not have hung up yet, so only the connection
+    // past the limit is checked.
+    let held: Vec<TcpStream> = (0..2)
+        .map(|_| TcpStream::connect(&address).unwrap())
+        .collect();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let mut excess = TcpStream::connect(&address).unwrap();
+    let status = raw_status_line(&mut excess);
+    assert!(
+        status.starts_with("HTTP/1.1 503"),
+        "expected a 503, got {status:?}"
+    );
+
+    // Once the held connections close, new ones are accepted again.
+    drop(held);
+    let mut status = String::new();
+    for _ in 0..50 {
+        let mut stream = TcpStream::connect(&address).unwrap();
+        status = raw_status_line(&mut stream);
+        if status.starts_with("HTTP/1.1 200") {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert!(
+        status.starts_with("HTTP/1.1 200"),
+        "expected a 200, got {status:?}"
+    );
+}
+
+#[test]
+fn auth_token_is_required() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let session = TestServeSession::new_with_args("empty", &["--auth-token", "secret"]);
+
+    // `wait_to_come_online` doesn't send a token, so wait for the server here.
+    let mut info = None;
+    for _ in 0..50 {
+        if let Ok(response) = session.get_api_rojo_with_token("secret") {
+            info = Some(response);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let info = info.expect("server did not come online");
+    assert_eq!(info.auth_token.as_deref(), Some("secret"));
+
+    let client = reqwest::blocking::Client::new();
+    let api_url = format!("http://localhost:{}/api/rojo", session.port());
+    let mcp_url = format!("http://localhost:{}/mcp", session.port());
+    let mcp_body = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#;
+
+    let missing = client.get(&api_url).send().unwrap();
+    assert_eq!(missing.status().as_u16(), 401);
+    let wrong = client.get(&api_url).bearer_auth("wrong").send().unwrap();
+    assert_eq!(wrong.status().as_u16(), 401);
+
+    let write = client
+        .post(format!("http://localhost:{}/api/write", session.port()))
+        .body(Vec::new())
+        .send()
+        .unwrap();
+    assert_eq!(write.status().as_u16(), 401);
+
+    let health = client
+        .get(format!("http://localhost:{}/api/health", session.port()))
+        .send()
+        .unwrap();
+    assert_eq!(health.status().as_u16(), 200);
+
+    let mcp_missing = client.post(&mcp_url).body(mcp_body).send().unwrap();
+    assert_eq!(mcp_missing.status().as_u16(), 401);
+    let mcp_ok = client
+        .post(&mcp_url)
+        .bearer_auth("secret")
+        .body(mcp_body)
+        .send()
+        .unwrap();
+    assert_eq!(mcp_ok.status().as_u16(), 200);
+}
+
+#[test]
+fn initial_syncback_mode_is_reported() {
+    let mut session = TestServeSession::new("empty");
+    let info = session.wait_to_come_online();
+    assert_eq!(info.initial_sync_mode, InitialSyncMode::Push);
+
+    let mut session = TestServeSession::new_with_args("empty", &["--initial-syncback", "merge"]);
+    let info = session.wait_to_come_online();
+    assert_eq!(info.initial_sync_mode, InitialSyncMode::Merge);
+}
+
+#[test]
+fn health_reports_ready_tree() {
+    let session = TestServeSession::new("scripts");
+
+    let mut health = None;
+    for _ in 0..50 {
+        if let Ok(response) = session.get_api_health() {
+            if response.ready {
+                health = Some(response);
+                break;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let health = health.expect("server never reported ready");
+
+    let info = session.get_api_rojo().unwrap();
+    assert_eq!(health.session_id, info.session_id);
+
+    let read = session.get_api_read(info.root_instance_id).unwrap();
+    assert_eq!(read.message_cursor, health.message_cursor);
+    assert!(read.instances.len() > 1, "tree should contain the scripts");
+}
+
+#[test]
+fn large_responses_are_gzipped() {
+    use flate2::read::GzDecoder;
+    use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+
+    let mut session = TestServeSession::new_with_args("scripts", &["--gzip-threshold", "64"]);
+    session.wait_to_come_online();
+
+    let url = format!("http://localhost:{}/api/snapshot", session.port());
+    let client = reqwest::blocking::Client::builder()
+        .no_gzip()
+        .build()
+        .unwrap();
+
+    let plain = client.get(&url).send().unwrap();
+    assert!(plain.headers().get(CONTENT_ENCODING).is_none());
+    let plain: serde_json::Value = serde_json::from_slice(&plain.bytes().unwrap()).unwrap();
+
+    let gzipped = client
+        .get(&url)
+        .header(ACCEPT_ENCODING, "gzip")
+        .send()
+        .unwrap();
+    assert_eq!(
+        gzipped.headers().get(CONTENT_ENCODING).unwrap(),
+        "gzip",
+        "a response above the threshold should be gzipped"
+    );
+
+    let mut decoded = Vec::new();
+    GzDecoder::new(gzipped.bytes().unwrap().as_ref())
+        .read_to_end(&mut decoded)
+        .unwrap();
+    let decoded: serde_json::Value = serde_json::from_slice(&decoded).unwrap();
+    assert_eq!(decoded, plain);
+}
+
+#[test]
+fn independent_file_writes_are_all_applied() {
+    use std::{collections::HashMap, thread, time::Instant};
+
+    use rbx_dom_weak::{types::Variant, ustr};
+
+    const FOLDERS: usize = 16;
+
+    let mut session = TestServeSession::new("scripts");
+    let info = session.wait_to_come_online();
+    let src = session.path().join("src");
+
+    // Maps each folder's name to the Source of the module inside it.
+    let sources = || -> HashMap<String, String> {
+        let read = session.get_api_read(info.root_instance_id).unwrap();
+        read.instances
+            .values()
+            .filter(|instance| instance.name == "Module")
+            .filter_map(|instance| {
+                let folder = read.instances.get(&instance.parent)?;
+                match instance.properties.get(&ustr("Source")).map(|v| v.as_ref()) {
+                    Some(Variant::String(source)) => {
+                        Some((folder.name.to_string(), source.clone()))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    };
+    let wait_for_sources = |expected: &dyn Fn(usize) -> String| {
+        let start = Instant::now();
+        loop {
+            let current = sources();
+            let done = (0..FOLDERS).all(|i| {
+                current.get(&format!("Folder{i}")).map(String::as_str) == Some(expected(i).as_str())
+            });
+            if done {
+                return;
+            }
+            assert!(
+                start.elapsed() < Duration::from_secs(15),
+                "not every write was applied: {current:?}"
+            );
+            thread::sleep(Duration::from_millis(50));
+        }
+    };
+
+    for i in 0..FOLDERS {
+        let dir = src.join(format!("Folder{i}"));
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("Module.luau"), "return 0").unwrap();
+    }
+    wait_for_sources(&|_| "return 0".to_owned());
+
+    // Rewrite every module from its own thread so the events for the
+    // unrelated folders tend to arrive in the same batch. Whether the groups
+    // overlap is covered by the change processor's unit tests; this checks
+    // that none of the writes are lost when they do.
+    thread::scope(|scope| {
+        for i in 0..FOLDERS {
+            let path = src.join(format!("Folder{i}")).join("Module.luau");
+            scope.spawn(move || fs::write(path, format!("return {i}")).unwrap());
+        }
+    });
+    wait_for_sources(&|i| format!("return {i}"));
+
+    session.assert_tree_fresh();
+}
+
+#[test]
+fn port_range_picks_next_free_port() {
+    use std::process::Stdio;
+
+    use crate::rojo_test::io_util::{atlas_command, get_working_dir_path, KillOnDrop};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut first = TestServeSession::new("empty");
+    first.wait_to_come_online();
+    let taken = first.port() as u16;
+
+    let range_end = taken.saturating_add(10);
+    let expected = (taken + 1..=range_end)
+        .find(|port| std::net::TcpListener::bind(("127.0.0.1", *port)).is_ok())
+        .expect("no free port after the first session's port");
+
+    let child = atlas_command()
+        .args([
+            "serve",
+            first.path().to_str().unwrap(),
+            "--port",
+            &taken.to_string(),
+            "--port-range",
+            &format!("{taken}-{range_end}"),
+        ])
+        .current_dir(get_working_dir_path())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't start Rojo");
+    let _second = KillOnDrop(child);
+
+    let url = format!("http://localhost:{expected}/api/rojo");
+    let mut online = false;
+    for _ in 0..50 {
+        if reqwest::blocking::get(&url).is_ok_and(|response| response.status().is_success()) {
+            online = true;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert!(
+        online,
+        "second session never came online on port {expected}"
+    );
+
+    // The first session keeps its port.
+    first.get_api_rojo().unwrap();
+}
+
+#[test]
+fn shutdown_endpoint_exits_cleanly() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut session = TestServeSession::new("empty");
+    let info = session.wait_to_come_online();
+
+    let status = session.post_api_shutdown(info.session_id).unwrap();
+    assert!(status.is_success(), "shutdown was rejected with {status}");
+
+    let exit_status = session.wait_for_exit(Duration::from_secs(15));
+    assert!(
+        exit_status.success(),
+        "atlas serve exited with {exit_status}"
+    );
+}
+
+#[test]
+fn shutdown_requires_session_id() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut session = TestServeSession::new("empty");
+    session.wait_to_come_online();
+
+    let status = session
+        .post_api_shutdown(librojo::SessionId::new())
+        .unwrap();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+
+    let status = reqwest::blocking::Client::new()
+        .post(format!("http://localhost:{}/api/shutdown", session.port()))
+        .send()
+        .unwrap()
+        .status();
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+
+    assert!(session
+        .try_wait_for_exit(Duration::from_millis(500))
+        .is_none());
+}
+
+#[test]
+fn shutdown_requires_auth_token() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut session = TestServeSession::new_with_args("empty", &["--auth-token", "secret"]);
+    let mut info = None;
+    for _ in 0..50 {
+        if let Ok(response) = session.get_api_rojo_with_token("secret") {
+            info = Some(response);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let info = info.expect("server never came online");
+
+    let status = session.post_api_shutdown(info.session_id).unwrap();
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+    assert!(session
+        .try_wait_for_exit(Duration::from_millis(500))
+        .is_none());
+}
+
+#[test]
+fn write_rate_limit_rejects_bursts() {
+    use std::collections::HashMap;
+
+    use librojo::web_api::WriteRequest;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut session = TestServeSession::new_with_args("empty", &["--max-writes-per-sec", "5"]);
+    let info = session.wait_to_come_online();
+    let request = WriteRequest {
+        session_id: info.session_id,
+        removed: vec![],
+        added: HashMap::new(),
+        updated: vec![],
+        stage_ids: vec![],
+        dry_run: true,
+    };
+
+    // Requests sent with one client reuse its connection, so they share a
+    // rate limit.
+    let client = reqwest::blocking::Client::new();
+    let statuses: Vec<_> = (0..30)
+        .map(|_| {
+            let response = session.post_api_write_raw(&client, &request).unwrap();
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                assert!(
+                    response
+                        .headers()
+                        .contains_key(reqwest::header::RETRY_AFTER),
+                    "429 response is missing Retry-After"
+                );
+            }
+            let status = response.status();
+            // Reading the body lets the connection go back to the pool.
+            let _ = response.bytes();
+            status
+        })
+        .collect();
+    assert!(statuses.iter().any(|status| status.is_success()));
+    assert!(
+        statuses.contains(&reqwest::StatusCode::TOO_MANY_REQUESTS),
+        "a burst of writes was never limited: {statuses:?}"
+    );
+
+    std::thread::sleep(Duration::from_millis(1200));
+    for _ in 0..5 {
+        let response = session.post_api_write_raw(&client, &request).unwrap();
+        let status = response.status();
+        let _ = response.bytes();
+        assert!(status.is_success(), "write at a normal rate got {status}");
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+#[test]
+fn rapid_writes_are_coalesced() {
+    let mut session = TestServeSession::new("edit_init");
+    session.wait_to_come_online();
+
+    let start_cursor = session.get_api_health().unwrap().message_cursor;
+    let path = session.path().join("src/init.luau");
+    for index in 0..20 {
+        fs::write(&path, format!("-- Edit {index}")).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    // Long enough for the debounce window to close and the patch to be
+    // broadcast.
+    std::thread::sleep(Duration::from_secs(1));
+    let broadcasts = session.get_api_health().unwrap().message_cursor - start_cursor;
+    assert!(broadcasts > 0, "the writes were never synced");
+    assert!(
+        broadcasts < 5,
+        "20 rapid writes produced {broadcasts} patch broadcasts"
+    );
+}
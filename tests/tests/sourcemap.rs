@@ -0,0 +1,69 @@
+use std::{
+    fs,
+    process::Stdio,
+    thread,
+    time::{Duration, Instant},
+};
+
+use tempfile::tempdir;
+
+use crate::rojo_test::io_util::{atlas_command, get_working_dir_path, KillOnDrop};
+
+#[test]
+fn watch_updates_sourcemap_after_rename() {
+    let dir = tempdir().expect("couldn't create temporary directory");
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("Original.luau"), "return 1").unwrap();
+    fs::write(
+        dir.path().join("default.project.json5"),
+        r#"{ "name": "watched", "tree": { "$path": "src" } }"#,
+    )
+    .unwrap();
+
+    let output_path = dir.path().join("sourcemap.json");
+    // --watch exits once stdin closes, so it's piped and left open for the
+    // life of the process.
+    let child = atlas_command()
+        .args([
+            "sourcemap",
+            dir.path().to_str().unwrap(),
+            "--watch",
+            "-o",
+            output_path.to_str().unwrap(),
+        ])
+        .env("RUST_LOG", "error")
+        .current_dir(get_working_dir_path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't start Rojo");
+    let _child = KillOnDrop(child);
+
+    let wait_for_child_names = |expected: &[&str]| {
+        let start = Instant::now();
+        let mut last = None;
+        while start.elapsed() < Duration::from_secs(10) {
+            if let Ok(contents) = fs::read_to_string(&output_path) {
+                let sourcemap: serde_json::Value = serde_json::from_str(&contents).unwrap();
+                let names: Vec<String> = sourcemap["children"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|child| child["name"].as_str().map(str::to_owned))
+                    .collect();
+                if names == expected {
+                    return;
+                }
+                last = Some(names);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!("sourcemap children never became {expected:?}, last saw {last:?}");
+    };
+
+    wait_for_child_names(&["Original"]);
+    fs::rename(src_dir.join("Original.luau"), src_dir.join("Renamed.luau")).unwrap();
+    wait_for_child_names(&["Renamed"]);
+}
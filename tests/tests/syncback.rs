@@ -1,9 +1,16 @@
-use std::ffi::OsStr;
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
 
 use insta::assert_snapshot;
+use tempfile::{tempdir, TempDir};
 
-use crate::rojo_test::syncback_util::{
-    run_syncback_test, run_syncback_test_incremental, snapshot_rbxm,
+use crate::rojo_test::{
+    io_util::{atlas_command, copy_recursive, SYNCBACK_TESTS_PATH},
+    syncback_util::{run_syncback_test, run_syncback_test_incremental, snapshot_rbxm},
 };
 
 macro_rules! syncback_tests {
@@ -118,3 +125,1053 @@ syncback_tests_incremental! {
     // Ensures that sync rules are respected (incremental mode only - uses old paths when possible)
     sync_rules => ["src/module.modulescript", "src/text.text"],
 }
+
+/// Copies the `input-project` of the syncback test case `name` into a fresh
+/// temporary directory. Returns the directory, which is removed when it's
+/// dropped, and the path of the project inside it.
+fn copy_syncback_fixture(name: &str) -> (TempDir, PathBuf) {
+    let source = Path::new(SYNCBACK_TESTS_PATH).join(name);
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join(name);
+    fs_err::create_dir(&project_path).expect("Couldn't create project directory");
+    copy_recursive(&source.join("input-project"), &project_path).expect("Couldn't copy project");
+    (test_dir, project_path)
+}
+
+/// Returns the path of `file_name` in the syncback test case `name`, e.g. its
+/// `input.rbxl`.
+fn syncback_fixture_file(name: &str, file_name: &str) -> PathBuf {
+    Path::new(SYNCBACK_TESTS_PATH).join(name).join(file_name)
+}
+
+/// Returns a command that syncs `input` back into `project`, with
+/// `extra_args` after the usual ones.
+fn syncback_command(project: &Path, input: &Path, extra_args: &[&str]) -> Command {
+    let mut command = atlas_command();
+    command
+        .args([
+            "--color",
+            "never",
+            "syncback",
+            project.to_str().unwrap(),
+            "--input",
+            input.to_str().unwrap(),
+        ])
+        .args(extra_args);
+    command
+}
+
+/// Runs `command` and asserts that it succeeds. `what` names the command in
+/// the failure message.
+fn run_successfully(command: &mut Command, what: &str) -> Output {
+    let output = command
+        .output()
+        .unwrap_or_else(|err| panic!("Couldn't spawn {what} process: {err}"));
+    assert!(
+        output.status.success(),
+        "{what} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+/// Syncs `input` back into `project` with `extra_args` and asserts that it
+/// succeeds.
+fn run_syncback_fixture(project: &Path, input: &Path, extra_args: &[&str]) -> Output {
+    run_successfully(
+        &mut syncback_command(project, input, extra_args),
+        "Syncback",
+    )
+}
+
+/// Builds `project` into `output` and asserts that it succeeds.
+fn run_build(project: &Path, output: &Path) {
+    run_successfully(
+        atlas_command().args([
+            "--color",
+            "never",
+            "build",
+            project.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+        ]),
+        "Build",
+    );
+}
+
+/// Runs a verbose syncback of the `rbxm_fallback` test case in a fresh copy of
+/// its project and returns stderr. That case falls back to rbxm, so the run
+/// always ends with an issue summary.
+fn run_verbose_rbxm_fallback_syncback(extra_args: &[&str]) -> String {
+    let (_test_dir, project_path) = copy_syncback_fixture("rbxm_fallback");
+    let input = syncback_fixture_file("rbxm_fallback", "input.rbxm");
+
+    let output = run_syncback_fixture(
+        &project_path,
+        &input,
+        &[&["--verbose"][..], extra_args].concat(),
+    );
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn summary_only_hides_phase_logs() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let verbose = run_verbose_rbxm_fallback_syncback(&[]);
+    assert!(
+        verbose.contains("librojo::syncback::phase"),
+        "phase timings should be logged at debug verbosity:\n{verbose}"
+    );
+
+    let summary_only = run_verbose_rbxm_fallback_syncback(&["--summary-only"]);
+    assert!(
+        !summary_only.contains("librojo::syncback::phase"),
+        "phase timings should be hidden by --summary-only:\n{summary_only}"
+    );
+    assert!(
+        summary_only.contains("Syncback completed with issues"),
+        "the stats summary should still be logged:\n{summary_only}"
+    );
+    assert!(summary_only.contains("Finished syncback"));
+}
+
+#[test]
+fn forced_middleware_writes_rbxmx() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("forced_middleware");
+    let input = syncback_fixture_file("forced_middleware", "input.rbxmx");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    // `.xml` isn't a known extension, so without `$middleware` this node
+    // would have been written out as a directory.
+    let pinned = project_path.join("Pinned.xml");
+    assert!(pinned.is_file(), "Pinned.xml should still be a file");
+
+    let content = fs_err::read_to_string(&pinned).unwrap();
+    assert!(content.starts_with("<roblox"), "expected rbxmx:\n{content}");
+    assert!(content.contains(r#"class="Model""#));
+    assert!(content.contains(r#"class="Part""#));
+    assert!(content.contains("Handle"));
+}
+
+#[test]
+fn include_trees_limits_writes_and_removals() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("include_trees");
+    let input = syncback_fixture_file("include_trees", "input.rbxl");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    // The included subtree is synced back as normal.
+    assert!(project_path.join("OnlyOneCopy/child_of_one.luau").is_file());
+
+    // Its sibling isn't written, even though it's in the place.
+    assert!(!project_path
+        .join("ReplicatedStorage/child_replicated_storage.luau")
+        .exists());
+
+    // Files outside the include set aren't treated as orphans, both next to
+    // the included subtree and in another service entirely.
+    assert!(project_path
+        .join("ReplicatedStorage/Untouched.luau")
+        .is_file());
+    assert!(project_path.join("ServerStorage/Stale.luau").is_file());
+}
+
+#[test]
+fn dedup_duplicate_names_writes_every_sibling() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (test_dir, project_path) = copy_syncback_fixture("dedup_duplicate_names");
+    let input = syncback_fixture_file("dedup_duplicate_names", "input.rbxmx");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    // `Part`, `part` and `PART` collide on case-insensitive file systems, so
+    // all but one of them get a `~N` suffix.
+    let written: Vec<PathBuf> = read_tree(&project_path.join("src"))
+        .into_keys()
+        .filter(|path| path.file_name() != Some(OsStr::new(".gitkeep")))
+        .collect();
+    assert_eq!(written.len(), 3, "expected three files: {written:?}");
+
+    // Building the project again should give back all three Instances under
+    // their original names.
+    let build_path = test_dir.path().join("rebuilt.rbxmx");
+    run_build(&project_path, &build_path);
+
+    let rebuilt = fs_err::read_to_string(&build_path).unwrap();
+    for name in ["Part", "part", "PART"] {
+        let property = format!(r#"<string name="Name">{name}</string>"#);
+        assert_eq!(
+            rebuilt.matches(&property).count(),
+            1,
+            "expected exactly one Instance named {name}:\n{rebuilt}"
+        );
+    }
+}
+
+#[test]
+fn warnings_as_errors_fails_on_duplicate_names() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("dedup_duplicate_names");
+    let input = syncback_fixture_file("dedup_duplicate_names", "input.rbxmx");
+
+    // Without `dedupDuplicateNames`, the colliding siblings are skipped with
+    // a warning.
+    fs_err::write(
+        project_path.join("default.project.json5"),
+        r#"{ "name": "duplicate_names", "tree": { "$path": "src" } }"#,
+    )
+    .unwrap();
+    let before = read_tree(&project_path);
+
+    let output = syncback_command(&project_path, &input, &["--warnings-as-errors"])
+        .output()
+        .expect("Couldn't spawn syncback process");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success(), "Syncback should have failed");
+    assert!(stderr.contains("--warnings-as-errors"), "{stderr}");
+    assert!(stderr.contains("duplicate"), "{stderr}");
+    assert_eq!(
+        read_tree(&project_path),
+        before,
+        "nothing should be written"
+    );
+}
+
+#[test]
+fn meta_format_toml_round_trips() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (test_dir, project_path) = copy_syncback_fixture("meta_format_toml");
+    let input = syncback_fixture_file("meta_format_toml", "input.rbxmx");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    let sword = project_path.join("src/Sword");
+    assert!(!sword.join("init.meta.json5").exists());
+    let meta = fs_err::read_to_string(sword.join("init.meta.toml")).unwrap();
+    assert!(meta.contains("Swing it"), "unexpected meta file:\n{meta}");
+
+    // Building the project should read the properties back out of the TOML
+    // meta file.
+    let build_path = test_dir.path().join("rebuilt.rbxmx");
+    run_build(&project_path, &build_path);
+
+    let rebuilt = fs_err::read_to_string(&build_path).unwrap();
+    assert!(rebuilt.contains(r#"<Item class="Tool""#), "{rebuilt}");
+    assert!(
+        rebuilt.contains(r#"<string name="ToolTip">Swing it</string>"#),
+        "{rebuilt}"
+    );
+}
+
+#[test]
+fn csv_syncback_keeps_locale_column_order() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("csv_locale_order");
+    let input = syncback_fixture_file("csv_locale_order", "input.rbxmx");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    // The Spanish translation changed, so the file is rewritten, but its
+    // columns stay in the order the existing header had them.
+    let csv = fs_err::read_to_string(project_path.join("src/Strings.csv")).unwrap();
+    assert_eq!(
+        csv,
+        "Key,Source,Context,Example,en,fr,es\nGreeting,Hello,,,Hello,Bonjour,¡Hola!\n"
+    );
+}
+
+#[test]
+fn always_emit_meta_writes_meta_for_plain_script() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (test_dir, project_path) = copy_syncback_fixture("always_emit_meta");
+    let input = syncback_fixture_file("always_emit_meta", "input.rbxmx");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    // The script has no properties besides Source, but still gets a meta file.
+    let src = project_path.join("src");
+    assert!(src.join("Plain.luau").is_file());
+    assert!(src.join("Plain.meta.json5").is_file());
+
+    // The (empty) meta file mustn't stop the project from building.
+    let build_path = test_dir.path().join("rebuilt.rbxmx");
+    run_build(&project_path, &build_path);
+
+    let rebuilt = fs_err::read_to_string(&build_path).unwrap();
+    assert!(
+        rebuilt.contains(r#"<string name="Name">Plain</string>"#),
+        "{rebuilt}"
+    );
+}
+
+#[test]
+fn out_of_band_edit_is_a_conflict() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("child_but_not");
+    let input = syncback_fixture_file("child_but_not", "input.rbxl");
+    let args = ["--incremental", "--fail-on-conflict"];
+
+    run_syncback_fixture(&project_path, &input, &args);
+    assert!(project_path.join(".rojo/sync-state.json").is_file());
+
+    // Running again without touching anything isn't a conflict.
+    run_syncback_fixture(&project_path, &input, &args);
+
+    let edited = project_path.join("OnlyOneCopy/child_of_one.luau");
+    fs_err::write(&edited, "-- edited by hand\n").unwrap();
+
+    let conflicted = syncback_command(&project_path, &input, &args)
+        .output()
+        .expect("Couldn't spawn syncback process");
+    let stderr = String::from_utf8_lossy(&conflicted.stderr);
+    assert!(!conflicted.status.success(), "Syncback should have failed");
+    assert!(stderr.contains("OnlyOneCopy/child_of_one.luau"), "{stderr}");
+    assert_eq!(
+        fs_err::read_to_string(&edited).unwrap(),
+        "-- edited by hand\n"
+    );
+}
+
+const UFOWAVE_PROJECT: &str = r#"{ "name": "UFOWave", "tree": { "$path": "src" } }"#;
+
+/// Syncs back the `UFOWave_matching` model into a fresh project using
+/// `threads` worker threads and returns every file that was written.
+fn syncback_ufowave_with_threads(threads: usize) -> BTreeMap<PathBuf, Vec<u8>> {
+    syncback_ufowave(UFOWAVE_PROJECT, threads)
+}
+
+/// Syncs back the `UFOWave_matching` model into a fresh project with the
+/// given project file and returns every file that was written.
+fn syncback_ufowave(project: &str, threads: usize) -> BTreeMap<PathBuf, Vec<u8>> {
+    let input = syncback_fixture_file("UFOWave_matching", "input.rbxm");
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("UFOWave");
+    fs_err::create_dir_all(project_path.join("src")).unwrap();
+    fs_err::write(project_path.join("default.project.json5"), project).unwrap();
+
+    run_successfully(
+        syncback_command(&project_path, &input, &[]).env("RAYON_NUM_THREADS", threads.to_string()),
+        "Syncback",
+    );
+
+    read_tree(&project_path)
+}
+
+#[test]
+fn parallel_syncback_matches_serial() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let serial = syncback_ufowave_with_threads(1);
+    let parallel = syncback_ufowave_with_threads(8);
+
+    assert!(serial.len() > 20, "fixture should produce a broad tree");
+    assert_eq!(
+        serial.keys().collect::<Vec<_>>(),
+        parallel.keys().collect::<Vec<_>>()
+    );
+    for (path, contents) in &serial {
+        assert!(
+            parallel[path] == *contents,
+            "{} differs between serial and parallel syncback",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn number_precision_rounds_json_floats() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let exact = syncback_ufowave(UFOWAVE_PROJECT, 1);
+    let rounded = syncback_ufowave(
+        r#"{
+            "name": "UFOWave",
+            "tree": { "$path": "src" },
+            "syncbackRules": { "numberPrecision": 3 },
+        }"#,
+        1,
+    );
+
+    let hitbox = Path::new("src/Hitbox/init.meta.json5");
+    let exact_hitbox = String::from_utf8_lossy(&exact[hitbox]);
+    let rounded_hitbox = String::from_utf8_lossy(&rounded[hitbox]);
+    assert!(exact_hitbox.contains("789.7578125"), "{exact_hitbox}");
+    assert!(rounded_hitbox.contains("789.758"), "{rounded_hitbox}");
+    assert!(!rounded_hitbox.contains("789.7578"), "{rounded_hitbox}");
+
+    // Every number in the rounded files is within rounding distance of the
+    // exact one and has at most 3 decimal places.
+    let number = regex::Regex::new(r"-?\d+(?:\.(\d+))?(?:[eE][+-]?\d+)?").unwrap();
+    assert_eq!(
+        exact.keys().collect::<Vec<_>>(),
+        rounded.keys().collect::<Vec<_>>()
+    );
+    for (path, contents) in &rounded {
+        if path.extension() != Some(OsStr::new("json5")) {
+            continue;
+        }
+        let rounded_contents = String::from_utf8_lossy(contents);
+        let exact_contents = String::from_utf8_lossy(&exact[path]);
+        let rounded_numbers: Vec<_> = number.captures_iter(&rounded_contents).collect();
+        let exact_numbers: Vec<_> = number.captures_iter(&exact_contents).collect();
+        assert_eq!(
+            rounded_numbers.len(),
+            exact_numbers.len(),
+            "{} has a different shape",
+            path.display()
+        );
+
+        for (rounded_number, exact_number) in rounded_numbers.iter().zip(&exact_numbers) {
+            let decimals = rounded_number.get(1).map_or(0, |d| d.as_str().len());
+            assert!(
+                decimals <= 3 || rounded_number[0].contains(['e', 'E']),
+                "{} in {} was not rounded",
+                &rounded_number[0],
+                path.display()
+            );
+
+            let rounded_value: f64 = rounded_number[0].parse().unwrap();
+            let exact_value: f64 = exact_number[0].parse().unwrap();
+            assert!(
+                (rounded_value - exact_value).abs() <= 0.0005 + exact_value.abs() * 1e-6,
+                "{rounded_value} is too far from {exact_value} in {}",
+                path.display()
+            );
+        }
+    }
+
+    // The reader still accepts the rounded files, and every property it reads
+    // back stays within rounding distance of the exact value.
+    let exact_snapshot = snapshot_files(&exact);
+    let rounded_snapshot = snapshot_files(&rounded);
+    assert_snapshots_close(&exact_snapshot, &rounded_snapshot, 0.001);
+}
+
+/// Writes `files` to a temporary directory and snapshots the project in it.
+fn snapshot_files(files: &BTreeMap<PathBuf, Vec<u8>>) -> librojo::InstanceSnapshot {
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    for (path, contents) in files {
+        let path = test_dir.path().join(path);
+        fs_err::create_dir_all(path.parent().unwrap()).unwrap();
+        fs_err::write(path, contents).unwrap();
+    }
+
+    let vfs = memofs::Vfs::new_default();
+    librojo::snapshot_from_vfs(&librojo::InstanceContext::default(), &vfs, test_dir.path())
+        .expect("snapshot failed")
+        .expect("snapshot returned None")
+}
+
+/// Asserts that two snapshots have the same shape and that their properties
+/// are equal, allowing floats to differ by up to `epsilon`.
+fn assert_snapshots_close(
+    expected: &librojo::InstanceSnapshot,
+    actual: &librojo::InstanceSnapshot,
+    epsilon: f64,
+) {
+    assert_eq!(expected.name, actual.name);
+    assert_eq!(expected.class_name, actual.class_name);
+    assert_eq!(
+        expected.properties.len(),
+        actual.properties.len(),
+        "{} has different properties",
+        expected.name
+    );
+    for (name, expected_value) in &expected.properties {
+        let actual_value = actual
+            .properties
+            .get(name)
+            .unwrap_or_else(|| panic!("{}.{name} is missing", expected.name));
+        assert!(
+            librojo::variant_eq::variant_eq_approx(expected_value, actual_value, epsilon),
+            "{}.{name} read back as {actual_value:?}, expected {expected_value:?}",
+            expected.name
+        );
+    }
+
+    assert_eq!(
+        expected.children.len(),
+        actual.children.len(),
+        "{} has different children",
+        expected.name
+    );
+    for (expected_child, actual_child) in expected.children.iter().zip(&actual.children) {
+        assert_snapshots_close(expected_child, actual_child, epsilon);
+    }
+}
+
+#[test]
+fn sourcemap_flag_writes_post_syncback_paths() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (test_dir, project_path) = copy_syncback_fixture("child_but_not");
+    let input = syncback_fixture_file("child_but_not", "input.rbxl");
+    let sourcemap_path = test_dir.path().join("custom.sourcemap.json");
+    let sourcemap_arg = format!("--sourcemap={}", sourcemap_path.display());
+    run_syncback_fixture(&project_path, &input, &[&sourcemap_arg]);
+
+    let sourcemap: serde_json::Value =
+        serde_json::from_slice(&fs_err::read(&sourcemap_path).unwrap()).unwrap();
+    let mut file_paths = Vec::new();
+    let mut stack = vec![&sourcemap];
+    while let Some(node) = stack.pop() {
+        if let Some(paths) = node["filePaths"].as_array() {
+            file_paths.extend(paths.iter().filter_map(|path| path.as_str()));
+        }
+        if let Some(children) = node["children"].as_array() {
+            stack.extend(children);
+        }
+    }
+    let file_paths: Vec<String> = file_paths.iter().map(|p| p.replace('\\', "/")).collect();
+
+    for expected in [
+        "OnlyOneCopy/child_of_one.luau",
+        "ReplicatedStorage/child_replicated_storage.luau",
+    ] {
+        assert!(
+            file_paths.iter().any(|path| path == expected),
+            "{expected} missing from sourcemap: {file_paths:?}"
+        );
+        assert!(project_path.join(expected).is_file());
+    }
+}
+
+#[test]
+fn progress_flag_reports_every_phase() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("child_but_not");
+    let input = syncback_fixture_file("child_but_not", "input.rbxl");
+    let output = run_syncback_fixture(&project_path, &input, &["--progress"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    for phase in ["prune", "orphan scan", "walk"] {
+        assert!(
+            stderr.contains(&format!("Syncback {phase}: ")),
+            "no progress reported for {phase}:\n{stderr}"
+        );
+    }
+    // Clean mode doesn't hash anything.
+    assert!(!stderr.contains("Syncback hash: "), "{stderr}");
+
+    let output = run_syncback_fixture(&project_path, &input, &["--progress", "--incremental"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Syncback hash: "),
+        "no progress reported for hash:\n{stderr}"
+    );
+}
+
+/// Reads every file under `root` into a map keyed by relative path.
+fn read_tree(root: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let relative = entry.path().strip_prefix(root).unwrap().to_path_buf();
+            (relative, fs_err::read(entry.path()).unwrap())
+        })
+        .collect()
+}
+
+fn run_dry_run_syncback(extra_args: &[&str]) -> String {
+    let (_test_dir, project_path) = copy_syncback_fixture("child_but_not");
+    let input = syncback_fixture_file("child_but_not", "input.rbxl");
+
+    let before = read_tree(&project_path);
+    let output = run_syncback_fixture(
+        &project_path,
+        &input,
+        &[&["--dry-run"][..], extra_args].concat(),
+    );
+
+    assert_eq!(
+        before,
+        read_tree(&project_path),
+        "dry run should not touch the project"
+    );
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn dry_run_reports_plan_without_writing() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let unified = run_dry_run_syncback(&[]);
+    assert!(
+        unified.contains("+++ b/OnlyOneCopy/child_of_one.luau"),
+        "unified plan should list written files:\n{unified}"
+    );
+
+    let json = run_dry_run_syncback(&["--json"]);
+    let plan: serde_json::Value = serde_json::from_str(&json).expect("plan should be JSON");
+    let plan = plan.as_array().expect("plan should be an array");
+    assert!(plan.iter().any(|change| {
+        change["path"] == "OnlyOneCopy/child_of_one.luau"
+            && change["instancePath"].is_string()
+            && change["op"].is_string()
+    }));
+}
+
+#[test]
+fn gitattributes_marked_files_survive_clean_syncback() {
+    use rbx_dom_weak::{InstanceBuilder, WeakDom};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (test_dir, project_path) = copy_syncback_fixture("clean_mode_orphan_removal");
+
+    let lfs_pointer = "version https://git-lfs.github.com/spec/v1\n\
+                       oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+                       size 12345\n";
+    fs_err::write(
+        project_path.join(".gitattributes"),
+        "*.png filter=lfs diff=lfs merge=lfs -text\n",
+    )
+    .unwrap();
+    fs_err::write(project_path.join("src/Logo.png"), lfs_pointer).unwrap();
+    fs_err::write(
+        project_path.join("src/OrphanedFolder/Texture.png"),
+        lfs_pointer,
+    )
+    .unwrap();
+
+    let mut place = WeakDom::new(InstanceBuilder::new("DataModel"));
+    let replicated_storage =
+        place.insert(place.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+    place.insert(
+        replicated_storage,
+        InstanceBuilder::new("ModuleScript")
+            .with_name("KeptScript")
+            .with_property("Source", "return 1"),
+    );
+    let input_path = test_dir.path().join("input.rbxl");
+    let mut input = Vec::new();
+    rbx_binary::to_writer(&mut input, &place, &[replicated_storage]).unwrap();
+    fs_err::write(&input_path, input).unwrap();
+
+    run_syncback_fixture(&project_path, &input_path, &[]);
+
+    let src = project_path.join("src");
+    assert!(src.join("KeptScript.luau").is_file());
+    assert!(!src.join("OrphanedScript.luau").exists());
+    assert!(!src.join("OrphanedFolder/NestedOrphan.luau").exists());
+
+    // The LFS-tracked assets aren't part of the place, but they're kept.
+    assert_eq!(
+        fs_err::read_to_string(src.join("Logo.png")).unwrap(),
+        lfs_pointer
+    );
+    assert_eq!(
+        fs_err::read_to_string(src.join("OrphanedFolder/Texture.png")).unwrap(),
+        lfs_pointer
+    );
+}
+
+#[test]
+fn json_model_dir_round_trips_child_order() {
+    use rbx_dom_weak::{types::Variant, ustr};
+
+    let (test_dir, project_path) = copy_syncback_fixture("json_model_dir");
+    let input = syncback_fixture_file("json_model_dir", "input.rbxmx");
+    run_syncback_fixture(&project_path, &input, &[]);
+
+    let init_path = project_path.join("src/Car/init.model.json5");
+    let init: serde_json::Value =
+        json5::from_str(&fs_err::read_to_string(&init_path).unwrap()).unwrap();
+    assert_eq!(
+        init["manifest"],
+        serde_json::json!([
+            "Zeta.legacy.luau",
+            "Body.model.json5",
+            "Alpha.luau",
+            "Wheels"
+        ])
+    );
+    assert!(project_path
+        .join("src/Car/Wheels/init.model.json5")
+        .is_file());
+
+    let output_path = test_dir.path().join("built.rbxmx");
+    run_build(&project_path, &output_path);
+
+    let dom = rbx_xml::from_str_default(fs_err::read_to_string(&output_path).unwrap())
+        .expect("built model should decode");
+    let root = dom.get_by_ref(dom.root().children()[0]).unwrap();
+    let car = root
+        .children()
+        .iter()
+        .map(|child| dom.get_by_ref(*child).unwrap())
+        .find(|inst| inst.name == "Car")
+        .expect("Car should be built");
+    assert_eq!(car.class, "Model");
+
+    let children: Vec<_> = car
+        .children()
+        .iter()
+        .map(|child| dom.get_by_ref(*child).unwrap())
+        .collect();
+    let names: Vec<&str> = children.iter().map(|child| child.name.as_str()).collect();
+    assert_eq!(names, ["Zeta", "Body", "Alpha", "Wheels"]);
+
+    assert_eq!(
+        children[0].properties.get(&ustr("Source")),
+        Some(&Variant::String("print(\"zeta\")".into()))
+    );
+    assert_eq!(
+        children[1].properties.get(&ustr("Anchored")),
+        Some(&Variant::Bool(true))
+    );
+    assert_eq!(
+        children[2].properties.get(&ustr("Source")),
+        Some(&Variant::String("return 1".into()))
+    );
+    let wheel = dom.get_by_ref(children[3].children()[0]).unwrap();
+    assert_eq!(
+        (wheel.name.as_str(), wheel.class.as_str()),
+        ("Wheel", "Part")
+    );
+}
+
+#[test]
+fn project_attributes_and_tags_round_trip() {
+    use rbx_dom_weak::{
+        types::{Attributes, Tags, Variant},
+        InstanceBuilder, WeakDom,
+    };
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("project_tags");
+    fs_err::create_dir(&project_path).expect("Couldn't create project directory");
+    let project_file = project_path.join("default.project.json5");
+    fs_err::write(
+        &project_file,
+        r#"{
+            "name": "project_tags",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": {
+                    "$className": "ReplicatedStorage",
+                    "$attributes": {
+                        "Difficulty": "Hard",
+                        "WalkSpeed": 16.5
+                    },
+                    "$tags": ["Persistent", "Settings"]
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let read_project = || -> serde_json::Value {
+        json5::from_str(&fs_err::read_to_string(&project_file).unwrap()).unwrap()
+    };
+    let run_syncback = |tags: &[&str]| {
+        let mut attributes = Attributes::new();
+        attributes.insert("Difficulty".to_owned(), Variant::String("Hard".into()));
+        attributes.insert("WalkSpeed".to_owned(), Variant::Float64(16.5));
+        let mut place_tags = Tags::new();
+        for tag in tags {
+            place_tags.push(tag);
+        }
+
+        let mut place = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let replicated_storage = place.insert(
+            place.root_ref(),
+            InstanceBuilder::new("ReplicatedStorage")
+                .with_property("Attributes", attributes)
+                .with_property("Tags", place_tags),
+        );
+        let input_path = test_dir.path().join("input.rbxl");
+        let mut input = Vec::new();
+        rbx_binary::to_writer(&mut input, &place, &[replicated_storage]).unwrap();
+        fs_err::write(&input_path, input).unwrap();
+
+        run_syncback_fixture(&project_path, &input_path, &[]);
+    };
+
+    let before = read_project();
+    run_syncback(&["Persistent", "Settings"]);
+    assert_eq!(read_project(), before);
+
+    run_syncback(&["Persistent", "Tuned"]);
+    let after = read_project();
+    let replicated_storage = &after["tree"]["ReplicatedStorage"];
+    assert_eq!(
+        replicated_storage["$tags"],
+        serde_json::json!(["Persistent", "Tuned"])
+    );
+    assert_eq!(
+        replicated_storage["$attributes"],
+        before["tree"]["ReplicatedStorage"]["$attributes"]
+    );
+    assert!(replicated_storage.get("$properties").is_none());
+}
+
+#[test]
+fn incremental_syncback_reuses_cached_hashes() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let (_test_dir, project_path) = copy_syncback_fixture("child_but_not");
+    let input = syncback_fixture_file("child_but_not", "input.rbxl");
+
+    let syncback = || {
+        let output = run_syncback_fixture(&project_path, &input, &["--verbose", "--incremental"]);
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    // The first run has nothing cached, and the second rehashes whatever the
+    // first one wrote.
+    let first = syncback();
+    assert!(first.contains("Reused 0 of"), "{first}");
+    assert!(project_path.join(".rojo/syncback-hashes.bin").is_file());
+    syncback();
+
+    let unchanged = syncback();
+    assert!(
+        unchanged.contains("wrote 0 files/folders, removed 0"),
+        "{unchanged}"
+    );
+    let reused_line = unchanged
+        .lines()
+        .find(|line| line.contains("Reused "))
+        .unwrap_or_else(|| panic!("no cache usage logged:\n{unchanged}"));
+    let counts: Vec<usize> = reused_line
+        .split_whitespace()
+        .filter_map(|word| word.parse().ok())
+        .collect();
+    assert_eq!(counts.len(), 2, "{reused_line}");
+    assert!(counts[0] > 0, "{reused_line}");
+}
+
+#[test]
+fn incremental_syncback_rehashes_instances_in_an_edited_model_file() {
+    use rbx_dom_weak::{types::Variant, ustr, InstanceBuilder, WeakDom};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("edited_model");
+    fs_err::create_dir_all(project_path.join("src")).expect("Couldn't create project directory");
+    fs_err::write(
+        project_path.join("default.project.json5"),
+        r#"{
+            "name": "edited_model",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": { "$path": "src" }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    // The duplicate names make Holder fall back to a model file, and Unique
+    // is cached under its own path inside it.
+    let mut place = WeakDom::new(InstanceBuilder::new("DataModel"));
+    let replicated_storage =
+        place.insert(place.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+    let holder = place.insert(
+        replicated_storage,
+        InstanceBuilder::new("Folder").with_name("Holder"),
+    );
+    for _ in 0..2 {
+        place.insert(holder, InstanceBuilder::new("Folder").with_name("Dup"));
+    }
+    place.insert(
+        holder,
+        InstanceBuilder::new("StringValue")
+            .with_name("Unique")
+            .with_property("Value", "original"),
+    );
+    let input_path = test_dir.path().join("input.rbxl");
+    let mut input = Vec::new();
+    rbx_binary::to_writer(&mut input, &place, &[replicated_storage]).unwrap();
+    fs_err::write(&input_path, input).unwrap();
+
+    let model_path = project_path.join("src/Holder.rbxm");
+    let unique_value = || {
+        let dom = rbx_binary::from_reader(fs_err::read(&model_path).unwrap().as_slice()).unwrap();
+        let unique = dom
+            .descendants()
+            .find(|inst| inst.name == "Unique")
+            .expect("Unique should be in the model file");
+        match unique.properties.get(&ustr("Value")) {
+            Some(Variant::String(value)) => value.clone(),
+            other => panic!("unexpected Value {other:?}"),
+        }
+    };
+
+    run_syncback_fixture(&project_path, &input_path, &["--incremental"]);
+    run_syncback_fixture(&project_path, &input_path, &["--incremental"]);
+    assert_eq!(unique_value(), "original");
+
+    // Edit the model file the way another tool would.
+    let mut dom = rbx_binary::from_reader(fs_err::read(&model_path).unwrap().as_slice()).unwrap();
+    let unique = dom
+        .descendants()
+        .find(|inst| inst.name == "Unique")
+        .unwrap()
+        .referent();
+    dom.get_by_ref_mut(unique).unwrap().properties.insert(
+        ustr("Value"),
+        Variant::String("edited elsewhere".to_owned()),
+    );
+    let roots = dom.root().children().to_vec();
+    let mut edited = Vec::new();
+    rbx_binary::to_writer(&mut edited, &dom, &roots).unwrap();
+    fs_err::write(&model_path, edited).unwrap();
+
+    run_syncback_fixture(&project_path, &input_path, &["--incremental"]);
+    assert_eq!(unique_value(), "original");
+}
+
+#[test]
+fn float_noise_does_not_rewrite_model_or_meta_files() {
+    use rbx_dom_weak::{
+        types::{Attributes, Variant},
+        InstanceBuilder, WeakDom,
+    };
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("float_noise");
+    fs_err::create_dir_all(project_path.join("src")).expect("Couldn't create project directory");
+    fs_err::write(
+        project_path.join("default.project.json5"),
+        r#"{
+            "name": "float_noise",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": { "$path": "src" }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let run_syncback = |value: f64| {
+        let mut attributes = Attributes::new();
+        attributes.insert("Scale".to_owned(), Variant::Float64(value));
+
+        let mut place = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let replicated_storage =
+            place.insert(place.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+        place.insert(
+            replicated_storage,
+            InstanceBuilder::new("NumberValue")
+                .with_name("Speed")
+                .with_property("Value", value),
+        );
+        let settings = place.insert(
+            replicated_storage,
+            InstanceBuilder::new("Folder")
+                .with_name("Settings")
+                .with_property("Attributes", attributes),
+        );
+        place.insert(
+            settings,
+            InstanceBuilder::new("ModuleScript")
+                .with_name("Module")
+                .with_property("Source", "return 1"),
+        );
+        let input_path = test_dir.path().join("input.rbxl");
+        let mut input = Vec::new();
+        rbx_binary::to_writer(&mut input, &place, &[replicated_storage]).unwrap();
+        fs_err::write(&input_path, input).unwrap();
+
+        run_syncback_fixture(&project_path, &input_path, &["--incremental"]);
+    };
+
+    let model = project_path.join("src/Speed.model.json5");
+    let meta = project_path.join("src/Settings/init.meta.json5");
+    let read_files = || {
+        (
+            fs_err::read_to_string(&model).unwrap(),
+            fs_err::read_to_string(&meta).unwrap(),
+        )
+    };
+
+    run_syncback(1.5);
+    let written = read_files();
+    assert!(written.0.contains("1.5"), "{}", written.0);
+    assert!(written.1.contains("1.5"), "{}", written.1);
+
+    // A change well inside the default epsilon leaves both files alone.
+    run_syncback(1.5 + 1e-7);
+    assert_eq!(read_files(), written);
+
+    // One outside it rewrites them.
+    run_syncback(1.75);
+    let (model_contents, meta_contents) = read_files();
+    assert!(model_contents.contains("1.75"), "{model_contents}");
+    assert!(meta_contents.contains("1.75"), "{meta_contents}");
+}
+
+#[test]
+fn markdown_string_value_with_children_becomes_directory() {
+    use rbx_dom_weak::{InstanceBuilder, WeakDom};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_path = test_dir.path().join("markdown_children");
+    fs_err::create_dir_all(project_path.join("src")).expect("Couldn't create project directory");
+    fs_err::write(
+        project_path.join("default.project.json5"),
+        r#"{
+            "name": "markdown_children",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": { "$path": "src" }
+            }
+        }"#,
+    )
+    .unwrap();
+    fs_err::write(project_path.join("src/Notes.md"), "# Notes\n").unwrap();
+
+    let mut place = WeakDom::new(InstanceBuilder::new("DataModel"));
+    let replicated_storage =
+        place.insert(place.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+    let notes = place.insert(
+        replicated_storage,
+        InstanceBuilder::new("StringValue")
+            .with_name("Notes")
+            .with_property("Value", "# Notes\n"),
+    );
+    place.insert(
+        notes,
+        InstanceBuilder::new("ModuleScript")
+            .with_name("Child")
+            .with_property("Source", "return 1"),
+    );
+    let input_path = test_dir.path().join("input.rbxl");
+    let mut input = Vec::new();
+    rbx_binary::to_writer(&mut input, &place, &[replicated_storage]).unwrap();
+    fs_err::write(&input_path, input).unwrap();
+
+    run_syncback_fixture(&project_path, &input_path, &["--incremental"]);
+
+    let notes_dir = project_path.join("src/Notes");
+    assert!(notes_dir.join("Child.luau").is_file());
+    assert!(!project_path.join("src/Notes.md").exists());
+
+    let snapshot = librojo::snapshot_from_vfs(
+        &librojo::InstanceContext::default(),
+        &memofs::Vfs::new_default(),
+        &notes_dir,
+    )
+    .unwrap()
+    .expect("Notes directory should snapshot");
+    assert_eq!(snapshot.class_name, "StringValue");
+    assert_eq!(snapshot.children.len(), 1);
+    assert_eq!(snapshot.children[0].name, "Child");
+}
@@ -36,6 +36,8 @@ fn make_added_instance(
         class_name: class_name.to_string(),
         properties,
         children,
+        target_path: None,
+        id: None,
     }
 }
 
@@ -56,6 +58,8 @@ fn make_child_instance(
         class_name: class_name.to_string(),
         properties,
         children,
+        target_path: None,
+        id: None,
     }
 }
 
@@ -76,6 +80,7 @@ fn send_write_request(
         added: added_map,
         updated: vec![],
         stage_ids: Vec::new(),
+        dry_run: false,
     };
 
     session
@@ -747,6 +752,8 @@ fn txt_file_format_preserved() {
             class_name: "StringValue".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         send_write_request(&session, &info.session_id, rs_id, added);
 
@@ -906,6 +913,7 @@ fn rapid_syncs_dont_create_duplicates() {
                 added: added_map,
                 updated: vec![],
                 stage_ids: Vec::new(),
+                dry_run: false,
             };
 
             session
@@ -1182,6 +1190,7 @@ fn scripts_only_mode_rapid_syncs() {
                 added: added_map,
                 updated: vec![],
                 stage_ids: Vec::new(),
+                dry_run: false,
             };
 
             session
@@ -1806,6 +1815,7 @@ fn sync_immediate_double_sync() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -1831,6 +1841,7 @@ fn sync_immediate_double_sync() {
             added: added_map2,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request2).unwrap();
 
@@ -1877,6 +1888,7 @@ fn stress_test_50_rapid_syncs() {
                 added: added_map,
                 updated: vec![],
                 stage_ids: Vec::new(),
+                dry_run: false,
             };
             session.post_api_write(&write_request).unwrap();
             // No delay between syncs!
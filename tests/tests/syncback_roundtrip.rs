@@ -169,7 +169,7 @@ fn compare_children(
 /// Compare properties of two instances.
 ///
 /// Focuses on properties that matter for roundtrip correctness:
-/// - Source (for scripts)
+/// - Source and RunContext (for scripts)
 /// - Value (for value types)
 /// - Other serializable properties
 fn compare_properties(
@@ -190,6 +190,17 @@ fn compare_properties(
         }
     }
 
+    // Check RunContext for scripts, since Server and Legacy Scripts are told
+    // apart only by their file extension
+    let run_context_a = inst_a.properties.get(&ustr("RunContext"));
+    let run_context_b = inst_b.properties.get(&ustr("RunContext"));
+    if run_context_a != run_context_b {
+        panic!(
+            "[{}] RunContext differs for '{}' ({}):\nOriginal: {:?}\nRoundtrip: {:?}",
+            test_name, inst_a.name, inst_a.class, run_context_a, run_context_b
+        );
+    }
+
     // Check Value property for value types
     if let (Some(value_a), Some(value_b)) = (
         inst_a.properties.get(&ustr("Value")),
@@ -250,6 +261,7 @@ roundtrip_tests! {
     module_init,
     server_in_folder,
     server_init,
+    script_run_contexts,
 
     // Nested structures
     deep_nesting,
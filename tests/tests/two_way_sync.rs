@@ -98,6 +98,7 @@ fn send_update(
         added: HashMap::new(),
         updated: vec![update],
         stage_ids: Vec::new(),
+        dry_run: false,
     };
     session
         .post_api_write(&write_request)
@@ -117,6 +118,7 @@ fn send_removal(
         added: HashMap::new(),
         updated: vec![],
         stage_ids: Vec::new(),
+        dry_run: false,
     };
     session
         .post_api_write(&write_request)
@@ -394,6 +396,45 @@ fn rename_standalone_script() {
     });
 }
 
+/// Renaming an instance so that only its casing changes must change the
+/// casing on disk too. On case-insensitive filesystems the old and new paths
+/// refer to the same file, so `exists()` can't tell them apart; inspect the
+/// directory listing instead.
+#[cfg(any(windows, target_os = "macos"))]
+#[test]
+fn rename_case_only_changes_casing_on_disk() {
+    run_serve_test("syncback_write", |session, _redactions| {
+        let (session_id, _rs_id, existing_id) = get_rs_and_existing(&session);
+        let src = session.path().join("src");
+
+        let listed = |name: &str| {
+            fs::read_dir(&src)
+                .unwrap()
+                .any(|entry| entry.unwrap().file_name() == name)
+        };
+        assert!(listed("existing.luau"), "existing.luau before rename");
+
+        send_update(
+            &session,
+            &session_id,
+            InstanceUpdate {
+                id: existing_id,
+                changed_name: Some("Existing".to_string()),
+                changed_class_name: None,
+                changed_properties: UstrMap::default(),
+                changed_metadata: None,
+            },
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !listed("Existing.luau") && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert!(listed("Existing.luau"), "Existing.luau after rename");
+        assert!(!listed("existing.luau"), "old casing should be gone");
+    });
+}
+
 /// Test 5: Renaming an instance also renames its adjacent meta file.
 #[test]
 fn rename_preserves_adjacent_meta_file() {
@@ -546,6 +587,49 @@ fn remove_instance_deletes_file_and_meta() {
     });
 }
 
+/// With `metaFormat: "toml"`, property updates write `.meta.toml` files,
+/// carry over a leftover `.meta.json5` that would otherwise shadow them, and
+/// removals clean up the TOML meta file.
+#[test]
+fn toml_meta_format_replaces_json5_meta() {
+    run_serve_test("syncback_write_toml", |session, _redactions| {
+        let (session_id, _rs_id, existing_id) = get_rs_and_existing(&session);
+
+        let src = session.path().join("src");
+        let json5_meta = src.join("existing.meta.json5");
+        let toml_meta = src.join("existing.meta.toml");
+        assert_file_exists(&json5_meta, "Leftover JSON5 meta before update");
+
+        let mut attrs = rbx_dom_weak::types::Attributes::new();
+        attrs.insert("TestAttribute".to_string(), Variant::Float64(42.0));
+        let mut props = UstrMap::default();
+        props.insert(ustr("Attributes"), Some(Variant::Attributes(attrs)));
+        send_update(
+            &session,
+            &session_id,
+            InstanceUpdate {
+                id: existing_id,
+                changed_name: None,
+                changed_class_name: None,
+                changed_properties: props,
+                changed_metadata: None,
+            },
+        );
+
+        assert_file_exists(&toml_meta, "TOML meta after property update");
+        assert_not_exists(&json5_meta, "Leftover JSON5 meta after property update");
+        let meta: toml::Value = toml::from_str(&fs::read_to_string(&toml_meta).unwrap()).unwrap();
+        assert_eq!(meta["attributes"]["TestAttribute"].as_float(), Some(42.0));
+        assert_eq!(meta["attributes"]["Stale"].as_bool(), Some(true));
+
+        let (session_id, _rs_id, existing_id) = get_rs_and_existing(&session);
+        send_removal(&session, &session_id, vec![existing_id]);
+
+        assert_not_exists(&src.join("existing.luau"), "File after removal");
+        assert_not_exists(&toml_meta, "TOML meta after removal");
+    });
+}
+
 /// Test 9: Removing a directory-format instance deletes the entire directory.
 #[test]
 fn remove_directory_instance() {
@@ -698,6 +782,8 @@ fn echo_suppression_prevents_redundant_patches() {
             class_name: "ModuleScript".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(Ref::new(), added);
@@ -707,6 +793,7 @@ fn echo_suppression_prevents_redundant_patches() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -735,6 +822,66 @@ fn echo_suppression_prevents_redundant_patches() {
     });
 }
 
+/// Test 12b: An added instance with `target_path` is created in that
+/// directory instead of its parent's, and paths outside the project's `$path`
+/// roots are rejected.
+#[test]
+fn add_instance_with_target_path() {
+    run_serve_test("syncback_write", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        let root_read = session.get_api_read(info.root_instance_id).unwrap();
+        let (rs_id, _) = find_by_class(&root_read.instances, "ReplicatedStorage");
+
+        let module = |name: &str, target_path: &str| {
+            let mut properties = HashMap::new();
+            properties.insert(
+                "Source".to_string(),
+                Variant::String(format!("return '{name}'")),
+            );
+            AddedInstance {
+                parent: Some(rs_id),
+                name: name.to_string(),
+                class_name: "ModuleScript".to_string(),
+                properties,
+                children: vec![],
+                target_path: Some(PathBuf::from(target_path)),
+                id: None,
+            }
+        };
+
+        let mut added_map = HashMap::new();
+        added_map.insert(Ref::new(), module("Targeted", "src/Nested/Deeper"));
+        added_map.insert(Ref::new(), module("Escaped", "../outside"));
+        added_map.insert(Ref::new(), module("Unmanaged", "not-a-path-root"));
+        let write_request = WriteRequest {
+            session_id: info.session_id,
+            removed: vec![],
+            added: added_map,
+            updated: vec![],
+            stage_ids: Vec::new(),
+            dry_run: false,
+        };
+        session.post_api_write(&write_request).unwrap();
+
+        poll_file_exists(
+            &session.path().join("src/Nested/Deeper/Targeted.luau"),
+            "Targeted.luau created in its target directory",
+        );
+        assert_not_exists(
+            &session.path().join("src/Targeted.luau"),
+            "Targeted.luau should not be created in its parent's directory",
+        );
+        assert_not_exists(
+            &session.path().join("../outside"),
+            "target paths may not escape the project",
+        );
+        assert_not_exists(
+            &session.path().join("not-a-path-root"),
+            "target paths must be inside a $path root",
+        );
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Helpers (syncback_format_transitions fixture)
 // ---------------------------------------------------------------------------
@@ -1122,6 +1269,8 @@ fn add_child_converts_standalone_to_directory() {
             class_name: "ModuleScript".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(Ref::new(), added);
@@ -1131,6 +1280,7 @@ fn add_child_converts_standalone_to_directory() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -1275,6 +1425,8 @@ fn add_child_to_encoded_module_creates_encoded_directory() {
             class_name: "ModuleScript".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(Ref::new(), added);
@@ -1284,6 +1436,7 @@ fn add_child_to_encoded_module_creates_encoded_directory() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -1349,6 +1502,8 @@ fn add_child_to_encoded_script_strips_suffix_correctly() {
             class_name: "ModuleScript".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(Ref::new(), added);
@@ -1358,6 +1513,7 @@ fn add_child_to_encoded_script_strips_suffix_correctly() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -1416,6 +1572,8 @@ fn add_child_to_encoded_model_creates_encoded_directory() {
             class_name: "ModuleScript".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(Ref::new(), added);
@@ -1425,6 +1583,7 @@ fn add_child_to_encoded_model_creates_encoded_directory() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -1529,6 +1688,7 @@ fn send_update_fast(
         added: HashMap::new(),
         updated: vec![update],
         stage_ids: Vec::new(),
+        dry_run: false,
     };
     session
         .post_api_write(&write_request)
@@ -1548,6 +1708,7 @@ fn send_update_no_wait(
         added: HashMap::new(),
         updated: vec![update],
         stage_ids: Vec::new(),
+        dry_run: false,
     };
     session
         .post_api_write(&write_request)
@@ -1566,6 +1727,7 @@ fn send_removal_fast(
         added: HashMap::new(),
         updated: vec![],
         stage_ids: Vec::new(),
+        dry_run: false,
     };
     session
         .post_api_write(&write_request)
@@ -2209,6 +2371,7 @@ fn multi_instance_source_update_single_request() {
             added: HashMap::new(),
             updated: updates,
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         wait_for_settle();
@@ -2246,6 +2409,7 @@ fn multi_instance_rename_single_request() {
             added: HashMap::new(),
             updated: updates,
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         wait_for_settle();
@@ -2341,6 +2505,8 @@ fn echo_suppression_rapid_adds_10x() {
                 class_name: "ModuleScript".to_string(),
                 properties,
                 children: vec![],
+                target_path: None,
+                id: None,
             };
             let mut added_map = HashMap::new();
             added_map.insert(Ref::new(), added);
@@ -2350,6 +2516,7 @@ fn echo_suppression_rapid_adds_10x() {
                 added: added_map,
                 updated: vec![],
                 stage_ids: Vec::new(),
+                dry_run: false,
             };
             session.post_api_write(&write_request).unwrap();
         }
@@ -2402,6 +2569,8 @@ fn echo_suppression_mixed_operations() {
                     class_name: "ModuleScript".to_string(),
                     properties,
                     children: vec![],
+                    target_path: None,
+                    id: None,
                 },
             );
         }
@@ -2423,6 +2592,7 @@ fn echo_suppression_mixed_operations() {
             added: added_map,
             updated: updates,
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         wait_for_settle();
@@ -2773,6 +2943,7 @@ fn fuzz_multi_instance_15_iterations() {
                 added: HashMap::new(),
                 updated: updates,
                 stage_ids: Vec::new(),
+                dry_run: false,
             };
             session.post_api_write(&write_request).unwrap();
             thread::sleep(Duration::from_millis(100));
@@ -4175,6 +4346,7 @@ fn multi_instance_combined_rename_class_source_batch() {
             added: HashMap::new(),
             updated: updates,
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         wait_for_settle();
@@ -5134,6 +5306,8 @@ fn add_instance_with_forbidden_chars_creates_slug_and_meta() {
             class_name: "ModuleScript".to_string(),
             properties,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(Ref::new(), added);
@@ -5143,6 +5317,7 @@ fn add_instance_with_forbidden_chars_creates_slug_and_meta() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -5184,6 +5359,8 @@ fn add_two_colliding_instances_deduplicates() {
             class_name: "ModuleScript".to_string(),
             properties: props1,
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         // Add "X:Y" (also slugs to "X_Y" — collision)
@@ -5198,6 +5375,8 @@ fn add_two_colliding_instances_deduplicates() {
             class_name: "ModuleScript".to_string(),
             properties: props2,
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut added_map = HashMap::new();
@@ -5209,6 +5388,7 @@ fn add_two_colliding_instances_deduplicates() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -5480,6 +5660,8 @@ fn add_three_colliding_instances_deduplicates() {
                     class_name: "ModuleScript".to_string(),
                     properties: props,
                     children: vec![],
+                    target_path: None,
+                    id: None,
                 },
             );
         }
@@ -5489,6 +5671,7 @@ fn add_three_colliding_instances_deduplicates() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -5632,6 +5815,8 @@ fn add_case_insensitive_colliding_instances() {
                 class_name: "ModuleScript".to_string(),
                 properties: props1,
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
         let mut props2 = HashMap::new();
@@ -5647,6 +5832,8 @@ fn add_case_insensitive_colliding_instances() {
                 class_name: "ModuleScript".to_string(),
                 properties: props2,
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -5656,6 +5843,7 @@ fn add_case_insensitive_colliding_instances() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -6751,6 +6939,8 @@ fn ref_to_instance_added_in_same_request() {
                 class_name: "Part".to_string(),
                 properties: HashMap::new(),
                 children: Vec::new(),
+                target_path: None,
+                id: None,
             },
         );
 
@@ -6770,6 +6960,7 @@ fn ref_to_instance_added_in_same_request() {
                 changed_metadata: None,
             }],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
 
         session
@@ -7252,6 +7443,8 @@ fn ref_same_batch_add_target_includes_extension() {
             class_name: "Script".to_string(),
             properties: add_props,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(target_guid, added_inst);
@@ -7272,6 +7465,7 @@ fn ref_same_batch_add_target_includes_extension() {
                 changed_metadata: None,
             }],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -7308,6 +7502,8 @@ fn ref_same_batch_add_module_target_includes_extension() {
             class_name: "ModuleScript".to_string(),
             properties: add_props,
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(target_guid, added_inst);
@@ -7327,6 +7523,7 @@ fn ref_same_batch_add_module_target_includes_extension() {
                 changed_metadata: None,
             }],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -7352,6 +7549,8 @@ fn ref_same_batch_add_folder_target_no_extension() {
             class_name: "Folder".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(target_guid, added_inst);
@@ -7371,6 +7570,7 @@ fn ref_same_batch_add_folder_target_no_extension() {
                 changed_metadata: None,
             }],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -7416,6 +7616,8 @@ fn delete_deduped_instance_group_to_1_cleanup() {
                 class_name: "ModuleScript".to_string(),
                 properties: props1,
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
         added_map.insert(
@@ -7426,6 +7628,8 @@ fn delete_deduped_instance_group_to_1_cleanup() {
                 class_name: "ModuleScript".to_string(),
                 properties: props2,
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -7435,6 +7639,7 @@ fn delete_deduped_instance_group_to_1_cleanup() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -7512,6 +7717,8 @@ fn delete_base_deduped_instance_promotes_lowest() {
                     class_name: "ModuleScript".to_string(),
                     properties: props,
                     children: vec![],
+                    target_path: None,
+                    id: None,
                 },
             );
         }
@@ -7521,6 +7728,7 @@ fn delete_base_deduped_instance_promotes_lowest() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -7591,6 +7799,8 @@ fn delete_middle_deduped_instance_tolerates_gap() {
                     class_name: "ModuleScript".to_string(),
                     properties: props,
                     children: vec![],
+                    target_path: None,
+                    id: None,
                 },
             );
         }
@@ -7600,6 +7810,7 @@ fn delete_middle_deduped_instance_tolerates_gap() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -7668,6 +7879,8 @@ fn add_same_named_folders_deduplicates() {
                 p
             },
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let child2 = AddedInstance {
             parent: None,
@@ -7682,6 +7895,8 @@ fn add_same_named_folders_deduplicates() {
                 p
             },
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut added_map = HashMap::new();
@@ -7693,6 +7908,8 @@ fn add_same_named_folders_deduplicates() {
                 class_name: "Folder".to_string(),
                 properties: HashMap::new(),
                 children: vec![child1],
+                target_path: None,
+                id: None,
             },
         );
         added_map.insert(
@@ -7703,6 +7920,8 @@ fn add_same_named_folders_deduplicates() {
                 class_name: "Folder".to_string(),
                 properties: HashMap::new(),
                 children: vec![child2],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -7712,6 +7931,7 @@ fn add_same_named_folders_deduplicates() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -7750,6 +7970,84 @@ fn add_same_named_folders_deduplicates() {
     });
 }
 
+/// Adding a Folder with two nested children should return the tree ID of the
+/// Folder and of each child that was given a placeholder `id`.
+#[test]
+fn add_nested_instances_returns_added_ids() {
+    run_serve_test("syncback_encoded_names", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        let root_read = session.get_api_read(info.root_instance_id).unwrap();
+        let (rs_id, _) = find_by_class(&root_read.instances, "ReplicatedStorage");
+
+        let folder_placeholder = Ref::new();
+        let child_placeholders = [Ref::new(), Ref::new()];
+        let children = ["First", "Second"]
+            .iter()
+            .zip(child_placeholders)
+            .map(|(name, placeholder)| AddedInstance {
+                parent: None,
+                name: name.to_string(),
+                class_name: "ModuleScript".to_string(),
+                properties: {
+                    let mut p = HashMap::new();
+                    p.insert("Source".to_string(), Variant::String(format!("-- {name}")));
+                    p
+                },
+                children: vec![],
+                target_path: None,
+                id: Some(placeholder),
+            })
+            .collect();
+
+        let mut added_map = HashMap::new();
+        added_map.insert(
+            folder_placeholder,
+            AddedInstance {
+                parent: Some(rs_id),
+                name: "Placeholders".to_string(),
+                class_name: "Folder".to_string(),
+                properties: HashMap::new(),
+                children,
+                target_path: None,
+                id: None,
+            },
+        );
+
+        let write_request = WriteRequest {
+            session_id: info.session_id,
+            removed: vec![],
+            added: added_map,
+            updated: vec![],
+            stage_ids: Vec::new(),
+            dry_run: false,
+        };
+        let response = session
+            .post_api_write_with_response(&write_request)
+            .unwrap();
+
+        assert_eq!(
+            response.added_ids.len(),
+            3,
+            "Every placeholder should be mapped, got {:?}",
+            response.added_ids
+        );
+        let expected = [
+            (folder_placeholder, "Placeholders"),
+            (child_placeholders[0], "First"),
+            (child_placeholders[1], "Second"),
+        ];
+        for (placeholder, name) in expected {
+            let id = response.added_ids[&placeholder];
+            let read = session.get_api_read(id).unwrap();
+            let instance = read
+                .instances
+                .get(&id)
+                .unwrap_or_else(|| panic!("{name} should be in the tree as {id}"));
+            assert_eq!(instance.name, name);
+        }
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Tests: Ref paths through dedup'd instances
 // ---------------------------------------------------------------------------
@@ -7772,6 +8070,8 @@ fn ref_through_deduped_instance() {
             class_name: "Part".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let child_other = AddedInstance {
             parent: None,
@@ -7779,6 +8079,8 @@ fn ref_through_deduped_instance() {
             class_name: "Part".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
 
         let mut model_props = HashMap::new();
@@ -7793,6 +8095,8 @@ fn ref_through_deduped_instance() {
                 class_name: "Part".to_string(),
                 properties: HashMap::new(),
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
         // Clear and re-build: add Model with children and PrimaryPart ref
@@ -7808,6 +8112,8 @@ fn ref_through_deduped_instance() {
                 class_name: "Model".to_string(),
                 properties: model_props,
                 children: vec![child_target, child_other],
+                target_path: None,
+                id: None,
             },
         );
         // The target_guid ref in PrimaryPart points to an instance added in
@@ -7823,6 +8129,8 @@ fn ref_through_deduped_instance() {
             class_name: "Part".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let child2 = AddedInstance {
             parent: None,
@@ -7830,6 +8138,8 @@ fn ref_through_deduped_instance() {
             class_name: "Part".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
         added_map.insert(
             Ref::new(),
@@ -7839,6 +8149,8 @@ fn ref_through_deduped_instance() {
                 class_name: "Model".to_string(),
                 properties: HashMap::new(),
                 children: vec![child1, child2],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -7848,6 +8160,7 @@ fn ref_through_deduped_instance() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
         thread::sleep(Duration::from_millis(500));
@@ -7918,6 +8231,8 @@ fn ref_path_updated_after_dedup_cleanup() {
             class_name: "ObjectValue".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(
@@ -7928,6 +8243,8 @@ fn ref_path_updated_after_dedup_cleanup() {
                 class_name: "Model".to_string(),
                 properties: HashMap::new(),
                 children: vec![child_ptr],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -7949,6 +8266,8 @@ fn ref_path_updated_after_dedup_cleanup() {
                 class_name: "ModuleScript".to_string(),
                 properties: props1,
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
         added_map.insert(
@@ -7959,6 +8278,8 @@ fn ref_path_updated_after_dedup_cleanup() {
                 class_name: "ModuleScript".to_string(),
                 properties: props2,
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -7968,6 +8289,7 @@ fn ref_path_updated_after_dedup_cleanup() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -8065,6 +8387,8 @@ fn rename_ref_target_updates_ref_paths() {
             class_name: "ObjectValue".to_string(),
             properties: HashMap::new(),
             children: vec![],
+            target_path: None,
+            id: None,
         };
         let mut added_map = HashMap::new();
         added_map.insert(
@@ -8082,6 +8406,8 @@ fn rename_ref_target_updates_ref_paths() {
                     p
                 },
                 children: vec![],
+                target_path: None,
+                id: None,
             },
         );
         added_map.insert(
@@ -8092,6 +8418,8 @@ fn rename_ref_target_updates_ref_paths() {
                 class_name: "Model".to_string(),
                 properties: HashMap::new(),
                 children: vec![child_obj],
+                target_path: None,
+                id: None,
             },
         );
 
@@ -8101,6 +8429,7 @@ fn rename_ref_target_updates_ref_paths() {
             added: added_map,
             updated: vec![],
             stage_ids: Vec::new(),
+            dry_run: false,
         };
         session.post_api_write(&write_request).unwrap();
 
@@ -8176,3 +8505,195 @@ fn rename_ref_target_updates_ref_paths() {
         );
     });
 }
+
+/// `/api/subscribe` pushes the patch produced by an `/api/write` without the
+/// client having to know a message cursor.
+#[test]
+fn subscribe_socket_receives_write_patch() {
+    use librojo::web_api::{SocketPacketBody, SocketPacketType};
+
+    run_serve_test("syncback_write", |session, _redactions| {
+        let (session_id, _rs_id, existing_id) = get_rs_and_existing(&session);
+
+        let packet = session
+            .recv_subscribe_packet(SocketPacketType::Messages, || {
+                let mut props = UstrMap::default();
+                props.insert(
+                    ustr("Source"),
+                    Some(Variant::String("return { pushed = true }".to_string())),
+                );
+                send_update(
+                    &session,
+                    &session_id,
+                    InstanceUpdate {
+                        id: existing_id,
+                        changed_name: None,
+                        changed_class_name: None,
+                        changed_properties: props,
+                        changed_metadata: None,
+                    },
+                );
+            })
+            .expect("Should receive a patch over /api/subscribe");
+
+        let SocketPacketBody::Messages(messages_packet) = packet.body;
+        assert!(
+            messages_packet
+                .messages
+                .iter()
+                .flat_map(|msg| &msg.updated)
+                .any(|update| update.id == existing_id),
+            "Subscription should include the update to the written instance"
+        );
+    });
+}
+
+/// `/api/read-batch` returns the same subtrees as separate `/api/read` calls,
+/// keyed by the requested IDs, at the current message cursor.
+#[test]
+fn read_batch_returns_each_subtree() {
+    run_serve_test("syncback_write", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        let root_read = session.get_api_read(info.root_instance_id).unwrap();
+        let (rs_id, _) = find_by_class(&root_read.instances, "ReplicatedStorage");
+        let rs_read = session.get_api_read(rs_id).unwrap();
+
+        let batch = session
+            .post_api_read_batch(info.session_id, &[info.root_instance_id, rs_id])
+            .unwrap();
+
+        assert_eq!(batch.session_id, info.session_id);
+        assert_eq!(batch.message_cursor, rs_read.message_cursor);
+        assert_eq!(batch.subtrees.len(), 2);
+
+        let root_subtree = &batch.subtrees[&info.root_instance_id];
+        let rs_subtree = &batch.subtrees[&rs_id];
+        assert_eq!(root_subtree.len(), root_read.instances.len());
+        assert_eq!(rs_subtree.len(), rs_read.instances.len());
+        assert!(root_subtree.contains_key(&rs_id));
+        find_by_name(rs_subtree, "existing");
+    });
+}
+
+/// A dry-run write reports bad parents, unknown classes and removals of
+/// project-defined instances, and leaves the tree and disk alone.
+#[test]
+fn write_dry_run_reports_problems_without_writing() {
+    use librojo::web_api::WriteProblemKind;
+
+    run_serve_test("syncback_write", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        let root_read = session.get_api_read(info.root_instance_id).unwrap();
+        let (rs_id, _) = find_by_class(&root_read.instances, "ReplicatedStorage");
+        let rs_read = session.get_api_read(rs_id).unwrap();
+        let (existing_id, _) = find_by_name(&rs_read.instances, "existing");
+
+        let src_dir = session.path().join("src");
+        let files_before = fs::read_dir(&src_dir).unwrap().count();
+        let existing_before = fs::read_to_string(src_dir.join("existing.luau")).unwrap();
+
+        let orphan_id = Ref::new();
+        let bogus_id = Ref::new();
+        let mut added = HashMap::new();
+        added.insert(
+            orphan_id,
+            AddedInstance {
+                parent: Some(Ref::new()),
+                name: "Orphan".to_string(),
+                class_name: "Folder".to_string(),
+                properties: HashMap::new(),
+                children: vec![],
+                target_path: None,
+                id: None,
+            },
+        );
+        added.insert(
+            bogus_id,
+            AddedInstance {
+                parent: Some(rs_id),
+                name: "Bogus".to_string(),
+                class_name: "NotARealClass".to_string(),
+                properties: HashMap::new(),
+                children: vec![],
+                target_path: None,
+                id: None,
+            },
+        );
+
+        let write_request = WriteRequest {
+            session_id: info.session_id,
+            removed: vec![rs_id],
+            added,
+            updated: vec![InstanceUpdate {
+                id: existing_id,
+                changed_name: Some("Renamed".to_string()),
+                changed_class_name: None,
+                changed_properties: UstrMap::default(),
+                changed_metadata: None,
+            }],
+            stage_ids: Vec::new(),
+            dry_run: true,
+        };
+        let response = session.post_api_write_dry_run(&write_request).unwrap();
+
+        let found: Vec<(Ref, WriteProblemKind)> = response
+            .problems
+            .iter()
+            .map(|problem| (problem.id, problem.kind))
+            .collect();
+        assert_eq!(
+            found.len(),
+            3,
+            "unexpected problems: {:?}",
+            response.problems
+        );
+        assert!(found.contains(&(orphan_id, WriteProblemKind::InvalidParent)));
+        assert!(found.contains(&(bogus_id, WriteProblemKind::UnknownClass)));
+        assert!(found.contains(&(rs_id, WriteProblemKind::ProjectNode)));
+
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(fs::read_dir(&src_dir).unwrap().count(), files_before);
+        assert_eq!(
+            fs::read_to_string(src_dir.join("existing.luau")).unwrap(),
+            existing_before
+        );
+        let rs_after = session.get_api_read(rs_id).unwrap();
+        find_by_name(&rs_after.instances, "existing");
+        assert_eq!(rs_after.message_cursor, rs_read.message_cursor);
+    });
+}
+
+/// A real write runs the same checks as a dry run, reports their problems and
+/// skips the entries they block.
+#[test]
+fn write_reports_and_skips_the_same_problems_as_a_dry_run() {
+    use librojo::web_api::WriteProblemKind;
+
+    run_serve_test("syncback_write", |session, _redactions| {
+        let info = session.get_api_rojo().unwrap();
+        let root_read = session.get_api_read(info.root_instance_id).unwrap();
+        let (rs_id, _) = find_by_class(&root_read.instances, "ReplicatedStorage");
+
+        let write_request = |dry_run| WriteRequest {
+            session_id: info.session_id,
+            removed: vec![rs_id],
+            added: HashMap::new(),
+            updated: vec![],
+            stage_ids: Vec::new(),
+            dry_run,
+        };
+        let dry_run = session
+            .post_api_write_dry_run(&write_request(true))
+            .unwrap();
+        let response = session
+            .post_api_write_with_response(&write_request(false))
+            .unwrap();
+
+        assert_eq!(response.problems, dry_run.problems);
+        assert_eq!(response.problems.len(), 1);
+        assert_eq!(response.problems[0].kind, WriteProblemKind::ProjectNode);
+
+        thread::sleep(Duration::from_millis(300));
+        session.get_api_read(rs_id).unwrap();
+    });
+}
@@ -0,0 +1,107 @@
+use std::{path::Path, process::Output};
+
+use tempfile::tempdir;
+
+use crate::rojo_test::io_util::atlas_command;
+
+fn validate(project_dir: &Path) -> Output {
+    atlas_command()
+        .args([
+            "--color",
+            "never",
+            "project",
+            "validate",
+            project_dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Couldn't spawn validate process")
+}
+
+#[test]
+fn valid_project_passes() {
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_dir = test_dir.path();
+    fs_err::create_dir(project_dir.join("src")).unwrap();
+    fs_err::write(
+        project_dir.join("default.project.json5"),
+        r#"{
+            "name": "valid",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": {
+                    "$className": "ReplicatedStorage",
+                    "Shared": { "$path": "src" },
+                    "Generated": { "$path": { "optional": "generated" } },
+                    "Modules": { "$path": "src/*.luau" }
+                }
+            },
+            "syncbackRules": {
+                "ignorePaths": ["src/temp/**"],
+                "ignoreTrees": ["ServerStorage/Secrets"]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let output = validate(project_dir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Validate failed:\n{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("is valid"), "{stdout}");
+}
+
+#[test]
+fn invalid_project_reports_every_problem() {
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_dir = test_dir.path();
+    fs_err::write(
+        project_dir.join("default.project.json5"),
+        r#"{
+            "name": "invalid",
+            "tree": {
+                "$className": "DataModel",
+                "ReplicatedStorage": {
+                    "$className": "ReplicatedStorage",
+                    "Shared": { "$path": "missing" }
+                }
+            },
+            "syncbackRules": {
+                "ignorePaths": ["src/[oops"]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let output = validate(project_dir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{stdout}");
+    assert!(
+        stdout.contains("invalid/ReplicatedStorage/Shared: $path"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("missing does not exist"), "{stdout}");
+    assert!(stdout.contains("syncbackRules.ignorePaths"), "{stdout}");
+    assert!(stdout.contains("src/[oops"), "{stdout}");
+}
+
+#[test]
+fn top_level_validate_is_an_alias() {
+    let test_dir = tempdir().expect("Couldn't create temporary directory");
+    let project_dir = test_dir.path();
+    fs_err::write(
+        project_dir.join("default.project.json5"),
+        r#"{ "name": "alias", "tree": { "$className": "Folder" } }"#,
+    )
+    .unwrap();
+
+    let output = atlas_command()
+        .args(["validate", project_dir.to_str().unwrap()])
+        .output()
+        .expect("Couldn't spawn validate process");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("is valid"), "{stdout}");
+}